@@ -2,6 +2,9 @@ package consensus
 
 const (
 	WITNESS_DISCOUNT_DIVISOR = 4
+	// Weight per CORE_ANCHOR / CORE_DA_COMMIT covenant_data byte: counted as
+	// base bytes with no discount (CANONICAL §9); pinned by CV-WEIGHT.
+	ANCHOR_WEIGHT_FACTOR = WITNESS_DISCOUNT_DIVISOR
 
 	TARGET_BLOCK_INTERVAL = 120
 	WINDOW_SIZE           = 10_080
@@ -0,0 +1,358 @@
+//! Differential `parse_tx` harness: Rust `parse_tx` vs an external consensus
+//! CLI (normally the Go `rubin-consensus-cli`).
+//!
+//! Usage:
+//! `parse-tx-diff run <corpus_dir> <report_dir> -- <cmd> [args...]`
+//! `parse-tx-diff seed <corpus_dir> [fixtures_dir]`
+//!
+//! `run` feeds every file in `<corpus_dir>` (raw tx bytes, e.g. a cargo-fuzz
+//! corpus) to the in-process Rust parser and, as a `{"op":"parse_tx"}` JSON
+//! request on stdin, to `<cmd>`. Accept/reject, txid, and consumed length must
+//! agree; each mismatch is written to `<report_dir>/<input>.json` and the
+//! process exits non-zero if any were found.
+//!
+//! `seed` writes every distinct `tx_hex` from the conformance fixtures
+//! (default: the repo's `conformance/fixtures`) into `<corpus_dir>`, one file
+//! per tx named by its SHA3-256, so fuzzing and differential runs start from
+//! known-valid encodings.
+
+use std::env;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::process::{self, Command, Stdio};
+
+use rubin_consensus::parse_tx;
+use serde_json::{json, Value};
+use sha3::{Digest, Sha3_256};
+
+const PROGRAM: &str = "parse-tx-diff";
+
+fn usage() -> String {
+    format!(
+        "usage: {PROGRAM} run <corpus_dir> <report_dir> -- <cmd> [args...]\n       {PROGRAM} seed <corpus_dir> [fixtures_dir]"
+    )
+}
+
+/// Parser verdict compared across implementations. `err` is carried into the
+/// report for triage but is not part of the comparison: only accept/reject,
+/// txid, and consumed length are required to match.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Verdict {
+    ok: bool,
+    err: Option<String>,
+    txid: Option<String>,
+    consumed: Option<usize>,
+}
+
+impl Verdict {
+    fn agrees_with(&self, other: &Verdict) -> bool {
+        self.ok == other.ok && self.txid == other.txid && self.consumed == other.consumed
+    }
+
+    fn to_json(&self) -> Value {
+        json!({
+            "ok": self.ok,
+            "err": self.err,
+            "txid": self.txid,
+            "consumed": self.consumed,
+        })
+    }
+}
+
+fn rust_verdict(data: &[u8]) -> Verdict {
+    match parse_tx(data) {
+        Ok((_tx, txid, _wtxid, consumed)) => Verdict {
+            ok: true,
+            err: None,
+            txid: Some(hex::encode(txid)),
+            consumed: Some(consumed),
+        },
+        Err(e) => Verdict {
+            ok: false,
+            err: Some(e.code.as_str().to_string()),
+            txid: None,
+            consumed: None,
+        },
+    }
+}
+
+/// Decode a consensus-CLI `parse_tx` response. Rejections carry neither txid
+/// nor consumed, matching the Rust side.
+fn verdict_from_response(raw: &[u8]) -> Result<Verdict, String> {
+    let v: Value =
+        serde_json::from_slice(raw).map_err(|err| format!("decode external response: {err}"))?;
+    let ok = v
+        .get("ok")
+        .and_then(Value::as_bool)
+        .ok_or_else(|| "external response missing ok".to_string())?;
+    let err = v.get("err").and_then(Value::as_str).map(str::to_string);
+    if !ok {
+        return Ok(Verdict {
+            ok,
+            err,
+            txid: None,
+            consumed: None,
+        });
+    }
+    let txid = v
+        .get("txid")
+        .and_then(Value::as_str)
+        .map(str::to_ascii_lowercase);
+    let consumed = v
+        .get("consumed")
+        .and_then(Value::as_u64)
+        .map(|n| n as usize);
+    Ok(Verdict {
+        ok,
+        err,
+        txid,
+        consumed,
+    })
+}
+
+fn external_verdict(cmd: &[String], data: &[u8]) -> Result<Verdict, String> {
+    let (program, args) = cmd.split_first().ok_or_else(usage)?;
+    let mut child = Command::new(program)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::inherit())
+        .spawn()
+        .map_err(|err| format!("spawn {program}: {err}"))?;
+    let req = json!({"op": "parse_tx", "tx_hex": hex::encode(data)});
+    {
+        let mut stdin = child.stdin.take().expect("piped stdin");
+        serde_json::to_writer(&mut stdin, &req).map_err(|err| format!("write request: {err}"))?;
+    }
+    let out = child
+        .wait_with_output()
+        .map_err(|err| format!("wait {program}: {err}"))?;
+    verdict_from_response(&out.stdout)
+}
+
+fn corpus_files(dir: &Path) -> Result<Vec<PathBuf>, String> {
+    let mut files = Vec::new();
+    for entry in fs::read_dir(dir).map_err(|err| format!("read {}: {err}", dir.display()))? {
+        let path = entry.map_err(|err| err.to_string())?.path();
+        if path.is_file() {
+            files.push(path);
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// Compare every corpus input and write one report per mismatch. Returns the
+/// number of inputs checked and the number of mismatches.
+fn run_diff(
+    corpus: &Path,
+    report: &Path,
+    external: impl Fn(&[u8]) -> Result<Verdict, String>,
+) -> Result<(usize, usize), String> {
+    let files = corpus_files(corpus)?;
+    let mut mismatches = 0usize;
+    for path in &files {
+        let data = fs::read(path).map_err(|err| format!("read {}: {err}", path.display()))?;
+        let rust = rust_verdict(&data);
+        let other = external(&data)?;
+        if rust.agrees_with(&other) {
+            continue;
+        }
+        mismatches += 1;
+        fs::create_dir_all(report).map_err(|err| format!("create {}: {err}", report.display()))?;
+        let name = path
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let body = json!({
+            "input": name,
+            "tx_hex": hex::encode(&data),
+            "rust": rust.to_json(),
+            "external": other.to_json(),
+        });
+        let out = report.join(format!("{name}.json"));
+        let encoded = serde_json::to_vec_pretty(&body).map_err(|err| err.to_string())?;
+        fs::write(&out, encoded).map_err(|err| format!("write {}: {err}", out.display()))?;
+    }
+    Ok((files.len(), mismatches))
+}
+
+fn collect_tx_hex(v: &Value, out: &mut Vec<String>) {
+    match v {
+        Value::Object(map) => {
+            for (key, value) in map {
+                match (key.as_str(), value) {
+                    ("tx_hex", Value::String(s)) => out.push(s.to_ascii_lowercase()),
+                    _ => collect_tx_hex(value, out),
+                }
+            }
+        }
+        Value::Array(items) => items.iter().for_each(|item| collect_tx_hex(item, out)),
+        _ => {}
+    }
+}
+
+/// Write each distinct decodable `tx_hex` found in `fixtures/*.json` into
+/// `corpus`. Returns the number of seeds written.
+fn seed_corpus(fixtures: &Path, corpus: &Path) -> Result<usize, String> {
+    let mut hexes = Vec::new();
+    for path in corpus_files(fixtures)? {
+        if path.extension().and_then(|e| e.to_str()) != Some("json") {
+            continue;
+        }
+        let raw = fs::read(&path).map_err(|err| format!("read {}: {err}", path.display()))?;
+        let v: Value = serde_json::from_slice(&raw)
+            .map_err(|err| format!("decode {}: {err}", path.display()))?;
+        collect_tx_hex(&v, &mut hexes);
+    }
+    hexes.sort();
+    hexes.dedup();
+
+    fs::create_dir_all(corpus).map_err(|err| format!("create {}: {err}", corpus.display()))?;
+    let mut written = 0usize;
+    for tx_hex in hexes {
+        let Ok(bytes) = hex::decode(&tx_hex) else {
+            continue;
+        };
+        let name = hex::encode(Sha3_256::digest(&bytes));
+        let out = corpus.join(name);
+        fs::write(&out, &bytes).map_err(|err| format!("write {}: {err}", out.display()))?;
+        written += 1;
+    }
+    Ok(written)
+}
+
+fn default_fixtures_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("../../../../conformance/fixtures")
+}
+
+fn run(args: &[String]) -> Result<(), String> {
+    match args.first().map(String::as_str) {
+        Some("run") => {
+            let split = args.iter().position(|a| a == "--").ok_or_else(usage)?;
+            let [corpus, report] = &args[1..split] else {
+                return Err(usage());
+            };
+            let cmd = &args[split + 1..];
+            if cmd.is_empty() {
+                return Err(usage());
+            }
+            let (checked, mismatches) = run_diff(Path::new(corpus), Path::new(report), |data| {
+                external_verdict(cmd, data)
+            })?;
+            println!("checked={checked} mismatches={mismatches}");
+            if mismatches > 0 {
+                return Err(format!("{mismatches} mismatch(es) written to {report}"));
+            }
+            Ok(())
+        }
+        Some("seed") => {
+            let (corpus, fixtures) = match &args[1..] {
+                [corpus] => (PathBuf::from(corpus), default_fixtures_dir()),
+                [corpus, fixtures] => (PathBuf::from(corpus), PathBuf::from(fixtures)),
+                _ => return Err(usage()),
+            };
+            let written = seed_corpus(&fixtures, &corpus)?;
+            println!("seeds={written}");
+            Ok(())
+        }
+        Some("-h") | Some("--help") => {
+            println!("{}", usage());
+            Ok(())
+        }
+        _ => Err(usage()),
+    }
+}
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    if let Err(err) = run(&args) {
+        let _ = writeln!(io::stderr(), "{err}");
+        process::exit(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(tag: &str) -> PathBuf {
+        let dir = env::temp_dir().join(format!("{PROGRAM}-{tag}-{}", process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).expect("mkdir");
+        dir
+    }
+
+    #[test]
+    fn response_decoding_ignores_fields_on_reject() {
+        let v = verdict_from_response(br#"{"ok":false,"err":"TX_ERR_PARSE","consumed":3}"#)
+            .expect("decode");
+        assert_eq!(v, rust_verdict(&[0x00]));
+
+        let v = verdict_from_response(br#"{"ok":true,"txid":"AB","consumed":7}"#).expect("decode");
+        assert_eq!(v.txid.as_deref(), Some("ab"));
+        assert_eq!(v.consumed, Some(7));
+        assert!(verdict_from_response(b"not json").is_err());
+    }
+
+    #[test]
+    fn seeded_conformance_txs_roundtrip_and_self_agree() {
+        let corpus = temp_dir("corpus");
+        let report = temp_dir("report");
+        let written = seed_corpus(&default_fixtures_dir(), &corpus).expect("seed");
+        assert!(written > 0);
+
+        let mut accepted = 0usize;
+        for path in corpus_files(&corpus).expect("list") {
+            let data = fs::read(&path).expect("read");
+            if let Ok((tx, _, _, consumed)) = parse_tx(&data) {
+                let bytes = rubin_consensus::marshal_tx(&tx).expect("marshal");
+                assert_eq!(bytes, data[..consumed], "{}", path.display());
+                accepted += 1;
+            }
+        }
+        assert!(accepted > 0);
+
+        let (checked, mismatches) =
+            run_diff(&corpus, &report, |data| Ok(rust_verdict(data))).expect("diff");
+        assert_eq!((checked, mismatches), (written, 0));
+        assert!(corpus_files(&report).expect("list").is_empty());
+        let _ = fs::remove_dir_all(&corpus);
+        let _ = fs::remove_dir_all(&report);
+    }
+
+    #[test]
+    fn mismatches_are_reported() {
+        let corpus = temp_dir("corpus-mm");
+        let report = temp_dir("report-mm");
+        fs::write(corpus.join("reject"), [0x00]).expect("write");
+        let (checked, mismatches) = run_diff(&corpus, &report, |_| {
+            Ok(Verdict {
+                ok: true,
+                err: None,
+                txid: Some("00".repeat(32)),
+                consumed: Some(1),
+            })
+        })
+        .expect("diff");
+        assert_eq!((checked, mismatches), (1, 1));
+        let body: Value =
+            serde_json::from_slice(&fs::read(report.join("reject.json")).expect("report"))
+                .expect("json");
+        assert_eq!(body["tx_hex"], "00");
+        assert_eq!(body["rust"]["ok"], false);
+        assert_eq!(body["external"]["ok"], true);
+        let _ = fs::remove_dir_all(&corpus);
+        let _ = fs::remove_dir_all(&report);
+    }
+
+    #[test]
+    fn run_rejects_malformed_args() {
+        let args = |items: &[&str]| items.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        assert!(run(&args(&[])).is_err());
+        assert!(run(&args(&["run", "a", "b"])).is_err());
+        assert!(run(&args(&["run", "a", "b", "--"])).is_err());
+        assert!(run(&args(&["seed"])).is_err());
+    }
+}
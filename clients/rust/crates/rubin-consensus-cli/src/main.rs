@@ -2,31 +2,36 @@ use num_bigint::BigUint;
 use num_traits::Zero;
 use rubin_consensus::constants::{
     COV_TYPE_HTLC, COV_TYPE_P2PK, LOCK_MODE_HEIGHT, MAX_HTLC_COVENANT_DATA,
-    MAX_WITNESS_BYTES_PER_TX, ML_DSA_87_PUBKEY_BYTES, ML_DSA_87_SIG_BYTES, SUITE_ID_SENTINEL,
+    MAX_WITNESS_BYTES_PER_TX, ML_DSA_87_PUBKEY_BYTES, ML_DSA_87_SIG_BYTES, SIGHASH_ALL,
+    SUITE_ID_SENTINEL, WINDOW_SIZE,
 };
 use rubin_consensus::merkle::witness_merkle_root_wtxids;
 use rubin_consensus::{
     apply_non_coinbase_tx_basic_update_with_mtp_and_core_ext_profiles_and_suite_context,
-    block_hash, compact_shortid,
+    apply_non_coinbase_tx_basic_update_with_mtp_and_core_ext_profiles_and_suite_context_observed,
+    block_expected_target, block_hash, compact_shortid,
     connect_block_basic_in_memory_at_height_and_core_ext_deployments_with_suite_context,
-    featurebit_state_at_height_from_window_counts, flagday_active_at_height, merkle_root_txids,
-    parse_tx, pow_check, retarget_v1, retarget_v1_clamped, sighash_v1_digest, simplicity,
-    tx_weight_and_stats_at_height, tx_weight_and_stats_public,
+    encode_compact_size, featurebit_state_at_height_from_window_counts, flagday_active_at_height,
+    hex_decode_32, hex_decode_exact, hex_decode_max, merkle_root_txids, output_descriptor_bytes,
+    output_descriptor_hash, parse_block_bytes, parse_block_header_bytes, parse_tx, pow_check,
+    retarget_v1, retarget_v1_clamped, sighash_v1_digest, sighash_v1_digest_with_cache, simplicity,
+    tx_weight_and_stats_at_height, tx_weight_and_stats_public, utxo_set_hash,
     validate_block_basic_with_context_and_fees_at_height,
     validate_block_basic_with_context_at_height, validate_htlc_spend,
     validate_rotation_descriptor_for_network, validate_rotation_set_for_network,
-    validate_tx_covenants_genesis, work_from_target, CryptoRotationDescriptor,
+    validate_tx_covenants_genesis, work_from_target, BlockHeader, CryptoRotationDescriptor,
     DescriptorRotationProvider, ErrorCode, FeatureBitDeployment, FeatureBitState,
-    FlagDayDeployment, HtlcSpendContext, InMemoryChainState, Outpoint, RotationProvider,
-    SuiteParams, SuiteRegistry, Tx, TxInput, TxOutput, UtxoEntry, WitnessItem,
+    FlagDayDeployment, HtlcSpendContext, InMemoryChainState, Outpoint, OutpointMap,
+    RotationProvider, SighashV1PrehashCache, SuiteParams, SuiteRegistry, Tx, TxInput, TxOutput,
+    UtxoEntry, WitnessItem, BLOCK_HEADER_BYTES,
     ROTATION_V1_PRODUCTION_AT_MOST_ONE_DESCRIPTOR_ERR_STEM,
     ROTATION_V1_PRODUCTION_FINITE_H4_REQUIRED_ERR_STEM,
 };
+use rubin_node::undo::{build_block_undo, unmarshal_block_undo};
 use rubin_node::{devnet_genesis_chain_id, ChainState, TxPool, TxPoolAdmitErrorKind, TxPoolConfig};
 use serde::de::{IgnoredAny, SeqAccess, Visitor};
 use serde::{Deserialize, Deserializer, Serialize};
 use serde_json::Value;
-use sha3::{Digest, Sha3_256};
 use std::cmp::Ordering;
 use std::collections::HashMap;
 
@@ -172,6 +177,12 @@ struct Request {
     #[serde(default)]
     input_value: u64,
 
+    #[serde(default)]
+    input_values: Vec<u64>,
+
+    #[serde(default)]
+    input_indices: Option<Vec<u32>>,
+
     #[serde(default)]
     chain_id: String,
 
@@ -235,6 +246,25 @@ struct Request {
     #[serde(default)]
     prev_timestamps: Vec<u64>,
 
+    /// Ancestor headers for `apply_block` / `connect_chain`, oldest first and
+    /// the parent header last.
+    #[serde(default)]
+    ancestor_headers_hex: Vec<String>,
+
+    #[serde(default)]
+    blocks_hex: Vec<String>,
+
+    /// Block undo record for `disconnect_block`, in the node's on-disk JSON
+    /// shape. When absent the record is rebuilt from `prev_utxos`.
+    #[serde(default)]
+    undo: Option<Value>,
+
+    #[serde(default)]
+    prev_utxos: Vec<UtxoJson>,
+
+    #[serde(default)]
+    prev_already_generated: u64,
+
     #[serde(default)]
     block_timestamp: u64,
 
@@ -687,6 +717,9 @@ struct Response {
     #[serde(skip_serializing_if = "Option::is_none")]
     digest: Option<String>,
 
+    #[serde(skip_serializing_if = "Option::is_none")]
+    digests: Option<Vec<String>>,
+
     #[serde(skip_serializing_if = "Option::is_none")]
     consumed: Option<usize>,
 
@@ -935,12 +968,41 @@ struct Response {
 
     #[serde(skip_serializing_if = "Option::is_none")]
     prioritize: Option<bool>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    utxo_set_hash: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tip_height: Option<u64>,
 }
 
 fn err_code(code: ErrorCode) -> String {
     code.as_str().to_string()
 }
 
+fn output_descriptor_response(req: &Request, hashed: bool) -> Response {
+    let cov_data = match hex::decode(&req.covenant_data_hex) {
+        Ok(v) => v,
+        Err(_) => {
+            return Response {
+                ok: false,
+                err: Some("bad covenant_data_hex".to_string()),
+                ..Default::default()
+            };
+        }
+    };
+    let digest = if hashed {
+        hex::encode(output_descriptor_hash(req.covenant_type, &cov_data))
+    } else {
+        hex::encode(output_descriptor_bytes(req.covenant_type, &cov_data))
+    };
+    Response {
+        ok: true,
+        digest: Some(digest),
+        ..Default::default()
+    }
+}
+
 fn htlc_refund_ordering_policy_response(
     req: &Request,
     suite_id: u8,
@@ -1050,21 +1112,6 @@ fn parse_hex_u256_to_32(s: &str) -> Result<[u8; 32], ()> {
     Ok(out)
 }
 
-fn parse_exact_hex32(s: &str) -> Result<[u8; 32], ()> {
-    let stripped = s
-        .trim()
-        .strip_prefix("0x")
-        .or_else(|| s.trim().strip_prefix("0X"))
-        .unwrap_or_else(|| s.trim());
-    let b = hex::decode(stripped).map_err(|_| ())?;
-    if b.len() != 32 {
-        return Err(());
-    }
-    let mut out = [0u8; 32];
-    out.copy_from_slice(&b);
-    Ok(out)
-}
-
 fn key_bytes(value: &Value) -> Result<Vec<u8>, ()> {
     if let Some(s) = value.as_str() {
         let stripped = s.trim().to_lowercase();
@@ -1209,7 +1256,8 @@ fn parse_optional_hex32_named(value: &str, bad_err: &str) -> Result<Option<[u8;
     if value.trim().is_empty() {
         return Ok(None);
     }
-    parse_exact_hex32(value)
+    let field = bad_err.strip_prefix("bad ").unwrap_or(bad_err);
+    hex_decode_32(value, field)
         .map(Some)
         .map_err(|_| bad_err.to_string())
 }
@@ -1407,10 +1455,35 @@ fn dominant_fee_floor(relay_fee_floor: u64, da_required_fee: u64) -> &'static st
     }
 }
 
-fn policy_utxo_map(items: &[UtxoJson]) -> Result<HashMap<Outpoint, UtxoEntry>, String> {
-    let mut utxos: HashMap<Outpoint, UtxoEntry> = HashMap::with_capacity(items.len());
+/// Conversion used by the consensus ops. Txids follow the shared hex input
+/// convention (`hex_decode_32`), matching the Go harness `parseExactHex32`.
+fn utxo_set_from_json(items: &[UtxoJson]) -> Result<OutpointMap<UtxoEntry>, String> {
+    let mut utxos: OutpointMap<UtxoEntry> =
+        OutpointMap::with_capacity_and_hasher(items.len(), Default::default());
+    for u in items {
+        let txid = hex_decode_32(&u.txid, "utxo txid").map_err(|_| "bad utxo txid".to_string())?;
+        let covenant_data =
+            hex::decode(&u.covenant_data).map_err(|_| "bad utxo covenant_data".to_string())?;
+        utxos.insert(
+            Outpoint { txid, vout: u.vout },
+            UtxoEntry {
+                value: u.value,
+                covenant_type: u.covenant_type,
+                covenant_data,
+                creation_height: u.creation_height,
+                created_by_coinbase: u.created_by_coinbase,
+            },
+        );
+    }
+    Ok(utxos)
+}
+
+fn policy_utxo_map(items: &[UtxoJson]) -> Result<OutpointMap<UtxoEntry>, String> {
+    let mut utxos: OutpointMap<UtxoEntry> =
+        OutpointMap::with_capacity_and_hasher(items.len(), Default::default());
     for u in items {
-        let op_txid = parse_exact_hex32(&u.txid).map_err(|_| "bad utxo txid".to_string())?;
+        let op_txid =
+            hex_decode_32(&u.txid, "utxo txid").map_err(|_| "bad utxo txid".to_string())?;
         let cov_data =
             hex::decode(&u.covenant_data).map_err(|_| "bad utxo covenant_data".to_string())?;
         utxos.insert(
@@ -1432,7 +1505,7 @@ fn policy_utxo_map(items: &[UtxoJson]) -> Result<HashMap<Outpoint, UtxoEntry>, S
 
 fn fee_from_policy_utxos(
     tx: &rubin_consensus::tx::Tx,
-    utxos: &HashMap<Outpoint, UtxoEntry>,
+    utxos: &OutpointMap<UtxoEntry>,
 ) -> Result<u64, String> {
     let mut total_in = 0u64;
     if tx.inputs.is_empty() {
@@ -1646,7 +1719,7 @@ fn mempool_relay_metadata_policy_response(req: &Request) -> Response {
     let chain_id = if req.chain_id.trim().is_empty() {
         devnet_genesis_chain_id()
     } else {
-        match parse_exact_hex32(&req.chain_id) {
+        match hex_decode_32(&req.chain_id, "chain_id") {
             Ok(v) => v,
             Err(_) => {
                 return Response {
@@ -1859,6 +1932,81 @@ fn build_core_ext_suite_context(
     Ok((rotation, registry))
 }
 
+/// Runs the non-coinbase apply path with a recording observer and returns the
+/// consensus checks it evaluated, in order, plus the first error. Unlike
+/// `validation_order`, the trace comes from the library itself.
+fn op_tx_validation_trace(req: &Request) -> Response {
+    fn fail(err: String) -> Response {
+        Response {
+            ok: false,
+            err: Some(err),
+            ..Default::default()
+        }
+    }
+
+    let tx_bytes = match hex::decode(&req.tx_hex) {
+        Ok(v) => v,
+        Err(_) => return fail("bad hex".to_string()),
+    };
+    let (tx, txid, _wtxid, _n) = match parse_tx(&tx_bytes) {
+        Ok(v) => v,
+        Err(e) => return fail(err_code(e.code)),
+    };
+    let utxo_set = match utxo_set_from_json(&req.utxos) {
+        Ok(v) => v,
+        Err(e) => return fail(e),
+    };
+    let mut chain_id = [0u8; 32];
+    if !req.chain_id.trim().is_empty() {
+        chain_id = match hex_decode_32(&req.chain_id, "chain_id") {
+            Ok(v) => v,
+            Err(_) => return fail("bad chain_id".to_string()),
+        };
+    }
+    if let Err(e) = reject_core_ext_profiles_from_json(
+        &req.core_ext_profiles,
+        &req.core_ext_profile_set_anchor_hex,
+    ) {
+        return fail(e);
+    }
+    let (rotation, registry) = match build_core_ext_suite_context(req) {
+        Ok(v) => v,
+        Err(e) => return fail(e),
+    };
+
+    let mut evaluated: Vec<&'static str> = Vec::new();
+    let result =
+        apply_non_coinbase_tx_basic_update_with_mtp_and_core_ext_profiles_and_suite_context_observed(
+            &tx,
+            txid,
+            &utxo_set,
+            req.height,
+            req.block_timestamp,
+            req.block_mtp.unwrap_or(req.block_timestamp),
+            chain_id,
+            rotation.as_ref().map(|rp| rp as &dyn RotationProvider),
+            registry.as_ref(),
+            &mut evaluated,
+        );
+    let evaluated = Some(evaluated.into_iter().map(str::to_string).collect());
+    match result {
+        Ok((_next_utxos, summary)) => Response {
+            ok: true,
+            fee: Some(summary.fee),
+            utxo_count: Some(summary.utxo_count),
+            evaluated,
+            ..Default::default()
+        },
+        Err(e) => Response {
+            ok: false,
+            err: Some(err_code(e.code)),
+            first_err: Some(err_code(e.code)),
+            evaluated,
+            ..Default::default()
+        },
+    }
+}
+
 fn op_featurebits_state(req: &Request) -> Response {
     let d = FeatureBitDeployment {
         name: req.name.clone(),
@@ -1983,6 +2131,492 @@ fn op_rotation_descriptor_check(req: &Request) -> Response {
     }
 }
 
+fn parse_ancestor_headers(items: &[String]) -> Result<Vec<([u8; 32], BlockHeader)>, String> {
+    let mut out = Vec::with_capacity(items.len());
+    for item in items {
+        let raw = hex_decode_exact(item, BLOCK_HEADER_BYTES, "ancestor_headers_hex")
+            .map_err(|_| "bad ancestor_headers_hex".to_string())?;
+        let header =
+            parse_block_header_bytes(&raw).map_err(|_| "bad ancestor_headers_hex".to_string())?;
+        let hash = block_hash(&raw).map_err(|_| "bad ancestor_headers_hex".to_string())?;
+        out.push((hash, header));
+    }
+    Ok(out)
+}
+
+fn parse_optional_exact_hex32(value: &str, bad_err: &str) -> Result<Option<[u8; 32]>, String> {
+    if value.trim().is_empty() {
+        return Ok(None);
+    }
+    let field = bad_err.strip_prefix("bad ").unwrap_or(bad_err);
+    hex_decode_32(value, field)
+        .map(Some)
+        .map_err(|_| bad_err.to_string())
+}
+
+/// Header context for connecting a block at `height` on top of `ancestors`
+/// (a contiguous canonical run, oldest first, parent last, at most `height`
+/// entries; see `block_expected_target`). Explicit request values win; otherwise the
+/// parent hash, the parent target (or the clamped retarget at a window
+/// boundary) and the newest-first MTP timestamps are derived from ancestors.
+struct ApplyBlockHeaderContext {
+    expected_prev_hash: Option<[u8; 32]>,
+    expected_target: Option<[u8; 32]>,
+    prev_timestamps: Vec<u64>,
+}
+
+fn apply_block_header_context(
+    ancestors: &[([u8; 32], BlockHeader)],
+    height: u64,
+    explicit_prev_hash: Option<[u8; 32]>,
+    explicit_target: Option<[u8; 32]>,
+    fallback_prev_timestamps: &[u64],
+) -> Result<ApplyBlockHeaderContext, String> {
+    let Some((parent_hash, parent)) = ancestors.last() else {
+        return Ok(ApplyBlockHeaderContext {
+            expected_prev_hash: explicit_prev_hash,
+            expected_target: explicit_target,
+            prev_timestamps: fallback_prev_timestamps.to_vec(),
+        });
+    };
+
+    let expected_target = match explicit_target {
+        Some(target) => Some(target),
+        None if !height.is_multiple_of(WINDOW_SIZE) => Some(parent.target),
+        None => {
+            if ancestors.len() < WINDOW_SIZE as usize {
+                return Err("insufficient ancestor_headers_hex for retarget".to_string());
+            }
+            let headers: Vec<BlockHeader> = ancestors.iter().map(|(_, h)| h.clone()).collect();
+            Some(block_expected_target(&headers, height).map_err(|e| err_code(e.code))?)
+        }
+    };
+
+    let prev_timestamps = ancestors
+        .iter()
+        .rev()
+        .take(11)
+        .map(|(_, h)| h.timestamp)
+        .collect();
+
+    Ok(ApplyBlockHeaderContext {
+        expected_prev_hash: Some(explicit_prev_hash.unwrap_or(*parent_hash)),
+        expected_target,
+        prev_timestamps,
+    })
+}
+
+fn block_weight_and_anchor_bytes(block_bytes: &[u8]) -> Result<(u64, u64), String> {
+    let pb = parse_block_bytes(block_bytes).map_err(|e| err_code(e.code))?;
+    let mut weight = 0u64;
+    let mut anchor_bytes = 0u64;
+    for tx in &pb.txs {
+        let (w, _da, anchor) = tx_weight_and_stats_public(tx).map_err(|e| err_code(e.code))?;
+        weight = weight.saturating_add(w);
+        anchor_bytes = anchor_bytes.saturating_add(anchor);
+    }
+    Ok((weight, anchor_bytes))
+}
+
+fn op_apply_block(req: &Request) -> Response {
+    let fail = |err: String| Response {
+        ok: false,
+        err: Some(err),
+        ..Default::default()
+    };
+
+    let block_bytes = match hex::decode(req.block_hex.trim()) {
+        Ok(v) => v,
+        Err(_) => return fail("bad block".to_string()),
+    };
+    let ancestors = match parse_ancestor_headers(&req.ancestor_headers_hex) {
+        Ok(v) => v,
+        Err(e) => return fail(e),
+    };
+    let explicit_prev =
+        match parse_optional_exact_hex32(&req.expected_prev_hash, "bad expected_prev_hash") {
+            Ok(v) => v,
+            Err(e) => return fail(e),
+        };
+    let explicit_target =
+        match parse_optional_exact_hex32(&req.expected_target, "bad expected_target") {
+            Ok(v) => v,
+            Err(e) => return fail(e),
+        };
+    let header_ctx = match apply_block_header_context(
+        &ancestors,
+        req.height,
+        explicit_prev,
+        explicit_target,
+        &req.prev_timestamps,
+    ) {
+        Ok(v) => v,
+        Err(e) => return fail(e),
+    };
+    let chain_id = match parse_optional_exact_hex32(&req.chain_id, "bad chain_id") {
+        Ok(v) => v.unwrap_or([0u8; 32]),
+        Err(e) => return fail(e),
+    };
+    let utxos = match policy_utxo_map(&req.utxos) {
+        Ok(v) => v,
+        Err(e) => return fail(e),
+    };
+    if let Err(e) = reject_core_ext_profiles_from_json(
+        &req.core_ext_profiles,
+        &req.core_ext_profile_set_anchor_hex,
+    ) {
+        return fail(e);
+    }
+    let (rotation, registry) = match build_core_ext_suite_context(req) {
+        Ok(v) => v,
+        Err(e) => return fail(e),
+    };
+
+    let mut state = InMemoryChainState {
+        utxos,
+        already_generated: u128::from(req.already_generated),
+    };
+    let prev_timestamps = if header_ctx.prev_timestamps.is_empty() {
+        None
+    } else {
+        Some(header_ctx.prev_timestamps.as_slice())
+    };
+    let summary =
+        match connect_block_basic_in_memory_at_height_and_core_ext_deployments_with_suite_context(
+            &block_bytes,
+            header_ctx.expected_prev_hash,
+            header_ctx.expected_target,
+            req.height,
+            prev_timestamps,
+            &mut state,
+            chain_id,
+            rotation.as_ref().map(|rp| rp as &dyn RotationProvider),
+            registry.as_ref(),
+        ) {
+            Ok(v) => v,
+            Err(e) => return fail(err_code(e.code)),
+        };
+
+    // The block already passed full validation, so parsing and per-tx weight
+    // accounting cannot fail here; the error arms stay for defence in depth.
+    let (weight, anchor_bytes) = match block_weight_and_anchor_bytes(&block_bytes) {
+        Ok(v) => v,
+        Err(e) => return fail(e),
+    };
+    let header_hash =
+        match parse_block_bytes(&block_bytes).and_then(|pb| block_hash(&pb.header_bytes)) {
+            Ok(v) => v,
+            Err(e) => return fail(err_code(e.code)),
+        };
+    let Ok(already_generated_n1) = u64::try_from(summary.already_generated_n1) else {
+        return fail("already_generated_overflow".to_string());
+    };
+
+    Response {
+        ok: true,
+        block_hash: Some(hex::encode(header_hash)),
+        sum_fees: Some(summary.sum_fees),
+        weight: Some(weight),
+        anchor_bytes: Some(anchor_bytes),
+        utxo_count: Some(summary.utxo_count),
+        already_generated_n1: Some(already_generated_n1),
+        utxo_set_hash: Some(hex::encode(summary.post_state_digest)),
+        ..Default::default()
+    }
+}
+
+/// Connects `blocks_hex` sequentially starting at `height` on top of the
+/// supplied UTXO set, mirroring the node's sequential chainstate replay. Each
+/// connected header becomes an ancestor of the next block, so linkage, target
+/// and MTP context are derived exactly as `apply_block` derives them.
+fn op_connect_chain(req: &Request) -> Response {
+    let fail = |err: String, index: Option<usize>| Response {
+        ok: false,
+        err: Some(err),
+        diagnostics: index.map(|i| serde_json::json!({ "block_index": i })),
+        ..Default::default()
+    };
+
+    if req.blocks_hex.is_empty() {
+        return fail("bad blocks_hex".to_string(), None);
+    }
+    let mut ancestors = match parse_ancestor_headers(&req.ancestor_headers_hex) {
+        Ok(v) => v,
+        Err(e) => return fail(e, None),
+    };
+    let mut explicit_prev =
+        match parse_optional_exact_hex32(&req.expected_prev_hash, "bad expected_prev_hash") {
+            Ok(v) => v,
+            Err(e) => return fail(e, None),
+        };
+    let explicit_target =
+        match parse_optional_exact_hex32(&req.expected_target, "bad expected_target") {
+            Ok(v) => v,
+            Err(e) => return fail(e, None),
+        };
+    let chain_id = match parse_optional_exact_hex32(&req.chain_id, "bad chain_id") {
+        Ok(v) => v.unwrap_or([0u8; 32]),
+        Err(e) => return fail(e, None),
+    };
+    let utxos = match policy_utxo_map(&req.utxos) {
+        Ok(v) => v,
+        Err(e) => return fail(e, None),
+    };
+    if let Err(e) = reject_core_ext_profiles_from_json(
+        &req.core_ext_profiles,
+        &req.core_ext_profile_set_anchor_hex,
+    ) {
+        return fail(e, None);
+    }
+    let (rotation, registry) = match build_core_ext_suite_context(req) {
+        Ok(v) => v,
+        Err(e) => return fail(e, None),
+    };
+
+    let mut state = InMemoryChainState {
+        utxos,
+        already_generated: u128::from(req.already_generated),
+    };
+    let mut fallback_prev_timestamps = req.prev_timestamps.clone();
+    let mut sum_fees = 0u64;
+    let mut weight = 0u64;
+    let mut anchor_bytes = 0u64;
+    let mut tip_hash = [0u8; 32];
+    let mut utxo_set_hash = [0u8; 32];
+    let mut height = req.height;
+
+    for (i, block_hex) in req.blocks_hex.iter().enumerate() {
+        let block_bytes = match hex::decode(block_hex.trim()) {
+            Ok(v) => v,
+            Err(_) => return fail("bad block".to_string(), Some(i)),
+        };
+        let header_ctx = match apply_block_header_context(
+            &ancestors,
+            height,
+            explicit_prev,
+            explicit_target,
+            &fallback_prev_timestamps,
+        ) {
+            Ok(v) => v,
+            Err(e) => return fail(e, Some(i)),
+        };
+        let prev_timestamps = if header_ctx.prev_timestamps.is_empty() {
+            None
+        } else {
+            Some(header_ctx.prev_timestamps.as_slice())
+        };
+        let summary = match connect_block_basic_in_memory_at_height_and_core_ext_deployments_with_suite_context(
+            &block_bytes,
+            header_ctx.expected_prev_hash,
+            header_ctx.expected_target,
+            height,
+            prev_timestamps,
+            &mut state,
+            chain_id,
+            rotation.as_ref().map(|rp| rp as &dyn RotationProvider),
+            registry.as_ref(),
+        ) {
+            Ok(v) => v,
+            Err(e) => return fail(err_code(e.code), Some(i)),
+        };
+        let pb = match parse_block_bytes(&block_bytes) {
+            Ok(v) => v,
+            Err(e) => return fail(err_code(e.code), Some(i)),
+        };
+        let (block_weight, block_anchor_bytes) = match block_weight_and_anchor_bytes(&block_bytes) {
+            Ok(v) => v,
+            Err(e) => return fail(e, Some(i)),
+        };
+        tip_hash = match block_hash(&pb.header_bytes) {
+            Ok(v) => v,
+            Err(e) => return fail(err_code(e.code), Some(i)),
+        };
+        sum_fees = sum_fees.saturating_add(summary.sum_fees);
+        weight = weight.saturating_add(block_weight);
+        anchor_bytes = anchor_bytes.saturating_add(block_anchor_bytes);
+        utxo_set_hash = summary.post_state_digest;
+
+        // Explicit linkage/timestamp overrides only describe the first block;
+        // every later block links to the header connected just before it.
+        explicit_prev = None;
+        fallback_prev_timestamps.clear();
+        ancestors.push((tip_hash, pb.header));
+
+        if i + 1 < req.blocks_hex.len() {
+            height = match height.checked_add(1) {
+                Some(v) => v,
+                None => return fail("height overflow".to_string(), Some(i)),
+            };
+        }
+    }
+
+    let Ok(already_generated) = u64::try_from(state.already_generated) else {
+        return fail("already_generated_overflow".to_string(), None);
+    };
+    Response {
+        ok: true,
+        tip_height: Some(height),
+        block_hash: Some(hex::encode(tip_hash)),
+        sum_fees: Some(sum_fees),
+        weight: Some(weight),
+        anchor_bytes: Some(anchor_bytes),
+        utxo_count: Some(state.utxos.len() as u64),
+        already_generated: Some(already_generated),
+        utxo_set_hash: Some(hex::encode(utxo_set_hash)),
+        ..Default::default()
+    }
+}
+
+fn op_utxo_set_hash(req: &Request) -> Response {
+    match policy_utxo_map(&req.utxos) {
+        Ok(utxos) => Response {
+            ok: true,
+            utxo_set_hash: Some(hex::encode(utxo_set_hash(&utxos))),
+            utxo_count: Some(utxos.len() as u64),
+            ..Default::default()
+        },
+        Err(e) => Response {
+            ok: false,
+            err: Some(e),
+            ..Default::default()
+        },
+    }
+}
+
+/// Disconnects `block_hex` (connected at `height`) from the post-state
+/// `utxos` using the node's undo path. The undo record is taken from `undo`
+/// when supplied, otherwise regenerated from `prev_utxos` /
+/// `prev_already_generated`.
+fn op_disconnect_block(req: &Request) -> Response {
+    let fail = |err: String| Response {
+        ok: false,
+        err: Some(err),
+        ..Default::default()
+    };
+
+    let block_bytes = match hex::decode(req.block_hex.trim()) {
+        Ok(v) => v,
+        Err(_) => return fail("bad block".to_string()),
+    };
+    let tip_hash = match parse_block_bytes(&block_bytes).and_then(|pb| block_hash(&pb.header_bytes))
+    {
+        Ok(v) => v,
+        Err(e) => return fail(err_code(e.code)),
+    };
+    let utxos = match policy_utxo_map(&req.utxos) {
+        Ok(v) => v,
+        Err(e) => return fail(e),
+    };
+    let undo = match &req.undo {
+        Some(raw) => {
+            let raw = match serde_json::to_vec(raw) {
+                Ok(v) => v,
+                Err(_) => return fail("bad undo".to_string()),
+            };
+            match unmarshal_block_undo(&raw) {
+                Ok(v) => v,
+                Err(e) => return fail(e),
+            }
+        }
+        None => {
+            let prev_utxos = match policy_utxo_map(&req.prev_utxos) {
+                Ok(v) => v,
+                Err(e) => return fail(e),
+            };
+            let prev_state = ChainState {
+                has_tip: req.height > 0,
+                height: req.height.saturating_sub(1),
+                tip_hash: [0u8; 32],
+                already_generated: req.prev_already_generated,
+                utxos: prev_utxos,
+            };
+            match build_block_undo(&prev_state, &block_bytes, req.height) {
+                Ok(v) => v,
+                Err(e) => return fail(e),
+            }
+        }
+    };
+
+    let mut state = ChainState {
+        has_tip: true,
+        height: req.height,
+        tip_hash,
+        already_generated: req.already_generated,
+        utxos,
+    };
+    let summary = match state.disconnect_block(&block_bytes, &undo) {
+        Ok(v) => v,
+        Err(e) => return fail(e),
+    };
+
+    Response {
+        ok: true,
+        block_hash: Some(hex::encode(summary.new_tip_hash)),
+        tip_height: summary.has_tip.then_some(summary.new_height),
+        utxo_count: Some(summary.utxo_count),
+        already_generated: Some(summary.already_generated),
+        utxo_set_hash: Some(hex::encode(state.utxo_set_hash())),
+        ..Default::default()
+    }
+}
+
+/// Batch form of `sighash_v1`: one SIGHASH_ALL digest per requested input,
+/// sharing a single prehash cache across the whole transaction.
+fn op_sighash_all(req: &Request) -> Response {
+    let fail = |err: String| Response {
+        ok: false,
+        err: Some(err),
+        ..Default::default()
+    };
+
+    let tx_bytes = match hex::decode(&req.tx_hex) {
+        Ok(v) => v,
+        Err(_) => return fail("bad hex".to_string()),
+    };
+    let tx = match parse_tx(&tx_bytes) {
+        Ok((tx, _txid, _wtxid, _n)) => tx,
+        Err(e) => return fail(err_code(e.code)),
+    };
+    let chain_id = match hex_decode_32(&req.chain_id, "chain_id") {
+        Ok(v) => v,
+        Err(_) => return fail("bad chain_id".to_string()),
+    };
+    if req.input_values.len() != tx.inputs.len() {
+        return fail(err_code(ErrorCode::TxErrParse));
+    }
+    let indices: Vec<u32> = match &req.input_indices {
+        Some(indices) => indices.clone(),
+        None => (0..tx.inputs.len() as u32).collect(),
+    };
+
+    let mut cache = match SighashV1PrehashCache::new(&tx) {
+        Ok(v) => v,
+        Err(e) => return fail(err_code(e.code)),
+    };
+    let mut digests = Vec::with_capacity(indices.len());
+    for input_index in indices {
+        let Some(&input_value) = req.input_values.get(input_index as usize) else {
+            return fail(err_code(ErrorCode::TxErrParse));
+        };
+        match sighash_v1_digest_with_cache(
+            &mut cache,
+            input_index,
+            input_value,
+            chain_id,
+            SIGHASH_ALL,
+        ) {
+            Ok(d) => digests.push(hex::encode(d)),
+            Err(e) => return fail(err_code(e.code)),
+        }
+    }
+
+    Response {
+        ok: true,
+        digests: Some(digests),
+        ..Default::default()
+    }
+}
+
 fn main() {
     let req: Request = match serde_json::from_reader(std::io::stdin()) {
         Ok(v) => v,
@@ -2193,7 +2827,7 @@ fn main() {
         "merkle_root" => {
             let mut txids: Vec<[u8; 32]> = Vec::with_capacity(req.txids.len());
             for h in &req.txids {
-                let b = match hex::decode(h) {
+                let a = match hex_decode_32(h, "txid") {
                     Ok(v) => v,
                     Err(_) => {
                         let resp = Response {
@@ -2214,26 +2848,6 @@ fn main() {
                         return;
                     }
                 };
-                if b.len() != 32 {
-                    let resp = Response {
-                        ok: false,
-                        err: Some("bad txid".to_string()),
-                        txid: None,
-                        wtxid: None,
-                        merkle_root: None,
-                        digest: None,
-                        consumed: None,
-                        block_hash: None,
-                        target_new: None,
-                        fee: None,
-                        utxo_count: None,
-                        ..Default::default()
-                    };
-                    let _ = serde_json::to_writer(std::io::stdout(), &resp);
-                    return;
-                }
-                let mut a = [0u8; 32];
-                a.copy_from_slice(&b);
                 txids.push(a);
             }
             match merkle_root_txids(&txids) {
@@ -2276,7 +2890,7 @@ fn main() {
         "witness_merkle_root" => {
             let mut wtxids: Vec<[u8; 32]> = Vec::with_capacity(req.wtxids.len());
             for h in &req.wtxids {
-                let b = match hex::decode(h) {
+                let a = match hex_decode_32(h, "wtxid") {
                     Ok(v) => v,
                     Err(_) => {
                         let resp = Response {
@@ -2288,17 +2902,6 @@ fn main() {
                         return;
                     }
                 };
-                if b.len() != 32 {
-                    let resp = Response {
-                        ok: false,
-                        err: Some("bad wtxid".to_string()),
-                        ..Default::default()
-                    };
-                    let _ = serde_json::to_writer(std::io::stdout(), &resp);
-                    return;
-                }
-                let mut a = [0u8; 32];
-                a.copy_from_slice(&b);
                 wtxids.push(a);
             }
             match witness_merkle_root_wtxids(&wtxids) {
@@ -2320,6 +2923,10 @@ fn main() {
                 }
             }
         }
+        "sighash_all" => {
+            let resp = op_sighash_all(&req);
+            let _ = serde_json::to_writer(std::io::stdout(), &resp);
+        }
         "sighash_v1" => {
             let tx_bytes = match hex::decode(&req.tx_hex) {
                 Ok(v) => v,
@@ -2364,7 +2971,7 @@ fn main() {
                 }
             };
 
-            let chain_id_bytes = match hex::decode(&req.chain_id) {
+            let chain_id = match hex_decode_32(&req.chain_id, "chain_id") {
                 Ok(v) => v,
                 Err(_) => {
                     let resp = Response {
@@ -2385,26 +2992,6 @@ fn main() {
                     return;
                 }
             };
-            if chain_id_bytes.len() != 32 {
-                let resp = Response {
-                    ok: false,
-                    err: Some("bad chain_id".to_string()),
-                    txid: None,
-                    wtxid: None,
-                    merkle_root: None,
-                    digest: None,
-                    consumed: None,
-                    block_hash: None,
-                    target_new: None,
-                    fee: None,
-                    utxo_count: None,
-                    ..Default::default()
-                };
-                let _ = serde_json::to_writer(std::io::stdout(), &resp);
-                return;
-            }
-            let mut chain_id = [0u8; 32];
-            chain_id.copy_from_slice(&chain_id_bytes);
 
             match sighash_v1_digest(&tx, req.input_index, req.input_value, chain_id) {
                 Ok(d) => {
@@ -2718,7 +3305,7 @@ fn main() {
             let _ = serde_json::to_writer(std::io::stdout(), &resp);
         }
         "block_hash" => {
-            let header_bytes = match hex::decode(&req.header_hex) {
+            let header_bytes = match hex_decode_max(&req.header_hex, usize::MAX, "header_hex") {
                 Ok(v) => v,
                 Err(_) => {
                     let resp = Response {
@@ -2777,7 +3364,7 @@ fn main() {
             }
         }
         "pow_check" => {
-            let header_bytes = match hex::decode(&req.header_hex) {
+            let header_bytes = match hex_decode_max(&req.header_hex, usize::MAX, "header_hex") {
                 Ok(v) => v,
                 Err(_) => {
                     let resp = Response {
@@ -2798,7 +3385,7 @@ fn main() {
                     return;
                 }
             };
-            let target_bytes = match hex::decode(&req.target_hex) {
+            let target = match hex_decode_32(&req.target_hex, "target") {
                 Ok(v) => v,
                 Err(_) => {
                     let resp = Response {
@@ -2819,26 +3406,6 @@ fn main() {
                     return;
                 }
             };
-            if target_bytes.len() != 32 {
-                let resp = Response {
-                    ok: false,
-                    err: Some("bad target".to_string()),
-                    txid: None,
-                    wtxid: None,
-                    merkle_root: None,
-                    digest: None,
-                    consumed: None,
-                    block_hash: None,
-                    target_new: None,
-                    fee: None,
-                    utxo_count: None,
-                    ..Default::default()
-                };
-                let _ = serde_json::to_writer(std::io::stdout(), &resp);
-                return;
-            }
-            let mut target = [0u8; 32];
-            target.copy_from_slice(&target_bytes);
 
             match pow_check(&header_bytes, target) {
                 Ok(()) => {
@@ -2878,7 +3445,7 @@ fn main() {
             }
         }
         "retarget_v1" => {
-            let old_bytes = match hex::decode(&req.target_old) {
+            let old = match hex_decode_32(&req.target_old, "target_old") {
                 Ok(v) => v,
                 Err(_) => {
                     let resp = Response {
@@ -2899,26 +3466,6 @@ fn main() {
                     return;
                 }
             };
-            if old_bytes.len() != 32 {
-                let resp = Response {
-                    ok: false,
-                    err: Some("bad target_old".to_string()),
-                    txid: None,
-                    wtxid: None,
-                    merkle_root: None,
-                    digest: None,
-                    consumed: None,
-                    block_hash: None,
-                    target_new: None,
-                    fee: None,
-                    utxo_count: None,
-                    ..Default::default()
-                };
-                let _ = serde_json::to_writer(std::io::stdout(), &resp);
-                return;
-            }
-            let mut old = [0u8; 32];
-            old.copy_from_slice(&old_bytes);
 
             let retarget_res = if !req.window_timestamps.is_empty() {
                 retarget_v1_clamped(old, &req.window_timestamps)
@@ -2962,6 +3509,22 @@ fn main() {
                 }
             }
         }
+        "apply_block" => {
+            let resp = op_apply_block(&req);
+            let _ = serde_json::to_writer(std::io::stdout(), &resp);
+        }
+        "connect_chain" => {
+            let resp = op_connect_chain(&req);
+            let _ = serde_json::to_writer(std::io::stdout(), &resp);
+        }
+        "utxo_set_hash" => {
+            let resp = op_utxo_set_hash(&req);
+            let _ = serde_json::to_writer(std::io::stdout(), &resp);
+        }
+        "disconnect_block" => {
+            let resp = op_disconnect_block(&req);
+            let _ = serde_json::to_writer(std::io::stdout(), &resp);
+        }
         "block_basic_check" => {
             let block_bytes = match hex::decode(&req.block_hex) {
                 Ok(v) => v,
@@ -2988,7 +3551,7 @@ fn main() {
             let expected_prev = if req.expected_prev_hash.is_empty() {
                 None
             } else {
-                let b = match hex::decode(&req.expected_prev_hash) {
+                let h = match hex_decode_32(&req.expected_prev_hash, "expected_prev_hash") {
                     Ok(v) => v,
                     Err(_) => {
                         let resp = Response {
@@ -3009,33 +3572,13 @@ fn main() {
                         return;
                     }
                 };
-                if b.len() != 32 {
-                    let resp = Response {
-                        ok: false,
-                        err: Some("bad expected_prev_hash".to_string()),
-                        txid: None,
-                        wtxid: None,
-                        merkle_root: None,
-                        digest: None,
-                        consumed: None,
-                        block_hash: None,
-                        target_new: None,
-                        fee: None,
-                        utxo_count: None,
-                        ..Default::default()
-                    };
-                    let _ = serde_json::to_writer(std::io::stdout(), &resp);
-                    return;
-                }
-                let mut h = [0u8; 32];
-                h.copy_from_slice(&b);
                 Some(h)
             };
 
             let expected_target = if req.expected_target.is_empty() {
                 None
             } else {
-                let b = match hex::decode(&req.expected_target) {
+                let h = match hex_decode_32(&req.expected_target, "expected_target") {
                     Ok(v) => v,
                     Err(_) => {
                         let resp = Response {
@@ -3056,26 +3599,6 @@ fn main() {
                         return;
                     }
                 };
-                if b.len() != 32 {
-                    let resp = Response {
-                        ok: false,
-                        err: Some("bad expected_target".to_string()),
-                        txid: None,
-                        wtxid: None,
-                        merkle_root: None,
-                        digest: None,
-                        consumed: None,
-                        block_hash: None,
-                        target_new: None,
-                        fee: None,
-                        utxo_count: None,
-                        ..Default::default()
-                    };
-                    let _ = serde_json::to_writer(std::io::stdout(), &resp);
-                    return;
-                }
-                let mut h = [0u8; 32];
-                h.copy_from_slice(&b);
                 Some(h)
             };
 
@@ -3154,7 +3677,7 @@ fn main() {
             let expected_prev = if req.expected_prev_hash.is_empty() {
                 None
             } else {
-                let b = match hex::decode(&req.expected_prev_hash) {
+                let h = match hex_decode_32(&req.expected_prev_hash, "expected_prev_hash") {
                     Ok(v) => v,
                     Err(_) => {
                         let resp = Response {
@@ -3175,33 +3698,13 @@ fn main() {
                         return;
                     }
                 };
-                if b.len() != 32 {
-                    let resp = Response {
-                        ok: false,
-                        err: Some("bad expected_prev_hash".to_string()),
-                        txid: None,
-                        wtxid: None,
-                        merkle_root: None,
-                        digest: None,
-                        consumed: None,
-                        block_hash: None,
-                        target_new: None,
-                        fee: None,
-                        utxo_count: None,
-                        ..Default::default()
-                    };
-                    let _ = serde_json::to_writer(std::io::stdout(), &resp);
-                    return;
-                }
-                let mut h = [0u8; 32];
-                h.copy_from_slice(&b);
                 Some(h)
             };
 
             let expected_target = if req.expected_target.is_empty() {
                 None
             } else {
-                let b = match hex::decode(&req.expected_target) {
+                let h = match hex_decode_32(&req.expected_target, "expected_target") {
                     Ok(v) => v,
                     Err(_) => {
                         let resp = Response {
@@ -3222,26 +3725,6 @@ fn main() {
                         return;
                     }
                 };
-                if b.len() != 32 {
-                    let resp = Response {
-                        ok: false,
-                        err: Some("bad expected_target".to_string()),
-                        txid: None,
-                        wtxid: None,
-                        merkle_root: None,
-                        digest: None,
-                        consumed: None,
-                        block_hash: None,
-                        target_new: None,
-                        fee: None,
-                        utxo_count: None,
-                        ..Default::default()
-                    };
-                    let _ = serde_json::to_writer(std::io::stdout(), &resp);
-                    return;
-                }
-                let mut h = [0u8; 32];
-                h.copy_from_slice(&b);
                 Some(h)
             };
 
@@ -3313,7 +3796,7 @@ fn main() {
             let expected_prev = if req.expected_prev_hash.is_empty() {
                 None
             } else {
-                let b = match hex::decode(&req.expected_prev_hash) {
+                let h = match hex_decode_32(&req.expected_prev_hash, "expected_prev_hash") {
                     Ok(v) => v,
                     Err(_) => {
                         let resp = Response {
@@ -3325,24 +3808,13 @@ fn main() {
                         return;
                     }
                 };
-                if b.len() != 32 {
-                    let resp = Response {
-                        ok: false,
-                        err: Some("bad expected_prev_hash".to_string()),
-                        ..Default::default()
-                    };
-                    let _ = serde_json::to_writer(std::io::stdout(), &resp);
-                    return;
-                }
-                let mut h = [0u8; 32];
-                h.copy_from_slice(&b);
                 Some(h)
             };
 
             let expected_target = if req.expected_target.is_empty() {
                 None
             } else {
-                let b = match hex::decode(&req.expected_target) {
+                let h = match hex_decode_32(&req.expected_target, "expected_target") {
                     Ok(v) => v,
                     Err(_) => {
                         let resp = Response {
@@ -3354,17 +3826,6 @@ fn main() {
                         return;
                     }
                 };
-                if b.len() != 32 {
-                    let resp = Response {
-                        ok: false,
-                        err: Some("bad expected_target".to_string()),
-                        ..Default::default()
-                    };
-                    let _ = serde_json::to_writer(std::io::stdout(), &resp);
-                    return;
-                }
-                let mut h = [0u8; 32];
-                h.copy_from_slice(&b);
                 Some(h)
             };
 
@@ -3374,59 +3835,18 @@ fn main() {
                 Some(req.prev_timestamps.as_slice())
             };
 
-            let mut utxo_set: HashMap<Outpoint, UtxoEntry> =
-                HashMap::with_capacity(req.utxos.len());
-            for u in &req.utxos {
-                let txid_raw = match hex::decode(&u.txid) {
-                    Ok(v) => v,
-                    Err(_) => {
-                        let resp = Response {
-                            ok: false,
-                            err: Some("bad utxo txid".to_string()),
-                            ..Default::default()
-                        };
-                        let _ = serde_json::to_writer(std::io::stdout(), &resp);
-                        return;
-                    }
-                };
-                if txid_raw.len() != 32 {
+            let utxo_set = match utxo_set_from_json(&req.utxos) {
+                Ok(v) => v,
+                Err(e) => {
                     let resp = Response {
                         ok: false,
-                        err: Some("bad utxo txid".to_string()),
+                        err: Some(e),
                         ..Default::default()
                     };
                     let _ = serde_json::to_writer(std::io::stdout(), &resp);
                     return;
                 }
-                let cov_data = match hex::decode(&u.covenant_data) {
-                    Ok(v) => v,
-                    Err(_) => {
-                        let resp = Response {
-                            ok: false,
-                            err: Some("bad utxo covenant_data".to_string()),
-                            ..Default::default()
-                        };
-                        let _ = serde_json::to_writer(std::io::stdout(), &resp);
-                        return;
-                    }
-                };
-
-                let mut op_txid = [0u8; 32];
-                op_txid.copy_from_slice(&txid_raw);
-                utxo_set.insert(
-                    Outpoint {
-                        txid: op_txid,
-                        vout: u.vout,
-                    },
-                    UtxoEntry {
-                        value: u.value,
-                        covenant_type: u.covenant_type,
-                        covenant_data: cov_data,
-                        creation_height: u.creation_height,
-                        created_by_coinbase: u.created_by_coinbase,
-                    },
-                );
-            }
+            };
 
             let mut state = InMemoryChainState {
                 utxos: utxo_set,
@@ -3435,7 +3855,7 @@ fn main() {
 
             let mut chain_id = [0u8; 32];
             if !req.chain_id.trim().is_empty() {
-                let b = match hex::decode(req.chain_id.trim()) {
+                chain_id = match hex_decode_32(&req.chain_id, "chain_id") {
                     Ok(v) => v,
                     Err(_) => {
                         let resp = Response {
@@ -3447,16 +3867,6 @@ fn main() {
                         return;
                     }
                 };
-                if b.len() != 32 {
-                    let resp = Response {
-                        ok: false,
-                        err: Some("bad chain_id".to_string()),
-                        ..Default::default()
-                    };
-                    let _ = serde_json::to_writer(std::io::stdout(), &resp);
-                    return;
-                }
-                chain_id.copy_from_slice(&b);
             }
 
             if let Err(e) = reject_core_ext_profiles_from_json(
@@ -3668,92 +4078,24 @@ fn main() {
                 }
             };
 
-            let mut utxo_set: HashMap<Outpoint, UtxoEntry> =
-                HashMap::with_capacity(req.utxos.len());
-            for u in &req.utxos {
-                let txid_raw = match hex::decode(&u.txid) {
-                    Ok(v) => v,
-                    Err(_) => {
-                        let resp = Response {
-                            ok: false,
-                            err: Some("bad utxo txid".to_string()),
-                            txid: None,
-                            wtxid: None,
-                            merkle_root: None,
-                            digest: None,
-                            consumed: None,
-                            block_hash: None,
-                            target_new: None,
-                            fee: None,
-                            utxo_count: None,
-                            ..Default::default()
-                        };
-                        let _ = serde_json::to_writer(std::io::stdout(), &resp);
-                        return;
-                    }
-                };
-                if txid_raw.len() != 32 {
+            let utxo_set = match utxo_set_from_json(&req.utxos) {
+                Ok(v) => v,
+                Err(e) => {
                     let resp = Response {
                         ok: false,
-                        err: Some("bad utxo txid".to_string()),
-                        txid: None,
-                        wtxid: None,
-                        merkle_root: None,
-                        digest: None,
-                        consumed: None,
-                        block_hash: None,
-                        target_new: None,
-                        fee: None,
-                        utxo_count: None,
+                        err: Some(e),
                         ..Default::default()
                     };
-                    let _ = serde_json::to_writer(std::io::stdout(), &resp);
-                    return;
-                }
-                let cov_data = match hex::decode(&u.covenant_data) {
-                    Ok(v) => v,
-                    Err(_) => {
-                        let resp = Response {
-                            ok: false,
-                            err: Some("bad utxo covenant_data".to_string()),
-                            txid: None,
-                            wtxid: None,
-                            merkle_root: None,
-                            digest: None,
-                            consumed: None,
-                            block_hash: None,
-                            target_new: None,
-                            fee: None,
-                            utxo_count: None,
-                            ..Default::default()
-                        };
-                        let _ = serde_json::to_writer(std::io::stdout(), &resp);
-                        return;
-                    }
-                };
-
-                let mut op_txid = [0u8; 32];
-                op_txid.copy_from_slice(&txid_raw);
-                utxo_set.insert(
-                    Outpoint {
-                        txid: op_txid,
-                        vout: u.vout,
-                    },
-                    UtxoEntry {
-                        value: u.value,
-                        covenant_type: u.covenant_type,
-                        covenant_data: cov_data,
-                        creation_height: u.creation_height,
-                        created_by_coinbase: u.created_by_coinbase,
-                    },
-                );
-            }
+                    let _ = serde_json::to_writer(std::io::stdout(), &resp);
+                    return;
+                }
+            };
 
             let block_mtp = req.block_mtp.unwrap_or(req.block_timestamp);
 
             let mut chain_id = [0u8; 32];
             if !req.chain_id.trim().is_empty() {
-                let b = match hex::decode(req.chain_id.trim()) {
+                chain_id = match hex_decode_32(&req.chain_id, "chain_id") {
                     Ok(v) => v,
                     Err(_) => {
                         let resp = Response {
@@ -3765,16 +4107,6 @@ fn main() {
                         return;
                     }
                 };
-                if b.len() != 32 {
-                    let resp = Response {
-                        ok: false,
-                        err: Some("bad chain_id".to_string()),
-                        ..Default::default()
-                    };
-                    let _ = serde_json::to_writer(std::io::stdout(), &resp);
-                    return;
-                }
-                chain_id.copy_from_slice(&b);
             }
             if let Err(e) = reject_core_ext_profiles_from_json(
                 &req.core_ext_profiles,
@@ -3851,7 +4183,7 @@ fn main() {
             }
         }
         "compact_shortid" => {
-            let wtxid_bytes = match hex::decode(&req.wtxid) {
+            let wtxid = match hex_decode_32(&req.wtxid, "wtxid") {
                 Ok(v) => v,
                 Err(_) => {
                     let resp = Response {
@@ -3872,26 +4204,6 @@ fn main() {
                     return;
                 }
             };
-            if wtxid_bytes.len() != 32 {
-                let resp = Response {
-                    ok: false,
-                    err: Some("bad wtxid".to_string()),
-                    txid: None,
-                    wtxid: None,
-                    merkle_root: None,
-                    digest: None,
-                    consumed: None,
-                    block_hash: None,
-                    target_new: None,
-                    fee: None,
-                    utxo_count: None,
-                    ..Default::default()
-                };
-                let _ = serde_json::to_writer(std::io::stdout(), &resp);
-                return;
-            }
-            let mut wtxid = [0u8; 32];
-            wtxid.copy_from_slice(&wtxid_bytes);
             let sid = compact_shortid(wtxid, req.nonce1, req.nonce2);
             let resp = Response {
                 ok: true,
@@ -3931,9 +4243,9 @@ fn main() {
 
             let mut wire: Vec<u8> = Vec::new();
             wire.push(suite_id);
-            wire.extend_from_slice(&encode_compact_size(pub_len as u64));
+            encode_compact_size(pub_len as u64, &mut wire);
             wire.extend(vec![0x11u8; pub_len]);
-            wire.extend_from_slice(&encode_compact_size(sig_len as u64));
+            encode_compact_size(sig_len as u64, &mut wire);
             wire.extend(vec![0x22u8; sig_len]);
 
             let mut off = 0usize;
@@ -4824,84 +5136,11 @@ fn main() {
             let _ = serde_json::to_writer(std::io::stdout(), &resp);
         }
         "output_descriptor_bytes" => {
-            let cov_data = match hex::decode(&req.covenant_data_hex) {
-                Ok(v) => v,
-                Err(_) => {
-                    let resp = Response {
-                        ok: false,
-                        err: Some("bad covenant_data_hex".to_string()),
-                        txid: None,
-                        wtxid: None,
-                        merkle_root: None,
-                        digest: None,
-                        consumed: None,
-                        block_hash: None,
-                        target_new: None,
-                        fee: None,
-                        utxo_count: None,
-                        ..Default::default()
-                    };
-                    let _ = serde_json::to_writer(std::io::stdout(), &resp);
-                    return;
-                }
-            };
-            let desc = output_descriptor_bytes(req.covenant_type, &cov_data);
-            let resp = Response {
-                ok: true,
-                err: None,
-                txid: None,
-                wtxid: None,
-                merkle_root: None,
-                digest: Some(hex::encode(desc)),
-                consumed: None,
-                block_hash: None,
-                target_new: None,
-                fee: None,
-                utxo_count: None,
-                ..Default::default()
-            };
+            let resp = output_descriptor_response(&req, false);
             let _ = serde_json::to_writer(std::io::stdout(), &resp);
         }
         "output_descriptor_hash" => {
-            let cov_data = match hex::decode(&req.covenant_data_hex) {
-                Ok(v) => v,
-                Err(_) => {
-                    let resp = Response {
-                        ok: false,
-                        err: Some("bad covenant_data_hex".to_string()),
-                        txid: None,
-                        wtxid: None,
-                        merkle_root: None,
-                        digest: None,
-                        consumed: None,
-                        block_hash: None,
-                        target_new: None,
-                        fee: None,
-                        utxo_count: None,
-                        ..Default::default()
-                    };
-                    let _ = serde_json::to_writer(std::io::stdout(), &resp);
-                    return;
-                }
-            };
-            let desc = output_descriptor_bytes(req.covenant_type, &cov_data);
-            let mut hasher = Sha3_256::new();
-            hasher.update(desc);
-            let h = hasher.finalize();
-            let resp = Response {
-                ok: true,
-                err: None,
-                txid: None,
-                wtxid: None,
-                merkle_root: None,
-                digest: Some(hex::encode(h)),
-                consumed: None,
-                block_hash: None,
-                target_new: None,
-                fee: None,
-                utxo_count: None,
-                ..Default::default()
-            };
+            let resp = output_descriptor_response(&req, true);
             let _ = serde_json::to_writer(std::io::stdout(), &resp);
         }
         "nonce_replay_intrablock" => {
@@ -4932,20 +5171,15 @@ fn main() {
             let _ = serde_json::to_writer(std::io::stdout(), &resp);
         }
         "timestamp_bounds" => {
-            let max_future_drift = req.max_future_drift.unwrap_or(7_200);
-            if req.timestamp <= req.mtp {
-                let resp = Response {
-                    ok: false,
-                    err: Some(err_code(ErrorCode::BlockErrTimestampOld)),
-                    ..Default::default()
-                };
-                let _ = serde_json::to_writer(std::io::stdout(), &resp);
-                return;
-            }
-            if req.timestamp > req.mtp.saturating_add(max_future_drift) {
+            let max_future_drift = req
+                .max_future_drift
+                .unwrap_or(rubin_consensus::constants::MAX_FUTURE_DRIFT);
+            if let Err(e) =
+                rubin_consensus::check_timestamp_bounds(req.timestamp, req.mtp, max_future_drift)
+            {
                 let resp = Response {
                     ok: false,
-                    err: Some(err_code(ErrorCode::BlockErrTimestampFuture)),
+                    err: Some(err_code(e.code)),
                     ..Default::default()
                 };
                 let _ = serde_json::to_writer(std::io::stdout(), &resp);
@@ -4994,6 +5228,10 @@ fn main() {
             };
             let _ = serde_json::to_writer(std::io::stdout(), &resp);
         }
+        "tx_validation_trace" => {
+            let resp = op_tx_validation_trace(&req);
+            let _ = serde_json::to_writer(std::io::stdout(), &resp);
+        }
         "validation_order" => {
             if req.checks.is_empty() {
                 let resp = Response {
@@ -5206,32 +5444,6 @@ fn main() {
     }
 }
 
-fn output_descriptor_bytes(covenant_type: u16, covenant_data: &[u8]) -> Vec<u8> {
-    let mut out = Vec::with_capacity(2 + 9 + covenant_data.len());
-    out.extend_from_slice(&covenant_type.to_le_bytes());
-    out.extend_from_slice(&encode_compact_size(covenant_data.len() as u64));
-    out.extend_from_slice(covenant_data);
-    out
-}
-
-fn encode_compact_size(n: u64) -> Vec<u8> {
-    if n < 0xfd {
-        vec![n as u8]
-    } else if n <= 0xffff {
-        let mut out = vec![0xfd, 0, 0];
-        out[1..3].copy_from_slice(&(n as u16).to_le_bytes());
-        out
-    } else if n <= 0xffff_ffff {
-        let mut out = vec![0xfe, 0, 0, 0, 0];
-        out[1..5].copy_from_slice(&(n as u32).to_le_bytes());
-        out
-    } else {
-        let mut out = vec![0xff, 0, 0, 0, 0, 0, 0, 0, 0];
-        out[1..9].copy_from_slice(&n.to_le_bytes());
-        out
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -5839,4 +6051,598 @@ mod tests {
         };
         assert!(err.to_string().contains("bad suite_registry"));
     }
+
+    fn load_fixture_vectors(name: &str) -> Vec<Value> {
+        let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("../../../../conformance/fixtures")
+            .join(name);
+        let raw = fs::read_to_string(path).expect("read fixture");
+        let fixture: Value = serde_json::from_str(&raw).expect("parse fixture");
+        fixture["vectors"].as_array().expect("vectors").clone()
+    }
+
+    fn devnet_chain_blocks(n: usize) -> Vec<Value> {
+        let mut blocks = load_fixture_vectors("CV-DEVNET-GENESIS.json");
+        blocks.extend(
+            load_fixture_vectors("CV-DEVNET-CHAIN.json")
+                .into_iter()
+                .take(n),
+        );
+        blocks
+    }
+
+    fn block_header_hex(vector: &Value) -> String {
+        let block_hex = vector["block_hex"].as_str().expect("block_hex");
+        block_hex[..BLOCK_HEADER_BYTES * 2].to_string()
+    }
+
+    fn apply_block_request(vector: &Value, ancestors: &[Value]) -> Request {
+        let mut req: Request = serde_json::from_value(vector.clone()).expect("request");
+        req.op = "apply_block".to_string();
+        req.expected_prev_hash.clear();
+        req.expected_target.clear();
+        req.prev_timestamps.clear();
+        req.ancestor_headers_hex = ancestors.iter().map(block_header_hex).collect();
+        req
+    }
+
+    #[test]
+    fn connect_chain_replays_devnet_genesis_and_three_blocks() {
+        let blocks = devnet_chain_blocks(3);
+        let tip = &blocks[3];
+        let req = Request {
+            op: "connect_chain".to_string(),
+            chain_id: blocks[0]["chain_id"].as_str().unwrap().to_string(),
+            blocks_hex: blocks
+                .iter()
+                .map(|b| b["block_hex"].as_str().unwrap().to_string())
+                .collect(),
+            ..Default::default()
+        };
+        let resp = op_connect_chain(&req);
+        assert!(resp.ok, "err={:?}", resp.err);
+        assert_eq!(resp.tip_height, Some(3));
+        assert_eq!(resp.block_hash.as_deref(), tip["block_hash"].as_str());
+        assert_eq!(resp.utxo_count, tip["expect_utxo_count"].as_u64());
+        assert_eq!(
+            resp.already_generated,
+            tip["chainstate_after"]["already_generated"].as_u64()
+        );
+        assert_eq!(resp.sum_fees, Some(0));
+        assert!(resp.weight.unwrap_or(0) > 0);
+        assert!(resp.utxo_set_hash.is_some());
+    }
+
+    #[test]
+    fn apply_block_matches_connect_chain_tip() {
+        let blocks = devnet_chain_blocks(3);
+        let chain = Request {
+            op: "connect_chain".to_string(),
+            chain_id: blocks[0]["chain_id"].as_str().unwrap().to_string(),
+            blocks_hex: blocks
+                .iter()
+                .map(|b| b["block_hex"].as_str().unwrap().to_string())
+                .collect(),
+            ..Default::default()
+        };
+        let chain_resp = op_connect_chain(&chain);
+        assert!(chain_resp.ok, "err={:?}", chain_resp.err);
+
+        let resp = op_apply_block(&apply_block_request(&blocks[3], &blocks[..3]));
+        assert!(resp.ok, "err={:?}", resp.err);
+        assert_eq!(resp.block_hash, chain_resp.block_hash);
+        assert_eq!(resp.utxo_set_hash, chain_resp.utxo_set_hash);
+        assert_eq!(resp.utxo_count, chain_resp.utxo_count);
+        assert_eq!(
+            resp.already_generated_n1,
+            blocks[3]["expect_already_generated_n1"].as_u64()
+        );
+    }
+
+    #[test]
+    fn apply_block_rejects_header_and_coinbase_mutations() {
+        let blocks = devnet_chain_blocks(3);
+        let base = || apply_block_request(&blocks[3], &blocks[..3]);
+        let reject = |req: &Request| op_apply_block(req).err.unwrap_or_default();
+
+        let mut wrong_parent = base();
+        wrong_parent.ancestor_headers_hex.pop();
+        assert_eq!(reject(&wrong_parent), "BLOCK_ERR_LINKAGE_INVALID");
+
+        let mut wrong_target = base();
+        wrong_target.expected_target = "7f".repeat(32);
+        assert_eq!(reject(&wrong_target), "BLOCK_ERR_TARGET_INVALID");
+
+        let mut bad_merkle = base();
+        let mut block = hex::decode(&bad_merkle.block_hex).unwrap();
+        block[36] ^= 0x01;
+        bad_merkle.block_hex = hex::encode(block);
+        assert_eq!(reject(&bad_merkle), "BLOCK_ERR_MERKLE_INVALID");
+
+        let mut future = base();
+        future.expected_prev_hash = blocks[2]["block_hash"].as_str().unwrap().to_string();
+        for ancestor in future.ancestor_headers_hex.iter_mut() {
+            let mut header = hex::decode(&*ancestor).unwrap();
+            header[68..76].copy_from_slice(&0u64.to_le_bytes());
+            *ancestor = hex::encode(header);
+        }
+        assert_eq!(reject(&future), "BLOCK_ERR_TIMESTAMP_FUTURE");
+
+        let mut old = base();
+        old.expected_prev_hash = blocks[2]["block_hash"].as_str().unwrap().to_string();
+        for ancestor in old.ancestor_headers_hex.iter_mut() {
+            let mut header = hex::decode(&*ancestor).unwrap();
+            header[68..76].copy_from_slice(&u64::MAX.to_le_bytes());
+            *ancestor = hex::encode(header);
+        }
+        assert_eq!(reject(&old), "BLOCK_ERR_TIMESTAMP_OLD");
+
+        let mut wrong_height = base();
+        wrong_height.height -= 1;
+        assert_eq!(reject(&wrong_height), "BLOCK_ERR_COINBASE_INVALID");
+
+        let mut exhausted = base();
+        exhausted.already_generated = u64::MAX / 2;
+        assert_eq!(reject(&exhausted), "BLOCK_ERR_SUBSIDY_EXCEEDED");
+    }
+
+    #[test]
+    fn apply_block_replays_block_basic_rejection_vectors() {
+        let mut vectors = load_fixture_vectors("CV-BLOCK-BASIC.json");
+        vectors.extend(load_fixture_vectors("CV-DA-INTEGRITY.json"));
+        let mut replayed = 0;
+        for vector in &vectors {
+            if vector["expect_ok"].as_bool() != Some(false) {
+                continue;
+            }
+            let id = vector["id"].as_str().unwrap_or_default();
+            let expect = vector["expect_err"].as_str().unwrap_or_default();
+            let mut req: Request = serde_json::from_value(vector.clone()).expect("request");
+            req.op = "apply_block".to_string();
+            let resp = op_apply_block(&req);
+            assert!(!resp.ok, "{id}");
+            assert_eq!(resp.err.as_deref(), Some(expect), "{id}");
+            replayed += 1;
+        }
+        assert!(replayed > 0);
+    }
+
+    #[test]
+    fn connect_chain_reports_failing_block_index() {
+        let blocks = devnet_chain_blocks(3);
+        let mut blocks_hex: Vec<String> = blocks
+            .iter()
+            .map(|b| b["block_hex"].as_str().unwrap().to_string())
+            .collect();
+        blocks_hex.swap(2, 3);
+        let req = Request {
+            op: "connect_chain".to_string(),
+            chain_id: blocks[0]["chain_id"].as_str().unwrap().to_string(),
+            blocks_hex,
+            ..Default::default()
+        };
+        let resp = op_connect_chain(&req);
+        assert!(!resp.ok);
+        assert_eq!(resp.err.as_deref(), Some("BLOCK_ERR_LINKAGE_INVALID"));
+        assert_eq!(
+            resp.diagnostics,
+            Some(serde_json::json!({ "block_index": 2 }))
+        );
+    }
+
+    fn utxos_from_value(value: &Value) -> Vec<UtxoJson> {
+        serde_json::from_value(value.clone()).expect("utxos")
+    }
+
+    #[test]
+    fn utxo_set_from_json_is_lossless_and_strict() {
+        let txid = "11".repeat(32);
+        let utxos = utxos_from_value(&serde_json::json!([{
+            "txid": txid,
+            "vout": 3,
+            "value": 42,
+            "covenant_type": 0,
+            "covenant_data": "01aa",
+            "creation_height": 7,
+            "created_by_coinbase": true
+        }]));
+        let set = utxo_set_from_json(&utxos).expect("utxo set");
+        let entry = &set[&Outpoint {
+            txid: [0x11; 32],
+            vout: 3,
+        }];
+        assert_eq!(
+            (entry.value, entry.covenant_type, entry.creation_height),
+            (42, 0, 7)
+        );
+        assert_eq!(entry.covenant_data, vec![0x01, 0xaa]);
+        assert!(entry.created_by_coinbase);
+        assert_eq!(policy_utxo_map(&utxos).expect("policy map"), set);
+
+        let prefixed = utxos_from_value(&serde_json::json!([{
+            "txid": format!(" 0x{txid}\n"), "vout": 3, "value": 42, "covenant_type": 0,
+            "covenant_data": "01aa", "creation_height": 7, "created_by_coinbase": true
+        }]));
+        assert_eq!(
+            utxo_set_from_json(&prefixed).expect("0x-prefixed txid"),
+            set
+        );
+
+        for bad_txid in ["1".repeat(63), "11".repeat(31), "zz".repeat(32)] {
+            let mut bad = utxos_from_value(&serde_json::json!([{
+                "txid": "", "vout": 0, "value": 0, "covenant_type": 0,
+                "covenant_data": "", "creation_height": 0, "created_by_coinbase": false
+            }]));
+            bad[0].txid = bad_txid;
+            assert_eq!(utxo_set_from_json(&bad).unwrap_err(), "bad utxo txid");
+        }
+        let mut bad = utxos;
+        bad[0].covenant_data = "0".to_string();
+        assert_eq!(
+            utxo_set_from_json(&bad).unwrap_err(),
+            "bad utxo covenant_data"
+        );
+    }
+
+    /// A digest pinned by the Go client's chainstate tests, read from the
+    /// Go source so both clients check the same golden value.
+    fn go_pinned_digest(name: &str) -> String {
+        let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("../../../go/node/chainstate_test.go");
+        let src = fs::read_to_string(&path).expect("read Go chainstate_test.go");
+        let line = src
+            .lines()
+            .find(|line| line.trim_start().starts_with(name) && line.contains('='))
+            .unwrap_or_else(|| panic!("{name} not in {}", path.display()));
+        line.split('"').nth(1).expect("quoted digest").to_string()
+    }
+
+    #[test]
+    fn utxo_set_hash_matches_go_golden_digests() {
+        let empty = op_utxo_set_hash(&Request::default());
+        assert!(empty.ok, "err={:?}", empty.err);
+        assert_eq!(
+            empty.utxo_set_hash,
+            Some(go_pinned_digest("chainStateEmptyDigestHex"))
+        );
+
+        // The Go vector is the state after connecting the devnet genesis
+        // block, which is the chainstate the genesis fixture records.
+        let genesis = load_fixture_vectors("CV-DEVNET-GENESIS.json");
+        let req = Request {
+            utxos: utxos_from_value(&genesis[0]["chainstate_after"]["utxos"]),
+            ..Default::default()
+        };
+        let resp = op_utxo_set_hash(&req);
+        assert!(resp.ok, "err={:?}", resp.err);
+        assert_eq!(resp.utxo_count, Some(1));
+        assert_eq!(
+            resp.utxo_set_hash,
+            Some(go_pinned_digest("chainStateGenesisOnlyDigestHex"))
+        );
+    }
+
+    #[test]
+    fn disconnect_block_restores_pre_connect_utxo_set_hash() {
+        let blocks = devnet_chain_blocks(3);
+        let vector = &blocks[3];
+        let connected = op_apply_block(&apply_block_request(vector, &blocks[..3]));
+        assert!(connected.ok, "err={:?}", connected.err);
+
+        let pre_hash = op_utxo_set_hash(&Request {
+            utxos: utxos_from_value(&vector["utxos"]),
+            ..Default::default()
+        })
+        .utxo_set_hash;
+        let post_utxos = &vector["chainstate_after"]["utxos"];
+        let post_hash = op_utxo_set_hash(&Request {
+            utxos: utxos_from_value(post_utxos),
+            ..Default::default()
+        })
+        .utxo_set_hash;
+        assert_eq!(post_hash, connected.utxo_set_hash);
+
+        let disconnect = |undo: Option<Value>| {
+            op_disconnect_block(&Request {
+                op: "disconnect_block".to_string(),
+                block_hex: vector["block_hex"].as_str().unwrap().to_string(),
+                height: 3,
+                already_generated: connected.already_generated_n1.unwrap(),
+                utxos: utxos_from_value(post_utxos),
+                prev_utxos: utxos_from_value(&vector["utxos"]),
+                prev_already_generated: vector["already_generated"].as_u64().unwrap(),
+                undo,
+                ..Default::default()
+            })
+        };
+
+        let regenerated = disconnect(None);
+        assert!(regenerated.ok, "err={:?}", regenerated.err);
+        assert_eq!(regenerated.utxo_set_hash, pre_hash);
+        assert_eq!(regenerated.tip_height, Some(2));
+        assert_eq!(
+            regenerated.block_hash.as_deref(),
+            blocks[2]["block_hash"].as_str()
+        );
+        assert_eq!(
+            regenerated.already_generated,
+            vector["already_generated"].as_u64()
+        );
+
+        let mut prev_state = ChainState::new();
+        prev_state.utxos = policy_utxo_map(&utxos_from_value(&vector["utxos"])).unwrap();
+        prev_state.already_generated = vector["already_generated"].as_u64().unwrap();
+        let block_bytes = hex::decode(vector["block_hex"].as_str().unwrap()).unwrap();
+        let undo = build_block_undo(&prev_state, &block_bytes, 3).expect("undo");
+        let undo_json: Value =
+            serde_json::from_slice(&rubin_node::undo::marshal_block_undo_v0(&undo).unwrap())
+                .unwrap();
+        let explicit = disconnect(Some(undo_json));
+        assert!(explicit.ok, "err={:?}", explicit.err);
+        assert_eq!(explicit.utxo_set_hash, pre_hash);
+    }
+
+    fn sixteen_input_tx_hex() -> String {
+        let tx = Tx {
+            version: 1,
+            tx_kind: 0x00,
+            tx_nonce: 7,
+            inputs: (0..16u8)
+                .map(|i| TxInput {
+                    prev_txid: [i; 32],
+                    prev_vout: u32::from(i),
+                    script_sig: Vec::new(),
+                    sequence: u32::from(i) * 3,
+                })
+                .collect(),
+            outputs: vec![TxOutput {
+                value: 1_000,
+                covenant_type: COV_TYPE_P2PK,
+                covenant_data: vec![0x01; 33],
+            }],
+            locktime: 0,
+            da_commit_core: None,
+            da_chunk_core: None,
+            witness: Vec::new(),
+            da_payload: Vec::new(),
+        };
+        hex::encode(rubin_consensus::marshal_tx(&tx).expect("marshal"))
+    }
+
+    #[test]
+    fn sighash_all_matches_per_input_sighash_v1() {
+        let tx_hex = sixteen_input_tx_hex();
+        let chain_id = [0x5a; 32];
+        let input_values: Vec<u64> = (0..16).map(|i| 1_000 + i * 17).collect();
+        let resp = op_sighash_all(&Request {
+            tx_hex: tx_hex.clone(),
+            chain_id: hex::encode(chain_id),
+            input_values: input_values.clone(),
+            ..Default::default()
+        });
+        assert!(resp.ok, "err={:?}", resp.err);
+        let digests = resp.digests.expect("digests");
+        assert_eq!(digests.len(), 16);
+
+        let (tx, _, _, _) = parse_tx(&hex::decode(&tx_hex).unwrap()).unwrap();
+        for (i, digest) in digests.iter().enumerate() {
+            let expected = sighash_v1_digest(&tx, i as u32, input_values[i], chain_id).unwrap();
+            assert_eq!(digest, &hex::encode(expected), "input {i}");
+        }
+
+        let subset = op_sighash_all(&Request {
+            tx_hex,
+            chain_id: hex::encode(chain_id),
+            input_values,
+            input_indices: Some(vec![15, 3]),
+            ..Default::default()
+        });
+        assert!(subset.ok, "err={:?}", subset.err);
+        assert_eq!(
+            subset.digests,
+            Some(vec![digests[15].clone(), digests[3].clone()])
+        );
+    }
+
+    #[test]
+    fn sighash_all_rejects_mismatched_input_values_and_indices() {
+        let tx_hex = sixteen_input_tx_hex();
+        let short = op_sighash_all(&Request {
+            tx_hex: tx_hex.clone(),
+            chain_id: "00".repeat(32),
+            input_values: vec![1; 15],
+            ..Default::default()
+        });
+        assert_eq!(short.err.as_deref(), Some("TX_ERR_PARSE"));
+
+        let out_of_range = op_sighash_all(&Request {
+            tx_hex,
+            chain_id: "00".repeat(32),
+            input_values: vec![1; 16],
+            input_indices: Some(vec![16]),
+            ..Default::default()
+        });
+        assert_eq!(out_of_range.err.as_deref(), Some("TX_ERR_PARSE"));
+    }
+
+    #[test]
+    fn output_descriptor_ops_match_pre_refactor_output() {
+        // Captured from the CLI before it switched to the library helpers.
+        let req = Request {
+            covenant_type: COV_TYPE_P2PK,
+            covenant_data_hex: "11".repeat(33),
+            ..Default::default()
+        };
+        let bytes = output_descriptor_response(&req, false);
+        assert!(bytes.ok);
+        assert_eq!(bytes.digest, Some(format!("000021{}", "11".repeat(33))));
+        let hash = output_descriptor_response(&req, true);
+        assert_eq!(
+            hash.digest.as_deref(),
+            Some("efe8afc6a04a30a7366c3088b5509fabaaf36358a07f2fac3cff15ed94ceab8e")
+        );
+
+        // CompactSize 0xfd path.
+        let req = Request {
+            covenant_type: 0x0102,
+            covenant_data_hex: "55".repeat(300),
+            ..Default::default()
+        };
+        assert_eq!(
+            output_descriptor_response(&req, true).digest.as_deref(),
+            Some("a4754d08e8312d3e2799c40d8bd1d6cd90bba1401ba50f7e92d2871382b76bc7")
+        );
+
+        let bad = output_descriptor_response(
+            &Request {
+                covenant_data_hex: "zz".to_string(),
+                ..Default::default()
+            },
+            true,
+        );
+        assert!(!bad.ok);
+        assert_eq!(bad.err.as_deref(), Some("bad covenant_data_hex"));
+    }
+
+    const TRACE_PREFIX: [&str; 4] = [
+        "input_count",
+        "tx_nonce",
+        "covenants_genesis",
+        "sighash_prehash",
+    ];
+    const TRACE_INPUT_RESOLUTION: [&str; 9] = [
+        "script_sig",
+        "sequence",
+        "coinbase_prevout",
+        "duplicate_input",
+        "utxo_lookup",
+        "spendable_covenant",
+        "coinbase_maturity",
+        "spend_covenant",
+        "witness_slots",
+    ];
+
+    fn p2pk_trace_request(
+        pubkey: &[u8],
+        sign: impl FnOnce(&mut Tx, &OutpointMap<UtxoEntry>),
+    ) -> Request {
+        let chain_id = [0x5a; 32];
+        let covenant_data = rubin_consensus::p2pk_covenant_data_for_pubkey(pubkey);
+        let prev = Outpoint {
+            txid: [0x11; 32],
+            vout: 0,
+        };
+        let mut utxo_set = OutpointMap::default();
+        utxo_set.insert(
+            prev.clone(),
+            UtxoEntry {
+                value: 1_000,
+                covenant_type: COV_TYPE_P2PK,
+                covenant_data: covenant_data.clone(),
+                creation_height: 1,
+                created_by_coinbase: false,
+            },
+        );
+        let mut tx = Tx {
+            version: 1,
+            tx_kind: 0x00,
+            tx_nonce: 1,
+            inputs: vec![TxInput {
+                prev_txid: prev.txid,
+                prev_vout: prev.vout,
+                script_sig: Vec::new(),
+                sequence: 0,
+            }],
+            outputs: vec![TxOutput {
+                value: 900,
+                covenant_type: COV_TYPE_P2PK,
+                covenant_data: covenant_data.clone(),
+            }],
+            locktime: 0,
+            da_commit_core: None,
+            da_chunk_core: None,
+            witness: Vec::new(),
+            da_payload: Vec::new(),
+        };
+        sign(&mut tx, &utxo_set);
+        Request {
+            tx_hex: hex::encode(rubin_consensus::marshal_tx(&tx).expect("marshal")),
+            utxos: utxos_from_value(&serde_json::json!([{
+                "txid": hex::encode(prev.txid),
+                "vout": prev.vout,
+                "value": 1_000,
+                "covenant_type": COV_TYPE_P2PK,
+                "covenant_data": hex::encode(&covenant_data),
+                "creation_height": 1,
+                "created_by_coinbase": false
+            }])),
+            height: 10,
+            block_timestamp: 1_777_000_000,
+            chain_id: hex::encode(chain_id),
+            ..Default::default()
+        }
+    }
+
+    fn fake_p2pk_witness(tx: &mut Tx, pubkey: &[u8]) {
+        let mut signature = vec![0u8; rubin_consensus::constants::ML_DSA_87_SIG_BYTES as usize];
+        signature.push(rubin_consensus::constants::SIGHASH_ALL);
+        tx.witness = vec![WitnessItem {
+            suite_id: rubin_consensus::constants::SUITE_ID_ML_DSA_87,
+            pubkey: pubkey.to_vec(),
+            signature,
+        }];
+    }
+
+    #[test]
+    fn tx_validation_trace_valid_p2pk_spend() {
+        let Ok(keypair) = rubin_consensus::Mldsa87Keypair::generate() else {
+            return; // ML-DSA backend unavailable
+        };
+        let req = p2pk_trace_request(&keypair.pubkey_bytes(), |tx, utxos| {
+            rubin_consensus::sign_transaction(tx, utxos, [0x5a; 32], &keypair).expect("sign");
+        });
+        let resp = op_tx_validation_trace(&req);
+        assert!(resp.ok, "err={:?}", resp.err);
+        assert_eq!(resp.fee, Some(100));
+        let mut expected: Vec<&str> = TRACE_PREFIX.to_vec();
+        expected.extend(TRACE_INPUT_RESOLUTION);
+        expected.extend([
+            "witness_count",
+            "p2pk_spend",
+            "outputs",
+            "value_conservation",
+        ]);
+        assert_eq!(resp.evaluated.expect("evaluated"), expected);
+        assert_eq!(resp.first_err, None);
+    }
+
+    #[test]
+    fn tx_validation_trace_stops_at_missing_utxo() {
+        let pubkey = vec![0x42; rubin_consensus::constants::ML_DSA_87_PUBKEY_BYTES as usize];
+        let mut req = p2pk_trace_request(&pubkey, |tx, _| fake_p2pk_witness(tx, &pubkey));
+        req.utxos.clear();
+        let resp = op_tx_validation_trace(&req);
+        assert!(!resp.ok);
+        assert_eq!(resp.err.as_deref(), Some("TX_ERR_MISSING_UTXO"));
+        assert_eq!(resp.first_err, resp.err);
+        let mut expected: Vec<&str> = TRACE_PREFIX.to_vec();
+        expected.extend(&TRACE_INPUT_RESOLUTION[..5]);
+        assert_eq!(resp.evaluated.expect("evaluated"), expected);
+    }
+
+    #[test]
+    fn tx_validation_trace_stops_at_bad_signature() {
+        let pubkey = vec![0x42; rubin_consensus::constants::ML_DSA_87_PUBKEY_BYTES as usize];
+        let req = p2pk_trace_request(&pubkey, |tx, _| fake_p2pk_witness(tx, &pubkey));
+        let resp = op_tx_validation_trace(&req);
+        assert!(!resp.ok);
+        assert_eq!(resp.first_err, resp.err);
+        if rubin_consensus::Mldsa87Keypair::generate().is_ok() {
+            assert_eq!(resp.err.as_deref(), Some("TX_ERR_SIG_INVALID"));
+        }
+        let mut expected: Vec<&str> = TRACE_PREFIX.to_vec();
+        expected.extend(TRACE_INPUT_RESOLUTION);
+        expected.extend(["witness_count", "p2pk_spend"]);
+        assert_eq!(resp.evaluated.expect("evaluated"), expected);
+    }
 }
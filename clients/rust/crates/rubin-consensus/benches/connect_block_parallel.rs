@@ -1,16 +1,14 @@
 #[path = "bench_support.rs"]
 mod bench_support;
 
-use std::collections::HashMap;
-
 use criterion::{
     black_box, criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion, Throughput,
 };
 
 use rubin_consensus::constants::COV_TYPE_P2PK;
 use rubin_consensus::{
-    connect_block_parallel_sig_verify, InMemoryChainState, Mldsa87Keypair, Outpoint, Tx, TxInput,
-    TxOutput, UtxoEntry,
+    connect_block_parallel_sig_verify, InMemoryChainState, Mldsa87Keypair, Outpoint, OutpointMap,
+    Tx, TxInput, TxOutput, UtxoEntry,
 };
 
 use bench_support::{
@@ -34,7 +32,7 @@ fn build_parallel_fixture(
     };
     let start_value = 100u64 * tx_count as u64;
     let mut state = InMemoryChainState {
-        utxos: HashMap::from([(
+        utxos: OutpointMap::from_iter([(
             prev_out,
             UtxoEntry {
                 value: start_value,
@@ -0,0 +1,79 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use rubin_consensus::constants::{SIGHASH_ALL, SIGHASH_ANYONECANPAY, SIGHASH_SINGLE};
+use rubin_consensus::{
+    merkle_root_txids, sighash_v1_digest_with_cache, SighashV1PrehashCache, Tx, TxInput, TxOutput,
+};
+
+const MERKLE_TXIDS: usize = 4_000;
+const SIGHASH_INPUTS: u32 = 1_024;
+
+fn merkle_root_4000_txids(c: &mut Criterion) {
+    let txids: Vec<[u8; 32]> = (0..MERKLE_TXIDS)
+        .map(|i| {
+            let mut id = [0u8; 32];
+            id[..8].copy_from_slice(&(i as u64).to_le_bytes());
+            id
+        })
+        .collect();
+    c.bench_function("merkle_root_txids_4000", |b| {
+        b.iter(|| black_box(merkle_root_txids(black_box(&txids)).expect("root")));
+    });
+}
+
+fn sighash_tx() -> Tx {
+    Tx {
+        version: 1,
+        tx_kind: 0x00,
+        tx_nonce: 1,
+        inputs: (0..SIGHASH_INPUTS)
+            .map(|i| TxInput {
+                prev_txid: [(i % 251) as u8; 32],
+                prev_vout: i,
+                script_sig: Vec::new(),
+                sequence: i,
+            })
+            .collect(),
+        outputs: (0..SIGHASH_INPUTS)
+            .map(|i| TxOutput {
+                value: u64::from(i) + 1,
+                covenant_type: 0x0000,
+                covenant_data: vec![(i % 251) as u8; 33],
+            })
+            .collect(),
+        locktime: 0,
+        da_commit_core: None,
+        da_chunk_core: None,
+        witness: Vec::new(),
+        da_payload: Vec::new(),
+    }
+}
+
+fn sighash_1024_inputs(c: &mut Criterion) {
+    let tx = sighash_tx();
+    let chain_id = [0x5a; 32];
+    let mut group = c.benchmark_group("sighash_v1_1024_inputs");
+    for (name, sighash_type) in [
+        ("all", SIGHASH_ALL),
+        ("single_anyonecanpay", SIGHASH_SINGLE | SIGHASH_ANYONECANPAY),
+    ] {
+        group.bench_function(name, |b| {
+            b.iter(|| {
+                let mut cache = SighashV1PrehashCache::new(&tx).expect("cache");
+                for index in 0..SIGHASH_INPUTS {
+                    black_box(
+                        sighash_v1_digest_with_cache(&mut cache, index, 1, chain_id, sighash_type)
+                            .expect("digest"),
+                    );
+                }
+            });
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(
+    hash_preimage_benches,
+    merkle_root_4000_txids,
+    sighash_1024_inputs
+);
+criterion_main!(hash_preimage_benches);
@@ -0,0 +1,355 @@
+//! Baseline for the consensus hot paths: parsing, weight, sighash, merkle,
+//! per-tx spend validation and a 2,000-tx block.
+//!
+//! No benchmark here touches OpenSSL. Witnesses carry fixed, correctly sized
+//! ML-DSA-87 key and signature bytes, and every signature check the fixtures
+//! make is pre-seeded into a `SigCache`, which stands in as the verifier: each
+//! check is a cache hit, so timings measure the structural work around it.
+
+#[path = "bench_support.rs"]
+mod bench_support;
+
+use criterion::{
+    black_box, criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion, Throughput,
+};
+
+use rubin_consensus::constants::{
+    COV_TYPE_HTLC, COV_TYPE_P2PK, LOCK_MODE_HEIGHT, ML_DSA_87_PUBKEY_BYTES, ML_DSA_87_SIG_BYTES,
+    SIGHASH_ALL, SUITE_ID_ML_DSA_87, SUITE_ID_SENTINEL, TX_WIRE_VERSION,
+};
+use rubin_consensus::{
+    first_tx_error, merkle_root_txids, p2pk_covenant_data_for_pubkey, parse_block_bytes, parse_tx,
+    precompute_tx_contexts, run_tx_validation_workers, sighash_v1_digest_with_cache,
+    sighash_v1_digest_with_type, tx_weight_and_stats_public, validate_tx_local, Outpoint,
+    OutpointMap, ParsedBlock, SigCache, SighashV1PrehashCache, Tx, TxInput, TxOutput, UtxoEntry,
+    WitnessItem, WorkerCancellationToken,
+};
+
+use bench_support::{
+    block_merkle_root, build_block_bytes, coinbase_with_witness_commitment_and_p2pk_value,
+    marshal_tx_expect, sha3_256, subsidy_with_fees, ZERO_CHAIN_ID,
+};
+
+/// As many ML-DSA-87 inputs as fit under `MAX_WITNESS_BYTES_PER_TX`.
+const LARGE_TX_INPUTS: usize = 13;
+const BLOCK_SPENDS: usize = 2_000;
+const BLOCK_HEIGHT: u64 = 1;
+const SPEND_VALUE: u64 = 1_000;
+const SPEND_FEE: u64 = 10;
+const HTLC_PREIMAGE: &[u8] = b"rubin-bench-htlc-preimage";
+
+/// A fixed signer: key bytes derived from `seed`, and a "signature" that is
+/// only ever checked through the seeded `SigCache`.
+struct MockSigner {
+    pubkey: Vec<u8>,
+    signature: Vec<u8>,
+}
+
+impl MockSigner {
+    fn new(seed: u8) -> Self {
+        Self {
+            pubkey: vec![seed; ML_DSA_87_PUBKEY_BYTES as usize],
+            signature: vec![seed ^ 0xff; ML_DSA_87_SIG_BYTES as usize],
+        }
+    }
+
+    fn key_id(&self) -> [u8; 32] {
+        sha3_256(&self.pubkey)
+    }
+
+    fn witness(&self) -> WitnessItem {
+        let mut signature = self.signature.clone();
+        signature.push(SIGHASH_ALL);
+        WitnessItem {
+            suite_id: SUITE_ID_ML_DSA_87,
+            pubkey: self.pubkey.clone(),
+            signature,
+        }
+    }
+
+    /// Records the check input `input_index` of `tx` makes as already verified.
+    fn seed(&self, cache: &SigCache, tx: &Tx, input_index: u32, input_value: u64) {
+        let digest =
+            sighash_v1_digest_with_type(tx, input_index, input_value, ZERO_CHAIN_ID, SIGHASH_ALL)
+                .expect("sighash");
+        cache.insert(SUITE_ID_ML_DSA_87, &self.pubkey, &self.signature, digest);
+    }
+}
+
+fn prevout(index: usize) -> Outpoint {
+    let mut txid = [0x5a; 32];
+    txid[..8].copy_from_slice(&(index as u64).to_le_bytes());
+    Outpoint { txid, vout: 0 }
+}
+
+fn utxo(covenant_type: u16, covenant_data: Vec<u8>) -> UtxoEntry {
+    UtxoEntry {
+        value: SPEND_VALUE,
+        covenant_type,
+        covenant_data,
+        creation_height: 0,
+        created_by_coinbase: false,
+    }
+}
+
+fn htlc_covenant_data(claim: &MockSigner, refund: &MockSigner) -> Vec<u8> {
+    let mut cov = Vec::with_capacity(105);
+    cov.extend_from_slice(&sha3_256(HTLC_PREIMAGE));
+    cov.push(LOCK_MODE_HEIGHT);
+    cov.extend_from_slice(&100u64.to_le_bytes());
+    cov.extend_from_slice(&claim.key_id());
+    cov.extend_from_slice(&refund.key_id());
+    cov
+}
+
+fn htlc_claim_selector(claim: &MockSigner) -> WitnessItem {
+    let mut payload = vec![0x00];
+    payload.extend_from_slice(&(HTLC_PREIMAGE.len() as u16).to_le_bytes());
+    payload.extend_from_slice(HTLC_PREIMAGE);
+    WitnessItem {
+        suite_id: SUITE_ID_SENTINEL,
+        pubkey: claim.key_id().to_vec(),
+        signature: payload,
+    }
+}
+
+/// Spends `prevouts` to a single P2PK output; witnesses are left empty.
+fn spend_tx(nonce: u64, prevouts: &[Outpoint], payout: &MockSigner) -> Tx {
+    Tx {
+        version: TX_WIRE_VERSION,
+        tx_kind: 0x00,
+        tx_nonce: nonce,
+        inputs: prevouts
+            .iter()
+            .map(|outpoint| TxInput {
+                prev_txid: outpoint.txid,
+                prev_vout: outpoint.vout,
+                script_sig: Vec::new(),
+                sequence: 0,
+            })
+            .collect(),
+        outputs: vec![TxOutput {
+            value: SPEND_VALUE * prevouts.len() as u64 - SPEND_FEE,
+            covenant_type: COV_TYPE_P2PK,
+            covenant_data: p2pk_covenant_data_for_pubkey(&payout.pubkey),
+        }],
+        locktime: 0,
+        da_commit_core: None,
+        da_chunk_core: None,
+        witness: Vec::new(),
+        da_payload: Vec::new(),
+    }
+}
+
+/// A P2PK spend of `inputs` prevouts, signed by `signer` for every input.
+fn p2pk_spend(inputs: usize, signer: &MockSigner) -> Tx {
+    let prevouts: Vec<Outpoint> = (0..inputs).map(prevout).collect();
+    let mut tx = spend_tx(1, &prevouts, signer);
+    tx.witness = (0..inputs).map(|_| signer.witness()).collect();
+    tx
+}
+
+/// A coinbase plus `spends`, with the UTXO snapshot they resolve against and
+/// a `SigCache` holding every signature check they make.
+struct BlockFixture {
+    block_bytes: Vec<u8>,
+    utxos: OutpointMap<UtxoEntry>,
+    sig_cache: SigCache,
+}
+
+fn block_fixture(spends: Vec<(Tx, Vec<(Outpoint, UtxoEntry)>)>, cache: SigCache) -> BlockFixture {
+    let fees = SPEND_FEE * spends.len() as u64;
+    let mut utxos = OutpointMap::default();
+    let mut non_coinbase = Vec::with_capacity(spends.len());
+    for (tx, prevouts) in spends {
+        utxos.extend(prevouts);
+        non_coinbase.push(marshal_tx_expect(&tx));
+    }
+    let coinbase = coinbase_with_witness_commitment_and_p2pk_value(
+        BLOCK_HEIGHT as u32,
+        subsidy_with_fees(BLOCK_HEIGHT, fees),
+        &non_coinbase,
+        p2pk_covenant_data_for_pubkey(&MockSigner::new(0x01).pubkey),
+    );
+    let mut txs = Vec::with_capacity(non_coinbase.len() + 1);
+    txs.push(coinbase);
+    txs.extend(non_coinbase);
+    let merkle_root = block_merkle_root(&txs);
+    BlockFixture {
+        block_bytes: build_block_bytes([0x77; 32], merkle_root, [0xff; 32], 1, &txs),
+        utxos,
+        sig_cache: cache,
+    }
+}
+
+fn p2pk_block(spends: usize) -> BlockFixture {
+    let signer = MockSigner::new(0x11);
+    let cov_data = p2pk_covenant_data_for_pubkey(&signer.pubkey);
+    let cache = SigCache::new(spends);
+    let spends = (0..spends)
+        .map(|index| {
+            let outpoint = prevout(index);
+            let mut tx = spend_tx(index as u64 + 1, std::slice::from_ref(&outpoint), &signer);
+            signer.seed(&cache, &tx, 0, SPEND_VALUE);
+            tx.witness = vec![signer.witness()];
+            (tx, vec![(outpoint, utxo(COV_TYPE_P2PK, cov_data.clone()))])
+        })
+        .collect();
+    block_fixture(spends, cache)
+}
+
+fn htlc_block() -> BlockFixture {
+    let (claim, refund) = (MockSigner::new(0x21), MockSigner::new(0x22));
+    let cache = SigCache::new(1);
+    let outpoint = prevout(0);
+    let mut tx = spend_tx(1, std::slice::from_ref(&outpoint), &claim);
+    claim.seed(&cache, &tx, 0, SPEND_VALUE);
+    tx.witness = vec![htlc_claim_selector(&claim), claim.witness()];
+    let entry = utxo(COV_TYPE_HTLC, htlc_covenant_data(&claim, &refund));
+    block_fixture(vec![(tx, vec![(outpoint, entry)])], cache)
+}
+
+fn parse_tx_bench(c: &mut Criterion) {
+    let signer = MockSigner::new(0x31);
+    let mut group = c.benchmark_group("parse_tx_bytes");
+    for (name, inputs) in [("small", 1), ("large", LARGE_TX_INPUTS)] {
+        let bytes = marshal_tx_expect(&p2pk_spend(inputs, &signer));
+        group.throughput(Throughput::Bytes(bytes.len() as u64));
+        group.bench_function(name, |b| {
+            b.iter(|| black_box(parse_tx(black_box(&bytes)).expect("parse tx")));
+        });
+    }
+    group.finish();
+}
+
+fn tx_weight_bench(c: &mut Criterion) {
+    let tx = p2pk_spend(LARGE_TX_INPUTS, &MockSigner::new(0x32));
+    c.bench_function("tx_weight", |b| {
+        b.iter(|| black_box(tx_weight_and_stats_public(black_box(&tx)).expect("weight")));
+    });
+}
+
+/// Digests every input of a large tx: rebuilding the prehash per input
+/// (`sighash_v1_digest`) against one shared `SighashV1PrehashCache`.
+fn sighash_bench(c: &mut Criterion) {
+    let tx = p2pk_spend(LARGE_TX_INPUTS, &MockSigner::new(0x33));
+    let mut group = c.benchmark_group("sighash_v1_digest");
+    group.throughput(Throughput::Elements(LARGE_TX_INPUTS as u64));
+    group.bench_function("uncached", |b| {
+        b.iter(|| {
+            for index in 0..LARGE_TX_INPUTS as u32 {
+                black_box(
+                    sighash_v1_digest_with_type(
+                        &tx,
+                        index,
+                        SPEND_VALUE,
+                        ZERO_CHAIN_ID,
+                        SIGHASH_ALL,
+                    )
+                    .expect("digest"),
+                );
+            }
+        });
+    });
+    group.bench_function("cached", |b| {
+        b.iter(|| {
+            let mut cache = SighashV1PrehashCache::new(&tx).expect("prehash cache");
+            for index in 0..LARGE_TX_INPUTS as u32 {
+                black_box(
+                    sighash_v1_digest_with_cache(
+                        &mut cache,
+                        index,
+                        SPEND_VALUE,
+                        ZERO_CHAIN_ID,
+                        SIGHASH_ALL,
+                    )
+                    .expect("digest"),
+                );
+            }
+        });
+    });
+    group.finish();
+}
+
+fn merkle_bench(c: &mut Criterion) {
+    let mut group = c.benchmark_group("merkle_root_txids");
+    for leaves in [100usize, 1_000, 4_000] {
+        let txids: Vec<[u8; 32]> = (0..leaves).map(|i| prevout(i).txid).collect();
+        group.throughput(Throughput::Elements(leaves as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(leaves), &txids, |b, txids| {
+            b.iter(|| black_box(merkle_root_txids(black_box(txids)).expect("root")));
+        });
+    }
+    group.finish();
+}
+
+/// One spend through the worker path a block applies it with: prevout
+/// resolution (`precompute_tx_contexts`) is setup, `validate_tx_local` is timed.
+fn apply_tx_bench(c: &mut Criterion) {
+    let mut group = c.benchmark_group("apply_tx");
+    for (name, fixture) in [("p2pk", p2pk_block(1)), ("htlc", htlc_block())] {
+        let parsed: ParsedBlock = parse_block_bytes(&fixture.block_bytes).expect("parse block");
+        let ptcs =
+            precompute_tx_contexts(&parsed, &fixture.utxos, BLOCK_HEIGHT).expect("precompute");
+        group.bench_function(name, |b| {
+            b.iter(|| {
+                let result = validate_tx_local(
+                    &ptcs[0],
+                    &parsed,
+                    ZERO_CHAIN_ID,
+                    BLOCK_HEIGHT,
+                    0,
+                    Some(&fixture.sig_cache),
+                );
+                assert!(result.valid, "{name} spend rejected: {:?}", result.err);
+                black_box(result);
+            });
+        });
+    }
+    group.finish();
+}
+
+/// Parse, prevout resolution and single-worker spend validation of a block
+/// carrying 2,000 independent P2PK spends.
+fn apply_block_bench(c: &mut Criterion) {
+    let fixture = p2pk_block(BLOCK_SPENDS);
+    let mut group = c.benchmark_group("apply_block");
+    group.sample_size(10);
+    group.throughput(Throughput::Elements(BLOCK_SPENDS as u64));
+    group.bench_function("p2pk_2000_txs", |b| {
+        b.iter_batched(
+            WorkerCancellationToken::new,
+            |token| {
+                let parsed = parse_block_bytes(&fixture.block_bytes).expect("parse block");
+                let ptcs = precompute_tx_contexts(&parsed, &fixture.utxos, BLOCK_HEIGHT)
+                    .expect("precompute");
+                let results = run_tx_validation_workers(
+                    &token,
+                    1,
+                    ptcs,
+                    &parsed,
+                    ZERO_CHAIN_ID,
+                    BLOCK_HEIGHT,
+                    0,
+                    Some(fixture.sig_cache.clone()),
+                )
+                .expect("validation workers");
+                assert!(first_tx_error(&results).is_none());
+                black_box(results);
+            },
+            BatchSize::SmallInput,
+        );
+    });
+    group.finish();
+}
+
+criterion_group!(
+    hot_path_benches,
+    parse_tx_bench,
+    tx_weight_bench,
+    sighash_bench,
+    merkle_bench,
+    apply_tx_bench,
+    apply_block_bench
+);
+criterion_main!(hot_path_benches);
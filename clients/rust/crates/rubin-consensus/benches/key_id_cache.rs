@@ -0,0 +1,49 @@
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, Criterion};
+use rubin_consensus::constants::ML_DSA_87_PUBKEY_BYTES;
+use rubin_consensus::KeyIdCache;
+use sha3::{Digest, Sha3_256};
+
+const BLOCK_INPUTS: usize = 500;
+const DISTINCT_KEYS: u8 = 3;
+
+fn block_input_pubkeys() -> Vec<Vec<u8>> {
+    let keys: Vec<Vec<u8>> = (0..DISTINCT_KEYS)
+        .map(|i| vec![0xa0 | i; ML_DSA_87_PUBKEY_BYTES as usize])
+        .collect();
+    (0..BLOCK_INPUTS)
+        .map(|i| keys[i % keys.len()].clone())
+        .collect()
+}
+
+fn key_id_uncached_block(c: &mut Criterion) {
+    let pubkeys = block_input_pubkeys();
+    c.bench_function("key_id_uncached_500_inputs_3_keys", |b| {
+        b.iter(|| {
+            for pubkey in &pubkeys {
+                black_box(Sha3_256::digest(pubkey));
+            }
+        });
+    });
+}
+
+fn key_id_cached_block(c: &mut Criterion) {
+    let pubkeys = block_input_pubkeys();
+    c.bench_function("key_id_cached_500_inputs_3_keys", |b| {
+        b.iter_batched(
+            || KeyIdCache::new(1024),
+            |mut cache| {
+                for pubkey in &pubkeys {
+                    black_box(cache.key_id(pubkey));
+                }
+            },
+            BatchSize::SmallInput,
+        );
+    });
+}
+
+criterion_group!(
+    key_id_cache_benches,
+    key_id_uncached_block,
+    key_id_cached_block
+);
+criterion_main!(key_id_cache_benches);
@@ -0,0 +1,57 @@
+use std::collections::HashMap;
+use std::hash::BuildHasher;
+
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, Criterion};
+use rubin_consensus::{Outpoint, OutpointBuildHasher};
+use sha3::{Digest, Sha3_256};
+
+const MAP_ENTRIES: u32 = 1_000_000;
+
+fn outpoints() -> Vec<Outpoint> {
+    (0..MAP_ENTRIES)
+        .map(|i| Outpoint {
+            txid: Sha3_256::digest((i / 4).to_le_bytes()).into(),
+            vout: i % 4,
+        })
+        .collect()
+}
+
+fn build<S: BuildHasher + Default>(keys: &[Outpoint]) -> HashMap<Outpoint, u32, S> {
+    let mut map = HashMap::with_capacity_and_hasher(keys.len(), S::default());
+    for (i, key) in keys.iter().enumerate() {
+        map.insert(key.clone(), i as u32);
+    }
+    map
+}
+
+fn bench_hasher<S: BuildHasher + Default>(c: &mut Criterion, name: &str, keys: &[Outpoint]) {
+    let mut group = c.benchmark_group(format!("outpoint_map_1m_{name}"));
+    group.sample_size(10);
+    group.bench_function("insert", |b| {
+        b.iter_batched(
+            || (),
+            |()| black_box(build::<S>(keys)),
+            BatchSize::PerIteration,
+        );
+    });
+    let map = build::<S>(keys);
+    group.bench_function("lookup", |b| {
+        b.iter(|| {
+            let mut sum = 0u64;
+            for key in keys {
+                sum += u64::from(*map.get(key).expect("present"));
+            }
+            black_box(sum)
+        });
+    });
+    group.finish();
+}
+
+fn outpoint_map_1m(c: &mut Criterion) {
+    let keys = outpoints();
+    bench_hasher::<std::collections::hash_map::RandomState>(c, "siphash", &keys);
+    bench_hasher::<OutpointBuildHasher>(c, "outpoint_hasher", &keys);
+}
+
+criterion_group!(outpoint_map_benches, outpoint_map_1m);
+criterion_main!(outpoint_map_benches);
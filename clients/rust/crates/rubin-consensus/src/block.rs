@@ -13,6 +13,20 @@ pub struct BlockHeader {
 
 pub const BLOCK_HEADER_BYTES: usize = 116;
 
+impl BlockHeader {
+    /// Canonical 116-byte wire encoding; inverse of [`parse_block_header_bytes`].
+    pub fn to_bytes(&self) -> [u8; BLOCK_HEADER_BYTES] {
+        let mut out = [0u8; BLOCK_HEADER_BYTES];
+        out[0..4].copy_from_slice(&self.version.to_le_bytes());
+        out[4..36].copy_from_slice(&self.prev_block_hash);
+        out[36..68].copy_from_slice(&self.merkle_root);
+        out[68..76].copy_from_slice(&self.timestamp.to_le_bytes());
+        out[76..108].copy_from_slice(&self.target);
+        out[108..116].copy_from_slice(&self.nonce.to_le_bytes());
+        out
+    }
+}
+
 pub fn parse_block_header_bytes(b: &[u8]) -> Result<BlockHeader, TxError> {
     if b.len() != BLOCK_HEADER_BYTES {
         return Err(TxError::new(
@@ -77,6 +91,7 @@ mod tests {
         assert_eq!(parsed.timestamp, 11);
         assert_eq!(parsed.target, [0xff; 32]);
         assert_eq!(parsed.nonce, 13);
+        assert_eq!(parsed.to_bytes(), bytes);
     }
 
     #[test]
@@ -1,14 +1,17 @@
 use crate::block::{BlockHeader, BLOCK_HEADER_BYTES};
+use crate::compactsize::encode_compact_size;
 use crate::constants::{MAX_ANCHOR_BYTES_PER_BLOCK, MAX_BLOCK_WEIGHT, MAX_DA_BYTES_PER_BLOCK};
 use crate::error::{ErrorCode, TxError};
 use crate::suite_registry::RotationProvider;
 use crate::tx::Tx;
+use crate::tx_helpers::marshal_tx;
 
 mod coinbase;
 mod da_set;
 mod header;
 mod orchestration;
 mod parser;
+mod stream;
 mod txs;
 mod weight;
 
@@ -18,9 +21,13 @@ use self::parser::parse_block_bytes_impl;
 use self::txs::BlockTxStats;
 use self::weight::tx_weight_and_stats;
 
+pub use self::coinbase::validate_coinbase_tx;
 pub(crate) use self::coinbase::{validate_coinbase_apply_outputs, validate_coinbase_value_bound};
-pub(crate) use self::header::median_time_past;
-pub use self::weight::{tx_weight_and_stats_at_height, tx_weight_and_stats_public};
+pub use self::header::{check_block_timestamp, check_timestamp_bounds, median_time_past};
+pub use self::stream::{BlockStreamParser, ParsedItem};
+pub use self::weight::{
+    tx_anchor_bytes, tx_weight_and_stats_at_height, tx_weight_and_stats_public,
+};
 
 #[derive(Clone, Debug)]
 pub struct ParsedBlock {
@@ -32,6 +39,18 @@ pub struct ParsedBlock {
     pub wtxids: Vec<[u8; 32]>,
 }
 
+impl ParsedBlock {
+    /// Canonical wire encoding; inverse of [`parse_block_bytes`].
+    pub fn to_bytes(&self) -> Result<Vec<u8>, TxError> {
+        let mut out = Vec::from(self.header.to_bytes());
+        encode_compact_size(self.txs.len() as u64, &mut out);
+        for tx in &self.txs {
+            out.extend_from_slice(&marshal_tx(tx)?);
+        }
+        Ok(out)
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct BlockBasicSummary {
     pub tx_count: u64,
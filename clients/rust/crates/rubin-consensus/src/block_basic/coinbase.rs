@@ -1,5 +1,6 @@
 use super::*;
 use crate::constants::{COV_TYPE_ANCHOR, COV_TYPE_VAULT};
+use crate::covenant_genesis::validate_tx_covenants_genesis;
 use crate::merkle::{witness_commitment_hash, witness_merkle_root_wtxids};
 use crate::subsidy::block_subsidy;
 use crate::{TxInput, TxOutput};
@@ -35,6 +36,91 @@ fn has_coinbase_input_shape(input: &TxInput) -> bool {
     .all(core::convert::identity)
 }
 
+/// Names the first coinbase shape rule `tx` breaks, in the order
+/// [`is_coinbase_tx`] checks them.
+fn coinbase_shape_violation(tx: &Tx) -> Option<&'static str> {
+    let tx_rules = [
+        (tx.tx_kind == 0x00, "coinbase tx_kind must be 0x00"),
+        (tx.tx_nonce == 0, "coinbase tx_nonce must be 0"),
+        (tx.inputs.len() == 1, "coinbase must have exactly one input"),
+        (
+            tx.witness.is_empty(),
+            "coinbase must not carry witness items",
+        ),
+        (
+            tx.da_payload.is_empty(),
+            "coinbase must not carry a DA payload",
+        ),
+    ];
+    if let Some((_, rule)) = tx_rules.into_iter().find(|(ok, _)| !ok) {
+        return Some(rule);
+    }
+    let input = &tx.inputs[0];
+    [
+        (
+            input.prev_txid == [0u8; 32],
+            "coinbase prev_txid must be zero",
+        ),
+        (
+            input.prev_vout == u32::MAX,
+            "coinbase prev_vout must be 0xffffffff",
+        ),
+        (
+            input.script_sig.is_empty(),
+            "coinbase script_sig must be empty",
+        ),
+        (
+            input.sequence == u32::MAX,
+            "coinbase sequence must be 0xffffffff",
+        ),
+    ]
+    .into_iter()
+    .find(|(ok, _)| !ok)
+    .map(|(_, rule)| rule)
+}
+
+/// Validates a standalone coinbase transaction against the block context it
+/// would be mined in: canonical input shape, locktime == height, output
+/// covenants, no CORE_VAULT outputs and the subsidy+fees value bound. The
+/// witness commitment needs the whole block and is not checked. Each failure
+/// names the rule it broke.
+pub fn validate_coinbase_tx(
+    tx: &Tx,
+    block_height: u64,
+    already_generated: u128,
+    sum_fees: u64,
+) -> Result<(), TxError> {
+    if let Some(rule) = coinbase_shape_violation(tx) {
+        return Err(TxError::new(ErrorCode::BlockErrCoinbaseInvalid, rule));
+    }
+    if tx.outputs.is_empty() {
+        return Err(TxError::new(
+            ErrorCode::BlockErrCoinbaseInvalid,
+            "coinbase must have at least one output",
+        ));
+    }
+    if u64::from(tx.locktime) != block_height {
+        return Err(TxError::new(
+            ErrorCode::BlockErrCoinbaseInvalid,
+            "coinbase locktime must equal block height",
+        ));
+    }
+    validate_tx_covenants_genesis(tx, block_height, None)?;
+    validate_coinbase_apply_outputs(tx)?;
+    if block_height == 0 {
+        return Ok(());
+    }
+    let sum_coinbase = sum_coinbase_outputs(tx)?;
+    let limit = coinbase_value_limit(block_height, already_generated, sum_fees)?;
+    if sum_coinbase > limit {
+        return Err(TxError::new(
+            ErrorCode::BlockErrSubsidyExceeded,
+            "coinbase outputs exceed subsidy+fees bound",
+        ));
+    }
+    Ok(())
+}
+
 pub(super) fn validate_coinbase_structure(
     pb: &ParsedBlock,
     block_height: u64,
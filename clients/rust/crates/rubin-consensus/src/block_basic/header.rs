@@ -46,14 +46,22 @@ pub(super) fn validate_timestamp_rules(
     let Some(median) = median_time_past(block_height, prev_timestamps)? else {
         return Ok(());
     };
-    if header_timestamp <= median {
+    check_block_timestamp(header_timestamp, median, None)
+}
+
+/// Consensus timestamp window: `mtp < timestamp <= mtp + max_future_drift`.
+pub fn check_timestamp_bounds(
+    timestamp: u64,
+    mtp: u64,
+    max_future_drift: u64,
+) -> Result<(), TxError> {
+    if timestamp <= mtp {
         return Err(TxError::new(
             ErrorCode::BlockErrTimestampOld,
             "timestamp <= MTP median",
         ));
     }
-    let upper_bound = median.saturating_add(crate::constants::MAX_FUTURE_DRIFT);
-    if header_timestamp > upper_bound {
+    if timestamp > mtp.saturating_add(max_future_drift) {
         return Err(TxError::new(
             ErrorCode::BlockErrTimestampFuture,
             "timestamp exceeds future drift",
@@ -62,7 +70,34 @@ pub(super) fn validate_timestamp_rules(
     Ok(())
 }
 
-pub(crate) fn median_time_past(
+/// Header timestamp check against `mtp` (see [`median_time_past`]) with the
+/// consensus `MAX_FUTURE_DRIFT`.
+///
+/// `local_time = None` is the consensus rule alone, which is what block
+/// connection uses: the upper bound is anchored to MTP, so every node reaches
+/// the same verdict. `Some(now)` additionally rejects timestamps beyond
+/// `now + MAX_FUTURE_DRIFT`; that bound depends on the local clock and is
+/// node policy only, never a reason to mark a block permanently invalid.
+pub fn check_block_timestamp(
+    header_timestamp: u64,
+    mtp: u64,
+    local_time: Option<u64>,
+) -> Result<(), TxError> {
+    check_timestamp_bounds(header_timestamp, mtp, crate::constants::MAX_FUTURE_DRIFT)?;
+    if let Some(now) = local_time {
+        if header_timestamp > now.saturating_add(crate::constants::MAX_FUTURE_DRIFT) {
+            return Err(TxError::new(
+                ErrorCode::BlockErrTimestampFuture,
+                "timestamp exceeds local clock future drift",
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Median of the last `min(block_height, 11)` timestamps, newest first in
+/// `prev_timestamps`. `None` for genesis or when no context is supplied.
+pub fn median_time_past(
     block_height: u64,
     prev_timestamps: Option<&[u64]>,
 ) -> Result<Option<u64>, TxError> {
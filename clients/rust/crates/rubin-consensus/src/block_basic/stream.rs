@@ -0,0 +1,222 @@
+use crate::block::{parse_block_header_bytes, BlockHeader, BLOCK_HEADER_BYTES};
+use crate::compactsize::read_compact_size_bytes;
+use crate::error::{ErrorCode, TxError};
+use crate::tx::{parse_tx, parse_tx_partial, PartialTx, Tx};
+
+/// One element of a block, in wire order.
+#[derive(Clone, Debug)]
+pub enum ParsedItem {
+    Header {
+        header: BlockHeader,
+        header_bytes: [u8; BLOCK_HEADER_BYTES],
+    },
+    TxCount(u64),
+    Tx {
+        tx: Box<Tx>,
+        txid: [u8; 32],
+        wtxid: [u8; 32],
+    },
+}
+
+#[derive(Clone, Copy, Debug)]
+enum Stage {
+    Header,
+    TxCount,
+    Txs { remaining: u64 },
+    Done,
+    Failed,
+}
+
+/// Incremental block parser: bytes are fed as they arrive and each element is
+/// yielded as soon as it is complete, so only the transaction currently being
+/// received is buffered. Accepts exactly what [`super::parse_block_bytes`]
+/// accepts (that function runs on top of this parser) and fails on the first
+/// invalid element. After an error the parser rejects all input.
+#[derive(Clone, Debug)]
+pub struct BlockStreamParser {
+    buf: Vec<u8>,
+    stage: Stage,
+    fed: usize,
+    max_bytes: usize,
+    /// Bytes the pending transaction needs before another parse attempt can
+    /// progress, so a large transaction is not re-parsed on every chunk.
+    tx_need: usize,
+}
+
+impl BlockStreamParser {
+    /// `max_bytes` bounds the whole block, e.g. `MAX_BLOCK_BYTES` on the P2P path.
+    pub fn new(max_bytes: usize) -> Self {
+        Self {
+            buf: Vec::new(),
+            stage: Stage::Header,
+            fed: 0,
+            max_bytes,
+            tx_need: 0,
+        }
+    }
+
+    /// Appends `chunk` and returns the elements it completed.
+    pub fn feed(&mut self, chunk: &[u8]) -> Result<Vec<ParsedItem>, TxError> {
+        self.fed = self.fed.saturating_add(chunk.len());
+        if self.fed > self.max_bytes {
+            self.stage = Stage::Failed;
+            return Err(TxError::new(
+                ErrorCode::BlockErrParse,
+                "block exceeds byte limit",
+            ));
+        }
+        let mut buf = std::mem::take(&mut self.buf);
+        buf.extend_from_slice(chunk);
+        let (items, used) = self.advance(&buf)?;
+        buf.drain(..used);
+        self.buf = buf;
+        Ok(items)
+    }
+
+    /// True once every announced transaction has been parsed.
+    pub fn is_complete(&self) -> bool {
+        matches!(self.stage, Stage::Done)
+    }
+
+    /// Ends the stream, failing if the block is incomplete.
+    pub fn finish(self) -> Result<(), TxError> {
+        self.check_complete(&self.buf)
+    }
+
+    /// Parses every element completed by `data`, returning them with the
+    /// number of bytes they used; the rest must be passed again with more input.
+    pub(super) fn advance(&mut self, data: &[u8]) -> Result<(Vec<ParsedItem>, usize), TxError> {
+        let mut items = Vec::new();
+        let mut off = 0usize;
+        loop {
+            let rest = &data[off..];
+            match self.advance_one(rest) {
+                Ok(Some((item, used))) => {
+                    items.push(item);
+                    off += used;
+                }
+                Ok(None) => return Ok((items, off)),
+                Err(err) => {
+                    self.stage = Stage::Failed;
+                    return Err(err);
+                }
+            }
+        }
+    }
+
+    /// `Ok(None)` means `rest` is too short for the next element.
+    fn advance_one(&mut self, rest: &[u8]) -> Result<Option<(ParsedItem, usize)>, TxError> {
+        match self.stage {
+            Stage::Header => {
+                if rest.len() < BLOCK_HEADER_BYTES {
+                    return Ok(None);
+                }
+                let mut header_bytes = [0u8; BLOCK_HEADER_BYTES];
+                header_bytes.copy_from_slice(&rest[..BLOCK_HEADER_BYTES]);
+                let header = parse_block_header_bytes(&header_bytes)
+                    .map_err(|_| TxError::new(ErrorCode::BlockErrParse, "invalid block header"))?;
+                self.stage = Stage::TxCount;
+                Ok(Some((
+                    ParsedItem::Header {
+                        header,
+                        header_bytes,
+                    },
+                    BLOCK_HEADER_BYTES,
+                )))
+            }
+            Stage::TxCount => {
+                let width = match rest.first() {
+                    None => return Ok(None),
+                    Some(0xfd) => 3,
+                    Some(0xfe) => 5,
+                    Some(0xff) => 9,
+                    Some(_) => 1,
+                };
+                if rest.len() < width {
+                    return Ok(None);
+                }
+                let (tx_count, used) = read_compact_size_bytes(rest)
+                    .map_err(|_| TxError::new(ErrorCode::BlockErrParse, "invalid tx_count"))?;
+                if tx_count == 0 {
+                    return Err(TxError::new(
+                        ErrorCode::BlockErrCoinbaseInvalid,
+                        "empty block tx list",
+                    ));
+                }
+                self.stage = Stage::Txs {
+                    remaining: tx_count,
+                };
+                Ok(Some((ParsedItem::TxCount(tx_count), used)))
+            }
+            Stage::Txs { remaining } => {
+                if rest.is_empty() || rest.len() < self.tx_need {
+                    return Ok(None);
+                }
+                let (tx, txid, wtxid, consumed) = match parse_tx_partial(rest)? {
+                    PartialTx::Complete {
+                        tx,
+                        txid,
+                        wtxid,
+                        consumed,
+                    } => (tx, txid, wtxid, consumed),
+                    PartialTx::NeedBytes(need) => {
+                        self.tx_need = need;
+                        return Ok(None);
+                    }
+                };
+                self.tx_need = 0;
+                if consumed == 0 {
+                    return Err(TxError::new(
+                        ErrorCode::BlockErrParse,
+                        "zero-length tx parse",
+                    ));
+                }
+                self.stage = match remaining {
+                    1 => Stage::Done,
+                    _ => Stage::Txs {
+                        remaining: remaining - 1,
+                    },
+                };
+                Ok(Some((ParsedItem::Tx { tx, txid, wtxid }, consumed)))
+            }
+            Stage::Done => {
+                if rest.is_empty() {
+                    return Ok(None);
+                }
+                Err(TxError::new(
+                    ErrorCode::BlockErrParse,
+                    "trailing bytes after tx list",
+                ))
+            }
+            Stage::Failed => Err(TxError::new(
+                ErrorCode::BlockErrParse,
+                "block stream already failed",
+            )),
+        }
+    }
+
+    /// Completion check over the bytes `advance` left unused.
+    pub(super) fn check_complete(&self, rest: &[u8]) -> Result<(), TxError> {
+        match self.stage {
+            Stage::Done => Ok(()),
+            Stage::Header => Err(TxError::new(ErrorCode::BlockErrParse, "block too short")),
+            Stage::TxCount if rest.is_empty() => {
+                Err(TxError::new(ErrorCode::BlockErrParse, "block too short"))
+            }
+            Stage::TxCount => Err(TxError::new(ErrorCode::BlockErrParse, "invalid tx_count")),
+            Stage::Txs { .. } if rest.is_empty() => Err(TxError::new(
+                ErrorCode::BlockErrParse,
+                "unexpected EOF in tx list",
+            )),
+            // The pending transaction is truncated; report its own parse error.
+            Stage::Txs { .. } => match parse_tx(rest) {
+                Err(err) => Err(err),
+                Ok(_) => unreachable!("advance leaves no complete tx unparsed"),
+            },
+            Stage::Failed => Err(TxError::new(
+                ErrorCode::BlockErrParse,
+                "block stream already failed",
+            )),
+        }
+    }
+}
@@ -1,13 +1,19 @@
 use crate::constants::{
-    COV_TYPE_ANCHOR, COV_TYPE_DA_COMMIT, ML_DSA_87_PUBKEY_BYTES, ML_DSA_87_SIG_BYTES,
-    SIMPLICITY_BASE_VERIFY_COST, SUITE_ID_ML_DSA_87, SUITE_ID_SENTINEL,
-    SUITE_ID_SIMPLICITY_ENVELOPE, VERIFY_COST_ML_DSA_87, VERIFY_COST_UNKNOWN_SUITE,
+    ANCHOR_WEIGHT_FACTOR, COV_TYPE_ANCHOR, COV_TYPE_DA_COMMIT, SIMPLICITY_BASE_VERIFY_COST,
+    SUITE_ID_SENTINEL, SUITE_ID_SIMPLICITY_ENVELOPE, VERIFY_COST_UNKNOWN_SUITE,
     WITNESS_DISCOUNT_DIVISOR,
 };
+use crate::da::tx_da_bytes;
 use crate::error::{ErrorCode, TxError};
-use crate::suite_registry::{RotationProvider, SuiteRegistry};
+use crate::suite_registry::{
+    default_native_spend_suites, default_suite_registry, RotationProvider, SuiteRegistry,
+};
 use crate::tx::{da_core_fields_bytes, Tx, TxInput, TxOutput, WitnessItem};
 
+// Anchor payload is weighed as ordinary base bytes. A discount would need a
+// CANONICAL change and a separate term in `tx_weight`, mirrored in Go.
+const _: () = assert!(ANCHOR_WEIGHT_FACTOR == WITNESS_DISCOUNT_DIVISOR);
+
 /// Shared weight-computation skeleton. `sig_cost_fn` receives each witness item
 /// and returns its verification cost (same pattern as Go `txWeightComponents`).
 fn tx_weight_components<F>(tx: &Tx, sig_cost_fn: F) -> Result<(u64, u64, u64), TxError>
@@ -55,6 +61,17 @@ fn add_output_sizes(mut base_size: u64, outputs: &[TxOutput]) -> Result<(u64, u6
     Ok((base_size, anchor_bytes))
 }
 
+/// Anchor-counted covenant bytes (CORE_ANCHOR and CORE_DA_COMMIT outputs), the
+/// `anchor_bytes` that [`tx_weight_and_stats_public`] reports. Lets policy price
+/// anchors on their own; they weigh `ANCHOR_WEIGHT_FACTOR` per byte.
+pub fn tx_anchor_bytes(tx: &Tx) -> u64 {
+    tx.outputs
+        .iter()
+        .filter(|output| is_anchor_counted_output(output))
+        .map(|output| output.covenant_data.len() as u64)
+        .sum()
+}
+
 fn is_anchor_counted_output(output: &TxOutput) -> bool {
     matches!(output.covenant_type, COV_TYPE_ANCHOR | COV_TYPE_DA_COMMIT)
 }
@@ -86,8 +103,7 @@ fn add_witness_item_size(mut witness_size: u64, witness: &WitnessItem) -> Result
 fn tx_da_size_and_bytes(tx: &Tx) -> Result<(u64, u64), TxError> {
     let da_len = tx.da_payload.len() as u64;
     let da_size = checked_add(compact_size_len(da_len), da_len)?;
-    let da_bytes = if tx.tx_kind != 0x00 { da_len } else { 0 };
-    Ok((da_size, da_bytes))
+    Ok((da_size, tx_da_bytes(tx)))
 }
 
 fn tx_weight(
@@ -104,21 +120,13 @@ fn tx_weight(
     checked_add(weight, sig_cost)
 }
 
-/// Legacy weight with hardcoded per-suite costs.
+/// Legacy weight: per-suite costs from the default registry and the
+/// pre-rotation spend set. Same table as Go `txWeightAndStats`.
 pub(super) fn tx_weight_and_stats(tx: &Tx) -> Result<(u64, u64, u64), TxError> {
-    tx_weight_components(tx, legacy_sig_cost)
-}
-
-fn legacy_sig_cost(witness: &WitnessItem) -> Result<u64, TxError> {
-    Ok(match witness.suite_id {
-        SUITE_ID_SENTINEL => 0,
-        // CANONICAL §9 / mirror of Go `txWeightAndStats`: a 0xF0 Simplicity
-        // envelope witness is priced at its own base cost, not the
-        // unknown-suite floor (numerically equal today; see the constant).
-        SUITE_ID_SIMPLICITY_ENVELOPE => SIMPLICITY_BASE_VERIFY_COST,
-        SUITE_ID_ML_DSA_87 if has_expected_mldsa87_shape(witness) => VERIFY_COST_ML_DSA_87,
-        SUITE_ID_ML_DSA_87 => 0,
-        _ => VERIFY_COST_UNKNOWN_SUITE,
+    let native_spend = default_native_spend_suites();
+    let registry = default_suite_registry();
+    tx_weight_components(tx, |witness| {
+        registry_sig_cost(witness, native_spend, registry)
     })
 }
 
@@ -166,19 +174,14 @@ fn registry_sig_cost(
     let Some(params) = registry.lookup(witness.suite_id) else {
         return Ok(VERIFY_COST_UNKNOWN_SUITE);
     };
-    if witness.pubkey.len() as u64 == params.pubkey_len
-        && witness.signature.len() as u64 == params.sig_len + 1
+    if params
+        .has_canonical_witness_shape(witness.pubkey.len() as u64, witness.signature.len() as u64)
     {
         return Ok(params.verify_cost);
     }
     Ok(0)
 }
 
-fn has_expected_mldsa87_shape(witness: &WitnessItem) -> bool {
-    witness.pubkey.len() as u64 == ML_DSA_87_PUBKEY_BYTES
-        && witness.signature.len() as u64 == ML_DSA_87_SIG_BYTES + 1
-}
-
 fn compact_size_len(n: u64) -> u64 {
     match n {
         0x00..=0xfc => 1,
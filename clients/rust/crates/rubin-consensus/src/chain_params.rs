@@ -0,0 +1,90 @@
+use crate::constants::{COINBASE_MATURITY, TARGET_BLOCK_INTERVAL, WINDOW_SIZE};
+
+/// Chain-instance consensus parameters that a non-production chain may
+/// override (e.g. a fast devnet for exercising reorg and maturity logic).
+/// Production chains always run [`ChainParams::V1`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ChainParams {
+    pub target_block_interval: u64,
+    /// Retarget window in blocks; a retarget happens at every multiple.
+    pub window_size: u64,
+    pub coinbase_maturity: u64,
+}
+
+impl ChainParams {
+    /// The v1.1 constants (CANONICAL §4 / §15).
+    pub const V1: Self = Self {
+        target_block_interval: TARGET_BLOCK_INTERVAL,
+        window_size: WINDOW_SIZE,
+        coinbase_maturity: COINBASE_MATURITY,
+    };
+
+    /// Derived per-block clamp for retarget timestamps, as
+    /// `MAX_TIMESTAMP_STEP_PER_BLOCK` is for the v1 constants.
+    pub fn max_timestamp_step_per_block(&self) -> u64 {
+        self.target_block_interval.saturating_mul(10)
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if self.target_block_interval == 0 {
+            return Err("chain params: target_block_interval must be > 0".to_string());
+        }
+        if self.window_size == 0 {
+            return Err("chain params: window_size must be > 0".to_string());
+        }
+        if self
+            .target_block_interval
+            .checked_mul(self.window_size)
+            .is_none()
+        {
+            return Err("chain params: target_block_interval * window_size overflows".to_string());
+        }
+        Ok(())
+    }
+}
+
+impl Default for ChainParams {
+    fn default() -> Self {
+        Self::V1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::MAX_TIMESTAMP_STEP_PER_BLOCK;
+
+    #[test]
+    fn default_chain_params_are_the_v1_constants() {
+        let params = ChainParams::default();
+        assert_eq!(params, ChainParams::V1);
+        assert_eq!(params.target_block_interval, TARGET_BLOCK_INTERVAL);
+        assert_eq!(params.window_size, WINDOW_SIZE);
+        assert_eq!(params.coinbase_maturity, COINBASE_MATURITY);
+        assert_eq!(
+            params.max_timestamp_step_per_block(),
+            MAX_TIMESTAMP_STEP_PER_BLOCK
+        );
+        params.validate().expect("v1 params");
+    }
+
+    #[test]
+    fn validate_rejects_degenerate_params() {
+        let zero_window = ChainParams {
+            window_size: 0,
+            ..ChainParams::V1
+        };
+        assert!(zero_window.validate().is_err());
+        let zero_interval = ChainParams {
+            target_block_interval: 0,
+            ..ChainParams::V1
+        };
+        assert!(zero_interval.validate().is_err());
+        let overflow = ChainParams {
+            target_block_interval: u64::MAX,
+            window_size: 2,
+            coinbase_maturity: 0,
+        };
+        assert!(overflow.validate().is_err());
+    }
+}
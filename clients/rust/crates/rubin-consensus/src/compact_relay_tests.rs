@@ -1,5 +1,8 @@
+use std::collections::HashSet;
+
 use crate::compact_relay::siphash24;
 use crate::compact_shortid;
+use crate::hash::sha3_256;
 
 #[test]
 fn compact_siphash_reference_vectors() {
@@ -24,3 +27,25 @@ fn compact_shortid_vector() {
     let got = compact_shortid(wtxid, 0x0706_0504_0302_0100, 0x0f0e_0d0c_0b0a_0908);
     assert_eq!(got, [0xb5, 0x0c, 0x6f, 0xb8, 0x6b, 0x2f]);
 }
+
+#[test]
+fn compact_shortid_no_collisions_over_100k_wtxids() {
+    // Expected 48-bit collisions for n = 100_000 is n^2 / 2^49 ~ 2e-5, so any
+    // collision here points at a truncation or keying bug, not bad luck.
+    let (k0, k1) = (0x0123_4567_89ab_cdefu64, 0xfedc_ba98_7654_3210u64);
+    let mut seen = HashSet::with_capacity(100_000);
+    for i in 0u64..100_000 {
+        let wtxid = sha3_256(&i.to_le_bytes());
+        assert!(
+            seen.insert(compact_shortid(wtxid, k0, k1)),
+            "collision at {i}"
+        );
+    }
+
+    // Keying matters: the same wtxid under swapped nonces gives another id.
+    let wtxid = sha3_256(b"rubin");
+    assert_ne!(
+        compact_shortid(wtxid, k0, k1),
+        compact_shortid(wtxid, k1, k0)
+    );
+}
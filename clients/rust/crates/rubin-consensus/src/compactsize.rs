@@ -36,6 +36,34 @@ pub fn read_compact_size_bytes(b: &[u8]) -> Result<(u64, usize), TxError> {
     read_compact_size(&mut r)
 }
 
+/// Reads a minimal CompactSize that is at most `max`, returning it as a
+/// `usize` with the number of bytes consumed. A larger value fails with
+/// `code` / `overflow_msg` before the caller can size anything from it.
+pub(crate) fn read_compact_size_bounded(
+    r: &mut Reader<'_>,
+    max: usize,
+    code: ErrorCode,
+    overflow_msg: &'static str,
+) -> Result<(usize, usize), TxError> {
+    let (v, consumed) = read_compact_size(r)?;
+    if v > max as u64 {
+        return Err(TxError::new(code, overflow_msg));
+    }
+    Ok((v as usize, consumed))
+}
+
+/// Decodes a minimal CompactSize from the front of `b` that is at most
+/// `max`, returning the value and the number of bytes consumed.
+pub fn compact_size_decode_bounded(b: &[u8], max: usize) -> Result<(usize, usize), TxError> {
+    let mut r = Reader::new(b);
+    read_compact_size_bounded(
+        &mut r,
+        max,
+        ErrorCode::TxErrParse,
+        "CompactSize exceeds bound",
+    )
+}
+
 pub fn encode_compact_size(n: u64, out: &mut Vec<u8>) {
     match n {
         0x00..=0xfc => out.push(n as u8),
@@ -1,19 +1,19 @@
-use std::collections::HashMap;
-
 use sha3::{Digest, Sha3_256};
 
 use crate::block_basic::{
     median_time_past, parse_block_bytes, validate_coinbase_apply_outputs,
     validate_coinbase_value_bound, validate_parsed_block_basic_with_context_at_height, ParsedBlock,
 };
+use crate::chain_params::ChainParams;
 use crate::compactsize::encode_compact_size;
 use crate::constants::{COV_TYPE_ANCHOR, COV_TYPE_DA_COMMIT};
 use crate::error::{ErrorCode, TxError};
+use crate::outpoint_map::OutpointMap;
 use crate::sig_queue::SigCheckQueue;
 use crate::subsidy::block_subsidy;
 use crate::suite_registry::{RotationProvider, SuiteRegistry};
 use crate::utxo_basic::{
-    apply_non_coinbase_tx_basic_update_with_mtp_and_core_ext_profiles_and_suite_context,
+    apply_non_coinbase_tx_basic_update_with_mtp_and_core_ext_profiles_and_suite_context_and_maturity,
     apply_non_coinbase_tx_basic_update_with_mtp_and_core_ext_profiles_and_suite_context_queued_sigchecks,
     Outpoint, UtxoEntry,
 };
@@ -22,7 +22,7 @@ const UTXO_SET_HASH_DST: &[u8] = b"RUBINv1-utxo-set-hash/";
 
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct InMemoryChainState {
-    pub utxos: HashMap<Outpoint, UtxoEntry>,
+    pub utxos: OutpointMap<UtxoEntry>,
     /// already_generated(h): subsidy-only (excluding fees).
     pub already_generated: u128,
 }
@@ -49,6 +49,7 @@ struct ConnectBlockContext<'a> {
     chain_id: [u8; 32],
     rotation: Option<&'a dyn RotationProvider>,
     registry: Option<&'a SuiteRegistry>,
+    coinbase_maturity: u64,
 }
 
 struct PreparedConnectBlock {
@@ -95,6 +96,37 @@ pub fn connect_block_basic_in_memory_at_height_and_core_ext_deployments_with_sui
     chain_id: [u8; 32],
     rotation: Option<&dyn RotationProvider>,
     registry: Option<&SuiteRegistry>,
+) -> Result<ConnectBlockBasicSummary, TxError> {
+    connect_block_basic_in_memory_at_height_with_chain_params(
+        block_bytes,
+        expected_prev_hash,
+        expected_target,
+        block_height,
+        prev_timestamps,
+        state,
+        chain_id,
+        rotation,
+        registry,
+        &ChainParams::V1,
+    )
+}
+
+/// [`connect_block_basic_in_memory_at_height_and_core_ext_deployments_with_suite_context`]
+/// for a chain instance whose parameters may differ from the v1 constants.
+/// Only `coinbase_maturity` applies here; the expected target is the
+/// caller's, as for every other connect entry point.
+#[allow(clippy::too_many_arguments)]
+pub fn connect_block_basic_in_memory_at_height_with_chain_params(
+    block_bytes: &[u8],
+    expected_prev_hash: Option<[u8; 32]>,
+    expected_target: Option<[u8; 32]>,
+    block_height: u64,
+    prev_timestamps: Option<&[u64]>,
+    state: &mut InMemoryChainState,
+    chain_id: [u8; 32],
+    rotation: Option<&dyn RotationProvider>,
+    registry: Option<&SuiteRegistry>,
+    params: &ChainParams,
 ) -> Result<ConnectBlockBasicSummary, TxError> {
     let ctx = ConnectBlockContext {
         expected_prev_hash,
@@ -104,6 +136,7 @@ pub fn connect_block_basic_in_memory_at_height_and_core_ext_deployments_with_sui
         chain_id,
         rotation,
         registry,
+        coinbase_maturity: params.coinbase_maturity,
     };
     connect_block_basic_in_memory_with_context(block_bytes, state, &ctx)
 }
@@ -148,6 +181,38 @@ pub fn connect_block_parallel_sig_verify_and_core_ext_deployments_with_suite_con
     rotation: Option<&dyn RotationProvider>,
     registry: Option<&SuiteRegistry>,
     workers: usize,
+) -> Result<ConnectBlockBasicSummary, TxError> {
+    connect_block_parallel_sig_verify_with_chain_params(
+        block_bytes,
+        expected_prev_hash,
+        expected_target,
+        block_height,
+        prev_timestamps,
+        state,
+        chain_id,
+        rotation,
+        registry,
+        &ChainParams::V1,
+        workers,
+    )
+}
+
+/// [`connect_block_parallel_sig_verify_and_core_ext_deployments_with_suite_context`]
+/// for a chain instance whose parameters may differ from the v1 constants;
+/// as on the sequential path only `coinbase_maturity` applies.
+#[allow(clippy::too_many_arguments)]
+pub fn connect_block_parallel_sig_verify_with_chain_params(
+    block_bytes: &[u8],
+    expected_prev_hash: Option<[u8; 32]>,
+    expected_target: Option<[u8; 32]>,
+    block_height: u64,
+    prev_timestamps: Option<&[u64]>,
+    state: &mut InMemoryChainState,
+    chain_id: [u8; 32],
+    rotation: Option<&dyn RotationProvider>,
+    registry: Option<&SuiteRegistry>,
+    params: &ChainParams,
+    workers: usize,
 ) -> Result<ConnectBlockBasicSummary, TxError> {
     let ctx = ConnectBlockContext {
         expected_prev_hash,
@@ -157,6 +222,7 @@ pub fn connect_block_parallel_sig_verify_and_core_ext_deployments_with_suite_con
         chain_id,
         rotation,
         registry,
+        coinbase_maturity: params.coinbase_maturity,
     };
     connect_block_parallel_sig_verify_with_context(block_bytes, state, &ctx, workers)
 }
@@ -209,15 +275,15 @@ fn connect_block_basic_in_memory_with_context(
 
 fn apply_non_coinbase_txs_sequential(
     prepared: &PreparedConnectBlock,
-    state_utxos: &HashMap<Outpoint, UtxoEntry>,
+    state_utxos: &OutpointMap<UtxoEntry>,
     ctx: &ConnectBlockContext<'_>,
-) -> Result<(HashMap<Outpoint, UtxoEntry>, u64), TxError> {
+) -> Result<(OutpointMap<UtxoEntry>, u64), TxError> {
     let mut work_utxos = None;
     let mut sum_fees: u64 = 0;
     for i in 1..prepared.pb.txs.len() {
         let base_utxos = work_utxos.as_ref().unwrap_or(state_utxos);
         let (next_utxos, summary) =
-            apply_non_coinbase_tx_basic_update_with_mtp_and_core_ext_profiles_and_suite_context(
+            apply_non_coinbase_tx_basic_update_with_mtp_and_core_ext_profiles_and_suite_context_and_maturity(
                 &prepared.pb.txs[i],
                 prepared.pb.txids[i],
                 base_utxos,
@@ -227,6 +293,7 @@ fn apply_non_coinbase_txs_sequential(
                 ctx.chain_id,
                 ctx.rotation,
                 ctx.registry,
+                ctx.coinbase_maturity,
             )?;
         work_utxos = Some(next_utxos);
         sum_fees = add_block_fee(sum_fees, summary.fee)?;
@@ -249,10 +316,10 @@ fn connect_block_parallel_sig_verify_with_context(
 
 fn apply_non_coinbase_txs_parallel(
     prepared: &PreparedConnectBlock,
-    state_utxos: &HashMap<Outpoint, UtxoEntry>,
+    state_utxos: &OutpointMap<UtxoEntry>,
     ctx: &ConnectBlockContext<'_>,
     workers: usize,
-) -> Result<(HashMap<Outpoint, UtxoEntry>, u64, u64), TxError> {
+) -> Result<(OutpointMap<UtxoEntry>, u64, u64), TxError> {
     let mut work_utxos = state_utxos.clone();
     let mut sig_queue = match ctx.registry {
         Some(registry) => SigCheckQueue::new(workers).with_registry(registry),
@@ -272,6 +339,7 @@ fn apply_non_coinbase_txs_parallel(
                 ctx.chain_id,
                 ctx.rotation,
                 ctx.registry,
+                ctx.coinbase_maturity,
                 &mut sig_queue,
             )?;
         work_utxos = next_utxos;
@@ -293,7 +361,7 @@ fn add_block_fee(sum_fees: u64, fee: u64) -> Result<u64, TxError> {
 fn finalize_connected_block(
     state: &mut InMemoryChainState,
     prepared: &PreparedConnectBlock,
-    mut work_utxos: HashMap<Outpoint, UtxoEntry>,
+    mut work_utxos: OutpointMap<UtxoEntry>,
     sum_fees: u64,
     sig_task_count: u64,
 ) -> Result<ConnectBlockBasicSummary, TxError> {
@@ -326,10 +394,7 @@ fn finalize_connected_block(
     })
 }
 
-fn add_coinbase_outputs(
-    work_utxos: &mut HashMap<Outpoint, UtxoEntry>,
-    prepared: &PreparedConnectBlock,
-) {
+fn add_coinbase_outputs(work_utxos: &mut OutpointMap<UtxoEntry>, prepared: &PreparedConnectBlock) {
     let coinbase_txid = prepared.pb.txids[0];
     for (i, out) in prepared.pb.txs[0].outputs.iter().enumerate() {
         if out.covenant_type == COV_TYPE_ANCHOR || out.covenant_type == COV_TYPE_DA_COMMIT {
@@ -364,16 +429,42 @@ fn already_generated_after_block(
     Ok(already_generated)
 }
 
+/// canonical_outpoint_key is the ordering key of the UTXO set digest:
+/// `txid (32, as stored) || vout_le (4)`. Comparing keys bytewise gives the
+/// canonical UTXO order; note vout is little-endian, so it is not numeric
+/// order within a txid once vout exceeds 255.
+pub fn canonical_outpoint_key(outpoint: &Outpoint) -> [u8; 36] {
+    let mut key = [0u8; 36];
+    key[..32].copy_from_slice(&outpoint.txid);
+    key[32..].copy_from_slice(&outpoint.vout.to_le_bytes());
+    key
+}
+
+/// canonical_utxo_entry_bytes returns the per-entry record hashed by
+/// utxo_set_hash: the canonical outpoint key followed by the entry fields.
+pub fn canonical_utxo_entry_bytes(outpoint: &Outpoint, entry: &UtxoEntry) -> Vec<u8> {
+    let mut buf = Vec::with_capacity(36 + 8 + 2 + 9 + entry.covenant_data.len() + 8 + 1);
+    append_canonical_utxo_entry(&mut buf, &canonical_outpoint_key(outpoint), entry);
+    buf
+}
+
+fn append_canonical_utxo_entry(buf: &mut Vec<u8>, key: &[u8; 36], entry: &UtxoEntry) {
+    buf.extend_from_slice(key);
+    buf.extend_from_slice(&entry.value.to_le_bytes());
+    buf.extend_from_slice(&entry.covenant_type.to_le_bytes());
+    encode_compact_size(entry.covenant_data.len() as u64, buf);
+    buf.extend_from_slice(&entry.covenant_data);
+    buf.extend_from_slice(&entry.creation_height.to_le_bytes());
+    buf.push(u8::from(entry.created_by_coinbase));
+}
+
 /// utxo_set_hash computes a deterministic SHA3-256 digest over the UTXO set.
 /// Must match Go consensus.UtxoSetHash and rubin-node chainstate for parity.
-pub(crate) fn utxo_set_hash(utxos: &HashMap<Outpoint, UtxoEntry>) -> [u8; 32] {
-    let mut items: Vec<([u8; 36], &UtxoEntry)> = Vec::with_capacity(utxos.len());
-    for (outpoint, entry) in utxos {
-        let mut key = [0u8; 36];
-        key[..32].copy_from_slice(&outpoint.txid);
-        key[32..].copy_from_slice(&outpoint.vout.to_le_bytes());
-        items.push((key, entry));
-    }
+pub fn utxo_set_hash(utxos: &OutpointMap<UtxoEntry>) -> [u8; 32] {
+    let mut items: Vec<([u8; 36], &UtxoEntry)> = utxos
+        .iter()
+        .map(|(outpoint, entry)| (canonical_outpoint_key(outpoint), entry))
+        .collect();
     // sort_unstable_by avoids decorate/sort/undecorate copies of the
     // [u8; 36] key that sort_by_key/sort_unstable_by_key would do —
     // important on the consensus digest path with large UTXO sets.
@@ -383,17 +474,9 @@ pub(crate) fn utxo_set_hash(utxos: &HashMap<Outpoint, UtxoEntry>) -> [u8; 32] {
     let mut buf = Vec::with_capacity(UTXO_SET_HASH_DST.len() + 8 + items.len() * 64);
     buf.extend_from_slice(UTXO_SET_HASH_DST);
     buf.extend_from_slice(&(items.len() as u64).to_le_bytes());
-
     for (key, entry) in items {
-        buf.extend_from_slice(&key);
-        buf.extend_from_slice(&entry.value.to_le_bytes());
-        buf.extend_from_slice(&entry.covenant_type.to_le_bytes());
-        encode_compact_size(entry.covenant_data.len() as u64, &mut buf);
-        buf.extend_from_slice(&entry.covenant_data);
-        buf.extend_from_slice(&entry.creation_height.to_le_bytes());
-        buf.push(u8::from(entry.created_by_coinbase));
+        append_canonical_utxo_entry(&mut buf, &key, entry);
     }
-
     Sha3_256::digest(&buf).into()
 }
 
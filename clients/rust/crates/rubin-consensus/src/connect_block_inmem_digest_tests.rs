@@ -31,7 +31,7 @@ fn make_entry(
 
 #[test]
 fn state_digest_empty_set() {
-    let utxos = HashMap::new();
+    let utxos = OutpointMap::default();
     let digest = utxo_set_hash(&utxos);
     // DST + count(0) = "RUBINv1-utxo-set-hash/" + 0u64 LE
     let mut expected_buf = Vec::new();
@@ -47,7 +47,7 @@ fn state_digest_empty_set() {
 
 #[test]
 fn state_digest_single_utxo_manual() {
-    let mut utxos = HashMap::new();
+    let mut utxos = OutpointMap::default();
     let op = make_outpoint!(0x42, 7);
     let entry = make_entry(1_000_000, 0x0000, &[], 100, false);
     utxos.insert(op.clone(), entry);
@@ -92,13 +92,13 @@ fn state_digest_deterministic_insertion_order() {
     let entry_c = make_entry(300, 0x0101, &[0xCD, 0xEF], 3, false);
 
     // Forward insertion
-    let mut forward = HashMap::new();
+    let mut forward = OutpointMap::default();
     forward.insert(op_a.clone(), entry_a.clone());
     forward.insert(op_b.clone(), entry_b.clone());
     forward.insert(op_c.clone(), entry_c.clone());
 
     // Reverse insertion
-    let mut reverse = HashMap::new();
+    let mut reverse = OutpointMap::default();
     reverse.insert(op_c.clone(), entry_c.clone());
     reverse.insert(op_b.clone(), entry_b.clone());
     reverse.insert(op_a.clone(), entry_a.clone());
@@ -116,10 +116,10 @@ fn state_digest_different_sets_differ() {
     let entry_a = make_entry(100, 0x0000, &[], 1, false);
     let entry_b = make_entry(101, 0x0000, &[], 1, false); // different value
 
-    let mut set_a = HashMap::new();
+    let mut set_a = OutpointMap::default();
     set_a.insert(op.clone(), entry_a);
 
-    let mut set_b = HashMap::new();
+    let mut set_b = OutpointMap::default();
     set_b.insert(op.clone(), entry_b);
 
     assert_ne!(utxo_set_hash(&set_a), utxo_set_hash(&set_b));
@@ -132,9 +132,9 @@ fn state_digest_different_sets_differ() {
 #[test]
 fn state_digest_sensitive_to_value() {
     let op = make_outpoint!(0x01, 0);
-    let mut s1 = HashMap::new();
+    let mut s1 = OutpointMap::default();
     s1.insert(op.clone(), make_entry(100, 0, &[], 0, false));
-    let mut s2 = HashMap::new();
+    let mut s2 = OutpointMap::default();
     s2.insert(op.clone(), make_entry(101, 0, &[], 0, false));
     assert_ne!(utxo_set_hash(&s1), utxo_set_hash(&s2));
 }
@@ -142,9 +142,9 @@ fn state_digest_sensitive_to_value() {
 #[test]
 fn state_digest_sensitive_to_covenant_type() {
     let op = make_outpoint!(0x01, 0);
-    let mut s1 = HashMap::new();
+    let mut s1 = OutpointMap::default();
     s1.insert(op.clone(), make_entry(100, 0x0000, &[], 0, false));
-    let mut s2 = HashMap::new();
+    let mut s2 = OutpointMap::default();
     s2.insert(op.clone(), make_entry(100, 0x0100, &[], 0, false));
     assert_ne!(utxo_set_hash(&s1), utxo_set_hash(&s2));
 }
@@ -152,9 +152,9 @@ fn state_digest_sensitive_to_covenant_type() {
 #[test]
 fn state_digest_sensitive_to_covenant_data() {
     let op = make_outpoint!(0x01, 0);
-    let mut s1 = HashMap::new();
+    let mut s1 = OutpointMap::default();
     s1.insert(op.clone(), make_entry(100, 0, &[0x01], 0, false));
-    let mut s2 = HashMap::new();
+    let mut s2 = OutpointMap::default();
     s2.insert(op.clone(), make_entry(100, 0, &[0x02], 0, false));
     assert_ne!(utxo_set_hash(&s1), utxo_set_hash(&s2));
 }
@@ -162,9 +162,9 @@ fn state_digest_sensitive_to_covenant_data() {
 #[test]
 fn state_digest_sensitive_to_creation_height() {
     let op = make_outpoint!(0x01, 0);
-    let mut s1 = HashMap::new();
+    let mut s1 = OutpointMap::default();
     s1.insert(op.clone(), make_entry(100, 0, &[], 10, false));
-    let mut s2 = HashMap::new();
+    let mut s2 = OutpointMap::default();
     s2.insert(op.clone(), make_entry(100, 0, &[], 11, false));
     assert_ne!(utxo_set_hash(&s1), utxo_set_hash(&s2));
 }
@@ -172,9 +172,9 @@ fn state_digest_sensitive_to_creation_height() {
 #[test]
 fn state_digest_sensitive_to_coinbase_flag() {
     let op = make_outpoint!(0x01, 0);
-    let mut s1 = HashMap::new();
+    let mut s1 = OutpointMap::default();
     s1.insert(op.clone(), make_entry(100, 0, &[], 0, false));
-    let mut s2 = HashMap::new();
+    let mut s2 = OutpointMap::default();
     s2.insert(op.clone(), make_entry(100, 0, &[], 0, true));
     assert_ne!(utxo_set_hash(&s1), utxo_set_hash(&s2));
 }
@@ -184,9 +184,9 @@ fn state_digest_sensitive_to_txid() {
     let op_a = make_outpoint!(0x01, 0);
     let op_b = make_outpoint!(0x02, 0);
     let entry = make_entry(100, 0, &[], 0, false);
-    let mut s1 = HashMap::new();
+    let mut s1 = OutpointMap::default();
     s1.insert(op_a, entry.clone());
-    let mut s2 = HashMap::new();
+    let mut s2 = OutpointMap::default();
     s2.insert(op_b, entry);
     assert_ne!(utxo_set_hash(&s1), utxo_set_hash(&s2));
 }
@@ -196,9 +196,9 @@ fn state_digest_sensitive_to_vout() {
     let op_a = make_outpoint!(0x01, 0);
     let op_b = make_outpoint!(0x01, 1);
     let entry = make_entry(100, 0, &[], 0, false);
-    let mut s1 = HashMap::new();
+    let mut s1 = OutpointMap::default();
     s1.insert(op_a, entry.clone());
-    let mut s2 = HashMap::new();
+    let mut s2 = OutpointMap::default();
     s2.insert(op_b, entry);
     assert_ne!(utxo_set_hash(&s1), utxo_set_hash(&s2));
 }
@@ -217,12 +217,12 @@ fn state_digest_sorting_by_outpoint() {
     let e1 = make_entry(200, 0, &[], 0, false);
     let e2 = make_entry(300, 0, &[], 0, false);
 
-    let mut desc = HashMap::new();
+    let mut desc = OutpointMap::default();
     desc.insert(op_2.clone(), e2.clone());
     desc.insert(op_1.clone(), e1.clone());
     desc.insert(op_0.clone(), e0.clone());
 
-    let mut asc = HashMap::new();
+    let mut asc = OutpointMap::default();
     asc.insert(op_0, e0);
     asc.insert(op_1, e1);
     asc.insert(op_2, e2);
@@ -240,7 +240,7 @@ fn state_digest_large_covenant_data() {
     let cov_data = vec![0xABu8; 300]; // 300 bytes → 0xfd prefix (3-byte compact size)
     let entry = make_entry(500, 0x0102, &cov_data, 42, true);
 
-    let mut utxos = HashMap::new();
+    let mut utxos = OutpointMap::default();
     utxos.insert(op.clone(), entry);
 
     let digest = utxo_set_hash(&utxos);
@@ -272,7 +272,7 @@ fn state_digest_large_covenant_data() {
 
 #[test]
 fn state_digest_idempotent() {
-    let mut utxos = HashMap::new();
+    let mut utxos = OutpointMap::default();
     utxos.insert(make_outpoint!(0x01, 0), make_entry(100, 0, &[], 1, false));
     utxos.insert(
         make_outpoint!(0x02, 5),
@@ -290,7 +290,7 @@ fn state_digest_idempotent() {
 
 #[test]
 fn state_digest_includes_dst() {
-    let utxos = HashMap::new();
+    let utxos = OutpointMap::default();
     let digest = utxo_set_hash(&utxos);
 
     // Hash of just count=0 (no DST) must differ
@@ -308,10 +308,10 @@ fn state_digest_count_changes_hash() {
     let op_b = make_outpoint!(0x02, 0);
     let entry = make_entry(100, 0, &[], 0, false);
 
-    let mut one = HashMap::new();
+    let mut one = OutpointMap::default();
     one.insert(op_a.clone(), entry.clone());
 
-    let mut two = HashMap::new();
+    let mut two = OutpointMap::default();
     two.insert(op_a, entry.clone());
     two.insert(op_b, entry);
 
@@ -327,7 +327,7 @@ fn state_digest_coinbase_flag_encoding() {
     let op = make_outpoint!(0x01, 0);
     let entry = make_entry(100, 0, &[], 0, true);
 
-    let mut utxos = HashMap::new();
+    let mut utxos = OutpointMap::default();
     utxos.insert(op.clone(), entry);
 
     let digest = utxo_set_hash(&utxos);
@@ -362,7 +362,7 @@ fn state_digest_max_values_no_panic() {
     };
     let entry = make_entry(u64::MAX, u16::MAX, &[0xFF; 252], u64::MAX, true);
 
-    let mut utxos = HashMap::new();
+    let mut utxos = OutpointMap::default();
     utxos.insert(op, entry);
 
     // Must not panic
@@ -387,7 +387,7 @@ fn state_digest_sort_by_txid_then_vout() {
     let e_b = make_entry(200, 0, &[], 0, false);
     let e_c = make_entry(300, 0, &[], 0, false);
 
-    let mut utxos = HashMap::new();
+    let mut utxos = OutpointMap::default();
     utxos.insert(op_a.clone(), e_a.clone());
     utxos.insert(op_b.clone(), e_b.clone());
     utxos.insert(op_c.clone(), e_c.clone());
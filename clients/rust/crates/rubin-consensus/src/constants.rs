@@ -1,4 +1,8 @@
 pub const WITNESS_DISCOUNT_DIVISOR: u64 = 4;
+/// Weight per CORE_ANCHOR / CORE_DA_COMMIT covenant_data byte. CANONICAL §9
+/// counts those bytes in the base size with no discount, so this equals the
+/// base-byte multiplier; pinned by CV-WEIGHT. Mirror of Go `ANCHOR_WEIGHT_FACTOR`.
+pub const ANCHOR_WEIGHT_FACTOR: u64 = WITNESS_DISCOUNT_DIVISOR;
 
 pub const TARGET_BLOCK_INTERVAL: u64 = 120;
 pub const WINDOW_SIZE: u64 = 10_080;
@@ -33,6 +33,17 @@ pub struct CoreExtCovenant<'a> {
     pub ext_payload: &'a [u8],
 }
 
+impl CoreExtCovenant<'_> {
+    /// Canonical covenant_data encoding; inverse of [`parse_core_ext_covenant_data`].
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(2 + 9 + self.ext_payload.len());
+        out.extend_from_slice(&self.ext_id.to_le_bytes());
+        encode_compact_size(self.ext_payload.len() as u64, &mut out);
+        out.extend_from_slice(self.ext_payload);
+        out
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum CoreExtVerificationBinding {
     /// Verify via native `verify_sig` dispatch.
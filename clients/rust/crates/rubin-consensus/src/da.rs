@@ -0,0 +1,95 @@
+//! DA transaction recognition.
+//!
+//! DA-bearing transactions are identified by `tx_kind` on the wire
+//! (0x01 = DA_COMMIT_TX, 0x02 = DA_CHUNK_TX); `parse_tx` already enforces
+//! that the matching core is present and the payload is in range, so
+//! classification is a projection of the parsed fields plus the commit
+//! `chunk_count` cap, re-checked for callers holding a hand-built `Tx`.
+
+use crate::constants::MAX_DA_CHUNK_COUNT;
+use crate::error::{ErrorCode, TxError};
+use crate::tx::Tx;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DaTxKind {
+    Commit {
+        da_id: [u8; 32],
+        chunk_count: u16,
+        batch_number: u64,
+        payload_len: u64,
+    },
+    Chunk {
+        da_id: [u8; 32],
+        index: u16,
+        chunk_hash: [u8; 32],
+        payload_len: u64,
+    },
+}
+
+impl DaTxKind {
+    pub fn da_id(&self) -> [u8; 32] {
+        match self {
+            Self::Commit { da_id, .. } | Self::Chunk { da_id, .. } => *da_id,
+        }
+    }
+}
+
+/// Rejects a DA commit `chunk_count` outside `1..=max_chunk_count` with
+/// `TX_ERR_PARSE`. Takes `u64` so wider inputs (harness requests, policy
+/// overrides) are range-checked before they are narrowed to the wire `u16`.
+pub fn check_da_chunk_count(chunk_count: u64, max_chunk_count: u64) -> Result<(), TxError> {
+    if chunk_count == 0 || chunk_count > max_chunk_count {
+        return Err(TxError::new(
+            ErrorCode::TxErrParse,
+            "chunk_count out of range for tx_kind=0x01",
+        ));
+    }
+    Ok(())
+}
+
+/// Classifies a parsed transaction as a DA commit or chunk, or `None` for
+/// an ordinary (`tx_kind = 0x00`) transaction. A commit whose `chunk_count`
+/// exceeds `MAX_DA_CHUNK_COUNT` (or is zero) fails with `TX_ERR_PARSE`.
+pub fn classify_tx(tx: &Tx) -> Result<Option<DaTxKind>, TxError> {
+    classify_tx_with_max_chunk_count(tx, MAX_DA_CHUNK_COUNT)
+}
+
+/// [`classify_tx`] against a caller-supplied commit chunk-count cap, for
+/// policy layers that run tighter than the consensus `MAX_DA_CHUNK_COUNT`.
+pub fn classify_tx_with_max_chunk_count(
+    tx: &Tx,
+    max_chunk_count: u64,
+) -> Result<Option<DaTxKind>, TxError> {
+    let payload_len = tx.da_payload.len() as u64;
+    match tx.tx_kind {
+        0x01 => {
+            let Some(core) = tx.da_commit_core.as_ref() else {
+                return Ok(None);
+            };
+            check_da_chunk_count(u64::from(core.chunk_count), max_chunk_count)?;
+            Ok(Some(DaTxKind::Commit {
+                da_id: core.da_id,
+                chunk_count: core.chunk_count,
+                batch_number: core.batch_number,
+                payload_len,
+            }))
+        }
+        0x02 => Ok(tx.da_chunk_core.as_ref().map(|core| DaTxKind::Chunk {
+            da_id: core.da_id,
+            index: core.chunk_index,
+            chunk_hash: core.chunk_hash,
+            payload_len,
+        })),
+        _ => Ok(None),
+    }
+}
+
+/// DA payload bytes counted against the block DA budget; the `da_bytes`
+/// component of `tx_weight_and_stats_public`.
+pub fn tx_da_bytes(tx: &Tx) -> u64 {
+    if tx.tx_kind == 0x00 {
+        0
+    } else {
+        tx.da_payload.len() as u64
+    }
+}
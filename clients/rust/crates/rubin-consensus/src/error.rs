@@ -1,97 +1,88 @@
 use core::fmt;
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
-pub enum ErrorCode {
-    TxErrParse,
-    TxErrWitnessOverflow,
-    TxErrSigNoncanonical,
-    TxErrSigAlgInvalid,
-    TxErrSigInvalid,
-    TxErrSighashTypeInvalid,
-    TxErrTimelockNotMet,
-    TxErrValueConservation,
-    TxErrTxNonceInvalid,
-    TxErrSequenceInvalid,
-    TxErrNonceReplay,
-    TxErrCovenantTypeInvalid,
-    TxErrVaultMalformed,
-    TxErrVaultParamsInvalid,
-    TxErrVaultKeysNotCanonical,
-    TxErrVaultWhitelistNotCanonical,
-    TxErrVaultOwnerDestinationForbidden,
-    TxErrVaultOwnerAuthRequired,
-    TxErrVaultFeeSponsorForbidden,
-    TxErrVaultMultiInputForbidden,
-    TxErrVaultOutputNotWhitelisted,
-    TxErrMissingUtxo,
-    TxErrCoinbaseImmature,
+/// Declares every consensus error code once, as `Variant => CANONICAL_NAME`.
+/// The enum, the [`codes`] string constants, [`ErrorCode::ALL`] and both
+/// string conversions are generated from that single list, so a code cannot
+/// exist in one of them and not the others.
+macro_rules! error_codes {
+    ($($variant:ident => $name:ident,)+) => {
+        #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+        pub enum ErrorCode {
+            $($variant,)+
+        }
 
-    BlockErrParse,
-    BlockErrWeightExceeded,
-    BlockErrAnchorBytesExceeded,
-    BlockErrPowInvalid,
-    BlockErrTargetInvalid,
-    BlockErrLinkageInvalid,
-    BlockErrMerkleInvalid,
-    BlockErrWitnessCommitment,
-    BlockErrCoinbaseInvalid,
-    BlockErrSubsidyExceeded,
-    BlockErrTimestampOld,
-    BlockErrTimestampFuture,
-    BlockErrDaIncomplete,
-    BlockErrDaChunkHashInvalid,
-    BlockErrDaSetInvalid,
-    BlockErrDaPayloadCommitInvalid,
-    BlockErrDaBatchExceeded,
-}
+        /// Canonical code strings (Go `ErrorCode` values), one per
+        /// [`ErrorCode`] variant.
+        pub mod codes {
+            $(pub const $name: &str = stringify!($name);)+
+        }
 
-impl ErrorCode {
-    pub fn as_str(self) -> &'static str {
-        match self {
-            ErrorCode::TxErrParse => "TX_ERR_PARSE",
-            ErrorCode::TxErrWitnessOverflow => "TX_ERR_WITNESS_OVERFLOW",
-            ErrorCode::TxErrSigNoncanonical => "TX_ERR_SIG_NONCANONICAL",
-            ErrorCode::TxErrSigAlgInvalid => "TX_ERR_SIG_ALG_INVALID",
-            ErrorCode::TxErrSigInvalid => "TX_ERR_SIG_INVALID",
-            ErrorCode::TxErrSighashTypeInvalid => "TX_ERR_SIGHASH_TYPE_INVALID",
-            ErrorCode::TxErrTimelockNotMet => "TX_ERR_TIMELOCK_NOT_MET",
-            ErrorCode::TxErrValueConservation => "TX_ERR_VALUE_CONSERVATION",
-            ErrorCode::TxErrTxNonceInvalid => "TX_ERR_TX_NONCE_INVALID",
-            ErrorCode::TxErrSequenceInvalid => "TX_ERR_SEQUENCE_INVALID",
-            ErrorCode::TxErrNonceReplay => "TX_ERR_NONCE_REPLAY",
-            ErrorCode::TxErrCovenantTypeInvalid => "TX_ERR_COVENANT_TYPE_INVALID",
-            ErrorCode::TxErrVaultMalformed => "TX_ERR_VAULT_MALFORMED",
-            ErrorCode::TxErrVaultParamsInvalid => "TX_ERR_VAULT_PARAMS_INVALID",
-            ErrorCode::TxErrVaultKeysNotCanonical => "TX_ERR_VAULT_KEYS_NOT_CANONICAL",
-            ErrorCode::TxErrVaultWhitelistNotCanonical => "TX_ERR_VAULT_WHITELIST_NOT_CANONICAL",
-            ErrorCode::TxErrVaultOwnerDestinationForbidden => {
-                "TX_ERR_VAULT_OWNER_DESTINATION_FORBIDDEN"
-            }
-            ErrorCode::TxErrVaultOwnerAuthRequired => "TX_ERR_VAULT_OWNER_AUTH_REQUIRED",
-            ErrorCode::TxErrVaultFeeSponsorForbidden => "TX_ERR_VAULT_FEE_SPONSOR_FORBIDDEN",
-            ErrorCode::TxErrVaultMultiInputForbidden => "TX_ERR_VAULT_MULTI_INPUT_FORBIDDEN",
-            ErrorCode::TxErrVaultOutputNotWhitelisted => "TX_ERR_VAULT_OUTPUT_NOT_WHITELISTED",
-            ErrorCode::TxErrMissingUtxo => "TX_ERR_MISSING_UTXO",
-            ErrorCode::TxErrCoinbaseImmature => "TX_ERR_COINBASE_IMMATURE",
+        impl ErrorCode {
+            /// Every variant, in declaration order.
+            pub const ALL: &'static [ErrorCode] = &[$(ErrorCode::$variant,)+];
 
-            ErrorCode::BlockErrParse => "BLOCK_ERR_PARSE",
-            ErrorCode::BlockErrWeightExceeded => "BLOCK_ERR_WEIGHT_EXCEEDED",
-            ErrorCode::BlockErrAnchorBytesExceeded => "BLOCK_ERR_ANCHOR_BYTES_EXCEEDED",
-            ErrorCode::BlockErrPowInvalid => "BLOCK_ERR_POW_INVALID",
-            ErrorCode::BlockErrTargetInvalid => "BLOCK_ERR_TARGET_INVALID",
-            ErrorCode::BlockErrLinkageInvalid => "BLOCK_ERR_LINKAGE_INVALID",
-            ErrorCode::BlockErrMerkleInvalid => "BLOCK_ERR_MERKLE_INVALID",
-            ErrorCode::BlockErrWitnessCommitment => "BLOCK_ERR_WITNESS_COMMITMENT",
-            ErrorCode::BlockErrCoinbaseInvalid => "BLOCK_ERR_COINBASE_INVALID",
-            ErrorCode::BlockErrSubsidyExceeded => "BLOCK_ERR_SUBSIDY_EXCEEDED",
-            ErrorCode::BlockErrTimestampOld => "BLOCK_ERR_TIMESTAMP_OLD",
-            ErrorCode::BlockErrTimestampFuture => "BLOCK_ERR_TIMESTAMP_FUTURE",
-            ErrorCode::BlockErrDaIncomplete => "BLOCK_ERR_DA_INCOMPLETE",
-            ErrorCode::BlockErrDaChunkHashInvalid => "BLOCK_ERR_DA_CHUNK_HASH_INVALID",
-            ErrorCode::BlockErrDaSetInvalid => "BLOCK_ERR_DA_SET_INVALID",
-            ErrorCode::BlockErrDaPayloadCommitInvalid => "BLOCK_ERR_DA_PAYLOAD_COMMIT_INVALID",
-            ErrorCode::BlockErrDaBatchExceeded => "BLOCK_ERR_DA_BATCH_EXCEEDED",
+            pub fn as_str(self) -> &'static str {
+                match self {
+                    $(ErrorCode::$variant => codes::$name,)+
+                }
+            }
         }
+    };
+}
+
+error_codes! {
+    TxErrParse => TX_ERR_PARSE,
+    TxErrWitnessOverflow => TX_ERR_WITNESS_OVERFLOW,
+    TxErrSigNoncanonical => TX_ERR_SIG_NONCANONICAL,
+    TxErrSigAlgInvalid => TX_ERR_SIG_ALG_INVALID,
+    TxErrSigInvalid => TX_ERR_SIG_INVALID,
+    TxErrSighashTypeInvalid => TX_ERR_SIGHASH_TYPE_INVALID,
+    TxErrTimelockNotMet => TX_ERR_TIMELOCK_NOT_MET,
+    TxErrValueConservation => TX_ERR_VALUE_CONSERVATION,
+    TxErrTxNonceInvalid => TX_ERR_TX_NONCE_INVALID,
+    TxErrSequenceInvalid => TX_ERR_SEQUENCE_INVALID,
+    TxErrNonceReplay => TX_ERR_NONCE_REPLAY,
+    TxErrCovenantTypeInvalid => TX_ERR_COVENANT_TYPE_INVALID,
+    TxErrVaultMalformed => TX_ERR_VAULT_MALFORMED,
+    TxErrVaultParamsInvalid => TX_ERR_VAULT_PARAMS_INVALID,
+    TxErrVaultKeysNotCanonical => TX_ERR_VAULT_KEYS_NOT_CANONICAL,
+    TxErrVaultWhitelistNotCanonical => TX_ERR_VAULT_WHITELIST_NOT_CANONICAL,
+    TxErrVaultOwnerDestinationForbidden => TX_ERR_VAULT_OWNER_DESTINATION_FORBIDDEN,
+    TxErrVaultOwnerAuthRequired => TX_ERR_VAULT_OWNER_AUTH_REQUIRED,
+    TxErrVaultFeeSponsorForbidden => TX_ERR_VAULT_FEE_SPONSOR_FORBIDDEN,
+    TxErrVaultMultiInputForbidden => TX_ERR_VAULT_MULTI_INPUT_FORBIDDEN,
+    TxErrVaultOutputNotWhitelisted => TX_ERR_VAULT_OUTPUT_NOT_WHITELISTED,
+    TxErrMissingUtxo => TX_ERR_MISSING_UTXO,
+    TxErrCoinbaseImmature => TX_ERR_COINBASE_IMMATURE,
+
+    BlockErrParse => BLOCK_ERR_PARSE,
+    BlockErrWeightExceeded => BLOCK_ERR_WEIGHT_EXCEEDED,
+    BlockErrAnchorBytesExceeded => BLOCK_ERR_ANCHOR_BYTES_EXCEEDED,
+    BlockErrPowInvalid => BLOCK_ERR_POW_INVALID,
+    BlockErrTargetInvalid => BLOCK_ERR_TARGET_INVALID,
+    BlockErrLinkageInvalid => BLOCK_ERR_LINKAGE_INVALID,
+    BlockErrMerkleInvalid => BLOCK_ERR_MERKLE_INVALID,
+    BlockErrWitnessCommitment => BLOCK_ERR_WITNESS_COMMITMENT,
+    BlockErrCoinbaseInvalid => BLOCK_ERR_COINBASE_INVALID,
+    BlockErrSubsidyExceeded => BLOCK_ERR_SUBSIDY_EXCEEDED,
+    BlockErrTimestampOld => BLOCK_ERR_TIMESTAMP_OLD,
+    BlockErrTimestampFuture => BLOCK_ERR_TIMESTAMP_FUTURE,
+    BlockErrDaIncomplete => BLOCK_ERR_DA_INCOMPLETE,
+    BlockErrDaChunkHashInvalid => BLOCK_ERR_DA_CHUNK_HASH_INVALID,
+    BlockErrDaSetInvalid => BLOCK_ERR_DA_SET_INVALID,
+    BlockErrDaPayloadCommitInvalid => BLOCK_ERR_DA_PAYLOAD_COMMIT_INVALID,
+    BlockErrDaBatchExceeded => BLOCK_ERR_DA_BATCH_EXCEEDED,
+}
+
+impl std::str::FromStr for ErrorCode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, String> {
+        ErrorCode::ALL
+            .iter()
+            .copied()
+            .find(|code| code.as_str() == s)
+            .ok_or_else(|| format!("unknown error code: {s}"))
     }
 }
 
@@ -105,6 +96,13 @@ impl TxError {
     pub fn new(code: ErrorCode, msg: &'static str) -> Self {
         Self { code, msg }
     }
+
+    /// Whether the OpenSSL ML-DSA provider failed (bootstrap, key or context
+    /// setup). Such errors travel as `TX_ERR_PARSE` but say nothing about
+    /// the data being checked.
+    pub fn is_crypto_provider(&self) -> bool {
+        self.code == ErrorCode::TxErrParse && self.msg.starts_with("openssl")
+    }
 }
 
 impl fmt::Display for TxError {
@@ -1,5 +1,6 @@
 use sha3::{Digest, Sha3_256};
 
+#[inline]
 pub fn sha3_256(b: &[u8]) -> [u8; 32] {
     let mut h = Sha3_256::new();
     h.update(b);
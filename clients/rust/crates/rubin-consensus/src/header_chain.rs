@@ -0,0 +1,247 @@
+//! Stateless validation of a header batch for headers-first sync.
+//!
+//! Only the header-level checks that block connection already performs are
+//! applied (linkage, expected target, PoW, MTP timestamp window), each through
+//! the same helper, so a header chain accepted here cannot be rejected by
+//! `connect_block_*` for a header reason.
+
+use crate::block::{block_hash, BlockHeader};
+use crate::block_basic::{check_block_timestamp, median_time_past};
+use crate::chain_params::ChainParams;
+use crate::error::{ErrorCode, TxError};
+use crate::pow::{block_expected_target_with_params, pow_check};
+
+/// Validates `headers` as consecutive blocks starting at `start_height` on top
+/// of `ancestors` (oldest first, parent of `headers[0]` last). `ancestors`
+/// must reach back far enough for the MTP window and, when the batch crosses
+/// a retarget boundary, for the full `WINDOW_SIZE` timestamp window.
+///
+/// Returns the block hash of every header, or the index of the first bad
+/// header together with its consensus error.
+pub fn validate_header_chain(
+    ancestors: &[BlockHeader],
+    headers: &[BlockHeader],
+    start_height: u64,
+) -> Result<Vec<[u8; 32]>, (usize, TxError)> {
+    validate_header_chain_with_params(ancestors, headers, start_height, &ChainParams::V1)
+}
+
+/// [`validate_header_chain`] retargeting per `params` instead of the v1
+/// constants.
+pub fn validate_header_chain_with_params(
+    ancestors: &[BlockHeader],
+    headers: &[BlockHeader],
+    start_height: u64,
+    params: &ChainParams,
+) -> Result<Vec<[u8; 32]>, (usize, TxError)> {
+    let mut chain: Vec<BlockHeader> = Vec::with_capacity(ancestors.len() + headers.len());
+    chain.extend_from_slice(ancestors);
+    let mut prev_hash = match ancestors.last() {
+        Some(parent) => Some(block_hash(&parent.to_bytes()).map_err(|e| (0, e))?),
+        None => None,
+    };
+    let mut hashes = Vec::with_capacity(headers.len());
+
+    for (i, header) in headers.iter().enumerate() {
+        let height = start_height
+            .checked_add(i as u64)
+            .ok_or_else(|| (i, TxError::new(ErrorCode::BlockErrParse, "height overflow")))?;
+        let header_bytes = header.to_bytes();
+        let hash = validate_next_header(&chain, prev_hash, header, &header_bytes, height, params)
+            .map_err(|e| (i, e))?;
+        hashes.push(hash);
+        prev_hash = Some(hash);
+        chain.push(header.clone());
+    }
+    Ok(hashes)
+}
+
+fn validate_next_header(
+    chain: &[BlockHeader],
+    prev_hash: Option<[u8; 32]>,
+    header: &BlockHeader,
+    header_bytes: &[u8],
+    height: u64,
+    params: &ChainParams,
+) -> Result<[u8; 32], TxError> {
+    let Some(prev_hash) = prev_hash else {
+        return Err(TxError::new(
+            ErrorCode::BlockErrLinkageInvalid,
+            "missing parent header",
+        ));
+    };
+    if header.prev_block_hash != prev_hash {
+        return Err(TxError::new(
+            ErrorCode::BlockErrLinkageInvalid,
+            "prev_block_hash mismatch",
+        ));
+    }
+    if header.target != block_expected_target_with_params(chain, height, params)? {
+        return Err(TxError::new(
+            ErrorCode::BlockErrTargetInvalid,
+            "target mismatch",
+        ));
+    }
+    pow_check(header_bytes, header.target)?;
+
+    let prev_timestamps: Vec<u64> = chain.iter().rev().take(11).map(|h| h.timestamp).collect();
+    if let Some(mtp) = median_time_past(height, Some(&prev_timestamps))? {
+        check_block_timestamp(header.timestamp, mtp, None)?;
+    }
+    block_hash(header_bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::{POW_LIMIT, TARGET_BLOCK_INTERVAL, WINDOW_SIZE};
+
+    /// Extends `chain` with `count` headers spaced `interval` apart, grinding
+    /// nonces until each one meets its expected target.
+    fn extend_chain(chain: &mut Vec<BlockHeader>, start_height: u64, count: usize, interval: u64) {
+        extend_chain_with_params(chain, start_height, count, interval, &ChainParams::V1);
+    }
+
+    fn extend_chain_with_params(
+        chain: &mut Vec<BlockHeader>,
+        start_height: u64,
+        count: usize,
+        interval: u64,
+        params: &ChainParams,
+    ) {
+        for i in 0..count {
+            let height = start_height + i as u64;
+            let parent = chain.last().expect("parent");
+            let mut header = BlockHeader {
+                version: 1,
+                prev_block_hash: block_hash(&parent.to_bytes()).unwrap(),
+                merkle_root: [0x5a; 32],
+                timestamp: parent.timestamp + interval,
+                target: block_expected_target_with_params(chain, height, params).unwrap(),
+                nonce: 0,
+            };
+            while pow_check(&header.to_bytes(), header.target).is_err() {
+                header.nonce += 1;
+            }
+            chain.push(header);
+        }
+    }
+
+    fn genesis() -> BlockHeader {
+        BlockHeader {
+            version: 1,
+            prev_block_hash: [0u8; 32],
+            merkle_root: [0u8; 32],
+            timestamp: 1_000_000,
+            target: POW_LIMIT,
+            nonce: 0,
+        }
+    }
+
+    /// Ancestors for heights `0..WINDOW_SIZE - 1000` plus a 2,016-header batch
+    /// that crosses the first retarget boundary. Blocks come twice as fast as
+    /// targeted, so the boundary header must carry a tightened target.
+    fn retarget_fixture() -> (Vec<BlockHeader>, Vec<BlockHeader>, u64) {
+        let start_height = WINDOW_SIZE - 1_000;
+        let mut chain = vec![genesis()];
+        extend_chain(
+            &mut chain,
+            1,
+            start_height as usize - 1,
+            TARGET_BLOCK_INTERVAL / 2,
+        );
+        extend_chain(&mut chain, start_height, 2_016, TARGET_BLOCK_INTERVAL / 2);
+        let batch = chain.split_off(start_height as usize);
+        (chain, batch, start_height)
+    }
+
+    #[test]
+    fn accepts_batch_crossing_retarget_boundary() {
+        let (ancestors, batch, start_height) = retarget_fixture();
+        let hashes = validate_header_chain(&ancestors, &batch, start_height).expect("valid");
+        assert_eq!(hashes.len(), batch.len());
+        assert_eq!(hashes[5], block_hash(&batch[5].to_bytes()).unwrap());
+
+        let boundary = (WINDOW_SIZE - start_height) as usize;
+        assert_eq!(batch[boundary - 1].target, POW_LIMIT);
+        assert_ne!(batch[boundary].target, POW_LIMIT);
+        assert_eq!(batch[boundary].target, batch[boundary + 1].target);
+    }
+
+    #[test]
+    fn reports_first_bad_header_at_start_middle_and_end() {
+        let (ancestors, batch, start_height) = retarget_fixture();
+        let boundary = (WINDOW_SIZE - start_height) as usize;
+        let last = batch.len() - 1;
+
+        let mut bad = batch.clone();
+        bad[0].prev_block_hash = [0x11; 32];
+        let (idx, err) = validate_header_chain(&ancestors, &bad, start_height).unwrap_err();
+        assert_eq!((idx, err.code), (0, ErrorCode::BlockErrLinkageInvalid));
+
+        // The boundary header keeps the pre-retarget target.
+        let mut bad = batch.clone();
+        bad[boundary].target = batch[boundary - 1].target;
+        let (idx, err) = validate_header_chain(&ancestors, &bad, start_height).unwrap_err();
+        assert_eq!(
+            (idx, err.code),
+            (boundary, ErrorCode::BlockErrTargetInvalid)
+        );
+
+        let mut bad = batch.clone();
+        bad[last].timestamp = batch[last - 6].timestamp;
+        let (idx, err) = validate_header_chain(&ancestors, &bad, start_height).unwrap_err();
+        assert_eq!((idx, err.code), (last, ErrorCode::BlockErrTimestampOld));
+    }
+
+    #[test]
+    fn rejects_target_mismatch_and_missing_context() {
+        let mut chain = vec![genesis()];
+        extend_chain(&mut chain, 1, 3, TARGET_BLOCK_INTERVAL);
+        let batch = chain.split_off(1);
+
+        let mut bad = batch.clone();
+        let mut tight = [0u8; 32];
+        tight[31] = 1;
+        bad[1].target = tight;
+        let (idx, err) = validate_header_chain(&chain, &bad, 1).unwrap_err();
+        assert_eq!((idx, err.code), (1, ErrorCode::BlockErrTargetInvalid));
+
+        let (idx, err) = validate_header_chain(&[], &batch, 1).unwrap_err();
+        assert_eq!((idx, err.code), (0, ErrorCode::BlockErrLinkageInvalid));
+
+        // A retarget boundary with a truncated ancestor window cannot be judged.
+        let mut parent = genesis();
+        parent.timestamp += WINDOW_SIZE * TARGET_BLOCK_INTERVAL;
+        let mut header = parent.clone();
+        header.prev_block_hash = block_hash(&parent.to_bytes()).unwrap();
+        let (idx, err) =
+            validate_header_chain(std::slice::from_ref(&parent), &[header], WINDOW_SIZE)
+                .unwrap_err();
+        assert_eq!((idx, err.code), (0, ErrorCode::BlockErrParse));
+    }
+
+    #[test]
+    fn fast_devnet_params_retarget_at_window_boundary() {
+        let fast = ChainParams {
+            target_block_interval: 2,
+            window_size: 16,
+            coinbase_maturity: 4,
+        };
+        let mut chain = vec![genesis()];
+        extend_chain_with_params(&mut chain, 1, 18, 1, &fast);
+        let batch = chain.split_off(12);
+
+        validate_header_chain_with_params(&chain, &batch, 12, &fast).expect("valid");
+        assert_eq!(
+            batch[3].target, POW_LIMIT,
+            "height 15 keeps the parent target"
+        );
+        assert_ne!(batch[4].target, POW_LIMIT, "height 16 retargets");
+        assert_eq!(batch[4].target, batch[5].target);
+
+        // Under the v1 window height 16 is not a boundary.
+        let (idx, err) = validate_header_chain(&chain, &batch, 12).unwrap_err();
+        assert_eq!((idx, err.code), (4, ErrorCode::BlockErrTargetInvalid));
+    }
+}
@@ -0,0 +1,213 @@
+//! Decoding of hex-encoded request fields with length expectations.
+//!
+//! Every caller gets the same input convention: surrounding whitespace is
+//! trimmed, one `0x`/`0X` prefix is accepted, and odd-length input is
+//! rejected rather than zero-padded. Lengths are checked on the digit count
+//! before decoding, so an oversized field is refused without allocating it.
+//! Errors carry the field name and the actual length, and their `Display`
+//! text is stable for harnesses to match on.
+
+use core::fmt;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HexInputErrorKind {
+    /// `digits` hex digits after trimming and prefix removal.
+    OddLength {
+        digits: usize,
+    },
+    /// Byte offset of the first pair that is not two hex digits.
+    InvalidDigit {
+        offset: usize,
+    },
+    WrongLength {
+        expected: usize,
+        actual: usize,
+    },
+    TooLong {
+        max: usize,
+        actual: usize,
+    },
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HexInputError {
+    pub field: String,
+    pub kind: HexInputErrorKind,
+}
+
+impl fmt::Display for HexInputError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let field = &self.field;
+        match self.kind {
+            HexInputErrorKind::OddLength { digits } => {
+                write!(f, "{field}: odd-length hex ({digits} digits)")
+            }
+            HexInputErrorKind::InvalidDigit { offset } => {
+                write!(f, "{field}: invalid hex digit at byte {offset}")
+            }
+            HexInputErrorKind::WrongLength { expected, actual } => {
+                write!(f, "{field}: expected {expected} bytes, got {actual}")
+            }
+            HexInputErrorKind::TooLong { max, actual } => {
+                write!(f, "{field}: expected at most {max} bytes, got {actual}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for HexInputError {}
+
+/// Decodes `s` into exactly `expected_len` bytes.
+pub fn hex_decode_exact(
+    s: &str,
+    expected_len: usize,
+    field_name: &str,
+) -> Result<Vec<u8>, HexInputError> {
+    let digits = even_digits(s, field_name)?;
+    let actual = digits.len() / 2;
+    if actual != expected_len {
+        return Err(error(
+            field_name,
+            HexInputErrorKind::WrongLength {
+                expected: expected_len,
+                actual,
+            },
+        ));
+    }
+    decode_digits(digits, field_name)
+}
+
+/// Decodes `s` into at most `max_len` bytes; empty input decodes to nothing.
+pub fn hex_decode_max(s: &str, max_len: usize, field_name: &str) -> Result<Vec<u8>, HexInputError> {
+    let digits = even_digits(s, field_name)?;
+    let actual = digits.len() / 2;
+    if actual > max_len {
+        return Err(error(
+            field_name,
+            HexInputErrorKind::TooLong {
+                max: max_len,
+                actual,
+            },
+        ));
+    }
+    decode_digits(digits, field_name)
+}
+
+/// [`hex_decode_exact`] for the 32-byte hashes, ids and targets.
+pub fn hex_decode_32(s: &str, field_name: &str) -> Result<[u8; 32], HexInputError> {
+    let mut out = [0u8; 32];
+    out.copy_from_slice(&hex_decode_exact(s, 32, field_name)?);
+    Ok(out)
+}
+
+fn even_digits<'a>(s: &'a str, field_name: &str) -> Result<&'a str, HexInputError> {
+    let trimmed = s.trim();
+    let digits = trimmed
+        .strip_prefix("0x")
+        .or_else(|| trimmed.strip_prefix("0X"))
+        .unwrap_or(trimmed);
+    if !digits.len().is_multiple_of(2) {
+        return Err(error(
+            field_name,
+            HexInputErrorKind::OddLength {
+                digits: digits.len(),
+            },
+        ));
+    }
+    Ok(digits)
+}
+
+fn decode_digits(digits: &str, field_name: &str) -> Result<Vec<u8>, HexInputError> {
+    hex::decode(digits).map_err(|err| {
+        let index = match err {
+            hex::FromHexError::InvalidHexCharacter { index, .. } => index,
+            _ => 0,
+        };
+        error(
+            field_name,
+            HexInputErrorKind::InvalidDigit { offset: index / 2 },
+        )
+    })
+}
+
+fn error(field_name: &str, kind: HexInputErrorKind) -> HexInputError {
+    HexInputError {
+        field: field_name.to_string(),
+        kind,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn prefix_and_whitespace_are_accepted_everywhere() {
+        for input in ["00ff", " 0x00ff\n", "0X00FF"] {
+            assert_eq!(hex_decode_exact(input, 2, "f").unwrap(), vec![0x00, 0xff]);
+            assert_eq!(hex_decode_max(input, 2, "f").unwrap(), vec![0x00, 0xff]);
+        }
+        assert_eq!(hex_decode_max("0x", 0, "f").unwrap(), Vec::<u8>::new());
+    }
+
+    #[test]
+    fn odd_length_is_rejected_not_padded() {
+        for result in [
+            hex_decode_exact("0xabc", 2, "f").map(|_| ()),
+            hex_decode_max("abc", 32, "f").map(|_| ()),
+            hex_decode_32(&"1".repeat(63), "f").map(|_| ()),
+        ] {
+            assert!(matches!(
+                result.unwrap_err().kind,
+                HexInputErrorKind::OddLength { .. }
+            ));
+        }
+    }
+
+    #[test]
+    fn length_is_checked_before_digits() {
+        let err = hex_decode_exact(&"zz".repeat(31), 32, "f").unwrap_err();
+        assert_eq!(
+            err.kind,
+            HexInputErrorKind::WrongLength {
+                expected: 32,
+                actual: 31
+            }
+        );
+        let err = hex_decode_max(&"zz".repeat(3), 2, "f").unwrap_err();
+        assert_eq!(err.kind, HexInputErrorKind::TooLong { max: 2, actual: 3 });
+    }
+
+    #[test]
+    fn error_messages_are_stable_per_field() {
+        let cases = [
+            (
+                hex_decode_32(&"11".repeat(31), "utxo.txid").unwrap_err(),
+                "utxo.txid: expected 32 bytes, got 31",
+            ),
+            (
+                hex_decode_32(&"1".repeat(65), "chain_id").unwrap_err(),
+                "chain_id: odd-length hex (65 digits)",
+            ),
+            (
+                hex_decode_32(&format!("{}zz", "00".repeat(31)), "expected_target").unwrap_err(),
+                "expected_target: invalid hex digit at byte 31",
+            ),
+            (
+                hex_decode_32("", "wtxid").unwrap_err(),
+                "wtxid: expected 32 bytes, got 0",
+            ),
+            (
+                hex_decode_exact(&"00".repeat(117), 116, "header_hex").unwrap_err(),
+                "header_hex: expected 116 bytes, got 117",
+            ),
+            (
+                hex_decode_max("000000", 2, "covenant_data").unwrap_err(),
+                "covenant_data: expected at most 2 bytes, got 3",
+            ),
+        ];
+        for (err, want) in cases {
+            assert_eq!(err.to_string(), want);
+        }
+    }
+}
@@ -1,6 +1,6 @@
 use crate::constants::{
-    LOCK_MODE_HEIGHT, LOCK_MODE_TIMESTAMP, MAX_HTLC_COVENANT_DATA, MAX_HTLC_PREIMAGE_BYTES,
-    MIN_HTLC_PREIMAGE_BYTES, SUITE_ID_SENTINEL,
+    COV_TYPE_HTLC, LOCK_MODE_HEIGHT, LOCK_MODE_TIMESTAMP, MAX_HTLC_COVENANT_DATA,
+    MAX_HTLC_PREIMAGE_BYTES, MIN_HTLC_PREIMAGE_BYTES, SUITE_ID_SENTINEL,
 };
 use crate::error::{ErrorCode, TxError};
 use crate::hash::sha3_256;
@@ -8,8 +8,9 @@ use crate::sig_queue::{queue_or_verify_signature, SigCheckQueue};
 use crate::sighash::{sighash_v1_digest_with_cache, SighashV1PrehashCache};
 use crate::spend_verify::extract_crypto_sig_and_sighash;
 use crate::suite_registry::{DefaultRotationProvider, RotationProvider, SuiteRegistry};
-use crate::tx::{Tx, WitnessItem};
+use crate::tx::{Tx, TxOutput, WitnessItem};
 use crate::utxo_basic::UtxoEntry;
+use crate::vault::witness_slots;
 
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct HtlcCovenant {
@@ -20,6 +21,19 @@ pub struct HtlcCovenant {
     pub refund_key_id: [u8; 32],
 }
 
+impl HtlcCovenant {
+    /// Canonical covenant_data encoding; inverse of [`parse_htlc_covenant_data`].
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(MAX_HTLC_COVENANT_DATA as usize);
+        out.extend_from_slice(&self.hash);
+        out.push(self.lock_mode);
+        out.extend_from_slice(&self.lock_value.to_le_bytes());
+        out.extend_from_slice(&self.claim_key_id);
+        out.extend_from_slice(&self.refund_key_id);
+        out
+    }
+}
+
 /// Per-input and block context required for CORE_HTLC spend validation.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct HtlcSpendContext {
@@ -163,6 +177,47 @@ pub(crate) fn validate_htlc_spend_q(
     )
 }
 
+/// Preimages revealed by the CORE_HTLC claims in `tx`, as
+/// `(input_index, preimage)`.
+///
+/// `prevouts[i]` must be the output spent by input `i`; it is needed to
+/// line inputs up with their witness slots. Only claim selectors whose
+/// preimage hashes to the covenant's `hash` are reported, so a swap
+/// counterparty can act on each entry without re-checking it. Signatures
+/// are not verified. When `prevouts` does not match `tx.inputs`, or a
+/// prevout has no witness slot layout, the scan stops at that input.
+pub fn htlc_revealed_preimages(tx: &Tx, prevouts: &[TxOutput]) -> Vec<(u32, Vec<u8>)> {
+    let mut revealed = Vec::new();
+    if prevouts.len() != tx.inputs.len() {
+        return revealed;
+    }
+    let mut witness_cursor: usize = 0;
+    for (input_index, prevout) in prevouts.iter().enumerate() {
+        let Ok(slots) = witness_slots(prevout.covenant_type, &prevout.covenant_data) else {
+            break;
+        };
+        let Some(path_item) = tx.witness.get(witness_cursor) else {
+            break;
+        };
+        witness_cursor += slots;
+        if prevout.covenant_type != COV_TYPE_HTLC {
+            continue;
+        }
+        let (Ok(cov), Ok(selector)) = (
+            parse_htlc_covenant_data(&prevout.covenant_data),
+            parse_htlc_selector(path_item),
+        ) else {
+            continue;
+        };
+        if selector.path_id == 0x00
+            && validate_htlc_claim_path(&cov, selector.payload, selector.key_id).is_ok()
+        {
+            revealed.push((input_index as u32, selector.payload[3..].to_vec()));
+        }
+    }
+    revealed
+}
+
 fn expected_htlc_spend_key_id(
     cov: &HtlcCovenant,
     selector: HtlcSelector<'_>,
@@ -313,9 +368,10 @@ fn validate_htlc_signature_precheck(
         )
     })?;
 
-    if sig_item.pubkey.len() as u64 != params.pubkey_len
-        || sig_item.signature.len() as u64 != params.sig_len + 1
-    {
+    if !params.has_canonical_witness_shape(
+        sig_item.pubkey.len() as u64,
+        sig_item.signature.len() as u64,
+    ) {
         return Err(TxError::new(
             ErrorCode::TxErrSigNoncanonical,
             "non-canonical witness item lengths",
@@ -532,4 +588,84 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn htlc_revealed_preimages_reports_matching_claims_only() {
+        let preimage = b"cross-chain-swap-secret-0001".to_vec();
+        let claim_key_id = [0x11; 32];
+        let refund_key_id = [0x22; 32];
+        let htlc_out = TxOutput {
+            value: 1000,
+            covenant_type: COV_TYPE_HTLC,
+            covenant_data: make_htlc_covenant_data(
+                sha3_256(&preimage),
+                LOCK_MODE_HEIGHT,
+                1,
+                claim_key_id,
+                refund_key_id,
+            ),
+        };
+        let p2pk_out = TxOutput {
+            value: 500,
+            covenant_type: crate::constants::COV_TYPE_P2PK,
+            covenant_data: vec![0u8; 33],
+        };
+        let selector = |key_id: [u8; 32], payload: Vec<u8>| WitnessItem {
+            suite_id: SUITE_ID_SENTINEL,
+            pubkey: key_id.to_vec(),
+            signature: payload,
+        };
+        let claim_payload = |pre: &[u8]| {
+            let mut payload = vec![0x00];
+            payload.extend_from_slice(&(pre.len() as u16).to_le_bytes());
+            payload.extend_from_slice(pre);
+            payload
+        };
+        let sig = WitnessItem {
+            suite_id: SUITE_ID_ML_DSA_87,
+            pubkey: vec![0u8; 4],
+            signature: vec![0u8; 4],
+        };
+
+        let mut tx = dummy_tx();
+        tx.inputs = (0..4u8)
+            .map(|i| TxInput {
+                prev_txid: [i; 32],
+                prev_vout: 0,
+                script_sig: vec![],
+                sequence: 0,
+            })
+            .collect();
+        // p2pk (1 slot), claim (2), refund (2), claim with a wrong preimage (2).
+        tx.witness = vec![
+            sig.clone(),
+            selector(claim_key_id, claim_payload(&preimage)),
+            sig.clone(),
+            selector(refund_key_id, vec![0x01]),
+            sig.clone(),
+            selector(claim_key_id, claim_payload(b"not-the-swap-secret-0001")),
+            sig,
+        ];
+        let prevouts = vec![
+            p2pk_out.clone(),
+            htlc_out.clone(),
+            htlc_out.clone(),
+            htlc_out.clone(),
+        ];
+        assert_eq!(
+            htlc_revealed_preimages(&tx, &prevouts),
+            vec![(1, preimage.clone())]
+        );
+
+        // Prevouts that do not line up with the inputs yield nothing.
+        assert!(htlc_revealed_preimages(&tx, &prevouts[..3]).is_empty());
+        // A misaligned witness (p2pk prevout read as HTLC) finds no claim.
+        let shifted = vec![
+            htlc_out.clone(),
+            p2pk_out.clone(),
+            htlc_out.clone(),
+            htlc_out,
+        ];
+        assert!(htlc_revealed_preimages(&tx, &shifted).is_empty());
+    }
 }
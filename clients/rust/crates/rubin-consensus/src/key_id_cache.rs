@@ -0,0 +1,195 @@
+use crate::hash::sha3_256;
+use std::collections::{BTreeMap, HashMap};
+
+const KEY_ID_CACHE_PREFIX_BYTES: usize = 8;
+
+type KeyIdCacheSlot = ([u8; KEY_ID_CACHE_PREFIX_BYTES], usize);
+
+#[derive(Debug)]
+struct KeyIdCacheEntry {
+    pubkey: Vec<u8>,
+    key_id: [u8; 32],
+    /// Recency stamp; the key of this slot in `KeyIdCache::recency`.
+    last_used: u64,
+}
+
+/// KeyIdCache memoizes `SHA3-256(pubkey)` key_id derivations across the
+/// transactions of one block, so inputs spending many outputs of the same key
+/// hash the 2,592-byte ML-DSA-87 pubkey once.
+///
+/// Slots are addressed by a cheap 8-byte prefix plus length, but a hit is only
+/// served after a full pubkey comparison; on mismatch the key_id is recomputed
+/// and the slot is left untouched. Correctness therefore never depends on the
+/// cache contents. Once `capacity` slots are held, admitting a new pubkey
+/// evicts the least recently used one.
+#[derive(Debug)]
+pub struct KeyIdCache {
+    entries: HashMap<KeyIdCacheSlot, KeyIdCacheEntry>,
+    /// Slots by `last_used`, oldest first.
+    recency: BTreeMap<u64, KeyIdCacheSlot>,
+    capacity: usize,
+    tick: u64,
+    hits: u64,
+    misses: u64,
+    evictions: u64,
+}
+
+impl KeyIdCache {
+    pub fn new(capacity: usize) -> Self {
+        let capacity = capacity.max(1);
+        Self {
+            entries: HashMap::new(),
+            recency: BTreeMap::new(),
+            capacity,
+            tick: 0,
+            hits: 0,
+            misses: 0,
+            evictions: 0,
+        }
+    }
+
+    pub fn key_id(&mut self, pubkey: &[u8]) -> [u8; 32] {
+        let slot = key_id_cache_slot(pubkey);
+        self.tick += 1;
+        if let Some(entry) = self.entries.get_mut(&slot) {
+            if entry.pubkey.as_slice() == pubkey {
+                self.recency.remove(&entry.last_used);
+                self.recency.insert(self.tick, slot);
+                entry.last_used = self.tick;
+                self.hits += 1;
+                return entry.key_id;
+            }
+            self.misses += 1;
+            return sha3_256(pubkey);
+        }
+        self.misses += 1;
+        let key_id = sha3_256(pubkey);
+        if self.entries.len() == self.capacity {
+            if let Some((_, oldest)) = self.recency.pop_first() {
+                self.entries.remove(&oldest);
+                self.evictions += 1;
+            }
+        }
+        self.recency.insert(self.tick, slot);
+        self.entries.insert(
+            slot,
+            KeyIdCacheEntry {
+                pubkey: pubkey.to_vec(),
+                key_id,
+                last_used: self.tick,
+            },
+        );
+        key_id
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn hits(&self) -> u64 {
+        self.hits
+    }
+
+    pub fn misses(&self) -> u64 {
+        self.misses
+    }
+
+    pub fn evictions(&self) -> u64 {
+        self.evictions
+    }
+}
+
+fn key_id_cache_slot(pubkey: &[u8]) -> KeyIdCacheSlot {
+    let mut prefix = [0u8; KEY_ID_CACHE_PREFIX_BYTES];
+    let n = pubkey.len().min(KEY_ID_CACHE_PREFIX_BYTES);
+    prefix[..n].copy_from_slice(&pubkey[..n]);
+    (prefix, pubkey.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::ML_DSA_87_PUBKEY_BYTES;
+
+    fn pubkey(fill: u8) -> Vec<u8> {
+        vec![fill; ML_DSA_87_PUBKEY_BYTES as usize]
+    }
+
+    #[test]
+    fn repeated_pubkey_hits_cache() {
+        let mut cache = KeyIdCache::new(8);
+        let pk = pubkey(0x11);
+        assert_eq!(cache.key_id(&pk), sha3_256(&pk));
+        assert_eq!(cache.key_id(&pk), sha3_256(&pk));
+        assert_eq!(cache.hits(), 1);
+        assert_eq!(cache.misses(), 1);
+        assert_eq!(cache.len(), 1);
+    }
+
+    #[test]
+    fn shared_prefix_and_length_does_not_alias() {
+        let mut cache = KeyIdCache::new(8);
+        let first = pubkey(0x22);
+        let mut second = first.clone();
+        *second.last_mut().unwrap() ^= 0x01;
+
+        assert_eq!(cache.key_id(&first), sha3_256(&first));
+        assert_eq!(cache.key_id(&second), sha3_256(&second));
+        assert_eq!(cache.key_id(&second), sha3_256(&second));
+        assert_eq!(cache.hits(), 0);
+        assert_eq!(cache.len(), 1);
+    }
+
+    #[test]
+    fn poisoned_entry_is_caught_by_full_comparison() {
+        let mut cache = KeyIdCache::new(8);
+        let pk = pubkey(0x33);
+        let honest = cache.key_id(&pk);
+
+        let mut forged = pk.clone();
+        forged[100] ^= 0xff;
+        let slot = key_id_cache_slot(&pk);
+        let entry = cache.entries.get_mut(&slot).expect("cached slot");
+        entry.pubkey = forged;
+        entry.key_id = [0xee; 32];
+
+        assert_eq!(cache.key_id(&pk), honest);
+        assert_eq!(cache.hits(), 0);
+    }
+
+    #[test]
+    fn full_cache_evicts_least_recently_used() {
+        let mut cache = KeyIdCache::new(2);
+        let (oldest, recent, newcomer) = (pubkey(0), pubkey(1), pubkey(2));
+        cache.key_id(&oldest);
+        cache.key_id(&recent);
+        // Touching `oldest` leaves `recent` as the least recently used.
+        cache.key_id(&oldest);
+        assert_eq!(cache.hits(), 1);
+
+        assert_eq!(cache.key_id(&newcomer), sha3_256(&newcomer));
+        assert_eq!(cache.len(), 2);
+        assert_eq!(cache.evictions(), 1);
+        assert!(!cache.entries.contains_key(&key_id_cache_slot(&recent)));
+
+        cache.key_id(&oldest);
+        cache.key_id(&newcomer);
+        assert_eq!(cache.hits(), 3);
+        assert_eq!(cache.key_id(&recent), sha3_256(&recent));
+        assert_eq!(cache.hits(), 3);
+        assert_eq!(cache.evictions(), 2);
+        assert_eq!(cache.len(), cache.recency.len());
+    }
+
+    #[test]
+    fn short_and_empty_pubkeys_accepted() {
+        let mut cache = KeyIdCache::new(0);
+        assert_eq!(cache.key_id(&[]), sha3_256(&[]));
+        assert_eq!(cache.key_id(&[1, 2, 3]), sha3_256(&[1, 2, 3]));
+        assert_eq!(cache.capacity, 1);
+    }
+}
@@ -1,20 +1,26 @@
 pub mod block;
 pub mod block_basic;
+pub mod chain_params;
 mod compact_relay;
 mod compactsize;
 pub mod connect_block_inmem;
 pub mod constants;
 pub mod core_ext;
 mod covenant_genesis;
+pub mod da;
 pub mod da_verify_parallel;
 pub mod error;
 pub mod featurebits;
 pub mod flagday;
 mod fork_choice;
 mod hash;
+mod header_chain;
+mod hex_input;
 mod htlc;
+mod key_id_cache;
 mod live_binding_policy;
 pub mod merkle;
+mod outpoint_map;
 pub mod pow;
 pub mod precompute;
 mod sig_cache;
@@ -33,6 +39,7 @@ mod tx_helpers;
 pub mod tx_validate_worker;
 pub mod txcontext;
 mod utxo_basic;
+pub mod utxo_compact;
 pub mod utxo_snapshot;
 mod vault;
 mod verify_sig_openssl;
@@ -41,22 +48,26 @@ pub mod worker_pool;
 
 pub use block::{block_hash, parse_block_header_bytes, BlockHeader, BLOCK_HEADER_BYTES};
 pub use block_basic::{
-    parse_block_bytes, tx_weight_and_stats_at_height, tx_weight_and_stats_public,
+    check_block_timestamp, check_timestamp_bounds, median_time_past, parse_block_bytes,
+    tx_anchor_bytes, tx_weight_and_stats_at_height, tx_weight_and_stats_public,
     validate_block_basic, validate_block_basic_at_height,
     validate_block_basic_with_context_and_fees_at_height,
     validate_block_basic_with_context_and_fees_at_height_and_rotation,
     validate_block_basic_with_context_at_height,
-    validate_block_basic_with_context_at_height_and_rotation, BlockBasicSummary, ParsedBlock,
+    validate_block_basic_with_context_at_height_and_rotation, validate_coinbase_tx,
+    BlockBasicSummary, BlockStreamParser, ParsedBlock, ParsedItem,
 };
+pub use chain_params::ChainParams;
 pub use compact_relay::compact_shortid;
 pub use compactsize::encode_compact_size;
-pub use compactsize::read_compact_size_bytes;
+pub use compactsize::{compact_size_decode_bounded, read_compact_size_bytes};
 pub use connect_block_inmem::{
-    connect_block_basic_in_memory_at_height,
+    canonical_outpoint_key, canonical_utxo_entry_bytes, connect_block_basic_in_memory_at_height,
     connect_block_basic_in_memory_at_height_and_core_ext_deployments_with_suite_context,
-    connect_block_parallel_sig_verify,
+    connect_block_basic_in_memory_at_height_with_chain_params, connect_block_parallel_sig_verify,
     connect_block_parallel_sig_verify_and_core_ext_deployments_with_suite_context,
-    ConnectBlockBasicSummary, InMemoryChainState,
+    connect_block_parallel_sig_verify_with_chain_params, utxo_set_hash, ConnectBlockBasicSummary,
+    InMemoryChainState,
 };
 pub use core_ext::{
     core_ext_openssl_digest32_binding_descriptor_bytes, core_ext_profile_set_anchor_v1,
@@ -71,11 +82,14 @@ pub use core_ext::{
     CORE_EXT_BINDING_NAME_VERIFY_SIG_EXT_OPENSSL_DIGEST32_V1,
 };
 pub use covenant_genesis::validate_tx_covenants_genesis;
+pub use da::{
+    check_da_chunk_count, classify_tx, classify_tx_with_max_chunk_count, tx_da_bytes, DaTxKind,
+};
 pub use da_verify_parallel::{
     collect_da_chunk_hash_tasks, collect_da_payload_commit_tasks, verify_da_chunk_hashes_parallel,
     verify_da_payload_commits_parallel, DaChunkHashTask, DaPayloadCommitTask,
 };
-pub use error::{ErrorCode, TxError};
+pub use error::{codes, ErrorCode, TxError};
 pub use featurebits::{
     featurebit_state_at_height_from_window_counts, FeatureBitDeployment, FeatureBitEval,
     FeatureBitState,
@@ -84,17 +98,31 @@ pub use flagday::{flagday_active_at_height, FlagDayDeployment};
 pub use fork_choice::{chain_work_from_targets, work_from_target};
 #[allow(deprecated)]
 pub use fork_choice::{fork_chainwork_from_targets, fork_work_from_target};
-pub use htlc::{parse_htlc_covenant_data, validate_htlc_spend, HtlcCovenant, HtlcSpendContext};
+pub use header_chain::{validate_header_chain, validate_header_chain_with_params};
+pub use hex_input::{
+    hex_decode_32, hex_decode_exact, hex_decode_max, HexInputError, HexInputErrorKind,
+};
+pub use htlc::{
+    htlc_revealed_preimages, parse_htlc_covenant_data, validate_htlc_spend, HtlcCovenant,
+    HtlcSpendContext,
+};
+pub use key_id_cache::KeyIdCache;
 pub use merkle::merkle_root_txids;
-pub use pow::{pow_check, retarget_v1, retarget_v1_clamped};
-pub use precompute::{precompute_tx_contexts, PrecomputedTxContext};
+pub use outpoint_map::{OutpointBuildHasher, OutpointHasher, OutpointMap};
+pub use pow::{
+    block_expected_target, block_expected_target_with_params, pow_check, retarget_v1,
+    retarget_v1_clamped, retarget_v1_clamped_with_params,
+};
+pub use precompute::{
+    precompute_tx_contexts, precompute_tx_contexts_with_chain_params, PrecomputedTxContext,
+};
 pub use sig_cache::SigCache;
 pub use sighash::{
     is_valid_sighash_type, sighash_v1_digest, sighash_v1_digest_with_cache,
     sighash_v1_digest_with_type, SighashV1PrehashCache,
 };
 pub use stealth::{parse_stealth_covenant_data, validate_stealth_spend, StealthCovenant};
-pub use subsidy::block_subsidy;
+pub use subsidy::{block_subsidy, cumulative_subsidy};
 pub use suite_registry::{
     canonical_rotation_network_name, canonical_rotation_network_name_normalized,
     is_v1_production_rotation_network, is_v1_production_rotation_network_normalized,
@@ -113,7 +141,8 @@ pub use tx_dep_graph::{
 };
 pub use tx_helpers::{marshal_tx, p2pk_covenant_data_for_pubkey, sign_transaction, DigestSigner};
 pub use tx_validate_worker::{
-    first_tx_error, run_tx_validation_workers, validate_tx_local, TxValidationResult,
+    first_tx_error, run_tx_validation_workers, validate_input_authorization,
+    validate_input_authorization_with_suite_context, validate_tx_local, TxValidationResult,
 };
 pub use txcontext::{
     TxContextBase, TxContextContinuing, TxOutputView, Uint128, TXCONTEXT_MAX_CONTINUING_OUTPUTS,
@@ -122,15 +151,24 @@ pub use utxo_basic::{
     apply_non_coinbase_tx_basic, apply_non_coinbase_tx_basic_update,
     apply_non_coinbase_tx_basic_update_with_mtp,
     apply_non_coinbase_tx_basic_update_with_mtp_and_core_ext_profiles_and_suite_context,
+    apply_non_coinbase_tx_basic_update_with_mtp_and_core_ext_profiles_and_suite_context_and_maturity,
     apply_non_coinbase_tx_basic_update_with_mtp_and_core_ext_profiles_and_suite_context_deferred_sigchecks,
+    apply_non_coinbase_tx_basic_update_with_mtp_and_core_ext_profiles_and_suite_context_observed,
     apply_non_coinbase_tx_basic_with_mtp, Outpoint, UtxoApplySummary, UtxoEntry,
+    ValidationObserver,
 };
+pub use utxo_compact::{CompactUtxo, COMPACT_UTXO_INLINE_COVENANT_BYTES};
 pub use utxo_snapshot::{utxo_snapshot_shard, UtxoSnapshot};
 pub use vault::{
-    output_descriptor_bytes, parse_multisig_covenant_data, parse_vault_covenant_data,
-    witness_slots, MultisigCovenant, VaultCovenant,
+    output_descriptor_bytes, output_descriptor_hash, parse_multisig_covenant_data,
+    parse_vault_covenant_data, witness_slots, MultisigCovenant, VaultCovenant,
+};
+pub use verify_sig_openssl::{
+    crypto_provider_info, verify_sig, verify_sig_with_registry, CryptoProviderInfo, Mldsa87Keypair,
 };
-pub use verify_sig_openssl::{verify_sig, verify_sig_with_registry, Mldsa87Keypair};
+
+/// Version of this crate, for build-info reporting by embedders.
+pub const CRATE_VERSION: &str = env!("CARGO_PKG_VERSION");
 pub use worker_pool::{
     collect_values, first_error, run_worker_pool, WorkerCancellationToken, WorkerPool,
     WorkerPoolError, WorkerPoolRunError, WorkerResult,
@@ -12,53 +12,66 @@ pub fn witness_merkle_root_wtxids(wtxids: &[[u8; 32]]) -> Result<[u8; 32], TxErr
             "merkle: empty wtxid list",
         ));
     }
-    let mut ids = wtxids.to_vec();
+    let mut level = merkle_leaves(wtxids, 0x02);
     // Break self-reference: coinbase witness commitment tree uses a zero id for index 0.
-    ids[0] = [0u8; 32];
-    merkle_root_tagged(&ids, 0x02, 0x03)
+    level[0] = merkle_leaf(&[0u8; 32], 0x02);
+    Ok(merkle_reduce(level, 0x03))
 }
 
 pub fn witness_commitment_hash(witness_root: [u8; 32]) -> [u8; 32] {
-    let mut preimage = Vec::with_capacity("RUBIN-WITNESS/".len() + 32);
-    preimage.extend_from_slice(b"RUBIN-WITNESS/");
-    preimage.extend_from_slice(&witness_root);
+    const PREFIX: &[u8; 14] = b"RUBIN-WITNESS/";
+    let mut preimage = [0u8; PREFIX.len() + 32];
+    preimage[..PREFIX.len()].copy_from_slice(PREFIX);
+    preimage[PREFIX.len()..].copy_from_slice(&witness_root);
     sha3_256(&preimage)
 }
 
+// An odd node is promoted to the next level unchanged rather than paired with
+// itself (CANONICAL §10.4.1), so a duplicated trailing id yields a different
+// root and there is no CVE-2012-2459-style mutation to defend against.
 fn merkle_root_tagged(ids: &[[u8; 32]], leaf_tag: u8, node_tag: u8) -> Result<[u8; 32], TxError> {
     if ids.is_empty() {
         return Err(TxError::new(ErrorCode::TxErrParse, "merkle: empty id list"));
     }
+    Ok(merkle_reduce(merkle_leaves(ids, leaf_tag), node_tag))
+}
 
-    let mut level: Vec<[u8; 32]> = Vec::with_capacity(ids.len());
+#[inline]
+fn merkle_leaf(id: &[u8; 32], leaf_tag: u8) -> [u8; 32] {
     let mut leaf_preimage = [0u8; 1 + 32];
     leaf_preimage[0] = leaf_tag;
-    for id in ids {
-        leaf_preimage[1..].copy_from_slice(id);
-        level.push(sha3_256(&leaf_preimage));
-    }
+    leaf_preimage[1..].copy_from_slice(id);
+    sha3_256(&leaf_preimage)
+}
 
+fn merkle_leaves(ids: &[[u8; 32]], leaf_tag: u8) -> Vec<[u8; 32]> {
+    ids.iter().map(|id| merkle_leaf(id, leaf_tag)).collect()
+}
+
+/// Folds `level` (non-empty) to its root in place: each parent is written
+/// over a slot its children have already been read from.
+fn merkle_reduce(mut level: Vec<[u8; 32]>, node_tag: u8) -> [u8; 32] {
     let mut node_preimage = [0u8; 1 + 32 + 32];
     node_preimage[0] = node_tag;
-    while level.len() > 1 {
-        let mut next: Vec<[u8; 32]> = Vec::with_capacity(level.len().div_ceil(2));
+    let mut len = level.len();
+    while len > 1 {
+        let mut next = 0usize;
         let mut i = 0usize;
-        while i < level.len() {
-            if i == level.len() - 1 {
+        while i < len {
+            if i == len - 1 {
                 // Odd promotion rule: carry forward unchanged.
-                next.push(level[i]);
-                i += 1;
-                continue;
+                level[next] = level[i];
+            } else {
+                node_preimage[1..33].copy_from_slice(&level[i]);
+                node_preimage[33..].copy_from_slice(&level[i + 1]);
+                level[next] = sha3_256(&node_preimage);
             }
-            node_preimage[1..33].copy_from_slice(&level[i]);
-            node_preimage[33..].copy_from_slice(&level[i + 1]);
-            next.push(sha3_256(&node_preimage));
+            next += 1;
             i += 2;
         }
-        level = next;
+        len = next;
     }
-
-    Ok(level[0])
+    level[0]
 }
 
 // ---------------------------------------------------------------------------
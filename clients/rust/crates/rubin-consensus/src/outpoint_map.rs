@@ -0,0 +1,131 @@
+use std::collections::hash_map::RandomState;
+use std::collections::HashMap;
+use std::hash::{BuildHasher, Hasher};
+
+use crate::utxo_basic::Outpoint;
+
+/// HashMap keyed by `Outpoint` with a hasher built for UTXO sets.
+///
+/// The default SipHash runs over the full 36-byte outpoint on every lookup.
+/// A txid is already uniform SHA3-256 output, so `OutpointHasher` keeps
+/// eight bytes of it, mixes in `vout`, and finishes with one keyed
+/// multiply.
+///
+/// Threat model: an entry only lands in a UTXO set once a PoW-committed
+/// block creates it, and its txid is a SHA3 digest. Two outpoints can share
+/// a hash outright only if their txids agree on a 64-bit prefix. Without the
+/// per-map seed, an attacker also cannot grind txids into one bucket. Maps
+/// whose keys come straight off the wire with no validation, such as
+/// duplicate-input detection or mempool spender indexes, can repeat any
+/// prefix for free. Those maps keep the default `RandomState`.
+pub type OutpointMap<V> = HashMap<Outpoint, V, OutpointBuildHasher>;
+
+const OUTPOINT_VOUT_MIX: u64 = 0x9e37_79b9_7f4a_7c15;
+
+/// Per-map seeded builder for `OutpointHasher`.
+#[derive(Clone, Debug)]
+pub struct OutpointBuildHasher {
+    seed: u64,
+    multiplier: u64,
+}
+
+impl Default for OutpointBuildHasher {
+    fn default() -> Self {
+        let keys = RandomState::new();
+        Self {
+            seed: keys.hash_one(0u64),
+            multiplier: keys.hash_one(1u64) | 1,
+        }
+    }
+}
+
+impl BuildHasher for OutpointBuildHasher {
+    type Hasher = OutpointHasher;
+
+    fn build_hasher(&self) -> OutpointHasher {
+        OutpointHasher {
+            state: self.seed,
+            multiplier: self.multiplier,
+        }
+    }
+}
+
+/// Hasher for `Outpoint` keys only: every `write` contributes just its
+/// first eight bytes, which for an outpoint is the txid prefix.
+#[derive(Clone, Debug)]
+pub struct OutpointHasher {
+    state: u64,
+    multiplier: u64,
+}
+
+impl Hasher for OutpointHasher {
+    fn write(&mut self, bytes: &[u8]) {
+        let mut word = [0u8; 8];
+        let len = bytes.len().min(word.len());
+        word[..len].copy_from_slice(&bytes[..len]);
+        self.state ^= u64::from_le_bytes(word);
+    }
+
+    fn write_u32(&mut self, i: u32) {
+        self.state ^= u64::from(i).wrapping_mul(OUTPOINT_VOUT_MIX);
+    }
+
+    fn finish(&self) -> u64 {
+        let full = u128::from(self.state) * u128::from(self.multiplier);
+        (full as u64) ^ ((full >> 64) as u64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hash::sha3_256;
+
+    fn outpoint(i: u32, vout: u32) -> Outpoint {
+        Outpoint {
+            txid: sha3_256(&i.to_le_bytes()),
+            vout,
+        }
+    }
+
+    #[test]
+    fn outpoint_map_matches_default_hashmap() {
+        let mut fast: OutpointMap<u64> = OutpointMap::default();
+        let mut reference: HashMap<Outpoint, u64> = HashMap::new();
+        for i in 0..2_000u32 {
+            for vout in 0..3 {
+                let key = outpoint(i, vout);
+                let value = u64::from(i) * 3 + u64::from(vout);
+                assert_eq!(
+                    fast.insert(key.clone(), value),
+                    reference.insert(key, value)
+                );
+            }
+        }
+        for i in (0..2_000u32).step_by(3) {
+            let key = outpoint(i, 1);
+            assert_eq!(fast.remove(&key), reference.remove(&key));
+        }
+        // Keys sharing the hashed txid prefix are still distinct entries.
+        let mut twin = outpoint(7, 0);
+        twin.txid[31] ^= 0xff;
+        assert_eq!(fast.get(&twin), None);
+        assert_eq!(fast.insert(twin.clone(), 1), reference.insert(twin, 1));
+
+        assert_eq!(fast.len(), reference.len());
+        for (key, value) in &reference {
+            assert_eq!(fast.get(key), Some(value));
+        }
+        assert_eq!(fast.get(&outpoint(9_999, 0)), None);
+    }
+
+    #[test]
+    fn outpoint_hasher_is_seeded_per_map() {
+        let key = outpoint(1, 0);
+        let a = OutpointBuildHasher::default();
+        let b = OutpointBuildHasher::default();
+        assert_eq!(a.hash_one(&key), a.clone().hash_one(&key));
+        assert_ne!(a.hash_one(&key), b.hash_one(&key));
+        assert_ne!(a.hash_one(&key), a.hash_one(outpoint(1, 1)));
+    }
+}
@@ -1,8 +1,7 @@
-use crate::constants::{
-    MAX_TIMESTAMP_STEP_PER_BLOCK, POW_LIMIT, TARGET_BLOCK_INTERVAL, WINDOW_SIZE,
-};
+use crate::chain_params::ChainParams;
+use crate::constants::POW_LIMIT;
 use crate::error::{ErrorCode, TxError};
-use crate::{block_hash, BLOCK_HEADER_BYTES};
+use crate::{block_hash, BlockHeader, BLOCK_HEADER_BYTES};
 use num_bigint::BigUint;
 use num_traits::{One, Zero};
 
@@ -16,7 +15,7 @@ pub fn retarget_v1(
     } else {
         timestamp_last - timestamp_first
     };
-    retarget_v1_with_actual(target_old, t_actual)
+    retarget_v1_with_actual(target_old, t_actual, &ChainParams::V1)
 }
 
 // Retarget using clamped per-block timestamps (CANONICAL §15).
@@ -25,7 +24,17 @@ pub fn retarget_v1_clamped(
     target_old: [u8; 32],
     window_timestamps: &[u64],
 ) -> Result<[u8; 32], TxError> {
-    if window_timestamps.len() != WINDOW_SIZE as usize {
+    retarget_v1_clamped_with_params(target_old, window_timestamps, &ChainParams::V1)
+}
+
+/// [`retarget_v1_clamped`] over `params.window_size` timestamps, with the
+/// clamp and expected timespan derived from `params`.
+pub fn retarget_v1_clamped_with_params(
+    target_old: [u8; 32],
+    window_timestamps: &[u64],
+    params: &ChainParams,
+) -> Result<[u8; 32], TxError> {
+    if window_timestamps.len() as u64 != params.window_size {
         return Err(TxError::new(
             ErrorCode::TxErrParse,
             "retarget: invalid window timestamp count",
@@ -35,20 +44,20 @@ pub fn retarget_v1_clamped(
     let mut prev = first;
 
     for raw in &window_timestamps[1..] {
-        prev = clamp_next_retarget_timestamp(prev, *raw)?;
+        prev = clamp_next_retarget_timestamp(prev, *raw, params.max_timestamp_step_per_block())?;
     }
 
     let mut t_actual = prev - first;
     if t_actual == 0 {
         t_actual = 1;
     }
-    retarget_v1_with_actual(target_old, t_actual)
+    retarget_v1_with_actual(target_old, t_actual, params)
 }
 
-fn clamp_next_retarget_timestamp(prev: u64, raw: u64) -> Result<u64, TxError> {
+fn clamp_next_retarget_timestamp(prev: u64, raw: u64, max_step: u64) -> Result<u64, TxError> {
     let lo = prev.checked_add(1).ok_or_else(retarget_clamp_overflow)?;
     let hi = prev
-        .checked_add(MAX_TIMESTAMP_STEP_PER_BLOCK)
+        .checked_add(max_step)
         .ok_or_else(retarget_clamp_overflow)?;
     Ok(raw.clamp(lo, hi))
 }
@@ -57,11 +66,15 @@ fn retarget_clamp_overflow() -> TxError {
     TxError::new(ErrorCode::TxErrParse, "retarget: timestamp clamp overflow")
 }
 
-fn retarget_v1_with_actual(target_old: [u8; 32], t_actual: u64) -> Result<[u8; 32], TxError> {
+fn retarget_v1_with_actual(
+    target_old: [u8; 32],
+    t_actual: u64,
+    params: &ChainParams,
+) -> Result<[u8; 32], TxError> {
     let pow_limit = BigUint::from_bytes_be(&POW_LIMIT);
     let t_old = BigUint::from_bytes_be(&target_old);
     validate_retarget_old_target(&t_old, &pow_limit)?;
-    let t_expected = retarget_expected_interval()?;
+    let t_expected = retarget_expected_interval(params)?;
 
     // floor(target_old * T_actual / T_expected)
     let mut t_new = (&t_old * BigUint::from(t_actual)) / BigUint::from(t_expected);
@@ -94,9 +107,10 @@ fn validate_retarget_old_target(t_old: &BigUint, pow_limit: &BigUint) -> Result<
     Ok(())
 }
 
-fn retarget_expected_interval() -> Result<u64, TxError> {
-    let t_expected = TARGET_BLOCK_INTERVAL
-        .checked_mul(WINDOW_SIZE)
+fn retarget_expected_interval(params: &ChainParams) -> Result<u64, TxError> {
+    let t_expected = params
+        .target_block_interval
+        .checked_mul(params.window_size)
         .ok_or_else(|| TxError::new(ErrorCode::TxErrParse, "retarget: t_expected overflow"))?;
     if t_expected == 0 {
         return Err(TxError::new(
@@ -115,6 +129,63 @@ fn retarget_upper_bound(t_old: &BigUint, pow_limit: BigUint) -> BigUint {
     core::cmp::min(t_old << 2, pow_limit)
 }
 
+/// Expected target for the block at `height`.
+///
+/// Caller contract: `ancestors` is a contiguous run of canonical headers,
+/// oldest first, ending with the parent, so `ancestors[i]` sits at height
+/// `height - ancestors.len() + i`. Any suffix of the chain is accepted; a
+/// list longer than `height` cannot satisfy the contract and is rejected.
+///
+/// Off a window boundary this is the parent's target. At a boundary it is
+/// the clamped retarget over the last `WINDOW_SIZE` ancestors, which under
+/// the contract are exactly heights `[height - WINDOW_SIZE, height - 1]`.
+pub fn block_expected_target(ancestors: &[BlockHeader], height: u64) -> Result<[u8; 32], TxError> {
+    block_expected_target_with_params(ancestors, height, &ChainParams::V1)
+}
+
+/// [`block_expected_target`] with the retarget window and timespan taken
+/// from `params`.
+pub fn block_expected_target_with_params(
+    ancestors: &[BlockHeader],
+    height: u64,
+    params: &ChainParams,
+) -> Result<[u8; 32], TxError> {
+    let window_size = params.window_size;
+    if window_size == 0 {
+        return Err(TxError::new(
+            ErrorCode::BlockErrParse,
+            "expected target: zero retarget window",
+        ));
+    }
+    let Some(parent) = ancestors.last() else {
+        return Err(TxError::new(
+            ErrorCode::BlockErrParse,
+            "expected target: missing parent header",
+        ));
+    };
+    let len = ancestors.len() as u64;
+    if len > height {
+        return Err(TxError::new(
+            ErrorCode::BlockErrParse,
+            "expected target: more ancestors than height",
+        ));
+    }
+    if !height.is_multiple_of(window_size) {
+        return Ok(parent.target);
+    }
+    if len < window_size {
+        return Err(TxError::new(
+            ErrorCode::BlockErrParse,
+            "expected target: insufficient ancestors for retarget",
+        ));
+    }
+    let window_timestamps: Vec<u64> = ancestors[(len - window_size) as usize..]
+        .iter()
+        .map(|h| h.timestamp)
+        .collect();
+    retarget_v1_clamped_with_params(parent.target, &window_timestamps, params)
+}
+
 pub fn pow_check(header_bytes: &[u8], target: [u8; 32]) -> Result<(), TxError> {
     if header_bytes.len() != BLOCK_HEADER_BYTES {
         return Err(TxError::new(
@@ -150,6 +221,7 @@ fn biguint_to_bytes32(x: &BigUint) -> Result<[u8; 32], TxError> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::constants::{MAX_TIMESTAMP_STEP_PER_BLOCK, WINDOW_SIZE};
 
     fn bytes32_u16(value: u16) -> [u8; 32] {
         let mut out = [0u8; 32];
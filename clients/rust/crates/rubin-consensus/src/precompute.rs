@@ -1,11 +1,11 @@
-use std::collections::{HashMap, HashSet};
+use std::collections::HashSet;
 
 use crate::block_basic::ParsedBlock;
-use crate::constants::{
-    COINBASE_MATURITY, COV_TYPE_ANCHOR, COV_TYPE_CORE_SIMPLICITY, COV_TYPE_DA_COMMIT,
-};
+use crate::chain_params::ChainParams;
+use crate::constants::{COV_TYPE_ANCHOR, COV_TYPE_CORE_SIMPLICITY, COV_TYPE_DA_COMMIT};
 use crate::covenant_genesis::validate_tx_covenants_genesis;
 use crate::error::{ErrorCode, TxError};
+use crate::outpoint_map::OutpointMap;
 use crate::simplicity_covenant::reject_core_simplicity_spend;
 use crate::utxo_basic::{Outpoint, UtxoEntry};
 use crate::vault::witness_slots;
@@ -60,8 +60,19 @@ pub(crate) fn add_witness_slots(total: usize, slots: usize) -> Result<usize, TxE
 /// Error behavior matches the sequential path exactly.
 pub fn precompute_tx_contexts(
     pb: &ParsedBlock,
-    utxo_snapshot: &HashMap<Outpoint, UtxoEntry>,
+    utxo_snapshot: &OutpointMap<UtxoEntry>,
     block_height: u64,
+) -> Result<Vec<PrecomputedTxContext>, TxError> {
+    precompute_tx_contexts_with_chain_params(pb, utxo_snapshot, block_height, &ChainParams::V1)
+}
+
+/// [`precompute_tx_contexts`] for a chain instance whose parameters may
+/// differ from the v1 constants; only `coinbase_maturity` applies.
+pub fn precompute_tx_contexts_with_chain_params(
+    pb: &ParsedBlock,
+    utxo_snapshot: &OutpointMap<UtxoEntry>,
+    block_height: u64,
+    params: &ChainParams,
 ) -> Result<Vec<PrecomputedTxContext>, TxError> {
     if pb.txs.is_empty() {
         return Err(TxError::new(
@@ -84,7 +95,8 @@ pub fn precompute_tx_contexts(
 
     // Working UTXO overlay: starts from immutable snapshot, tracks same-block
     // produced outputs. The original snapshot is never modified.
-    let mut overlay: HashMap<Outpoint, UtxoEntry> = HashMap::with_capacity(utxo_snapshot.len());
+    let mut overlay: OutpointMap<UtxoEntry> =
+        OutpointMap::with_capacity_and_hasher(utxo_snapshot.len(), Default::default());
     for (k, v) in utxo_snapshot {
         overlay.insert(k.clone(), v.clone());
     }
@@ -123,6 +135,15 @@ pub fn precompute_tx_contexts(
         let mut stopped_at_core_simplicity = false;
 
         for input in &tx.inputs {
+            // No genesis covenant reads script_sig, so any bytes there are
+            // rejected before the prevout is known, as on the sequential path.
+            if !input.script_sig.is_empty() {
+                return Err(TxError::new(
+                    ErrorCode::TxErrParse,
+                    "script_sig must be empty under genesis covenant set",
+                ));
+            }
+
             // Coinbase prevout encoding forbidden in non-coinbase.
             if input.prev_vout == 0xffff_ffff && input.prev_txid == zero_txid {
                 return Err(TxError::new(
@@ -152,7 +173,7 @@ pub fn precompute_tx_contexts(
             // also checked downstream in the sequential validation path).
             if entry.created_by_coinbase
                 && (block_height < entry.creation_height
-                    || block_height - entry.creation_height < COINBASE_MATURITY)
+                    || block_height - entry.creation_height < params.coinbase_maturity)
             {
                 return Err(TxError::new(
                     ErrorCode::TxErrCoinbaseImmature,
@@ -1,5 +1,6 @@
 use crate::constants::{MAX_BLOCK_WEIGHT, ML_DSA_87_PUBKEY_BYTES, ML_DSA_87_SIG_BYTES};
 use crate::error::{ErrorCode, TxError};
+use crate::key_id_cache::KeyIdCache;
 use crate::sig_cache::SigCache;
 use crate::suite_registry::SuiteRegistry;
 use crate::verify_sig_openssl::verify_sig_with_registry;
@@ -13,6 +14,9 @@ use crate::worker_pool::{
 const MAX_SIGCHECK_QUEUE_BYTES: usize = MAX_BLOCK_WEIGHT as usize;
 const SIGCHECK_TASK_FIXED_OVERHEAD_BYTES: usize = 1 + 32 + 1;
 const CURRENT_NATIVE_QUEUE_PAYLOAD_FLOOR_BYTES: u64 = ML_DSA_87_PUBKEY_BYTES + ML_DSA_87_SIG_BYTES;
+// Distinct signing keys per block are few in practice; this bounds the
+// retained pubkey bytes to a few MiB in the adversarial case.
+const SIGCHECK_KEY_ID_CACHE_CAPACITY: usize = 1024;
 
 #[derive(Clone, Debug, PartialEq, Eq)]
 struct SigCheckTask {
@@ -50,6 +54,7 @@ pub(crate) struct SigCheckQueue {
     queued_bytes: usize,
     registry: Option<SuiteRegistry>,
     cache: Option<SigCache>,
+    key_ids: KeyIdCache,
     workers: usize,
 }
 
@@ -60,6 +65,7 @@ impl Default for SigCheckQueue {
             queued_bytes: 0,
             registry: None,
             cache: None,
+            key_ids: KeyIdCache::new(SIGCHECK_KEY_ID_CACHE_CAPACITY),
             workers: 1,
         }
     }
@@ -90,6 +96,7 @@ impl SigCheckQueue {
             queued_bytes: 0,
             registry: None,
             cache: None,
+            key_ids: KeyIdCache::new(SIGCHECK_KEY_ID_CACHE_CAPACITY),
             workers: workers.max(1),
         }
     }
@@ -127,6 +134,12 @@ impl SigCheckQueue {
         Ok(())
     }
 
+    /// key_id derivation shared across every transaction validated against
+    /// this queue (one block).
+    pub(crate) fn key_id(&mut self, pubkey: &[u8]) -> [u8; 32] {
+        self.key_ids.key_id(pubkey)
+    }
+
     pub(crate) fn len(&self) -> usize {
         self.tasks.len()
     }
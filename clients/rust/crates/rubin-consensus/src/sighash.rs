@@ -13,8 +13,15 @@ pub struct SighashV1PrehashCache<'a> {
     hash_all_sequences: Option<[u8; 32]>,
     hash_all_outputs: Option<[u8; 32]>,
     single_outputs: HashMap<u32, [u8; 32]>,
+    /// Scratch buffer reused for every preimage assembled for this tx.
+    preimage: Vec<u8>,
 }
 
+/// Byte length of the fixed-layout sighash v1 preimage.
+const SIGHASH_V1_PREIMAGE_BYTES: usize =
+    16 + 32 + 4 + 1 + 8 + 32 + 32 + 32 + 4 + 32 + 4 + 8 + 4 + 32 + 4 + 1;
+
+#[inline]
 pub fn is_valid_sighash_type(sighash_type: u8) -> bool {
     sighash_type == SIGHASH_ALL
         || sighash_type == SIGHASH_NONE
@@ -53,6 +60,7 @@ impl<'a> SighashV1PrehashCache<'a> {
             hash_all_sequences: None,
             hash_all_outputs: None,
             single_outputs: HashMap::new(),
+            preimage: Vec::with_capacity(SIGHASH_V1_PREIMAGE_BYTES.max(tx.inputs.len() * (32 + 4))),
         })
     }
 
@@ -69,12 +77,13 @@ impl<'a> SighashV1PrehashCache<'a> {
         if let Some(hash) = self.hash_all_prevouts {
             return hash;
         }
-        let mut prevouts = Vec::with_capacity(self.tx.inputs.len() * (32 + 4));
+        let prevouts = &mut self.preimage;
+        prevouts.clear();
         for tx_in in &self.tx.inputs {
             prevouts.extend_from_slice(&tx_in.prev_txid);
             prevouts.extend_from_slice(&tx_in.prev_vout.to_le_bytes());
         }
-        let hash = sha3_256(&prevouts);
+        let hash = sha3_256(prevouts);
         self.hash_all_prevouts = Some(hash);
         hash
     }
@@ -83,11 +92,12 @@ impl<'a> SighashV1PrehashCache<'a> {
         if let Some(hash) = self.hash_all_sequences {
             return hash;
         }
-        let mut sequences = Vec::with_capacity(self.tx.inputs.len() * 4);
+        let sequences = &mut self.preimage;
+        sequences.clear();
         for tx_in in &self.tx.inputs {
             sequences.extend_from_slice(&tx_in.sequence.to_le_bytes());
         }
-        let hash = sha3_256(&sequences);
+        let hash = sha3_256(sequences);
         self.hash_all_sequences = Some(hash);
         hash
     }
@@ -96,14 +106,15 @@ impl<'a> SighashV1PrehashCache<'a> {
         if let Some(hash) = self.hash_all_outputs {
             return hash;
         }
-        let mut outputs_bytes = Vec::with_capacity(self.tx.outputs.len() * 64);
+        let outputs_bytes = &mut self.preimage;
+        outputs_bytes.clear();
         for o in &self.tx.outputs {
             outputs_bytes.extend_from_slice(&o.value.to_le_bytes());
             outputs_bytes.extend_from_slice(&o.covenant_type.to_le_bytes());
-            encode_compact_size(o.covenant_data.len() as u64, &mut outputs_bytes);
+            encode_compact_size(o.covenant_data.len() as u64, outputs_bytes);
             outputs_bytes.extend_from_slice(&o.covenant_data);
         }
-        let hash = sha3_256(&outputs_bytes);
+        let hash = sha3_256(outputs_bytes);
         self.hash_all_outputs = Some(hash);
         hash
     }
@@ -116,12 +127,13 @@ impl<'a> SighashV1PrehashCache<'a> {
         let idx = input_index as usize;
         let hash = if idx < self.tx.outputs.len() {
             let o = &self.tx.outputs[idx];
-            let mut output_bytes = Vec::with_capacity(64);
+            let output_bytes = &mut self.preimage;
+            output_bytes.clear();
             output_bytes.extend_from_slice(&o.value.to_le_bytes());
             output_bytes.extend_from_slice(&o.covenant_type.to_le_bytes());
-            encode_compact_size(o.covenant_data.len() as u64, &mut output_bytes);
+            encode_compact_size(o.covenant_data.len() as u64, output_bytes);
             output_bytes.extend_from_slice(&o.covenant_data);
-            sha3_256(&output_bytes)
+            sha3_256(output_bytes)
         } else {
             sha3_256(&[])
         };
@@ -157,18 +169,16 @@ pub fn sighash_v1_digest_with_cache(
     let i = &tx.inputs[idx];
 
     let hash_prevouts = if anyone_can_pay {
-        let mut prevouts = Vec::with_capacity(32 + 4);
-        prevouts.extend_from_slice(&i.prev_txid);
-        prevouts.extend_from_slice(&i.prev_vout.to_le_bytes());
-        sha3_256(&prevouts)
+        let mut prevout = [0u8; 32 + 4];
+        prevout[..32].copy_from_slice(&i.prev_txid);
+        prevout[32..].copy_from_slice(&i.prev_vout.to_le_bytes());
+        sha3_256(&prevout)
     } else {
         cache.hash_all_prevouts()
     };
 
     let hash_sequences = if anyone_can_pay {
-        let mut sequences = Vec::with_capacity(4);
-        sequences.extend_from_slice(&i.sequence.to_le_bytes());
-        sha3_256(&sequences)
+        sha3_256(&i.sequence.to_le_bytes())
     } else {
         cache.hash_all_sequences()
     };
@@ -185,13 +195,15 @@ pub fn sighash_v1_digest_with_cache(
         }
     };
 
-    let mut preimage = Vec::with_capacity(256);
+    let hash_of_da_core_fields = cache.hash_of_da_core_fields()?;
+    let preimage = &mut cache.preimage;
+    preimage.clear();
     preimage.extend_from_slice(b"RUBINv1-sighash/");
     preimage.extend_from_slice(&chain_id);
     preimage.extend_from_slice(&tx.version.to_le_bytes());
     preimage.push(tx.tx_kind);
     preimage.extend_from_slice(&tx.tx_nonce.to_le_bytes());
-    preimage.extend_from_slice(&cache.hash_of_da_core_fields()?);
+    preimage.extend_from_slice(&hash_of_da_core_fields);
     preimage.extend_from_slice(&hash_prevouts);
     preimage.extend_from_slice(&hash_sequences);
     preimage.extend_from_slice(&input_index.to_le_bytes());
@@ -202,8 +214,9 @@ pub fn sighash_v1_digest_with_cache(
     preimage.extend_from_slice(&hash_outputs);
     preimage.extend_from_slice(&tx.locktime.to_le_bytes());
     preimage.push(sighash_type);
+    debug_assert_eq!(preimage.len(), SIGHASH_V1_PREIMAGE_BYTES);
 
-    Ok(sha3_256(&preimage))
+    Ok(sha3_256(preimage))
 }
 
 #[cfg(test)]
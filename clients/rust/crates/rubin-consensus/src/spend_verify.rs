@@ -164,8 +164,7 @@ fn spend_suite_params<'a>(
 }
 
 fn validate_witness_item_lengths(w: &WitnessItem, params: &SuiteParams) -> Result<(), TxError> {
-    if w.pubkey.len() as u64 == params.pubkey_len && w.signature.len() as u64 == params.sig_len + 1
-    {
+    if params.has_canonical_witness_shape(w.pubkey.len() as u64, w.signature.len() as u64) {
         return Ok(());
     }
     Err(TxError::new(
@@ -436,7 +435,11 @@ pub(crate) fn verify_mldsa_key_and_sig_q(
     key_binding_error: TxError,
     invalid_sig_error: TxError,
 ) -> Result<(), TxError> {
-    if sha3_256(&w.pubkey) != expected_key_id {
+    let key_id = match sig_queue.as_deref_mut() {
+        Some(queue) => queue.key_id(&w.pubkey),
+        None => sha3_256(&w.pubkey),
+    };
+    if key_id != expected_key_id {
         return Err(key_binding_error);
     }
     let (crypto_sig, sighash_type) = extract_crypto_sig_and_sighash(w)?;
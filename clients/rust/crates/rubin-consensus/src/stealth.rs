@@ -16,6 +16,16 @@ pub struct StealthCovenant {
     pub one_time_key_id: [u8; 32],
 }
 
+impl StealthCovenant {
+    /// Canonical covenant_data encoding; inverse of [`parse_stealth_covenant_data`].
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(MAX_STEALTH_COVENANT_DATA as usize);
+        out.extend_from_slice(&self.ciphertext);
+        out.extend_from_slice(&self.one_time_key_id);
+        out
+    }
+}
+
 pub fn parse_stealth_covenant_data(cov_data: &[u8]) -> Result<StealthCovenant, TxError> {
     if cov_data.len() as u64 != MAX_STEALTH_COVENANT_DATA {
         return Err(TxError::new(
@@ -138,8 +148,7 @@ fn stealth_suite_params<'a>(
 }
 
 fn validate_stealth_witness_shape(w: &WitnessItem, params: &SuiteParams) -> Result<(), TxError> {
-    if w.pubkey.len() as u64 != params.pubkey_len || w.signature.len() as u64 != params.sig_len + 1
-    {
+    if !params.has_canonical_witness_shape(w.pubkey.len() as u64, w.signature.len() as u64) {
         return Err(TxError::new(
             ErrorCode::TxErrSigNoncanonical,
             "non-canonical witness item lengths",
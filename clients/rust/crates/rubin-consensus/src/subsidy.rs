@@ -22,6 +22,15 @@ pub fn block_subsidy(height: u64, already_generated: u128) -> u64 {
     clamp_base_reward_to_u64(base_reward)
 }
 
+/// already_generated(h) for a chain that paid exactly `block_subsidy` at every
+/// height 1..h-1, i.e. the value connect-block tracks for the next block at
+/// `height`. Linear in `height`; intended for tooling, not the connect path.
+pub fn cumulative_subsidy(height: u64) -> u128 {
+    (1..height).fold(0u128, |already_generated, h| {
+        already_generated.saturating_add(u128::from(block_subsidy(h, already_generated)))
+    })
+}
+
 /// Narrow `base_reward` from the u128 subsidy arithmetic into `u64`
 /// with an explicit overflow guard — the Rust equivalent of the
 /// `baseReward.IsUint64()` branch in `BlockSubsidyBig`
@@ -108,6 +117,15 @@ mod tests {
         assert_eq!(clamp_base_reward_to_u64(u128::MAX), TAIL_EMISSION_PER_BLOCK);
     }
 
+    #[test]
+    fn cumulative_subsidy_sums_prior_heights() {
+        assert_eq!(cumulative_subsidy(0), 0);
+        assert_eq!(cumulative_subsidy(1), 0);
+        assert_eq!(cumulative_subsidy(2), u128::from(block_subsidy(1, 0)));
+        let g2 = cumulative_subsidy(2);
+        assert_eq!(cumulative_subsidy(3), g2 + u128::from(block_subsidy(2, g2)));
+    }
+
     #[test]
     fn block_subsidy_repeat_is_deterministic() {
         let height = 42;
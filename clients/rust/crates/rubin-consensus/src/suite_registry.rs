@@ -4,6 +4,7 @@ use crate::constants::{
 };
 use std::borrow::Cow;
 use std::collections::{BTreeMap, BTreeSet};
+use std::sync::OnceLock;
 
 pub const ROTATION_V1_PRODUCTION_AT_MOST_ONE_DESCRIPTOR_ERR_STEM: &str =
     "rotation: v1 production profile allows at most one descriptor";
@@ -11,6 +12,9 @@ pub const ROTATION_V1_PRODUCTION_FINITE_H4_REQUIRED_ERR_STEM: &str =
     "rotation: v1 production profile requires finite sunset_height (H4)";
 const MAX_LIVE_NATIVE_SUITE_SET_CARDINALITY: usize = 2;
 
+static DEFAULT_SUITE_REGISTRY: OnceLock<SuiteRegistry> = OnceLock::new();
+static DEFAULT_NATIVE_SPEND_SUITES: OnceLock<NativeSuiteSet> = OnceLock::new();
+
 /// Consensus parameters for a single signature suite.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct SuiteParams {
@@ -22,6 +26,14 @@ pub struct SuiteParams {
     pub alg_name: &'static str,
 }
 
+impl SuiteParams {
+    /// The canonical witness shape for this suite: an exact-length pubkey and
+    /// a signature of exactly `sig_len` plus the trailing sighash byte.
+    pub fn has_canonical_witness_shape(&self, pubkey_len: u64, sig_len_with_sighash: u64) -> bool {
+        pubkey_len == self.pubkey_len && sig_len_with_sighash == self.sig_len + 1
+    }
+}
+
 /// Reports whether `suite_id` is reserved for a structural witness carrier (e.g.
 /// the §5.4 Simplicity envelope, 0xF0) rather than native cryptographic
 /// verification. Mirror of Go `IsStructuralWitnessCarrierSuiteID`.
@@ -112,6 +124,19 @@ impl SuiteRegistry {
     }
 }
 
+/// Process-wide `SuiteRegistry::default_registry()`, for the context-free
+/// paths (wire parsing, legacy weight, registry-less verification) that have
+/// no caller-supplied registry.
+pub(crate) fn default_suite_registry() -> &'static SuiteRegistry {
+    DEFAULT_SUITE_REGISTRY.get_or_init(SuiteRegistry::default_registry)
+}
+
+/// Native spend suites of `DefaultRotationProvider`, which do not depend on
+/// height.
+pub(crate) fn default_native_spend_suites() -> &'static NativeSuiteSet {
+    DEFAULT_NATIVE_SPEND_SUITES.get_or_init(|| DefaultRotationProvider.native_spend_suites(0))
+}
+
 /// Set of suite IDs valid for native covenant operations at a given height.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct NativeSuiteSet {
@@ -445,6 +445,70 @@ fn validate_block_basic_anchor_bytes_precede_nonce_replay() {
     assert_eq!(err.code, ErrorCode::BlockErrAnchorBytesExceeded);
 }
 
+fn block_with_txs(non_coinbase: &[Vec<u8>], prev_tag: u8) -> ([u8; 32], [u8; 32], Vec<u8>) {
+    let coinbase = coinbase_with_witness_commitment(1, non_coinbase);
+    let mut txs = vec![coinbase];
+    txs.extend_from_slice(non_coinbase);
+    let txids: Vec<[u8; 32]> = txs
+        .iter()
+        .map(|tx| parse_tx(tx).expect("parse tx").1)
+        .collect();
+    let root = merkle_root_txids(&txids).expect("root");
+    let mut prev = [0u8; 32];
+    prev[0] = prev_tag;
+    let target = [0xffu8; 32];
+    (
+        prev,
+        target,
+        build_block_bytes(prev, root, target, 43, &txs),
+    )
+}
+
+fn p2pk_spend_with_nonce(nonce: u64) -> Vec<u8> {
+    tx_with_nonce_and_outputs(
+        nonce,
+        &[TestOutput {
+            value: 1,
+            covenant_type: COV_TYPE_P2PK,
+            covenant_data: valid_p2pk_covenant_data(),
+        }],
+    )
+}
+
+// Odd levels promote the last node instead of duplicating it, and leaves and
+// inner nodes are domain-tagged, so appending a copy of the last txid always
+// changes the root. A block cannot be mutated into a duplicate-tail twin that
+// shares its header; blocks that do repeat a transaction are rejected by the
+// per-block tx_nonce uniqueness rule wherever the repeat sits.
+#[test]
+fn merkle_root_changes_when_last_txid_is_duplicated() {
+    let ids: Vec<[u8; 32]> = (1..=5u8).map(|i| [i; 32]).collect();
+    for n in 1..=ids.len() {
+        let base = merkle_root_txids(&ids[..n]).expect("root");
+        let mut dup = ids[..n].to_vec();
+        dup.push(ids[n - 1]);
+        assert_ne!(merkle_root_txids(&dup).expect("dup root"), base, "n={n}");
+    }
+}
+
+#[test]
+fn validate_block_basic_rejects_duplicated_final_tx() {
+    let tx1 = p2pk_spend_with_nonce(1);
+    let tx2 = p2pk_spend_with_nonce(2);
+    let (prev, target, block) = block_with_txs(&[tx1, tx2.clone(), tx2], 0xa3);
+    let err = validate_block_basic_at_height(&block, Some(prev), Some(target), 1).unwrap_err();
+    assert_eq!(err.code, ErrorCode::TxErrNonceReplay);
+}
+
+#[test]
+fn validate_block_basic_rejects_duplicated_non_final_tx() {
+    let tx1 = p2pk_spend_with_nonce(1);
+    let tx2 = p2pk_spend_with_nonce(2);
+    let (prev, target, block) = block_with_txs(&[tx1.clone(), tx1, tx2], 0xa4);
+    let err = validate_block_basic_at_height(&block, Some(prev), Some(target), 1).unwrap_err();
+    assert_eq!(err.code, ErrorCode::TxErrNonceReplay);
+}
+
 #[test]
 fn validate_block_basic_weight_precedes_coinbase_structure() {
     let overweight_tx = tx_with_nonce_and_outputs(1, &repeated_anchor_outputs(1024, 17_000));
@@ -510,6 +574,34 @@ fn validate_block_basic_coinbase_locktime_must_match_height() {
     assert_eq!(err.code, ErrorCode::BlockErrCoinbaseInvalid);
 }
 
+#[test]
+fn validate_coinbase_tx_names_failing_rule() {
+    let height = 3u64;
+    let already_generated = crate::subsidy::cumulative_subsidy(height);
+    let subsidy = crate::subsidy::block_subsidy(height, already_generated);
+    let tx_bytes = coinbase_with_witness_commitment_and_p2pk_value(height as u32, subsidy, &[]);
+    let (tx, _txid, _w, _n) = parse_tx(&tx_bytes).expect("tx");
+    validate_coinbase_tx(&tx, height, already_generated, 0).expect("valid coinbase");
+
+    let err = validate_coinbase_tx(&tx, height, already_generated.saturating_add(1 << 60), 0)
+        .unwrap_err();
+    assert_eq!(err.code, ErrorCode::BlockErrSubsidyExceeded);
+
+    let mut with_witness = tx.clone();
+    with_witness.witness.push(WitnessItem {
+        suite_id: 0,
+        pubkey: Vec::new(),
+        signature: Vec::new(),
+    });
+    let err = validate_coinbase_tx(&with_witness, height, already_generated, 0).unwrap_err();
+    assert_eq!(err.code, ErrorCode::BlockErrCoinbaseInvalid);
+    assert_eq!(err.msg, "coinbase must not carry witness items");
+
+    let err = validate_coinbase_tx(&tx, height + 1, already_generated, 0).unwrap_err();
+    assert_eq!(err.code, ErrorCode::BlockErrCoinbaseInvalid);
+    assert_eq!(err.msg, "coinbase locktime must equal block height");
+}
+
 #[test]
 fn validate_block_basic_coinbase_like_tx_forbidden_after_index_zero() {
     let coinbase_like = coinbase_tx_with_outputs(
@@ -998,6 +1090,59 @@ fn tx_weight_sig_cost_special_cases_mirror_go() {
     }
 }
 
+#[test]
+fn anchor_bytes_weigh_as_base_bytes_at_max_payload() {
+    // No anchor discount: at MAX_ANCHOR_PAYLOAD_SIZE an anchor output weighs
+    // exactly what the same bytes under a non-anchor covenant type weigh, and
+    // the reported anchor_bytes are what `tx_anchor_bytes` exposes to policy.
+    let payload_len = MAX_ANCHOR_PAYLOAD_SIZE as usize;
+    let anchor = tx_with_nonce_and_outputs(1, &repeated_anchor_outputs(1, payload_len));
+    let (anchor_tx, _, _, _) = parse_tx(&anchor).expect("parse max anchor");
+    let mut plain_tx = anchor_tx.clone();
+    plain_tx.outputs[0].covenant_type = COV_TYPE_P2PK;
+
+    let (anchor_weight, _, anchor_bytes) =
+        crate::block_basic::tx_weight_and_stats_public(&anchor_tx).expect("anchor weight");
+    let (plain_weight, _, plain_anchor_bytes) =
+        crate::block_basic::tx_weight_and_stats_public(&plain_tx).expect("plain weight");
+    assert_eq!(anchor_bytes, MAX_ANCHOR_PAYLOAD_SIZE);
+    assert_eq!(
+        crate::block_basic::tx_anchor_bytes(&anchor_tx),
+        anchor_bytes
+    );
+    assert_eq!(plain_anchor_bytes, 0);
+    assert_eq!(crate::block_basic::tx_anchor_bytes(&plain_tx), 0);
+    assert_eq!(anchor_weight, plain_weight);
+
+    // Just under the boundary the anchor and plain weights move together too.
+    let mut shorter = anchor_tx.clone();
+    shorter.outputs[0].covenant_data.pop();
+    let mut shorter_plain = plain_tx.clone();
+    shorter_plain.outputs[0].covenant_data.pop();
+    let (shorter_weight, _, _) =
+        crate::block_basic::tx_weight_and_stats_public(&shorter).expect("shorter weight");
+    let (shorter_plain_weight, _, _) =
+        crate::block_basic::tx_weight_and_stats_public(&shorter_plain).expect("shorter plain");
+    assert_eq!(
+        anchor_weight - shorter_weight,
+        plain_weight - shorter_plain_weight
+    );
+    assert_eq!(
+        crate::block_basic::tx_anchor_bytes(&shorter),
+        anchor_bytes - 1
+    );
+}
+
+#[test]
+fn anchor_payload_over_max_is_rejected_at_parse() {
+    let oversized = tx_with_nonce_and_outputs(
+        1,
+        &repeated_anchor_outputs(1, MAX_ANCHOR_PAYLOAD_SIZE as usize + 1),
+    );
+    let err = parse_tx(&oversized).unwrap_err();
+    assert_eq!(err.code, ErrorCode::TxErrParse);
+}
+
 #[test]
 fn tx_weight_at_height_native_suite_uses_registry_cost() {
     use crate::suite_registry::{DefaultRotationProvider, SuiteRegistry};
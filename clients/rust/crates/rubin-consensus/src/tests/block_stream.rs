@@ -0,0 +1,210 @@
+use super::*;
+use crate::constants::MAX_BLOCK_BYTES;
+use crate::error::TxError;
+use crate::{BlockStreamParser, ParsedBlock, ParsedItem};
+
+type Flattened = (
+    [u8; BLOCK_HEADER_BYTES],
+    u64,
+    Vec<crate::tx::Tx>,
+    Vec<[u8; 32]>,
+    Vec<[u8; 32]>,
+);
+
+fn flatten(pb: ParsedBlock) -> Flattened {
+    (pb.header_bytes, pb.tx_count, pb.txs, pb.txids, pb.wtxids)
+}
+
+/// Feeds `block` in chunks of the sizes `next_len` yields and collects the
+/// items into the shape `parse_block_bytes` returns.
+fn stream_parse(block: &[u8], mut next_len: impl FnMut() -> usize) -> Result<Flattened, TxError> {
+    let mut parser = BlockStreamParser::new(MAX_BLOCK_BYTES as usize);
+    let mut out: Flattened = (
+        [0u8; BLOCK_HEADER_BYTES],
+        0,
+        Vec::new(),
+        Vec::new(),
+        Vec::new(),
+    );
+    let mut rest = block;
+    while !rest.is_empty() {
+        let (chunk, tail) = rest.split_at(next_len().clamp(1, rest.len()));
+        rest = tail;
+        for item in parser.feed(chunk)? {
+            match item {
+                ParsedItem::Header { header_bytes, .. } => out.0 = header_bytes,
+                ParsedItem::TxCount(n) => out.1 = n,
+                ParsedItem::Tx { tx, txid, wtxid } => {
+                    out.2.push(*tx);
+                    out.3.push(txid);
+                    out.4.push(wtxid);
+                }
+            }
+        }
+    }
+    parser.finish()?;
+    Ok(out)
+}
+
+fn sample_block() -> Vec<u8> {
+    let big_output = TestOutput {
+        value: 5,
+        covenant_type: COV_TYPE_ANCHOR,
+        covenant_data: vec![0x5a; 300],
+    };
+    let txs = vec![
+        tx_with_nonce_and_outputs(1, std::slice::from_ref(&big_output)),
+        tx_with_nonce_and_outputs(2, &[big_output.clone(), big_output]),
+    ];
+    let mut all = vec![coinbase_with_witness_commitment(0, &txs)];
+    all.extend(txs);
+    build_block_bytes([0x11; 32], [0x22; 32], [0xff; 32], 3, &all)
+}
+
+/// Deterministic xorshift chunk sizes in `1..=max`.
+fn random_lengths(mut seed: u64, max: usize) -> impl FnMut() -> usize {
+    move || {
+        seed ^= seed << 13;
+        seed ^= seed >> 7;
+        seed ^= seed << 17;
+        (seed % max as u64) as usize + 1
+    }
+}
+
+fn assert_stream_matches_one_shot(block: &[u8]) {
+    let one_shot = parse_block_bytes(block).map(flatten);
+    assert_eq!(stream_parse(block, || 1), one_shot, "1-byte chunks");
+    assert_eq!(stream_parse(block, || 7), one_shot, "7-byte chunks");
+    for seed in 1..=16u64 {
+        assert_eq!(
+            stream_parse(block, random_lengths(seed, 512)),
+            one_shot,
+            "random chunks, seed {seed}"
+        );
+    }
+    assert_eq!(
+        stream_parse(block, || block.len()),
+        one_shot,
+        "single chunk"
+    );
+}
+
+#[test]
+fn stream_parse_valid_block_matches_one_shot() {
+    let block = sample_block();
+    let parsed = parse_block_bytes(&block).expect("one-shot parse");
+    assert_eq!(parsed.tx_count, 3);
+    assert_stream_matches_one_shot(&block);
+}
+
+#[test]
+fn stream_parse_invalid_blocks_match_one_shot() {
+    let block = sample_block();
+
+    let mut trailing = block.clone();
+    trailing.push(0x00);
+    let mut non_minimal_count = block[..BLOCK_HEADER_BYTES].to_vec();
+    non_minimal_count.extend_from_slice(&[0xfd, 0x03, 0x00]);
+    non_minimal_count.extend_from_slice(&block[BLOCK_HEADER_BYTES + 1..]);
+    let mut empty_tx_list = block[..BLOCK_HEADER_BYTES].to_vec();
+    empty_tx_list.push(0x00);
+    let mut bad_tx_version = block.clone();
+    bad_tx_version[BLOCK_HEADER_BYTES + 1] ^= 0xff;
+
+    let mut cases = vec![
+        trailing,
+        non_minimal_count,
+        empty_tx_list,
+        bad_tx_version,
+        block[..BLOCK_HEADER_BYTES - 1].to_vec(),
+        block[..BLOCK_HEADER_BYTES].to_vec(),
+    ];
+    cases.extend(
+        [1usize, 40, 200, 500]
+            .into_iter()
+            .map(|cut| block[..block.len() - cut].to_vec()),
+    );
+    for case in &cases {
+        assert!(parse_block_bytes(case).is_err());
+        assert_stream_matches_one_shot(case);
+    }
+}
+
+#[test]
+fn stream_parse_yields_header_before_the_tx_list_arrives() {
+    let block = sample_block();
+    let mut parser = BlockStreamParser::new(MAX_BLOCK_BYTES as usize);
+    let items = parser.feed(&block[..BLOCK_HEADER_BYTES]).expect("header");
+    assert!(matches!(items.as_slice(), [ParsedItem::Header { .. }]));
+    assert!(!parser.is_complete());
+    let items = parser.feed(&block[BLOCK_HEADER_BYTES..]).expect("rest");
+    assert_eq!(items.len(), 4);
+    assert!(parser.is_complete());
+    parser.finish().expect("complete block");
+}
+
+#[test]
+fn stream_parse_fails_fast_and_stays_failed() {
+    let block = sample_block();
+    let mut parser = BlockStreamParser::new(MAX_BLOCK_BYTES as usize);
+    // tx_count, then the first tx's version: rejected before the rest arrives.
+    let mut bad = block[..BLOCK_HEADER_BYTES + 5].to_vec();
+    bad[BLOCK_HEADER_BYTES + 1] ^= 0xff;
+    let err = parser.feed(&bad).unwrap_err();
+    assert_eq!(err.code, ErrorCode::TxErrParse);
+    let err = parser.feed(&block[BLOCK_HEADER_BYTES + 5..]).unwrap_err();
+    assert_eq!(err.msg, "block stream already failed");
+}
+
+#[test]
+fn stream_parse_enforces_byte_limit() {
+    let block = sample_block();
+    let mut parser = BlockStreamParser::new(block.len() - 1);
+    parser.feed(&block[..block.len() - 1]).expect("under limit");
+    let err = parser.feed(&block[block.len() - 1..]).unwrap_err();
+    assert_eq!(err.code, ErrorCode::BlockErrParse);
+    assert_eq!(err.msg, "block exceeds byte limit");
+
+    let mut parser = BlockStreamParser::new(block.len());
+    parser.feed(&block).expect("at limit");
+    parser.finish().expect("complete block");
+}
+
+#[test]
+fn partial_tx_parse_reports_the_bytes_it_still_needs() {
+    use crate::tx::{parse_tx_partial, PartialTx};
+
+    let tx = tx_with_nonce_and_outputs(
+        1,
+        &[TestOutput {
+            value: 5,
+            covenant_type: COV_TYPE_ANCHOR,
+            covenant_data: vec![0x5a; 300],
+        }],
+    );
+    // Cut inside the covenant: the next attempt needs the whole of it, so a
+    // stream does not re-parse the tx for every chunk in between.
+    let mut have = 100;
+    let mut needs = Vec::new();
+    loop {
+        match parse_tx_partial(&tx[..have]).expect("valid prefix") {
+            PartialTx::NeedBytes(need) => {
+                assert!(need > have && need <= tx.len(), "need {need} at {have}");
+                needs.push(need);
+                have = need;
+            }
+            PartialTx::Complete { consumed, txid, .. } => {
+                assert_eq!(consumed, tx.len());
+                assert_eq!(txid, parse_tx(&tx).expect("parse").1);
+                break;
+            }
+        }
+    }
+    assert!(needs[0] > 300, "{needs:?}");
+    assert!(needs.len() < 10, "{needs:?}");
+
+    // A malformed prefix is an error, not a request for more bytes.
+    let mut bad = tx.clone();
+    bad[0] ^= 0xff;
+    assert!(parse_tx_partial(&bad[..100]).is_err());
+}
@@ -0,0 +1,112 @@
+use super::*;
+
+use crate::connect_block_inmem::InMemoryChainState;
+use crate::error::TxError;
+use crate::{
+    connect_block_basic_in_memory_at_height_with_chain_params,
+    connect_block_parallel_sig_verify_with_chain_params, ChainParams,
+};
+
+const FAST_DEVNET: ChainParams = ChainParams {
+    target_block_interval: 2,
+    window_size: 16,
+    coinbase_maturity: 4,
+};
+
+/// Connects a block at `height` spending the height-1 coinbase output, on
+/// the parallel signature path when `workers` is set. The spend carries a
+/// sentinel witness, so a mature spend still fails — just not on maturity.
+fn connect_coinbase_spend_with(
+    height: u64,
+    params: &ChainParams,
+    workers: Option<usize>,
+) -> Result<(), TxError> {
+    let prev = [0x4c; 32];
+    let target = [0xffu8; 32];
+    let mut state = InMemoryChainState {
+        utxos: OutpointMap::default(),
+        already_generated: 0,
+    };
+    state.utxos.insert(
+        Outpoint {
+            txid: prev,
+            vout: 0,
+        },
+        UtxoEntry {
+            value: 100,
+            covenant_type: COV_TYPE_P2PK,
+            covenant_data: valid_p2pk_covenant_data(),
+            creation_height: 1,
+            created_by_coinbase: true,
+        },
+    );
+    let spend =
+        tx_with_one_input_one_output(prev, 0, 90, COV_TYPE_P2PK, &valid_p2pk_covenant_data());
+    let (_t, spend_txid, _w, _n) = parse_tx(&spend).expect("parse spend");
+    let coinbase = coinbase_with_witness_commitment(height as u32, std::slice::from_ref(&spend));
+    let (_t, coinbase_txid, _w, _n) = parse_tx(&coinbase).expect("parse coinbase");
+    let root = merkle_root_txids(&[coinbase_txid, spend_txid]).expect("merkle root");
+    let block = build_block_bytes(prev, root, target, 1, &[coinbase, spend]);
+    match workers {
+        Some(workers) => connect_block_parallel_sig_verify_with_chain_params(
+            &block,
+            Some(prev),
+            Some(target),
+            height,
+            None,
+            &mut state,
+            ZERO_CHAIN_ID,
+            None,
+            None,
+            params,
+            workers,
+        ),
+        None => connect_block_basic_in_memory_at_height_with_chain_params(
+            &block,
+            Some(prev),
+            Some(target),
+            height,
+            None,
+            &mut state,
+            ZERO_CHAIN_ID,
+            None,
+            None,
+            params,
+        ),
+    }
+    .map(|_| ())
+}
+
+fn connect_coinbase_spend(height: u64, params: &ChainParams) -> Result<(), TxError> {
+    connect_coinbase_spend_with(height, params, None)
+}
+
+#[test]
+fn fast_devnet_coinbase_matures_after_four_blocks() {
+    let err = connect_coinbase_spend(4, &FAST_DEVNET).unwrap_err();
+    assert_eq!(err.code, ErrorCode::TxErrCoinbaseImmature);
+
+    let err = connect_coinbase_spend(5, &FAST_DEVNET).unwrap_err();
+    assert_ne!(err.code, ErrorCode::TxErrCoinbaseImmature, "{err}");
+}
+
+#[test]
+fn v1_params_keep_the_coinbase_maturity_constant() {
+    let err = connect_coinbase_spend(5, &ChainParams::V1).unwrap_err();
+    assert_eq!(err.code, ErrorCode::TxErrCoinbaseImmature);
+
+    let err = connect_coinbase_spend(1 + COINBASE_MATURITY, &ChainParams::V1).unwrap_err();
+    assert_ne!(err.code, ErrorCode::TxErrCoinbaseImmature, "{err}");
+}
+
+#[test]
+fn parallel_sig_verify_applies_chain_params_maturity() {
+    let err = connect_coinbase_spend_with(4, &FAST_DEVNET, Some(2)).unwrap_err();
+    assert_eq!(err.code, ErrorCode::TxErrCoinbaseImmature);
+
+    let err = connect_coinbase_spend_with(5, &FAST_DEVNET, Some(2)).unwrap_err();
+    assert_ne!(err.code, ErrorCode::TxErrCoinbaseImmature, "{err}");
+
+    let err = connect_coinbase_spend_with(5, &ChainParams::V1, Some(2)).unwrap_err();
+    assert_eq!(err.code, ErrorCode::TxErrCoinbaseImmature);
+}
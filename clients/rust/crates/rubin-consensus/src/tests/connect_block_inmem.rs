@@ -104,7 +104,7 @@ fn connect_block_ok_computes_fees_and_updates_state() {
     let (_tx, spend_txid, _wtxid, _n) = parse_tx(&spend_bytes).expect("parse spend tx");
 
     let mut state = InMemoryChainState {
-        utxos: HashMap::from([(
+        utxos: OutpointMap::from_iter([(
             prev_out,
             UtxoEntry {
                 value: 100,
@@ -206,7 +206,7 @@ fn connect_block_ok_computes_fees_and_updates_state() {
 #[test]
 fn connect_block_empty_block_bytes_returns_error() {
     let mut state = InMemoryChainState {
-        utxos: HashMap::new(),
+        utxos: OutpointMap::default(),
         already_generated: 0,
     };
 
@@ -246,7 +246,7 @@ fn connect_block_coinbase_only_at_height0_succeeds() {
     let block = build_block_bytes(prev, root, target, 5, &[coinbase]);
 
     let mut state = InMemoryChainState {
-        utxos: HashMap::new(),
+        utxos: OutpointMap::default(),
         already_generated: 0,
     };
 
@@ -285,7 +285,7 @@ fn connect_block_height0_does_not_advance_already_generated() {
     let block = build_block_bytes(prev, root, target, 4, &[coinbase]);
 
     let mut state = InMemoryChainState {
-        utxos: HashMap::new(),
+        utxos: OutpointMap::default(),
         already_generated: 123,
     };
 
@@ -370,7 +370,7 @@ fn connect_block_rejects_subsidy_exceeded() {
     let (_tx, spend_txid, _wtxid, _n) = parse_tx(&spend_bytes).expect("parse spend tx");
 
     let mut state = InMemoryChainState {
-        utxos: HashMap::from([(
+        utxos: OutpointMap::from_iter([(
             prev_out,
             UtxoEntry {
                 value: 100,
@@ -435,7 +435,7 @@ fn connect_block_rejects_coinbase_vault_output() {
     let block = build_block_bytes(prev, root, target, 51, &[coinbase]);
 
     let mut state = InMemoryChainState {
-        utxos: HashMap::new(),
+        utxos: OutpointMap::default(),
         already_generated: 0,
     };
 
@@ -531,7 +531,7 @@ fn connect_block_coinbase_vault_reject_does_not_mutate_applied_spends() {
     let block = build_block_bytes(prev, root, target, 52, &[coinbase, spend_bytes]);
 
     let mut state = InMemoryChainState {
-        utxos: HashMap::from([(
+        utxos: OutpointMap::from_iter([(
             prev_out.clone(),
             UtxoEntry {
                 value: 100,
@@ -621,7 +621,7 @@ fn connect_block_rejects_nonzero_anchor_in_coinbase() {
     let block = build_block_bytes(prev, root, target, 60, &[coinbase]);
 
     let mut state = InMemoryChainState {
-        utxos: HashMap::new(),
+        utxos: OutpointMap::default(),
         already_generated: 0,
     };
 
@@ -716,7 +716,7 @@ fn connect_block_non_coinbase_vault_output_accepted() {
     let (_tx, spend_txid, _wtxid, _n) = parse_tx(&spend_bytes).expect("parse spend tx");
 
     let mut state = InMemoryChainState {
-        utxos: HashMap::from([(
+        utxos: OutpointMap::from_iter([(
             prev_out,
             UtxoEntry {
                 value: 100,
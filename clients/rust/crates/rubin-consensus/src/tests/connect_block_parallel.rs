@@ -1,7 +1,7 @@
 use super::*;
 
 fn clone_state(
-    utxos: &HashMap<Outpoint, UtxoEntry>,
+    utxos: &OutpointMap<UtxoEntry>,
     already_generated: u128,
 ) -> crate::connect_block_inmem::InMemoryChainState {
     crate::connect_block_inmem::InMemoryChainState {
@@ -10,12 +10,12 @@ fn clone_state(
     }
 }
 
-fn signed_p2pk_apply_case() -> Option<(crate::tx::Tx, HashMap<Outpoint, UtxoEntry>, [u8; 32])> {
+fn signed_p2pk_apply_case() -> Option<(crate::tx::Tx, OutpointMap<UtxoEntry>, [u8; 32])> {
     let kp = test_mldsa87_keypair()?;
     let cov_data = p2pk_covenant_data_for_pubkey(&kp.pubkey);
     let mut prev_txid = [0u8; 32];
     prev_txid[0] = 0x42;
-    let utxo_set = HashMap::from([(
+    let utxo_set = OutpointMap::from_iter([(
         Outpoint {
             txid: prev_txid,
             vout: 0,
@@ -104,7 +104,7 @@ fn connect_block_parallel_sig_verify_matches_sequential_for_single_p2pk_spend()
     );
     let (_tx, spend_txid, _wtxid, _n) = parse_tx(&spend_bytes).expect("parse spend tx");
 
-    let start_utxos = HashMap::from([(
+    let start_utxos = OutpointMap::from_iter([(
         prev_out.clone(),
         UtxoEntry {
             value: 100,
@@ -174,7 +174,7 @@ fn connect_block_parallel_sig_verify_matches_sequential_for_multiple_inputs() {
     let kp = kp_or_skip!();
     let cov_data = p2pk_covenant_data_for_pubkey(&kp.pubkey);
 
-    let mut start_utxos = HashMap::new();
+    let mut start_utxos = OutpointMap::default();
     let mut inputs = Vec::new();
     let mut total_in = 0u64;
     for i in 0..4u8 {
@@ -324,7 +324,7 @@ fn connect_block_parallel_sig_verify_rejects_invalid_signature_without_state_mut
     let root = merkle_root_txids(&[coinbase_txid, spend_txid]).expect("merkle root");
     let block = build_block_bytes(prev, root, target, 1, &[coinbase, spend_bytes]);
 
-    let start_utxos = HashMap::from([(
+    let start_utxos = OutpointMap::from_iter([(
         prev_out.clone(),
         UtxoEntry {
             value: 100,
@@ -366,7 +366,7 @@ fn connect_block_parallel_sig_verify_coinbase_only_reports_empty_sig_queue() {
     let block = build_block_bytes(prev, root, target, 1, &[coinbase]);
 
     let mut seq_state = crate::connect_block_inmem::InMemoryChainState {
-        utxos: HashMap::new(),
+        utxos: OutpointMap::default(),
         already_generated: 0,
     };
     let seq_summary = crate::connect_block_basic_in_memory_at_height(
@@ -381,7 +381,7 @@ fn connect_block_parallel_sig_verify_coinbase_only_reports_empty_sig_queue() {
     .expect("sequential coinbase-only");
 
     let mut par_state = crate::connect_block_inmem::InMemoryChainState {
-        utxos: HashMap::new(),
+        utxos: OutpointMap::default(),
         already_generated: 0,
     };
     let summary = crate::connect_block_parallel_sig_verify(
@@ -451,7 +451,7 @@ fn connect_block_parallel_sig_verify_single_worker_matches_sequential() {
     );
     let (_tx, spend_txid, _wtxid, _n) = parse_tx(&spend_bytes).expect("parse spend tx");
 
-    let start_utxos = HashMap::from([(
+    let start_utxos = OutpointMap::from_iter([(
         prev_out,
         UtxoEntry {
             value: 100,
@@ -510,7 +510,7 @@ fn connect_block_parallel_sig_verify_post_state_digest_matches_sequential() {
     let kp = kp_or_skip!();
     let prev_txid = [0x10u8; 32];
     let cov_data = p2pk_covenant_data_for_pubkey(&kp.pubkey);
-    let start_utxos = HashMap::from([(
+    let start_utxos = OutpointMap::from_iter([(
         Outpoint {
             txid: prev_txid,
             vout: 0,
@@ -655,7 +655,7 @@ fn apply_non_coinbase_tx_basic_update_deferred_sigchecks_missing_utxo_fails_befo
         crate::apply_non_coinbase_tx_basic_update_with_mtp_and_core_ext_profiles_and_suite_context_deferred_sigchecks(
             &tx,
             [0u8; 32],
-            &HashMap::new(),
+            &OutpointMap::default(),
             1,
             0,
             0,
@@ -4,9 +4,9 @@ use crate::output_descriptor_bytes;
 fn deferred_apply(
     tx: &crate::tx::Tx,
     txid: [u8; 32],
-    utxos: &HashMap<Outpoint, UtxoEntry>,
+    utxos: &OutpointMap<UtxoEntry>,
     height: u64,
-) -> Result<(HashMap<Outpoint, UtxoEntry>, crate::UtxoApplySummary), crate::error::TxError> {
+) -> Result<(OutpointMap<UtxoEntry>, crate::UtxoApplySummary), crate::error::TxError> {
     crate::apply_non_coinbase_tx_basic_update_with_mtp_and_core_ext_profiles_and_suite_context_deferred_sigchecks(
         tx,
         txid,
@@ -99,7 +99,7 @@ fn apply_non_coinbase_tx_basic_workq_multisig_branch() {
         da_commit_core: None,
         da_chunk_core: None,
     };
-    let utxos = HashMap::from([(
+    let utxos = OutpointMap::from_iter([(
         Outpoint {
             txid: prev_txid,
             vout: 0,
@@ -200,7 +200,7 @@ fn apply_non_coinbase_tx_basic_workq_htlc_claim_branch() {
         da_commit_core: None,
         da_chunk_core: None,
     };
-    let utxos = HashMap::from([(
+    let utxos = OutpointMap::from_iter([(
         Outpoint {
             txid: prev_txid,
             vout: 0,
@@ -265,7 +265,7 @@ fn apply_non_coinbase_tx_basic_workq_stealth_branch() {
         da_commit_core: None,
         da_chunk_core: None,
     };
-    let utxos = HashMap::from([(
+    let utxos = OutpointMap::from_iter([(
         Outpoint {
             txid: prev_txid,
             vout: 0,
@@ -300,7 +300,7 @@ fn apply_non_coinbase_tx_basic_workq_error_paths() {
             da_chunk_core: None,
         },
         [0u8; 32],
-        &HashMap::new(),
+        &OutpointMap::default(),
         0,
     )
     .unwrap_err();
@@ -325,7 +325,7 @@ fn apply_non_coinbase_tx_basic_workq_error_paths() {
             da_chunk_core: None,
         },
         [0u8; 32],
-        &HashMap::new(),
+        &OutpointMap::default(),
         0,
     )
     .unwrap_err();
@@ -361,7 +361,7 @@ fn apply_non_coinbase_tx_basic_workq_error_paths() {
         ZERO_CHAIN_ID,
         &kp,
     )];
-    let err = deferred_apply(&missing_utxo_tx, [0u8; 32], &HashMap::new(), 1).unwrap_err();
+    let err = deferred_apply(&missing_utxo_tx, [0u8; 32], &OutpointMap::default(), 1).unwrap_err();
     assert_eq!(err.code, ErrorCode::TxErrMissingUtxo);
 
     let prev_txid = [0x24; 32];
@@ -398,7 +398,7 @@ fn apply_non_coinbase_tx_basic_workq_error_paths() {
         sign_input_witness(&duplicate_input_tx, 0, 100, ZERO_CHAIN_ID, &kp),
         sign_input_witness(&duplicate_input_tx, 1, 100, ZERO_CHAIN_ID, &kp),
     ];
-    let utxos = HashMap::from([(
+    let utxos = OutpointMap::from_iter([(
         Outpoint {
             txid: prev_txid,
             vout: 0,
@@ -414,7 +414,7 @@ fn apply_non_coinbase_tx_basic_workq_error_paths() {
     let err = deferred_apply(&duplicate_input_tx, [0u8; 32], &utxos, 1).unwrap_err();
     assert_eq!(err.code, ErrorCode::TxErrParse);
 
-    let immature_utxos = HashMap::from([(
+    let immature_utxos = OutpointMap::from_iter([(
         Outpoint {
             txid: prev_txid,
             vout: 0,
@@ -573,7 +573,7 @@ fn apply_non_coinbase_tx_basic_workq_vault_spend_ok() {
         da_commit_core: None,
         da_chunk_core: None,
     };
-    let utxos = HashMap::from([
+    let utxos = OutpointMap::from_iter([
         (
             Outpoint {
                 txid: prev_vault,
@@ -640,7 +640,7 @@ fn apply_non_coinbase_tx_basic_workq_vault_creation_ok() {
         da_chunk_core: None,
     };
     tx.witness = vec![sign_input_witness(&tx, 0, 100, ZERO_CHAIN_ID, &owner_kp)];
-    let utxos = HashMap::from([(
+    let utxos = OutpointMap::from_iter([(
         Outpoint {
             txid: prev_owner,
             vout: 0,
@@ -706,7 +706,7 @@ fn apply_non_coinbase_tx_basic_workq_vault_error_paths() {
         sign_input_witness(&forbidden_output_tx, 0, 100, ZERO_CHAIN_ID, &vault_kp),
         sign_input_witness(&forbidden_output_tx, 1, 10, ZERO_CHAIN_ID, &owner_kp),
     ];
-    let base_utxos = HashMap::from([
+    let base_utxos = OutpointMap::from_iter([
         (
             Outpoint {
                 txid: [0xe1; 32],
@@ -779,7 +779,7 @@ fn apply_non_coinbase_tx_basic_workq_vault_error_paths() {
         sign_input_witness(&fee_sponsor_tx, 1, 10, ZERO_CHAIN_ID, &owner_kp),
         sign_input_witness(&fee_sponsor_tx, 2, 5, ZERO_CHAIN_ID, &sponsor_kp),
     ];
-    let sponsor_utxos = HashMap::from([
+    let sponsor_utxos = OutpointMap::from_iter([
         (
             Outpoint {
                 txid: [0xf1; 32],
@@ -870,7 +870,7 @@ fn apply_non_coinbase_tx_basic_workq_vault_error_paths() {
         ZERO_CHAIN_ID,
         &input_kp,
     )];
-    let creation_utxos = HashMap::from([(
+    let creation_utxos = OutpointMap::from_iter([(
         Outpoint {
             txid: [0xb1; 32],
             vout: 0,
@@ -938,7 +938,7 @@ fn apply_non_coinbase_tx_basic_workq_core_ext_0x0102_rejects() {
         da_commit_core: None,
         da_chunk_core: None,
     };
-    let ext_utxos = HashMap::from([(
+    let ext_utxos = OutpointMap::from_iter([(
         Outpoint {
             txid: prev_txid,
             vout: 0,
@@ -992,7 +992,7 @@ fn apply_non_coinbase_tx_basic_workq_anchor_output_skip() {
         da_chunk_core: None,
     };
     tx.witness = vec![sign_input_witness(&tx, 0, 100, ZERO_CHAIN_ID, &kp)];
-    let utxos = HashMap::from([(
+    let utxos = OutpointMap::from_iter([(
         Outpoint {
             txid: prev_txid,
             vout: 0,
@@ -1040,7 +1040,7 @@ fn apply_wrapper() {
             da_chunk_core: None,
         };
         tx.witness = vec![sign_input_witness(&tx, 0, 100, ZERO_CHAIN_ID, &kp)];
-        let utxos = HashMap::from([(
+        let utxos = OutpointMap::from_iter([(
             Outpoint {
                 txid: prev_txid,
                 vout: 0,
@@ -1073,7 +1073,7 @@ fn apply_wrapper() {
             da_chunk_core: None,
         },
         [0u8; 32],
-        &HashMap::new(),
+        &OutpointMap::default(),
         0,
     )
     .unwrap_err();
@@ -1107,7 +1107,7 @@ fn apply_non_coinbase_tx_basic_workq_covenant_genesis_error() {
         da_chunk_core: None,
     };
     tx.witness = vec![sign_input_witness(&tx, 0, 100, ZERO_CHAIN_ID, &kp)];
-    let utxos = HashMap::from([(
+    let utxos = OutpointMap::from_iter([(
         Outpoint {
             txid: prev_txid,
             vout: 0,
@@ -1153,7 +1153,7 @@ fn apply_non_coinbase_tx_basic_workq_sighash_prehash_error() {
     };
     tx.witness = vec![sign_input_witness(&tx, 0, 100, ZERO_CHAIN_ID, &kp)];
     tx.tx_kind = 0x01;
-    let utxos = HashMap::from([(
+    let utxos = OutpointMap::from_iter([(
         Outpoint {
             txid: prev_txid,
             vout: 0,
@@ -1198,7 +1198,7 @@ fn apply_non_coinbase_tx_basic_workq_check_spend_covenant_error() {
         da_chunk_core: None,
     };
     tx.witness = vec![sign_input_witness(&tx, 0, 100, ZERO_CHAIN_ID, &kp)];
-    let utxos = HashMap::from([(
+    let utxos = OutpointMap::from_iter([(
         Outpoint {
             txid: prev_txid,
             vout: 0,
@@ -1246,7 +1246,7 @@ fn apply_non_coinbase_tx_basic_workq_p2pk_spend_q_error() {
         da_commit_core: None,
         da_chunk_core: None,
     };
-    let utxos = HashMap::from([(
+    let utxos = OutpointMap::from_iter([(
         Outpoint {
             txid: prev_txid,
             vout: 0,
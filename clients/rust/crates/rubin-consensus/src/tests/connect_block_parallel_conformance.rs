@@ -7,10 +7,10 @@ const PARALLEL_TEST_WORKERS: usize = 4;
 const MAX_FIXTURE_HEX_BYTES: usize = 100 * 1024;
 const MAX_FIXTURE_UTXOS: usize = 1_000;
 const MAX_FIXTURE_PREV_TIMESTAMPS: usize = 1_000;
-type TestBlockContext = (Vec<u8>, [u8; 32], [u8; 32], HashMap<Outpoint, UtxoEntry>);
+type TestBlockContext = (Vec<u8>, [u8; 32], [u8; 32], OutpointMap<UtxoEntry>);
 
 fn clone_chain_state(
-    utxos: &HashMap<Outpoint, UtxoEntry>,
+    utxos: &OutpointMap<UtxoEntry>,
     already_generated: u128,
 ) -> crate::InMemoryChainState {
     crate::InMemoryChainState {
@@ -92,7 +92,7 @@ struct VectorUtxo {
     created_by_coinbase: bool,
 }
 
-fn build_utxo_map_from_vector(utxos: &[VectorUtxo]) -> HashMap<Outpoint, UtxoEntry> {
+fn build_utxo_map_from_vector(utxos: &[VectorUtxo]) -> OutpointMap<UtxoEntry> {
     utxos
         .iter()
         .map(|u| {
@@ -246,7 +246,7 @@ fn build_test_block(coinbase_value: u64) -> Option<TestBlockContext> {
     let root = merkle_root_txids(&[coinbase_txid, spend_txid]).expect("merkle root");
     let block = build_block_bytes(prev, root, target, 1, &[coinbase, spend_bytes]);
 
-    let utxos = HashMap::from([(
+    let utxos = OutpointMap::from_iter([(
         prev_out,
         UtxoEntry {
             value: 100,
@@ -307,7 +307,7 @@ fn connect_block_parallel_sig_verify_conformance_parity() {
 #[test]
 fn connect_block_parallel_sig_verify_guard_paths() {
     let mut state = crate::InMemoryChainState {
-        utxos: HashMap::new(),
+        utxos: OutpointMap::default(),
         already_generated: 0,
     };
     let err = crate::connect_block_parallel_sig_verify(
@@ -332,7 +332,7 @@ fn connect_block_parallel_sig_verify_tx_validation_error_missing_utxo() {
         return;
     };
     let mut state = crate::InMemoryChainState {
-        utxos: HashMap::new(),
+        utxos: OutpointMap::default(),
         already_generated: 0,
     };
     let err = crate::connect_block_parallel_sig_verify(
@@ -445,7 +445,7 @@ fn connect_block_parallel_sig_verify_coinbase_vault_forbidden() {
     let block = build_block_bytes(prev, root, target, 1, &[coinbase]);
 
     let mut state = crate::InMemoryChainState {
-        utxos: HashMap::new(),
+        utxos: OutpointMap::default(),
         already_generated: 0,
     };
     let err = crate::connect_block_parallel_sig_verify(
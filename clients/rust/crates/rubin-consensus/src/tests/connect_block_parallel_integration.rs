@@ -1,7 +1,7 @@
 use super::*;
 
 fn clone_state(
-    utxos: &HashMap<Outpoint, UtxoEntry>,
+    utxos: &OutpointMap<UtxoEntry>,
     already_generated: u128,
 ) -> crate::InMemoryChainState {
     crate::InMemoryChainState {
@@ -23,7 +23,7 @@ fn integration_parity_valid_only() {
 
     let kp = kp_or_skip!();
     let cov_data = p2pk_covenant_data_for_pubkey(&kp.pubkey);
-    let start_utxos = HashMap::from([(
+    let start_utxos = OutpointMap::from_iter([(
         Outpoint {
             txid: prev,
             vout: 0,
@@ -110,7 +110,7 @@ fn integration_parity_invalid_one() {
 
     let kp = kp_or_skip!();
     let cov_data = p2pk_covenant_data_for_pubkey(&kp.pubkey);
-    let start_utxos = HashMap::from([(
+    let start_utxos = OutpointMap::from_iter([(
         Outpoint {
             txid: prev,
             vout: 0,
@@ -197,7 +197,7 @@ fn integration_parity_mixed() {
 
     let kp = kp_or_skip!();
     let cov_data = p2pk_covenant_data_for_pubkey(&kp.pubkey);
-    let start_utxos = HashMap::from([(
+    let start_utxos = OutpointMap::from_iter([(
         Outpoint {
             txid: prev,
             vout: 0,
@@ -316,7 +316,7 @@ fn integration_parity_multiple_valid_txs() {
 
     let kp = kp_or_skip!();
     let cov_data = p2pk_covenant_data_for_pubkey(&kp.pubkey);
-    let start_utxos = HashMap::from([(
+    let start_utxos = OutpointMap::from_iter([(
         Outpoint {
             txid: prev,
             vout: 0,
@@ -341,3 +341,91 @@ fn witness_slots_unknown_covenant_rejected() {
     let err = crate::witness_slots(0x7777, &[]).unwrap_err();
     assert_eq!(err.code, ErrorCode::TxErrCovenantTypeInvalid);
 }
+
+#[test]
+fn output_descriptor_golden_vectors_per_covenant_type() {
+    use crate::vault::{output_descriptor_bytes, output_descriptor_hash};
+
+    // (covenant_type, data byte, data len, descriptor prefix, descriptor hash)
+    let vectors: [(u16, u8, usize, &str, &str); 9] = [
+        (
+            COV_TYPE_P2PK,
+            0x11,
+            33,
+            "000021",
+            "efe8afc6a04a30a7366c3088b5509fabaaf36358a07f2fac3cff15ed94ceab8e",
+        ),
+        (
+            COV_TYPE_ANCHOR,
+            0x22,
+            32,
+            "020020",
+            "102deca91736586498f9e5adc99044da9b96ce9d6e91d1b1e187eb3064b2a51f",
+        ),
+        (
+            COV_TYPE_HTLC,
+            0x33,
+            105,
+            "000169",
+            "6838860ae80445d05891ab6282a326d082b801b0f7751492e6bc501f96c332c3",
+        ),
+        (
+            COV_TYPE_VAULT,
+            0x44,
+            100,
+            "010164",
+            "c411399cce445a6182848b160bc0640b4c3fd72a1c25a9c5fd777f07152e0393",
+        ),
+        (
+            COV_TYPE_CORE_EXT,
+            0x55,
+            300,
+            "0201fd2c01",
+            "a4754d08e8312d3e2799c40d8bd1d6cd90bba1401ba50f7e92d2871382b76bc7",
+        ),
+        (
+            COV_TYPE_DA_COMMIT,
+            0x66,
+            32,
+            "030120",
+            "480e3d2b013f878b15950f03974e741d8e999a942b560900018f7208525e433e",
+        ),
+        (
+            COV_TYPE_MULTISIG,
+            0x77,
+            66,
+            "040142",
+            "d6e6747d5f08a81aaee2a5b35fb742dbe4c91cf0e79340a8adb92c39466f9ec6",
+        ),
+        (
+            COV_TYPE_CORE_STEALTH,
+            0x88,
+            252,
+            "0501fc",
+            "37c7f6153acf07b551f26326d16d132e1c55d5de113b77faac356be93afd1c1b",
+        ),
+        (
+            COV_TYPE_CORE_SIMPLICITY,
+            0x99,
+            253,
+            "0601fdfd00",
+            "56ae93ebd1b7aad044fce222e3fd673f4e290d8f234777a292ef947616535c6f",
+        ),
+    ];
+    for (covenant_type, byte, len, prefix, hash) in vectors {
+        let data = vec![byte; len];
+        let desc = output_descriptor_bytes(covenant_type, &data);
+        let prefix = hex::decode(prefix).expect("prefix hex");
+        assert_eq!(
+            desc[..prefix.len()],
+            prefix[..],
+            "type={covenant_type:#06x}"
+        );
+        assert_eq!(desc[prefix.len()..], data[..], "type={covenant_type:#06x}");
+        assert_eq!(
+            hex::encode(output_descriptor_hash(covenant_type, &data)),
+            hash,
+            "type={covenant_type:#06x}"
+        );
+    }
+}
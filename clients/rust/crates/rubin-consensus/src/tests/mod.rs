@@ -8,11 +8,11 @@ use crate::{
     apply_non_coinbase_tx_basic, apply_non_coinbase_tx_basic_with_mtp, block_hash,
     merkle_root_txids, parse_block_bytes, parse_tx, validate_block_basic,
     validate_block_basic_at_height, validate_block_basic_with_context_and_fees_at_height,
-    validate_tx_covenants_genesis, Outpoint, UtxoEntry, BLOCK_HEADER_BYTES,
+    validate_coinbase_tx, validate_tx_covenants_genesis, Outpoint, OutpointMap, UtxoEntry,
+    BLOCK_HEADER_BYTES,
 };
 use num_bigint::BigUint;
 use num_traits::One;
-use std::collections::HashMap;
 
 const ZERO_CHAIN_ID: [u8; 32] = [0u8; 32];
 
@@ -614,6 +614,8 @@ fn encode_htlc_covenant_data(
 }
 
 mod block_basic;
+mod block_stream;
+mod chain_params;
 mod connect_block_inmem;
 mod connect_block_parallel;
 mod connect_block_parallel_branches;
@@ -40,7 +40,7 @@ fn happy_path_block_bytes() -> (Vec<u8>, [u8; 32], [u8; 32]) {
 fn connect_block_basic_in_memory_parses_once_on_happy_path() {
     let (block, prev, target) = happy_path_block_bytes();
     let mut state = InMemoryChainState {
-        utxos: HashMap::new(),
+        utxos: OutpointMap::default(),
         already_generated: 0,
     };
 
@@ -74,7 +74,7 @@ fn connect_block_basic_in_memory_parses_once_on_happy_path() {
 fn connect_block_basic_in_memory_does_not_double_parse_on_validation_error() {
     let (block, _prev, target) = happy_path_block_bytes();
     let mut state = InMemoryChainState {
-        utxos: HashMap::new(),
+        utxos: OutpointMap::default(),
         already_generated: 0,
     };
 
@@ -118,11 +118,11 @@ fn connect_block_basic_in_memory_does_not_double_parse_on_validation_error() {
 fn connect_block_basic_in_memory_parses_once_on_reapply() {
     let (block, prev, target) = happy_path_block_bytes();
     let mut state1 = InMemoryChainState {
-        utxos: HashMap::new(),
+        utxos: OutpointMap::default(),
         already_generated: 0,
     };
     let mut state2 = InMemoryChainState {
-        utxos: HashMap::new(),
+        utxos: OutpointMap::default(),
         already_generated: 0,
     };
 
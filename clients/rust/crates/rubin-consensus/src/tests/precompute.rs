@@ -1,14 +1,14 @@
 use super::{encode_htlc_covenant_data, p2pk_covenant_data_for_pubkey, test_mldsa87_keypair};
-use std::collections::HashMap;
 
 use crate::block::{BlockHeader, BLOCK_HEADER_BYTES};
 use crate::block_basic::ParsedBlock;
 use crate::constants::*;
 use crate::hash::sha3_256;
-use crate::precompute::precompute_tx_contexts;
+use crate::outpoint_map::OutpointMap;
+use crate::precompute::{precompute_tx_contexts, precompute_tx_contexts_with_chain_params};
 use crate::tx::{Tx, TxInput, TxOutput, WitnessItem};
 use crate::utxo_basic::{Outpoint, UtxoEntry};
-use crate::ErrorCode;
+use crate::{ChainParams, ErrorCode};
 
 fn valid_p2pk_covenant_data() -> Vec<u8> {
     // Genesis-valid CORE_P2PK covenant_data: suite_id byte + key-id placeholder.
@@ -83,7 +83,7 @@ fn simple_coinbase() -> Tx {
 #[test]
 fn precompute_coinbase_only_block() {
     let pb = make_parsed_block(simple_coinbase(), vec![]);
-    let utxos = HashMap::new();
+    let utxos = OutpointMap::default();
     let results = precompute_tx_contexts(&pb, &utxos, 100).unwrap();
     assert!(results.is_empty());
 }
@@ -105,7 +105,7 @@ fn precompute_nil_block() {
         txids: vec![],
         wtxids: vec![],
     };
-    let err = precompute_tx_contexts(&pb, &HashMap::new(), 0);
+    let err = precompute_tx_contexts(&pb, &OutpointMap::default(), 0);
     assert!(err.is_err());
 }
 
@@ -117,7 +117,7 @@ fn precompute_single_p2pk() {
         txid: prev_txid,
         vout: 0,
     };
-    let utxos = HashMap::from([(
+    let utxos = OutpointMap::from_iter([(
         op.clone(),
         UtxoEntry {
             value: 1000,
@@ -178,7 +178,7 @@ fn precompute_core_ext_0x0102_output_rejected() {
         txid: prev_txid,
         vout: 0,
     };
-    let utxos = HashMap::from([(
+    let utxos = OutpointMap::from_iter([(
         op,
         UtxoEntry {
             value: 1000,
@@ -223,7 +223,7 @@ fn precompute_witness_cursor_parity() {
     let prev0 = sha3_256(b"utxo-0");
     let prev1 = sha3_256(b"utxo-1");
 
-    let utxos = HashMap::from([
+    let utxos = OutpointMap::from_iter([
         (
             Outpoint {
                 txid: prev0,
@@ -313,7 +313,7 @@ fn precompute_same_block_parent_child() {
     let cov_data = valid_p2pk_covenant_data();
     let prev_txid = sha3_256(b"genesis-utxo");
 
-    let utxos = HashMap::from([(
+    let utxos = OutpointMap::from_iter([(
         Outpoint {
             txid: prev_txid,
             vout: 0,
@@ -409,15 +409,84 @@ fn precompute_missing_utxo() {
         da_payload: Vec::new(),
     };
     let pb = make_parsed_block(simple_coinbase(), vec![tx]);
-    let err = precompute_tx_contexts(&pb, &HashMap::new(), 100).unwrap_err();
+    let err = precompute_tx_contexts(&pb, &OutpointMap::default(), 100).unwrap_err();
     assert_eq!(err.code.as_str(), "TX_ERR_MISSING_UTXO");
 }
 
+/// No genesis covenant reads `script_sig`: for every covenant type a 1-byte
+/// one is `TX_ERR_PARSE` on both the sequential and the precompute path
+/// (before the spend is examined), and a 10 KB one never parses.
+#[test]
+fn precompute_and_sequential_reject_script_sig_for_every_covenant_type() {
+    let prev_txid = sha3_256(b"script-sig-prevout");
+    for covenant_type in [
+        COV_TYPE_P2PK,
+        COV_TYPE_ANCHOR,
+        COV_TYPE_RESERVED_FUTURE,
+        COV_TYPE_HTLC,
+        COV_TYPE_VAULT,
+        COV_TYPE_CORE_EXT,
+        COV_TYPE_DA_COMMIT,
+        COV_TYPE_MULTISIG,
+        COV_TYPE_CORE_STEALTH,
+        COV_TYPE_CORE_SIMPLICITY,
+    ] {
+        let utxos = OutpointMap::from_iter([(
+            Outpoint {
+                txid: prev_txid,
+                vout: 0,
+            },
+            UtxoEntry {
+                value: 1000,
+                covenant_type,
+                covenant_data: vec![0u8; 32],
+                creation_height: 0,
+                created_by_coinbase: false,
+            },
+        )]);
+        let mut tx = Tx {
+            version: 1,
+            tx_kind: 0x00,
+            tx_nonce: 1,
+            inputs: vec![TxInput {
+                prev_txid,
+                prev_vout: 0,
+                script_sig: vec![0x01],
+                sequence: 0,
+            }],
+            outputs: vec![TxOutput {
+                value: 900,
+                covenant_type: COV_TYPE_P2PK,
+                covenant_data: valid_p2pk_covenant_data(),
+            }],
+            locktime: 0,
+            da_commit_core: None,
+            da_chunk_core: None,
+            witness: vec![dummy_witness()],
+            da_payload: Vec::new(),
+        };
+
+        let err = crate::apply_non_coinbase_tx_basic(&tx, [0x5a; 32], &utxos, 100, 0, [0u8; 32])
+            .unwrap_err();
+        assert_eq!(err.code, ErrorCode::TxErrParse, "{covenant_type:#06x}");
+        assert!(err.msg.contains("script_sig must be empty"), "{err}");
+        let pb = make_parsed_block(simple_coinbase(), vec![tx.clone()]);
+        let err = precompute_tx_contexts(&pb, &utxos, 100).unwrap_err();
+        assert_eq!(err.code, ErrorCode::TxErrParse, "{covenant_type:#06x}");
+        assert!(err.msg.contains("script_sig must be empty"), "{err}");
+
+        tx.inputs[0].script_sig = vec![0x01; 10 * 1024];
+        let bytes = crate::marshal_tx(&tx).expect("marshal");
+        let err = crate::parse_tx(&bytes).unwrap_err();
+        assert_eq!(err.code, ErrorCode::TxErrParse, "{covenant_type:#06x}");
+    }
+}
+
 #[test]
 fn precompute_duplicate_input() {
     let cov_data = valid_p2pk_covenant_data();
     let prev_txid = sha3_256(b"dup-input");
-    let utxos = HashMap::from([(
+    let utxos = OutpointMap::from_iter([(
         Outpoint {
             txid: prev_txid,
             vout: 0,
@@ -469,7 +538,7 @@ fn precompute_duplicate_input() {
 fn precompute_witness_underflow() {
     let cov_data = valid_p2pk_covenant_data();
     let prev_txid = sha3_256(b"witness-underflow");
-    let utxos = HashMap::from([(
+    let utxos = OutpointMap::from_iter([(
         Outpoint {
             txid: prev_txid,
             vout: 0,
@@ -513,7 +582,7 @@ fn precompute_witness_underflow() {
 fn precompute_witness_count_mismatch() {
     let cov_data = valid_p2pk_covenant_data();
     let prev_txid = sha3_256(b"witness-overflow");
-    let utxos = HashMap::from([(
+    let utxos = OutpointMap::from_iter([(
         Outpoint {
             txid: prev_txid,
             vout: 0,
@@ -557,7 +626,7 @@ fn precompute_witness_count_mismatch() {
 fn precompute_outputs_exceed_inputs() {
     let cov_data = valid_p2pk_covenant_data();
     let prev_txid = sha3_256(b"value-overflow");
-    let utxos = HashMap::from([(
+    let utxos = OutpointMap::from_iter([(
         Outpoint {
             txid: prev_txid,
             vout: 0,
@@ -600,7 +669,7 @@ fn precompute_outputs_exceed_inputs() {
 #[test]
 fn precompute_non_spendable_covenant() {
     let prev_txid = sha3_256(b"anchor-spend");
-    let utxos = HashMap::from([(
+    let utxos = OutpointMap::from_iter([(
         Outpoint {
             txid: prev_txid,
             vout: 0,
@@ -664,7 +733,7 @@ fn precompute_coinbase_prevout_forbidden() {
         da_payload: Vec::new(),
     };
     let pb = make_parsed_block(simple_coinbase(), vec![tx]);
-    let err = precompute_tx_contexts(&pb, &HashMap::new(), 100).unwrap_err();
+    let err = precompute_tx_contexts(&pb, &OutpointMap::default(), 100).unwrap_err();
     assert_eq!(err.code.as_str(), "TX_ERR_PARSE");
 }
 
@@ -687,7 +756,7 @@ fn precompute_no_inputs() {
         da_payload: Vec::new(),
     };
     let pb = make_parsed_block(simple_coinbase(), vec![tx]);
-    let err = precompute_tx_contexts(&pb, &HashMap::new(), 100).unwrap_err();
+    let err = precompute_tx_contexts(&pb, &OutpointMap::default(), 100).unwrap_err();
     assert_eq!(err.code.as_str(), "TX_ERR_PARSE");
 }
 
@@ -708,7 +777,7 @@ fn precompute_nil_like_non_coinbase_tx_rejected() {
         da_payload: Vec::new(),
     };
     let pb = make_parsed_block(simple_coinbase(), vec![tx]);
-    let err = precompute_tx_contexts(&pb, &HashMap::new(), 0).unwrap_err();
+    let err = precompute_tx_contexts(&pb, &OutpointMap::default(), 0).unwrap_err();
     assert_eq!(err.code, ErrorCode::TxErrParse);
 }
 
@@ -720,7 +789,7 @@ fn precompute_snapshot_not_mutated() {
         txid: prev_txid,
         vout: 0,
     };
-    let utxos = HashMap::from([(
+    let utxos = OutpointMap::from_iter([(
         op.clone(),
         UtxoEntry {
             value: 1000,
@@ -781,7 +850,7 @@ fn precompute_htlc_witness_slots() {
         creation_height: 0,
         created_by_coinbase: false,
     };
-    let utxos = HashMap::from([(
+    let utxos = OutpointMap::from_iter([(
         Outpoint {
             txid: prev_txid,
             vout: 0,
@@ -843,7 +912,7 @@ fn precompute_txids_txs_length_mismatch() {
         txid: prev_txid,
         vout: 0,
     };
-    let mut utxos = HashMap::new();
+    let mut utxos = OutpointMap::default();
     utxos.insert(
         op.clone(),
         UtxoEntry {
@@ -897,7 +966,7 @@ fn precompute_immature_coinbase_spend_rejected() {
         txid: prev_txid,
         vout: 0,
     };
-    let mut utxos = HashMap::new();
+    let mut utxos = OutpointMap::default();
     utxos.insert(
         op.clone(),
         UtxoEntry {
@@ -943,22 +1012,22 @@ fn precompute_immature_coinbase_spend_rejected() {
     assert_eq!(err.code, crate::error::ErrorCode::TxErrCoinbaseImmature);
 }
 
-#[test]
-fn precompute_mature_coinbase_spend_accepted() {
+/// Block spending a coinbase output created at `creation_height`.
+fn coinbase_spend_fixture(creation_height: u64) -> (ParsedBlock, OutpointMap<UtxoEntry>) {
     let cov_data = valid_p2pk_covenant_data();
     let prev_txid = sha3_256(b"coinbase-mature");
     let op = Outpoint {
         txid: prev_txid,
         vout: 0,
     };
-    let mut utxos = HashMap::new();
+    let mut utxos = OutpointMap::default();
     utxos.insert(
         op.clone(),
         UtxoEntry {
             value: 1000,
             covenant_type: COV_TYPE_P2PK,
             covenant_data: cov_data.clone(),
-            creation_height: 50,
+            creation_height,
             created_by_coinbase: true,
         },
     );
@@ -985,12 +1054,32 @@ fn precompute_mature_coinbase_spend_accepted() {
         da_payload: Vec::new(),
     };
 
-    let pb = make_parsed_block(simple_coinbase(), vec![tx]);
+    (make_parsed_block(simple_coinbase(), vec![tx]), utxos)
+}
 
+#[test]
+fn precompute_mature_coinbase_spend_accepted() {
+    let (pb, utxos) = coinbase_spend_fixture(50);
     // Block height 150: maturity gap = 150 - 50 = 100 == COINBASE_MATURITY. Should pass.
     precompute_tx_contexts(&pb, &utxos, 150).unwrap();
 }
 
+#[test]
+fn precompute_applies_chain_params_maturity() {
+    let fast = ChainParams {
+        target_block_interval: 2,
+        window_size: 16,
+        coinbase_maturity: 4,
+    };
+    let (pb, utxos) = coinbase_spend_fixture(50);
+    let err = precompute_tx_contexts_with_chain_params(&pb, &utxos, 53, &fast).unwrap_err();
+    assert_eq!(err.code, ErrorCode::TxErrCoinbaseImmature);
+    precompute_tx_contexts_with_chain_params(&pb, &utxos, 54, &fast).unwrap();
+
+    let err = precompute_tx_contexts(&pb, &utxos, 54).unwrap_err();
+    assert_eq!(err.code, ErrorCode::TxErrCoinbaseImmature);
+}
+
 #[test]
 fn add_witness_slots_overflow() {
     use crate::precompute::add_witness_slots;
@@ -1025,7 +1114,7 @@ fn add_witness_slots_overflow() {
 #[test]
 fn precompute_core_simplicity_0x0106_spend_rejects_ahead_of_witness_errors() {
     let prev_txid = sha3_256(b"simplicity-spend");
-    let utxos = HashMap::from([(
+    let utxos = OutpointMap::from_iter([(
         Outpoint {
             txid: prev_txid,
             vout: 0,
@@ -1080,7 +1169,7 @@ fn precompute_core_simplicity_0x0106_spend_rejects_ahead_of_witness_errors() {
 fn precompute_core_simplicity_0x0106_spend_wins_over_trailing_input_error() {
     let simp_prev = sha3_256(b"simplicity-first-input");
     let missing_prev = sha3_256(b"missing-second-input");
-    let utxos = HashMap::from([(
+    let utxos = OutpointMap::from_iter([(
         Outpoint {
             txid: simp_prev,
             vout: 0,
@@ -690,3 +690,158 @@ fn test_descriptor_sunset_at_spend_rejected() {
     };
     assert!(d.validate(&r).is_err());
 }
+
+const FAKE_SUITE_ID: u8 = 0x42;
+const FAKE_VERIFY_COST: u64 = 777;
+
+/// Test-only registry: the production ML-DSA-87 entry plus a fake suite with
+/// its own lengths, cost and algorithm name.
+fn fake_suite_registry() -> SuiteRegistry {
+    let mut suites = SuiteRegistry::default_registry().suites;
+    suites.insert(
+        FAKE_SUITE_ID,
+        SuiteParams {
+            suite_id: FAKE_SUITE_ID,
+            pubkey_len: 8,
+            sig_len: 16,
+            verify_cost: FAKE_VERIFY_COST,
+            alg_name: "FAKE-SUITE",
+        },
+    );
+    SuiteRegistry::with_suites(suites)
+}
+
+struct FakeSpendRotation;
+
+impl RotationProvider for FakeSpendRotation {
+    fn native_create_suites(&self, _height: u64) -> NativeSuiteSet {
+        NativeSuiteSet::new(&[SUITE_ID_ML_DSA_87, FAKE_SUITE_ID])
+    }
+
+    fn native_spend_suites(&self, _height: u64) -> NativeSuiteSet {
+        NativeSuiteSet::new(&[SUITE_ID_ML_DSA_87, FAKE_SUITE_ID])
+    }
+}
+
+fn tx_with_witness(suite_id: u8, pubkey_len: usize, sig_len: usize) -> crate::tx::Tx {
+    crate::tx::Tx {
+        version: crate::constants::TX_WIRE_VERSION,
+        tx_kind: 0x00,
+        tx_nonce: 1,
+        inputs: vec![crate::tx::TxInput {
+            prev_txid: [0x11; 32],
+            prev_vout: 0,
+            script_sig: Vec::new(),
+            sequence: 0,
+        }],
+        outputs: Vec::new(),
+        locktime: 0,
+        da_commit_core: None,
+        da_chunk_core: None,
+        witness: vec![crate::tx::WitnessItem {
+            suite_id,
+            pubkey: vec![0u8; pubkey_len],
+            signature: vec![0u8; sig_len],
+        }],
+        da_payload: Vec::new(),
+    }
+}
+
+#[test]
+fn test_fake_suite_cost_is_gated_by_spend_set_and_read_from_registry() {
+    use crate::block_basic::tx_weight_and_stats_at_height;
+    use crate::constants::VERIFY_COST_UNKNOWN_SUITE;
+
+    let registry = fake_suite_registry();
+    let canonical = tx_with_witness(FAKE_SUITE_ID, 8, 17);
+    let weight = |tx: &crate::tx::Tx, rotation: &dyn RotationProvider| {
+        tx_weight_and_stats_at_height(tx, 1, Some(rotation), Some(&registry))
+            .expect("weight")
+            .0
+    };
+
+    let gated_in = weight(&canonical, &FakeSpendRotation);
+    let gated_out = weight(&canonical, &DefaultRotationProvider);
+    assert_eq!(
+        gated_in - gated_out,
+        FAKE_VERIFY_COST - VERIFY_COST_UNKNOWN_SUITE
+    );
+
+    // Off-shape witnesses of a registered suite carry no verify cost.
+    let off_shape = tx_with_witness(FAKE_SUITE_ID, 8, 16);
+    let base = weight(&off_shape, &FakeSpendRotation);
+    let (legacy, _, _) = tx_weight_and_stats_at_height(&off_shape, 1, None, None).expect("legacy");
+    assert_eq!(legacy - base, VERIFY_COST_UNKNOWN_SUITE);
+}
+
+#[test]
+fn test_legacy_weight_matches_default_registry_table() {
+    use crate::block_basic::tx_weight_and_stats_at_height;
+
+    let registry = SuiteRegistry::default_registry();
+    for (suite_id, pubkey_len, sig_len) in [
+        (
+            SUITE_ID_ML_DSA_87,
+            ML_DSA_87_PUBKEY_BYTES as usize,
+            ML_DSA_87_SIG_BYTES as usize + 1,
+        ),
+        (
+            SUITE_ID_ML_DSA_87,
+            ML_DSA_87_PUBKEY_BYTES as usize,
+            ML_DSA_87_SIG_BYTES as usize,
+        ),
+        (FAKE_SUITE_ID, 8, 17),
+        (SUITE_ID_SIMPLICITY_ENVELOPE, 0, 2),
+        (crate::constants::SUITE_ID_SENTINEL, 0, 0),
+    ] {
+        let tx = tx_with_witness(suite_id, pubkey_len, sig_len);
+        assert_eq!(
+            tx_weight_and_stats_at_height(&tx, 1, None, None).expect("legacy"),
+            tx_weight_and_stats_at_height(&tx, 1, Some(&DefaultRotationProvider), Some(&registry))
+                .expect("registry"),
+            "suite 0x{suite_id:02x} pubkey={pubkey_len} sig={sig_len}"
+        );
+    }
+}
+
+#[test]
+fn test_parse_shape_check_reads_production_registry_only() {
+    use crate::{marshal_tx, parse_tx};
+
+    // A suite absent from the production table is left to spend-time gating.
+    let fake = tx_with_witness(FAKE_SUITE_ID, 3, 5);
+    parse_tx(&marshal_tx(&fake).expect("marshal")).expect("unknown suite parses");
+
+    let bad_mldsa = tx_with_witness(SUITE_ID_ML_DSA_87, 8, 17);
+    let err = parse_tx(&marshal_tx(&bad_mldsa).expect("marshal")).unwrap_err();
+    assert_eq!(err.code, crate::error::ErrorCode::TxErrSigNoncanonical);
+    assert_eq!(err.msg, "non-canonical ML-DSA witness item lengths");
+}
+
+#[test]
+fn test_fake_suite_dispatch_resolves_through_registry() {
+    use crate::error::ErrorCode;
+    use crate::verify_sig_with_registry;
+
+    let registry = fake_suite_registry();
+    let digest = [0u8; 32];
+
+    // Registered: the lookup succeeds and dispatch reaches verifier binding,
+    // which has no live binding for the fake algorithm.
+    let err = verify_sig_with_registry(FAKE_SUITE_ID, &[0; 8], &[0; 16], &digest, Some(&registry))
+        .unwrap_err();
+    assert_ne!(err.msg, "verify_sig: unsupported suite_id");
+
+    // Unregistered, in the test registry and in the production default.
+    for registry in [Some(&registry), None] {
+        let err = verify_sig_with_registry(0x43, &[0; 8], &[0; 16], &digest, registry).unwrap_err();
+        assert_eq!(err.code, ErrorCode::TxErrSigAlgInvalid);
+        assert_eq!(err.msg, "verify_sig: unsupported suite_id");
+    }
+    let err =
+        verify_sig_with_registry(FAKE_SUITE_ID, &[0; 8], &[0; 16], &digest, None).unwrap_err();
+    assert_eq!(err.code, ErrorCode::TxErrSigAlgInvalid);
+
+    assert!(default_suite_registry().is_canonical_default_live_manifest());
+    assert!(!default_native_spend_suites().contains(FAKE_SUITE_ID));
+}
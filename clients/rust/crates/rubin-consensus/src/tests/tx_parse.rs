@@ -431,6 +431,52 @@ fn witness_merkle_root_single_uses_zero_coinbase_id() {
     assert_eq!(root, sha3_256(&leaf_preimage));
 }
 
+#[test]
+fn merkle_roots_match_level_by_level_reference() {
+    fn reference(ids: &[[u8; 32]], leaf_tag: u8, node_tag: u8) -> [u8; 32] {
+        let mut level: Vec<[u8; 32]> = ids
+            .iter()
+            .map(|id| {
+                let mut preimage = vec![leaf_tag];
+                preimage.extend_from_slice(id);
+                sha3_256(&preimage)
+            })
+            .collect();
+        while level.len() > 1 {
+            level = level
+                .chunks(2)
+                .map(|pair| match pair {
+                    [left, right] => {
+                        let mut preimage = vec![node_tag];
+                        preimage.extend_from_slice(left);
+                        preimage.extend_from_slice(right);
+                        sha3_256(&preimage)
+                    }
+                    [odd] => *odd,
+                    _ => unreachable!(),
+                })
+                .collect();
+        }
+        level[0]
+    }
+
+    for n in 1..=17u8 {
+        let ids: Vec<[u8; 32]> = (1..=n).map(|i| [i; 32]).collect();
+        assert_eq!(
+            merkle_root_txids(&ids).expect("root"),
+            reference(&ids, 0x00, 0x01),
+            "n={n}"
+        );
+        let mut witness_ids = ids.clone();
+        witness_ids[0] = [0u8; 32];
+        assert_eq!(
+            witness_merkle_root_wtxids(&ids).expect("witness root"),
+            reference(&witness_ids, 0x02, 0x03),
+            "n={n}"
+        );
+    }
+}
+
 #[test]
 fn witness_commitment_hash_uses_prefix() {
     let mut root = [0u8; 32];
@@ -1,14 +1,17 @@
-use std::collections::HashMap;
-
 use crate::block::{BlockHeader, BLOCK_HEADER_BYTES};
 use crate::block_basic::ParsedBlock;
 use crate::constants::*;
 use crate::error::ErrorCode;
 use crate::hash::sha3_256;
+use crate::outpoint_map::OutpointMap;
 use crate::precompute::{precompute_tx_contexts, PrecomputedTxContext};
+use crate::suite_registry::{
+    DefaultRotationProvider, NativeSuiteSet, RotationProvider, SuiteParams, SuiteRegistry,
+};
 use crate::tx::{Tx, TxInput, TxOutput, WitnessItem};
 use crate::tx_validate_worker::{
-    first_tx_error, run_tx_validation_workers, validate_tx_local, TxValidationResult,
+    first_tx_error, run_tx_validation_workers, validate_input_authorization,
+    validate_input_authorization_with_suite_context, validate_tx_local, TxValidationResult,
 };
 use crate::utxo_basic::{Outpoint, UtxoEntry};
 use crate::worker_pool::{WorkerCancellationToken, WorkerPoolError, WorkerResult};
@@ -112,8 +115,8 @@ fn simple_p2pk_tx(prev_txid_seed: u8) -> Tx {
     }
 }
 
-fn make_utxo_snapshot_for_tx(tx: &Tx, value: u64) -> HashMap<Outpoint, UtxoEntry> {
-    let mut snap = HashMap::new();
+fn make_utxo_snapshot_for_tx(tx: &Tx, value: u64) -> OutpointMap<UtxoEntry> {
+    let mut snap = OutpointMap::default();
     for input in &tx.inputs {
         snap.insert(
             Outpoint {
@@ -140,7 +143,7 @@ fn precompute_single_tx(
 ) -> (
     ParsedBlock,
     Vec<PrecomputedTxContext>,
-    HashMap<Outpoint, UtxoEntry>,
+    OutpointMap<UtxoEntry>,
 ) {
     let snapshot = make_utxo_snapshot_for_tx(&tx, input_value);
     let pb = make_parsed_block(simple_coinbase(), vec![tx]);
@@ -227,6 +230,106 @@ fn validate_tx_local_default_covenant_passthrough() {
     assert!(r.err.is_none());
 }
 
+#[test]
+fn validate_input_authorization_checks_index_and_witness_window() {
+    let tx = simple_p2pk_tx(0x42);
+    let snap = make_utxo_snapshot_for_tx(&tx, 100);
+    let entry = snap.values().next().expect("prevout");
+
+    let err = validate_input_authorization(&tx, 1, entry, 0, [0u8; 32], 100, 0)
+        .expect_err("index out of range");
+    assert_eq!(err.code, ErrorCode::TxErrParse);
+    assert_eq!(err.msg, "input index out of range");
+
+    // The single witness item belongs to an earlier input.
+    let err = validate_input_authorization(&tx, 0, entry, 1, [0u8; 32], 100, 0)
+        .expect_err("witness underflow");
+    assert_eq!(err.code, ErrorCode::TxErrParse);
+    assert!(err.msg.contains("witness underflow"));
+
+    let mut anchor = entry.clone();
+    anchor.covenant_type = COV_TYPE_ANCHOR;
+    let err = validate_input_authorization(&tx, 0, &anchor, 0, [0u8; 32], 100, 0)
+        .expect_err("anchor has no witness slots");
+    assert_eq!(err.code, ErrorCode::TxErrCovenantTypeInvalid);
+}
+
+struct WithSuite(u8);
+
+impl RotationProvider for WithSuite {
+    fn native_create_suites(&self, _height: u64) -> NativeSuiteSet {
+        NativeSuiteSet::new(&[SUITE_ID_ML_DSA_87, self.0])
+    }
+
+    fn native_spend_suites(&self, _height: u64) -> NativeSuiteSet {
+        NativeSuiteSet::new(&[SUITE_ID_ML_DSA_87, self.0])
+    }
+}
+
+#[test]
+fn validate_input_authorization_gates_and_shapes_from_suite_context() {
+    const FAKE: u8 = 0x42;
+    let mut suites = std::collections::BTreeMap::new();
+    suites.insert(
+        FAKE,
+        SuiteParams {
+            suite_id: FAKE,
+            pubkey_len: 8,
+            sig_len: 16,
+            verify_cost: 1,
+            alg_name: "FAKE-SUITE",
+        },
+    );
+    let registry = SuiteRegistry::with_suites(suites);
+
+    let pubkey = vec![0x5a; 8];
+    let mut signature = vec![0u8; 16];
+    signature.push(SIGHASH_ALL);
+    let mut tx = simple_p2pk_tx(0x42);
+    tx.witness = vec![WitnessItem {
+        suite_id: FAKE,
+        pubkey: pubkey.clone(),
+        signature,
+    }];
+    let mut covenant_data = vec![FAKE];
+    covenant_data.extend_from_slice(&sha3_256(&pubkey));
+    let entry = UtxoEntry {
+        value: 100,
+        covenant_type: COV_TYPE_P2PK,
+        covenant_data,
+        creation_height: 1,
+        created_by_coinbase: false,
+    };
+    let check = |tx: &Tx, rotation: &dyn RotationProvider, registry: &SuiteRegistry| {
+        validate_input_authorization_with_suite_context(
+            tx, 0, &entry, 0, [0u8; 32], 100, 0, rotation, registry,
+        )
+        .unwrap_err()
+    };
+
+    // Unknown to the default context: gated before any shape or crypto work.
+    let err = validate_input_authorization(&tx, 0, &entry, 0, [0u8; 32], 100, 0).unwrap_err();
+    assert_eq!(err.code, ErrorCode::TxErrSigAlgInvalid);
+    let err = check(&tx, &DefaultRotationProvider, &registry);
+    assert_eq!(err.code, ErrorCode::TxErrSigAlgInvalid);
+    assert_eq!(err.msg, "CORE_P2PK suite not in native spend set");
+    let err = check(&tx, &WithSuite(FAKE), &SuiteRegistry::default_registry());
+    assert_eq!(err.msg, "CORE_P2PK suite not registered");
+
+    // In the spend set and registered: the shape rule is the fake suite's.
+    let mut short = tx.clone();
+    short.witness[0].signature.pop();
+    let err = check(&short, &WithSuite(FAKE), &registry);
+    assert_eq!(err.code, ErrorCode::TxErrSigNoncanonical);
+
+    // Canonical shape: past gating and key binding, into verifier dispatch.
+    let err = check(&tx, &WithSuite(FAKE), &registry);
+    assert_ne!(err.code, ErrorCode::TxErrSigNoncanonical);
+    assert_ne!(err.msg, "CORE_P2PK suite not in native spend set");
+    assert_ne!(err.msg, "CORE_P2PK suite not registered");
+    assert_ne!(err.msg, "CORE_P2PK key binding mismatch");
+}
+
 /// Covers validate_input_spend → COV_TYPE_P2PK branch. The dummy signature
 /// will fail inside validate_p2pk_spend_at_height, but the branch is entered
 /// and lines 176-194 of tx_validate_worker.rs are exercised.
@@ -498,7 +601,7 @@ fn validate_tx_local_real_signature_full_path() {
         vout: 0,
     };
 
-    let mut utxo_map = HashMap::new();
+    let mut utxo_map = OutpointMap::default();
     utxo_map.insert(
         outpoint.clone(),
         UtxoEntry {
@@ -674,7 +777,7 @@ fn run_tx_validation_workers_with_sig_cache_reuses_positive_result() {
         vout: 0,
     };
 
-    let mut utxo_map = HashMap::new();
+    let mut utxo_map = OutpointMap::default();
     utxo_map.insert(
         outpoint,
         UtxoEntry {
@@ -806,7 +909,7 @@ fn run_tx_validation_workers_valid_p2pk_real_signature() {
         vout: 0,
     };
 
-    let mut utxo_map = HashMap::new();
+    let mut utxo_map = OutpointMap::default();
     utxo_map.insert(
         outpoint,
         UtxoEntry {
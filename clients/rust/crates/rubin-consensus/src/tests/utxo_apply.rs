@@ -7,7 +7,7 @@ fn apply_non_coinbase_tx_basic_missing_utxo() {
     let tx_bytes =
         tx_with_one_input_one_output(prev, 0, 1, COV_TYPE_P2PK, &valid_p2pk_covenant_data());
     let (tx, txid, _wtxid, _n) = parse_tx(&tx_bytes).expect("parse");
-    let utxos: HashMap<Outpoint, UtxoEntry> = HashMap::new();
+    let utxos: OutpointMap<UtxoEntry> = OutpointMap::default();
 
     let err = apply_non_coinbase_tx_basic(&tx, txid, &utxos, 100, 1000, ZERO_CHAIN_ID).unwrap_err();
     assert_eq!(err.code, ErrorCode::TxErrMissingUtxo);
@@ -21,7 +21,7 @@ fn apply_non_coinbase_tx_basic_spend_anchor_rejected() {
         tx_with_one_input_one_output(prev, 0, 1, COV_TYPE_P2PK, &valid_p2pk_covenant_data());
     let (tx, txid, _wtxid, _n) = parse_tx(&tx_bytes).expect("parse");
 
-    let mut utxos: HashMap<Outpoint, UtxoEntry> = HashMap::new();
+    let mut utxos: OutpointMap<UtxoEntry> = OutpointMap::default();
     utxos.insert(
         Outpoint {
             txid: prev,
@@ -49,7 +49,7 @@ fn apply_non_coinbase_tx_basic_zero_witness_count_rejected() {
     let (mut tx, txid, _wtxid, _n) = parse_tx(&tx_bytes).expect("parse");
     tx.witness.clear();
 
-    let mut utxos: HashMap<Outpoint, UtxoEntry> = HashMap::new();
+    let mut utxos: OutpointMap<UtxoEntry> = OutpointMap::default();
     utxos.insert(
         Outpoint {
             txid: prev,
@@ -76,7 +76,7 @@ fn apply_non_coinbase_tx_basic_unknown_covenant_spend_rejected() {
         tx_with_one_input_one_output(prev, 0, 90, COV_TYPE_P2PK, &valid_p2pk_covenant_data());
     let (tx, txid, _wtxid, _n) = parse_tx(&tx_bytes).expect("parse");
 
-    let mut utxos: HashMap<Outpoint, UtxoEntry> = HashMap::new();
+    let mut utxos: OutpointMap<UtxoEntry> = OutpointMap::default();
     utxos.insert(
         Outpoint {
             txid: prev,
@@ -128,7 +128,7 @@ fn apply_non_coinbase_tx_basic_value_conservation() {
     };
     tx.witness = vec![sign_input_witness(&tx, 0, 100, ZERO_CHAIN_ID, &kp)];
 
-    let mut utxos: HashMap<Outpoint, UtxoEntry> = HashMap::new();
+    let mut utxos: OutpointMap<UtxoEntry> = OutpointMap::default();
     utxos.insert(
         Outpoint {
             txid: prev,
@@ -194,7 +194,7 @@ fn connect_block_basic_in_memory_at_height_ok_computes_fees_and_updates_state()
     let (_t, spend_txid, _wtxid, _n) = parse_tx(&spend_bytes).expect("parse spend tx");
 
     let mut state = crate::connect_block_inmem::InMemoryChainState {
-        utxos: HashMap::new(),
+        utxos: OutpointMap::default(),
         already_generated: 0,
     };
     state.utxos.insert(
@@ -287,7 +287,7 @@ fn connect_block_basic_in_memory_at_height_rejects_subsidy_exceeded() {
     let (_t, spend_txid, _wtxid, _n) = parse_tx(&spend_bytes).expect("parse spend tx");
 
     let mut state = crate::connect_block_inmem::InMemoryChainState {
-        utxos: HashMap::new(),
+        utxos: OutpointMap::default(),
         already_generated: 0,
     };
     state.utxos.insert(
@@ -378,7 +378,7 @@ fn connect_block_basic_in_memory_at_height_rejects_coinbase_vault_output() {
     let block = build_block_bytes(prev, root, target, 51, &[coinbase]);
 
     let mut state = crate::connect_block_inmem::InMemoryChainState {
-        utxos: HashMap::new(),
+        utxos: OutpointMap::default(),
         already_generated: 0,
     };
     let err = crate::connect_block_basic_in_memory_at_height(
@@ -453,7 +453,7 @@ fn connect_block_basic_in_memory_at_height_coinbase_vault_reject_does_not_mutate
     let block = build_block_bytes(prev, root, target, 52, &[coinbase, spend_bytes]);
 
     let mut state = crate::connect_block_inmem::InMemoryChainState {
-        utxos: HashMap::new(),
+        utxos: OutpointMap::default(),
         already_generated: 0,
     };
     let prev_out = Outpoint {
@@ -551,7 +551,7 @@ fn apply_non_coinbase_tx_basic_ok() {
     };
     tx.witness = vec![sign_input_witness(&tx, 0, 100, ZERO_CHAIN_ID, &kp)];
 
-    let mut utxos: HashMap<Outpoint, UtxoEntry> = HashMap::new();
+    let mut utxos: OutpointMap<UtxoEntry> = OutpointMap::default();
     utxos.insert(
         Outpoint {
             txid: prev,
@@ -634,7 +634,7 @@ fn apply_non_coinbase_tx_basic_vault_cannot_fund_fee() {
         sign_input_witness(&tx, 1, 10, ZERO_CHAIN_ID, &owner_kp),
     ];
 
-    let mut utxos: HashMap<Outpoint, UtxoEntry> = HashMap::new();
+    let mut utxos: OutpointMap<UtxoEntry> = OutpointMap::default();
     utxos.insert(
         Outpoint {
             txid: prev_vault,
@@ -728,7 +728,7 @@ fn apply_non_coinbase_tx_basic_vault_preserved_with_owner_fee_input() {
         sign_input_witness(&tx, 1, 10, ZERO_CHAIN_ID, &owner_kp),
     ];
 
-    let mut utxos: HashMap<Outpoint, UtxoEntry> = HashMap::new();
+    let mut utxos: OutpointMap<UtxoEntry> = OutpointMap::default();
     utxos.insert(
         Outpoint {
             txid: prev_vault,
@@ -823,7 +823,7 @@ fn apply_non_coinbase_tx_basic_vault_allows_owner_top_up() {
         sign_input_witness(&tx, 1, 10, ZERO_CHAIN_ID, &owner_kp),
     ];
 
-    let mut utxos: HashMap<Outpoint, UtxoEntry> = HashMap::new();
+    let mut utxos: OutpointMap<UtxoEntry> = OutpointMap::default();
     utxos.insert(
         Outpoint {
             txid: prev_vault,
@@ -920,7 +920,7 @@ fn apply_non_coinbase_tx_basic_htlc_then_p2pk_cursor_handling() {
         sign_input_witness(&tx, 1, 70, ZERO_CHAIN_ID, &p2pk_kp),
     ];
 
-    let mut utxos: HashMap<Outpoint, UtxoEntry> = HashMap::new();
+    let mut utxos: OutpointMap<UtxoEntry> = OutpointMap::default();
     utxos.insert(
         Outpoint {
             txid: prev_htlc,
@@ -1005,7 +1005,7 @@ fn apply_non_coinbase_tx_basic_htlc_timestamp_uses_mtp() {
     };
     tx.witness[1] = sign_input_witness(&tx, 0, 100, ZERO_CHAIN_ID, &refund_kp);
 
-    let mut utxos: HashMap<Outpoint, UtxoEntry> = HashMap::new();
+    let mut utxos: OutpointMap<UtxoEntry> = OutpointMap::default();
     utxos.insert(
         Outpoint {
             txid: prev,
@@ -1102,7 +1102,7 @@ fn apply_non_coinbase_tx_basic_vault_whitelist_rejects_output() {
         sign_input_witness(&tx, 1, 10, ZERO_CHAIN_ID, &owner_kp),
     ];
 
-    let mut utxos: HashMap<Outpoint, UtxoEntry> = HashMap::new();
+    let mut utxos: OutpointMap<UtxoEntry> = OutpointMap::default();
     utxos.insert(
         Outpoint {
             txid: prev_vault,
@@ -1199,7 +1199,7 @@ fn apply_non_coinbase_tx_basic_vault_rejects_unassigned_core_ext_destination() {
         sign_input_witness(&tx, 1, 10, ZERO_CHAIN_ID, &owner_kp),
     ];
 
-    let mut utxos: HashMap<Outpoint, UtxoEntry> = HashMap::new();
+    let mut utxos: OutpointMap<UtxoEntry> = OutpointMap::default();
     utxos.insert(
         Outpoint {
             txid: prev_vault,
@@ -1291,7 +1291,7 @@ fn apply_non_coinbase_tx_basic_vault_recursion_rejected() {
         sign_input_witness(&tx, 1, 10, ZERO_CHAIN_ID, &owner_kp),
     ];
 
-    let mut utxos: HashMap<Outpoint, UtxoEntry> = HashMap::new();
+    let mut utxos: OutpointMap<UtxoEntry> = OutpointMap::default();
     utxos.insert(
         Outpoint {
             txid: prev_vault,
@@ -1361,7 +1361,7 @@ fn apply_non_coinbase_tx_basic_multisig_input_accepted() {
     };
     tx.witness = vec![sign_input_witness(&tx, 0, 100, ZERO_CHAIN_ID, &ms_kp)];
 
-    let mut utxos: HashMap<Outpoint, UtxoEntry> = HashMap::new();
+    let mut utxos: OutpointMap<UtxoEntry> = OutpointMap::default();
     utxos.insert(
         Outpoint {
             txid: prev,
@@ -1471,7 +1471,7 @@ fn apply_non_coinbase_tx_basic_coinbase_maturity_overflow_safe() {
     let (tx, txid, _wtxid, _n) = parse_tx(&tx_bytes).expect("parse");
 
     let near_max: u64 = u64::MAX - 10;
-    let mut utxos: HashMap<Outpoint, UtxoEntry> = HashMap::new();
+    let mut utxos: OutpointMap<UtxoEntry> = OutpointMap::default();
     utxos.insert(
         Outpoint {
             txid: prev,
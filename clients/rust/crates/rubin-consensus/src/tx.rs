@@ -1,7 +1,9 @@
-use crate::compactsize::read_compact_size;
+use crate::compactsize::{read_compact_size, read_compact_size_bounded};
 use crate::constants::*;
+use crate::da::check_da_chunk_count;
 use crate::error::{ErrorCode, TxError};
 use crate::hash::sha3_256;
+use crate::suite_registry::default_suite_registry;
 use crate::wire_read::Reader;
 
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -65,15 +67,48 @@ pub struct DaChunkCore {
 /// Callers that need stable identifiers must hash `b[..core_end]` for `txid`
 /// and `b[..total_end]` for `wtxid`; this helper only parses wire structure.
 pub(crate) fn parse_tx_without_hashes(b: &[u8]) -> Result<(Tx, usize, usize), TxError> {
+    parse_tx_from_reader(&mut Reader::new(b))
+}
+
+/// [`parse_tx`] over a prefix of a transaction that is still arriving.
+pub(crate) enum PartialTx {
+    Complete {
+        tx: Box<Tx>,
+        txid: [u8; 32],
+        wtxid: [u8; 32],
+        consumed: usize,
+    },
+    /// `b` ends inside the transaction; parsing cannot progress until at
+    /// least this many bytes are available.
+    NeedBytes(usize),
+}
+
+/// Parses the transaction at the front of `b`, which may be cut short. Only
+/// running out of `b` itself yields [`PartialTx::NeedBytes`]; every other
+/// failure, including a truncated structure nested in a complete field, is
+/// the transaction's error.
+pub(crate) fn parse_tx_partial(b: &[u8]) -> Result<PartialTx, TxError> {
     let mut r = Reader::new(b);
-    let (version, tx_kind, tx_nonce) = parse_tx_prefix(&mut r)?;
-    let inputs = parse_tx_inputs(&mut r)?;
-    let outputs = parse_tx_outputs(&mut r)?;
+    match parse_tx_from_reader(&mut r) {
+        Ok((tx, core_end, total_end)) => Ok(PartialTx::Complete {
+            tx: Box::new(tx),
+            txid: sha3_256(&b[..core_end]),
+            wtxid: sha3_256(&b[..total_end]),
+            consumed: total_end,
+        }),
+        Err(err) => r.eof_needed().map(PartialTx::NeedBytes).ok_or(err),
+    }
+}
+
+fn parse_tx_from_reader(r: &mut Reader<'_>) -> Result<(Tx, usize, usize), TxError> {
+    let (version, tx_kind, tx_nonce) = parse_tx_prefix(r)?;
+    let inputs = parse_tx_inputs(r)?;
+    let outputs = parse_tx_outputs(r)?;
     let locktime = r.read_u32_le()?;
-    let (da_commit_core, da_chunk_core) = parse_da_core(&mut r, tx_kind)?;
+    let (da_commit_core, da_chunk_core) = parse_da_core(r, tx_kind)?;
     let core_end = r.offset();
-    let witness = parse_witnesses(&mut r)?;
-    let da_payload = parse_da_payload(&mut r, tx_kind)?;
+    let witness = parse_witnesses(r)?;
+    let da_payload = parse_da_payload(r, tx_kind)?;
     let total_end = r.offset();
 
     let tx = Tx {
@@ -109,28 +144,27 @@ fn parse_tx_prefix(r: &mut Reader<'_>) -> Result<(u32, u8, u64), TxError> {
 }
 
 fn parse_tx_inputs(r: &mut Reader<'_>) -> Result<Vec<TxInput>, TxError> {
-    let (in_count, _) = read_compact_size(r)?;
-    if in_count > MAX_TX_INPUTS {
-        return Err(TxError::new(ErrorCode::TxErrParse, "input_count overflow"));
-    }
-    let mut inputs = Vec::with_capacity(in_count as usize);
-    for _ in 0..in_count as usize {
+    let in_count = r.read_compact_size_usize(MAX_TX_INPUTS as usize, "input_count overflow")?;
+    let mut inputs = Vec::with_capacity(bounded_capacity(in_count, r));
+    for _ in 0..in_count {
         inputs.push(parse_tx_input(r)?);
     }
     Ok(inputs)
 }
 
+/// Every element consumes at least one byte, so capping the reservation at
+/// the unread length keeps it proportional to the input rather than to a
+/// count the input claims but does not carry.
+fn bounded_capacity(count: usize, r: &Reader<'_>) -> usize {
+    count.min(r.remaining())
+}
+
 fn parse_tx_input(r: &mut Reader<'_>) -> Result<TxInput, TxError> {
     let prev_txid = read_32(r)?;
     let prev_vout = r.read_u32_le()?;
-    let (script_sig_len, _) = read_compact_size(r)?;
-    if script_sig_len > MAX_SCRIPT_SIG_BYTES {
-        return Err(TxError::new(
-            ErrorCode::TxErrParse,
-            "script_sig_len overflow",
-        ));
-    }
-    let script_sig = r.read_bytes(script_sig_len as usize)?.to_vec();
+    let script_sig_len =
+        r.read_compact_size_usize(MAX_SCRIPT_SIG_BYTES as usize, "script_sig_len overflow")?;
+    let script_sig = r.read_bytes(script_sig_len)?.to_vec();
     let sequence = r.read_u32_le()?;
     Ok(TxInput {
         prev_txid,
@@ -141,12 +175,9 @@ fn parse_tx_input(r: &mut Reader<'_>) -> Result<TxInput, TxError> {
 }
 
 fn parse_tx_outputs(r: &mut Reader<'_>) -> Result<Vec<TxOutput>, TxError> {
-    let (out_count, _) = read_compact_size(r)?;
-    if out_count > MAX_TX_OUTPUTS {
-        return Err(TxError::new(ErrorCode::TxErrParse, "output_count overflow"));
-    }
-    let mut outputs = Vec::with_capacity(out_count as usize);
-    for _ in 0..out_count as usize {
+    let out_count = r.read_compact_size_usize(MAX_TX_OUTPUTS as usize, "output_count overflow")?;
+    let mut outputs = Vec::with_capacity(bounded_capacity(out_count, r));
+    for _ in 0..out_count {
         outputs.push(parse_tx_output(r)?);
     }
     Ok(outputs)
@@ -155,20 +186,11 @@ fn parse_tx_outputs(r: &mut Reader<'_>) -> Result<Vec<TxOutput>, TxError> {
 fn parse_tx_output(r: &mut Reader<'_>) -> Result<TxOutput, TxError> {
     let value = r.read_u64_le()?;
     let covenant_type = r.read_u16_le()?;
-    let (cov_len_u64, _) = read_compact_size(r)?;
-    if cov_len_u64 > usize::MAX as u64 {
-        return Err(TxError::new(
-            ErrorCode::TxErrParse,
-            "covenant_data_len overflows usize",
-        ));
-    }
-    if cov_len_u64 > MAX_COVENANT_DATA_PER_OUTPUT {
-        return Err(TxError::new(
-            ErrorCode::TxErrParse,
-            "covenant_data_len exceeds MAX_COVENANT_DATA_PER_OUTPUT",
-        ));
-    }
-    let covenant_data = r.read_bytes(cov_len_u64 as usize)?.to_vec();
+    let cov_len = r.read_compact_size_usize(
+        MAX_COVENANT_DATA_PER_OUTPUT as usize,
+        "covenant_data_len exceeds MAX_COVENANT_DATA_PER_OUTPUT",
+    )?;
+    let covenant_data = r.read_bytes(cov_len)?.to_vec();
     Ok(TxOutput {
         value,
         covenant_type,
@@ -216,12 +238,7 @@ struct DaCommitFields {
 
 fn read_da_commit_chunk_count(r: &mut Reader<'_>) -> Result<u16, TxError> {
     let chunk_count = r.read_u16_le()?;
-    if chunk_count == 0 || (chunk_count as u64) > MAX_DA_CHUNK_COUNT {
-        return Err(TxError::new(
-            ErrorCode::TxErrParse,
-            "chunk_count out of range for tx_kind=0x01",
-        ));
-    }
+    check_da_chunk_count(u64::from(chunk_count), MAX_DA_CHUNK_COUNT)?;
     Ok(chunk_count)
 }
 
@@ -236,14 +253,11 @@ fn read_da_commit_fields(r: &mut Reader<'_>) -> Result<DaCommitFields, TxError>
 }
 
 fn read_da_batch_sig(r: &mut Reader<'_>) -> Result<Vec<u8>, TxError> {
-    let (batch_sig_len_u64, _) = read_compact_size(r)?;
-    if batch_sig_len_u64 > MAX_DA_MANIFEST_BYTES_PER_TX || batch_sig_len_u64 > usize::MAX as u64 {
-        return Err(TxError::new(
-            ErrorCode::TxErrParse,
-            "batch_sig_len overflow",
-        ));
-    }
-    Ok(r.read_bytes(batch_sig_len_u64 as usize)?.to_vec())
+    let batch_sig_len = r.read_compact_size_usize(
+        MAX_DA_MANIFEST_BYTES_PER_TX as usize,
+        "batch_sig_len overflow",
+    )?;
+    Ok(r.read_bytes(batch_sig_len)?.to_vec())
 }
 
 fn parse_da_chunk_core(r: &mut Reader<'_>) -> Result<DaChunkCore, TxError> {
@@ -264,16 +278,15 @@ fn parse_da_chunk_core(r: &mut Reader<'_>) -> Result<DaChunkCore, TxError> {
 }
 
 fn parse_witnesses(r: &mut Reader<'_>) -> Result<Vec<WitnessItem>, TxError> {
-    let (witness_count_u64, witness_count_varint_bytes) = read_compact_size(r)?;
-    if witness_count_u64 > MAX_WITNESS_ITEMS {
-        return Err(TxError::new(
-            ErrorCode::TxErrWitnessOverflow,
-            "witness_count overflow",
-        ));
-    }
+    let (witness_count, witness_count_varint_bytes) = read_compact_size_bounded(
+        r,
+        MAX_WITNESS_ITEMS as usize,
+        ErrorCode::TxErrWitnessOverflow,
+        "witness_count overflow",
+    )?;
     let mut witness_bytes = witness_count_varint_bytes;
-    let mut witness = Vec::with_capacity(witness_count_u64 as usize);
-    for _ in 0..witness_count_u64 as usize {
+    let mut witness = Vec::with_capacity(bounded_capacity(witness_count, r));
+    for _ in 0..witness_count {
         witness.push(parse_witness_item(r, &mut witness_bytes)?);
     }
     Ok(witness)
@@ -308,19 +321,12 @@ fn read_witness_bytes(
     witness_bytes: &mut usize,
     overflow_msg: &'static str,
 ) -> Result<(u64, Vec<u8>), TxError> {
-    let (len_u64, len_varint_bytes) = read_compact_size(r)?;
+    let (len, len_varint_bytes) =
+        read_compact_size_bounded(r, usize::MAX, ErrorCode::TxErrParse, overflow_msg)?;
     *witness_bytes += len_varint_bytes;
-    let len = checked_usize_len(len_u64, overflow_msg)?;
     let bytes = r.read_bytes(len)?.to_vec();
     *witness_bytes += len;
-    Ok((len_u64, bytes))
-}
-
-fn checked_usize_len(len: u64, msg: &'static str) -> Result<usize, TxError> {
-    if len > usize::MAX as u64 {
-        return Err(TxError::new(ErrorCode::TxErrParse, msg));
-    }
-    Ok(len as usize)
+    Ok((len as u64, bytes))
 }
 
 fn validate_witness_item_ordered(
@@ -353,20 +359,30 @@ fn validate_witness_item_shape(
 ) -> Result<(), TxError> {
     match suite_id {
         SUITE_ID_SENTINEL => validate_sentinel_witness(pub_len_u64 as usize, signature),
-        SUITE_ID_ML_DSA_87
-            if !(pub_len_u64 == ML_DSA_87_PUBKEY_BYTES
-                && sig_len_u64 == ML_DSA_87_SIG_BYTES + 1) =>
-        {
-            Err(TxError::new(
-                ErrorCode::TxErrSigNoncanonical,
-                "non-canonical ML-DSA witness item lengths",
-            ))
-        }
         SUITE_ID_SIMPLICITY_ENVELOPE if pub_len_u64 != 0 => Err(TxError::new(
             ErrorCode::TxErrParse,
             "non-canonical Simplicity envelope witness item",
         )),
         SUITE_ID_SIMPLICITY_ENVELOPE => validate_simplicity_envelope_signature(signature),
+        _ => validate_native_witness_shape(suite_id, pub_len_u64, sig_len_u64),
+    }
+}
+
+/// Parse-time shape check for suites in the default registry. Suites it does
+/// not know pass here and are rejected at spend time with
+/// `TX_ERR_SIG_ALG_INVALID`. The message text is pinned to Go `tx_parse.go`.
+fn validate_native_witness_shape(
+    suite_id: u8,
+    pub_len_u64: u64,
+    sig_len_u64: u64,
+) -> Result<(), TxError> {
+    match default_suite_registry().lookup(suite_id) {
+        Some(params) if !params.has_canonical_witness_shape(pub_len_u64, sig_len_u64) => {
+            Err(TxError::new(
+                ErrorCode::TxErrSigNoncanonical,
+                "non-canonical ML-DSA witness item lengths",
+            ))
+        }
         _ => Ok(()),
     }
 }
@@ -396,23 +412,15 @@ fn validate_simplicity_envelope_signature(signature: &[u8]) -> Result<(), TxErro
             "non-canonical Simplicity envelope witness item",
         ));
     }
-    let (program_len_u64, _) = read_compact_size(&mut r)?;
-    if program_len_u64 > MAX_SIMPLICITY_PROGRAM_BYTES {
-        return Err(TxError::new(
-            ErrorCode::TxErrParse,
-            "Simplicity program too large",
-        ));
-    }
-    r.read_bytes(program_len_u64 as usize)?;
-    let (witness_len_u64, _) = read_compact_size(&mut r)?;
+    let program_len = r.read_compact_size_usize(
+        MAX_SIMPLICITY_PROGRAM_BYTES as usize,
+        "Simplicity program too large",
+    )?;
+    r.read_bytes(program_len)?;
     // `isize::MAX` == Go's `math.MaxInt` on every target: byte-identical, and blocks a 32-bit `as usize` truncation.
-    if witness_len_u64 > isize::MAX as u64 {
-        return Err(TxError::new(
-            ErrorCode::TxErrParse,
-            "Simplicity witness_len overflows int",
-        ));
-    }
-    r.read_bytes(witness_len_u64 as usize)?;
+    let witness_len =
+        r.read_compact_size_usize(isize::MAX as usize, "Simplicity witness_len overflows int")?;
+    r.read_bytes(witness_len)?;
     if r.offset() != envelope.len() {
         return Err(TxError::new(
             ErrorCode::TxErrParse,
@@ -1,5 +1,3 @@
-use std::collections::HashMap;
-
 use crate::compactsize::encode_compact_size;
 use crate::constants::{
     COV_TYPE_P2PK, MAX_P2PK_COVENANT_DATA, ML_DSA_87_PUBKEY_BYTES, ML_DSA_87_SIG_BYTES,
@@ -7,6 +5,7 @@ use crate::constants::{
 };
 use crate::error::{ErrorCode, TxError};
 use crate::hash::sha3_256;
+use crate::outpoint_map::OutpointMap;
 use crate::sighash::{sighash_v1_digest_with_cache, SighashV1PrehashCache};
 use crate::tx::{da_core_fields_bytes, Tx, TxInput, WitnessItem};
 use crate::utxo_basic::{Outpoint, UtxoEntry};
@@ -84,7 +83,7 @@ fn signing_outpoint(input: &TxInput) -> Outpoint {
 }
 
 fn signing_entry<'a>(
-    utxo_set: &'a HashMap<Outpoint, UtxoEntry>,
+    utxo_set: &'a OutpointMap<UtxoEntry>,
     input: &TxInput,
     key_id: &[u8; 32],
 ) -> Result<&'a UtxoEntry, TxError> {
@@ -142,7 +141,7 @@ fn signed_witness_item(
 
 pub fn sign_transaction(
     tx: &mut Tx,
-    utxo_set: &HashMap<Outpoint, UtxoEntry>,
+    utxo_set: &OutpointMap<UtxoEntry>,
     chain_id: [u8; 32],
     signer: &impl DigestSigner,
 ) -> Result<(), TxError> {
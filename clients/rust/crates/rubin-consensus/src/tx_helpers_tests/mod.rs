@@ -116,8 +116,8 @@ fn da_chunk_tx() -> Tx {
     }
 }
 
-fn test_utxos(pubkey: &[u8]) -> HashMap<Outpoint, UtxoEntry> {
-    HashMap::from([(
+fn test_utxos(pubkey: &[u8]) -> OutpointMap<UtxoEntry> {
+    OutpointMap::from_iter([(
         Outpoint {
             txid: [0x11; 32],
             vout: 0,
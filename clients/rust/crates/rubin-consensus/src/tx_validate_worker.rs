@@ -197,6 +197,79 @@ pub fn validate_tx_local(
     result
 }
 
+/// Check the spend authorization of one input against its prevout, with any
+/// signature verified immediately. `witness_start` is the input's first
+/// witness item: the `witness_slots` sum over all preceding inputs.
+///
+/// Only the per-input covenant rules run here. Transaction-level rules
+/// (value conservation, vault policy, duplicate inputs, coinbase maturity)
+/// need the whole spend and are enforced by the UTXO apply path.
+#[allow(clippy::too_many_arguments)]
+pub fn validate_input_authorization(
+    tx: &Tx,
+    input_index: u32,
+    entry: &UtxoEntry,
+    witness_start: usize,
+    chain_id: [u8; 32],
+    block_height: u64,
+    block_mtp: u64,
+) -> Result<(), TxError> {
+    validate_input_authorization_with_suite_context(
+        tx,
+        input_index,
+        entry,
+        witness_start,
+        chain_id,
+        block_height,
+        block_mtp,
+        &DefaultRotationProvider,
+        &SuiteRegistry::default_registry(),
+    )
+}
+
+/// [`validate_input_authorization`] against a caller-supplied rotation
+/// provider and suite registry. Suite gating, witness shape and the verifier
+/// binding all come from `rotation` / `registry`; a suite outside the spend
+/// set or missing from the registry fails with `TX_ERR_SIG_ALG_INVALID`.
+#[allow(clippy::too_many_arguments)]
+pub fn validate_input_authorization_with_suite_context(
+    tx: &Tx,
+    input_index: u32,
+    entry: &UtxoEntry,
+    witness_start: usize,
+    chain_id: [u8; 32],
+    block_height: u64,
+    block_mtp: u64,
+    rotation: &dyn RotationProvider,
+    registry: &SuiteRegistry,
+) -> Result<(), TxError> {
+    if input_index as usize >= tx.inputs.len() {
+        return Err(TxError::new(
+            ErrorCode::TxErrParse,
+            "input index out of range",
+        ));
+    }
+    reject_core_simplicity_spend_if_present(std::slice::from_ref(entry))?;
+    let slots = witness_slots(entry.covenant_type, &entry.covenant_data)?;
+    let assigned = assigned_worker_witness(tx, witness_start, slots, tx.witness.len())?;
+    let mut sighash_cache = build_tx_local_preflight(tx)?;
+    let mut sig_queue = sig_queue_with_optional_cache(registry, None);
+    validate_input_spend(
+        entry,
+        assigned,
+        input_index,
+        entry.value,
+        chain_id,
+        block_height,
+        block_mtp,
+        &mut sighash_cache,
+        rotation,
+        registry,
+        Some(&mut sig_queue),
+    )?;
+    sig_queue.flush()
+}
+
 /// Dispatch a single input to the appropriate spend validator based on
 /// covenant type. Mirrors the switch in
 /// `apply_non_coinbase_tx_basic_update_with_mtp_and_core_ext_profiles_and_suite_context`
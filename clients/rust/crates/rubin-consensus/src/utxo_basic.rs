@@ -7,8 +7,8 @@ use crate::constants::{
 };
 use crate::covenant_genesis::validate_tx_covenants_genesis;
 use crate::error::{ErrorCode, TxError};
-use crate::hash::sha3_256;
 use crate::htlc::{parse_htlc_covenant_data, validate_htlc_spend_q, HtlcSpendContext};
+use crate::outpoint_map::OutpointMap;
 use crate::sig_queue::SigCheckQueue;
 use crate::sighash::SighashV1PrehashCache;
 use crate::simplicity_covenant::reject_core_simplicity_spend;
@@ -17,7 +17,7 @@ use crate::stealth::{parse_stealth_covenant_data, validate_stealth_spend_q};
 use crate::suite_registry::{RotationProvider, SuiteRegistry};
 use crate::tx::Tx;
 use crate::vault::{
-    hash_in_sorted_32, output_descriptor_bytes, parse_multisig_covenant_data,
+    hash_in_sorted_32, output_descriptor_hash, parse_multisig_covenant_data,
     parse_vault_covenant_data, parse_vault_covenant_data_for_spend, witness_slots,
 };
 
@@ -42,26 +42,42 @@ pub struct UtxoApplySummary {
     pub utxo_count: u64,
 }
 
+/// Receives the name of every consensus check the non-coinbase apply path
+/// evaluates, in evaluation order. A check is reported just before it runs,
+/// so on a rejected tx the last reported name is the check that failed.
+/// Per-input checks are reported once per input.
+pub trait ValidationObserver {
+    fn on_check(&mut self, name: &'static str);
+}
+
+impl ValidationObserver for Vec<&'static str> {
+    fn on_check(&mut self, name: &'static str) {
+        self.push(name);
+    }
+}
+
 struct UtxoApplyImplContext<'a> {
     tx: &'a Tx,
     txid: [u8; 32],
-    utxo_set: &'a HashMap<Outpoint, UtxoEntry>,
+    utxo_set: &'a OutpointMap<UtxoEntry>,
     height: u64,
     block_timestamp: u64,
     block_mtp: u64,
     chain_id: [u8; 32],
     rotation: Option<&'a dyn RotationProvider>,
     registry: Option<&'a SuiteRegistry>,
+    coinbase_maturity: u64,
+    observer: Option<&'a mut dyn ValidationObserver>,
 }
 
 pub fn apply_non_coinbase_tx_basic_update(
     tx: &Tx,
     txid: [u8; 32],
-    utxo_set: &HashMap<Outpoint, UtxoEntry>,
+    utxo_set: &OutpointMap<UtxoEntry>,
     height: u64,
     block_timestamp: u64,
     chain_id: [u8; 32],
-) -> Result<(HashMap<Outpoint, UtxoEntry>, UtxoApplySummary), TxError> {
+) -> Result<(OutpointMap<UtxoEntry>, UtxoApplySummary), TxError> {
     apply_non_coinbase_tx_basic_update_with_mtp(
         tx,
         txid,
@@ -76,12 +92,12 @@ pub fn apply_non_coinbase_tx_basic_update(
 pub fn apply_non_coinbase_tx_basic_update_with_mtp(
     tx: &Tx,
     txid: [u8; 32],
-    utxo_set: &HashMap<Outpoint, UtxoEntry>,
+    utxo_set: &OutpointMap<UtxoEntry>,
     height: u64,
     block_timestamp: u64,
     block_mtp: u64,
     chain_id: [u8; 32],
-) -> Result<(HashMap<Outpoint, UtxoEntry>, UtxoApplySummary), TxError> {
+) -> Result<(OutpointMap<UtxoEntry>, UtxoApplySummary), TxError> {
     apply_non_coinbase_tx_basic_update_with_mtp_and_core_ext_profiles_and_suite_context(
         tx,
         txid,
@@ -99,14 +115,48 @@ pub fn apply_non_coinbase_tx_basic_update_with_mtp(
 pub fn apply_non_coinbase_tx_basic_update_with_mtp_and_core_ext_profiles_and_suite_context(
     tx: &Tx,
     txid: [u8; 32],
-    utxo_set: &HashMap<Outpoint, UtxoEntry>,
+    utxo_set: &OutpointMap<UtxoEntry>,
+    height: u64,
+    block_timestamp: u64,
+    block_mtp: u64,
+    chain_id: [u8; 32],
+    rotation: Option<&dyn RotationProvider>,
+    registry: Option<&SuiteRegistry>,
+) -> Result<(OutpointMap<UtxoEntry>, UtxoApplySummary), TxError> {
+    apply_non_coinbase_tx_basic_update_with_mtp_and_core_ext_profiles_and_suite_context_impl(
+        UtxoApplyImplContext {
+            tx,
+            txid,
+            utxo_set,
+            height,
+            block_timestamp,
+            block_mtp,
+            chain_id,
+            rotation,
+            registry,
+            coinbase_maturity: COINBASE_MATURITY,
+            observer: None,
+        },
+        None,
+    )
+}
+
+/// Same as
+/// [`apply_non_coinbase_tx_basic_update_with_mtp_and_core_ext_profiles_and_suite_context`],
+/// reporting each evaluated check to `observer`.
+#[allow(clippy::too_many_arguments)]
+pub fn apply_non_coinbase_tx_basic_update_with_mtp_and_core_ext_profiles_and_suite_context_observed(
+    tx: &Tx,
+    txid: [u8; 32],
+    utxo_set: &OutpointMap<UtxoEntry>,
     height: u64,
     block_timestamp: u64,
     block_mtp: u64,
     chain_id: [u8; 32],
     rotation: Option<&dyn RotationProvider>,
     registry: Option<&SuiteRegistry>,
-) -> Result<(HashMap<Outpoint, UtxoEntry>, UtxoApplySummary), TxError> {
+    observer: &mut dyn ValidationObserver,
+) -> Result<(OutpointMap<UtxoEntry>, UtxoApplySummary), TxError> {
     apply_non_coinbase_tx_basic_update_with_mtp_and_core_ext_profiles_and_suite_context_impl(
         UtxoApplyImplContext {
             tx,
@@ -118,6 +168,8 @@ pub fn apply_non_coinbase_tx_basic_update_with_mtp_and_core_ext_profiles_and_sui
             chain_id,
             rotation,
             registry,
+            coinbase_maturity: COINBASE_MATURITY,
+            observer: Some(observer),
         },
         None,
     )
@@ -127,15 +179,16 @@ pub fn apply_non_coinbase_tx_basic_update_with_mtp_and_core_ext_profiles_and_sui
 pub(crate) fn apply_non_coinbase_tx_basic_update_with_mtp_and_core_ext_profiles_and_suite_context_queued_sigchecks(
     tx: &Tx,
     txid: [u8; 32],
-    utxo_set: &HashMap<Outpoint, UtxoEntry>,
+    utxo_set: &OutpointMap<UtxoEntry>,
     height: u64,
     block_timestamp: u64,
     block_mtp: u64,
     chain_id: [u8; 32],
     rotation: Option<&dyn RotationProvider>,
     registry: Option<&SuiteRegistry>,
+    coinbase_maturity: u64,
     sig_queue: &mut SigCheckQueue,
-) -> Result<(HashMap<Outpoint, UtxoEntry>, UtxoApplySummary), TxError> {
+) -> Result<(OutpointMap<UtxoEntry>, UtxoApplySummary), TxError> {
     apply_non_coinbase_tx_basic_update_with_mtp_and_core_ext_profiles_and_suite_context_impl(
         UtxoApplyImplContext {
             tx,
@@ -147,23 +200,59 @@ pub(crate) fn apply_non_coinbase_tx_basic_update_with_mtp_and_core_ext_profiles_
             chain_id,
             rotation,
             registry,
+            coinbase_maturity,
+            observer: None,
         },
         Some(sig_queue),
     )
 }
 
+/// [`apply_non_coinbase_tx_basic_update_with_mtp_and_core_ext_profiles_and_suite_context`]
+/// with the chain's coinbase maturity instead of `COINBASE_MATURITY`, for
+/// chain instances running non-v1 [`crate::ChainParams`].
+#[allow(clippy::too_many_arguments)]
+pub fn apply_non_coinbase_tx_basic_update_with_mtp_and_core_ext_profiles_and_suite_context_and_maturity(
+    tx: &Tx,
+    txid: [u8; 32],
+    utxo_set: &OutpointMap<UtxoEntry>,
+    height: u64,
+    block_timestamp: u64,
+    block_mtp: u64,
+    chain_id: [u8; 32],
+    rotation: Option<&dyn RotationProvider>,
+    registry: Option<&SuiteRegistry>,
+    coinbase_maturity: u64,
+) -> Result<(OutpointMap<UtxoEntry>, UtxoApplySummary), TxError> {
+    apply_non_coinbase_tx_basic_update_with_mtp_and_core_ext_profiles_and_suite_context_impl(
+        UtxoApplyImplContext {
+            tx,
+            txid,
+            utxo_set,
+            height,
+            block_timestamp,
+            block_mtp,
+            chain_id,
+            rotation,
+            registry,
+            coinbase_maturity,
+            observer: None,
+        },
+        None,
+    )
+}
+
 #[allow(clippy::too_many_arguments)]
 pub fn apply_non_coinbase_tx_basic_update_with_mtp_and_core_ext_profiles_and_suite_context_deferred_sigchecks(
     tx: &Tx,
     txid: [u8; 32],
-    utxo_set: &HashMap<Outpoint, UtxoEntry>,
+    utxo_set: &OutpointMap<UtxoEntry>,
     height: u64,
     block_timestamp: u64,
     block_mtp: u64,
     chain_id: [u8; 32],
     rotation: Option<&dyn RotationProvider>,
     registry: Option<&SuiteRegistry>,
-) -> Result<(HashMap<Outpoint, UtxoEntry>, UtxoApplySummary), TxError> {
+) -> Result<(OutpointMap<UtxoEntry>, UtxoApplySummary), TxError> {
     let mut sig_queue = SigCheckQueue::new(1);
     let queue_mark = sig_queue.mark();
     let result = apply_non_coinbase_tx_basic_update_with_mtp_and_core_ext_profiles_and_suite_context_queued_sigchecks(
@@ -176,6 +265,7 @@ pub fn apply_non_coinbase_tx_basic_update_with_mtp_and_core_ext_profiles_and_sui
         chain_id,
         rotation,
         registry,
+        COINBASE_MATURITY,
         &mut sig_queue,
     );
     let (work, summary) = match result {
@@ -192,7 +282,7 @@ pub fn apply_non_coinbase_tx_basic_update_with_mtp_and_core_ext_profiles_and_sui
 fn apply_non_coinbase_tx_basic_update_with_mtp_and_core_ext_profiles_and_suite_context_impl(
     ctx: UtxoApplyImplContext<'_>,
     sig_queue: Option<&mut SigCheckQueue>,
-) -> Result<(HashMap<Outpoint, UtxoEntry>, UtxoApplySummary), TxError> {
+) -> Result<(OutpointMap<UtxoEntry>, UtxoApplySummary), TxError> {
     let UtxoApplyImplContext {
         tx,
         txid,
@@ -203,15 +293,24 @@ fn apply_non_coinbase_tx_basic_update_with_mtp_and_core_ext_profiles_and_suite_c
         chain_id,
         rotation,
         registry,
+        coinbase_maturity,
+        mut observer,
     } = ctx;
     let _ = block_timestamp;
     let mut sig_queue = sig_queue;
+    let mut check = |name: &'static str| {
+        if let Some(observer) = observer.as_deref_mut() {
+            observer.on_check(name);
+        }
+    };
+    check("input_count");
     if tx.inputs.is_empty() {
         return Err(TxError::new(
             ErrorCode::TxErrParse,
             "non-coinbase must have at least one input",
         ));
     }
+    check("tx_nonce");
     if tx.tx_nonce == 0 {
         return Err(TxError::new(
             ErrorCode::TxErrTxNonceInvalid,
@@ -219,9 +318,11 @@ fn apply_non_coinbase_tx_basic_update_with_mtp_and_core_ext_profiles_and_suite_c
         ));
     }
 
+    check("covenants_genesis");
     validate_tx_covenants_genesis(tx, height, rotation)?;
 
     let mut work = utxo_set.clone();
+    check("sighash_prehash");
     let mut sighash_cache = SighashV1PrehashCache::new(tx)?;
     let mut sum_in: u128 = 0;
     let mut sum_in_vault: u128 = 0;
@@ -244,18 +345,21 @@ fn apply_non_coinbase_tx_basic_update_with_mtp_and_core_ext_profiles_and_suite_c
     let zero_txid: [u8; 32] = [0u8; 32];
 
     for input in &tx.inputs {
+        check("script_sig");
         if !input.script_sig.is_empty() {
             return Err(TxError::new(
                 ErrorCode::TxErrParse,
                 "script_sig must be empty under genesis covenant set",
             ));
         }
+        check("sequence");
         if input.sequence > 0x7fffffff {
             return Err(TxError::new(
                 ErrorCode::TxErrSequenceInvalid,
                 "sequence exceeds 0x7fffffff",
             ));
         }
+        check("coinbase_prevout");
         if input.prev_vout == 0xffff_ffff && input.prev_txid == zero_txid {
             return Err(TxError::new(
                 ErrorCode::TxErrParse,
@@ -266,6 +370,7 @@ fn apply_non_coinbase_tx_basic_update_with_mtp_and_core_ext_profiles_and_suite_c
             txid: input.prev_txid,
             vout: input.prev_vout,
         };
+        check("duplicate_input");
         if seen_inputs.contains_key(&op) {
             return Err(TxError::new(
                 ErrorCode::TxErrParse,
@@ -273,11 +378,13 @@ fn apply_non_coinbase_tx_basic_update_with_mtp_and_core_ext_profiles_and_suite_c
             ));
         }
         seen_inputs.insert(op.clone(), ());
+        check("utxo_lookup");
         let entry = match work.get(&op) {
             Some(v) => v.clone(),
             None => return Err(TxError::new(ErrorCode::TxErrMissingUtxo, "utxo not found")),
         };
 
+        check("spendable_covenant");
         if entry.covenant_type == COV_TYPE_ANCHOR || entry.covenant_type == COV_TYPE_DA_COMMIT {
             return Err(TxError::new(
                 ErrorCode::TxErrMissingUtxo,
@@ -285,10 +392,11 @@ fn apply_non_coinbase_tx_basic_update_with_mtp_and_core_ext_profiles_and_suite_c
             ));
         }
 
-        // Overflow-safe maturity check: avoid entry.creation_height + COINBASE_MATURITY wrapping.
+        check("coinbase_maturity");
+        // Overflow-safe maturity check: avoid entry.creation_height + coinbase_maturity wrapping.
         if entry.created_by_coinbase
             && (height < entry.creation_height
-                || height - entry.creation_height < COINBASE_MATURITY)
+                || height - entry.creation_height < coinbase_maturity)
         {
             return Err(TxError::new(
                 ErrorCode::TxErrCoinbaseImmature,
@@ -296,6 +404,7 @@ fn apply_non_coinbase_tx_basic_update_with_mtp_and_core_ext_profiles_and_suite_c
             ));
         }
         if entry.covenant_type == COV_TYPE_VAULT {
+            check("vault_input_count");
             vault_input_count += 1;
             if vault_input_count > 1 {
                 return Err(TxError::new(
@@ -304,6 +413,7 @@ fn apply_non_coinbase_tx_basic_update_with_mtp_and_core_ext_profiles_and_suite_c
                 ));
             }
         }
+        check("spend_covenant");
         // Fail-closed: a CORE_SIMPLICITY (0x0106) spend is rejected with the
         // dedicated message ahead of the generic check_spend_covenant/witness
         // errors, matching Go's input-resolution order.
@@ -311,6 +421,7 @@ fn apply_non_coinbase_tx_basic_update_with_mtp_and_core_ext_profiles_and_suite_c
             return Err(reject_core_simplicity_spend());
         }
         check_spend_covenant(entry.covenant_type, &entry.covenant_data)?;
+        check("witness_slots");
         let slots = witness_slots(entry.covenant_type, &entry.covenant_data)?;
         if slots == 0 {
             return Err(TxError::new(ErrorCode::TxErrParse, "invalid witness slots"));
@@ -324,6 +435,7 @@ fn apply_non_coinbase_tx_basic_update_with_mtp_and_core_ext_profiles_and_suite_c
         resolved_outpoints.push(op);
         witness_cursor += slots;
     }
+    check("witness_count");
     if witness_cursor != tx.witness.len() {
         return Err(TxError::new(
             ErrorCode::TxErrParse,
@@ -344,6 +456,7 @@ fn apply_non_coinbase_tx_basic_update_with_mtp_and_core_ext_profiles_and_suite_c
         let assigned = &tx.witness[assigned_range.clone()];
         match entry.covenant_type {
             COV_TYPE_P2PK => {
+                check("p2pk_spend");
                 if assigned.len() != 1 {
                     return Err(TxError::new(
                         ErrorCode::TxErrParse,
@@ -364,6 +477,7 @@ fn apply_non_coinbase_tx_basic_update_with_mtp_and_core_ext_profiles_and_suite_c
                 )?;
             }
             COV_TYPE_MULTISIG => {
+                check("multisig_spend");
                 let m = parse_multisig_covenant_data(&entry.covenant_data)?;
                 validate_threshold_sig_spend_q(
                     &m.keys,
@@ -381,6 +495,7 @@ fn apply_non_coinbase_tx_basic_update_with_mtp_and_core_ext_profiles_and_suite_c
                 )?;
             }
             COV_TYPE_VAULT => {
+                check("vault_spend_params");
                 let v = parse_vault_covenant_data_for_spend(&entry.covenant_data)?;
                 // CORE_VAULT signature threshold is checked later (CANONICAL §24.1),
                 // after owner-authorization and no-fee-sponsorship checks.
@@ -394,6 +509,7 @@ fn apply_non_coinbase_tx_basic_update_with_mtp_and_core_ext_profiles_and_suite_c
                 have_vault_sig = true;
             }
             COV_TYPE_HTLC => {
+                check("htlc_spend");
                 if assigned.len() != 2 {
                     return Err(TxError::new(
                         ErrorCode::TxErrParse,
@@ -418,6 +534,7 @@ fn apply_non_coinbase_tx_basic_update_with_mtp_and_core_ext_profiles_and_suite_c
                 )?;
             }
             COV_TYPE_CORE_STEALTH => {
+                check("stealth_spend");
                 if assigned.len() != 1 {
                     return Err(TxError::new(
                         ErrorCode::TxErrParse,
@@ -440,8 +557,7 @@ fn apply_non_coinbase_tx_basic_update_with_mtp_and_core_ext_profiles_and_suite_c
             _ => {}
         }
 
-        let desc = output_descriptor_bytes(entry.covenant_type, &entry.covenant_data);
-        let input_lock_id = sha3_256(&desc);
+        let input_lock_id = output_descriptor_hash(entry.covenant_type, &entry.covenant_data);
         input_lock_ids.push(input_lock_id);
         input_cov_types.push(entry.covenant_type);
 
@@ -456,6 +572,7 @@ fn apply_non_coinbase_tx_basic_update_with_mtp_and_core_ext_profiles_and_suite_c
         work.remove(op);
     }
 
+    check("outputs");
     let mut sum_out: u128 = 0;
     let mut creates_vault = false;
     for (i, out) in tx.outputs.iter().enumerate() {
@@ -488,6 +605,7 @@ fn apply_non_coinbase_tx_basic_update_with_mtp_and_core_ext_profiles_and_suite_c
 
     // CORE_VAULT creation rule: any tx creating CORE_VAULT outputs must include an owner-authorized input.
     if creates_vault {
+        check("vault_creation_owner_auth");
         for out in &tx.outputs {
             if out.covenant_type != COV_TYPE_VAULT {
                 continue;
@@ -506,6 +624,7 @@ fn apply_non_coinbase_tx_basic_update_with_mtp_and_core_ext_profiles_and_suite_c
 
     // CORE_VAULT spend rules: safe-only model with owner binding and strict whitelist.
     if vault_input_count == 1 {
+        check("vault_sig_context");
         if !have_vault_sig {
             return Err(TxError::new(
                 ErrorCode::TxErrParse,
@@ -513,6 +632,7 @@ fn apply_non_coinbase_tx_basic_update_with_mtp_and_core_ext_profiles_and_suite_c
             ));
         }
         // Owner input required.
+        check("vault_owner_auth");
         if !has_owner_lock_input(&input_lock_ids, vault_owner_lock_id) {
             return Err(TxError::new(
                 ErrorCode::TxErrVaultOwnerAuthRequired,
@@ -520,6 +640,7 @@ fn apply_non_coinbase_tx_basic_update_with_mtp_and_core_ext_profiles_and_suite_c
             ));
         }
 
+        check("vault_fee_sponsor");
         // No fee sponsorship: all non-vault inputs must be owned by the same owner lock.
         if !non_vault_inputs_owned_by(&input_lock_ids, &input_cov_types, vault_owner_lock_id) {
             return Err(TxError::new(
@@ -528,6 +649,7 @@ fn apply_non_coinbase_tx_basic_update_with_mtp_and_core_ext_profiles_and_suite_c
             ));
         }
 
+        check("vault_no_vault_outputs");
         // Circular-reference hardening: vault spends MUST NOT create new CORE_VAULT outputs.
         for out in &tx.outputs {
             if out.covenant_type == COV_TYPE_VAULT {
@@ -539,6 +661,7 @@ fn apply_non_coinbase_tx_basic_update_with_mtp_and_core_ext_profiles_and_suite_c
         }
 
         // Signature threshold check (CANONICAL §24.1 step 7).
+        check("vault_sig_threshold");
         let vault_sig_witness = match vault_sig_witness_range.as_ref() {
             Some(range) => &tx.witness[range.clone()],
             None => unreachable!("vault witness range must exist when have_vault_sig is true"),
@@ -559,6 +682,7 @@ fn apply_non_coinbase_tx_basic_update_with_mtp_and_core_ext_profiles_and_suite_c
         )?;
 
         // Whitelist enforcement: all outputs must be whitelisted.
+        check("vault_whitelist");
         for out in &tx.outputs {
             if out.covenant_type != COV_TYPE_P2PK
                 && out.covenant_type != COV_TYPE_MULTISIG
@@ -569,8 +693,7 @@ fn apply_non_coinbase_tx_basic_update_with_mtp_and_core_ext_profiles_and_suite_c
                     "disallowed destination covenant_type for CORE_VAULT spend",
                 ));
             }
-            let desc = output_descriptor_bytes(out.covenant_type, &out.covenant_data);
-            let h = sha3_256(&desc);
+            let h = output_descriptor_hash(out.covenant_type, &out.covenant_data);
             if !hash_in_sorted_32(&vault_whitelist, &h) {
                 return Err(TxError::new(
                     ErrorCode::TxErrVaultOutputNotWhitelisted,
@@ -580,17 +703,21 @@ fn apply_non_coinbase_tx_basic_update_with_mtp_and_core_ext_profiles_and_suite_c
         }
     }
 
+    check("value_conservation");
     if sum_out > sum_in {
         return Err(TxError::new(
             ErrorCode::TxErrValueConservation,
             "sum_out exceeds sum_in",
         ));
     }
-    if vault_input_count == 1 && sum_out < sum_in_vault {
-        return Err(TxError::new(
-            ErrorCode::TxErrValueConservation,
-            "CORE_VAULT value must not fund miner fee",
-        ));
+    if vault_input_count == 1 {
+        check("vault_fee_funding");
+        if sum_out < sum_in_vault {
+            return Err(TxError::new(
+                ErrorCode::TxErrValueConservation,
+                "CORE_VAULT value must not fund miner fee",
+            ));
+        }
     }
 
     let fee = u64::try_from(sum_in - sum_out)
@@ -607,7 +734,7 @@ fn apply_non_coinbase_tx_basic_update_with_mtp_and_core_ext_profiles_and_suite_c
 pub fn apply_non_coinbase_tx_basic(
     tx: &Tx,
     txid: [u8; 32],
-    utxo_set: &HashMap<Outpoint, UtxoEntry>,
+    utxo_set: &OutpointMap<UtxoEntry>,
     height: u64,
     block_timestamp: u64,
     chain_id: [u8; 32],
@@ -626,7 +753,7 @@ pub fn apply_non_coinbase_tx_basic(
 pub fn apply_non_coinbase_tx_basic_with_mtp(
     tx: &Tx,
     txid: [u8; 32],
-    utxo_set: &HashMap<Outpoint, UtxoEntry>,
+    utxo_set: &OutpointMap<UtxoEntry>,
     height: u64,
     block_timestamp: u64,
     block_mtp: u64,
@@ -705,9 +832,10 @@ fn check_spend_covenant(covenant_type: u16, covenant_data: &[u8]) -> Result<(),
 mod tests {
     use super::*;
     use crate::constants::{
-        COV_TYPE_CORE_EXT, LOCK_MODE_HEIGHT, MAX_STEALTH_COVENANT_DATA, SIGHASH_ALL,
-        SUITE_ID_ML_DSA_87, SUITE_ID_SENTINEL,
+        COV_TYPE_CORE_EXT, LOCK_MODE_HEIGHT, MAX_STEALTH_COVENANT_DATA, ML_DSA_87_PUBKEY_BYTES,
+        ML_DSA_87_SIG_BYTES, SIGHASH_ALL, SUITE_ID_ML_DSA_87, SUITE_ID_SENTINEL,
     };
+    use crate::hash::sha3_256;
     use crate::sighash::sighash_v1_digest;
     use crate::tx::{DaCommitCore, Tx, TxInput, TxOutput, WitnessItem};
     use crate::tx_helpers::{p2pk_covenant_data_for_pubkey, sign_transaction};
@@ -727,7 +855,7 @@ mod tests {
         let prev_txid = [0x71; 32];
         let txid = [0x72; 32];
         let chain_id = [0x73; 32];
-        let funding = HashMap::from([utxo(
+        let funding = OutpointMap::from_iter([utxo(
             prev_txid,
             100,
             COV_TYPE_P2PK,
@@ -754,7 +882,8 @@ mod tests {
         let spend_txid = [0x82; 32];
         let spend_chain = [0x83; 32];
         for cov_data in [vec![], vec![0x07, 0x00, 0x00], vec![0xffu8; 8]] {
-            let spend_set = HashMap::from([utxo(spend_prev, 100, COV_TYPE_CORE_EXT, cov_data)]);
+            let spend_set =
+                OutpointMap::from_iter([utxo(spend_prev, 100, COV_TYPE_CORE_EXT, cov_data)]);
             let mut spend_tx = unsigned_tx(
                 0x00,
                 2,
@@ -798,7 +927,7 @@ mod tests {
         let spend_chain = [0x93; 32];
         for cov_data in [vec![], vec![0xffu8; 8]] {
             let spend_set =
-                HashMap::from([utxo(spend_prev, 100, COV_TYPE_CORE_SIMPLICITY, cov_data)]);
+                OutpointMap::from_iter([utxo(spend_prev, 100, COV_TYPE_CORE_SIMPLICITY, cov_data)]);
             let mut spend_tx = unsigned_tx(
                 0x00,
                 2,
@@ -853,7 +982,7 @@ mod tests {
         let prev = [0x67u8; 32];
         let (op, mut entry) = utxo(prev, 100, COV_TYPE_CORE_SIMPLICITY, simplicity_cov(0x67));
         entry.created_by_coinbase = true; // creation_height 0
-        let set = HashMap::from([(op, entry)]);
+        let set = OutpointMap::from_iter([(op, entry)]);
         let tx = unsigned_tx(
             0x00,
             1,
@@ -887,7 +1016,7 @@ mod tests {
         let simp_prev = [0xE1u8; 32];
         let later_missing = [0xE2u8; 32];
         // Only the CORE_SIMPLICITY input is resolvable; the later input is missing.
-        let set = HashMap::from([utxo(
+        let set = OutpointMap::from_iter([utxo(
             simp_prev,
             100,
             COV_TYPE_CORE_SIMPLICITY,
@@ -929,7 +1058,7 @@ mod tests {
         let pubkey = keypair.pubkey_bytes();
         let p2pk_prev = [0xE0u8; 32];
         let simp_prev = [0xE1u8; 32];
-        let set = HashMap::from([
+        let set = OutpointMap::from_iter([
             utxo(
                 p2pk_prev,
                 100,
@@ -978,7 +1107,7 @@ mod tests {
         let pubkey = keypair.pubkey_bytes();
         let first_prev = [0xE7u8; 32];
         let later_prev = [0xE8u8; 32];
-        let set = HashMap::from([
+        let set = OutpointMap::from_iter([
             utxo(
                 first_prev,
                 100,
@@ -1067,13 +1196,13 @@ mod tests {
         }
     }
 
-    fn signed_p2pk_case() -> (Tx, HashMap<Outpoint, UtxoEntry>, [u8; 32], [u8; 32]) {
+    fn signed_p2pk_case() -> (Tx, OutpointMap<UtxoEntry>, [u8; 32], [u8; 32]) {
         let keypair = Mldsa87Keypair::generate().expect("keypair");
         let pubkey = keypair.pubkey_bytes();
         let prev_txid = [0x71; 32];
         let txid = [0x72; 32];
         let chain_id = [0x73; 32];
-        let utxo_set = HashMap::from([(
+        let utxo_set = OutpointMap::from_iter([(
             Outpoint {
                 txid: prev_txid,
                 vout: 0,
@@ -1111,13 +1240,13 @@ mod tests {
         (tx, utxo_set, txid, chain_id)
     }
 
-    fn signed_anchor_output_case() -> (Tx, HashMap<Outpoint, UtxoEntry>, [u8; 32], [u8; 32]) {
+    fn signed_anchor_output_case() -> (Tx, OutpointMap<UtxoEntry>, [u8; 32], [u8; 32]) {
         let keypair = Mldsa87Keypair::generate().expect("anchor keypair");
         let pubkey = keypair.pubkey_bytes();
         let prev_txid = [0x74; 32];
         let txid = [0x75; 32];
         let chain_id = [0x76; 32];
-        let utxo_set = HashMap::from([(
+        let utxo_set = OutpointMap::from_iter([(
             Outpoint {
                 txid: prev_txid,
                 vout: 0,
@@ -1155,13 +1284,13 @@ mod tests {
         (tx, utxo_set, txid, chain_id)
     }
 
-    fn signed_da_commit_output_case() -> (Tx, HashMap<Outpoint, UtxoEntry>, [u8; 32], [u8; 32]) {
+    fn signed_da_commit_output_case() -> (Tx, OutpointMap<UtxoEntry>, [u8; 32], [u8; 32]) {
         let keypair = Mldsa87Keypair::generate().expect("da commit keypair");
         let pubkey = keypair.pubkey_bytes();
         let prev_txid = [0x77; 32];
         let txid = [0x78; 32];
         let chain_id = [0x79; 32];
-        let utxo_set = HashMap::from([utxo(
+        let utxo_set = OutpointMap::from_iter([utxo(
             prev_txid,
             100,
             COV_TYPE_P2PK,
@@ -1269,7 +1398,7 @@ mod tests {
         covenant_data
     }
 
-    fn signed_vault_case() -> (Tx, HashMap<Outpoint, UtxoEntry>, [u8; 32], [u8; 32]) {
+    fn signed_vault_case() -> (Tx, OutpointMap<UtxoEntry>, [u8; 32], [u8; 32]) {
         let prev_vault = [0x81; 32];
         let prev_fee = [0x82; 32];
         let txid = [0x83; 32];
@@ -1280,10 +1409,10 @@ mod tests {
         let dest_kp = Mldsa87Keypair::generate().expect("dest keypair");
 
         let owner_cov = p2pk_covenant_data_for_pubkey(&owner_kp.pubkey_bytes());
-        let owner_lock_id = sha3_256(&output_descriptor_bytes(COV_TYPE_P2PK, &owner_cov));
+        let owner_lock_id = output_descriptor_hash(COV_TYPE_P2PK, &owner_cov);
 
         let dest_cov = p2pk_covenant_data_for_pubkey(&dest_kp.pubkey_bytes());
-        let whitelist_h = sha3_256(&output_descriptor_bytes(COV_TYPE_P2PK, &dest_cov));
+        let whitelist_h = output_descriptor_hash(COV_TYPE_P2PK, &dest_cov);
         let vault_key_id = sha3_256(&vault_kp.pubkey_bytes());
         let vault_cov =
             encode_vault_covenant_data(owner_lock_id, 1, &[vault_key_id], &[whitelist_h]);
@@ -1299,7 +1428,7 @@ mod tests {
             sign_input_witness(&tx, 1, 10, chain_id, &owner_kp),
         ];
 
-        let utxo_set = HashMap::from([
+        let utxo_set = OutpointMap::from_iter([
             utxo(prev_vault, 100, COV_TYPE_VAULT, vault_cov),
             utxo(prev_fee, 10, COV_TYPE_P2PK, owner_cov),
         ]);
@@ -1307,7 +1436,7 @@ mod tests {
         (tx, utxo_set, txid, chain_id)
     }
 
-    fn signed_multisig_case() -> (Tx, HashMap<Outpoint, UtxoEntry>, [u8; 32], [u8; 32]) {
+    fn signed_multisig_case() -> (Tx, OutpointMap<UtxoEntry>, [u8; 32], [u8; 32]) {
         let prev_txid = [0x91; 32];
         let txid = [0x92; 32];
         let chain_id = [0x93; 32];
@@ -1342,7 +1471,7 @@ mod tests {
         };
         tx.witness = vec![sign_input_witness(&tx, 0, 100, chain_id, &multisig_kp)];
 
-        let utxo_set = HashMap::from([(
+        let utxo_set = OutpointMap::from_iter([(
             Outpoint {
                 txid: prev_txid,
                 vout: 0,
@@ -1359,7 +1488,7 @@ mod tests {
         (tx, utxo_set, txid, chain_id)
     }
 
-    fn signed_htlc_case() -> (Tx, HashMap<Outpoint, UtxoEntry>, [u8; 32], [u8; 32]) {
+    fn signed_htlc_case() -> (Tx, OutpointMap<UtxoEntry>, [u8; 32], [u8; 32]) {
         let prev_htlc = [0xa1; 32];
         let prev_p2pk = [0xa2; 32];
         let txid = [0xa3; 32];
@@ -1392,7 +1521,7 @@ mod tests {
             sign_input_witness(&tx, 1, 70, chain_id, &p2pk_kp),
         ];
 
-        let utxo_set = HashMap::from([
+        let utxo_set = OutpointMap::from_iter([
             utxo(
                 prev_htlc,
                 100,
@@ -1411,7 +1540,7 @@ mod tests {
         (tx, utxo_set, txid, chain_id)
     }
 
-    fn signed_stealth_case() -> (Tx, HashMap<Outpoint, UtxoEntry>, [u8; 32], [u8; 32]) {
+    fn signed_stealth_case() -> (Tx, OutpointMap<UtxoEntry>, [u8; 32], [u8; 32]) {
         let prev_txid = [0xb1; 32];
         let txid = [0xb2; 32];
         let chain_id = [0xb3; 32];
@@ -1442,7 +1571,7 @@ mod tests {
         };
         tx.witness = vec![sign_input_witness(&tx, 0, 500, chain_id, &keypair)];
 
-        let utxo_set = HashMap::from([(
+        let utxo_set = OutpointMap::from_iter([(
             Outpoint {
                 txid: prev_txid,
                 vout: 0,
@@ -1461,7 +1590,7 @@ mod tests {
 
     fn assert_apply_preserves_caller_utxos(
         tx: &Tx,
-        utxo_set: &HashMap<Outpoint, UtxoEntry>,
+        utxo_set: &OutpointMap<UtxoEntry>,
         txid: [u8; 32],
         chain_id: [u8; 32],
     ) {
@@ -1518,6 +1647,51 @@ mod tests {
         assert_apply_preserves_caller_utxos(&tx, &utxo_set, txid, chain_id);
     }
 
+    #[test]
+    fn htlc_refund_with_non_empty_script_sig_is_parse_error() {
+        let prev_htlc = [0xc1; 32];
+        let claim_key_id = [0xc2; 32];
+        let refund_key_id = [0xc3; 32];
+        let utxo_set = OutpointMap::from_iter([utxo(
+            prev_htlc,
+            100,
+            COV_TYPE_HTLC,
+            encode_htlc_covenant_data([0xc4; 32], LOCK_MODE_HEIGHT, 1, claim_key_id, refund_key_id),
+        )]);
+        // The refund path never carries a preimage: 31 and 33 bytes must not
+        // slip through as a near-miss 32-byte claim, nor 1 byte as a selector.
+        for len in [1usize, 31, 33] {
+            let mut p2pk_cov = vec![0u8; 33];
+            p2pk_cov[0] = SUITE_ID_ML_DSA_87;
+            let mut tx = unsigned_tx(
+                0x00,
+                1,
+                vec![tx_input(prev_htlc)],
+                vec![tx_output(90, COV_TYPE_P2PK, p2pk_cov)],
+            );
+            tx.inputs[0].script_sig = vec![0x5a; len];
+            tx.witness = vec![
+                WitnessItem {
+                    suite_id: SUITE_ID_SENTINEL,
+                    pubkey: refund_key_id.to_vec(),
+                    signature: vec![0x01],
+                },
+                WitnessItem {
+                    suite_id: SUITE_ID_ML_DSA_87,
+                    pubkey: vec![0u8; ML_DSA_87_PUBKEY_BYTES as usize],
+                    signature: vec![0u8; ML_DSA_87_SIG_BYTES as usize + 1],
+                },
+            ];
+            let err = apply_non_coinbase_tx_basic(&tx, [0xc5; 32], &utxo_set, 10, 0, [0u8; 32])
+                .expect_err("non-empty script_sig");
+            assert_eq!(err.code, ErrorCode::TxErrParse, "len={len}");
+            assert!(
+                err.to_string().contains("script_sig must be empty"),
+                "len={len}: {err}"
+            );
+        }
+    }
+
     #[test]
     fn apply_non_coinbase_tx_basic_update_stealth_does_not_mutate_caller_utxos() {
         let (tx, utxo_set, txid, chain_id) = signed_stealth_case();
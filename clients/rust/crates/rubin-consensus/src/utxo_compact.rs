@@ -0,0 +1,255 @@
+use crate::constants::MAX_P2PK_COVENANT_DATA;
+use crate::utxo_basic::UtxoEntry;
+
+/// Covenant data up to this length is stored inline; a P2PK output
+/// (suite id + 32-byte key id) fits without a heap allocation.
+pub const COMPACT_UTXO_INLINE_COVENANT_BYTES: usize = MAX_P2PK_COVENANT_DATA as usize;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum CovenantBytes {
+    Inline {
+        len: u8,
+        bytes: [u8; COMPACT_UTXO_INLINE_COVENANT_BYTES],
+    },
+    Heap(Box<[u8]>),
+}
+
+impl CovenantBytes {
+    fn from_slice(data: &[u8]) -> Self {
+        if data.len() <= COMPACT_UTXO_INLINE_COVENANT_BYTES {
+            let mut bytes = [0u8; COMPACT_UTXO_INLINE_COVENANT_BYTES];
+            bytes[..data.len()].copy_from_slice(data);
+            CovenantBytes::Inline {
+                len: data.len() as u8,
+                bytes,
+            }
+        } else {
+            CovenantBytes::Heap(data.into())
+        }
+    }
+
+    fn as_slice(&self) -> &[u8] {
+        match self {
+            CovenantBytes::Inline { len, bytes } => &bytes[..usize::from(*len)],
+            CovenantBytes::Heap(bytes) => bytes,
+        }
+    }
+}
+
+/// In-memory form of a `UtxoEntry` for large UTXO sets: covenant data is
+/// kept inline when it fits and otherwise in an exact-size boxed slice, so
+/// the common P2PK entry costs no heap allocation and no spare capacity is
+/// retained. Conversions to and from `UtxoEntry` are lossless; consensus
+/// APIs keep taking `UtxoEntry`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CompactUtxo {
+    pub value: u64,
+    pub creation_height: u64,
+    covenant_data: CovenantBytes,
+    pub covenant_type: u16,
+    pub created_by_coinbase: bool,
+}
+
+impl CompactUtxo {
+    pub fn covenant_data(&self) -> &[u8] {
+        self.covenant_data.as_slice()
+    }
+
+    /// Heap bytes owned by this entry beyond `size_of::<CompactUtxo>()`.
+    pub fn heap_bytes(&self) -> usize {
+        match &self.covenant_data {
+            CovenantBytes::Inline { .. } => 0,
+            CovenantBytes::Heap(bytes) => bytes.len(),
+        }
+    }
+
+    pub fn to_entry(&self) -> UtxoEntry {
+        UtxoEntry::from(self)
+    }
+}
+
+impl From<&UtxoEntry> for CompactUtxo {
+    fn from(entry: &UtxoEntry) -> Self {
+        Self {
+            value: entry.value,
+            creation_height: entry.creation_height,
+            covenant_data: CovenantBytes::from_slice(&entry.covenant_data),
+            covenant_type: entry.covenant_type,
+            created_by_coinbase: entry.created_by_coinbase,
+        }
+    }
+}
+
+impl From<UtxoEntry> for CompactUtxo {
+    fn from(entry: UtxoEntry) -> Self {
+        if entry.covenant_data.len() <= COMPACT_UTXO_INLINE_COVENANT_BYTES {
+            return Self::from(&entry);
+        }
+        Self {
+            value: entry.value,
+            creation_height: entry.creation_height,
+            covenant_data: CovenantBytes::Heap(entry.covenant_data.into_boxed_slice()),
+            covenant_type: entry.covenant_type,
+            created_by_coinbase: entry.created_by_coinbase,
+        }
+    }
+}
+
+impl From<&CompactUtxo> for UtxoEntry {
+    fn from(compact: &CompactUtxo) -> Self {
+        UtxoEntry {
+            value: compact.value,
+            covenant_type: compact.covenant_type,
+            covenant_data: compact.covenant_data().to_vec(),
+            creation_height: compact.creation_height,
+            created_by_coinbase: compact.created_by_coinbase,
+        }
+    }
+}
+
+impl From<CompactUtxo> for UtxoEntry {
+    fn from(compact: CompactUtxo) -> Self {
+        let covenant_data = match compact.covenant_data {
+            CovenantBytes::Heap(bytes) => bytes.into_vec(),
+            inline => inline.as_slice().to_vec(),
+        };
+        UtxoEntry {
+            value: compact.value,
+            covenant_type: compact.covenant_type,
+            covenant_data,
+            creation_height: compact.creation_height,
+            created_by_coinbase: compact.created_by_coinbase,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::mem::size_of;
+
+    use super::{CompactUtxo, COMPACT_UTXO_INLINE_COVENANT_BYTES};
+    use crate::constants::{
+        COV_TYPE_ANCHOR, COV_TYPE_CORE_EXT, COV_TYPE_CORE_SIMPLICITY, COV_TYPE_CORE_STEALTH,
+        COV_TYPE_DA_COMMIT, COV_TYPE_HTLC, COV_TYPE_MULTISIG, COV_TYPE_P2PK,
+        COV_TYPE_RESERVED_FUTURE, COV_TYPE_VAULT, MAX_HTLC_COVENANT_DATA, MAX_P2PK_COVENANT_DATA,
+    };
+    use crate::utxo_basic::UtxoEntry;
+
+    const VAULT_COVENANT_DATA_LEN: usize = 81;
+
+    /// (covenant_type, covenant_data_len, share_of_100).
+    type MixRow = (u16, usize, usize);
+
+    fn entry(covenant_type: u16, len: usize, seed: u8) -> UtxoEntry {
+        UtxoEntry {
+            value: 50_000 + u64::from(seed),
+            covenant_type,
+            covenant_data: (0..len).map(|i| (i as u8) ^ seed).collect(),
+            creation_height: 1_000 + u64::from(seed),
+            created_by_coinbase: seed % 2 == 1,
+        }
+    }
+
+    /// Footprint of a `UtxoEntry` as it sits in a map: the struct plus its
+    /// covenant data allocation.
+    fn legacy_bytes(e: &UtxoEntry) -> usize {
+        size_of::<UtxoEntry>() + e.covenant_data.capacity()
+    }
+
+    fn compact_bytes(c: &CompactUtxo) -> usize {
+        size_of::<CompactUtxo>() + c.heap_bytes()
+    }
+
+    #[test]
+    fn conversions_are_lossless_for_every_covenant_type() {
+        let p2pk = MAX_P2PK_COVENANT_DATA as usize;
+        let htlc = MAX_HTLC_COVENANT_DATA as usize;
+        let cases = [
+            (COV_TYPE_P2PK, vec![0, p2pk]),
+            (COV_TYPE_ANCHOR, vec![1, 32, 65_536]),
+            (COV_TYPE_HTLC, vec![htlc]),
+            (COV_TYPE_VAULT, vec![VAULT_COVENANT_DATA_LEN]),
+            (COV_TYPE_MULTISIG, vec![34, 66, 2 + 32 * 12]),
+            (COV_TYPE_DA_COMMIT, vec![32]),
+            (COV_TYPE_CORE_EXT, vec![2, 3, 40]),
+            (COV_TYPE_CORE_STEALTH, vec![1_600]),
+            (COV_TYPE_CORE_SIMPLICITY, vec![33, 34, 256]),
+            (COV_TYPE_RESERVED_FUTURE, vec![0]),
+        ];
+        for (covenant_type, lens) in cases {
+            for len in lens {
+                for seed in [0u8, 0x5a, 0xff] {
+                    let original = entry(covenant_type, len, seed);
+                    let by_ref = CompactUtxo::from(&original);
+                    let by_value = CompactUtxo::from(original.clone());
+                    assert_eq!(by_ref, by_value, "type={covenant_type:#06x} len={len}");
+                    assert_eq!(by_ref.covenant_data(), original.covenant_data.as_slice());
+                    assert_eq!(by_ref.to_entry(), original);
+                    assert_eq!(UtxoEntry::from(by_value), original);
+                    let inline = len <= COMPACT_UTXO_INLINE_COVENANT_BYTES;
+                    assert_eq!(by_ref.heap_bytes() == 0, inline, "len={len}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn inline_boundary_does_not_alias_trailing_bytes() {
+        // Shorter data must not compare equal to a longer prefix-sharing one.
+        let short = CompactUtxo::from(&entry(COV_TYPE_P2PK, 32, 7));
+        let long = CompactUtxo::from(&entry(COV_TYPE_P2PK, 33, 7));
+        assert_ne!(short, long);
+        assert_eq!(short.covenant_data().len(), 32);
+        assert_eq!(long.covenant_data().len(), 33);
+    }
+
+    #[test]
+    fn bytes_per_entry_for_representative_mixes() {
+        let p2pk = MAX_P2PK_COVENANT_DATA as usize;
+        let htlc = MAX_HTLC_COVENANT_DATA as usize;
+        let mixes: [(&str, &[MixRow]); 3] = [
+            ("p2pk-only", &[(COV_TYPE_P2PK, p2pk, 100)]),
+            (
+                "typical",
+                &[
+                    (COV_TYPE_P2PK, p2pk, 90),
+                    (COV_TYPE_ANCHOR, 32, 4),
+                    (COV_TYPE_MULTISIG, 2 + 32 * 3, 3),
+                    (COV_TYPE_VAULT, VAULT_COVENANT_DATA_LEN, 2),
+                    (COV_TYPE_HTLC, htlc, 1),
+                ],
+            ),
+            (
+                "covenant-heavy",
+                &[
+                    (COV_TYPE_P2PK, p2pk, 40),
+                    (COV_TYPE_VAULT, VAULT_COVENANT_DATA_LEN, 30),
+                    (COV_TYPE_HTLC, htlc, 30),
+                ],
+            ),
+        ];
+        for (label, mix) in mixes {
+            let (mut legacy, mut compact, mut n) = (0usize, 0usize, 0usize);
+            for &(covenant_type, len, share) in mix {
+                for seed in 0..share {
+                    let e = entry(covenant_type, len, seed as u8);
+                    let c = CompactUtxo::from(&e);
+                    legacy += legacy_bytes(&e);
+                    compact += compact_bytes(&c);
+                    n += 1;
+                }
+            }
+            println!(
+                "utxo bytes/entry [{label}]: legacy={} compact={}",
+                legacy / n,
+                compact / n
+            );
+            if label != "covenant-heavy" {
+                assert!(compact < legacy, "{label}: {compact} >= {legacy}");
+            }
+        }
+        // The inline case never allocates, so it is bounded by the struct.
+        let c = CompactUtxo::from(&entry(COV_TYPE_P2PK, p2pk, 1));
+        assert_eq!(compact_bytes(&c), size_of::<CompactUtxo>());
+    }
+}
@@ -1,21 +1,26 @@
-use std::collections::HashMap;
-
+use crate::connect_block_inmem::canonical_outpoint_key;
 use crate::error::{ErrorCode, TxError};
+use crate::outpoint_map::OutpointMap;
 use crate::tx::Tx;
 use crate::utxo_basic::{Outpoint, UtxoEntry};
+use crate::utxo_compact::CompactUtxo;
 
 /// Immutable block-start view of the UTXO set for read-only input resolution.
+/// Entries are held as `CompactUtxo` and handed out as owned `UtxoEntry`.
 #[derive(Clone, Debug, Default, PartialEq, Eq)]
 pub struct UtxoSnapshot {
-    utxos: HashMap<Outpoint, UtxoEntry>,
+    utxos: OutpointMap<CompactUtxo>,
     count: usize,
 }
 
 impl UtxoSnapshot {
-    pub fn new(utxos: Option<&HashMap<Outpoint, UtxoEntry>>) -> Self {
+    pub fn new(utxos: Option<&OutpointMap<UtxoEntry>>) -> Self {
         match utxos {
             Some(utxos) => {
-                let utxos = utxos.clone();
+                let utxos: OutpointMap<CompactUtxo> = utxos
+                    .iter()
+                    .map(|(op, entry)| (op.clone(), CompactUtxo::from(entry)))
+                    .collect();
                 let count = utxos.len();
                 Self { utxos, count }
             }
@@ -23,8 +28,8 @@ impl UtxoSnapshot {
         }
     }
 
-    pub fn get(&self, op: &Outpoint) -> Option<&UtxoEntry> {
-        self.utxos.get(op)
+    pub fn get(&self, op: &Outpoint) -> Option<UtxoEntry> {
+        self.utxos.get(op).map(UtxoEntry::from)
     }
 
     pub fn contains(&self, op: &Outpoint) -> bool {
@@ -42,7 +47,7 @@ impl UtxoSnapshot {
                 txid: input.prev_txid,
                 vout: input.prev_vout,
             };
-            let entry = self.get(&op).cloned().ok_or_else(|| {
+            let entry = self.get(&op).ok_or_else(|| {
                 TxError::new(ErrorCode::TxErrMissingUtxo, "input references missing UTXO")
             })?;
             entries.push(entry);
@@ -50,12 +55,17 @@ impl UtxoSnapshot {
         Ok(entries)
     }
 
+    /// Visits every entry in `canonical_outpoint_key` order, the order
+    /// `utxo_set_hash` commits to, so anything exported from a snapshot is
+    /// independent of map layout and hasher seed.
     pub fn for_each<F>(&self, mut f: F)
     where
         F: FnMut(Outpoint, UtxoEntry),
     {
-        for (op, entry) in &self.utxos {
-            f(op.clone(), entry.clone());
+        let mut entries: Vec<(&Outpoint, &CompactUtxo)> = self.utxos.iter().collect();
+        entries.sort_by_cached_key(|(op, _)| canonical_outpoint_key(op));
+        for (op, entry) in entries {
+            f(op.clone(), entry.to_entry());
         }
     }
 }
@@ -120,10 +130,11 @@ mod tests {
     use std::collections::HashMap;
     use std::sync::{Arc, Mutex};
 
+    use crate::outpoint_map::OutpointMap;
     use crate::tx::{Tx, TxInput};
     use crate::utxo_basic::{Outpoint, UtxoEntry};
 
-    use super::{utxo_snapshot_shard, UtxoSnapshot};
+    use super::{canonical_outpoint_key, utxo_snapshot_shard, UtxoSnapshot};
 
     fn test_outpoint(txid_byte: u8, vout: u32) -> Outpoint {
         let mut txid = [0u8; 32];
@@ -141,8 +152,8 @@ mod tests {
         }
     }
 
-    fn test_utxo_set() -> HashMap<Outpoint, UtxoEntry> {
-        HashMap::from([
+    fn test_utxo_set() -> OutpointMap<UtxoEntry> {
+        OutpointMap::from_iter([
             (test_outpoint(0xaa, 0), test_entry(1000, 0)),
             (test_outpoint(0xaa, 1), test_entry(2000, 0)),
             (test_outpoint(0xbb, 0), test_entry(3000, 1)),
@@ -182,7 +193,7 @@ mod tests {
 
     #[test]
     fn utxo_snapshot_empty_map_is_empty() {
-        let utxos = HashMap::new();
+        let utxos = OutpointMap::default();
         let snapshot = UtxoSnapshot::new(Some(&utxos));
         assert_eq!(snapshot.count(), 0);
     }
@@ -326,9 +337,31 @@ mod tests {
         assert_eq!(*total_value.lock().unwrap(), 10_000);
     }
 
+    #[test]
+    fn utxo_snapshot_for_each_is_canonically_ordered() {
+        let forward = test_utxo_set();
+        let mut reversed: Vec<_> = forward.clone().into_iter().collect();
+        reversed.reverse();
+        let reversed: OutpointMap<UtxoEntry> = reversed.into_iter().collect();
+
+        let visit = |utxos: &OutpointMap<UtxoEntry>| {
+            let mut seen = Vec::new();
+            UtxoSnapshot::new(Some(utxos)).for_each(|op, entry| seen.push((op, entry.value)));
+            seen
+        };
+        let seen = visit(&forward);
+        assert_eq!(seen, visit(&reversed));
+        let keys: Vec<[u8; 36]> = seen
+            .iter()
+            .map(|(op, _)| canonical_outpoint_key(op))
+            .collect();
+        assert!(keys.windows(2).all(|w| w[0] < w[1]));
+        assert_eq!(seen.len(), 4);
+    }
+
     #[test]
     fn utxo_snapshot_sequential_parallel_parity() {
-        let mut utxos = HashMap::with_capacity(100);
+        let mut utxos = OutpointMap::with_capacity_and_hasher(100, Default::default());
         for i in 0..100u8 {
             utxos.insert(
                 test_outpoint(i, 0),
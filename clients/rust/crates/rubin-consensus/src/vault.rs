@@ -5,6 +5,7 @@ use crate::constants::{
     MAX_VAULT_WHITELIST_ENTRIES, SIMPLICITY_WITNESS_SLOTS,
 };
 use crate::error::{ErrorCode, TxError};
+use crate::hash::sha3_256;
 
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct VaultCovenant {
@@ -23,6 +24,38 @@ pub struct MultisigCovenant {
     pub keys: Vec<[u8; 32]>,
 }
 
+impl VaultCovenant {
+    /// Canonical covenant_data encoding; inverse of [`parse_vault_covenant_data`].
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out =
+            Vec::with_capacity(32 + 1 + 1 + 32 * self.keys.len() + 2 + 32 * self.whitelist.len());
+        out.extend_from_slice(&self.owner_lock_id);
+        out.push(self.threshold);
+        out.push(self.key_count);
+        for key in &self.keys {
+            out.extend_from_slice(key);
+        }
+        out.extend_from_slice(&self.whitelist_count.to_le_bytes());
+        for entry in &self.whitelist {
+            out.extend_from_slice(entry);
+        }
+        out
+    }
+}
+
+impl MultisigCovenant {
+    /// Canonical covenant_data encoding; inverse of [`parse_multisig_covenant_data`].
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(multisig_expected_len(self.key_count));
+        out.push(self.threshold);
+        out.push(self.key_count);
+        for key in &self.keys {
+            out.extend_from_slice(key);
+        }
+        out
+    }
+}
+
 pub fn parse_vault_covenant_data(covenant_data: &[u8]) -> Result<VaultCovenant, TxError> {
     parse_vault_covenant_data_inner(covenant_data, true)
 }
@@ -202,6 +235,8 @@ pub fn parse_multisig_covenant_data(covenant_data: &[u8]) -> Result<MultisigCove
     })
 }
 
+/// `covenant_type:u16le || CompactSize(len) || covenant_data`, the canonical
+/// byte form of an output's lock.
 pub fn output_descriptor_bytes(covenant_type: u16, covenant_data: &[u8]) -> Vec<u8> {
     let mut out = Vec::with_capacity(2 + 9 + covenant_data.len());
     out.extend_from_slice(&covenant_type.to_le_bytes());
@@ -210,6 +245,12 @@ pub fn output_descriptor_bytes(covenant_type: u16, covenant_data: &[u8]) -> Vec<
     out
 }
 
+/// SHA3-256 of `output_descriptor_bytes`: the lock_id of an output, as
+/// committed to by vault owner locks and whitelists.
+pub fn output_descriptor_hash(covenant_type: u16, covenant_data: &[u8]) -> [u8; 32] {
+    sha3_256(&output_descriptor_bytes(covenant_type, covenant_data))
+}
+
 pub fn witness_slots(covenant_type: u16, covenant_data: &[u8]) -> Result<usize, TxError> {
     match covenant_type {
         COV_TYPE_P2PK => Ok(1),
@@ -53,6 +53,15 @@ fn read_mldsa87_pubkey(pkey: *mut openssl_sys::EVP_PKEY) -> Result<Vec<u8>, TxEr
 }
 
 fn new_digest_sign_ctx(keypair: &Mldsa87Keypair) -> Result<*mut openssl_sys::EVP_MD_CTX, TxError> {
+    new_digest_sign_ctx_with_params(keypair, core::ptr::null())
+}
+
+/// `params` is a null pointer or an `OSSL_PARAM` array ending in
+/// `OSSL_PARAM_construct_end()` that outlives the call.
+fn new_digest_sign_ctx_with_params(
+    keypair: &Mldsa87Keypair,
+    params: *const core::ffi::c_void,
+) -> Result<*mut openssl_sys::EVP_MD_CTX, TxError> {
     let pkey = keypair.pkey;
     if pkey.is_null() {
         return Err(openssl_parse_error("openssl: nil ML-DSA keypair"));
@@ -74,7 +83,7 @@ fn new_digest_sign_ctx(keypair: &Mldsa87Keypair) -> Result<*mut openssl_sys::EVP
             core::ptr::null_mut(),
             core::ptr::null(),
             pkey,
-            core::ptr::null(),
+            params,
         ) <= 0
         {
             ffi::EVP_MD_CTX_free(mctx);
@@ -165,6 +174,66 @@ impl Mldsa87Keypair {
         }
     }
 
+    /// Derives the keypair from a 32-byte FIPS 204 key-generation seed, so
+    /// one seed always yields one key. Meant for reproducible devnet
+    /// fixtures; real keys come from `generate`.
+    pub fn from_seed(seed: &[u8; 32]) -> Result<Self, TxError> {
+        ensure_openssl_bootstrap()?;
+        let alg = suite_alg_name(SUITE_ID_ML_DSA_87)?;
+        let mut seed_buf = *seed;
+        unsafe {
+            // SAFETY: alg is a static NUL-terminated CStr from the suite
+            // registry. params points at seed_buf, which lives on this frame
+            // until EVP_PKEY_fromdata returns, and ends with the terminator.
+            // ctx is freed on every path after allocation; on success pkey
+            // ownership moves to read_mldsa87_pubkey (freed on its failure)
+            // and then into the returned keypair.
+            openssl_sys::ERR_clear_error();
+            let ctx = ffi::EVP_PKEY_CTX_new_from_name(
+                core::ptr::null_mut(),
+                alg.as_ptr(),
+                core::ptr::null(),
+            );
+            if ctx.is_null() {
+                return Err(openssl_parse_error(ERR_KEY_CTX));
+            }
+            if openssl_sys::EVP_PKEY_fromdata_init(ctx) <= 0 {
+                openssl_sys::EVP_PKEY_CTX_free(ctx);
+                return Err(openssl_parse_error(
+                    "openssl: EVP_PKEY_fromdata_init failed",
+                ));
+            }
+            let mut params = [
+                openssl_sys::OSSL_PARAM_construct_octet_string(
+                    c"seed".as_ptr(),
+                    seed_buf.as_mut_ptr().cast(),
+                    seed_buf.len(),
+                ),
+                openssl_sys::OSSL_PARAM_construct_end(),
+            ];
+            let mut pkey: *mut openssl_sys::EVP_PKEY = core::ptr::null_mut();
+            if openssl_sys::EVP_PKEY_fromdata(
+                ctx,
+                &mut pkey,
+                openssl_sys::EVP_PKEY_KEYPAIR,
+                params.as_mut_ptr(),
+            ) <= 0
+                || pkey.is_null()
+            {
+                openssl_sys::EVP_PKEY_CTX_free(ctx);
+                if !pkey.is_null() {
+                    openssl_sys::EVP_PKEY_free(pkey);
+                }
+                return Err(openssl_parse_error(
+                    "openssl: EVP_PKEY_fromdata(seed) failed",
+                ));
+            }
+            openssl_sys::EVP_PKEY_CTX_free(ctx);
+            let pubkey = read_mldsa87_pubkey(pkey)?;
+            Ok(Self { pkey, pubkey })
+        }
+    }
+
     pub fn pubkey_bytes(&self) -> Vec<u8> {
         self.pubkey.clone()
     }
@@ -173,6 +242,27 @@ impl Mldsa87Keypair {
         let mctx = new_digest_sign_ctx(self)?;
         sign_mldsa87_digest(mctx, digest32)
     }
+
+    /// Signs with the deterministic ML-DSA variant: the same key and digest
+    /// always give the same signature. Both variants verify identically;
+    /// this one only gives up the hedging randomness, so keep it to
+    /// fixtures.
+    pub fn sign_digest32_deterministic(&self, digest32: [u8; 32]) -> Result<Vec<u8>, TxError> {
+        let mut deterministic: core::ffi::c_uint = 1;
+        // SAFETY: the array borrows `deterministic`, which outlives the
+        // signing-context setup below, and ends with the terminator.
+        let params = unsafe {
+            [
+                openssl_sys::OSSL_PARAM_construct_uint(
+                    c"deterministic".as_ptr(),
+                    &mut deterministic,
+                ),
+                openssl_sys::OSSL_PARAM_construct_end(),
+            ]
+        };
+        let mctx = new_digest_sign_ctx_with_params(self, params.as_ptr().cast())?;
+        sign_mldsa87_digest(mctx, digest32)
+    }
 }
 
 impl DigestSigner for Mldsa87Keypair {
@@ -283,6 +373,43 @@ fn openssl_consensus_bootstrap() -> Result<(), TxError> {
     Ok(())
 }
 
+/// Description of the signature backend this build verifies with, for
+/// version reporting.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CryptoProviderInfo {
+    pub name: &'static str,
+    /// `OpenSSL_version(OPENSSL_VERSION)` of the library loaded at runtime.
+    pub library_version: String,
+    /// `RUBIN_OPENSSL_FIPS_MODE` as parsed: `off`, `ready`, `only` or `invalid`.
+    pub fips_mode: &'static str,
+    /// Whether the consensus bootstrap succeeded, ML-DSA-87 included.
+    pub ml_dsa_87_available: bool,
+}
+
+pub fn crypto_provider_info() -> CryptoProviderInfo {
+    let library_version = unsafe {
+        // SAFETY: OpenSSL_version returns a pointer to a static NUL-terminated
+        // string owned by the library; it is never null for OPENSSL_VERSION.
+        CStr::from_ptr(openssl_sys::OpenSSL_version(openssl_sys::OPENSSL_VERSION))
+            .to_string_lossy()
+            .into_owned()
+    };
+    let fips_mode = match bootstrap::parse_openssl_fips_mode(
+        &std::env::var("RUBIN_OPENSSL_FIPS_MODE").unwrap_or_default(),
+    ) {
+        Ok(bootstrap::OpenSslFipsMode::Off) => "off",
+        Ok(bootstrap::OpenSslFipsMode::Ready) => "ready",
+        Ok(bootstrap::OpenSslFipsMode::Only) => "only",
+        Err(_) => "invalid",
+    };
+    CryptoProviderInfo {
+        name: "openssl",
+        library_version,
+        fips_mode,
+        ml_dsa_87_available: ensure_openssl_consensus_init().is_ok(),
+    }
+}
+
 pub(crate) fn ensure_openssl_consensus_init() -> Result<(), TxError> {
     OPENSSL_CONSENSUS_INIT
         .get_or_init(openssl_consensus_bootstrap)
@@ -295,19 +422,7 @@ pub fn verify_sig(
     signature: &[u8],
     digest32: &[u8; 32],
 ) -> Result<bool, TxError> {
-    if suite_id != SUITE_ID_ML_DSA_87 {
-        return Err(TxError::new(
-            ErrorCode::TxErrSigAlgInvalid,
-            "verify_sig: unsupported suite_id",
-        ));
-    }
-    ensure_openssl_consensus_init()?;
-    let binding = binding::resolve_suite_verifier_binding(
-        "ML-DSA-87",
-        ML_DSA_87_PUBKEY_BYTES,
-        ML_DSA_87_SIG_BYTES,
-    )?;
-    binding::verify_sig_with_binding(&binding, pubkey, signature, digest32)
+    verify_sig_with_registry(suite_id, pubkey, signature, digest32, None)
 }
 
 // v1 keeps the current live verifier contract pinned to the canonical
@@ -6,11 +6,6 @@ use crate::live_binding_policy::{
     live_binding_policy_runtime_entry, LiveBindingPolicyLookupError,
     LIVE_BINDING_POLICY_RUNTIME_OPENSSL_DIGEST32_V1,
 };
-use std::sync::OnceLock;
-
-static DEFAULT_RUNTIME_SUITE_REGISTRY: OnceLock<crate::suite_registry::SuiteRegistry> =
-    OnceLock::new();
-
 pub(super) enum SuiteVerifierBinding {
     OpenSslDigest32V1 {
         alg: &'static core::ffi::CStr,
@@ -103,8 +98,7 @@ pub(super) fn verify_sig_with_binding(
 }
 
 pub(super) fn default_runtime_suite_registry() -> &'static crate::suite_registry::SuiteRegistry {
-    DEFAULT_RUNTIME_SUITE_REGISTRY
-        .get_or_init(crate::suite_registry::SuiteRegistry::default_registry)
+    crate::suite_registry::default_suite_registry()
 }
 
 pub(super) fn runtime_verification_registry_with_default<'a>(
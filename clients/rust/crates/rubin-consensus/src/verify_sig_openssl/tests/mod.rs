@@ -99,6 +99,16 @@ fn map_openssl_init_rc_maps_failure_to_parse() {
     assert_eq!(err.msg, "bootstrap failed");
 }
 
+#[test]
+fn provider_failures_are_told_apart_from_verdicts() {
+    let err = parse_openssl_fips_mode("definitely-invalid").expect_err("unknown mode");
+    assert!(err.is_crypto_provider(), "{err}");
+    let err = map_digest_verify_rc(-1).expect_err("rc<0 is an invalid signature");
+    assert!(!err.is_crypto_provider(), "{err}");
+    let err = crate::error::TxError::new(ErrorCode::TxErrParse, "truncated input");
+    assert!(!err.is_crypto_provider());
+}
+
 #[test]
 fn parse_openssl_fips_mode_accepts_supported_values() {
     assert_eq!(
@@ -179,6 +189,46 @@ fn mldsa87_keypair_generate_sign_and_verify_roundtrip() {
     assert!(ok);
 }
 
+#[test]
+fn mldsa87_keypair_from_seed_is_reproducible_and_signs_deterministically() {
+    let _guard = OpenSslEnvGuard::acquire();
+    let keypair = match Mldsa87Keypair::from_seed(&[0x5a; 32]) {
+        Ok(value) => value,
+        Err(err) => {
+            assert_eq!(err.code, ErrorCode::TxErrParse);
+            assert!(
+                err.msg.contains("EVP_PKEY_CTX_new_from_name"),
+                "{}",
+                err.msg
+            );
+            return;
+        }
+    };
+    let again = Mldsa87Keypair::from_seed(&[0x5a; 32]).expect("same seed");
+    let other = Mldsa87Keypair::from_seed(&[0xa5; 32]).expect("other seed");
+    assert_eq!(keypair.pubkey_bytes(), again.pubkey_bytes());
+    assert_ne!(keypair.pubkey_bytes(), other.pubkey_bytes());
+
+    let digest = [0x42; 32];
+    let signature = keypair
+        .sign_digest32_deterministic(digest)
+        .expect("sign digest");
+    assert_eq!(
+        signature,
+        again
+            .sign_digest32_deterministic(digest)
+            .expect("sign digest again")
+    );
+    let ok = super::verify_sig(
+        crate::constants::SUITE_ID_ML_DSA_87,
+        &keypair.pubkey_bytes(),
+        &signature,
+        &digest,
+    )
+    .expect("verify signature");
+    assert!(ok);
+}
+
 #[test]
 fn openssl_consensus_bootstrap_ignores_inherited_openssl_env() {
     if std::env::var_os(CONSENSUS_BOOTSTRAP_COLD_START_CHILD).is_some() {
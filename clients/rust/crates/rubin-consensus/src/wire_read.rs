@@ -3,22 +3,40 @@ use crate::error::{ErrorCode, TxError};
 pub struct Reader<'a> {
     b: &'a [u8],
     off: usize,
+    eof_needed: Option<usize>,
 }
 
 impl<'a> Reader<'a> {
     pub fn new(b: &'a [u8]) -> Self {
-        Self { b, off: 0 }
+        Self {
+            b,
+            off: 0,
+            eof_needed: None,
+        }
+    }
+
+    /// Input length the failed read needed, when it failed by running out of
+    /// input: the same read succeeds once that many bytes are available.
+    pub fn eof_needed(&self) -> Option<usize> {
+        self.eof_needed
     }
 
     pub fn offset(&self) -> usize {
         self.off
     }
 
-    fn checked_end(&self, n: usize, eof_msg: &'static str) -> Result<usize, TxError> {
-        self.off
-            .checked_add(n)
-            .filter(|end| *end <= self.b.len())
-            .ok_or_else(|| TxError::new(ErrorCode::TxErrParse, eof_msg))
+    /// Bytes left to read.
+    pub fn remaining(&self) -> usize {
+        self.b.len() - self.off
+    }
+
+    fn checked_end(&mut self, n: usize, eof_msg: &'static str) -> Result<usize, TxError> {
+        let end = self.off.saturating_add(n);
+        if end > self.b.len() {
+            self.eof_needed = Some(end);
+            return Err(TxError::new(ErrorCode::TxErrParse, eof_msg));
+        }
+        Ok(end)
     }
 
     pub fn read_u8(&mut self) -> Result<u8, TxError> {
@@ -49,6 +67,22 @@ impl<'a> Reader<'a> {
         Ok(v)
     }
 
+    /// Reads a minimal CompactSize length or count bounded by `max`; a larger
+    /// value fails with `TxErrParse` carrying `overflow_msg`.
+    pub fn read_compact_size_usize(
+        &mut self,
+        max: usize,
+        overflow_msg: &'static str,
+    ) -> Result<usize, TxError> {
+        crate::compactsize::read_compact_size_bounded(
+            self,
+            max,
+            ErrorCode::TxErrParse,
+            overflow_msg,
+        )
+        .map(|(v, _)| v)
+    }
+
     pub fn read_bytes(&mut self, n: usize) -> Result<&'a [u8], TxError> {
         let end = self.checked_end(n, "unexpected EOF (bytes)")?;
         let v = &self.b[self.off..end];
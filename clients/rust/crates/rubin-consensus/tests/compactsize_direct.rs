@@ -1,4 +1,6 @@
-use rubin_consensus::{encode_compact_size, read_compact_size_bytes, ErrorCode};
+use rubin_consensus::{
+    compact_size_decode_bounded, encode_compact_size, read_compact_size_bytes, ErrorCode,
+};
 
 // =============================================================
 // encode_compact_size — encoding correctness
@@ -313,3 +315,34 @@ fn decode_fd_with_trailing() {
     assert_eq!(v, 253);
     assert_eq!(consumed, 3);
 }
+
+// =============================================================
+// compact_size_decode_bounded — bound and minimality in one place
+// =============================================================
+
+#[test]
+fn bounded_accepts_value_at_max() {
+    assert_eq!(
+        compact_size_decode_bounded(&[0xfd, 0x00, 0x04], 1024).unwrap(),
+        (1024, 3)
+    );
+}
+
+#[test]
+fn bounded_rejects_value_over_max() {
+    let err = compact_size_decode_bounded(&[0xfd, 0x01, 0x04], 1024).unwrap_err();
+    assert_eq!(err.code, ErrorCode::TxErrParse);
+    assert_eq!(err.msg, "CompactSize exceeds bound");
+}
+
+#[test]
+fn bounded_rejects_u64_max_without_truncation() {
+    let err = compact_size_decode_bounded(&[0xff; 9], usize::MAX - 1).unwrap_err();
+    assert_eq!(err.msg, "CompactSize exceeds bound");
+}
+
+#[test]
+fn bounded_still_rejects_nonminimal() {
+    let err = compact_size_decode_bounded(&[0xfd, 0x01, 0x00], usize::MAX).unwrap_err();
+    assert!(err.msg.contains("non-minimal"));
+}
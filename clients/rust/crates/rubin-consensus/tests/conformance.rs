@@ -0,0 +1,413 @@
+//! In-process conformance vector runner. Executes `conformance/fixtures`-style
+//! JSON vectors directly against the library (no CLI subprocess) and checks
+//! the expected ok/err code plus the per-op outputs the cross-client runner
+//! compares.
+//!
+//! By default the vendored mini set under `tests/conformance_vectors/` is
+//! used. Point `RUBIN_CONFORMANCE_FIXTURES_DIR` at the full fixture tree to run
+//! everything; ops this harness does not implement (and vectors whose inputs
+//! are expanded by the Python runner) are listed in the summary instead of
+//! being dropped silently.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use rubin_consensus::constants::MAX_FUTURE_DRIFT;
+use rubin_consensus::merkle::witness_merkle_root_wtxids;
+use rubin_consensus::{
+    block_hash, check_timestamp_bounds, connect_block_basic_in_memory_at_height, merkle_root_txids,
+    parse_tx, pow_check, retarget_v1, sighash_v1_digest, tx_weight_and_stats_public,
+    validate_block_basic_with_context_at_height, ErrorCode, InMemoryChainState, Outpoint,
+    OutpointMap, UtxoEntry,
+};
+use serde_json::Value;
+
+const FIXTURES_DIR_ENV: &str = "RUBIN_CONFORMANCE_FIXTURES_DIR";
+
+/// Vector fields that the Python runner expands into concrete inputs before
+/// invoking a client; this harness reports such vectors as skipped.
+const RUNNER_EXPANDED_FIELDS: &[&str] = &[
+    "tx_hex_parts",
+    "window_pattern",
+    "scenario",
+    "rotation_descriptor",
+    "suite_registry",
+    "core_ext_profiles",
+];
+
+enum Outcome {
+    Pass,
+    Fail(String),
+    Skip(String),
+}
+
+fn fixtures_dir() -> PathBuf {
+    match std::env::var_os(FIXTURES_DIR_ENV) {
+        Some(dir) => PathBuf::from(dir),
+        None => Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/conformance_vectors"),
+    }
+}
+
+fn str_field<'a>(v: &'a Value, key: &str) -> &'a str {
+    v.get(key).and_then(Value::as_str).unwrap_or_default()
+}
+
+/// A required u64 field (a JSON number or decimal string). A missing or
+/// malformed value is a broken fixture, not a zero input.
+fn u64_field(v: &Value, key: &str) -> u64 {
+    let parsed = match v.get(key) {
+        Some(Value::Number(n)) => n.as_u64(),
+        Some(Value::String(s)) => s.parse().ok(),
+        _ => None,
+    };
+    parsed.unwrap_or_else(|| {
+        panic!(
+            "vector {:?}: field {key:?} must be a u64, got {:?}",
+            str_field(v, "id"),
+            v.get(key)
+        )
+    })
+}
+
+/// [`u64_field`] for a field the fixtures may omit.
+fn u64_field_or(v: &Value, key: &str, default: u64) -> u64 {
+    if v.get(key).is_none() {
+        return default;
+    }
+    u64_field(v, key)
+}
+
+fn hex32(s: &str) -> Result<[u8; 32], String> {
+    let raw = hex::decode(s.trim()).map_err(|_| format!("bad hex32 {s:?}"))?;
+    raw.try_into().map_err(|_| format!("bad hex32 {s:?}"))
+}
+
+fn opt_hex32(v: &Value, key: &str) -> Result<Option<[u8; 32]>, String> {
+    let s = str_field(v, key);
+    if s.trim().is_empty() {
+        return Ok(None);
+    }
+    hex32(s).map(Some)
+}
+
+fn hex32_list(v: &Value, key: &str, bad_err: &str) -> Result<Vec<[u8; 32]>, String> {
+    v.get(key)
+        .and_then(Value::as_array)
+        .map(|items| {
+            items
+                .iter()
+                .map(|item| hex32(item.as_str().unwrap_or_default()))
+                .collect::<Result<Vec<_>, _>>()
+        })
+        .unwrap_or_else(|| Ok(Vec::new()))
+        .map_err(|_| bad_err.to_string())
+}
+
+fn u64_list(v: &Value, key: &str) -> Vec<u64> {
+    v.get(key)
+        .and_then(Value::as_array)
+        .map(|items| items.iter().filter_map(Value::as_u64).collect())
+        .unwrap_or_default()
+}
+
+fn utxo_map(v: &Value) -> Result<OutpointMap<UtxoEntry>, String> {
+    let mut utxos = OutpointMap::default();
+    for u in v
+        .get("utxos")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+    {
+        utxos.insert(
+            Outpoint {
+                txid: hex32(str_field(u, "txid"))?,
+                vout: u64_field(u, "vout") as u32,
+            },
+            UtxoEntry {
+                value: u64_field(u, "value"),
+                covenant_type: u64_field(u, "covenant_type") as u16,
+                covenant_data: hex::decode(str_field(u, "covenant_data"))
+                    .map_err(|_| "bad utxo covenant_data".to_string())?,
+                creation_height: u64_field(u, "creation_height"),
+                created_by_coinbase: u
+                    .get("created_by_coinbase")
+                    .and_then(Value::as_bool)
+                    .unwrap_or(false),
+            },
+        );
+    }
+    Ok(utxos)
+}
+
+/// Op output: `Ok(fields)` mirrors the CLI success response, `Err(code)` the
+/// error string.
+type OpResult = Result<BTreeMap<&'static str, Value>, String>;
+
+fn err_code(code: ErrorCode) -> String {
+    code.as_str().to_string()
+}
+
+fn run_op(op: &str, v: &Value) -> Option<OpResult> {
+    let res = match op {
+        "parse_tx" => run_parse_tx(v),
+        "merkle_root" => hex32_list(v, "txids", "bad txid").and_then(|txids| {
+            let root = merkle_root_txids(&txids).map_err(|e| err_code(e.code))?;
+            Ok(BTreeMap::from([("merkle_root", hex::encode(root).into())]))
+        }),
+        "witness_merkle_root" => hex32_list(v, "wtxids", "bad wtxid").and_then(|wtxids| {
+            let root = witness_merkle_root_wtxids(&wtxids).map_err(|e| err_code(e.code))?;
+            Ok(BTreeMap::from([(
+                "witness_merkle_root",
+                hex::encode(root).into(),
+            )]))
+        }),
+        "block_hash" => hex::decode(str_field(v, "header_hex"))
+            .map_err(|_| "bad header".to_string())
+            .and_then(|header| {
+                let hash = block_hash(&header).map_err(|e| err_code(e.code))?;
+                Ok(BTreeMap::from([("block_hash", hex::encode(hash).into())]))
+            }),
+        "pow_check" => run_pow_check(v),
+        "retarget_v1" => hex32(str_field(v, "target_old")).and_then(|old| {
+            let new = retarget_v1(
+                old,
+                u64_field(v, "timestamp_first"),
+                u64_field(v, "timestamp_last"),
+            )
+            .map_err(|e| err_code(e.code))?;
+            Ok(BTreeMap::from([("target_new", hex::encode(new).into())]))
+        }),
+        "sighash_v1" => run_sighash_v1(v),
+        "tx_weight_and_stats" => run_tx_weight_and_stats(v),
+        "timestamp_bounds" => run_timestamp_bounds(v),
+        "block_basic_check" => run_block_basic_check(v),
+        "connect_block_basic" => run_connect_block_basic(v),
+        _ => return None,
+    };
+    Some(res)
+}
+
+fn run_parse_tx(v: &Value) -> OpResult {
+    let raw = hex::decode(str_field(v, "tx_hex")).map_err(|_| "bad hex".to_string())?;
+    let (_tx, txid, wtxid, consumed) = parse_tx(&raw).map_err(|e| err_code(e.code))?;
+    Ok(BTreeMap::from([
+        ("txid", hex::encode(txid).into()),
+        ("wtxid", hex::encode(wtxid).into()),
+        ("consumed", consumed.into()),
+    ]))
+}
+
+fn run_pow_check(v: &Value) -> OpResult {
+    let header = hex::decode(str_field(v, "header_hex")).map_err(|_| "bad header".to_string())?;
+    let target = hex32(str_field(v, "target_hex")).map_err(|_| "bad target".to_string())?;
+    pow_check(&header, target).map_err(|e| err_code(e.code))?;
+    Ok(BTreeMap::new())
+}
+
+fn run_sighash_v1(v: &Value) -> OpResult {
+    let raw = hex::decode(str_field(v, "tx_hex")).map_err(|_| "bad hex".to_string())?;
+    let (tx, _, _, _) = parse_tx(&raw).map_err(|e| err_code(e.code))?;
+    let chain_id = hex32(str_field(v, "chain_id")).map_err(|_| "bad chain_id".to_string())?;
+    let digest = sighash_v1_digest(
+        &tx,
+        u64_field(v, "input_index") as u32,
+        u64_field(v, "input_value"),
+        chain_id,
+    )
+    .map_err(|e| err_code(e.code))?;
+    Ok(BTreeMap::from([("digest", hex::encode(digest).into())]))
+}
+
+fn run_tx_weight_and_stats(v: &Value) -> OpResult {
+    let raw = hex::decode(str_field(v, "tx_hex")).map_err(|_| "bad hex".to_string())?;
+    let (tx, _, _, _) = parse_tx(&raw).map_err(|e| err_code(e.code))?;
+    let (weight, da_bytes, anchor_bytes) =
+        tx_weight_and_stats_public(&tx).map_err(|e| err_code(e.code))?;
+    Ok(BTreeMap::from([
+        ("weight", weight.into()),
+        ("da_bytes", da_bytes.into()),
+        ("anchor_bytes", anchor_bytes.into()),
+    ]))
+}
+
+fn run_timestamp_bounds(v: &Value) -> OpResult {
+    let mtp = u64_field(v, "mtp");
+    let timestamp = u64_field(v, "timestamp");
+    let drift = u64_field_or(v, "max_future_drift", MAX_FUTURE_DRIFT);
+    check_timestamp_bounds(timestamp, mtp, drift).map_err(|e| err_code(e.code))?;
+    Ok(BTreeMap::new())
+}
+
+fn run_block_basic_check(v: &Value) -> OpResult {
+    let block = hex::decode(str_field(v, "block_hex")).map_err(|_| "bad block".to_string())?;
+    let prev = opt_hex32(v, "expected_prev_hash").map_err(|_| "bad expected_prev_hash")?;
+    let target = opt_hex32(v, "expected_target").map_err(|_| "bad expected_target")?;
+    let prev_timestamps = u64_list(v, "prev_timestamps");
+    let prev_timestamps = (!prev_timestamps.is_empty()).then_some(prev_timestamps.as_slice());
+    validate_block_basic_with_context_at_height(
+        &block,
+        prev,
+        target,
+        u64_field_or(v, "height", 0),
+        prev_timestamps,
+    )
+    .map_err(|e| err_code(e.code))?;
+    let header = block
+        .get(..rubin_consensus::BLOCK_HEADER_BYTES)
+        .ok_or_else(|| "bad block".to_string())?;
+    let hash = block_hash(header).map_err(|e| err_code(e.code))?;
+    Ok(BTreeMap::from([("block_hash", hex::encode(hash).into())]))
+}
+
+fn run_connect_block_basic(v: &Value) -> OpResult {
+    let block = hex::decode(str_field(v, "block_hex")).map_err(|_| "bad block".to_string())?;
+    let prev = opt_hex32(v, "expected_prev_hash").map_err(|_| "bad expected_prev_hash")?;
+    let target = opt_hex32(v, "expected_target").map_err(|_| "bad expected_target")?;
+    let chain_id = opt_hex32(v, "chain_id")
+        .map_err(|_| "bad chain_id")?
+        .unwrap_or([0u8; 32]);
+    let prev_timestamps = u64_list(v, "prev_timestamps");
+    let prev_timestamps = (!prev_timestamps.is_empty()).then_some(prev_timestamps.as_slice());
+    let mut state = InMemoryChainState {
+        utxos: utxo_map(v)?,
+        already_generated: u128::from(u64_field(v, "already_generated")),
+    };
+    let summary = connect_block_basic_in_memory_at_height(
+        &block,
+        prev,
+        target,
+        u64_field(v, "height"),
+        prev_timestamps,
+        &mut state,
+        chain_id,
+    )
+    .map_err(|e| err_code(e.code))?;
+    Ok(BTreeMap::from([
+        ("sum_fees", summary.sum_fees.into()),
+        ("utxo_count", summary.utxo_count.into()),
+        (
+            "already_generated",
+            (summary.already_generated as u64).into(),
+        ),
+        (
+            "already_generated_n1",
+            (summary.already_generated_n1 as u64).into(),
+        ),
+        ("digest", hex::encode(summary.post_state_digest).into()),
+    ]))
+}
+
+fn values_match(expected: &Value, got: &Value) -> bool {
+    match (expected, got) {
+        (Value::String(s), Value::Number(n)) => s.parse::<u64>().ok() == n.as_u64(),
+        _ => expected == got,
+    }
+}
+
+fn check_vector(v: &Value) -> Outcome {
+    let op = str_field(v, "op");
+    if let Some(field) = RUNNER_EXPANDED_FIELDS.iter().find(|f| v.get(**f).is_some()) {
+        return Outcome::Skip(format!("{op} (runner-expanded {field})"));
+    }
+    let Some(result) = run_op(op, v) else {
+        return Outcome::Skip(op.to_string());
+    };
+    let expect_ok = v.get("expect_ok").and_then(Value::as_bool).unwrap_or(true);
+    match (expect_ok, result) {
+        (true, Err(err)) => Outcome::Fail(format!("expected ok, got {err}")),
+        (false, Ok(_)) => Outcome::Fail("expected error, got ok".to_string()),
+        (false, Err(err)) => match v.get("expect_err").and_then(Value::as_str) {
+            Some(expected) if expected != err => {
+                Outcome::Fail(format!("expected {expected}, got {err}"))
+            }
+            _ => Outcome::Pass,
+        },
+        (true, Ok(fields)) => {
+            for (name, got) in &fields {
+                let Some(expected) = v.get(format!("expect_{name}")) else {
+                    continue;
+                };
+                if !values_match(expected, got) {
+                    return Outcome::Fail(format!("{name}: expected {expected}, got {got}"));
+                }
+            }
+            if let (Some(unexpected), Some(got)) =
+                (v.get("expect_not_merkle_root"), fields.get("merkle_root"))
+            {
+                if unexpected == got {
+                    return Outcome::Fail("merkle_root equals expect_not_merkle_root".to_string());
+                }
+            }
+            Outcome::Pass
+        }
+    }
+}
+
+fn fixture_files(dir: &Path) -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = fs::read_dir(dir)
+        .unwrap_or_else(|e| panic!("read {}: {e}", dir.display()))
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|p| p.extension().is_some_and(|ext| ext == "json"))
+        .collect();
+    files.sort();
+    files
+}
+
+#[test]
+fn conformance_vectors_pass_in_process() {
+    let dir = fixtures_dir();
+    let vendored = std::env::var_os(FIXTURES_DIR_ENV).is_none();
+    let mut passed = 0usize;
+    let mut failures = Vec::new();
+    let mut skipped: BTreeMap<String, usize> = BTreeMap::new();
+
+    for path in fixture_files(&dir) {
+        let raw = fs::read_to_string(&path).expect("read fixture");
+        let fixture = serde_json::from_str::<Value>(&raw)
+            .unwrap_or_else(|e| panic!("parse fixture {}: {e}", path.display()));
+        let vectors = fixture
+            .get("vectors")
+            .and_then(Value::as_array)
+            .filter(|vectors| !vectors.is_empty())
+            .unwrap_or_else(|| panic!("fixture {} has no vectors", path.display()));
+        let gate = str_field(&fixture, "gate");
+        for v in vectors {
+            let outcome = std::panic::catch_unwind(|| check_vector(v))
+                .unwrap_or_else(|_| panic!("fixture {}: malformed vector", path.display()));
+            match outcome {
+                Outcome::Pass => passed += 1,
+                Outcome::Fail(msg) => {
+                    failures.push(format!("{gate}/{}: {msg}", str_field(v, "id")))
+                }
+                Outcome::Skip(op) => *skipped.entry(op).or_default() += 1,
+            }
+        }
+    }
+
+    eprintln!(
+        "conformance: dir={} passed={passed} failed={} skipped={}",
+        dir.display(),
+        failures.len(),
+        skipped.values().sum::<usize>()
+    );
+    for (op, count) in &skipped {
+        eprintln!("conformance: skipped op {op} x{count}");
+    }
+    assert!(
+        failures.is_empty(),
+        "conformance failures:\n{}",
+        failures.join("\n")
+    );
+    assert!(
+        passed > 0,
+        "no conformance vectors executed in {}",
+        dir.display()
+    );
+    if vendored {
+        assert!(
+            skipped.is_empty(),
+            "vendored conformance vectors use unsupported ops: {skipped:?}"
+        );
+    }
+}
@@ -4,13 +4,12 @@
 //! Invariant: no panic on any block_bytes + chain state; deterministic results.
 
 use rubin_consensus::{
-    connect_block_basic_in_memory_at_height, InMemoryChainState, Outpoint, UtxoEntry,
+    connect_block_basic_in_memory_at_height, InMemoryChainState, Outpoint, OutpointMap, UtxoEntry,
 };
-use std::collections::HashMap;
 
 fn empty_state() -> InMemoryChainState {
     InMemoryChainState {
-        utxos: HashMap::new(),
+        utxos: OutpointMap::default(),
         already_generated: 0,
     }
 }
@@ -197,7 +196,7 @@ fn connect_with_empty_timestamps() {
 
 #[test]
 fn connect_with_existing_utxos() {
-    let mut utxos = HashMap::new();
+    let mut utxos = OutpointMap::default();
     utxos.insert(
         Outpoint {
             txid: [0x01; 32],
@@ -0,0 +1,148 @@
+use std::path::PathBuf;
+
+use rubin_consensus::constants::MAX_DA_CHUNK_COUNT;
+use rubin_consensus::{
+    classify_tx, classify_tx_with_max_chunk_count, parse_block_bytes, parse_tx, tx_da_bytes,
+    tx_weight_and_stats_public, DaTxKind, ErrorCode,
+};
+use serde_json::Value;
+
+// Inputs come from the shared conformance fixtures both clients execute:
+// CV-WEIGHT pins `da_bytes` per tx, CV-DA-INTEGRITY CV-DA-01 is a valid
+// block carrying one complete DA set.
+
+fn vectors(file: &str) -> Vec<Value> {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("../../../../conformance/fixtures");
+    path.push(file);
+    let raw = std::fs::read_to_string(&path).unwrap_or_else(|e| panic!("read {file}: {e}"));
+    let doc: Value = serde_json::from_str(&raw).unwrap_or_else(|e| panic!("parse {file}: {e}"));
+    doc["vectors"].as_array().expect("vectors").clone()
+}
+
+fn hex_field(v: &Value, key: &str) -> Vec<u8> {
+    hex::decode(v[key].as_str().expect("hex field")).expect("hex")
+}
+
+#[test]
+fn tx_da_bytes_matches_weight_vectors() {
+    let mut checked = 0;
+    for v in vectors("CV-WEIGHT.json") {
+        if v["op"] != "tx_weight_and_stats" || v["expect_ok"] != true || v.get("tx_hex").is_none() {
+            continue;
+        }
+        let id = v["id"].as_str().unwrap_or_default();
+        let (tx, _, _, _) = parse_tx(&hex_field(&v, "tx_hex")).expect(id);
+        let want = v["expect_da_bytes"].as_u64().expect("expect_da_bytes");
+        assert_eq!(tx_da_bytes(&tx), want, "{id}");
+        let (_, stats_da_bytes, _) = tx_weight_and_stats_public(&tx).expect(id);
+        assert_eq!(stats_da_bytes, want, "{id}");
+        assert_eq!(
+            classify_tx(&tx).expect(id).is_some(),
+            tx.tx_kind != 0x00,
+            "{id}"
+        );
+        checked += 1;
+    }
+    assert!(checked > 0, "no tx_weight_and_stats vectors");
+}
+
+#[test]
+fn classify_tx_recovers_complete_da_set_from_cv_da_01() {
+    let v = vectors("CV-DA-INTEGRITY.json")
+        .into_iter()
+        .find(|v| v["id"] == "CV-DA-01")
+        .expect("CV-DA-01");
+    let block = parse_block_bytes(&hex_field(&v, "block_hex")).expect("parse block");
+
+    assert_eq!(classify_tx(&block.txs[0]), Ok(None), "coinbase");
+    let kinds: Vec<DaTxKind> = block.txs[1..]
+        .iter()
+        .filter_map(|tx| classify_tx(tx).expect("classify"))
+        .collect();
+    let commits: Vec<&DaTxKind> = kinds
+        .iter()
+        .filter(|k| matches!(k, DaTxKind::Commit { .. }))
+        .collect();
+    assert_eq!(commits.len(), 1);
+    let DaTxKind::Commit {
+        da_id, chunk_count, ..
+    } = *commits[0]
+    else {
+        unreachable!()
+    };
+
+    let mut indices: Vec<u16> = kinds
+        .iter()
+        .filter_map(|k| match k {
+            DaTxKind::Chunk {
+                da_id: chunk_da_id,
+                index,
+                payload_len,
+                ..
+            } => {
+                assert_eq!(*chunk_da_id, da_id);
+                assert!(*payload_len > 0);
+                Some(*index)
+            }
+            DaTxKind::Commit { .. } => None,
+        })
+        .collect();
+    indices.sort_unstable();
+    assert_eq!(indices, (0..chunk_count).collect::<Vec<_>>());
+    assert!(kinds.iter().all(|k| k.da_id() == da_id));
+
+    let total: u64 = block.txs.iter().map(tx_da_bytes).sum();
+    let payloads: u64 = kinds
+        .iter()
+        .map(|k| match k {
+            DaTxKind::Commit { payload_len, .. } | DaTxKind::Chunk { payload_len, .. } => {
+                *payload_len
+            }
+        })
+        .sum();
+    assert_eq!(total, payloads);
+}
+
+#[test]
+fn classify_tx_enforces_commit_chunk_count_cap() {
+    let v = vectors("CV-DA-INTEGRITY.json")
+        .into_iter()
+        .find(|v| v["id"] == "CV-DA-01")
+        .expect("CV-DA-01");
+    let block = parse_block_bytes(&hex_field(&v, "block_hex")).expect("parse block");
+    let mut commit = block
+        .txs
+        .iter()
+        .find(|tx| tx.tx_kind == 0x01)
+        .expect("commit tx")
+        .clone();
+    let max = MAX_DA_CHUNK_COUNT as u16;
+    let with_count = |commit: &mut rubin_consensus::Tx, count: u16| {
+        commit
+            .da_commit_core
+            .as_mut()
+            .expect("commit core")
+            .chunk_count = count;
+        classify_tx(commit)
+    };
+
+    assert!(matches!(
+        with_count(&mut commit, max),
+        Ok(Some(DaTxKind::Commit { chunk_count, .. })) if chunk_count == max
+    ));
+    for count in [max + 1, 0, u16::MAX] {
+        let err = with_count(&mut commit, count).expect_err("out-of-range chunk_count");
+        assert_eq!(err.code, ErrorCode::TxErrParse, "chunk_count {count}");
+    }
+
+    // A tighter policy cap rejects what consensus alone would admit.
+    commit
+        .da_commit_core
+        .as_mut()
+        .expect("commit core")
+        .chunk_count = 2;
+    assert!(classify_tx_with_max_chunk_count(&commit, 2).is_ok());
+    let err = classify_tx_with_max_chunk_count(&commit, 1).expect_err("policy cap");
+    assert_eq!(err.code, ErrorCode::TxErrParse);
+}
@@ -0,0 +1,138 @@
+//! Smoke mode for the decode fuzz targets: replays every input checked in
+//! under `clients/rust/fuzz/regressions/<target>/` through the same invariants
+//! the cargo-fuzz targets assert (`fuzz/invariants.rs`), so a fuzz finding
+//! copied there becomes a permanent `cargo test` case.
+//!
+//! On top of those invariants this binary bounds what the bare decoder
+//! allocates by the input length: a decoder must never reserve memory for
+//! elements an input claims (a count, a length prefix) but does not carry.
+//! The counting allocator follows `block_txcount_no_overalloc.rs`: it sums
+//! bytes requested while armed and never subtracts frees, and this binary
+//! runs a single test so nothing else allocates inside the armed window.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::fs;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+#[path = "../../../fuzz/invariants.rs"]
+mod invariants;
+
+use invariants::TARGETS;
+
+struct CountingAllocator;
+
+static ARMED: AtomicBool = AtomicBool::new(false);
+static ALLOCATED_BYTES: AtomicUsize = AtomicUsize::new(0);
+
+fn add_saturating(counter: &AtomicUsize, n: usize) {
+    let mut cur = counter.load(Ordering::Relaxed);
+    loop {
+        let next = cur.saturating_add(n);
+        match counter.compare_exchange_weak(cur, next, Ordering::Relaxed, Ordering::Relaxed) {
+            Ok(_) => return,
+            Err(actual) => cur = actual,
+        }
+    }
+}
+
+// SAFETY: every request is forwarded verbatim to `System`; the only added
+// work is non-allocating atomic bookkeeping.
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        if ARMED.load(Ordering::Relaxed) {
+            add_saturating(&ALLOCATED_BYTES, layout.size());
+        }
+        // SAFETY: `layout` is forwarded unchanged to the System allocator.
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        // SAFETY: `ptr`/`layout` come from a prior `System` allocation.
+        System.dealloc(ptr, layout);
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        if ARMED.load(Ordering::Relaxed) && new_size > layout.size() {
+            add_saturating(&ALLOCATED_BYTES, new_size - layout.size());
+        }
+        // SAFETY: `ptr`/`layout`/`new_size` are forwarded unchanged to System.
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static ALLOC: CountingAllocator = CountingAllocator;
+
+/// A decoded element costs at most a few machine words per wire byte (a
+/// 41-byte tx input decodes to a 64-byte `TxInput`); the slack covers the
+/// error value and small fixed buffers.
+const ALLOC_PER_INPUT_BYTE: usize = 128;
+const ALLOC_SLACK: usize = 4 * 1024;
+
+/// The corpus the request asked for; replay fails if it silently shrinks.
+const MIN_REGRESSION_INPUTS: usize = 200;
+
+fn regressions_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("../../fuzz/regressions")
+}
+
+fn measure(decode: fn(&[u8]) -> bool, data: &[u8]) -> usize {
+    // Unarmed first call: lazily initialised registries are not per-input cost.
+    decode(data);
+    ALLOCATED_BYTES.store(0, Ordering::Relaxed);
+    ARMED.store(true, Ordering::Relaxed);
+    decode(data);
+    ARMED.store(false, Ordering::Relaxed);
+    ALLOCATED_BYTES.load(Ordering::Relaxed)
+}
+
+fn sorted_inputs(dir: &Path) -> Vec<PathBuf> {
+    let mut inputs: Vec<PathBuf> = fs::read_dir(dir)
+        .unwrap_or_else(|err| panic!("read {}: {err}", dir.display()))
+        .map(|entry| entry.expect("corpus entry").path())
+        .collect();
+    inputs.sort();
+    inputs
+}
+
+#[test]
+fn replay_decode_regression_corpus() {
+    let mut replayed = 0usize;
+    let mut failures = Vec::new();
+    for target in TARGETS {
+        let inputs = sorted_inputs(&regressions_dir().join(target.name));
+        assert!(
+            !inputs.is_empty(),
+            "no regression inputs for {}",
+            target.name
+        );
+        for path in inputs {
+            let data = fs::read(&path).expect("read regression input");
+            let name = format!(
+                "{}/{}",
+                target.name,
+                path.file_name().unwrap().to_string_lossy()
+            );
+            replayed += 1;
+
+            let allocated = measure(target.decode, &data);
+            let bound = ALLOC_PER_INPUT_BYTE * data.len() + ALLOC_SLACK;
+            if allocated > bound {
+                failures.push(format!(
+                    "{name}: decoder allocated {allocated} bytes for a {}-byte input (bound {bound})",
+                    data.len()
+                ));
+            }
+            if panic::catch_unwind(AssertUnwindSafe(|| (target.check)(&data))).is_err() {
+                failures.push(format!("{name}: invariant violated"));
+            }
+        }
+    }
+    assert!(
+        replayed >= MIN_REGRESSION_INPUTS,
+        "replayed {replayed} regression inputs, expected at least {MIN_REGRESSION_INPUTS}"
+    );
+    assert!(failures.is_empty(), "{}", failures.join("\n"));
+}
@@ -1,4 +1,4 @@
-use rubin_consensus::{ErrorCode, TxError};
+use rubin_consensus::{codes, ErrorCode, TxError};
 
 // --- ErrorCode derive coverage ---
 
@@ -307,3 +307,42 @@ fn tx_error_display() {
     let e2 = TxError::new(ErrorCode::TxErrParse, "bad");
     assert_eq!(e2.to_string(), "TX_ERR_PARSE: bad");
 }
+
+// --- single source of truth ---
+
+#[test]
+fn every_code_appears_once_and_round_trips() {
+    let names: std::collections::BTreeSet<&str> =
+        ErrorCode::ALL.iter().map(|code| code.as_str()).collect();
+    assert_eq!(names.len(), ErrorCode::ALL.len(), "duplicate code string");
+    for &code in ErrorCode::ALL {
+        assert_eq!(code.as_str().parse::<ErrorCode>(), Ok(code));
+    }
+    assert_eq!(codes::TX_ERR_PARSE, ErrorCode::TxErrParse.as_str());
+    assert_eq!(
+        codes::BLOCK_ERR_DA_BATCH_EXCEEDED,
+        ErrorCode::BlockErrDaBatchExceeded.as_str()
+    );
+    assert_eq!(
+        "TX_ERR_NOT_A_CODE".parse::<ErrorCode>(),
+        Err("unknown error code: TX_ERR_NOT_A_CODE".to_string())
+    );
+}
+
+#[test]
+fn error_codes_match_go_constants() {
+    // Simplicity codes live in the separate `simplicity::ErrorCode`.
+    let path =
+        std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("../../../go/consensus/errors.go");
+    let src = std::fs::read_to_string(&path).expect("read Go errors.go");
+    let go: std::collections::BTreeSet<&str> = src
+        .split('"')
+        .skip(1)
+        .step_by(2)
+        .filter(|s| s.starts_with("TX_ERR_") || s.starts_with("BLOCK_ERR_"))
+        .filter(|s| !s.starts_with("TX_ERR_SIMPLICITY_"))
+        .collect();
+    let rust: std::collections::BTreeSet<&str> =
+        ErrorCode::ALL.iter().map(|code| code.as_str()).collect();
+    assert_eq!(rust, go);
+}
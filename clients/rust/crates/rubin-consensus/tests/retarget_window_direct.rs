@@ -0,0 +1,147 @@
+use std::path::PathBuf;
+
+use rubin_consensus::constants::WINDOW_SIZE;
+use rubin_consensus::{block_expected_target, retarget_v1_clamped, BlockHeader, ErrorCode};
+use serde_json::Value;
+
+// Windows below mirror conformance/fixtures/CV-POW.json POW-11 (height
+// WINDOW_SIZE) and POW-12 (height 2*WINDOW_SIZE); the expected targets are
+// read from that shared fixture, which both clients execute.
+
+fn pow_vector(id: &str) -> Value {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("../../../../conformance/fixtures/CV-POW.json");
+    let raw = std::fs::read_to_string(&path).expect("read CV-POW.json");
+    let doc: Value = serde_json::from_str(&raw).expect("parse CV-POW.json");
+    doc["vectors"]
+        .as_array()
+        .expect("vectors")
+        .iter()
+        .find(|v| v["id"] == id)
+        .cloned()
+        .unwrap_or_else(|| panic!("missing vector {id}"))
+}
+
+fn hex32(v: &Value) -> [u8; 32] {
+    hex::decode(v.as_str().expect("hex string"))
+        .expect("hex")
+        .try_into()
+        .expect("32 bytes")
+}
+
+/// Expands a `step_with_last_jump` window pattern like the shared runner.
+fn pattern_timestamps(v: &Value) -> Vec<u64> {
+    let p = &v["window_pattern"];
+    let size = p["window_size"].as_u64().unwrap() as usize;
+    let step = p["step"].as_u64().unwrap();
+    let last_jump = p["last_jump"].as_u64().unwrap();
+    let mut ts = vec![p["start"].as_u64().unwrap()];
+    for _ in 1..size {
+        ts.push(ts.last().unwrap() + step);
+    }
+    if last_jump > 0 {
+        ts[size - 1] = ts[size - 2] + last_jump;
+    }
+    ts
+}
+
+fn header(timestamp: u64, target: [u8; 32]) -> BlockHeader {
+    BlockHeader {
+        version: 1,
+        prev_block_hash: [0u8; 32],
+        merkle_root: [0u8; 32],
+        timestamp,
+        target,
+        nonce: 0,
+    }
+}
+
+/// Canonical headers for heights `0..2*WINDOW_SIZE`: the first window uses
+/// POW-11's timestamps and target_old, the second POW-12's.
+fn two_window_chain() -> (Vec<BlockHeader>, [u8; 32], [u8; 32]) {
+    let v11 = pow_vector("POW-11");
+    let v12 = pow_vector("POW-12");
+    let target_1 = hex32(&v11["target_old"]);
+    let target_2 = hex32(&v12["target_old"]);
+    assert_eq!(target_2, hex32(&v11["expect_target_new"]));
+    let mut chain: Vec<BlockHeader> = pattern_timestamps(&v11)
+        .into_iter()
+        .map(|ts| header(ts, target_1))
+        .collect();
+    chain.extend(
+        pattern_timestamps(&v12)
+            .into_iter()
+            .map(|ts| header(ts, target_2)),
+    );
+    (
+        chain,
+        hex32(&v11["expect_target_new"]),
+        hex32(&v12["expect_target_new"]),
+    )
+}
+
+// =============================================================
+// Height WINDOW_SIZE
+// =============================================================
+
+#[test]
+fn first_boundary_exact_window_matches_pow_11() {
+    let (chain, want, _) = two_window_chain();
+    let w = WINDOW_SIZE as usize;
+    let got = block_expected_target(&chain[..w], WINDOW_SIZE).expect("target");
+    assert_eq!(got, want);
+}
+
+#[test]
+fn first_boundary_rejects_more_ancestors_than_height() {
+    let (chain, _, _) = two_window_chain();
+    let w = WINDOW_SIZE as usize;
+    let err = block_expected_target(&chain[..w + 1], WINDOW_SIZE).unwrap_err();
+    assert_eq!(err.code, ErrorCode::BlockErrParse);
+}
+
+#[test]
+fn first_boundary_rejects_short_window() {
+    let (chain, _, _) = two_window_chain();
+    let w = WINDOW_SIZE as usize;
+    let err = block_expected_target(&chain[1..w], WINDOW_SIZE).unwrap_err();
+    assert_eq!(err.code, ErrorCode::BlockErrParse);
+}
+
+// =============================================================
+// Height 2*WINDOW_SIZE — exactly sized and over-sized ancestors
+// =============================================================
+
+#[test]
+fn second_boundary_exact_and_oversized_windows_match_pow_12() {
+    let (chain, _, want) = two_window_chain();
+    let w = WINDOW_SIZE as usize;
+    let height = 2 * WINDOW_SIZE;
+    for start in [w, w - 1, w / 2, 1, 0] {
+        let got = block_expected_target(&chain[start..], height)
+            .unwrap_or_else(|e| panic!("start={start}: {e}"));
+        assert_eq!(got, want, "ancestors from height {start}");
+    }
+}
+
+#[test]
+fn second_boundary_window_is_not_the_oldest_ancestors() {
+    // Retargeting over heights 0..WINDOW_SIZE-1 (the first w entries of an
+    // over-sized list) would reproduce POW-11's ratio, not POW-12's.
+    let (chain, _, want) = two_window_chain();
+    let w = WINDOW_SIZE as usize;
+    let parent_target = chain.last().unwrap().target;
+    let wrong_ts: Vec<u64> = chain[..w].iter().map(|h| h.timestamp).collect();
+    let wrong = retarget_v1_clamped(parent_target, &wrong_ts).expect("retarget");
+    assert_ne!(wrong, want);
+}
+
+#[test]
+fn off_boundary_returns_parent_target() {
+    let (chain, _, _) = two_window_chain();
+    let w = WINDOW_SIZE as usize;
+    let got = block_expected_target(&chain[..w + 5], WINDOW_SIZE + 5).expect("target");
+    assert_eq!(got, chain[w + 4].target);
+    let got = block_expected_target(&chain[w..w + 5], WINDOW_SIZE + 5).expect("suffix");
+    assert_eq!(got, chain[w + 4].target);
+}
@@ -0,0 +1,76 @@
+use rubin_consensus::constants::MAX_FUTURE_DRIFT;
+use rubin_consensus::{check_block_timestamp, check_timestamp_bounds, median_time_past, ErrorCode};
+
+// =============================================================
+// median_time_past — window selection
+// =============================================================
+
+#[test]
+fn mtp_none_for_genesis_or_missing_context() {
+    assert_eq!(median_time_past(0, Some(&[5, 6, 7])).unwrap(), None);
+    assert_eq!(median_time_past(1, None).unwrap(), None);
+    assert_eq!(median_time_past(1, Some(&[])).unwrap(), None);
+}
+
+#[test]
+fn mtp_short_window_uses_block_height_entries() {
+    // Height 3 looks at the newest 3 timestamps only.
+    assert_eq!(
+        median_time_past(3, Some(&[30, 10, 20, 999])).unwrap(),
+        Some(20)
+    );
+    // Even-sized windows take the lower median.
+    assert_eq!(median_time_past(2, Some(&[40, 10])).unwrap(), Some(10));
+}
+
+#[test]
+fn mtp_full_window_caps_at_eleven() {
+    let prev: Vec<u64> = (0..20).rev().collect();
+    // Newest-first 19..=9 -> median 14.
+    assert_eq!(median_time_past(100, Some(&prev)).unwrap(), Some(14));
+}
+
+#[test]
+fn mtp_rejects_insufficient_context() {
+    let err = median_time_past(11, Some(&[1, 2, 3])).unwrap_err();
+    assert_eq!(err.code, ErrorCode::BlockErrParse);
+}
+
+// =============================================================
+// check_timestamp_bounds / check_block_timestamp — boundaries
+// =============================================================
+
+#[test]
+fn timestamp_equal_to_mtp_is_old() {
+    let err = check_block_timestamp(1_000, 1_000, None).unwrap_err();
+    assert_eq!(err.code, ErrorCode::BlockErrTimestampOld);
+    check_block_timestamp(1_001, 1_000, None).expect("mtp + 1");
+}
+
+#[test]
+fn timestamp_future_drift_boundary() {
+    check_block_timestamp(1_000 + MAX_FUTURE_DRIFT, 1_000, None).expect("at bound");
+    let err = check_block_timestamp(1_000 + MAX_FUTURE_DRIFT + 1, 1_000, None).unwrap_err();
+    assert_eq!(err.code, ErrorCode::BlockErrTimestampFuture);
+}
+
+#[test]
+fn timestamp_bounds_honours_explicit_drift() {
+    check_timestamp_bounds(15, 10, 5).expect("at bound");
+    let err = check_timestamp_bounds(16, 10, 5).unwrap_err();
+    assert_eq!(err.code, ErrorCode::BlockErrTimestampFuture);
+    let err = check_timestamp_bounds(10, 10, 5).unwrap_err();
+    assert_eq!(err.code, ErrorCode::BlockErrTimestampOld);
+    check_timestamp_bounds(u64::MAX, u64::MAX - 1, u64::MAX).expect("saturating bound");
+}
+
+#[test]
+fn local_clock_bound_is_optional() {
+    let mtp = 1_000;
+    let now = 900;
+    check_block_timestamp(now + MAX_FUTURE_DRIFT, mtp, Some(now)).expect("at local bound");
+    let err = check_block_timestamp(now + MAX_FUTURE_DRIFT + 1, mtp, Some(now)).unwrap_err();
+    assert_eq!(err.code, ErrorCode::BlockErrTimestampFuture);
+    // Without a local clock only the MTP window applies.
+    check_block_timestamp(now + MAX_FUTURE_DRIFT + 1, mtp, None).expect("consensus only");
+}
@@ -6,9 +6,8 @@
 
 use rubin_consensus::{
     apply_non_coinbase_tx_basic, apply_non_coinbase_tx_basic_with_mtp,
-    p2pk_covenant_data_for_pubkey, parse_tx, Outpoint, UtxoEntry,
+    p2pk_covenant_data_for_pubkey, parse_tx, Outpoint, OutpointMap, UtxoEntry,
 };
-use std::collections::HashMap;
 
 const COV_TYPE_P2PK: u16 = 0x0000;
 
@@ -22,7 +21,7 @@ fn dummy_p2pk_cov_data() -> Vec<u8> {
 
 fn make_utxo_set_for_tx_inputs(
     tx_bytes: &[u8],
-) -> Option<(rubin_consensus::Tx, [u8; 32], HashMap<Outpoint, UtxoEntry>)> {
+) -> Option<(rubin_consensus::Tx, [u8; 32], OutpointMap<UtxoEntry>)> {
     let (tx, txid, _wtxid, _consumed) = match parse_tx(tx_bytes) {
         Ok(v) => v,
         Err(_) => return None,
@@ -31,7 +30,7 @@ fn make_utxo_set_for_tx_inputs(
         return None;
     }
 
-    let mut utxos = HashMap::new();
+    let mut utxos = OutpointMap::default();
     for inp in &tx.inputs {
         utxos.insert(
             Outpoint {
@@ -86,7 +85,7 @@ fn utxo_apply_missing_input_in_utxo_set() {
     // Construct a minimal tx that parses, but provide empty UTXO set
     let tx_bytes = minimal_tx_bytes();
     let (tx, txid, _wtxid, _consumed) = parse_tx(&tx_bytes).expect("minimal_tx_bytes must parse");
-    let empty_utxos = HashMap::new();
+    let empty_utxos = OutpointMap::default();
     let chain_id = [0u8; 32];
     // Should error (missing UTXO), not panic
     let _ = apply_non_coinbase_tx_basic(&tx, txid, &empty_utxos, 1, 1000, chain_id);
@@ -3,8 +3,7 @@
 //!
 //! Invariant: no panic; deterministic; immutability; shard bounds.
 
-use rubin_consensus::{utxo_snapshot_shard, Outpoint, UtxoEntry, UtxoSnapshot};
-use std::collections::HashMap;
+use rubin_consensus::{utxo_snapshot_shard, Outpoint, OutpointMap, UtxoEntry, UtxoSnapshot};
 
 fn test_outpoint(txid_byte: u8, vout: u32) -> Outpoint {
     let mut txid = [0u8; 32];
@@ -36,14 +35,14 @@ fn snapshot_none_is_empty() {
 
 #[test]
 fn snapshot_empty_map() {
-    let utxos = HashMap::new();
+    let utxos = OutpointMap::default();
     let snap = UtxoSnapshot::new(Some(&utxos));
     assert_eq!(snap.count(), 0);
 }
 
 #[test]
 fn snapshot_single_entry() {
-    let mut utxos = HashMap::new();
+    let mut utxos = OutpointMap::default();
     utxos.insert(test_outpoint(0x01, 0), test_entry(1000));
     let snap = UtxoSnapshot::new(Some(&utxos));
     assert_eq!(snap.count(), 1);
@@ -54,7 +53,7 @@ fn snapshot_single_entry() {
 
 #[test]
 fn snapshot_multiple_entries() {
-    let mut utxos = HashMap::new();
+    let mut utxos = OutpointMap::default();
     for i in 0..10u8 {
         utxos.insert(test_outpoint(i, i as u32), test_entry(i as u64 * 100));
     }
@@ -72,7 +71,7 @@ fn snapshot_multiple_entries() {
 
 #[test]
 fn snapshot_immutable_after_creation() {
-    let mut utxos = HashMap::new();
+    let mut utxos = OutpointMap::default();
     utxos.insert(test_outpoint(0xAA, 0), test_entry(1000));
     utxos.insert(test_outpoint(0xBB, 0), test_entry(2000));
     let snap = UtxoSnapshot::new(Some(&utxos));
@@ -93,7 +92,7 @@ fn snapshot_immutable_after_creation() {
 
 #[test]
 fn snapshot_resolve_inputs_all_found() {
-    let mut utxos = HashMap::new();
+    let mut utxos = OutpointMap::default();
     utxos.insert(test_outpoint(0xAA, 0), test_entry(1000));
     utxos.insert(test_outpoint(0xBB, 1), test_entry(2000));
     let snap = UtxoSnapshot::new(Some(&utxos));
@@ -132,7 +131,7 @@ fn snapshot_resolve_inputs_all_found() {
 
 #[test]
 fn snapshot_resolve_inputs_missing() {
-    let utxos = HashMap::new();
+    let utxos = OutpointMap::default();
     let snap = UtxoSnapshot::new(Some(&utxos));
 
     let tx = rubin_consensus::Tx {
@@ -159,7 +158,7 @@ fn snapshot_resolve_inputs_missing() {
 
 #[test]
 fn snapshot_resolve_inputs_empty() {
-    let snap = UtxoSnapshot::new(Some(&HashMap::new()));
+    let snap = UtxoSnapshot::new(Some(&OutpointMap::default()));
     let tx = rubin_consensus::Tx {
         version: 1,
         tx_kind: 0,
@@ -182,7 +181,7 @@ fn snapshot_resolve_inputs_empty() {
 
 #[test]
 fn snapshot_for_each_visits_all() {
-    let mut utxos = HashMap::new();
+    let mut utxos = OutpointMap::default();
     for i in 0..5u8 {
         utxos.insert(test_outpoint(i, 0), test_entry(i as u64 * 100));
     }
@@ -267,7 +266,7 @@ fn shard_distribution_nontrivial() {
 
 #[test]
 fn snapshot_repeated_reads_stable() {
-    let mut utxos = HashMap::new();
+    let mut utxos = OutpointMap::default();
     for i in 0..50u8 {
         utxos.insert(test_outpoint(i, 0), test_entry(i as u64 * 10));
     }
@@ -1,7 +1,10 @@
+use std::path::PathBuf;
+
 use rubin_consensus::{
-    output_descriptor_bytes, parse_multisig_covenant_data, parse_vault_covenant_data,
-    witness_slots, ErrorCode,
+    output_descriptor_bytes, parse_multisig_covenant_data, parse_tx, parse_vault_covenant_data,
+    validate_tx_covenants_genesis, witness_slots, ErrorCode,
 };
+use serde_json::Value;
 
 // --- helpers ---
 
@@ -550,3 +553,78 @@ fn output_descriptor_bytes_large_data_3byte_compact_size() {
     assert_eq!(desc[3..5], 300u16.to_le_bytes()); // 300 as u16 LE
     assert_eq!(&desc[5..], &cov_data[..]);
 }
+
+// --- shared conformance coverage ---
+
+const VAULT_ERROR_CODES: [ErrorCode; 9] = [
+    ErrorCode::TxErrVaultMalformed,
+    ErrorCode::TxErrVaultParamsInvalid,
+    ErrorCode::TxErrVaultKeysNotCanonical,
+    ErrorCode::TxErrVaultWhitelistNotCanonical,
+    ErrorCode::TxErrVaultOwnerDestinationForbidden,
+    ErrorCode::TxErrVaultOwnerAuthRequired,
+    ErrorCode::TxErrVaultFeeSponsorForbidden,
+    ErrorCode::TxErrVaultMultiInputForbidden,
+    ErrorCode::TxErrVaultOutputNotWhitelisted,
+];
+
+fn fixtures_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../../../../conformance/fixtures")
+}
+
+fn fixture_vectors(file: &str) -> Vec<Value> {
+    let path = fixtures_dir().join(file);
+    let raw = std::fs::read_to_string(&path).unwrap_or_else(|e| panic!("read {file}: {e}"));
+    let doc: Value = serde_json::from_str(&raw).unwrap_or_else(|e| panic!("parse {file}: {e}"));
+    doc["vectors"].as_array().expect("vectors").clone()
+}
+
+#[test]
+fn every_vault_error_code_is_pinned_by_a_consensus_vector() {
+    // `vault_policy_rules` only models the rule order; a code counts as
+    // pinned when a vector drives it through a real consensus op, which both
+    // clients execute.
+    let mut pinned = Vec::new();
+    for entry in std::fs::read_dir(fixtures_dir()).expect("read fixtures") {
+        let name = entry.expect("fixture entry").file_name();
+        let name = name.to_string_lossy();
+        if !(name.starts_with("CV-") && name.ends_with(".json")) {
+            continue;
+        }
+        for v in fixture_vectors(&name) {
+            if v["op"] == "vault_policy_rules" {
+                continue;
+            }
+            if let Some(code) = v["expect_err"].as_str() {
+                pinned.push(code.to_string());
+            }
+        }
+    }
+    for code in VAULT_ERROR_CODES {
+        assert!(
+            pinned.iter().any(|p| p == code.as_str()),
+            "{} has no consensus conformance vector",
+            code.as_str()
+        );
+    }
+}
+
+#[test]
+fn covenant_genesis_vault_vectors_match() {
+    let mut checked = 0;
+    for v in fixture_vectors("CV-COVENANT-GENESIS.json") {
+        let Some(want) = v["expect_err"].as_str() else {
+            continue;
+        };
+        if !want.starts_with("TX_ERR_VAULT_") {
+            continue;
+        }
+        let id = v["id"].as_str().unwrap_or_default();
+        let bytes = hex::decode(v["tx_hex"].as_str().expect("tx_hex")).expect(id);
+        let (tx, _, _, _) = parse_tx(&bytes).expect(id);
+        let err = validate_tx_covenants_genesis(&tx, 0, None).expect_err(id);
+        assert_eq!(err.code.as_str(), want, "{id}");
+        checked += 1;
+    }
+    assert!(checked >= 5, "only {checked} vault genesis vectors");
+}
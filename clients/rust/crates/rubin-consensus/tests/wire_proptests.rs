@@ -0,0 +1,531 @@
+//! Property-based round-trip and mutation tests for the tx / block wire encoding.
+//!
+//! Gated behind the `proptests` feature so default `cargo test` time stays
+//! bounded:
+//!
+//! `cargo test -p rubin-consensus --features proptests --test wire_proptests`
+//!
+//! Strategies generate structurally valid `Tx` values (all tx kinds, all
+//! covenant types, every witness suite shape) and blocks built from them.
+//! Properties:
+//! - serialize → parse → serialize is a fixed point;
+//! - `txid` ignores witness mutations, `wtxid` does not;
+//! - a non-minimal CompactSize at any varint position is rejected;
+//! - truncation at every byte offset is rejected without panic;
+//! - the bounded CompactSize decode agrees with decode-then-check and turns
+//!   away oversized counts before sizing anything from them;
+//! - the DA commit chunk-count cap admits exactly `1..=MAX_DA_CHUNK_COUNT`.
+
+#![cfg(feature = "proptests")]
+
+use proptest::collection::vec;
+use proptest::prelude::*;
+use rubin_consensus::block::{BlockHeader, BLOCK_HEADER_BYTES};
+use rubin_consensus::constants::{
+    COV_TYPE_ANCHOR, COV_TYPE_CORE_EXT, COV_TYPE_CORE_SIMPLICITY, COV_TYPE_CORE_STEALTH,
+    COV_TYPE_DA_COMMIT, COV_TYPE_HTLC, COV_TYPE_MULTISIG, COV_TYPE_P2PK, COV_TYPE_RESERVED_FUTURE,
+    COV_TYPE_VAULT, MAX_DA_CHUNK_COUNT, MAX_HTLC_PREIMAGE_BYTES, MAX_TX_INPUTS,
+    MIN_HTLC_PREIMAGE_BYTES, ML_DSA_87_PUBKEY_BYTES, ML_DSA_87_SIG_BYTES, SUITE_ID_ML_DSA_87,
+    SUITE_ID_SENTINEL, SUITE_ID_SIMPLICITY_ENVELOPE, TX_WIRE_VERSION,
+};
+use rubin_consensus::{
+    check_da_chunk_count, compact_size_decode_bounded, encode_compact_size, marshal_tx,
+    parse_block_bytes, parse_tx, read_compact_size_bytes, DaChunkCore, DaCommitCore, ErrorCode, Tx,
+    TxInput, TxOutput, WitnessItem,
+};
+
+const COV_TYPES: &[u16] = &[
+    COV_TYPE_P2PK,
+    COV_TYPE_ANCHOR,
+    COV_TYPE_RESERVED_FUTURE,
+    COV_TYPE_HTLC,
+    COV_TYPE_VAULT,
+    COV_TYPE_CORE_EXT,
+    COV_TYPE_DA_COMMIT,
+    COV_TYPE_MULTISIG,
+    COV_TYPE_CORE_STEALTH,
+    COV_TYPE_CORE_SIMPLICITY,
+];
+
+fn bytes(max: usize) -> impl Strategy<Value = Vec<u8>> {
+    vec(any::<u8>(), 0..=max)
+}
+
+fn covenant_type() -> impl Strategy<Value = u16> {
+    prop_oneof![4 => proptest::sample::select(COV_TYPES), 1 => any::<u16>()]
+}
+
+fn tx_input() -> impl Strategy<Value = TxInput> {
+    (any::<[u8; 32]>(), any::<u32>(), bytes(32), any::<u32>()).prop_map(
+        |(prev_txid, prev_vout, script_sig, sequence)| TxInput {
+            prev_txid,
+            prev_vout,
+            script_sig,
+            sequence,
+        },
+    )
+}
+
+fn tx_output() -> impl Strategy<Value = TxOutput> {
+    (any::<u64>(), covenant_type(), bytes(80)).prop_map(|(value, covenant_type, covenant_data)| {
+        TxOutput {
+            value,
+            covenant_type,
+            covenant_data,
+        }
+    })
+}
+
+fn sentinel_witness() -> impl Strategy<Value = WitnessItem> {
+    let preimage = MIN_HTLC_PREIMAGE_BYTES as usize..=MAX_HTLC_PREIMAGE_BYTES as usize;
+    prop_oneof![
+        Just((Vec::new(), Vec::new())),
+        any::<[u8; 32]>().prop_map(|pk| (pk.to_vec(), vec![0x01])),
+        (any::<[u8; 32]>(), vec(any::<u8>(), preimage)).prop_map(|(pk, pre)| {
+            let mut sig = vec![0x00];
+            sig.extend_from_slice(&(pre.len() as u16).to_le_bytes());
+            sig.extend_from_slice(&pre);
+            (pk.to_vec(), sig)
+        }),
+    ]
+    .prop_map(|(pubkey, signature)| WitnessItem {
+        suite_id: SUITE_ID_SENTINEL,
+        pubkey,
+        signature,
+    })
+}
+
+fn mldsa_witness() -> impl Strategy<Value = WitnessItem> {
+    (any::<u8>(), any::<u8>()).prop_map(|(fill, sighash)| {
+        let mut signature = vec![fill ^ 0x5a; ML_DSA_87_SIG_BYTES as usize];
+        signature.push(sighash);
+        WitnessItem {
+            suite_id: SUITE_ID_ML_DSA_87,
+            pubkey: vec![fill; ML_DSA_87_PUBKEY_BYTES as usize],
+            signature,
+        }
+    })
+}
+
+fn simplicity_witness() -> impl Strategy<Value = WitnessItem> {
+    (bytes(64), bytes(64), any::<u8>()).prop_map(|(program, witness, sighash)| {
+        let mut signature = vec![0x01];
+        encode_compact_size(program.len() as u64, &mut signature);
+        signature.extend_from_slice(&program);
+        encode_compact_size(witness.len() as u64, &mut signature);
+        signature.extend_from_slice(&witness);
+        signature.push(sighash);
+        WitnessItem {
+            suite_id: SUITE_ID_SIMPLICITY_ENVELOPE,
+            pubkey: Vec::new(),
+            signature,
+        }
+    })
+}
+
+fn unknown_suite_witness() -> impl Strategy<Value = WitnessItem> {
+    let suite = any::<u8>().prop_filter("known suite", |s| {
+        ![
+            SUITE_ID_SENTINEL,
+            SUITE_ID_ML_DSA_87,
+            SUITE_ID_SIMPLICITY_ENVELOPE,
+        ]
+        .contains(s)
+    });
+    (suite, bytes(64), vec(any::<u8>(), 1..=64)).prop_map(|(suite_id, pubkey, signature)| {
+        WitnessItem {
+            suite_id,
+            pubkey,
+            signature,
+        }
+    })
+}
+
+/// ML-DSA-87 items are ~7 KiB each, so they are only mixed in where the
+/// property cost is linear in the encoding size.
+fn witness_item(with_mldsa: bool) -> BoxedStrategy<WitnessItem> {
+    if with_mldsa {
+        prop_oneof![
+            sentinel_witness(),
+            mldsa_witness(),
+            simplicity_witness(),
+            unknown_suite_witness(),
+        ]
+        .boxed()
+    } else {
+        prop_oneof![
+            sentinel_witness(),
+            simplicity_witness(),
+            unknown_suite_witness()
+        ]
+        .boxed()
+    }
+}
+
+fn witness(with_mldsa: bool) -> impl Strategy<Value = Vec<WitnessItem>> {
+    vec(witness_item(with_mldsa), 0..=3)
+}
+
+fn da_commit_core() -> impl Strategy<Value = DaCommitCore> {
+    (
+        any::<[[u8; 32]; 5]>(),
+        1..=MAX_DA_CHUNK_COUNT as u16,
+        any::<u64>(),
+        any::<u8>(),
+        bytes(64),
+    )
+        .prop_map(
+            |(
+                [da_id, retl_domain_id, tx_data_root, state_root, withdrawals_root],
+                chunk_count,
+                batch_number,
+                batch_sig_suite,
+                batch_sig,
+            )| {
+                DaCommitCore {
+                    da_id,
+                    chunk_count,
+                    retl_domain_id,
+                    batch_number,
+                    tx_data_root,
+                    state_root,
+                    withdrawals_root,
+                    batch_sig_suite,
+                    batch_sig,
+                }
+            },
+        )
+}
+
+fn da_chunk_core() -> impl Strategy<Value = DaChunkCore> {
+    (
+        any::<[u8; 32]>(),
+        0..MAX_DA_CHUNK_COUNT as u16,
+        any::<[u8; 32]>(),
+    )
+        .prop_map(|(da_id, chunk_index, chunk_hash)| DaChunkCore {
+            da_id,
+            chunk_index,
+            chunk_hash,
+        })
+}
+
+/// Kind-specific DA section: (tx_kind, commit core, chunk core, payload).
+type DaSection = (u8, Option<DaCommitCore>, Option<DaChunkCore>, Vec<u8>);
+
+fn da_section() -> impl Strategy<Value = DaSection> {
+    prop_oneof![
+        Just((0x00, None, None, Vec::new())),
+        (da_commit_core(), bytes(128)).prop_map(|(core, payload)| (
+            0x01,
+            Some(core),
+            None,
+            payload
+        )),
+        (da_chunk_core(), vec(any::<u8>(), 1..=128)).prop_map(|(core, payload)| (
+            0x02,
+            None,
+            Some(core),
+            payload
+        )),
+    ]
+}
+
+fn arb_tx(with_mldsa: bool) -> impl Strategy<Value = Tx> {
+    (
+        any::<u64>(),
+        vec(tx_input(), 0..=3),
+        vec(tx_output(), 0..=3),
+        any::<u32>(),
+        da_section(),
+        witness(with_mldsa),
+    )
+        .prop_map(
+            |(tx_nonce, inputs, outputs, locktime, (tx_kind, commit, chunk, payload), witness)| {
+                Tx {
+                    version: TX_WIRE_VERSION,
+                    tx_kind,
+                    tx_nonce,
+                    inputs,
+                    outputs,
+                    locktime,
+                    da_commit_core: commit,
+                    da_chunk_core: chunk,
+                    witness,
+                    da_payload: payload,
+                }
+            },
+        )
+}
+
+fn arb_header() -> impl Strategy<Value = BlockHeader> {
+    (
+        any::<u32>(),
+        any::<[[u8; 32]; 3]>(),
+        any::<u64>(),
+        any::<u64>(),
+    )
+        .prop_map(
+            |(version, [prev_block_hash, merkle_root, target], timestamp, nonce)| BlockHeader {
+                version,
+                prev_block_hash,
+                merkle_root,
+                timestamp,
+                target,
+                nonce,
+            },
+        )
+}
+
+fn marshal_header(h: &BlockHeader) -> Vec<u8> {
+    let mut out = Vec::with_capacity(BLOCK_HEADER_BYTES);
+    out.extend_from_slice(&h.version.to_le_bytes());
+    out.extend_from_slice(&h.prev_block_hash);
+    out.extend_from_slice(&h.merkle_root);
+    out.extend_from_slice(&h.timestamp.to_le_bytes());
+    out.extend_from_slice(&h.target);
+    out.extend_from_slice(&h.nonce.to_le_bytes());
+    out
+}
+
+fn marshal_block(h: &BlockHeader, txs: &[Tx]) -> Vec<u8> {
+    let mut out = marshal_header(h);
+    encode_compact_size(txs.len() as u64, &mut out);
+    for tx in txs {
+        out.extend_from_slice(&marshal_tx(tx).expect("marshal tx"));
+    }
+    out
+}
+
+/// Byte offsets of every CompactSize emitted by `marshal_tx`, walking the same
+/// field order as the encoder. Offsets are relative to the start of `tx`.
+fn tx_varint_offsets(tx: &Tx) -> Vec<usize> {
+    fn cs_len(n: usize) -> usize {
+        let mut buf = Vec::new();
+        encode_compact_size(n as u64, &mut buf);
+        buf.len()
+    }
+    let mut offsets = Vec::new();
+    let mut at = 4 + 1 + 8;
+    offsets.push(at);
+    at += cs_len(tx.inputs.len());
+    for input in &tx.inputs {
+        at += 32 + 4;
+        offsets.push(at);
+        at += cs_len(input.script_sig.len()) + input.script_sig.len() + 4;
+    }
+    offsets.push(at);
+    at += cs_len(tx.outputs.len());
+    for output in &tx.outputs {
+        at += 8 + 2;
+        offsets.push(at);
+        at += cs_len(output.covenant_data.len()) + output.covenant_data.len();
+    }
+    at += 4;
+    if let Some(core) = &tx.da_commit_core {
+        at += 32 + 2 + 32 + 8 + 32 * 3 + 1;
+        offsets.push(at);
+        at += cs_len(core.batch_sig.len()) + core.batch_sig.len();
+    }
+    if tx.da_chunk_core.is_some() {
+        at += 32 + 2 + 32;
+    }
+    offsets.push(at);
+    at += cs_len(tx.witness.len());
+    for item in &tx.witness {
+        at += 1;
+        offsets.push(at);
+        at += cs_len(item.pubkey.len()) + item.pubkey.len();
+        offsets.push(at);
+        at += cs_len(item.signature.len()) + item.signature.len();
+    }
+    offsets.push(at);
+    offsets
+}
+
+/// Re-encode the CompactSize at `offset` one width wider than minimal.
+fn widen_compact_size(encoded: &[u8], offset: usize) -> Vec<u8> {
+    let (value, width) = match encoded[offset] {
+        0xfd => (
+            u16::from_le_bytes([encoded[offset + 1], encoded[offset + 2]]) as u64,
+            3,
+        ),
+        0xfe => (
+            u32::from_le_bytes(encoded[offset + 1..offset + 5].try_into().unwrap()) as u64,
+            5,
+        ),
+        0xff => panic!("9-byte CompactSize cannot be widened"),
+        tag => (tag as u64, 1),
+    };
+    let mut wide = Vec::new();
+    match width {
+        1 => {
+            wide.push(0xfd);
+            wide.extend_from_slice(&(value as u16).to_le_bytes());
+        }
+        3 => {
+            wide.push(0xfe);
+            wide.extend_from_slice(&(value as u32).to_le_bytes());
+        }
+        _ => {
+            wide.push(0xff);
+            wide.extend_from_slice(&value.to_le_bytes());
+        }
+    }
+    let mut out = encoded[..offset].to_vec();
+    out.extend_from_slice(&wide);
+    out.extend_from_slice(&encoded[offset + width..]);
+    out
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(128))]
+
+    #[test]
+    fn tx_serialize_parse_serialize_is_fixed_point(tx in arb_tx(true)) {
+        let encoded = marshal_tx(&tx).expect("marshal");
+        let (parsed, _, _, consumed) = parse_tx(&encoded).expect("parse");
+        prop_assert_eq!(consumed, encoded.len());
+        prop_assert_eq!(&parsed, &tx);
+        prop_assert_eq!(marshal_tx(&parsed).expect("remarshal"), encoded);
+    }
+
+    #[test]
+    fn txid_ignores_witness_wtxid_does_not(tx in arb_tx(true), other in witness(true)) {
+        let (_, txid, wtxid, _) = parse_tx(&marshal_tx(&tx).expect("marshal")).expect("parse");
+        let mut mutated = tx.clone();
+        mutated.witness = other;
+        let (_, txid_m, wtxid_m, _) =
+            parse_tx(&marshal_tx(&mutated).expect("marshal")).expect("parse mutated");
+        prop_assert_eq!(txid, txid_m);
+        prop_assert_eq!(wtxid == wtxid_m, tx.witness == mutated.witness);
+    }
+
+    #[test]
+    fn non_minimal_compact_size_rejected_at_every_varint(tx in arb_tx(false)) {
+        let encoded = marshal_tx(&tx).expect("marshal");
+        let offsets = tx_varint_offsets(&tx);
+        for &offset in &offsets {
+            let widened = widen_compact_size(&encoded, offset);
+            prop_assert!(parse_tx(&widened).is_err(), "accepted widened varint at {}", offset);
+        }
+    }
+
+    #[test]
+    fn tx_truncation_rejected_at_every_offset(tx in arb_tx(false)) {
+        let encoded = marshal_tx(&tx).expect("marshal");
+        for cut in 0..encoded.len() {
+            prop_assert!(parse_tx(&encoded[..cut]).is_err(), "accepted prefix of len {}", cut);
+        }
+    }
+
+    #[test]
+    fn block_serialize_parse_serialize_is_fixed_point(
+        header in arb_header(),
+        txs in vec(arb_tx(true), 1..=4),
+    ) {
+        let encoded = marshal_block(&header, &txs);
+        let pb = parse_block_bytes(&encoded).expect("parse block");
+        prop_assert_eq!(&pb.header, &header);
+        prop_assert_eq!(&pb.txs, &txs);
+        prop_assert_eq!(marshal_block(&pb.header, &pb.txs), encoded);
+    }
+
+    #[test]
+    fn block_truncation_rejected_at_every_offset(
+        header in arb_header(),
+        txs in vec(arb_tx(false), 1..=2),
+    ) {
+        let encoded = marshal_block(&header, &txs);
+        for cut in 0..encoded.len() {
+            prop_assert!(parse_block_bytes(&encoded[..cut]).is_err(), "accepted prefix of len {}", cut);
+        }
+    }
+
+    #[test]
+    fn varint_offsets_match_marshal_layout(tx in arb_tx(false)) {
+        // Guards the offset walker above: every recorded offset must decode to
+        // the field length the encoder wrote there.
+        let encoded = marshal_tx(&tx).expect("marshal");
+        let mut want = vec![tx.inputs.len()];
+        want.extend(tx.inputs.iter().map(|i| i.script_sig.len()));
+        want.push(tx.outputs.len());
+        want.extend(tx.outputs.iter().map(|o| o.covenant_data.len()));
+        if let Some(core) = &tx.da_commit_core {
+            want.push(core.batch_sig.len());
+        }
+        want.push(tx.witness.len());
+        for item in &tx.witness {
+            want.push(item.pubkey.len());
+            want.push(item.signature.len());
+        }
+        want.push(tx.da_payload.len());
+        let offsets = tx_varint_offsets(&tx);
+        prop_assert_eq!(offsets.len(), want.len());
+        for (&offset, &len) in offsets.iter().zip(&want) {
+            let (v, _) = read_compact_size_bytes(&encoded[offset..]).expect("varint");
+            prop_assert_eq!(v, len as u64);
+        }
+    }
+
+    #[test]
+    fn bounded_compact_size_matches_two_step_decode(v in arb_u64_any_width(), max in arb_usize_bound()) {
+        let mut buf = Vec::new();
+        encode_compact_size(v, &mut buf);
+        let (decoded, consumed) = read_compact_size_bytes(&buf).expect("minimal encoding");
+        let two_step = usize::try_from(decoded).ok().filter(|n| *n <= max);
+        match compact_size_decode_bounded(&buf, max) {
+            Ok((n, used)) => {
+                prop_assert_eq!(Some(n), two_step);
+                prop_assert_eq!(used, consumed);
+            }
+            Err(_) => prop_assert_eq!(two_step, None),
+        }
+    }
+
+    #[test]
+    fn bounded_compact_size_rejects_what_plain_decode_rejects(raw in any::<[u8; 9]>(), cut in 0usize..=9) {
+        let raw = &raw[..cut];
+        if read_compact_size_bytes(raw).is_err() {
+            prop_assert!(compact_size_decode_bounded(raw, usize::MAX).is_err());
+        }
+    }
+
+    #[test]
+    fn oversized_input_count_rejected_before_allocation(v in (MAX_TX_INPUTS + 1)..=u64::MAX) {
+        // A 1-byte body after the count: honouring `v` as a capacity would
+        // abort the process long before the EOF check.
+        let mut b = Vec::new();
+        b.extend_from_slice(&TX_WIRE_VERSION.to_le_bytes());
+        b.push(0x00);
+        b.extend_from_slice(&0u64.to_le_bytes());
+        encode_compact_size(v, &mut b);
+        b.push(0x00);
+        let err = parse_tx(&b).unwrap_err();
+        prop_assert_eq!(err.msg, "input_count overflow");
+    }
+
+    #[test]
+    fn da_chunk_count_cap_holds_for_any_width(count in arb_u64_any_width()) {
+        // Counts past u32 (and u16) must be turned away before any narrowing.
+        let checked = check_da_chunk_count(count, MAX_DA_CHUNK_COUNT);
+        prop_assert_eq!(checked.is_ok(), (1..=MAX_DA_CHUNK_COUNT).contains(&count));
+        if let Err(err) = checked {
+            prop_assert_eq!(err.code, ErrorCode::TxErrParse);
+        }
+    }
+}
+
+/// Values spread across all four CompactSize widths and their edges.
+fn arb_u64_any_width() -> impl Strategy<Value = u64> {
+    prop_oneof![
+        any::<u64>(),
+        0u64..=0x1_0001,
+        (0u32..64, -2i64..=2).prop_map(|(shift, delta)| (1u64 << shift).wrapping_add_signed(delta)),
+        Just(u64::MAX),
+    ]
+}
+
+fn arb_usize_bound() -> impl Strategy<Value = usize> {
+    prop_oneof![any::<usize>(), 0usize..=0x1_0001, Just(usize::MAX)]
+}
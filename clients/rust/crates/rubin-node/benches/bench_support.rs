@@ -1,4 +1,4 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::time::{SystemTime, UNIX_EPOCH};
 
 use rubin_consensus::constants::POW_LIMIT;
@@ -12,7 +12,7 @@ use rubin_node::undo::{build_block_undo, BlockUndo};
 use rubin_node::{
     block_store_path, build_coinbase_tx, chain_state_path, default_mine_address,
     default_sync_config, devnet_genesis_block_bytes, devnet_genesis_chain_id, BlockStore,
-    ChainState, SyncEngine, TxPool,
+    ChainState, Miner, MinerConfig, SyncEngine, TxPool,
 };
 
 #[allow(dead_code)]
@@ -267,6 +267,37 @@ pub fn fresh_sync_engine(prefix: &str) -> SyncFixture {
     }
 }
 
+/// Store-backed engine over `dir`, starting from an empty chain.
+#[allow(dead_code)]
+pub fn store_engine(dir: &Path) -> SyncEngine {
+    let block_store = BlockStore::open(block_store_path(dir)).expect("open block store");
+    SyncEngine::new(
+        ChainState::new(),
+        Some(block_store),
+        default_sync_config(None, devnet_genesis_chain_id(), Some(chain_state_path(dir))),
+    )
+    .expect("new sync engine")
+}
+
+/// Store-backed engine holding a freshly mined chain of `blocks` blocks.
+/// Coinbase-only blocks keep signature verification out of the
+/// measurement.
+#[allow(dead_code)]
+pub fn mined_chain_fixture(prefix: &str, blocks: u64) -> SyncFixture {
+    let dir = unique_temp_dir(prefix);
+    let mut engine = store_engine(&dir);
+    let cfg = MinerConfig {
+        timestamp_source: || 1_777_000_000,
+        ..MinerConfig::default()
+    };
+    Miner::new(&mut engine, None, cfg)
+        .expect("miner")
+        .mine_n(blocks as usize, &[])
+        .expect("mine chain");
+    let store = engine.block_store_snapshot().expect("mined block store");
+    SyncFixture { dir, store, engine }
+}
+
 pub fn engine_after_genesis(prefix: &str) -> SyncFixture {
     let mut fixture = fresh_sync_engine(prefix);
     fixture
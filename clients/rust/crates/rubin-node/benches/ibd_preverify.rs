@@ -0,0 +1,61 @@
+#[allow(dead_code)]
+#[path = "bench_support.rs"]
+mod bench_support;
+
+use std::time::{Duration, Instant};
+
+use criterion::{criterion_group, criterion_main, Criterion};
+
+use bench_support::{mined_chain_fixture, store_engine, unique_temp_dir};
+
+#[allow(dead_code)]
+pub const IBD_PREVERIFY_BENCH_COMMAND: &str =
+    "cargo bench --manifest-path clients/rust/Cargo.toml -p rubin-node --bench ibd_preverify -- --noplot --sample-size 10";
+const IBD_PREVERIFY_GROUP: &str = "rubin_node_ibd_import_1000";
+const IBD_CHAIN_BLOCKS: u64 = 1_000;
+
+/// Imports the 1,000-block synthetic chain with one pre-verification worker
+/// (pre-verification still overlaps the serial section on its own thread)
+/// and with the available parallelism. `blocks_preverified_ahead` is the
+/// overlap: blocks that were ready before the serial section reached them.
+/// It is reported once per configuration, from the last measured import.
+fn ibd_preverify_bench(c: &mut Criterion) {
+    let mut source = mined_chain_fixture("rubin-node-ibd-source", IBD_CHAIN_BLOCKS);
+
+    let mut group = c.benchmark_group(IBD_PREVERIFY_GROUP);
+    group.sample_size(10);
+    group.measurement_time(Duration::from_secs(20));
+    for (label, workers) in [("preverify_1_worker", 1usize), ("preverify_all_cores", 0)] {
+        let mut last_summary = None;
+        group.bench_function(label, |b| {
+            b.iter_custom(|iters| {
+                let mut total = Duration::ZERO;
+                for _ in 0..iters {
+                    let dir = unique_temp_dir("rubin-node-ibd-local");
+                    let mut local = store_engine(&dir);
+                    let start = Instant::now();
+                    let summary = local
+                        .import_blocks_with_preverify_workers(&mut source.engine, workers)
+                        .expect("import");
+                    total += start.elapsed();
+                    assert_eq!(summary.blocks_applied, IBD_CHAIN_BLOCKS);
+                    last_summary = Some(summary);
+                    drop(local);
+                    std::fs::remove_dir_all(&dir).expect("cleanup temp dir");
+                }
+                total
+            })
+        });
+        if let Some(summary) = last_summary {
+            eprintln!(
+                "{label}: blocks_preverified_ahead={}/{}",
+                summary.blocks_preverified_ahead, summary.blocks_applied
+            );
+        }
+    }
+    group.finish();
+    source.cleanup();
+}
+
+criterion_group!(ibd_preverify_benches, ibd_preverify_bench);
+criterion_main!(ibd_preverify_benches);
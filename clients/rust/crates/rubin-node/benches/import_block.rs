@@ -0,0 +1,63 @@
+#[allow(dead_code)]
+#[path = "bench_support.rs"]
+mod bench_support;
+
+use std::time::{Duration, Instant};
+
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+
+use bench_support::{mined_chain_fixture, store_engine, unique_temp_dir};
+
+#[allow(dead_code)]
+pub const IMPORT_BLOCK_BENCH_COMMAND: &str =
+    "cargo bench --manifest-path clients/rust/Cargo.toml -p rubin-node --bench import_block -- --noplot";
+const IMPORT_BLOCK_GROUP: &str = "rubin_node_import_block";
+const IMPORT_CHAIN_BLOCKS: u64 = 100;
+
+/// Mines the synthetic chain and returns its blocks in height order.
+fn mine_chain() -> Vec<Vec<u8>> {
+    let source = mined_chain_fixture("rubin-node-import-source", IMPORT_CHAIN_BLOCKS);
+    let blocks = (0..IMPORT_CHAIN_BLOCKS)
+        .map(|height| {
+            let hash = source
+                .store
+                .canonical_hash(height)
+                .expect("canonical hash")
+                .expect("mined height");
+            source.store.get_block_by_hash(hash).expect("mined block")
+        })
+        .collect();
+    source.cleanup();
+    blocks
+}
+
+/// Connects each block of the chain into a fresh store-backed engine: full
+/// validation, the block/undo writes and the chainstate update. Opening the
+/// store is outside the timed region.
+fn import_block_bench(c: &mut Criterion) {
+    let blocks = mine_chain();
+    let mut group = c.benchmark_group(IMPORT_BLOCK_GROUP);
+    group.sample_size(10);
+    group.throughput(Throughput::Elements(IMPORT_CHAIN_BLOCKS));
+    group.bench_function("coinbase_chain", |b| {
+        b.iter_custom(|iters| {
+            let mut total = Duration::ZERO;
+            for _ in 0..iters {
+                let dir = unique_temp_dir("rubin-node-import-local");
+                let mut local = store_engine(&dir);
+                let start = Instant::now();
+                for block in &blocks {
+                    local.apply_block(block, None).expect("import block");
+                }
+                total += start.elapsed();
+                drop(local);
+                std::fs::remove_dir_all(&dir).expect("cleanup temp dir");
+            }
+            total
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(import_block_benches, import_block_bench);
+criterion_main!(import_block_benches);
@@ -0,0 +1,374 @@
+//! Bech32m (BIP-350) text addresses for covenant outputs.
+//!
+//! An address encodes `version || covenant_type (u16 LE) || covenant_data`
+//! under a network-specific human-readable part, so a devnet address can
+//! never be mistaken for a mainnet one. Only output kinds a wallet can hand
+//! out as a receive target are addressable: `CORE_P2PK` and `CORE_VAULT`.
+
+use rubin_consensus::constants::{COV_TYPE_P2PK, COV_TYPE_VAULT, MAX_P2PK_COVENANT_DATA};
+use rubin_consensus::{
+    canonical_rotation_network_name_normalized, normalized_rotation_network_name,
+    parse_vault_covenant_data, TxOutput, SUPPORTED_ROTATION_NETWORK_NAMES_CSV,
+};
+
+pub const ADDRESS_VERSION_V0: u8 = 0;
+
+/// Upper bound on the full address string. BIP-350's 90-character limit is
+/// too small for vault covenants, so a larger fixed cap bounds decode work.
+pub const MAX_ADDRESS_LEN: usize = 1023;
+
+const BECH32M_CONST: u32 = 0x2bc8_30a3;
+const CHECKSUM_LEN: usize = 6;
+const CHARSET: &[u8; 32] = b"qpzry9x8gf2tvdw0s3jn54khce6mua7l";
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Address {
+    pub covenant_type: u16,
+    pub covenant_data: Vec<u8>,
+}
+
+impl Address {
+    pub fn to_tx_output(&self, value: u64) -> TxOutput {
+        TxOutput {
+            value,
+            covenant_type: self.covenant_type,
+            covenant_data: self.covenant_data.clone(),
+        }
+    }
+}
+
+/// Returns the human-readable part for a chain profile network name.
+pub fn address_hrp(network: &str) -> Result<&'static str, String> {
+    let normalized = normalized_rotation_network_name(network);
+    match canonical_rotation_network_name_normalized(normalized.as_ref()) {
+        Some("mainnet") => Ok("rbn"),
+        Some("testnet") => Ok("trbn"),
+        Some("devnet") => Ok("drbn"),
+        _ => Err(format!(
+            "address: unsupported network {network:?} (expected one of: {SUPPORTED_ROTATION_NETWORK_NAMES_CSV})"
+        )),
+    }
+}
+
+pub fn encode_address(
+    network: &str,
+    covenant_type: u16,
+    covenant_data: &[u8],
+) -> Result<String, String> {
+    let hrp = address_hrp(network)?;
+    validate_addressable_covenant(covenant_type, covenant_data)?;
+    let mut payload = Vec::with_capacity(3 + covenant_data.len());
+    payload.push(ADDRESS_VERSION_V0);
+    payload.extend_from_slice(&covenant_type.to_le_bytes());
+    payload.extend_from_slice(covenant_data);
+
+    let mut data = convert_bits(&payload, 8, 5, true)?;
+    data.extend_from_slice(&bech32m_checksum(hrp.as_bytes(), &data));
+    let mut out = String::with_capacity(hrp.len() + 1 + data.len());
+    out.push_str(hrp);
+    out.push('1');
+    out.extend(data.iter().map(|v| CHARSET[*v as usize] as char));
+    if out.len() > MAX_ADDRESS_LEN {
+        return Err(format!("address: encoded length {} exceeds cap", out.len()));
+    }
+    Ok(out)
+}
+
+pub fn encode_tx_output_address(network: &str, output: &TxOutput) -> Result<String, String> {
+    encode_address(network, output.covenant_type, &output.covenant_data)
+}
+
+/// Decodes `addr` for `network`, rejecting mixed case, bad checksums, HRPs
+/// of other networks, unknown versions and non-addressable covenants.
+pub fn decode_address(network: &str, addr: &str) -> Result<Address, String> {
+    let expected_hrp = address_hrp(network)?;
+    if addr.len() > MAX_ADDRESS_LEN {
+        return Err(format!("address: length {} exceeds cap", addr.len()));
+    }
+    if addr.bytes().any(|b| !(33..=126).contains(&b)) {
+        return Err("address: invalid character".to_string());
+    }
+    let has_lower = addr.bytes().any(|b| b.is_ascii_lowercase());
+    let has_upper = addr.bytes().any(|b| b.is_ascii_uppercase());
+    if has_lower && has_upper {
+        return Err("address: mixed case".to_string());
+    }
+    let addr = addr.to_ascii_lowercase();
+    let sep = addr
+        .rfind('1')
+        .ok_or_else(|| "address: missing separator".to_string())?;
+    let (hrp, data_part) = (&addr[..sep], &addr[sep + 1..]);
+    if hrp.is_empty() {
+        return Err("address: empty hrp".to_string());
+    }
+    if data_part.len() < CHECKSUM_LEN {
+        return Err("address: data part too short".to_string());
+    }
+    let data = data_part
+        .bytes()
+        .map(|c| {
+            CHARSET
+                .iter()
+                .position(|x| *x == c)
+                .map(|v| v as u8)
+                .ok_or_else(|| format!("address: invalid data character {:?}", c as char))
+        })
+        .collect::<Result<Vec<u8>, String>>()?;
+    if polymod_with_hrp(hrp.as_bytes(), &data) != BECH32M_CONST {
+        return Err("address: invalid bech32m checksum".to_string());
+    }
+    if hrp != expected_hrp {
+        return Err(format!(
+            "address: hrp {hrp:?} does not match network {network:?} (expected {expected_hrp:?})"
+        ));
+    }
+
+    let payload = convert_bits(&data[..data.len() - CHECKSUM_LEN], 5, 8, false)?;
+    if payload.len() < 3 {
+        return Err("address: payload too short".to_string());
+    }
+    if payload[0] != ADDRESS_VERSION_V0 {
+        return Err(format!("address: unsupported version {}", payload[0]));
+    }
+    let covenant_type = u16::from_le_bytes([payload[1], payload[2]]);
+    let covenant_data = payload[3..].to_vec();
+    validate_addressable_covenant(covenant_type, &covenant_data)?;
+    Ok(Address {
+        covenant_type,
+        covenant_data,
+    })
+}
+
+fn validate_addressable_covenant(covenant_type: u16, covenant_data: &[u8]) -> Result<(), String> {
+    match covenant_type {
+        COV_TYPE_P2PK => {
+            if covenant_data.len() != MAX_P2PK_COVENANT_DATA as usize {
+                return Err(format!(
+                    "address: CORE_P2PK covenant_data must be {MAX_P2PK_COVENANT_DATA} bytes, got {}",
+                    covenant_data.len()
+                ));
+            }
+            Ok(())
+        }
+        COV_TYPE_VAULT => parse_vault_covenant_data(covenant_data)
+            .map(|_| ())
+            .map_err(|err| format!("address: invalid CORE_VAULT covenant_data: {err}")),
+        other => Err(format!(
+            "address: covenant_type 0x{other:04x} is not addressable"
+        )),
+    }
+}
+
+fn polymod(values: impl IntoIterator<Item = u8>) -> u32 {
+    const GEN: [u32; 5] = [
+        0x3b6a_57b2,
+        0x2650_8e6d,
+        0x1ea1_19fa,
+        0x3d42_33dd,
+        0x2a14_62b3,
+    ];
+    let mut chk: u32 = 1;
+    for v in values {
+        let top = chk >> 25;
+        chk = ((chk & 0x01ff_ffff) << 5) ^ u32::from(v);
+        for (i, g) in GEN.iter().enumerate() {
+            if (top >> i) & 1 == 1 {
+                chk ^= g;
+            }
+        }
+    }
+    chk
+}
+
+fn hrp_expand(hrp: &[u8]) -> impl Iterator<Item = u8> + '_ {
+    hrp.iter()
+        .map(|c| c >> 5)
+        .chain(std::iter::once(0))
+        .chain(hrp.iter().map(|c| c & 0x1f))
+}
+
+fn polymod_with_hrp(hrp: &[u8], data: &[u8]) -> u32 {
+    polymod(hrp_expand(hrp).chain(data.iter().copied()))
+}
+
+fn bech32m_checksum(hrp: &[u8], data: &[u8]) -> [u8; CHECKSUM_LEN] {
+    let pm = polymod(
+        hrp_expand(hrp)
+            .chain(data.iter().copied())
+            .chain([0u8; CHECKSUM_LEN]),
+    ) ^ BECH32M_CONST;
+    let mut out = [0u8; CHECKSUM_LEN];
+    for (i, slot) in out.iter_mut().enumerate() {
+        *slot = ((pm >> (5 * (5 - i))) & 0x1f) as u8;
+    }
+    out
+}
+
+fn convert_bits(data: &[u8], from: u32, to: u32, pad: bool) -> Result<Vec<u8>, String> {
+    let mut acc: u32 = 0;
+    let mut bits: u32 = 0;
+    let max_v: u32 = (1 << to) - 1;
+    let mut out = Vec::with_capacity(data.len() * from as usize / to as usize + 1);
+    for value in data {
+        acc = (acc << from) | u32::from(*value);
+        bits += from;
+        while bits >= to {
+            bits -= to;
+            out.push(((acc >> bits) & max_v) as u8);
+        }
+    }
+    if pad {
+        if bits > 0 {
+            out.push(((acc << (to - bits)) & max_v) as u8);
+        }
+    } else if bits >= from || ((acc << (to - bits)) & max_v) != 0 {
+        return Err("address: invalid padding".to_string());
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rubin_consensus::constants::{COV_TYPE_HTLC, SUITE_ID_ML_DSA_87};
+
+    fn p2pk_data(fill: u8) -> Vec<u8> {
+        let mut data = vec![fill; MAX_P2PK_COVENANT_DATA as usize];
+        data[0] = SUITE_ID_ML_DSA_87;
+        data
+    }
+
+    fn vault_data() -> Vec<u8> {
+        let mut data = vec![0x11; 32];
+        data.extend_from_slice(&[1, 1]);
+        data.extend_from_slice(&[0x22; 32]);
+        data.extend_from_slice(&1u16.to_le_bytes());
+        data.extend_from_slice(&[0x33; 32]);
+        data
+    }
+
+    #[test]
+    fn bip350_checksum_vectors() {
+        for valid in [
+            "a1lqfn3a",
+            "abcdef1l7aum6echk45nj3s0wdvt2fg8x9yrzpqzd3ryx",
+            "?1v759aa",
+        ] {
+            let sep = valid.rfind('1').unwrap();
+            let data: Vec<u8> = valid[sep + 1..]
+                .bytes()
+                .map(|c| CHARSET.iter().position(|x| *x == c).unwrap() as u8)
+                .collect();
+            assert_eq!(
+                polymod_with_hrp(&valid.as_bytes()[..sep], &data),
+                BECH32M_CONST,
+                "{valid}"
+            );
+        }
+    }
+
+    #[test]
+    fn p2pk_and_vault_round_trip() {
+        for (covenant_type, data) in [
+            (COV_TYPE_P2PK, p2pk_data(0x5a)),
+            (COV_TYPE_VAULT, vault_data()),
+        ] {
+            for network in ["devnet", "testnet", "mainnet"] {
+                let addr = encode_address(network, covenant_type, &data).expect("encode");
+                assert!(addr.starts_with(&format!("{}1", address_hrp(network).unwrap())));
+                let decoded = decode_address(network, &addr).expect("decode");
+                assert_eq!(decoded.covenant_type, covenant_type);
+                assert_eq!(decoded.covenant_data, data);
+                let out = decoded.to_tx_output(42);
+                assert_eq!(out.value, 42);
+                assert_eq!(encode_tx_output_address(network, &out).unwrap(), addr);
+                // All-uppercase is the same address.
+                assert_eq!(
+                    decode_address(network, &addr.to_ascii_uppercase()).expect("upper"),
+                    decoded
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn rejects_mixed_case() {
+        let addr = encode_address("devnet", COV_TYPE_P2PK, &p2pk_data(1)).unwrap();
+        let mixed = format!("D{}", &addr[1..]);
+        let err = decode_address("devnet", &mixed).unwrap_err();
+        assert_eq!(err, "address: mixed case");
+    }
+
+    #[test]
+    fn rejects_checksum_corruption() {
+        let addr = encode_address("mainnet", COV_TYPE_P2PK, &p2pk_data(2)).unwrap();
+        let sep = addr.rfind('1').unwrap();
+        for pos in [sep + 1, sep + 10, addr.len() - 1] {
+            let mut bytes = addr.clone().into_bytes();
+            bytes[pos] = if bytes[pos] == b'q' { b'p' } else { b'q' };
+            let err = decode_address("mainnet", std::str::from_utf8(&bytes).unwrap()).unwrap_err();
+            assert_eq!(err, "address: invalid bech32m checksum", "pos={pos}");
+        }
+    }
+
+    #[test]
+    fn rejects_hrp_mismatch_between_networks() {
+        let devnet = encode_address("devnet", COV_TYPE_P2PK, &p2pk_data(3)).unwrap();
+        let err = decode_address("mainnet", &devnet).unwrap_err();
+        assert!(err.contains("does not match network"), "{err}");
+        let mainnet = encode_address("mainnet", COV_TYPE_P2PK, &p2pk_data(3)).unwrap();
+        let err = decode_address("devnet", &mainnet).unwrap_err();
+        assert!(err.contains("does not match network"), "{err}");
+        assert!(address_hrp("regtest").is_err());
+    }
+
+    #[test]
+    fn rejects_bad_lengths_and_unaddressable_covenants() {
+        let err = encode_address("devnet", COV_TYPE_P2PK, &[SUITE_ID_ML_DSA_87; 32]).unwrap_err();
+        assert!(err.contains("must be 33 bytes"), "{err}");
+        let err = encode_address("devnet", COV_TYPE_VAULT, &vault_data()[..40]).unwrap_err();
+        assert!(err.contains("CORE_VAULT"), "{err}");
+        let err = encode_address("devnet", COV_TYPE_HTLC, &[0u8; 105]).unwrap_err();
+        assert!(err.contains("not addressable"), "{err}");
+
+        // Hand-build a correctly checksummed address whose P2PK payload is
+        // one byte short; decode must still reject it.
+        let mut payload = vec![ADDRESS_VERSION_V0];
+        payload.extend_from_slice(&COV_TYPE_P2PK.to_le_bytes());
+        payload.extend_from_slice(&[SUITE_ID_ML_DSA_87; 32]);
+        let mut data = convert_bits(&payload, 8, 5, true).unwrap();
+        data.extend_from_slice(&bech32m_checksum(b"drbn", &data));
+        let addr: String = "drbn1"
+            .chars()
+            .chain(data.iter().map(|v| CHARSET[*v as usize] as char))
+            .collect();
+        let err = decode_address("devnet", &addr).unwrap_err();
+        assert!(err.contains("must be 33 bytes"), "{err}");
+
+        let mut payload = vec![1u8];
+        payload.extend_from_slice(&COV_TYPE_P2PK.to_le_bytes());
+        payload.extend_from_slice(&p2pk_data(4));
+        let mut data = convert_bits(&payload, 8, 5, true).unwrap();
+        data.extend_from_slice(&bech32m_checksum(b"drbn", &data));
+        let addr: String = "drbn1"
+            .chars()
+            .chain(data.iter().map(|v| CHARSET[*v as usize] as char))
+            .collect();
+        assert_eq!(
+            decode_address("devnet", &addr).unwrap_err(),
+            "address: unsupported version 1"
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_strings() {
+        assert!(decode_address("devnet", "drbnqqqqqqqq").is_err());
+        assert!(decode_address("devnet", "drbn1b").is_err());
+        assert!(decode_address("devnet", "1qqqqqqqq").is_err());
+        assert!(decode_address("devnet", "drbn1qqqq qqqq").is_err());
+        let long = format!("drbn1{}", "q".repeat(MAX_ADDRESS_LEN));
+        assert!(decode_address("devnet", &long)
+            .unwrap_err()
+            .contains("exceeds cap"));
+    }
+}
@@ -0,0 +1,506 @@
+//! Command-line front end for the RPST partially-signed transaction format
+//! (`rubin_node::rpst`).
+//!
+//! Usage:
+//!
+//! ```text
+//! rubin-rpst [--max-input-bytes <n>] <command> ...
+//! rubin-rpst create <unsigned_tx_hex|-> <out.rpst> <prevout>...
+//! rubin-rpst create --tx-hex-file <path|-> <out.rpst> <prevout>...
+//! rubin-rpst sighash <in.rpst> <input_index> <chain_id_hex>
+//! rubin-rpst sign <in.rpst> <out.rpst> <input_index> <pubkey_hex> <signature_hex>
+//! rubin-rpst merge <out.rpst> <in.rpst>...
+//! rubin-rpst finalize <in.rpst>
+//! ```
+//!
+//! Large transactions (SLH-DSA witnesses run to ~100 KB of hex) overflow
+//! argv, so the unsigned tx can come from stdin (`-`) or a file instead.
+//! Whitespace and newlines in the hex are ignored, and no input is read past
+//! `--max-input-bytes` (default 8 MiB).
+//!
+//! One `<prevout>` per input, in input order:
+//! `<value>:<covenant_type>:<covenant_data_hex>:<creation_height>[:<role>]`
+//! where `covenant_type` is decimal or `0x`-prefixed hex and `role` is one of
+//! `signer` (default), `htlc-claim`, `htlc-refund`.
+//!
+//! `Mldsa87Keypair` cannot be loaded from disk, so `sign` does not hold keys:
+//! it attaches an ML-DSA-87 signature produced elsewhere over the digest that
+//! `sighash` prints, appending `SIGHASH_ALL`. Signatures are not verified here;
+//! consensus checks them when the finalized tx is submitted. `finalize` prints
+//! the fully witnessed transaction hex.
+
+use std::env;
+use std::fs;
+use std::io::{self, Read, Write};
+use std::path::Path;
+use std::process;
+
+use rubin_consensus::constants::{ML_DSA_87_SIG_BYTES, SIGHASH_ALL, SUITE_ID_ML_DSA_87};
+use rubin_consensus::{marshal_tx, parse_tx, TxOutput, WitnessItem};
+use rubin_node::{Rpst, RpstRole};
+
+const PROGRAM: &str = "rubin-rpst";
+const DEFAULT_MAX_INPUT_BYTES: usize = 8 << 20;
+
+/// Per-invocation input settings shared by the commands that read hex.
+struct Input<'a> {
+    stdin: &'a mut dyn Read,
+    max_bytes: usize,
+}
+
+impl Input<'_> {
+    /// Hex for `label` from an inline argument, or from stdin when `value`
+    /// is `-`. `file` is the `--<label>-file` alternative; giving both an
+    /// inline value and a file is rejected.
+    fn hex(
+        &mut self,
+        label: &str,
+        value: Option<&str>,
+        file: Option<&str>,
+    ) -> Result<Vec<u8>, String> {
+        let text = match (value, file) {
+            (Some(_), Some(_)) => {
+                return Err(format!(
+                    "{label}: give either inline hex or a file, not both"
+                ))
+            }
+            (None, None) => return Err(usage()),
+            (Some("-"), None) | (None, Some("-")) => self.read_limited(label, "stdin", None)?,
+            (None, Some(path)) => self.read_limited(label, path, Some(path))?,
+            (Some(inline), None) => {
+                if inline.len() > self.max_bytes {
+                    return Err(self.too_large(label));
+                }
+                inline.to_string()
+            }
+        };
+        let digits: String = text.split_ascii_whitespace().collect();
+        hex::decode(digits).map_err(|err| format!("{label}: {err}"))
+    }
+
+    fn read_limited(
+        &mut self,
+        label: &str,
+        name: &str,
+        path: Option<&str>,
+    ) -> Result<String, String> {
+        let mut raw = Vec::new();
+        let limit = self.max_bytes as u64 + 1;
+        let read = match path {
+            Some(path) => {
+                fs::File::open(path).and_then(|file| file.take(limit).read_to_end(&mut raw))
+            }
+            None => self.stdin.take(limit).read_to_end(&mut raw),
+        };
+        read.map_err(|err| format!("read {name}: {err}"))?;
+        if raw.len() > self.max_bytes {
+            return Err(self.too_large(label));
+        }
+        String::from_utf8(raw).map_err(|_| format!("{label}: {name} is not valid UTF-8"))
+    }
+
+    fn too_large(&self, label: &str) -> String {
+        format!(
+            "{label}: input exceeds --max-input-bytes {}",
+            self.max_bytes
+        )
+    }
+}
+
+fn usage() -> String {
+    format!(
+        "usage: {PROGRAM} [--max-input-bytes <n>] <command> ...\n       \
+         {PROGRAM} create <unsigned_tx_hex|-> <out.rpst> <prevout>...\n       \
+         {PROGRAM} create --tx-hex-file <path|-> <out.rpst> <prevout>...\n       \
+         {PROGRAM} sighash <in.rpst> <input_index> <chain_id_hex>\n       \
+         {PROGRAM} sign <in.rpst> <out.rpst> <input_index> <pubkey_hex> <signature_hex>\n       \
+         {PROGRAM} merge <out.rpst> <in.rpst>...\n       \
+         {PROGRAM} finalize <in.rpst>\n       \
+         prevout: <value>:<covenant_type>:<covenant_data_hex>:<creation_height>[:signer|htlc-claim|htlc-refund]"
+    )
+}
+
+fn decode_hex(label: &str, value: &str) -> Result<Vec<u8>, String> {
+    hex::decode(value.trim()).map_err(|err| format!("{label}: {err}"))
+}
+
+fn parse_index(value: &str) -> Result<usize, String> {
+    value
+        .parse::<usize>()
+        .map_err(|_| format!("invalid input_index: {value}"))
+}
+
+fn parse_prevout(spec: &str) -> Result<(TxOutput, u64, RpstRole), String> {
+    let parts: Vec<&str> = spec.split(':').collect();
+    let (value, cov_type, cov_data, height, role) = match parts.as_slice() {
+        [v, t, d, h] => (v, t, d, h, "signer"),
+        [v, t, d, h, r] => (v, t, d, h, *r),
+        _ => return Err(format!("invalid prevout spec: {spec}")),
+    };
+    let covenant_type = match cov_type.strip_prefix("0x") {
+        Some(hex_digits) => u16::from_str_radix(hex_digits, 16),
+        None => cov_type.parse::<u16>(),
+    }
+    .map_err(|_| format!("invalid covenant_type: {cov_type}"))?;
+    let role = match role {
+        "signer" => RpstRole::Signer,
+        "htlc-claim" => RpstRole::HtlcClaim,
+        "htlc-refund" => RpstRole::HtlcRefund,
+        other => return Err(format!("invalid role: {other}")),
+    };
+    let prevout = TxOutput {
+        value: value
+            .parse::<u64>()
+            .map_err(|_| format!("invalid prevout value: {value}"))?,
+        covenant_type,
+        covenant_data: decode_hex("covenant_data", cov_data)?,
+    };
+    let height = height
+        .parse::<u64>()
+        .map_err(|_| format!("invalid creation_height: {height}"))?;
+    Ok((prevout, height, role))
+}
+
+fn read_rpst(path: &str) -> Result<Rpst, String> {
+    let raw = fs::read(path).map_err(|err| format!("read {path}: {err}"))?;
+    Rpst::parse(&raw).map_err(|err| format!("{path}: {err}"))
+}
+
+fn write_rpst(path: &str, rpst: &Rpst) -> Result<(), String> {
+    fs::write(Path::new(path), rpst.serialize()?).map_err(|err| format!("write {path}: {err}"))
+}
+
+fn create(args: &[String], input: &mut Input<'_>) -> Result<String, String> {
+    let (tx_file, args) = match args {
+        [flag, path, rest @ ..] if flag == "--tx-hex-file" => (Some(path.as_str()), rest),
+        _ => (None, args),
+    };
+    let (tx_hex, out, prevouts) = match (tx_file, args) {
+        // `-` is never an output path, so it marks a second tx source.
+        (Some(_), [out, prevouts @ ..]) if out != "-" => (None, out, prevouts),
+        (_, [tx_hex, out, prevouts @ ..]) => (Some(tx_hex.as_str()), out, prevouts),
+        _ => return Err(usage()),
+    };
+    let raw = input.hex("unsigned_tx_hex", tx_hex, tx_file)?;
+    let (tx, _, _, consumed) = parse_tx(&raw).map_err(|err| format!("parse tx: {err}"))?;
+    if consumed != raw.len() {
+        return Err("unsigned tx has trailing bytes".to_string());
+    }
+    if !tx.witness.is_empty() {
+        return Err("unsigned tx must not carry witness items".to_string());
+    }
+    if prevouts.len() != tx.inputs.len() {
+        return Err(format!(
+            "expected {} prevout specs, got {}",
+            tx.inputs.len(),
+            prevouts.len()
+        ));
+    }
+    let mut rpst = Rpst::new(tx)?;
+    for (index, spec) in prevouts.iter().enumerate() {
+        let (prevout, height, role) = parse_prevout(spec)?;
+        rpst.set_prevout(index, prevout, height, role)?;
+    }
+    write_rpst(out, &rpst)?;
+    Ok(out.clone())
+}
+
+fn sighash(args: &[String]) -> Result<String, String> {
+    let [path, index, chain_id_hex] = args else {
+        return Err(usage());
+    };
+    let chain_id: [u8; 32] = decode_hex("chain_id_hex", chain_id_hex)?
+        .try_into()
+        .map_err(|_| "chain_id_hex must be 32 bytes".to_string())?;
+    let digest = read_rpst(path)?.sighash(parse_index(index)?, chain_id)?;
+    Ok(hex::encode(digest))
+}
+
+fn sign(args: &[String]) -> Result<String, String> {
+    let [path, out, index, pubkey_hex, signature_hex] = args else {
+        return Err(usage());
+    };
+    let mut signature = decode_hex("signature_hex", signature_hex)?;
+    if signature.len() as u64 != ML_DSA_87_SIG_BYTES {
+        return Err(format!(
+            "signature_hex: expected {ML_DSA_87_SIG_BYTES} bytes, got {}",
+            signature.len()
+        ));
+    }
+    signature.push(SIGHASH_ALL);
+    let item = WitnessItem {
+        suite_id: SUITE_ID_ML_DSA_87,
+        pubkey: decode_hex("pubkey_hex", pubkey_hex)?,
+        signature,
+    };
+    let mut rpst = read_rpst(path)?;
+    let slot = rpst.add_signature(parse_index(index)?, item)?;
+    write_rpst(out, &rpst)?;
+    Ok(format!("{out} slot={slot}"))
+}
+
+fn merge(args: &[String]) -> Result<String, String> {
+    let [out, first, rest @ ..] = args else {
+        return Err(usage());
+    };
+    let mut rpst = read_rpst(first)?;
+    for path in rest {
+        rpst.merge(&read_rpst(path)?)
+            .map_err(|err| format!("{path}: {err}"))?;
+    }
+    write_rpst(out, &rpst)?;
+    Ok(out.clone())
+}
+
+fn finalize(args: &[String]) -> Result<String, String> {
+    let [path] = args else {
+        return Err(usage());
+    };
+    let tx = read_rpst(path)?.finalize()?;
+    let raw = marshal_tx(&tx).map_err(|err| format!("marshal tx: {err}"))?;
+    Ok(hex::encode(raw))
+}
+
+fn run(args: &[String], stdin: &mut dyn Read) -> Result<String, String> {
+    let mut input = Input {
+        stdin,
+        max_bytes: DEFAULT_MAX_INPUT_BYTES,
+    };
+    let args = match args {
+        [flag, value, rest @ ..] if flag == "--max-input-bytes" => {
+            input.max_bytes = value
+                .parse::<usize>()
+                .ok()
+                .filter(|max| *max > 0)
+                .ok_or_else(|| format!("invalid --max-input-bytes: {value}"))?;
+            rest
+        }
+        _ => args,
+    };
+    let Some((command, rest)) = args.split_first() else {
+        return Err(usage());
+    };
+    match command.as_str() {
+        "-h" | "--help" => Ok(usage()),
+        "create" => create(rest, &mut input),
+        "sighash" => sighash(rest),
+        "sign" => sign(rest),
+        "merge" => merge(rest),
+        "finalize" => finalize(rest),
+        other => Err(format!("unknown command: {other}\n{}", usage())),
+    }
+}
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    match run(&args, &mut io::stdin().lock()) {
+        Ok(out) => println!("{out}"),
+        Err(err) => {
+            let _ = writeln!(io::stderr(), "{err}");
+            process::exit(1);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rubin_consensus::constants::{
+        COV_TYPE_ANCHOR, COV_TYPE_P2PK, COV_TYPE_VAULT, ML_DSA_87_PUBKEY_BYTES, SUITE_ID_SENTINEL,
+        TX_WIRE_VERSION,
+    };
+    use rubin_consensus::{Tx, TxInput};
+    use sha3::{Digest, Sha3_256};
+    use std::path::PathBuf;
+
+    fn run(args: &[String]) -> Result<String, String> {
+        super::run(args, &mut io::empty())
+    }
+
+    fn temp_dir(tag: &str) -> PathBuf {
+        let dir = env::temp_dir().join(format!("{PROGRAM}-{tag}-{}", process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).expect("mkdir");
+        dir
+    }
+
+    fn args(items: &[&str]) -> Vec<String> {
+        items.iter().map(|s| (*s).to_string()).collect()
+    }
+
+    fn unsigned_tx_hex() -> String {
+        let mut p2pk = vec![0x33; 33];
+        p2pk[0] = SUITE_ID_ML_DSA_87;
+        let tx = Tx {
+            version: TX_WIRE_VERSION,
+            tx_kind: 0x00,
+            tx_nonce: 1,
+            inputs: vec![TxInput {
+                prev_txid: [0x44; 32],
+                prev_vout: 0,
+                script_sig: Vec::new(),
+                sequence: 0,
+            }],
+            outputs: vec![TxOutput {
+                value: 900,
+                covenant_type: COV_TYPE_P2PK,
+                covenant_data: p2pk,
+            }],
+            locktime: 0,
+            da_commit_core: None,
+            da_chunk_core: None,
+            witness: Vec::new(),
+            da_payload: Vec::new(),
+        };
+        hex::encode(marshal_tx(&tx).expect("marshal"))
+    }
+
+    fn vault_data(pubkeys: &[Vec<u8>]) -> String {
+        let mut ids: Vec<[u8; 32]> = pubkeys.iter().map(|k| Sha3_256::digest(k).into()).collect();
+        ids.sort_unstable();
+        let mut data = vec![0x11; 32];
+        data.extend_from_slice(&[2, ids.len() as u8]);
+        for id in &ids {
+            data.extend_from_slice(id);
+        }
+        data.extend_from_slice(&1u16.to_le_bytes());
+        data.extend_from_slice(&[0x22; 32]);
+        hex::encode(data)
+    }
+
+    #[test]
+    fn create_sign_merge_finalize_vault_spend() {
+        let dir = temp_dir("vault");
+        let path = |name: &str| dir.join(name).display().to_string();
+        let pubkeys: Vec<Vec<u8>> = [0xa1u8, 0xb2]
+            .iter()
+            .map(|fill| vec![*fill; ML_DSA_87_PUBKEY_BYTES as usize])
+            .collect();
+        let prevout = format!("1000:0x{COV_TYPE_VAULT:04x}:{}:5", vault_data(&pubkeys));
+        run(&args(&[
+            "create",
+            &unsigned_tx_hex(),
+            &path("base.rpst"),
+            &prevout,
+        ]))
+        .expect("create");
+
+        let digest = run(&args(&[
+            "sighash",
+            &path("base.rpst"),
+            "0",
+            &"00".repeat(32),
+        ]))
+        .expect("sighash");
+        assert_eq!(digest.len(), 64);
+
+        let sig = "5a".repeat(ML_DSA_87_SIG_BYTES as usize);
+        // Second key signs first.
+        for (pk, out) in [(&pubkeys[1], "b.rpst"), (&pubkeys[0], "a.rpst")] {
+            run(&args(&[
+                "sign",
+                &path("base.rpst"),
+                &path(out),
+                "0",
+                &hex::encode(pk),
+                &sig,
+            ]))
+            .expect("sign");
+        }
+        run(&args(&[
+            "merge",
+            &path("all.rpst"),
+            &path("b.rpst"),
+            &path("a.rpst"),
+        ]))
+        .expect("merge");
+        let tx_hex = run(&args(&["finalize", &path("all.rpst")])).expect("finalize");
+        let (tx, _, _, _) = parse_tx(&hex::decode(tx_hex).unwrap()).expect("parse");
+        assert_eq!(tx.witness.len(), 2);
+        assert!(tx.witness.iter().all(|w| w.suite_id != SUITE_ID_SENTINEL));
+
+        let err = run(&args(&["finalize", &path("b.rpst")])).expect_err("below threshold");
+        assert!(err.contains("1 of 2"), "{err}");
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn create_reads_large_tx_from_stdin_and_file() {
+        let dir = temp_dir("stdin");
+        let path = |name: &str| dir.join(name).display().to_string();
+        let (tx, _, _, _) = parse_tx(&hex::decode(unsigned_tx_hex()).unwrap()).expect("parse");
+        let mut tx = tx;
+        tx.outputs.extend((0..4).map(|_| TxOutput {
+            value: 0,
+            covenant_type: COV_TYPE_ANCHOR,
+            covenant_data: vec![0x5a; 60_000],
+        }));
+        let tx_hex = hex::encode(marshal_tx(&tx).expect("marshal"));
+        assert!(tx_hex.len() > 400_000);
+        let prevout = format!("1000:0x{COV_TYPE_P2PK:04x}:{}:5", "33".repeat(33));
+
+        // Piped with a trailing newline, as `cat tx.hex | rubin-rpst` does.
+        let mut stdin = io::Cursor::new(format!("{tx_hex}\n"));
+        let out = path("stdin.rpst");
+        super::run(&args(&["create", "-", &out, &prevout]), &mut stdin).expect("create");
+        let digest = run(&args(&["sighash", &out, "0", &"00".repeat(32)])).expect("sighash");
+
+        // Same tx from a file wrapped at 64 columns.
+        let wrapped: Vec<&str> = tx_hex
+            .as_bytes()
+            .chunks(64)
+            .map(|line| std::str::from_utf8(line).unwrap())
+            .collect();
+        fs::write(path("tx.hex"), wrapped.join("\n")).expect("write hex");
+        let out = path("file.rpst");
+        run(&args(&[
+            "create",
+            "--tx-hex-file",
+            &path("tx.hex"),
+            &out,
+            &prevout,
+        ]))
+        .expect("create from file");
+        let from_file = run(&args(&["sighash", &out, "0", &"00".repeat(32)])).expect("sighash");
+        assert_eq!(from_file, digest);
+
+        let err = run(&args(&[
+            "create",
+            "--tx-hex-file",
+            &path("tx.hex"),
+            "-",
+            &out,
+            &prevout,
+        ]))
+        .unwrap_err();
+        assert!(err.contains("not both"), "{err}");
+        let mut stdin = io::Cursor::new(tx_hex);
+        let err = super::run(
+            &args(&["--max-input-bytes", "200000", "create", "-", &out, &prevout]),
+            &mut stdin,
+        )
+        .unwrap_err();
+        assert!(err.contains("exceeds --max-input-bytes 200000"), "{err}");
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn rejects_bad_arguments() {
+        assert!(run(&[]).is_err());
+        assert!(run(&args(&["nope"]))
+            .unwrap_err()
+            .contains("unknown command"));
+        assert!(parse_prevout("1:0:zz:1").is_err());
+        assert!(parse_prevout("1:0:00").is_err());
+        assert!(parse_prevout("1:0:00:1:owner").is_err());
+        let (out, height, role) = parse_prevout("7:0x0101:00:3:htlc-refund").expect("ok");
+        assert_eq!((out.value, out.covenant_type, height), (7, 0x0101, 3));
+        assert_eq!(role, RpstRole::HtlcRefund);
+
+        let dir = temp_dir("badargs");
+        let out = dir.join("x.rpst").display().to_string();
+        let err = run(&args(&["create", &unsigned_tx_hex(), &out])).unwrap_err();
+        assert!(err.contains("expected 1 prevout specs, got 0"), "{err}");
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,502 @@
+//! Raw block files for moving a chain between datadirs and clients.
+//!
+//! `rubin-node exportblocks` writes the canonical chain, genesis first, into
+//! `blocks-NNNNN.dat` files; `rubin-node importblocks` reads every `*.dat`
+//! file of a directory in file-name order and connects the blocks through
+//! the same pre-verify + reorg-aware store path as initial sync.
+//!
+//! A block file is a plain concatenation of records, with no file header:
+//!
+//! ```text
+//! record := chain_id[32] || block_len:u32le || block_bytes[block_len]
+//! ```
+//!
+//! `chain_id` is the 32-byte chain id of the network the block belongs to and
+//! `block_bytes` is the canonical wire encoding (header || tx_count || txs),
+//! exactly as served by `getdata`. Other clients exporting in this container
+//! produce files either client can import.
+//!
+//! Import tolerates blocks that arrive before their parent (files written
+//! out of order, or split differently) by buffering them until the parent
+//! connects, up to a caller-chosen limit. Anything that does not frame as a
+//! whole record, and any record for another chain, aborts the import with an
+//! error naming the file and byte offset.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use rubin_consensus::{block_hash, BLOCK_HEADER_BYTES};
+use serde::Serialize;
+
+use crate::block_preverify::check_block_structure;
+use crate::blockstore::BlockStatus;
+use crate::io_utils::write_file_atomic;
+use crate::op_error::{OpError, OpErrorKind};
+use crate::sync::SyncEngine;
+
+/// Extension of block files read by import and written by export.
+pub const BLOCK_FILE_EXTENSION: &str = "dat";
+/// Blocks per file written by `export_block_files` unless overridden.
+pub const DEFAULT_EXPORT_BLOCKS_PER_FILE: u64 = 1000;
+/// Blocks import may hold while waiting for their parent unless overridden.
+pub const DEFAULT_IMPORT_MAX_BUFFERED_BLOCKS: usize = 1024;
+
+const RECORD_PREFIX_BYTES: usize = 32 + 4;
+
+/// Name of the `index`-th export file.
+pub fn block_file_name(index: u64) -> String {
+    format!("blocks-{index:05}.{BLOCK_FILE_EXTENSION}")
+}
+
+/// Appends one record for `block_bytes` to `out`.
+pub fn encode_block_record(
+    chain_id: [u8; 32],
+    block_bytes: &[u8],
+    out: &mut Vec<u8>,
+) -> Result<(), String> {
+    let len = u32::try_from(block_bytes.len())
+        .map_err(|_| format!("block of {} bytes exceeds record limit", block_bytes.len()))?;
+    out.extend_from_slice(&chain_id);
+    out.extend_from_slice(&len.to_le_bytes());
+    out.extend_from_slice(block_bytes);
+    Ok(())
+}
+
+/// One framed record: the block bytes and the offset its record starts at.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BlockRecord {
+    pub offset: u64,
+    pub block_bytes: Vec<u8>,
+}
+
+/// Splits the contents of block file `name` into records, checking each
+/// record's chain id against `chain_id`.
+pub fn decode_block_file(
+    name: &str,
+    raw: &[u8],
+    chain_id: [u8; 32],
+) -> Result<Vec<BlockRecord>, String> {
+    let mut records = Vec::new();
+    let mut offset = 0usize;
+    while offset < raw.len() {
+        let rest = &raw[offset..];
+        if rest.len() < RECORD_PREFIX_BYTES {
+            return Err(format!(
+                "{name}: offset {offset}: {} unknown trailing bytes",
+                rest.len()
+            ));
+        }
+        if rest[..32] != chain_id {
+            return Err(format!(
+                "{name}: offset {offset}: block for chain_id {}, expected {}",
+                hex::encode(&rest[..32]),
+                hex::encode(chain_id)
+            ));
+        }
+        let mut len_bytes = [0u8; 4];
+        len_bytes.copy_from_slice(&rest[32..RECORD_PREFIX_BYTES]);
+        let len = u32::from_le_bytes(len_bytes) as usize;
+        let body = &rest[RECORD_PREFIX_BYTES..];
+        if len < BLOCK_HEADER_BYTES || len > body.len() {
+            return Err(format!(
+                "{name}: offset {offset}: record declares {len} block bytes, {} available",
+                body.len()
+            ));
+        }
+        records.push(BlockRecord {
+            offset: offset as u64,
+            block_bytes: body[..len].to_vec(),
+        });
+        offset += RECORD_PREFIX_BYTES + len;
+    }
+    Ok(records)
+}
+
+/// Per-file result of an export or import.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct BlockFileStats {
+    pub file: String,
+    pub bytes: u64,
+    pub blocks: u64,
+    /// Blocks connected (or stored as a side branch) by this import,
+    /// including ones that first waited for their parent.
+    pub imported: u64,
+    /// Blocks the store already held.
+    pub already_known: u64,
+    /// Blocks that arrived before their parent and were buffered.
+    pub buffered: u64,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct BlockFilesReport {
+    pub files: Vec<BlockFileStats>,
+    pub blocks: u64,
+    pub tip_height: Option<u64>,
+    pub tip_hash: Option<String>,
+    pub utxo_set_hash: String,
+}
+
+impl BlockFilesReport {
+    fn new(files: Vec<BlockFileStats>, engine: &SyncEngine) -> Result<Self, String> {
+        let tip = engine.tip()?;
+        Ok(Self {
+            blocks: files.iter().map(|file| file.blocks).sum(),
+            files,
+            tip_height: tip.map(|(height, _)| height),
+            tip_hash: tip.map(|(_, hash)| hex::encode(hash)),
+            utxo_set_hash: hex::encode(engine.chain_state.utxo_set_hash()),
+        })
+    }
+}
+
+/// Writes the canonical chain of `engine` into `out_dir`, `blocks_per_file`
+/// blocks per file. Existing files of the same name are replaced.
+pub fn export_block_files(
+    engine: &SyncEngine,
+    out_dir: &Path,
+    blocks_per_file: u64,
+) -> Result<BlockFilesReport, String> {
+    if blocks_per_file == 0 {
+        return Err("blocks per file must be > 0".to_string());
+    }
+    let block_store = engine
+        .block_store
+        .as_ref()
+        .ok_or_else(|| "sync engine missing blockstore".to_string())?;
+    fs::create_dir_all(out_dir)
+        .map_err(|err| format!("create {} failed: {err}", out_dir.display()))?;
+    let chain_id = engine.chain_id();
+    let tip_height = engine.tip()?.map(|(height, _)| height);
+    let mut files = Vec::new();
+    let mut buf = Vec::new();
+    let mut stats = BlockFileStats::default();
+    let mut flush = |buf: &mut Vec<u8>, stats: &mut BlockFileStats| -> Result<(), String> {
+        let name = block_file_name(files.len() as u64);
+        write_file_atomic(&out_dir.join(&name), buf)?;
+        stats.file = name;
+        stats.bytes = buf.len() as u64;
+        files.push(std::mem::take(stats));
+        buf.clear();
+        Ok(())
+    };
+    for height in tip_height.map_or(0..0, |tip| 0..tip + 1) {
+        let hash = block_store
+            .canonical_hash(height)?
+            .ok_or_else(|| format!("canonical block missing at height {height}"))?;
+        let block_bytes = block_store.get_block_by_hash(hash)?;
+        encode_block_record(chain_id, &block_bytes, &mut buf)?;
+        stats.blocks += 1;
+        if stats.blocks == blocks_per_file {
+            flush(&mut buf, &mut stats)?;
+        }
+    }
+    if stats.blocks > 0 {
+        flush(&mut buf, &mut stats)?;
+    }
+    BlockFilesReport::new(files, engine)
+}
+
+/// A block waiting for its parent, and where it came from.
+struct BufferedBlock {
+    file_index: usize,
+    offset: u64,
+    hash: [u8; 32],
+    block_bytes: Vec<u8>,
+}
+
+/// Imports every block file in `in_dir` into `engine`; see the module docs.
+/// At most `max_buffered` blocks may wait for a parent at any time, and none
+/// may still be waiting once the last file is read. The chainstate is saved
+/// once the import ends, so an aborted import keeps what it connected. A
+/// block that fails validation ends the import with
+/// `OpErrorKind::Consensus`.
+pub fn import_block_files(
+    engine: &mut SyncEngine,
+    in_dir: &Path,
+    max_buffered: usize,
+) -> Result<BlockFilesReport, OpError> {
+    if engine.block_store.is_none() {
+        return Err("sync engine missing blockstore".to_string().into());
+    }
+    let paths = list_block_files(in_dir)?;
+    let mut files: Vec<BlockFileStats> = Vec::with_capacity(paths.len());
+    let mut waiting: HashMap<[u8; 32], Vec<BufferedBlock>> = HashMap::new();
+    let mut waiting_count = 0usize;
+    let result = (|| -> Result<(), OpError> {
+        for path in &paths {
+            let name = path
+                .file_name()
+                .map_or_else(String::new, |name| name.to_string_lossy().into_owned());
+            let raw =
+                fs::read(path).map_err(|err| format!("read {} failed: {err}", path.display()))?;
+            let records = decode_block_file(&name, &raw, engine.chain_id())?;
+            let file_index = files.len();
+            files.push(BlockFileStats {
+                file: name,
+                bytes: raw.len() as u64,
+                blocks: records.len() as u64,
+                ..BlockFileStats::default()
+            });
+            for record in records {
+                let header = &record.block_bytes[..BLOCK_HEADER_BYTES];
+                let hash = block_hash(header).map_err(|err| err.to_string())?;
+                if engine.has_block(hash)? {
+                    files[file_index].already_known += 1;
+                    continue;
+                }
+                let mut prev = [0u8; 32];
+                prev.copy_from_slice(&header[4..36]);
+                let block = BufferedBlock {
+                    file_index,
+                    offset: record.offset,
+                    hash,
+                    block_bytes: record.block_bytes,
+                };
+                if !parent_known(engine, prev)? {
+                    if waiting_count == max_buffered {
+                        return Err(format!(
+                            "{}: offset {}: more than {max_buffered} blocks waiting for a parent",
+                            files[file_index].file, block.offset
+                        )
+                        .into());
+                    }
+                    files[file_index].buffered += 1;
+                    waiting_count += 1;
+                    waiting.entry(prev).or_default().push(block);
+                    continue;
+                }
+                let mut ready = vec![block];
+                while let Some(block) = ready.pop() {
+                    let hash = block.hash;
+                    connect_buffered(engine, block, &mut files)?;
+                    if let Some(children) = waiting.remove(&hash) {
+                        waiting_count -= children.len();
+                        ready.extend(children);
+                    }
+                }
+            }
+        }
+        if let Some(block) = waiting
+            .values()
+            .flatten()
+            .min_by_key(|block| (block.file_index, block.offset))
+        {
+            return Err(format!(
+                "{}: offset {}: parent of block {} never arrived ({waiting_count} blocks unconnected)",
+                files[block.file_index].file,
+                block.offset,
+                hex::encode(block.hash)
+            )
+            .into());
+        }
+        Ok(())
+    })();
+    if let Some(path) = engine.cfg.chain_state_path.as_ref() {
+        engine.chain_state.save(path)?;
+    }
+    result?;
+    Ok(BlockFilesReport::new(files, engine)?)
+}
+
+fn list_block_files(dir: &Path) -> Result<Vec<PathBuf>, String> {
+    let entries =
+        fs::read_dir(dir).map_err(|err| format!("read {} failed: {err}", dir.display()))?;
+    let mut paths = Vec::new();
+    for entry in entries {
+        let path = entry
+            .map_err(|err| format!("read {} failed: {err}", dir.display()))?
+            .path();
+        if path.is_file()
+            && path
+                .extension()
+                .is_some_and(|ext| ext == BLOCK_FILE_EXTENSION)
+        {
+            paths.push(path);
+        }
+    }
+    paths.sort();
+    Ok(paths)
+}
+
+/// Whether a block whose parent is `prev` can be connected now: the parent
+/// is stored, or the block is genesis of an empty store.
+fn parent_known(engine: &SyncEngine, prev: [u8; 32]) -> Result<bool, String> {
+    if engine.tip()?.is_none() {
+        return Ok(prev == [0u8; 32]);
+    }
+    engine.has_block(prev)
+}
+
+fn connect_buffered(
+    engine: &mut SyncEngine,
+    block: BufferedBlock,
+    files: &mut [BlockFileStats],
+) -> Result<(), OpError> {
+    let file = &mut files[block.file_index];
+    let at = format!("{}: offset {}", file.file, block.offset);
+    // The hash was taken from the record's own header, so only the
+    // consensus checks can fail here.
+    let prepared = check_block_structure(block.hash, block.block_bytes)
+        .map_err(|err| OpError::new(OpErrorKind::Consensus, format!("{at}: {err}")))?;
+    if let Err(err) = engine.apply_prepared_block_with_reorg(&prepared) {
+        // The engine marks a block that failed validation as invalid; any
+        // other failure leaves its status alone.
+        let status = engine
+            .block_store
+            .as_ref()
+            .and_then(|store| store.get_block_status(block.hash).ok().flatten());
+        let kind = match status {
+            Some(BlockStatus::Invalid { .. }) => OpErrorKind::Consensus,
+            _ => OpErrorKind::Other,
+        };
+        return Err(OpError::new(kind, format!("{at}: {err}")));
+    }
+    file.imported += 1;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::blockstore::{block_store_path, BlockStore};
+    use crate::chainstate::{chain_state_path, ChainState};
+    use crate::genesis::{devnet_genesis_block_bytes, devnet_genesis_chain_id};
+    use crate::miner::{Miner, MinerConfig};
+    use crate::sync::default_sync_config;
+    use crate::test_helpers::mutate_block_body_keeping_header;
+
+    fn store_engine(dir: &Path) -> SyncEngine {
+        let block_store = BlockStore::open(block_store_path(dir)).expect("blockstore");
+        SyncEngine::new(
+            ChainState::new(),
+            Some(block_store),
+            default_sync_config(None, devnet_genesis_chain_id(), Some(chain_state_path(dir))),
+        )
+        .expect("sync")
+    }
+
+    fn test_dir(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("rubin-block-files-{name}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    fn mined_engine(dir: &Path, blocks: usize) -> SyncEngine {
+        let mut engine = store_engine(dir);
+        let cfg = MinerConfig {
+            timestamp_source: || 1,
+            ..MinerConfig::default()
+        };
+        Miner::new(&mut engine, None, cfg)
+            .expect("miner")
+            .mine_n(blocks, &[])
+            .expect("mine");
+        engine
+    }
+
+    #[test]
+    fn export_wipe_import_round_trips_utxo_set_hash() {
+        let dir = test_dir("round-trip");
+        let source = mined_engine(&dir.join("source"), 7);
+        let exported = export_block_files(&source, &dir.join("files"), 3).expect("export");
+        assert_eq!(exported.blocks, 7);
+        assert_eq!(exported.files.len(), 3);
+        let want_hash = source.chain_state.utxo_set_hash();
+        let want_tip = source.tip().expect("tip");
+        drop(source);
+        fs::remove_dir_all(dir.join("source")).expect("wipe");
+
+        // Swap the first two files so blocks 3..5 arrive before their parents.
+        let first = dir.join("files").join(block_file_name(0));
+        let second = dir.join("files").join(block_file_name(1));
+        let parked = dir.join("files").join("parked");
+        fs::rename(&first, &parked).expect("rename");
+        fs::rename(&second, &first).expect("rename");
+        fs::rename(&parked, &second).expect("rename");
+
+        let mut local = store_engine(&dir.join("source"));
+        let imported = import_block_files(&mut local, &dir.join("files"), 3).expect("import");
+        assert_eq!(local.chain_state.utxo_set_hash(), want_hash);
+        assert_eq!(local.tip().expect("tip"), want_tip);
+        assert_eq!(imported.utxo_set_hash, hex::encode(want_hash));
+        assert_eq!(imported.files[0].buffered, 3);
+        assert_eq!(imported.files.iter().map(|f| f.imported).sum::<u64>(), 7);
+
+        // A second pass finds everything already stored.
+        let again = import_block_files(&mut local, &dir.join("files"), 0).expect("reimport");
+        assert_eq!(again.files.iter().map(|f| f.already_known).sum::<u64>(), 7);
+
+        // With too small a buffer the reordered files cannot be imported.
+        let mut tight = store_engine(&dir.join("tight"));
+        let err = import_block_files(&mut tight, &dir.join("files"), 2).unwrap_err();
+        assert_eq!(err.kind, OpErrorKind::Other);
+        assert!(
+            err.message.starts_with("blocks-00000.dat: offset "),
+            "{err}"
+        );
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn import_reports_an_invalid_block_as_a_consensus_failure() {
+        let dir = test_dir("invalid-block");
+        let files = dir.join("files");
+        fs::create_dir_all(&files).expect("mkdir");
+        let chain_id = devnet_genesis_chain_id();
+        let mutated = mutate_block_body_keeping_header(&devnet_genesis_block_bytes());
+        let mut raw = Vec::new();
+        encode_block_record(chain_id, &mutated, &mut raw).expect("encode");
+        fs::write(files.join(block_file_name(0)), raw).expect("write");
+
+        let mut engine = store_engine(&dir.join("node"));
+        let err = import_block_files(&mut engine, &files, 0).unwrap_err();
+        assert_eq!(err.kind, OpErrorKind::Consensus);
+        assert!(err.message.contains("BLOCK_ERR_MERKLE_INVALID"), "{err}");
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn decode_rejects_trailing_bytes_and_foreign_chain_id() {
+        let chain_id = devnet_genesis_chain_id();
+        let block = vec![7u8; BLOCK_HEADER_BYTES + 1];
+        let mut raw = Vec::new();
+        encode_block_record(chain_id, &block, &mut raw).expect("encode");
+        let record_len = raw.len();
+        assert_eq!(
+            decode_block_file("f.dat", &raw, chain_id).expect("decode"),
+            vec![BlockRecord {
+                offset: 0,
+                block_bytes: block.clone()
+            }]
+        );
+
+        let mut trailing = raw.clone();
+        trailing.extend_from_slice(&[0u8; 5]);
+        assert_eq!(
+            decode_block_file("f.dat", &trailing, chain_id).unwrap_err(),
+            format!("f.dat: offset {record_len}: 5 unknown trailing bytes")
+        );
+
+        let mut truncated = raw.clone();
+        encode_block_record(chain_id, &block, &mut truncated).expect("encode");
+        truncated.pop();
+        let err = decode_block_file("f.dat", &truncated, chain_id).unwrap_err();
+        assert!(
+            err.starts_with(&format!("f.dat: offset {record_len}: record declares")),
+            "{err}"
+        );
+
+        let mut foreign = raw.clone();
+        encode_block_record([9u8; 32], &block, &mut foreign).expect("encode");
+        let err = decode_block_file("f.dat", &foreign, chain_id).unwrap_err();
+        assert!(
+            err.starts_with(&format!(
+                "f.dat: offset {record_len}: block for chain_id {}",
+                hex::encode([9u8; 32])
+            )),
+            "{err}"
+        );
+    }
+}
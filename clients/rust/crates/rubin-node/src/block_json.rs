@@ -0,0 +1,458 @@
+//! JSON rendering of stored blocks shared by the `/get_block` RPC and the
+//! offline `--get-block` CLI mode, and of the store's branch tips shared by
+//! the `/get_chain_tips` RPC and `rubin-node getchaintips`.
+//!
+//! Verbosity follows the Bitcoin Core convention:
+//! - 0: raw block hex;
+//! - 1: decoded header, size/weight and the txid list;
+//! - 2: level 1 plus fully decoded transactions with covenant breakdowns and
+//!   per-transaction fees.
+//!
+//! Fees need the spent prevouts. They come from the block's undo record plus
+//! outputs created earlier in the same block; when the undo record is not
+//! available the `fee` fields are omitted rather than guessed. The same
+//! prevouts let level 2 surface the preimage of every CORE_HTLC claim, which
+//! is what a swap counterparty watches confirmed blocks for.
+
+use rubin_consensus::constants::{
+    COV_TYPE_ANCHOR, COV_TYPE_CORE_EXT, COV_TYPE_CORE_SIMPLICITY, COV_TYPE_CORE_STEALTH,
+    COV_TYPE_DA_COMMIT, COV_TYPE_HTLC, COV_TYPE_MULTISIG, COV_TYPE_P2PK, COV_TYPE_VAULT,
+};
+use rubin_consensus::{
+    htlc_revealed_preimages, marshal_tx, parse_block_bytes, parse_htlc_covenant_data,
+    parse_multisig_covenant_data, parse_vault_covenant_data, tx_weight_and_stats_public, Outpoint,
+    OutpointMap, Tx, TxOutput, UtxoEntry,
+};
+use serde::Serialize;
+use serde_json::{json, Value};
+
+use crate::blockstore::{BlockInvalidReason, BlockStore, ChainTipStatus};
+
+pub const MAX_BLOCK_VERBOSITY: u8 = 2;
+
+/// Parses a `verbosity` query/flag value (`0`, `1` or `2`).
+pub fn parse_block_verbosity(raw: &str) -> Result<u8, String> {
+    match raw.trim().parse::<u8>() {
+        Ok(v) if v <= MAX_BLOCK_VERBOSITY => Ok(v),
+        _ => Err("invalid verbosity".to_string()),
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct BlockJson {
+    pub hash: String,
+    /// Canonical height; `None` for a stored side-chain block.
+    pub height: Option<u64>,
+    pub canonical: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub block_hex: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub header: Option<BlockHeaderJson>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub size: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub weight: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tx_count: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub txids: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tx: Option<Vec<TxJson>>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct BlockHeaderJson {
+    pub version: u32,
+    pub prev_block_hash: String,
+    pub merkle_root: String,
+    pub timestamp: u64,
+    pub target: String,
+    pub nonce: u64,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct TxJson {
+    pub txid: String,
+    pub wtxid: String,
+    pub version: u32,
+    pub tx_kind: u8,
+    pub tx_nonce: u64,
+    pub locktime: u32,
+    pub size: u64,
+    pub weight: u64,
+    pub inputs: Vec<TxInputJson>,
+    pub outputs: Vec<TxOutputJson>,
+    /// Absent for the coinbase and whenever a prevout is unknown.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fee: Option<u64>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct TxInputJson {
+    pub prev_txid: String,
+    pub prev_vout: u32,
+    pub sequence: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub prevout: Option<TxOutputJson>,
+    /// Hex preimage revealed by a CORE_HTLC claim of `prevout`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub htlc_preimage: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct TxOutputJson {
+    pub value: u64,
+    pub covenant_type: u16,
+    pub covenant: &'static str,
+    pub covenant_data: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub details: Option<Value>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct ChainTipJson {
+    pub hash: String,
+    pub height: u64,
+    /// Cumulative work through the tip, lowercase hex.
+    pub chainwork: String,
+    pub status: ChainTipStatus,
+    pub branch_len: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub invalid_reason: Option<BlockInvalidReason>,
+}
+
+/// Renders `BlockStore::chain_tips`, highest first.
+pub fn chain_tips_to_json(store: &BlockStore) -> Result<Vec<ChainTipJson>, String> {
+    Ok(store
+        .chain_tips()?
+        .into_iter()
+        .map(|tip| ChainTipJson {
+            hash: hex::encode(tip.hash),
+            height: tip.height,
+            chainwork: tip.chainwork.to_str_radix(16),
+            status: tip.status,
+            branch_len: tip.branch_len,
+            invalid_reason: tip.invalid_reason,
+        })
+        .collect())
+}
+
+/// Renders the stored block `block_hash` at `verbosity` (0..=2).
+pub fn block_to_json(
+    store: &BlockStore,
+    block_hash: [u8; 32],
+    verbosity: u8,
+) -> Result<BlockJson, String> {
+    if verbosity > MAX_BLOCK_VERBOSITY {
+        return Err("invalid verbosity".to_string());
+    }
+    let block_bytes = store.get_block_by_hash(block_hash)?;
+    let height = store.find_canonical_height(block_hash)?;
+    let mut out = BlockJson {
+        hash: hex::encode(block_hash),
+        height,
+        canonical: height.is_some(),
+        block_hex: None,
+        header: None,
+        size: None,
+        weight: None,
+        tx_count: None,
+        txids: None,
+        tx: None,
+    };
+    if verbosity == 0 {
+        out.block_hex = Some(hex::encode(&block_bytes));
+        return Ok(out);
+    }
+
+    let pb = parse_block_bytes(&block_bytes).map_err(|e| e.to_string())?;
+    let mut weight: u64 = 0;
+    let mut tx_weights = Vec::with_capacity(pb.txs.len());
+    for tx in &pb.txs {
+        let (w, _da, _anchor) = tx_weight_and_stats_public(tx).map_err(|e| e.to_string())?;
+        weight = weight.saturating_add(w);
+        tx_weights.push(w);
+    }
+    out.header = Some(BlockHeaderJson {
+        version: pb.header.version,
+        prev_block_hash: hex::encode(pb.header.prev_block_hash),
+        merkle_root: hex::encode(pb.header.merkle_root),
+        timestamp: pb.header.timestamp,
+        target: hex::encode(pb.header.target),
+        nonce: pb.header.nonce,
+    });
+    out.size = Some(block_bytes.len() as u64);
+    out.weight = Some(weight);
+    out.tx_count = Some(pb.tx_count);
+    if verbosity == 1 {
+        out.txids = Some(pb.txids.iter().map(hex::encode).collect());
+        return Ok(out);
+    }
+
+    let mut prevouts = block_prevouts(store, block_hash, &pb.txs, &pb.txids);
+    let mut txs = Vec::with_capacity(pb.txs.len());
+    for (i, tx) in pb.txs.iter().enumerate() {
+        let size = marshal_tx(tx).map_err(|e| e.to_string())?.len() as u64;
+        let mut inputs = Vec::with_capacity(tx.inputs.len());
+        let mut input_sum: Option<u64> = Some(0);
+        let mut spent: Vec<TxOutput> = Vec::with_capacity(tx.inputs.len());
+        for input in &tx.inputs {
+            let prevout = if i == 0 {
+                None
+            } else {
+                prevouts.as_mut().and_then(|known| {
+                    known.remove(&Outpoint {
+                        txid: input.prev_txid,
+                        vout: input.prev_vout,
+                    })
+                })
+            };
+            input_sum = match (&prevout, input_sum) {
+                (Some(entry), Some(sum)) => sum.checked_add(entry.value),
+                _ => None,
+            };
+            if let Some(entry) = &prevout {
+                spent.push(TxOutput {
+                    value: entry.value,
+                    covenant_type: entry.covenant_type,
+                    covenant_data: entry.covenant_data.clone(),
+                });
+            }
+            inputs.push(TxInputJson {
+                prev_txid: hex::encode(input.prev_txid),
+                prev_vout: input.prev_vout,
+                sequence: input.sequence,
+                prevout: prevout.map(|entry| utxo_entry_json(&entry)),
+                htlc_preimage: None,
+            });
+        }
+        // Witness slots can only be lined up once every prevout is known.
+        for (input_index, preimage) in htlc_revealed_preimages(tx, &spent) {
+            inputs[input_index as usize].htlc_preimage = Some(hex::encode(preimage));
+        }
+        let output_sum = tx
+            .outputs
+            .iter()
+            .try_fold(0u64, |sum, out| sum.checked_add(out.value));
+        let fee = match (i, input_sum, output_sum) {
+            (0, _, _) => None,
+            (_, Some(ins), Some(outs)) => ins.checked_sub(outs),
+            _ => None,
+        };
+        txs.push(TxJson {
+            txid: hex::encode(pb.txids[i]),
+            wtxid: hex::encode(pb.wtxids[i]),
+            version: tx.version,
+            tx_kind: tx.tx_kind,
+            tx_nonce: tx.tx_nonce,
+            locktime: tx.locktime,
+            size,
+            weight: tx_weights[i],
+            inputs,
+            outputs: tx.outputs.iter().map(tx_output_json).collect(),
+            fee,
+        });
+    }
+    out.tx = Some(txs);
+    Ok(out)
+}
+
+/// Prevouts spent by the block: undo entries for pre-block UTXOs plus every
+/// output the block itself creates. `None` when the undo record is missing.
+fn block_prevouts(
+    store: &BlockStore,
+    block_hash: [u8; 32],
+    txs: &[Tx],
+    txids: &[[u8; 32]],
+) -> Option<OutpointMap<UtxoEntry>> {
+    let undo = store.get_undo(block_hash).ok()?;
+    let mut known = OutpointMap::default();
+    for tx_undo in undo.txs {
+        for spent in tx_undo.spent {
+            known.insert(spent.outpoint, spent.entry);
+        }
+    }
+    for (tx, txid) in txs.iter().zip(txids) {
+        for (vout, out) in tx.outputs.iter().enumerate() {
+            known.insert(
+                Outpoint {
+                    txid: *txid,
+                    vout: vout as u32,
+                },
+                UtxoEntry {
+                    value: out.value,
+                    covenant_type: out.covenant_type,
+                    covenant_data: out.covenant_data.clone(),
+                    creation_height: 0,
+                    created_by_coinbase: false,
+                },
+            );
+        }
+    }
+    Some(known)
+}
+
+fn utxo_entry_json(entry: &UtxoEntry) -> TxOutputJson {
+    output_json(entry.value, entry.covenant_type, &entry.covenant_data)
+}
+
+fn tx_output_json(out: &TxOutput) -> TxOutputJson {
+    output_json(out.value, out.covenant_type, &out.covenant_data)
+}
+
+fn output_json(value: u64, covenant_type: u16, covenant_data: &[u8]) -> TxOutputJson {
+    TxOutputJson {
+        value,
+        covenant_type,
+        covenant: covenant_name(covenant_type),
+        covenant_data: hex::encode(covenant_data),
+        details: covenant_details(covenant_type, covenant_data),
+    }
+}
+
+fn covenant_name(covenant_type: u16) -> &'static str {
+    match covenant_type {
+        COV_TYPE_P2PK => "p2pk",
+        COV_TYPE_ANCHOR => "anchor",
+        COV_TYPE_HTLC => "htlc",
+        COV_TYPE_VAULT => "vault",
+        COV_TYPE_CORE_EXT => "core_ext",
+        COV_TYPE_DA_COMMIT => "da_commit",
+        COV_TYPE_MULTISIG => "multisig",
+        COV_TYPE_CORE_STEALTH => "stealth",
+        COV_TYPE_CORE_SIMPLICITY => "simplicity",
+        _ => "unknown",
+    }
+}
+
+/// Field-level breakdown for covenants with a fixed layout. Unparseable data
+/// (or a covenant without one) yields no `details` rather than an error.
+fn covenant_details(covenant_type: u16, data: &[u8]) -> Option<Value> {
+    match covenant_type {
+        COV_TYPE_P2PK if !data.is_empty() => Some(json!({
+            "suite_id": data[0],
+            "key_id": hex::encode(&data[1..]),
+        })),
+        COV_TYPE_HTLC => parse_htlc_covenant_data(data).ok().map(|c| {
+            json!({
+                "hash": hex::encode(c.hash),
+                "lock_mode": c.lock_mode,
+                "lock_value": c.lock_value,
+                "claim_key_id": hex::encode(c.claim_key_id),
+                "refund_key_id": hex::encode(c.refund_key_id),
+            })
+        }),
+        COV_TYPE_VAULT => parse_vault_covenant_data(data).ok().map(|c| {
+            json!({
+                "owner_lock_id": hex::encode(c.owner_lock_id),
+                "threshold": c.threshold,
+                "keys": c.keys.iter().map(hex::encode).collect::<Vec<_>>(),
+                "whitelist": c.whitelist.iter().map(hex::encode).collect::<Vec<_>>(),
+            })
+        }),
+        COV_TYPE_MULTISIG => parse_multisig_covenant_data(data).ok().map(|c| {
+            json!({
+                "threshold": c.threshold,
+                "keys": c.keys.iter().map(hex::encode).collect::<Vec<_>>(),
+            })
+        }),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::blockstore::block_store_path;
+    use crate::chainstate::{chain_state_path, ChainState};
+    use crate::io_utils::unique_temp_path;
+    use crate::sync::{default_sync_config, SyncEngine};
+    use crate::test_helpers::{coinbase_only_block, genesis_info};
+    use rubin_consensus::constants::POW_LIMIT;
+
+    fn store_with_two_blocks(suffix: &str) -> (SyncEngine, std::path::PathBuf, Vec<u8>) {
+        let dir = unique_temp_path(suffix);
+        let store = BlockStore::open(block_store_path(&dir)).expect("open blockstore");
+        let cfg = default_sync_config(Some(POW_LIMIT), [0u8; 32], Some(chain_state_path(&dir)));
+        let mut engine = SyncEngine::new(ChainState::new(), Some(store), cfg).expect("sync");
+        let (genesis, genesis_hash, gen_ts) = genesis_info();
+        engine.apply_block(&genesis, None).expect("genesis");
+        let block1 = coinbase_only_block(1, genesis_hash, gen_ts + 1);
+        engine.apply_block(&block1, None).expect("block1");
+        (engine, dir, block1)
+    }
+
+    #[test]
+    fn verbosity_levels_render_stored_block() {
+        let (engine, dir, block1) = store_with_two_blocks("rubin-block-json-levels");
+        let store = engine.block_store.as_ref().expect("store");
+        let (_, hash) = store.tip().unwrap().unwrap();
+        let pb = parse_block_bytes(&block1).unwrap();
+
+        // Level 0: raw hex that reparses to the same block.
+        let level0 = serde_json::to_value(block_to_json(store, hash, 0).unwrap()).unwrap();
+        assert_eq!(level0["height"], 1);
+        assert_eq!(level0["canonical"], true);
+        let raw = hex::decode(level0["block_hex"].as_str().unwrap()).unwrap();
+        assert_eq!(raw, block1);
+        assert_eq!(parse_block_bytes(&raw).unwrap().txids, pb.txids);
+        assert!(level0.get("header").is_none());
+
+        // Level 1: header fields, size/weight and txids, no decoded txs.
+        let level1 = serde_json::to_value(block_to_json(store, hash, 1).unwrap()).unwrap();
+        assert!(level1.get("block_hex").is_none());
+        assert!(level1.get("tx").is_none());
+        assert_eq!(level1["header"]["timestamp"], pb.header.timestamp);
+        assert_eq!(
+            level1["header"]["prev_block_hash"],
+            hex::encode(pb.header.prev_block_hash)
+        );
+        assert_eq!(level1["size"], block1.len() as u64);
+        assert_eq!(level1["tx_count"], 1);
+        assert_eq!(level1["txids"], json!([hex::encode(pb.txids[0])]));
+        assert!(level1["weight"].as_u64().unwrap() > 0);
+
+        // Level 2: decoded coinbase with covenant breakdown and no fee.
+        let level2 = serde_json::to_value(block_to_json(store, hash, 2).unwrap()).unwrap();
+        let coinbase = &level2["tx"][0];
+        assert_eq!(coinbase["txid"], hex::encode(pb.txids[0]));
+        assert_eq!(coinbase["weight"], level1["weight"]);
+        assert!(coinbase.get("fee").is_none());
+        assert!(coinbase["inputs"][0].get("prevout").is_none());
+        let out0 = &coinbase["outputs"][0];
+        assert_eq!(out0["value"], pb.txs[0].outputs[0].value);
+        assert_eq!(out0["covenant"], "p2pk");
+        assert_eq!(
+            out0["details"]["key_id"],
+            hex::encode(&pb.txs[0].outputs[0].covenant_data[1..])
+        );
+
+        std::fs::remove_dir_all(&dir).expect("cleanup");
+    }
+
+    #[test]
+    fn fee_needs_known_prevouts() {
+        let (engine, dir, block1) = store_with_two_blocks("rubin-block-json-fees");
+        let store = engine.block_store.as_ref().expect("store");
+        let pb = parse_block_bytes(&block1).unwrap();
+        let (_, hash) = store.tip().unwrap().unwrap();
+
+        // Prevouts come from undo plus the block's own earlier outputs.
+        let known = block_prevouts(store, hash, &pb.txs, &pb.txids).expect("undo present");
+        let coinbase_out = Outpoint {
+            txid: pb.txids[0],
+            vout: 0,
+        };
+        assert_eq!(known[&coinbase_out].value, pb.txs[0].outputs[0].value);
+
+        // Without an undo record no prevout set is available at all.
+        assert!(block_prevouts(store, [0x42; 32], &pb.txs, &pb.txids).is_none());
+        assert_eq!(
+            parse_block_verbosity("3"),
+            Err("invalid verbosity".to_string())
+        );
+        assert_eq!(parse_block_verbosity(" 2 "), Ok(2));
+
+        std::fs::remove_dir_all(&dir).expect("cleanup");
+    }
+}
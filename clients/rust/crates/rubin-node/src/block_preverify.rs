@@ -0,0 +1,133 @@
+//! Stateless pre-verification of downloaded blocks.
+//!
+//! `check_block_structure` runs the checks that need nothing but the block
+//! bytes: parse, header hash against the requested hash, proof of work
+//! against the claimed target, merkle root, and the block resource limits.
+//! It is safe to run on any thread and ahead of the chain tip, so initial
+//! sync runs it in a worker pool while the serial section connects earlier
+//! blocks. A block that fails here was never going to connect, but the
+//! failure is charged to the peer that served it rather than recorded
+//! against the block hash: a mutated body can carry a valid header.
+
+use rubin_consensus::constants::{
+    MAX_ANCHOR_BYTES_PER_BLOCK, MAX_BLOCK_WEIGHT, MAX_DA_BYTES_PER_BLOCK,
+};
+use rubin_consensus::{
+    block_hash, merkle_root_txids, parse_block_bytes, pow_check, tx_weight_and_stats_public,
+    ErrorCode, ParsedBlock, TxError,
+};
+
+/// A downloaded block that passed `check_block_structure`, with the parse
+/// and txids cached for the serial import section.
+#[derive(Clone, Debug)]
+pub struct PreparedBlock {
+    pub block_hash: [u8; 32],
+    pub block_bytes: Vec<u8>,
+    pub parsed: ParsedBlock,
+}
+
+/// Stateless block checks; see the module docs. Resource totals use the
+/// same weight function as block validation, so a block accepted by
+/// consensus is never rejected here.
+pub fn check_block_structure(
+    expected_hash: [u8; 32],
+    block_bytes: Vec<u8>,
+) -> Result<PreparedBlock, String> {
+    let parsed = parse_block_bytes(&block_bytes).map_err(|e| e.to_string())?;
+    let got = block_hash(&parsed.header_bytes).map_err(|e| e.to_string())?;
+    if got != expected_hash {
+        return Err(format!(
+            "peer served block {} for {}",
+            hex::encode(got),
+            hex::encode(expected_hash)
+        ));
+    }
+    check_parsed_block_structure(&parsed)
+        .map_err(|e| format!("block {}: {e}", hex::encode(got)))?;
+    Ok(PreparedBlock {
+        block_hash: got,
+        block_bytes,
+        parsed,
+    })
+}
+
+fn check_parsed_block_structure(parsed: &ParsedBlock) -> Result<(), TxError> {
+    pow_check(&parsed.header_bytes, parsed.header.target)?;
+    if merkle_root_txids(&parsed.txids)? != parsed.header.merkle_root {
+        return Err(TxError::new(
+            ErrorCode::BlockErrMerkleInvalid,
+            "merkle_root mismatch",
+        ));
+    }
+    let (mut weight, mut da_bytes, mut anchor_bytes) = (0u64, 0u64, 0u64);
+    for tx in &parsed.txs {
+        let (w, da, anchor) = tx_weight_and_stats_public(tx)?;
+        weight = weight.saturating_add(w);
+        da_bytes = da_bytes.saturating_add(da);
+        anchor_bytes = anchor_bytes.saturating_add(anchor);
+    }
+    if weight > MAX_BLOCK_WEIGHT {
+        return Err(TxError::new(
+            ErrorCode::BlockErrWeightExceeded,
+            "block weight exceeded",
+        ));
+    }
+    if da_bytes > MAX_DA_BYTES_PER_BLOCK {
+        return Err(TxError::new(
+            ErrorCode::BlockErrWeightExceeded,
+            "DA bytes exceeded",
+        ));
+    }
+    if anchor_bytes > MAX_ANCHOR_BYTES_PER_BLOCK {
+        return Err(TxError::new(
+            ErrorCode::BlockErrAnchorBytesExceeded,
+            "anchor bytes exceeded",
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use rubin_consensus::BLOCK_HEADER_BYTES;
+
+    use super::*;
+    use crate::genesis::devnet_genesis_block_bytes;
+    use crate::test_helpers::mutate_block_body_keeping_header;
+
+    fn genesis() -> ([u8; 32], Vec<u8>) {
+        let bytes = devnet_genesis_block_bytes();
+        let hash = block_hash(&bytes[..BLOCK_HEADER_BYTES]).expect("hash");
+        (hash, bytes)
+    }
+
+    #[test]
+    fn check_block_structure_caches_parse() {
+        let (hash, bytes) = genesis();
+        let prepared = check_block_structure(hash, bytes.clone()).expect("genesis");
+        assert_eq!(prepared.block_hash, hash);
+        assert_eq!(prepared.block_bytes, bytes);
+        assert_eq!(prepared.parsed.txids.len(), prepared.parsed.txs.len());
+        assert!(!prepared.parsed.txs.is_empty());
+    }
+
+    #[test]
+    fn check_block_structure_rejects_wrong_hash_and_mutated_body() {
+        let (hash, bytes) = genesis();
+        let err = check_block_structure([0x11; 32], bytes.clone()).unwrap_err();
+        assert!(err.starts_with("peer served block"), "{err}");
+
+        let mutated = mutate_block_body_keeping_header(&bytes);
+        let err = check_block_structure(hash, mutated).unwrap_err();
+        assert_eq!(
+            err,
+            format!(
+                "block {}: BLOCK_ERR_MERKLE_INVALID: merkle_root mismatch",
+                hex::encode(hash)
+            )
+        );
+
+        let err = check_block_structure(hash, vec![0u8; 8]).unwrap_err();
+        assert!(err.contains("PARSE"), "{err}");
+    }
+}
@@ -0,0 +1,51 @@
+//! Block submission for miners: the `/submit_block` RPC and the node's own
+//! mining loop both hand finished blocks to `submit_block`, so a locally
+//! mined block passes exactly the checks an external miner's block does.
+
+use std::sync::Mutex;
+
+use rubin_consensus::{block_hash, parse_block_bytes};
+
+use crate::block_preverify::check_block_structure;
+use crate::sync::SyncEngine;
+use crate::txpool::TxPool;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SubmittedBlock {
+    pub hash: [u8; 32],
+    pub height: u64,
+    /// Whether the block joined the canonical chain, directly or through a
+    /// reorg; false when it was stored as a side-branch block.
+    pub canonical: bool,
+}
+
+/// Runs `block_bytes` through the stateless checks and the import pipeline,
+/// then removes what it confirmed or conflicts with from the pool. Locks
+/// the engine before the pool, like every other caller holding both.
+pub fn submit_block(
+    sync_engine: &Mutex<SyncEngine>,
+    tx_pool: &Mutex<TxPool>,
+    block_bytes: &[u8],
+) -> Result<SubmittedBlock, String> {
+    let parsed = parse_block_bytes(block_bytes).map_err(|e| e.to_string())?;
+    let hash = block_hash(&parsed.header_bytes).map_err(|e| e.to_string())?;
+    let prepared = check_block_structure(hash, block_bytes.to_vec())?;
+    let mut engine = sync_engine
+        .lock()
+        .map_err(|_| "sync engine unavailable".to_string())?;
+    let outcome = engine.apply_prepared_block_with_reorg(&prepared)?;
+    let mut pool = tx_pool
+        .lock()
+        .map_err(|_| "tx pool unavailable".to_string())?;
+    outcome.tx_pool_cleanup.apply(
+        &mut pool,
+        &engine.chain_state,
+        engine.block_store.as_ref(),
+        engine.chain_id(),
+    );
+    Ok(SubmittedBlock {
+        hash,
+        height: outcome.summary.block_height,
+        canonical: !outcome.summary.canonical_applied_blocks.is_empty(),
+    })
+}
@@ -2,19 +2,33 @@ use std::fs;
 use std::path::{Path, PathBuf};
 
 use num_bigint::BigUint;
+use std::collections::BTreeMap;
+
+use rubin_consensus::constants::SIGNAL_WINDOW;
 use rubin_consensus::{
-    block_hash, chain_work_from_targets, parse_block_header_bytes, BLOCK_HEADER_BYTES,
+    block_expected_target_with_params, block_hash, chain_work_from_targets,
+    parse_block_header_bytes, BlockHeader, ChainParams, FeatureBitDeployment, BLOCK_HEADER_BYTES,
 };
 use serde::{Deserialize, Serialize};
 
+use crate::chainstate::BlockStats;
 use crate::io_utils::{
     parse_hex32, read_file_from_dir, write_file_atomic, write_file_exclusive, AtomicWriteError,
 };
+use crate::store_keys::{BlockKey, HeaderKey, StatsKey, StatusKey, UndoKey};
 use crate::undo::{marshal_block_undo, unmarshal_block_undo, BlockUndo};
 use std::ffi::OsStr;
 
 pub const BLOCK_STORE_DIR_NAME: &str = "blockstore";
-const BLOCK_STORE_INDEX_VERSION: u32 = 1;
+/// Version 2 added the chain anchors; version 1 indexes are migrated on
+/// open (see `load_blockstore_index`).
+const BLOCK_STORE_INDEX_VERSION: u32 = 2;
+/// Version of the per-block index entry records under `status/`. Records
+/// written before versioning are a bare `BlockStatus` and are migrated on
+/// read (see `decode_block_index_entry`).
+pub const BLOCK_INDEX_ENTRY_VERSION: u32 = 1;
+/// Median-time-past window (BIP-113 style): the last 11 timestamps.
+pub(crate) const MTP_WINDOW_BLOCKS: u64 = 11;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct BlockStore {
@@ -23,6 +37,8 @@ pub struct BlockStore {
     blocks_dir: PathBuf,
     headers_dir: PathBuf,
     undo_dir: PathBuf,
+    stats_dir: PathBuf,
+    status_dir: PathBuf,
     index: BlockStoreIndexDisk,
     /// E.7: O(1) canonical-height -> hash cache, mirror of Go's eager
     /// `buildCanonicalHeightIndex` precompute (see `clients/go/node/blockstore.go`
@@ -36,6 +52,10 @@ pub struct BlockStore {
     /// in the per-block `commit_canonical_block` no-op probe pay no
     /// hex-parse tax.
     canonical_hash_by_height: Vec<[u8; 32]>,
+    /// Per-bit version signal counts of complete canonical `SIGNAL_WINDOW`
+    /// windows, keyed by window index. Filled on first count and dropped
+    /// from the first window a canonical rewrite touches; never persisted.
+    signal_window_counts: BTreeMap<u64, [u32; 32]>,
     /// Test-only: force `truncate_canonical` to return an error.
     #[cfg(test)]
     pub(crate) force_truncate_error: bool,
@@ -48,10 +68,289 @@ pub struct BlockStore {
     pub(crate) force_undo_error: bool,
 }
 
+/// Per-block status recorded alongside the block files. Absent for the
+/// common case of a block that is simply canonical or a plain side branch.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum BlockStatus {
+    /// Fork choice preferred this side-branch tip, but its fork point was
+    /// deeper than `max_auto_reorg_depth`; the switch waits for an operator
+    /// `--force-reorg`.
+    NeedsManualReorg { fork_depth: u64 },
+    /// The block failed consensus validation on import.
+    Invalid { reason: BlockInvalidReason },
+}
+
+/// Why a block was rejected, as reported by the import pipeline.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlockInvalidReason {
+    /// Canonical `BLOCK_ERR_*` / `TX_ERR_*` code.
+    pub code: String,
+    /// Offending transaction, when the failing check names one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub txid_hex: Option<String>,
+    /// Full error text of the failing stage.
+    pub detail: String,
+}
+
+impl BlockInvalidReason {
+    /// Parses a pipeline error of the form `<CODE>: <detail>`. Returns
+    /// `None` for errors that carry no consensus code (I/O, missing parent,
+    /// and the like), which say nothing about the block's validity.
+    pub fn from_error(err: &str) -> Option<Self> {
+        let code = err.split_once(':').map_or(err, |(code, _)| code).trim();
+        if !(code.starts_with("BLOCK_ERR_") || code.starts_with("TX_ERR_")) {
+            return None;
+        }
+        Some(Self {
+            code: code.to_string(),
+            txid_hex: None,
+            detail: err.to_string(),
+        })
+    }
+}
+
+/// Everything the store records about a block beyond its bytes.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlockIndexEntry {
+    pub status: Option<BlockStatus>,
+    /// Unix seconds when the block was first received; 0 when unknown
+    /// (entries migrated from before this field existed).
+    pub received_unix: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct BlockIndexEntryDisk {
+    version: u32,
+    #[serde(flatten)]
+    entry: BlockIndexEntry,
+}
+
+fn decode_block_index_entry(raw: &[u8]) -> Result<BlockIndexEntry, String> {
+    let value: serde_json::Value =
+        serde_json::from_slice(raw).map_err(|e| format!("decode block index entry: {e}"))?;
+    match value.get("version").and_then(serde_json::Value::as_u64) {
+        Some(v) if v == u64::from(BLOCK_INDEX_ENTRY_VERSION) => {
+            let disk: BlockIndexEntryDisk = serde_json::from_value(value)
+                .map_err(|e| format!("decode block index entry: {e}"))?;
+            Ok(disk.entry)
+        }
+        Some(v) => Err(format!("unsupported block index entry version: {v}")),
+        // Pre-versioning record: a bare status, receive time unknown.
+        None => {
+            let status: BlockStatus =
+                serde_json::from_value(value).map_err(|e| format!("decode block status: {e}"))?;
+            Ok(BlockIndexEntry {
+                status: Some(status),
+                received_unix: 0,
+            })
+        }
+    }
+}
+
+/// How a branch tip relates to the canonical chain, as reported by
+/// `BlockStore::chain_tips`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChainTipStatus {
+    /// The canonical tip.
+    Active,
+    /// A fully stored side branch with no block marked invalid.
+    ValidFork,
+    /// A side branch containing a block marked `BlockStatus::Invalid`.
+    Invalid,
+    /// A side branch with a header whose block bytes are not stored.
+    HeadersOnly,
+}
+
+/// A stored block no other stored block builds on.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChainTip {
+    pub hash: [u8; 32],
+    pub height: u64,
+    pub chainwork: BigUint,
+    pub status: ChainTipStatus,
+    /// Blocks between the tip and the canonical chain; 0 for the active tip.
+    pub branch_len: u64,
+    /// Why the branch is invalid, from the invalid block nearest the tip.
+    pub invalid_reason: Option<BlockInvalidReason>,
+}
+
+/// Ancestor window a block is validated against, built by
+/// `BlockStore::validation_context`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlockValidationContext {
+    pub height: u64,
+    /// Ancestor headers, oldest first, parent last; empty at genesis. Covers
+    /// the MTP window, or the full retarget window at a `WINDOW_SIZE`
+    /// boundary.
+    pub ancestors: Vec<BlockHeader>,
+    /// Local clock for the future-drift check; `None` skips it.
+    pub local_time: Option<u64>,
+}
+
+impl BlockValidationContext {
+    /// Number of ancestors a block at `new_height` is validated against:
+    /// the MTP window, or the full retarget window at a `WINDOW_SIZE`
+    /// boundary, capped at `new_height`.
+    pub fn ancestor_count(new_height: u64) -> u64 {
+        Self::ancestor_count_with_params(new_height, &ChainParams::V1)
+    }
+
+    /// `ancestor_count` for a chain retargeting every `params.window_size`
+    /// blocks.
+    pub fn ancestor_count_with_params(new_height: u64, params: &ChainParams) -> u64 {
+        let window_size = params.window_size;
+        let window = if new_height > 0 && new_height.is_multiple_of(window_size) {
+            window_size.max(MTP_WINDOW_BLOCKS)
+        } else {
+            MTP_WINDOW_BLOCKS
+        };
+        new_height.min(window)
+    }
+
+    /// `prev_timestamps` in the order `median_time_past` reads them (parent
+    /// first, at most 11); `None` at genesis.
+    pub fn prev_timestamps(&self) -> Option<Vec<u64>> {
+        if self.height == 0 {
+            return None;
+        }
+        Some(
+            self.ancestors
+                .iter()
+                .rev()
+                .take(MTP_WINDOW_BLOCKS as usize)
+                .map(|header| header.timestamp)
+                .collect(),
+        )
+    }
+
+    /// Consensus target for the block at `height`, retargeting at window
+    /// boundaries.
+    pub fn expected_target(&self) -> Result<[u8; 32], String> {
+        self.expected_target_with_params(&ChainParams::V1)
+    }
+
+    /// `expected_target` under the chain instance's retarget parameters.
+    pub fn expected_target_with_params(&self, params: &ChainParams) -> Result<[u8; 32], String> {
+        block_expected_target_with_params(&self.ancestors, self.height, params)
+            .map_err(|e| e.to_string())
+    }
+}
+
+/// A block named by the store's chain anchors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChainAnchor {
+    pub height: u64,
+    pub hash: [u8; 32],
+}
+
+/// The store's record of where the chain stands, written in the same index
+/// update as the canonical change it describes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChainAnchors {
+    /// Best validated header. Ahead of `best_block` while headers-first
+    /// sync holds headers whose blocks are not connected yet.
+    pub best_header: ChainAnchor,
+    /// The canonical tip.
+    pub best_block: ChainAnchor,
+    /// `utxo_set_hash` of the chain state at `utxo_block`, the last block
+    /// whose connect persisted a chainstate snapshot. Equal to
+    /// `best_block` unless the snapshot cadence skipped later blocks.
+    pub utxo: Option<(ChainAnchor, [u8; 32])>,
+}
+
+impl ChainAnchors {
+    /// Anchors after the canonical tip moved to `best_block`: the best
+    /// header never falls behind the tip, and a UTXO hash survives only
+    /// while its block is still canonical.
+    fn advance(
+        prev: Option<&Self>,
+        best_block: ChainAnchor,
+        is_canonical: impl Fn(&ChainAnchor) -> bool,
+        utxo_set_hash: Option<[u8; 32]>,
+    ) -> Self {
+        let best_header = match prev {
+            Some(prev) if prev.best_header.height > best_block.height => prev.best_header,
+            _ => best_block,
+        };
+        let utxo = match utxo_set_hash {
+            Some(hash) => Some((best_block, hash)),
+            None => prev
+                .and_then(|prev| prev.utxo)
+                .filter(|(block, _)| block.height <= best_block.height && is_canonical(block)),
+        };
+        Self {
+            best_header,
+            best_block,
+            utxo,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct ChainAnchorsDisk {
+    best_header_height: u64,
+    best_header_hash: String,
+    best_block_height: u64,
+    best_block_hash: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    utxo_block_height: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    utxo_block_hash: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    utxo_set_hash: Option<String>,
+}
+
+impl ChainAnchorsDisk {
+    fn from_anchors(anchors: &ChainAnchors) -> Self {
+        Self {
+            best_header_height: anchors.best_header.height,
+            best_header_hash: hex::encode(anchors.best_header.hash),
+            best_block_height: anchors.best_block.height,
+            best_block_hash: hex::encode(anchors.best_block.hash),
+            utxo_block_height: anchors.utxo.map(|(block, _)| block.height),
+            utxo_block_hash: anchors.utxo.map(|(block, _)| hex::encode(block.hash)),
+            utxo_set_hash: anchors.utxo.map(|(_, hash)| hex::encode(hash)),
+        }
+    }
+
+    fn to_anchors(&self) -> Result<ChainAnchors, String> {
+        let utxo = match (
+            self.utxo_block_height,
+            &self.utxo_block_hash,
+            &self.utxo_set_hash,
+        ) {
+            (Some(height), Some(block_hash), Some(utxo_set_hash)) => Some((
+                ChainAnchor {
+                    height,
+                    hash: parse_hex32("anchors.utxo_block_hash", block_hash)?,
+                },
+                parse_hex32("anchors.utxo_set_hash", utxo_set_hash)?,
+            )),
+            (None, None, None) => None,
+            _ => return Err("blockstore index anchors: partial utxo anchor".to_string()),
+        };
+        Ok(ChainAnchors {
+            best_header: ChainAnchor {
+                height: self.best_header_height,
+                hash: parse_hex32("anchors.best_header_hash", &self.best_header_hash)?,
+            },
+            best_block: ChainAnchor {
+                height: self.best_block_height,
+                hash: parse_hex32("anchors.best_block_hash", &self.best_block_hash)?,
+            },
+            utxo,
+        })
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 struct BlockStoreIndexDisk {
     version: u32,
     canonical: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    anchors: Option<ChainAnchorsDisk>,
 }
 
 impl BlockStore {
@@ -65,6 +364,8 @@ impl BlockStore {
         let blocks_dir = root_path.join("blocks");
         let headers_dir = root_path.join("headers");
         let undo_dir = root_path.join("undo");
+        let stats_dir = root_path.join("stats");
+        let status_dir = root_path.join("status");
 
         fs::create_dir_all(&blocks_dir)
             .map_err(|e| format!("create blockstore blocks {}: {e}", blocks_dir.display()))?;
@@ -72,17 +373,38 @@ impl BlockStore {
             .map_err(|e| format!("create blockstore headers {}: {e}", headers_dir.display()))?;
         fs::create_dir_all(&undo_dir)
             .map_err(|e| format!("create blockstore undo {}: {e}", undo_dir.display()))?;
+        fs::create_dir_all(&stats_dir)
+            .map_err(|e| format!("create blockstore stats {}: {e}", stats_dir.display()))?;
+        fs::create_dir_all(&status_dir)
+            .map_err(|e| format!("create blockstore status {}: {e}", status_dir.display()))?;
 
-        let index = load_blockstore_index(&index_path)?;
+        let mut index = load_blockstore_index(&index_path)?;
         let canonical_hash_by_height = build_canonical_hash_cache(&index.canonical)?;
+        if index.anchors.is_none() {
+            // Migrated version-1 index: anchor the existing tip; the next
+            // index write persists it.
+            index.anchors = canonical_hash_by_height.last().map(|hash| {
+                let tip = ChainAnchor {
+                    height: canonical_hash_by_height.len() as u64 - 1,
+                    hash: *hash,
+                };
+                ChainAnchorsDisk::from_anchors(&ChainAnchors::advance(None, tip, |_| false, None))
+            });
+        }
+        if let Some(anchors) = &index.anchors {
+            anchors.to_anchors()?;
+        }
         Ok(Self {
             root_path,
             index_path,
             blocks_dir,
             headers_dir,
             undo_dir,
+            stats_dir,
+            status_dir,
             index,
             canonical_hash_by_height,
+            signal_window_counts: BTreeMap::new(),
             #[cfg(test)]
             force_truncate_error: false,
             #[cfg(test)]
@@ -145,6 +467,47 @@ impl BlockStore {
         header_bytes: &[u8],
         block_bytes: &[u8],
         undo: &BlockUndo,
+    ) -> Result<(), String> {
+        self.commit_canonical_block_anchored(
+            height,
+            block_hash_bytes,
+            header_bytes,
+            block_bytes,
+            undo,
+            None,
+        )
+    }
+
+    /// `commit_canonical_block` that records the connected chain state's
+    /// `utxo_set_hash` in the chain anchors, in the same index write that
+    /// advances the tip.
+    pub fn commit_canonical_block_with_utxo_set_hash(
+        &mut self,
+        height: u64,
+        block_hash_bytes: [u8; 32],
+        header_bytes: &[u8],
+        block_bytes: &[u8],
+        undo: &BlockUndo,
+        utxo_set_hash: [u8; 32],
+    ) -> Result<(), String> {
+        self.commit_canonical_block_anchored(
+            height,
+            block_hash_bytes,
+            header_bytes,
+            block_bytes,
+            undo,
+            Some(utxo_set_hash),
+        )
+    }
+
+    fn commit_canonical_block_anchored(
+        &mut self,
+        height: u64,
+        block_hash_bytes: [u8; 32],
+        header_bytes: &[u8],
+        block_bytes: &[u8],
+        undo: &BlockUndo,
+        utxo_set_hash: Option<[u8; 32]>,
     ) -> Result<(), String> {
         // 0. Reject mismatched undo up front. If `undo.block_height` does
         //    not match the canonical height being committed, a later
@@ -237,7 +600,7 @@ impl BlockStore {
         //    `CommitCanonicalBlock` (StoreBlock → PutUndo → SetCanonicalTip).
         self.put_undo(block_hash_bytes, undo)?;
         // 3. Advance canonical tip LAST — this is the atomic commit point.
-        self.set_canonical_tip(height, block_hash_bytes)
+        self.set_canonical_tip_with_utxo_set_hash(height, block_hash_bytes, utxo_set_hash)
     }
 
     /// Cheap header consistency check — length + computed hash equals
@@ -273,13 +636,14 @@ impl BlockStore {
         block_bytes: &[u8],
     ) -> Result<(), String> {
         self.validate_header_matches_hash(header_bytes, block_hash_bytes)?;
-        let hash_hex = hex::encode(block_hash_bytes);
         write_file_if_absent(
-            &self.blocks_dir.join(format!("{hash_hex}.bin")),
+            &self.blocks_dir.join(BlockKey(block_hash_bytes).file_name()),
             block_bytes,
         )?;
         write_file_if_absent(
-            &self.headers_dir.join(format!("{hash_hex}.bin")),
+            &self
+                .headers_dir
+                .join(HeaderKey(block_hash_bytes).file_name()),
             header_bytes,
         )
     }
@@ -295,6 +659,17 @@ impl BlockStore {
         &mut self,
         height: u64,
         block_hash_bytes: [u8; 32],
+    ) -> Result<(), String> {
+        self.set_canonical_tip_with_utxo_set_hash(height, block_hash_bytes, None)
+    }
+
+    /// `set_canonical_tip` that also anchors the chain state's
+    /// `utxo_set_hash` at the new tip in the same index write.
+    fn set_canonical_tip_with_utxo_set_hash(
+        &mut self,
+        height: u64,
+        block_hash_bytes: [u8; 32],
+        utxo_set_hash: Option<[u8; 32]>,
     ) -> Result<(), String> {
         let hash_hex = hex::encode(block_hash_bytes);
         let current_len = self.index.canonical.len() as u64;
@@ -315,8 +690,15 @@ impl BlockStore {
             self.canonical_hash_by_height.truncate(height as usize);
             self.index.canonical.push(hash_hex);
             self.canonical_hash_by_height.push(block_hash_bytes);
+            self.drop_signal_windows_from(height);
         }
-        if let Err(e) = save_blockstore_index(&self.index_path, &self.index) {
+        let saved = self
+            .next_anchors(&self.canonical_hash_by_height, utxo_set_hash)
+            .and_then(|anchors| {
+                self.index.anchors = anchors;
+                save_blockstore_index(&self.index_path, &self.index)
+            });
+        if let Err(e) = saved {
             self.reload_index_from_disk();
             return Err(e);
         }
@@ -337,13 +719,72 @@ impl BlockStore {
         }
         self.index.canonical.truncate(height as usize + 1);
         self.canonical_hash_by_height.truncate(height as usize + 1);
-        if let Err(e) = save_blockstore_index(&self.index_path, &self.index) {
+        self.drop_signal_windows_from(height + 1);
+        let saved = self
+            .next_anchors(&self.canonical_hash_by_height, None)
+            .and_then(|anchors| {
+                self.index.anchors = anchors;
+                save_blockstore_index(&self.index_path, &self.index)
+            });
+        if let Err(e) = saved {
             self.reload_index_from_disk();
             return Err(e);
         }
         Ok(())
     }
 
+    /// The chain anchors, or `None` for an empty store.
+    pub fn chain_anchors(&self) -> Result<Option<ChainAnchors>, String> {
+        self.index
+            .anchors
+            .as_ref()
+            .map(ChainAnchorsDisk::to_anchors)
+            .transpose()
+    }
+
+    /// Records a validated header beyond the connected chain as the best
+    /// header (headers-first sync). Heights at or below the current best
+    /// header are ignored; the store must already have a canonical tip.
+    pub fn record_best_header(&mut self, height: u64, hash: [u8; 32]) -> Result<(), String> {
+        let Some(mut anchors) = self.chain_anchors()? else {
+            return Err("record best header: blockstore has no canonical tip".to_string());
+        };
+        if height <= anchors.best_header.height {
+            return Ok(());
+        }
+        anchors.best_header = ChainAnchor { height, hash };
+        let prev = self
+            .index
+            .anchors
+            .replace(ChainAnchorsDisk::from_anchors(&anchors));
+        if let Err(e) = save_blockstore_index(&self.index_path, &self.index) {
+            self.index.anchors = prev;
+            return Err(e);
+        }
+        Ok(())
+    }
+
+    /// Anchors for the canonical chain `canonical` after a tip change, or
+    /// `None` when it is empty.
+    fn next_anchors(
+        &self,
+        canonical: &[[u8; 32]],
+        utxo_set_hash: Option<[u8; 32]>,
+    ) -> Result<Option<ChainAnchorsDisk>, String> {
+        let Some(tip_hash) = canonical.last() else {
+            return Ok(None);
+        };
+        let prev = self.chain_anchors()?;
+        let tip = ChainAnchor {
+            height: canonical.len() as u64 - 1,
+            hash: *tip_hash,
+        };
+        let is_canonical =
+            |block: &ChainAnchor| canonical.get(block.height as usize) == Some(&block.hash);
+        let anchors = ChainAnchors::advance(prev.as_ref(), tip, is_canonical, utxo_set_hash);
+        Ok(Some(ChainAnchorsDisk::from_anchors(&anchors)))
+    }
+
     /// E.7: O(1) hot lookup served from `canonical_hash_by_height`
     /// (Go parity: `clients/go/node/blockstore.go` `CanonicalHash` reads
     /// the in-memory canonical slice that was decoded once at open).
@@ -372,14 +813,14 @@ impl BlockStore {
         // `<hex>.bin` cannot in practice contain a separator, but the
         // guard removes the entire class of "leaf name from on-disk
         // index drift becomes a traversal" without runtime cost.
-        let name = format!("{}.bin", hex::encode(block_hash_bytes));
+        let name = BlockKey(block_hash_bytes).file_name();
         read_file_from_dir(&self.blocks_dir, &name)
             .map_err(|e| format!("read block {}: {e}", self.blocks_dir.join(&name).display()))
     }
 
     pub fn get_header_by_hash(&self, block_hash_bytes: [u8; 32]) -> Result<Vec<u8>, String> {
         // E.10: see `get_block_by_hash` doc.
-        let name = format!("{}.bin", hex::encode(block_hash_bytes));
+        let name = HeaderKey(block_hash_bytes).file_name();
         read_file_from_dir(&self.headers_dir, &name).map_err(|e| {
             format!(
                 "read header {}: {e}",
@@ -390,7 +831,7 @@ impl BlockStore {
 
     pub fn has_block(&self, block_hash_bytes: [u8; 32]) -> bool {
         self.headers_dir
-            .join(format!("{}.bin", hex::encode(block_hash_bytes)))
+            .join(HeaderKey(block_hash_bytes).file_name())
             .exists()
     }
 
@@ -407,7 +848,7 @@ impl BlockStore {
         try_has_file_at(
             &self
                 .headers_dir
-                .join(format!("{}.bin", hex::encode(block_hash_bytes))),
+                .join(HeaderKey(block_hash_bytes).file_name()),
         )
     }
 
@@ -415,11 +856,7 @@ impl BlockStore {
     /// semantics as `try_has_block`: only `NotFound` returns
     /// `Ok(false)`, every other metadata failure surfaces as `Err`.
     pub fn try_has_block_data(&self, block_hash_bytes: [u8; 32]) -> Result<bool, String> {
-        try_has_file_at(
-            &self
-                .blocks_dir
-                .join(format!("{}.bin", hex::encode(block_hash_bytes))),
-        )
+        try_has_file_at(&self.blocks_dir.join(BlockKey(block_hash_bytes).file_name()))
     }
 
     /// Fallible undo-file presence probe. Same semantics as
@@ -427,11 +864,7 @@ impl BlockStore {
     /// incomplete_canonical_suffix` and any other path that must
     /// distinguish "missing" from "present but unreadable".
     pub fn try_has_undo(&self, block_hash_bytes: [u8; 32]) -> Result<bool, String> {
-        try_has_file_at(
-            &self
-                .undo_dir
-                .join(format!("{}.json", hex::encode(block_hash_bytes))),
-        )
+        try_has_file_at(&self.undo_dir.join(UndoKey(block_hash_bytes).file_name()))
     }
 
     pub fn find_canonical_height(&self, block_hash_bytes: [u8; 32]) -> Result<Option<u64>, String> {
@@ -522,6 +955,116 @@ impl BlockStore {
         self.persist_block_bytes(block_hash_bytes, header_bytes, block_bytes)
     }
 
+    // ----- Validation context -----
+
+    /// Validation context for a block at `new_height` whose parent is
+    /// `tip_hash`: the last `max(11, retarget window)` ancestor headers
+    /// (capped at `new_height`), walked through parent links so a side
+    /// branch resolves the same way as the canonical chain.
+    pub fn validation_context(
+        &self,
+        tip_hash: [u8; 32],
+        new_height: u64,
+        local_time: Option<u64>,
+    ) -> Result<BlockValidationContext, String> {
+        self.validation_context_with_params(tip_hash, new_height, local_time, &ChainParams::V1)
+    }
+
+    /// `validation_context` sized for the retarget window in `params`.
+    pub fn validation_context_with_params(
+        &self,
+        tip_hash: [u8; 32],
+        new_height: u64,
+        local_time: Option<u64>,
+        params: &ChainParams,
+    ) -> Result<BlockValidationContext, String> {
+        Ok(BlockValidationContext {
+            height: new_height,
+            ancestors: self.ancestor_headers(
+                tip_hash,
+                BlockValidationContext::ancestor_count_with_params(new_height, params),
+            )?,
+            local_time,
+        })
+    }
+
+    /// The `count` headers ending at `tip_hash`, oldest first.
+    pub fn ancestor_headers(
+        &self,
+        tip_hash: [u8; 32],
+        count: u64,
+    ) -> Result<Vec<BlockHeader>, String> {
+        let mut out = Vec::with_capacity(count as usize);
+        let mut current = tip_hash;
+        while (out.len() as u64) < count {
+            if current == [0u8; 32] {
+                return Err(format!(
+                    "missing ancestor header {} of {count} below {}",
+                    out.len(),
+                    hex::encode(tip_hash)
+                ));
+            }
+            let header_bytes = self.get_header_by_hash(current)?;
+            let header = parse_block_header_bytes(&header_bytes).map_err(|e| e.to_string())?;
+            current = header.prev_block_hash;
+            out.push(header);
+        }
+        out.reverse();
+        Ok(out)
+    }
+
+    // ----- Featurebit signalling -----
+
+    /// Signal counts for `deployment.bit` per complete `SIGNAL_WINDOW`
+    /// window below the boundary of `up_to_height`, in the shape
+    /// `featurebit_state_at_height_from_window_counts` takes. Windows that
+    /// end before the deployment's first start boundary are reported as 0
+    /// without being read, matching the Go node's telemetry scan.
+    pub fn window_signal_counts(
+        &mut self,
+        deployment: &FeatureBitDeployment,
+        up_to_height: u64,
+    ) -> Result<Vec<u32>, String> {
+        if deployment.bit > 31 {
+            return Err(format!("featurebits: bit out of range: {}", deployment.bit));
+        }
+        let windows = up_to_height / SIGNAL_WINDOW;
+        let first_window = deployment.start_height.div_ceil(SIGNAL_WINDOW);
+        let mut counts = vec![0u32; windows as usize];
+        for window in first_window..windows {
+            counts[window as usize] =
+                self.signal_window_bit_counts(window)?[deployment.bit as usize];
+        }
+        Ok(counts)
+    }
+
+    fn signal_window_bit_counts(&mut self, window: u64) -> Result<[u32; 32], String> {
+        if let Some(counts) = self.signal_window_counts.get(&window) {
+            return Ok(*counts);
+        }
+        let mut counts = [0u32; 32];
+        let start = window * SIGNAL_WINDOW;
+        for height in start..start + SIGNAL_WINDOW {
+            let hash = self
+                .canonical_hash(height)?
+                .ok_or_else(|| format!("missing canonical hash at height {height}"))?;
+            let header = parse_block_header_bytes(&self.get_header_by_hash(hash)?)
+                .map_err(|e| e.to_string())?;
+            for (bit, count) in counts.iter_mut().enumerate() {
+                *count += (header.version >> bit) & 1;
+            }
+        }
+        self.signal_window_counts.insert(window, counts);
+        Ok(counts)
+    }
+
+    /// Forget cached signal counts for every window containing `height` or
+    /// later; called wherever canonical entries from `height` on change.
+    fn drop_signal_windows_from(&mut self, height: u64) {
+        self.signal_window_counts
+            .split_off(&(height / SIGNAL_WINDOW));
+    }
+
     // ----- Chain work -----
 
     /// Compute cumulative proof-of-work from genesis up to (and including)
@@ -545,6 +1088,92 @@ impl BlockStore {
         chain_work_from_targets(&targets).map_err(|e| e.to_string())
     }
 
+    // ----- Chain tips -----
+
+    /// Every branch tip in the store: the canonical tip plus each stored
+    /// block that no stored block names as its parent, ordered by height,
+    /// highest first. Rejected blocks whose bytes were never stored have
+    /// no header to place them and are not listed.
+    pub fn chain_tips(&self) -> Result<Vec<ChainTip>, String> {
+        let entries = fs::read_dir(&self.headers_dir).map_err(|e| {
+            format!(
+                "read blockstore headers {}: {e}",
+                self.headers_dir.display()
+            )
+        })?;
+        let mut hashes = Vec::new();
+        let mut parents = std::collections::HashSet::new();
+        for entry in entries {
+            let entry = entry.map_err(|e| format!("read blockstore headers: {e}"))?;
+            let Some(name) = entry.file_name().to_str().map(str::to_string) else {
+                continue;
+            };
+            // Leftover temp files from interrupted writes are not keys.
+            let Ok(key) = HeaderKey::from_file_name(&name) else {
+                continue;
+            };
+            let header = parse_block_header_bytes(&self.get_header_by_hash(key.0)?)
+                .map_err(|e| e.to_string())?;
+            parents.insert(header.prev_block_hash);
+            hashes.push(key.0);
+        }
+        let active = self.tip()?.map(|(_, hash)| hash);
+        let mut tips = Vec::new();
+        for hash in hashes {
+            if parents.contains(&hash) && Some(hash) != active {
+                continue;
+            }
+            tips.push(self.chain_tip(hash, Some(hash) == active)?);
+        }
+        tips.sort_by(|a, b| b.height.cmp(&a.height).then(a.hash.cmp(&b.hash)));
+        Ok(tips)
+    }
+
+    fn chain_tip(&self, tip_hash: [u8; 32], active: bool) -> Result<ChainTip, String> {
+        let mut branch_len = 0u64;
+        let mut headers_only = false;
+        let mut invalid_reason = None;
+        let mut current = tip_hash;
+        let fork_height = loop {
+            if let Some(height) = self.find_canonical_height(current)? {
+                break height;
+            }
+            if invalid_reason.is_none() {
+                if let Some(BlockStatus::Invalid { reason }) = self.get_block_status(current)? {
+                    invalid_reason = Some(reason);
+                }
+            }
+            headers_only |= !self.try_has_block_data(current)?;
+            let header = parse_block_header_bytes(&self.get_header_by_hash(current)?)
+                .map_err(|e| e.to_string())?;
+            branch_len += 1;
+            if header.prev_block_hash == [0u8; 32] {
+                return Err(format!(
+                    "branch of {} reaches genesis off the canonical chain",
+                    hex::encode(tip_hash)
+                ));
+            }
+            current = header.prev_block_hash;
+        };
+        let status = if active {
+            ChainTipStatus::Active
+        } else if invalid_reason.is_some() {
+            ChainTipStatus::Invalid
+        } else if headers_only {
+            ChainTipStatus::HeadersOnly
+        } else {
+            ChainTipStatus::ValidFork
+        };
+        Ok(ChainTip {
+            hash: tip_hash,
+            height: fork_height + branch_len,
+            chainwork: self.chain_work(tip_hash)?,
+            status,
+            branch_len,
+            invalid_reason,
+        })
+    }
+
     // ----- Undo storage -----
 
     /// Persist a single undo record. Crate-private so that any in-crate
@@ -568,9 +1197,7 @@ impl BlockStore {
             return Err("forced undo error (test)".to_string());
         }
         let raw = marshal_block_undo(undo)?;
-        let path = self
-            .undo_dir
-            .join(format!("{}.json", hex::encode(block_hash_bytes)));
+        let path = self.undo_dir.join(UndoKey(block_hash_bytes).file_name());
         // Undo files intentionally stay on the raw `write_file_atomic`
         // path (no `lexical_clean`). Writer and readers share one
         // dir-resolution strategy:
@@ -593,11 +1220,169 @@ impl BlockStore {
     }
 
     pub fn get_undo(&self, block_hash_bytes: [u8; 32]) -> Result<BlockUndo, String> {
+        unmarshal_block_undo(&self.get_undo_bytes(block_hash_bytes)?)
+    }
+
+    /// Raw undo record bytes, undecoded. The file keeps its historical
+    /// `.json` name whatever the record version.
+    pub fn get_undo_bytes(&self, block_hash_bytes: [u8; 32]) -> Result<Vec<u8>, String> {
         // E.10: see `get_block_by_hash` doc.
-        let name = format!("{}.json", hex::encode(block_hash_bytes));
-        let raw = read_file_from_dir(&self.undo_dir, &name)
-            .map_err(|e| format!("read undo {}: {e}", self.undo_dir.join(&name).display()))?;
-        unmarshal_block_undo(&raw)
+        let name = UndoKey(block_hash_bytes).file_name();
+        read_file_from_dir(&self.undo_dir, &name)
+            .map_err(|e| format!("read undo {}: {e}", self.undo_dir.join(&name).display()))
+    }
+
+    /// Persists per-block stats keyed by block hash. Stats are a pure
+    /// function of the block bytes, so they are written before the tip
+    /// advances and, like block files, stay behind as orphans on reorg.
+    pub fn put_block_stats(
+        &self,
+        block_hash_bytes: [u8; 32],
+        stats: &BlockStats,
+    ) -> Result<(), String> {
+        let raw = serde_json::to_vec(stats).map_err(|e| format!("encode block stats: {e}"))?;
+        let path = self.stats_dir.join(StatsKey(block_hash_bytes).file_name());
+        write_file_atomic(&path, &raw)
+    }
+
+    /// Returns the stored stats for a block, or `None` when none were
+    /// recorded (blocks committed before stats existed, or never applied).
+    pub fn get_block_stats(
+        &self,
+        block_hash_bytes: [u8; 32],
+    ) -> Result<Option<BlockStats>, String> {
+        let name = StatsKey(block_hash_bytes).file_name();
+        if !try_has_file_at(&self.stats_dir.join(&name))? {
+            return Ok(None);
+        }
+        let raw = read_file_from_dir(&self.stats_dir, &name).map_err(|e| {
+            format!(
+                "read block stats {}: {e}",
+                self.stats_dir.join(&name).display()
+            )
+        })?;
+        serde_json::from_slice(&raw)
+            .map(Some)
+            .map_err(|e| format!("decode block stats: {e}"))
+    }
+
+    /// Sums stored stats over canonical heights `0..=height`. Derived from
+    /// the canonical index on every call, so a reorg rolls it back for free.
+    pub fn cumulative_block_stats(&self, height: u64) -> Result<BlockStats, String> {
+        let mut total = BlockStats::default();
+        for h in 0..=height {
+            let hash = self
+                .canonical_hash(h)?
+                .ok_or_else(|| format!("no canonical block at height {h}"))?;
+            let stats = self
+                .get_block_stats(hash)?
+                .ok_or_else(|| format!("missing block stats at height {h}"))?;
+            total.accumulate(&stats);
+        }
+        Ok(total)
+    }
+
+    fn block_index_entry_path(&self, block_hash_bytes: [u8; 32]) -> PathBuf {
+        self.status_dir
+            .join(StatusKey(block_hash_bytes).file_name())
+    }
+
+    pub fn get_block_index_entry(
+        &self,
+        block_hash_bytes: [u8; 32],
+    ) -> Result<Option<BlockIndexEntry>, String> {
+        let name = StatusKey(block_hash_bytes).file_name();
+        if !try_has_file_at(&self.status_dir.join(&name))? {
+            return Ok(None);
+        }
+        let raw = read_file_from_dir(&self.status_dir, &name).map_err(|e| {
+            format!(
+                "read block status {}: {e}",
+                self.status_dir.join(&name).display()
+            )
+        })?;
+        decode_block_index_entry(&raw).map(Some)
+    }
+
+    /// Writes `entry`, or removes the record when it holds nothing.
+    fn put_block_index_entry(
+        &self,
+        block_hash_bytes: [u8; 32],
+        entry: BlockIndexEntry,
+    ) -> Result<(), String> {
+        let path = self.block_index_entry_path(block_hash_bytes);
+        if entry == BlockIndexEntry::default() {
+            return match fs::remove_file(&path) {
+                Ok(()) => Ok(()),
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+                Err(e) => Err(format!("remove block status {}: {e}", path.display())),
+            };
+        }
+        let disk = BlockIndexEntryDisk {
+            version: BLOCK_INDEX_ENTRY_VERSION,
+            entry,
+        };
+        let raw = serde_json::to_vec(&disk).map_err(|e| format!("encode block status: {e}"))?;
+        write_file_atomic(&path, &raw)
+    }
+
+    /// Records `now_unix` as the block's receive time unless one is already
+    /// known, so re-deliveries never make a block look younger.
+    pub fn note_block_received(
+        &self,
+        block_hash_bytes: [u8; 32],
+        now_unix: u64,
+    ) -> Result<(), String> {
+        let mut entry = self
+            .get_block_index_entry(block_hash_bytes)?
+            .unwrap_or_default();
+        if entry.received_unix != 0 {
+            return Ok(());
+        }
+        entry.received_unix = now_unix;
+        self.put_block_index_entry(block_hash_bytes, entry)
+    }
+
+    pub fn put_block_status(
+        &self,
+        block_hash_bytes: [u8; 32],
+        status: BlockStatus,
+    ) -> Result<(), String> {
+        let mut entry = self
+            .get_block_index_entry(block_hash_bytes)?
+            .unwrap_or_default();
+        entry.status = Some(status);
+        self.put_block_index_entry(block_hash_bytes, entry)
+    }
+
+    pub fn get_block_status(
+        &self,
+        block_hash_bytes: [u8; 32],
+    ) -> Result<Option<BlockStatus>, String> {
+        Ok(self
+            .get_block_index_entry(block_hash_bytes)?
+            .and_then(|entry| entry.status))
+    }
+
+    /// Drops the block's status, keeping its receive time.
+    pub fn clear_block_status(&self, block_hash_bytes: [u8; 32]) -> Result<(), String> {
+        let Some(mut entry) = self.get_block_index_entry(block_hash_bytes)? else {
+            return Ok(());
+        };
+        entry.status = None;
+        self.put_block_index_entry(block_hash_bytes, entry)
+    }
+
+    /// Forgets a recorded invalidity so the block can be imported again.
+    /// Returns whether the block was marked invalid.
+    pub fn reconsider_block(&self, block_hash_bytes: [u8; 32]) -> Result<bool, String> {
+        match self.get_block_status(block_hash_bytes)? {
+            Some(BlockStatus::Invalid { .. }) => {
+                self.clear_block_status(block_hash_bytes)?;
+                Ok(true)
+            }
+            _ => Ok(false),
+        }
     }
 
     /// Cheap undo-presence check used by the same-hash replay branch
@@ -609,7 +1394,7 @@ impl BlockStore {
     /// than silently looking like NotFound.
     fn has_undo(&self, block_hash_bytes: [u8; 32]) -> bool {
         self.undo_dir
-            .join(format!("{}.json", hex::encode(block_hash_bytes)))
+            .join(UndoKey(block_hash_bytes).file_name())
             .is_file()
     }
 
@@ -659,15 +1444,19 @@ impl BlockStore {
         // ("Err means no state change") requires every fallible step to
         // run before `save_blockstore_index_serializable`.
         let next_cache = build_canonical_hash_cache(&next_canonical)?;
+        let next_anchors = self.next_anchors(&next_cache, None)?;
         let view = BlockStoreIndexView {
             version: self.index.version,
             canonical: &next_canonical,
+            anchors: next_anchors.as_ref(),
         };
         save_blockstore_index_serializable(&self.index_path, &view)?;
         // Disk save succeeded — commit to in-memory (E.7 parity: mirror
         // Go's `replaceCanonicalState` rebuild after rollback).
         self.index.canonical = next_canonical;
+        self.index.anchors = next_anchors;
         self.canonical_hash_by_height = next_cache;
+        self.drop_signal_windows_from(clamped_base as u64);
         Ok(())
     }
 
@@ -693,18 +1482,22 @@ impl BlockStore {
         if new_len == current_len {
             return Ok(());
         }
+        let next_anchors = self.next_anchors(&self.canonical_hash_by_height[..new_len], None)?;
         let view = BlockStoreIndexView {
             version: self.index.version,
             canonical: &self.index.canonical[..new_len],
+            anchors: next_anchors.as_ref(),
         };
         save_blockstore_index_serializable(&self.index_path, &view)?;
         // Save succeeded — now apply O(1) in-memory truncate.
         self.index.canonical.truncate(new_len);
+        self.index.anchors = next_anchors;
         // E.7: keep height->hash cache coherent with the canonical
         // slice. Truncate is the only path that needs this on the
         // accepted-cases test (`canonical_hash` after `truncate_canonical(n)`
         // returns None for h >= n).
         self.canonical_hash_by_height.truncate(new_len);
+        self.drop_signal_windows_from(new_len as u64);
         Ok(())
     }
 
@@ -726,6 +1519,7 @@ impl BlockStore {
             if let Ok(cache) = build_canonical_hash_cache(&disk.canonical) {
                 self.canonical_hash_by_height = cache;
                 self.index = disk;
+                self.signal_window_counts.clear();
             }
         }
     }
@@ -802,17 +1596,19 @@ fn load_blockstore_index(path: &Path) -> Result<BlockStoreIndexDisk, String> {
             return Ok(BlockStoreIndexDisk {
                 version: BLOCK_STORE_INDEX_VERSION,
                 canonical: vec![],
+                anchors: None,
             });
         }
         Err(e) => return Err(format!("read blockstore index {}: {e}", path.display())),
     };
-    let index: BlockStoreIndexDisk = serde_json::from_slice(&raw)
+    let mut index: BlockStoreIndexDisk = serde_json::from_slice(&raw)
         .map_err(|e| format!("decode blockstore index {}: {e}", path.display()))?;
-    if index.version != BLOCK_STORE_INDEX_VERSION {
-        return Err(format!(
-            "unsupported blockstore index version: {}",
-            index.version
-        ));
+    match index.version {
+        BLOCK_STORE_INDEX_VERSION => {}
+        // Version 1 predates the chain anchors; `BlockStore::open` anchors
+        // the tip and the next index write persists version 2.
+        1 => index.version = BLOCK_STORE_INDEX_VERSION,
+        version => return Err(format!("unsupported blockstore index version: {version}")),
     }
     // Canonical hash validation is performed in `build_canonical_hash_cache`
     // when callers (e.g. `BlockStore::open`, `reload_index_from_disk`) build
@@ -856,6 +1652,8 @@ fn save_blockstore_index_serializable<S: serde::Serialize + ?Sized>(
 struct BlockStoreIndexView<'a> {
     version: u32,
     canonical: &'a [String],
+    #[serde(skip_serializing_if = "Option::is_none")]
+    anchors: Option<&'a ChainAnchorsDisk>,
 }
 
 /// Write `content` to `path` only if the destination is absent
@@ -1008,6 +1806,76 @@ mod tests {
 
     use super::{block_store_path, write_file_if_absent, BlockStore, BLOCK_STORE_DIR_NAME};
 
+    #[test]
+    fn block_index_entry_migrates_legacy_status_records() {
+        use super::{BlockIndexEntry, BlockInvalidReason, BlockStatus};
+
+        let dir = unique_temp_path("rubin-blockstore-index-entry");
+        let store = BlockStore::open(block_store_path(&dir)).expect("open");
+        let hash = [0x42; 32];
+        let path = block_store_path(&dir)
+            .join("status")
+            .join(format!("{}.json", hex::encode(hash)));
+
+        // A pre-versioning record is a bare status with no receive time.
+        std::fs::write(&path, br#"{"status":"needs_manual_reorg","fork_depth":2}"#)
+            .expect("write legacy");
+        assert_eq!(
+            store.get_block_index_entry(hash).expect("entry"),
+            Some(BlockIndexEntry {
+                status: Some(BlockStatus::NeedsManualReorg { fork_depth: 2 }),
+                received_unix: 0,
+            })
+        );
+
+        // Unknown receive time is filled in once, then never overwritten;
+        // the rewrite migrates the record to the current version.
+        store.note_block_received(hash, 1_700).expect("note");
+        store.note_block_received(hash, 1_900).expect("note again");
+        let raw = std::fs::read(&path).expect("read");
+        let value: serde_json::Value = serde_json::from_slice(&raw).expect("json");
+        assert_eq!(value["version"], super::BLOCK_INDEX_ENTRY_VERSION);
+        let entry = store.get_block_index_entry(hash).expect("entry").unwrap();
+        assert_eq!(entry.received_unix, 1_700);
+        assert_eq!(
+            entry.status,
+            Some(BlockStatus::NeedsManualReorg { fork_depth: 2 })
+        );
+
+        let reason = BlockInvalidReason::from_error("TX_ERR_SIG_INVALID: bad sig").unwrap();
+        assert_eq!(reason.code, "TX_ERR_SIG_INVALID");
+        assert!(BlockInvalidReason::from_error("parent block not found").is_none());
+        store
+            .put_block_status(hash, BlockStatus::Invalid { reason })
+            .expect("put");
+        assert!(store.reconsider_block(hash).expect("reconsider"));
+        assert_eq!(store.get_block_status(hash).expect("status"), None);
+        assert_eq!(
+            store
+                .get_block_index_entry(hash)
+                .expect("entry")
+                .unwrap()
+                .received_unix,
+            1_700
+        );
+
+        // An entry with nothing left in it is removed.
+        let bare = [0x43; 32];
+        store
+            .put_block_status(bare, BlockStatus::NeedsManualReorg { fork_depth: 1 })
+            .expect("put");
+        store.clear_block_status(bare).expect("clear");
+        assert_eq!(store.get_block_index_entry(bare).expect("entry"), None);
+
+        std::fs::write(&path, br#"{"version":9}"#).expect("write future");
+        let err = store
+            .get_block_index_entry(hash)
+            .expect_err("future version");
+        assert!(err.contains("unsupported block index entry version: 9"));
+
+        std::fs::remove_dir_all(&dir).expect("cleanup");
+    }
+
     /// Happy path for the E.3-hardened helper: destination absent,
     /// write_file_if_absent creates it via the atomic hard_link path,
     /// and a subsequent call with matching bytes is an idempotent
@@ -1180,6 +2048,238 @@ mod tests {
         std::fs::remove_dir_all(&dir).expect("cleanup");
     }
 
+    /// Stores `count` linked headers `interval` seconds apart (header bytes
+    /// double as the block body) and returns them with their hashes.
+    fn store_header_chain(
+        store: &BlockStore,
+        count: usize,
+        interval: u64,
+    ) -> (Vec<rubin_consensus::BlockHeader>, Vec<[u8; 32]>) {
+        use rubin_consensus::constants::POW_LIMIT;
+        use rubin_consensus::{block_hash, BlockHeader};
+
+        let (mut headers, mut hashes) = (Vec::with_capacity(count), Vec::with_capacity(count));
+        let mut prev_block_hash = [0u8; 32];
+        for height in 0..count as u64 {
+            let header = BlockHeader {
+                version: 1,
+                prev_block_hash,
+                merkle_root: [0x5a; 32],
+                timestamp: 1_000_000 + height * interval,
+                target: POW_LIMIT,
+                nonce: height,
+            };
+            let bytes = header.to_bytes();
+            prev_block_hash = block_hash(&bytes).expect("hash");
+            store
+                .store_block(prev_block_hash, &bytes, &bytes)
+                .expect("store header");
+            headers.push(header);
+            hashes.push(prev_block_hash);
+        }
+        (headers, hashes)
+    }
+
+    #[test]
+    fn blockstore_validation_context_near_genesis() {
+        let dir = unique_temp_path("rubin-blockstore-vctx");
+        let store = BlockStore::open(block_store_path(&dir)).expect("open");
+        let (headers, hashes) = store_header_chain(&store, 13, 120);
+
+        let genesis_ctx = store
+            .validation_context([0u8; 32], 0, None)
+            .expect("genesis");
+        assert!(genesis_ctx.ancestors.is_empty());
+        assert_eq!(genesis_ctx.prev_timestamps(), None);
+
+        for height in 1..=13u64 {
+            let ctx = store
+                .validation_context(hashes[height as usize - 1], height, Some(42))
+                .expect("context");
+            let from = height.saturating_sub(11) as usize;
+            assert_eq!(
+                ctx.ancestors,
+                headers[from..height as usize],
+                "height {height}"
+            );
+            assert_eq!(ctx.local_time, Some(42));
+            let want: Vec<u64> = headers[from..height as usize]
+                .iter()
+                .rev()
+                .map(|header| header.timestamp)
+                .collect();
+            assert_eq!(ctx.prev_timestamps(), Some(want), "height {height}");
+            assert_eq!(ctx.expected_target(), Ok(headers[0].target));
+        }
+
+        // A tip that cannot supply the window (height claims more ancestors
+        // than the chain below it has) is an error, not a short window.
+        let err = store
+            .validation_context(hashes[2], 5, None)
+            .expect_err("short chain");
+        assert!(err.contains("missing ancestor header 3 of 5"), "{err}");
+
+        std::fs::remove_dir_all(&dir).expect("cleanup");
+    }
+
+    #[test]
+    fn blockstore_validation_context_spans_retarget_window() {
+        use rubin_consensus::constants::{TARGET_BLOCK_INTERVAL, WINDOW_SIZE};
+        use rubin_consensus::retarget_v1_clamped;
+
+        let dir = unique_temp_path("rubin-blockstore-vctx-retarget");
+        let store = BlockStore::open(block_store_path(&dir)).expect("open");
+        let window = WINDOW_SIZE as usize;
+        let (headers, hashes) = store_header_chain(&store, window + 1, TARGET_BLOCK_INTERVAL / 2);
+
+        let ctx = store
+            .validation_context(hashes[window - 1], WINDOW_SIZE, None)
+            .expect("boundary context");
+        assert_eq!(ctx.ancestors.len(), window);
+        assert_eq!(ctx.ancestors, headers[..window]);
+        assert_eq!(ctx.prev_timestamps().map(|ts| ts.len()), Some(11));
+        let timestamps: Vec<u64> = headers[..window].iter().map(|h| h.timestamp).collect();
+        let want = retarget_v1_clamped(headers[0].target, &timestamps).expect("retarget");
+        assert_eq!(ctx.expected_target(), Ok(want));
+        assert_ne!(want, headers[0].target, "fast blocks tighten the target");
+
+        // Past the boundary only the MTP window is loaded.
+        let ctx = store
+            .validation_context(hashes[window], WINDOW_SIZE + 1, None)
+            .expect("post-boundary context");
+        assert_eq!(ctx.ancestors, headers[window - 10..]);
+
+        std::fs::remove_dir_all(&dir).expect("cleanup");
+    }
+
+    #[test]
+    fn blockstore_validation_context_follows_fast_devnet_window() {
+        use rubin_consensus::{retarget_v1_clamped_with_params, ChainParams};
+
+        let fast = ChainParams {
+            target_block_interval: 2,
+            window_size: 16,
+            coinbase_maturity: 4,
+        };
+        let dir = unique_temp_path("rubin-blockstore-vctx-fast");
+        let store = BlockStore::open(block_store_path(&dir)).expect("open");
+        let (headers, hashes) = store_header_chain(&store, 17, 1);
+
+        let ctx = store
+            .validation_context_with_params(hashes[14], 15, None, &fast)
+            .expect("pre-boundary context");
+        assert_eq!(
+            ctx.expected_target_with_params(&fast),
+            Ok(headers[14].target)
+        );
+
+        let ctx = store
+            .validation_context_with_params(hashes[15], 16, None, &fast)
+            .expect("boundary context");
+        assert_eq!(ctx.ancestors, headers[..16]);
+        let timestamps: Vec<u64> = headers[..16].iter().map(|h| h.timestamp).collect();
+        let want = retarget_v1_clamped_with_params(headers[0].target, &timestamps, &fast)
+            .expect("retarget");
+        assert_eq!(ctx.expected_target_with_params(&fast), Ok(want));
+        assert_ne!(want, headers[15].target, "height 16 retargets");
+
+        // The v1 window keeps the parent target at height 16.
+        let ctx = store
+            .validation_context(hashes[15], 16, None)
+            .expect("v1 context");
+        assert_eq!(ctx.expected_target(), Ok(headers[15].target));
+
+        std::fs::remove_dir_all(&dir).expect("cleanup");
+    }
+
+    #[test]
+    fn blockstore_window_signal_counts_follow_canonical_reorg() {
+        use rubin_consensus::constants::{POW_LIMIT, SIGNAL_THRESHOLD, SIGNAL_WINDOW};
+        use rubin_consensus::{
+            block_hash, featurebit_state_at_height_from_window_counts, BlockHeader,
+            FeatureBitDeployment, FeatureBitState,
+        };
+
+        let dir = unique_temp_path("rubin-blockstore-featurebits");
+        let mut store = BlockStore::open(block_store_path(&dir)).expect("open");
+        let window = SIGNAL_WINDOW;
+        let deployment = FeatureBitDeployment {
+            name: "X".to_string(),
+            bit: 3,
+            start_height: window,
+            timeout_height: 10 * window,
+        };
+        // Exactly the threshold signals, packed at the end of window 1.
+        let signals = |height: u64| {
+            height / window == 1 && height % window >= window - SIGNAL_THRESHOLD as u64
+        };
+        let store_header = |height: u64, prev_block_hash: [u8; 32], version: u32| {
+            let bytes = BlockHeader {
+                version,
+                prev_block_hash,
+                merkle_root: [0x5a; 32],
+                timestamp: 1_000_000 + height * 120,
+                target: POW_LIMIT,
+                nonce: height,
+            }
+            .to_bytes();
+            let hash = block_hash(&bytes).expect("hash");
+            store
+                .store_block(hash, &bytes, &bytes)
+                .expect("store header");
+            hash
+        };
+        let mut hashes = Vec::new();
+        let mut prev_block_hash = [0u8; 32];
+        for height in 0..3 * window {
+            let version = if signals(height) { 1 | 1 << 3 } else { 1 };
+            prev_block_hash = store_header(height, prev_block_hash, version);
+            hashes.push(prev_block_hash);
+        }
+        let alt = store_header(2 * window - 1, hashes[2 * window as usize - 2], 1);
+        store
+            .rollback_canonical(0, hashes.iter().map(hex::encode).collect())
+            .expect("canonical");
+
+        let state_at = |store: &mut BlockStore, height: u64| {
+            let counts = store
+                .window_signal_counts(&deployment, height)
+                .expect("counts");
+            featurebit_state_at_height_from_window_counts(&deployment, height, &counts)
+                .expect("eval")
+                .state
+        };
+        assert_eq!(state_at(&mut store, window - 1), FeatureBitState::Defined);
+        assert_eq!(state_at(&mut store, window), FeatureBitState::Started);
+        assert_eq!(
+            state_at(&mut store, 2 * window - 1),
+            FeatureBitState::Started
+        );
+        assert_eq!(state_at(&mut store, 2 * window), FeatureBitState::LockedIn);
+        assert_eq!(state_at(&mut store, 3 * window), FeatureBitState::Active);
+        assert_eq!(
+            store.window_signal_counts(&deployment, 3 * window),
+            Ok(vec![0, SIGNAL_THRESHOLD, 0])
+        );
+
+        // Replacing the last block of window 1 with a non-signalling sibling
+        // must not be served from the cached window.
+        store
+            .rollback_canonical(2 * window as usize - 1, vec![hex::encode(alt)])
+            .expect("reorg");
+        assert_eq!(
+            store.window_signal_counts(&deployment, 2 * window),
+            Ok(vec![0, SIGNAL_THRESHOLD - 1])
+        );
+        assert_eq!(state_at(&mut store, 2 * window), FeatureBitState::Started);
+        assert_eq!(
+            store.window_signal_counts(&deployment, 3 * window),
+            Err(format!("missing canonical hash at height {}", 2 * window))
+        );
+
+        std::fs::remove_dir_all(&dir).expect("cleanup");
+    }
+
     #[test]
     fn blockstore_chain_work_from_genesis() {
         use crate::genesis::devnet_genesis_block_bytes;
@@ -1247,6 +2347,95 @@ mod tests {
         std::fs::remove_dir_all(&dir).expect("cleanup");
     }
 
+    #[test]
+    fn chain_anchors_follow_canonical_changes_and_best_header() {
+        use super::ChainAnchor;
+
+        let dir = unique_temp_path("rubin-blockstore-anchors");
+        let root = block_store_path(&dir);
+        let mut store = BlockStore::open(&root).expect("open");
+        assert_eq!(store.chain_anchors().expect("anchors"), None);
+        assert!(store.record_best_header(0, [0x01; 32]).is_err());
+
+        let at = |height, byte| ChainAnchor {
+            height,
+            hash: [byte; 32],
+        };
+        store.set_canonical_tip(0, [0xA0; 32]).expect("set 0");
+        store
+            .set_canonical_tip_with_utxo_set_hash(1, [0xA1; 32], Some([0x77; 32]))
+            .expect("set 1");
+        store.set_canonical_tip(2, [0xA2; 32]).expect("set 2");
+        store.record_best_header(5, [0xB5; 32]).expect("header 5");
+        store.record_best_header(4, [0xB4; 32]).expect("header 4");
+        let anchors = store.chain_anchors().expect("anchors").expect("some");
+        assert_eq!(anchors.best_header, at(5, 0xB5));
+        assert_eq!(anchors.best_block, at(2, 0xA2));
+        assert_eq!(anchors.utxo, Some((at(1, 0xA1), [0x77; 32])));
+
+        let reopened = BlockStore::open(&root).expect("reopen");
+        assert_eq!(reopened.chain_anchors().expect("anchors"), Some(anchors));
+
+        // A UTXO hash survives only while its block stays canonical.
+        store.truncate_canonical(2).expect("truncate");
+        let anchors = store.chain_anchors().expect("anchors").expect("some");
+        assert_eq!(anchors.best_header, at(5, 0xB5));
+        assert_eq!(anchors.best_block, at(1, 0xA1));
+        assert_eq!(anchors.utxo, Some((at(1, 0xA1), [0x77; 32])));
+        store.set_canonical_tip(1, [0xC1; 32]).expect("replace 1");
+        let anchors = store.chain_anchors().expect("anchors").expect("some");
+        assert_eq!(anchors.best_block, at(1, 0xC1));
+        assert_eq!(anchors.utxo, None);
+
+        store.truncate_canonical(0).expect("truncate all");
+        assert_eq!(store.chain_anchors().expect("anchors"), None);
+
+        std::fs::remove_dir_all(&dir).expect("cleanup");
+    }
+
+    #[test]
+    fn blockstore_index_v1_migrates_to_anchored_v2() {
+        use super::ChainAnchor;
+
+        let dir = unique_temp_path("rubin-blockstore-index-v1");
+        let root = block_store_path(&dir);
+        drop(BlockStore::open(&root).expect("create"));
+        let index_path = root.join("index.json");
+        std::fs::write(
+            &index_path,
+            format!(
+                r#"{{"version":1,"canonical":["{}","{}"]}}"#,
+                hex::encode([0xA0; 32]),
+                hex::encode([0xA1; 32])
+            ),
+        )
+        .expect("write v1 index");
+
+        let mut store = BlockStore::open(&root).expect("open v1");
+        let tip = ChainAnchor {
+            height: 1,
+            hash: [0xA1; 32],
+        };
+        let anchors = store.chain_anchors().expect("anchors").expect("some");
+        assert_eq!((anchors.best_header, anchors.best_block), (tip, tip));
+        assert_eq!(anchors.utxo, None);
+
+        store.set_canonical_tip(2, [0xA2; 32]).expect("set 2");
+        let raw: serde_json::Value =
+            serde_json::from_slice(&std::fs::read(&index_path).expect("read")).expect("json");
+        assert_eq!(raw["version"], 2);
+        assert_eq!(raw["anchors"]["best_block_height"], 2);
+
+        std::fs::write(&index_path, r#"{"version":3,"canonical":[]}"#).expect("write v3");
+        let err = BlockStore::open(&root).unwrap_err();
+        assert!(
+            err.contains("unsupported blockstore index version: 3"),
+            "{err}"
+        );
+
+        std::fs::remove_dir_all(&dir).expect("cleanup");
+    }
+
     /// Go parity / crash-safety for Q-IMPL-RUST-STORAGE-ATOMIC-CANONICAL-COMMIT-01:
     /// `commit_canonical_block` persists block/header/undo BEFORE
     /// advancing the canonical tip. The happy-path roundtrip confirms
@@ -1726,4 +2915,69 @@ mod tests {
 
         std::fs::remove_dir_all(&dir).expect("cleanup");
     }
+
+    #[test]
+    fn chain_tips_reports_active_stale_fork_and_invalid_branch() {
+        use super::{BlockInvalidReason, BlockStatus, ChainTipStatus};
+        use crate::test_helpers::{coinbase_only_block, genesis_info};
+        use rubin_consensus::BLOCK_HEADER_BYTES;
+
+        let dir = unique_temp_path("rubin-blockstore-chain-tips");
+        let mut store = BlockStore::open(block_store_path(&dir)).expect("open");
+        let hash_of =
+            |block: &[u8]| rubin_consensus::block_hash(&block[..BLOCK_HEADER_BYTES]).expect("hash");
+        let (genesis, genesis_hash, ts) = genesis_info();
+        let mut canonical = vec![(genesis, genesis_hash)];
+        for height in 1..=3u64 {
+            let block = coinbase_only_block(height, canonical.last().unwrap().1, ts + height);
+            let hash = hash_of(&block);
+            canonical.push((block, hash));
+        }
+        for (height, (block, hash)) in canonical.iter().enumerate() {
+            store
+                .put_block(height as u64, *hash, &block[..BLOCK_HEADER_BYTES], block)
+                .expect("canonical");
+        }
+        let store_side = |prev: [u8; 32], height: u64, ts: u64| {
+            let block = coinbase_only_block(height, prev, ts);
+            let hash = hash_of(&block);
+            store
+                .store_block(hash, &block[..BLOCK_HEADER_BYTES], &block)
+                .expect("side");
+            hash
+        };
+        // A stale fork of one block off height 1, and a two-block branch
+        // off height 2 whose first block failed validation.
+        let stale = store_side(canonical[1].1, 2, ts + 100);
+        let invalid_base = store_side(canonical[2].1, 3, ts + 200);
+        let invalid_tip = store_side(invalid_base, 4, ts + 201);
+        let reason = BlockInvalidReason::from_error("BLOCK_ERR_COINBASE_INVALID: bad").unwrap();
+        store
+            .put_block_status(
+                invalid_base,
+                BlockStatus::Invalid {
+                    reason: reason.clone(),
+                },
+            )
+            .expect("status");
+
+        let tips = store.chain_tips().expect("chain tips");
+        let summary: Vec<_> = tips
+            .iter()
+            .map(|tip| (tip.hash, tip.height, tip.status, tip.branch_len))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (invalid_tip, 4, ChainTipStatus::Invalid, 2),
+                (canonical[3].1, 3, ChainTipStatus::Active, 0),
+                (stale, 2, ChainTipStatus::ValidFork, 1),
+            ]
+        );
+        assert_eq!(tips[0].invalid_reason, Some(reason));
+        assert_eq!(tips[1].chainwork, store.chain_work(canonical[3].1).unwrap());
+        assert!(tips[0].chainwork > tips[1].chainwork);
+
+        std::fs::remove_dir_all(&dir).expect("cleanup");
+    }
 }
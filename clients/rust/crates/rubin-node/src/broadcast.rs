@@ -0,0 +1,565 @@
+//! Tracking of locally submitted transactions until they confirm.
+//!
+//! `/submit_tx` announces a transaction once; the `BroadcastManager` keeps
+//! it afterwards: it is announced again every `rebroadcast_interval_secs`
+//! and to every newly connected peer while pending, follows the chain as
+//! blocks connect and disconnect, and gives up after `expiry_secs`. The
+//! state is persisted to `<datadir>/broadcast.json`, which is what
+//! `rubin-node gettxstatus` reads.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use rubin_consensus::{parse_block_bytes, parse_block_header_bytes};
+use serde::{Deserialize, Serialize};
+
+use crate::blockstore::BlockStore;
+use crate::io_utils::{parse_hex32, write_file_atomic};
+
+pub const BROADCAST_STATE_FILE_NAME: &str = "broadcast.json";
+pub const DEFAULT_REBROADCAST_INTERVAL_SECS: u64 = 15 * 60;
+pub const DEFAULT_BROADCAST_EXPIRY_SECS: u64 = 14 * 24 * 60 * 60;
+const BROADCAST_STATE_VERSION: u32 = 1;
+
+pub fn broadcast_state_path<P: AsRef<Path>>(data_dir: P) -> PathBuf {
+    data_dir.as_ref().join(BROADCAST_STATE_FILE_NAME)
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BroadcastConfig {
+    pub rebroadcast_interval_secs: u64,
+    /// Measured from submission; confirmed transactions never expire.
+    pub expiry_secs: u64,
+}
+
+impl Default for BroadcastConfig {
+    fn default() -> Self {
+        Self {
+            rebroadcast_interval_secs: DEFAULT_REBROADCAST_INTERVAL_SECS,
+            expiry_secs: DEFAULT_BROADCAST_EXPIRY_SECS,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BroadcastState {
+    Pending,
+    Confirmed {
+        height: u64,
+        block_hash: [u8; 32],
+    },
+    /// Dropped from the local mempool without confirming.
+    Evicted,
+    Expired,
+}
+
+impl BroadcastState {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Pending => "pending",
+            Self::Confirmed { .. } => "confirmed",
+            Self::Evicted => "evicted",
+            Self::Expired => "expired",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BroadcastEntry {
+    pub tx_bytes: Vec<u8>,
+    pub fee: u64,
+    pub submitted_at: u64,
+    /// `None` schedules a rebroadcast on the next tick.
+    pub last_broadcast_at: Option<u64>,
+    pub state: BroadcastState,
+}
+
+/// The `gettxstatus` / `/broadcast_status` view of one entry.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct BroadcastStatus {
+    pub txid: String,
+    pub state: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub height: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub block_hash: Option<String>,
+    pub submitted_at: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_broadcast_at: Option<u64>,
+}
+
+#[derive(Debug, Default)]
+pub struct BroadcastManager {
+    cfg: BroadcastConfig,
+    entries: BTreeMap<[u8; 32], BroadcastEntry>,
+    /// Canonical tip the confirmations were last reconciled against.
+    synced_tip: Option<(u64, [u8; 32])>,
+    known_peers: BTreeSet<String>,
+}
+
+impl BroadcastManager {
+    pub fn new(cfg: BroadcastConfig) -> Self {
+        Self {
+            cfg,
+            ..Self::default()
+        }
+    }
+
+    pub fn config(&self) -> BroadcastConfig {
+        self.cfg
+    }
+
+    /// Tracks a transaction the caller just announced. Resubmitting a
+    /// tracked, unconfirmed transaction restarts its expiry window.
+    pub fn submit(&mut self, txid: [u8; 32], tx_bytes: &[u8], fee: u64, now: u64) {
+        if matches!(
+            self.entries.get(&txid).map(|e| e.state),
+            Some(BroadcastState::Confirmed { .. })
+        ) {
+            return;
+        }
+        self.entries.insert(
+            txid,
+            BroadcastEntry {
+                tx_bytes: tx_bytes.to_vec(),
+                fee,
+                submitted_at: now,
+                last_broadcast_at: Some(now),
+                state: BroadcastState::Pending,
+            },
+        );
+    }
+
+    pub fn entry(&self, txid: &[u8; 32]) -> Option<&BroadcastEntry> {
+        self.entries.get(txid)
+    }
+
+    pub fn status(&self, txid: &[u8; 32]) -> Option<BroadcastStatus> {
+        let entry = self.entries.get(txid)?;
+        let (height, block_hash) = match entry.state {
+            BroadcastState::Confirmed { height, block_hash } => {
+                (Some(height), Some(hex::encode(block_hash)))
+            }
+            _ => (None, None),
+        };
+        Some(BroadcastStatus {
+            txid: hex::encode(txid),
+            state: entry.state.name(),
+            height,
+            block_hash,
+            submitted_at: entry.submitted_at,
+            last_broadcast_at: entry.last_broadcast_at,
+        })
+    }
+
+    /// Pending and evicted transactions: the ones `note_mempool` tracks.
+    pub fn unconfirmed_txids(&self) -> Vec<[u8; 32]> {
+        self.entries
+            .iter()
+            .filter(|(_, e)| matches!(e.state, BroadcastState::Pending | BroadcastState::Evicted))
+            .map(|(txid, _)| *txid)
+            .collect()
+    }
+
+    /// `(txid, tx_bytes, fee)` of every pending transaction, for announcing
+    /// to newly connected peers.
+    pub fn pending_txs(&self) -> Vec<([u8; 32], Vec<u8>, u64)> {
+        self.entries
+            .iter()
+            .filter(|(_, e)| e.state == BroadcastState::Pending)
+            .map(|(txid, e)| (*txid, e.tx_bytes.clone(), e.fee))
+            .collect()
+    }
+
+    /// Confirms every tracked transaction in `txids`, the transactions of
+    /// the block `block_hash` connected at `height`.
+    pub fn on_block_connected(&mut self, height: u64, block_hash: [u8; 32], txids: &[[u8; 32]]) {
+        for txid in txids {
+            if let Some(entry) = self.entries.get_mut(txid) {
+                entry.state = BroadcastState::Confirmed { height, block_hash };
+            }
+        }
+    }
+
+    /// Returns transactions confirmed at `height` or above to pending, due
+    /// for an immediate rebroadcast.
+    pub fn on_block_disconnected(&mut self, height: u64) {
+        for entry in self.entries.values_mut() {
+            if matches!(entry.state, BroadcastState::Confirmed { height: h, .. } if h >= height) {
+                entry.state = BroadcastState::Pending;
+                entry.last_broadcast_at = None;
+            }
+        }
+    }
+
+    /// Records whether the local mempool holds `txid`: a pending transaction
+    /// it dropped is evicted, and an evicted one it holds again (requeued
+    /// after a reorg, or resubmitted) is pending.
+    pub fn note_mempool(&mut self, txid: &[u8; 32], in_mempool: bool) {
+        let Some(entry) = self.entries.get_mut(txid) else {
+            return;
+        };
+        entry.state = match (entry.state, in_mempool) {
+            (BroadcastState::Pending, false) => BroadcastState::Evicted,
+            (BroadcastState::Evicted, true) => BroadcastState::Pending,
+            (state, _) => state,
+        };
+    }
+
+    /// Expires unconfirmed transactions past the expiry window, then returns
+    /// the pending ones whose rebroadcast is due and stamps them as sent.
+    pub fn due_for_rebroadcast(&mut self, now: u64) -> Vec<([u8; 32], Vec<u8>, u64)> {
+        let mut due = Vec::new();
+        for (txid, entry) in &mut self.entries {
+            if matches!(
+                entry.state,
+                BroadcastState::Pending | BroadcastState::Evicted
+            ) && now.saturating_sub(entry.submitted_at) >= self.cfg.expiry_secs
+            {
+                entry.state = BroadcastState::Expired;
+            }
+            if entry.state != BroadcastState::Pending {
+                continue;
+            }
+            let is_due = entry
+                .last_broadcast_at
+                .is_none_or(|last| now.saturating_sub(last) >= self.cfg.rebroadcast_interval_secs);
+            if is_due {
+                entry.last_broadcast_at = Some(now);
+                due.push((*txid, entry.tx_bytes.clone(), entry.fee));
+            }
+        }
+        due
+    }
+
+    /// Returns the peers of `current` not seen by an earlier call and
+    /// forgets the ones that disconnected, so a reconnect counts as new.
+    pub fn new_peers(&mut self, current: &[String]) -> Vec<String> {
+        let current: BTreeSet<String> = current.iter().cloned().collect();
+        let fresh = current.difference(&self.known_peers).cloned().collect();
+        self.known_peers = current;
+        fresh
+    }
+
+    /// Reconciles the tracked transactions with the canonical chain of
+    /// `store`: blocks disconnected since the last call un-confirm, and
+    /// blocks connected since then confirm. The first call only records
+    /// the tip.
+    pub fn sync_with_chain(&mut self, store: &BlockStore) -> Result<(), String> {
+        let Some((tip_height, tip_hash)) = store.tip()? else {
+            return Ok(());
+        };
+        let Some((mut height, mut hash)) = self.synced_tip else {
+            self.synced_tip = Some((tip_height, tip_hash));
+            return Ok(());
+        };
+        if (height, hash) == (tip_height, tip_hash) {
+            return Ok(());
+        }
+        // Walk the old branch back to the fork point.
+        let old_height = height;
+        while store.canonical_hash(height)? != Some(hash) {
+            if height == 0 {
+                return Err("broadcast: previous tip shares no ancestor with the chain".into());
+            }
+            let header = store.get_header_by_hash(hash)?;
+            hash = parse_block_header_bytes(&header)
+                .map_err(|e| e.to_string())?
+                .prev_block_hash;
+            height -= 1;
+        }
+        if height < old_height {
+            self.on_block_disconnected(height + 1);
+        }
+        let unconfirmed = self
+            .entries
+            .values()
+            .any(|e| !matches!(e.state, BroadcastState::Confirmed { .. }));
+        if unconfirmed {
+            for h in height + 1..=tip_height {
+                let Some(block_hash) = store.canonical_hash(h)? else {
+                    break;
+                };
+                let block = store.get_block_by_hash(block_hash)?;
+                let parsed = parse_block_bytes(&block).map_err(|e| e.to_string())?;
+                self.on_block_connected(h, block_hash, &parsed.txids);
+            }
+        }
+        self.synced_tip = Some((tip_height, tip_hash));
+        Ok(())
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), String> {
+        write_file_atomic(path.as_ref(), &self.encode()?)
+    }
+
+    /// The JSON `save` writes.
+    pub fn encode(&self) -> Result<Vec<u8>, String> {
+        let disk = BroadcastDisk {
+            version: BROADCAST_STATE_VERSION,
+            synced_tip: self
+                .synced_tip
+                .map(|(height, hash)| (height, hex::encode(hash))),
+            entries: self
+                .entries
+                .iter()
+                .map(|(txid, e)| BroadcastDiskEntry::from_entry(txid, e))
+                .collect(),
+        };
+        let mut raw =
+            serde_json::to_vec_pretty(&disk).map_err(|e| format!("encode broadcast state: {e}"))?;
+        raw.push(b'\n');
+        Ok(raw)
+    }
+}
+
+/// Loads the state saved at `path`; a missing file is an empty manager.
+pub fn load_broadcast_manager<P: AsRef<Path>>(
+    path: P,
+    cfg: BroadcastConfig,
+) -> Result<BroadcastManager, String> {
+    let path = path.as_ref();
+    let raw = match fs::read(path) {
+        Ok(raw) => raw,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            return Ok(BroadcastManager::new(cfg))
+        }
+        Err(e) => return Err(format!("read broadcast state {}: {e}", path.display())),
+    };
+    let disk: BroadcastDisk = serde_json::from_slice(&raw)
+        .map_err(|e| format!("parse broadcast state {}: {e}", path.display()))?;
+    if disk.version != BROADCAST_STATE_VERSION {
+        return Err(format!(
+            "unsupported broadcast state version: {}",
+            disk.version
+        ));
+    }
+    let mut mgr = BroadcastManager::new(cfg);
+    if let Some((height, hash)) = disk.synced_tip {
+        mgr.synced_tip = Some((height, parse_hex32("synced_tip", &hash)?));
+    }
+    for e in disk.entries {
+        let (txid, entry) = e.into_entry()?;
+        mgr.entries.insert(txid, entry);
+    }
+    Ok(mgr)
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct BroadcastDisk {
+    version: u32,
+    synced_tip: Option<(u64, String)>,
+    entries: Vec<BroadcastDiskEntry>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct BroadcastDiskEntry {
+    txid: String,
+    tx_hex: String,
+    fee: u64,
+    submitted_at: u64,
+    last_broadcast_at: Option<u64>,
+    state: String,
+    height: Option<u64>,
+    block_hash: Option<String>,
+}
+
+impl BroadcastDiskEntry {
+    fn from_entry(txid: &[u8; 32], e: &BroadcastEntry) -> Self {
+        let (height, block_hash) = match e.state {
+            BroadcastState::Confirmed { height, block_hash } => {
+                (Some(height), Some(hex::encode(block_hash)))
+            }
+            _ => (None, None),
+        };
+        Self {
+            txid: hex::encode(txid),
+            tx_hex: hex::encode(&e.tx_bytes),
+            fee: e.fee,
+            submitted_at: e.submitted_at,
+            last_broadcast_at: e.last_broadcast_at,
+            state: e.state.name().to_string(),
+            height,
+            block_hash,
+        }
+    }
+
+    fn into_entry(self) -> Result<([u8; 32], BroadcastEntry), String> {
+        let txid = parse_hex32("txid", &self.txid)?;
+        let state = match (self.state.as_str(), self.height, self.block_hash) {
+            ("pending", _, _) => BroadcastState::Pending,
+            ("evicted", _, _) => BroadcastState::Evicted,
+            ("expired", _, _) => BroadcastState::Expired,
+            ("confirmed", Some(height), Some(hash)) => BroadcastState::Confirmed {
+                height,
+                block_hash: parse_hex32("block_hash", &hash)?,
+            },
+            (other, _, _) => return Err(format!("invalid broadcast state: {other}")),
+        };
+        let tx_bytes = hex::decode(&self.tx_hex).map_err(|e| format!("tx_hex: {e}"))?;
+        Ok((
+            txid,
+            BroadcastEntry {
+                tx_bytes,
+                fee: self.fee,
+                submitted_at: self.submitted_at,
+                last_broadcast_at: self.last_broadcast_at,
+                state,
+            },
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rubin_consensus::block_hash;
+    use rubin_consensus::constants::POW_LIMIT;
+
+    use super::*;
+    use crate::coinbase::default_mine_address;
+    use crate::io_utils::unique_temp_path;
+    use crate::test_helpers::{block_with_txs_to, coinbase_only_block, genesis_info};
+    use crate::{block_store_path, chain_state_path, default_sync_config, ChainState, SyncEngine};
+
+    const TXID: [u8; 32] = [0xaa; 32];
+    const CFG: BroadcastConfig = BroadcastConfig {
+        rebroadcast_interval_secs: 60,
+        expiry_secs: 3600,
+    };
+
+    fn submitted() -> BroadcastManager {
+        let mut mgr = BroadcastManager::new(CFG);
+        mgr.submit(TXID, &[1, 2, 3], 10, 1_000);
+        mgr
+    }
+
+    fn header_hash(block: &[u8]) -> [u8; 32] {
+        block_hash(&block[..rubin_consensus::BLOCK_HEADER_BYTES]).expect("block hash")
+    }
+
+    #[test]
+    fn submit_is_pending_and_rebroadcasts_on_the_interval() {
+        let mut mgr = submitted();
+        let status = mgr.status(&TXID).expect("tracked");
+        assert_eq!(status.state, "pending");
+        assert_eq!(status.last_broadcast_at, Some(1_000));
+        assert!(mgr.status(&[0xbb; 32]).is_none());
+        assert_eq!(mgr.pending_txs(), vec![(TXID, vec![1, 2, 3], 10)]);
+
+        assert!(mgr.due_for_rebroadcast(1_059).is_empty());
+        let due = mgr.due_for_rebroadcast(1_060);
+        assert_eq!(due, vec![(TXID, vec![1, 2, 3], 10)]);
+        assert!(mgr.due_for_rebroadcast(1_061).is_empty());
+    }
+
+    #[test]
+    fn confirmed_then_disconnected_is_pending_and_due_now() {
+        let mut mgr = submitted();
+        mgr.on_block_connected(5, [0x55; 32], &[[0x01; 32], TXID]);
+        let status = mgr.status(&TXID).expect("tracked");
+        assert_eq!(status.state, "confirmed");
+        assert_eq!(status.height, Some(5));
+        assert_eq!(status.block_hash, Some(hex::encode([0x55; 32])));
+        assert!(mgr.due_for_rebroadcast(10_000).is_empty());
+
+        mgr.on_block_disconnected(6);
+        assert_eq!(mgr.status(&TXID).unwrap().state, "confirmed");
+        mgr.on_block_disconnected(5);
+        let entry = mgr.entry(&TXID).expect("tracked");
+        assert_eq!(entry.state, BroadcastState::Pending);
+        assert_eq!(entry.last_broadcast_at, None);
+        assert_eq!(mgr.due_for_rebroadcast(1_001).len(), 1);
+    }
+
+    #[test]
+    fn unconfirmed_transactions_expire_after_the_window() {
+        let mut mgr = submitted();
+        mgr.submit([0xbb; 32], &[4], 1, 1_000);
+        mgr.note_mempool(&[0xbb; 32], false);
+        assert_eq!(mgr.status(&[0xbb; 32]).unwrap().state, "evicted");
+        mgr.note_mempool(&[0xbb; 32], true);
+        assert_eq!(mgr.status(&[0xbb; 32]).unwrap().state, "pending");
+        mgr.note_mempool(&[0xbb; 32], false);
+
+        assert_eq!(mgr.due_for_rebroadcast(4_599).len(), 1);
+        assert!(mgr.due_for_rebroadcast(4_600).is_empty());
+        assert_eq!(mgr.status(&TXID).unwrap().state, "expired");
+        assert_eq!(mgr.status(&[0xbb; 32]).unwrap().state, "expired");
+        assert!(mgr.unconfirmed_txids().is_empty());
+    }
+
+    #[test]
+    fn new_peers_reports_each_connection_once() {
+        let mut mgr = submitted();
+        let peers = |names: &[&str]| names.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        assert_eq!(mgr.new_peers(&peers(&["a", "b"])), peers(&["a", "b"]));
+        assert_eq!(mgr.new_peers(&peers(&["a", "c"])), peers(&["c"]));
+        assert_eq!(mgr.new_peers(&peers(&["a", "b"])), peers(&["b"]));
+    }
+
+    #[test]
+    fn save_and_load_round_trip() {
+        let dir = unique_temp_path("rubin-node-broadcast-state");
+        let path = broadcast_state_path(&dir);
+        let mut mgr = submitted();
+        mgr.submit([0xbb; 32], &[4], 1, 1_001);
+        mgr.on_block_connected(7, [0x77; 32], &[[0xbb; 32]]);
+        mgr.synced_tip = Some((7, [0x77; 32]));
+        mgr.save(&path).expect("save");
+
+        let loaded = load_broadcast_manager(&path, CFG).expect("load");
+        assert_eq!(loaded.entries, mgr.entries);
+        assert_eq!(loaded.synced_tip, mgr.synced_tip);
+        let missing = load_broadcast_manager(dir.join("missing.json"), CFG).expect("missing");
+        assert!(missing.entries.is_empty());
+        fs::remove_dir_all(dir).expect("cleanup");
+    }
+
+    #[test]
+    fn sync_with_chain_follows_a_reorg() {
+        let dir = unique_temp_path("rubin-node-broadcast-reorg");
+        let store = BlockStore::open(block_store_path(&dir)).expect("open blockstore");
+        let cfg = default_sync_config(Some(POW_LIMIT), [0u8; 32], Some(chain_state_path(&dir)));
+        let mut engine = SyncEngine::new(ChainState::new(), Some(store), cfg).expect("new sync");
+        let (genesis, genesis_hash, ts) = genesis_info();
+        engine.apply_block(&genesis, None).expect("genesis");
+
+        let mut mgr = BroadcastManager::new(CFG);
+        mgr.sync_with_chain(&engine.block_store_snapshot().unwrap())
+            .expect("initial sync");
+
+        // The tracked "transaction" is the coinbase of the first branch,
+        // which the competing branch pays to a different address.
+        let a1 = coinbase_only_block(1, genesis_hash, ts + 1);
+        let a1_txid = parse_block_bytes(&a1).unwrap().txids[0];
+        mgr.submit(a1_txid, &[0x01], 0, 1_000);
+        engine.apply_block(&a1, None).expect("a1");
+        mgr.sync_with_chain(&engine.block_store_snapshot().unwrap())
+            .expect("sync a1");
+        assert_eq!(
+            mgr.entry(&a1_txid).unwrap().state,
+            BroadcastState::Confirmed {
+                height: 1,
+                block_hash: header_hash(&a1)
+            }
+        );
+
+        let mut other_addr = default_mine_address();
+        other_addr[1] = 0x01;
+        let b1 = block_with_txs_to(1, 0, genesis_hash, ts + 2, &other_addr, &[]);
+        let subsidy1 = rubin_consensus::block_subsidy(1, 0);
+        let b2 = block_with_txs_to(2, subsidy1, header_hash(&b1), ts + 3, &other_addr, &[]);
+        engine.apply_block_with_reorg(&b1, None).expect("b1");
+        engine.apply_block_with_reorg(&b2, None).expect("b2");
+        let store = engine.block_store_snapshot().unwrap();
+        assert_eq!(store.tip().unwrap(), Some((2, header_hash(&b2))));
+        mgr.sync_with_chain(&store).expect("sync reorg");
+
+        let entry = mgr.entry(&a1_txid).unwrap();
+        assert_eq!(entry.state, BroadcastState::Pending);
+        assert_eq!(entry.last_broadcast_at, None);
+        assert_eq!(mgr.due_for_rebroadcast(1_001).len(), 1);
+        fs::remove_dir_all(dir).expect("cleanup");
+    }
+}
@@ -0,0 +1,93 @@
+//! What this binary is: crate versions, git commit, build profile, crypto
+//! backend and the chain it is configured for. Served by `--version` and the
+//! `/get_info` RPC so deployment tooling can check a fleet is homogeneous.
+
+use rubin_consensus::{crypto_provider_info, CRATE_VERSION as CONSENSUS_CRATE_VERSION};
+use serde::Serialize;
+
+use crate::datadir::DATADIR_MANIFEST_VERSION;
+
+/// Bumped when a field is removed or changes meaning.
+pub const BUILD_INFO_VERSION: u64 = 1;
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct CryptoProviderJson {
+    pub name: String,
+    pub library_version: String,
+    pub fips_mode: String,
+    pub ml_dsa_87_available: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct BuildInfo {
+    pub build_info_version: u64,
+    pub node_version: String,
+    pub consensus_version: String,
+    /// Absent when the build had neither a git checkout nor `RUBIN_GIT_COMMIT`.
+    pub git_commit: Option<String>,
+    /// `debug` or `release`, from `debug_assertions`.
+    pub profile: String,
+    pub crypto_provider: CryptoProviderJson,
+    pub network: String,
+    pub chain_id_hex: String,
+    pub datadir_manifest_version: u32,
+}
+
+impl BuildInfo {
+    pub fn new(network: &str, chain_id: [u8; 32]) -> Self {
+        let provider = crypto_provider_info();
+        Self {
+            build_info_version: BUILD_INFO_VERSION,
+            node_version: env!("CARGO_PKG_VERSION").to_string(),
+            consensus_version: CONSENSUS_CRATE_VERSION.to_string(),
+            git_commit: option_env!("RUBIN_NODE_GIT_COMMIT").map(str::to_string),
+            profile: if cfg!(debug_assertions) {
+                "debug"
+            } else {
+                "release"
+            }
+            .to_string(),
+            crypto_provider: CryptoProviderJson {
+                name: provider.name.to_string(),
+                library_version: provider.library_version,
+                fips_mode: provider.fips_mode.to_string(),
+                ml_dsa_87_available: provider.ml_dsa_87_available,
+            },
+            network: network.to_string(),
+            chain_id_hex: hex::encode(chain_id),
+            datadir_manifest_version: DATADIR_MANIFEST_VERSION,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn build_info_serializes_every_field() {
+        let json = serde_json::to_value(BuildInfo::new("devnet", [0x5a; 32])).expect("json");
+        assert_eq!(json["build_info_version"], BUILD_INFO_VERSION);
+        assert_eq!(json["node_version"], env!("CARGO_PKG_VERSION"));
+        assert_eq!(json["consensus_version"], CONSENSUS_CRATE_VERSION);
+        assert!(json["git_commit"].is_string() || json["git_commit"].is_null());
+        assert!(matches!(
+            json["profile"].as_str(),
+            Some("debug" | "release")
+        ));
+        let provider = &json["crypto_provider"];
+        assert_eq!(provider["name"], "openssl");
+        assert!(provider["library_version"]
+            .as_str()
+            .is_some_and(|v| v.starts_with("OpenSSL")));
+        assert!(matches!(
+            provider["fips_mode"].as_str(),
+            Some("off" | "ready" | "only" | "invalid")
+        ));
+        assert!(provider["ml_dsa_87_available"].is_boolean());
+        assert_eq!(json["network"], "devnet");
+        assert_eq!(json["chain_id_hex"], "5a".repeat(32));
+        assert_eq!(json["datadir_manifest_version"], DATADIR_MANIFEST_VERSION);
+        assert_eq!(json.as_object().map(|o| o.len()), Some(9));
+    }
+}
@@ -1,17 +1,16 @@
 use std::cmp::Ordering;
-use std::collections::HashMap;
 use std::fs;
 use std::path::{Path, PathBuf};
 
 use rubin_consensus::{
-    block_hash,
-    connect_block_basic_in_memory_at_height_and_core_ext_deployments_with_suite_context,
-    encode_compact_size, parse_block_bytes, ConnectBlockBasicSummary, InMemoryChainState, Outpoint,
+    block_hash, canonical_outpoint_key, connect_block_basic_in_memory_at_height_with_chain_params,
+    hex_decode_max, parse_block_bytes, tx_weight_and_stats_public, utxo_set_hash, ChainParams,
+    ConnectBlockBasicSummary, InMemoryChainState, Outpoint, OutpointMap, ParsedBlock,
     RotationProvider, SuiteRegistry, UtxoEntry,
 };
 use serde::{Deserialize, Serialize};
-use sha3::{Digest, Sha3_256};
 
+use crate::blockstore::ChainAnchors;
 use crate::genesis::validate_incoming_chain_id;
 use crate::io_utils::{parse_hex32, write_file_atomic};
 
@@ -25,7 +24,7 @@ pub struct ChainState {
     pub height: u64,
     pub tip_hash: [u8; 32],
     pub already_generated: u64,
-    pub utxos: HashMap<Outpoint, UtxoEntry>,
+    pub utxos: OutpointMap<UtxoEntry>,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -42,9 +41,53 @@ pub struct ChainStateConnectSummary {
     pub already_generated: u64,
     pub already_generated_n1: u64,
     pub utxo_count: u64,
+    pub stats: BlockStats,
     pub canonical_applied_blocks: Vec<CanonicalAppliedBlock>,
 }
 
+/// Per-block economic and resource statistics, persisted in the blockstore
+/// so tooling can report them without replaying blocks. `Default` is the
+/// empty aggregate used for side-chain summaries and cumulative sums.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlockStats {
+    pub total_fees: u64,
+    pub total_weight: u64,
+    pub anchor_bytes: u64,
+    pub tx_count: u64,
+    pub coinbase_value: u64,
+}
+
+impl BlockStats {
+    pub fn from_parsed_block(parsed: &ParsedBlock, total_fees: u64) -> Result<Self, String> {
+        let mut stats = BlockStats {
+            total_fees,
+            tx_count: parsed.txs.len() as u64,
+            ..BlockStats::default()
+        };
+        for tx in &parsed.txs {
+            let (weight, _da, anchor_bytes) =
+                tx_weight_and_stats_public(tx).map_err(|e| e.to_string())?;
+            stats.total_weight = stats.total_weight.saturating_add(weight);
+            stats.anchor_bytes = stats.anchor_bytes.saturating_add(anchor_bytes);
+        }
+        if let Some(coinbase) = parsed.txs.first() {
+            for out in &coinbase.outputs {
+                stats.coinbase_value = stats.coinbase_value.saturating_add(out.value);
+            }
+        }
+        Ok(stats)
+    }
+
+    /// Adds `other` into `self`; used to build cumulative chain totals.
+    pub fn accumulate(&mut self, other: &BlockStats) {
+        self.total_fees = self.total_fees.saturating_add(other.total_fees);
+        self.total_weight = self.total_weight.saturating_add(other.total_weight);
+        self.anchor_bytes = self.anchor_bytes.saturating_add(other.anchor_bytes);
+        self.tx_count = self.tx_count.saturating_add(other.tx_count);
+        self.coinbase_value = self.coinbase_value.saturating_add(other.coinbase_value);
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 struct ChainStateDisk {
     version: u32,
@@ -73,7 +116,7 @@ impl ChainState {
             height: 0,
             tip_hash: [0u8; 32],
             already_generated: 0,
-            utxos: HashMap::new(),
+            utxos: OutpointMap::default(),
         }
     }
 
@@ -135,6 +178,30 @@ impl ChainState {
         chain_id: [u8; 32],
         rotation: Option<&dyn RotationProvider>,
         registry: Option<&SuiteRegistry>,
+    ) -> Result<ChainStateConnectSummary, String> {
+        self.connect_block_with_chain_params(
+            block_bytes,
+            expected_target,
+            prev_timestamps,
+            chain_id,
+            rotation,
+            registry,
+            &ChainParams::V1,
+        )
+    }
+
+    /// `connect_block_with_suite_context` under the chain instance's
+    /// consensus parameters (`SyncConfig::chain_params`).
+    #[allow(clippy::too_many_arguments)]
+    pub fn connect_block_with_chain_params(
+        &mut self,
+        block_bytes: &[u8],
+        expected_target: Option<[u8; 32]>,
+        prev_timestamps: Option<&[u64]>,
+        chain_id: [u8; 32],
+        rotation: Option<&dyn RotationProvider>,
+        registry: Option<&SuiteRegistry>,
+        params: &ChainParams,
     ) -> Result<ChainStateConnectSummary, String> {
         let (block_height, expected_prev_hash) = self.next_block_context()?;
         validate_incoming_chain_id(block_height, chain_id)?;
@@ -144,7 +211,7 @@ impl ChainState {
         };
 
         let connect_summary: ConnectBlockBasicSummary =
-            connect_block_basic_in_memory_at_height_and_core_ext_deployments_with_suite_context(
+            connect_block_basic_in_memory_at_height_with_chain_params(
                 block_bytes,
                 expected_prev_hash,
                 expected_target,
@@ -154,11 +221,13 @@ impl ChainState {
                 chain_id,
                 rotation,
                 registry,
+                params,
             )
             .map_err(|e| e.to_string())?;
 
         let parsed = parse_block_bytes(block_bytes).map_err(|e| e.to_string())?;
         let tip_hash = block_hash(&parsed.header_bytes).map_err(|e| e.to_string())?;
+        let stats = BlockStats::from_parsed_block(&parsed, connect_summary.sum_fees)?;
 
         self.has_tip = true;
         self.height = block_height;
@@ -176,6 +245,7 @@ impl ChainState {
             already_generated_n1: u64::try_from(connect_summary.already_generated_n1)
                 .map_err(|_| "already_generated_n1 overflow".to_string())?,
             utxo_count: connect_summary.utxo_count,
+            stats,
             canonical_applied_blocks: vec![CanonicalAppliedBlock {
                 hash: tip_hash,
                 block_bytes: block_bytes.to_vec(),
@@ -236,6 +306,15 @@ impl ChainState {
         outpoints
     }
 
+    /// Iterates the UTXO set in the canonical order used by `utxo_set_hash`
+    /// (see `canonical_outpoint_key`), independent of `HashMap` layout, so
+    /// cross-client dumps and diffs are reproducible.
+    pub fn iter_utxos(&self) -> impl Iterator<Item = (&Outpoint, &UtxoEntry)> {
+        let mut entries: Vec<(&Outpoint, &UtxoEntry)> = self.utxos.iter().collect();
+        entries.sort_by_cached_key(|(op, _)| canonical_outpoint_key(op));
+        entries.into_iter()
+    }
+
     /// Returns how many current UTXOs explicitly bind to suite_id.
     pub fn utxo_exposure_count_by_suite_id(&self, suite_id: u8) -> u64 {
         self.utxos
@@ -281,7 +360,7 @@ pub(crate) fn copy_utxo_entry(entry: &UtxoEntry) -> UtxoEntry {
 /// weaker claim — but in practice both `std` and `hashbrown` reuse the
 /// existing layout, which is the implementation reason for picking
 /// `src.clone()` over a hand-rolled re-insert.
-pub(crate) fn copy_utxo_set(src: &HashMap<Outpoint, UtxoEntry>) -> HashMap<Outpoint, UtxoEntry> {
+pub(crate) fn copy_utxo_set(src: &OutpointMap<UtxoEntry>) -> OutpointMap<UtxoEntry> {
     src.clone()
 }
 
@@ -314,6 +393,57 @@ pub fn load_chain_state<P: AsRef<Path>>(path: P) -> Result<ChainState, String> {
     chain_state_from_disk(disk)
 }
 
+/// [`load_chain_state`] cross-checked against the blockstore's chain
+/// anchors, which are written together with the canonical tip and so win
+/// any disagreement: a snapshot past the connected chain, or one at the
+/// anchored block with a different `utxo_set_hash`, is logged and replaced
+/// by an empty state for startup reconcile to rebuild from the blockstore.
+pub fn load_chain_state_with_anchors<P: AsRef<Path>>(
+    path: P,
+    anchors: Option<&ChainAnchors>,
+) -> Result<ChainState, String> {
+    let path = path.as_ref();
+    let state = load_chain_state(path)?;
+    let Some(reason) = anchors.and_then(|anchors| anchor_mismatch(&state, anchors)) else {
+        return Ok(state);
+    };
+    eprintln!(
+        "chainstate: {} disagrees with blockstore anchors ({reason}); rebuilding from blockstore",
+        path.display()
+    );
+    Ok(ChainState::new())
+}
+
+fn anchor_mismatch(state: &ChainState, anchors: &ChainAnchors) -> Option<String> {
+    if !state.has_tip {
+        return None;
+    }
+    let best = anchors.best_block;
+    if state.height > best.height {
+        return Some(format!(
+            "height {} is above best block height {}",
+            state.height, best.height
+        ));
+    }
+    if state.height == best.height && state.tip_hash != best.hash {
+        return Some(format!(
+            "tip at height {} is not the best block",
+            state.height
+        ));
+    }
+    let (block, utxo_set_hash) = anchors.utxo?;
+    if state.height == block.height
+        && state.tip_hash == block.hash
+        && state.utxo_set_hash() != utxo_set_hash
+    {
+        return Some(format!(
+            "utxo_set_hash at height {} does not match the anchored hash",
+            state.height
+        ));
+    }
+    None
+}
+
 fn state_to_disk(s: &ChainState) -> Result<ChainStateDisk, String> {
     let mut utxos: Vec<UtxoDiskEntry> = s
         .utxos
@@ -343,37 +473,6 @@ fn state_to_disk(s: &ChainState) -> Result<ChainStateDisk, String> {
     })
 }
 
-fn utxo_set_hash(utxos: &HashMap<Outpoint, UtxoEntry>) -> [u8; 32] {
-    let mut items: Vec<([u8; 36], &UtxoEntry)> = Vec::with_capacity(utxos.len());
-    for (outpoint, entry) in utxos {
-        let mut key = [0u8; 36];
-        key[..32].copy_from_slice(&outpoint.txid);
-        key[32..].copy_from_slice(&outpoint.vout.to_le_bytes());
-        items.push((key, entry));
-    }
-    // sort_unstable_by avoids decorate/sort/undecorate copies of the
-    // [u8; 36] key that sort_by_key/sort_unstable_by_key would do —
-    // important on the consensus digest path with large UTXO sets.
-    #[allow(clippy::unnecessary_sort_by)]
-    items.sort_unstable_by(|a, b| a.0.cmp(&b.0));
-
-    let mut buf = Vec::with_capacity(UTXO_SET_HASH_DST.len() + 8 + items.len() * 64);
-    buf.extend_from_slice(UTXO_SET_HASH_DST);
-    buf.extend_from_slice(&(items.len() as u64).to_le_bytes());
-
-    for (key, entry) in items {
-        buf.extend_from_slice(&key);
-        buf.extend_from_slice(&entry.value.to_le_bytes());
-        buf.extend_from_slice(&entry.covenant_type.to_le_bytes());
-        encode_compact_size(entry.covenant_data.len() as u64, &mut buf);
-        buf.extend_from_slice(&entry.covenant_data);
-        buf.extend_from_slice(&entry.creation_height.to_le_bytes());
-        buf.push(u8::from(entry.created_by_coinbase));
-    }
-
-    Sha3_256::digest(&buf).into()
-}
-
 fn explicit_suite_id_for_utxo_entry(entry: &UtxoEntry) -> Option<u8> {
     match entry.covenant_type {
         rubin_consensus::constants::COV_TYPE_P2PK
@@ -396,7 +495,7 @@ fn chain_state_from_disk(disk: ChainStateDisk) -> Result<ChainState, String> {
     }
 
     let tip_hash = parse_hex32("tip_hash", &disk.tip_hash)?;
-    let mut utxos = HashMap::with_capacity(disk.utxos.len());
+    let mut utxos = OutpointMap::with_capacity_and_hasher(disk.utxos.len(), Default::default());
     for item in disk.utxos {
         let txid = parse_hex32("utxo.txid", &item.txid)?;
         let covenant_data = parse_hex("utxo.covenant_data", &item.covenant_data)?;
@@ -432,16 +531,11 @@ fn chain_state_from_disk(disk: ChainStateDisk) -> Result<ChainState, String> {
 }
 
 fn parse_hex(name: &str, value: &str) -> Result<Vec<u8>, String> {
-    let trimmed = value.trim();
-    if !trimmed.len().is_multiple_of(2) {
-        return Err(format!("{name}: odd-length hex"));
-    }
-    hex::decode(trimmed).map_err(|e| format!("{name}: {e}"))
+    hex_decode_max(value, usize::MAX, name).map_err(|e| e.to_string())
 }
 
 #[cfg(test)]
 mod tests {
-    use std::collections::HashMap;
 
     use crate::coinbase::{build_coinbase_tx, default_mine_address};
     use crate::genesis::{devnet_genesis_block_bytes, devnet_genesis_chain_id};
@@ -454,10 +548,12 @@ mod tests {
     use rubin_consensus::constants::POW_LIMIT;
     use rubin_consensus::merkle::{witness_commitment_hash, witness_merkle_root_wtxids};
     use rubin_consensus::{
-        apply_non_coinbase_tx_basic_with_mtp, block_hash, block_subsidy, encode_compact_size,
-        merkle_root_txids, parse_block_bytes, parse_tx, Outpoint, UtxoEntry, BLOCK_HEADER_BYTES,
+        apply_non_coinbase_tx_basic_with_mtp, block_hash, block_subsidy,
+        canonical_utxo_entry_bytes, encode_compact_size, merkle_root_txids, parse_block_bytes,
+        parse_tx, Outpoint, OutpointMap, UtxoEntry, BLOCK_HEADER_BYTES,
     };
     use serde::Deserialize;
+    use sha3::Digest;
 
     const GENESIS_ONLY_STATE_DIGEST_HEX: &str =
         "8b172fb3a5e70b56de9ae78ce750c04eccbc4dd8b3be55751252e5a1b4f2e752";
@@ -541,8 +637,8 @@ mod tests {
         out
     }
 
-    fn fixture_utxos_to_map(items: &[FixtureUtxo]) -> HashMap<Outpoint, UtxoEntry> {
-        let mut out = HashMap::with_capacity(items.len());
+    fn fixture_utxos_to_map(items: &[FixtureUtxo]) -> OutpointMap<UtxoEntry> {
+        let mut out = OutpointMap::with_capacity_and_hasher(items.len(), Default::default());
         for item in items {
             let txid = parse_hex32_test("fixture utxo txid", &item.txid);
             let covenant_data =
@@ -651,6 +747,65 @@ mod tests {
         std::fs::remove_dir_all(&dir).expect("cleanup");
     }
 
+    #[test]
+    fn load_chain_state_with_anchors_prefers_the_anchors_on_mismatch() {
+        use super::load_chain_state_with_anchors;
+        use crate::blockstore::{ChainAnchor, ChainAnchors};
+
+        let dir = unique_temp_path("rubin-chainstate-anchors");
+        let path = chain_state_path(&dir);
+        let mut st = ChainState::new();
+        st.has_tip = true;
+        st.height = 3;
+        st.tip_hash = [0x33; 32];
+        st.save(&path).expect("save");
+
+        let tip = ChainAnchor {
+            height: 3,
+            hash: [0x33; 32],
+        };
+        let anchors = ChainAnchors {
+            best_header: tip,
+            best_block: tip,
+            utxo: Some((tip, st.utxo_set_hash())),
+        };
+        let load = |anchors: &ChainAnchors| {
+            load_chain_state_with_anchors(&path, Some(anchors)).expect("load")
+        };
+        assert_eq!(load(&anchors), st);
+        assert_eq!(
+            load_chain_state_with_anchors(&path, None).expect("load"),
+            st
+        );
+
+        let behind = ChainAnchor {
+            height: 2,
+            hash: [0x22; 32],
+        };
+        for mismatched in [
+            ChainAnchors {
+                utxo: Some((tip, [0xEE; 32])),
+                ..anchors
+            },
+            ChainAnchors {
+                best_header: behind,
+                best_block: behind,
+                utxo: None,
+            },
+            ChainAnchors {
+                best_block: ChainAnchor {
+                    height: 3,
+                    hash: [0x44; 32],
+                },
+                ..anchors
+            },
+        ] {
+            assert_eq!(load(&mismatched), ChainState::new());
+        }
+
+        std::fs::remove_dir_all(&dir).expect("cleanup");
+    }
+
     /// `ChainState::save` must accept a bare-filename path (e.g.
     /// `"chainstate.json"`) without the pre-write `create_dir_all`
     /// running against `""`. An earlier version of `save` called
@@ -1080,7 +1235,7 @@ mod tests {
 
     #[test]
     fn copy_utxo_set_deep_copies_all_entries() {
-        let mut src = HashMap::new();
+        let mut src = OutpointMap::default();
         src.insert(sample_outpoint(1), sample_entry(10, 0x11));
         src.insert(sample_outpoint(2), sample_entry(20, 0x22));
 
@@ -1140,4 +1295,31 @@ mod tests {
         } // owned dropped here
         assert_eq!(st.utxos.get(&op).expect("still present").value, 42);
     }
+
+    #[test]
+    fn iter_utxos_follows_canonical_key_and_reproduces_utxo_set_hash() {
+        // vout 256 sorts before vout 1 under the little-endian canonical key.
+        let mut st = ChainState::new();
+        for (byte, vout) in [(2u8, 0u32), (1, 1), (1, 256), (1, 0)] {
+            let op = Outpoint {
+                txid: [byte; 32],
+                vout,
+            };
+            st.utxos.insert(op, sample_entry(u64::from(vout) + 1, byte));
+        }
+        let order: Vec<(u8, u32)> = st
+            .iter_utxos()
+            .map(|(op, _)| (op.txid[0], op.vout))
+            .collect();
+        assert_eq!(order, vec![(1, 0), (1, 256), (1, 1), (2, 0)]);
+
+        // Digest rebuilt from the ordered scan matches the HashMap digest.
+        let mut preimage = b"RUBINv1-utxo-set-hash/".to_vec();
+        preimage.extend_from_slice(&(st.utxos.len() as u64).to_le_bytes());
+        for (op, entry) in st.iter_utxos() {
+            preimage.extend_from_slice(&canonical_utxo_entry_bytes(op, entry));
+        }
+        let scanned: [u8; 32] = sha3::Sha3_256::digest(&preimage).into();
+        assert_eq!(scanned, st.utxo_set_hash());
+    }
 }
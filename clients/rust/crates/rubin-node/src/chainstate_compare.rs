@@ -0,0 +1,346 @@
+//! Cross-client chainstate comparison.
+//!
+//! Each client emits a [`ChainStateSummary`] (tip height/hash, `utxo_set_hash`,
+//! and optionally the full UTXO list in canonical order); a peer summary is
+//! then diffed against the local chainstate. Tip disagreement is classified
+//! ahead of UTXO disagreement so an operator sees the most fundamental
+//! divergence first.
+//!
+//! Ordering contract: every list in a summary or report is built from
+//! `ChainState::iter_utxos` or a `BTreeMap`, never from `HashMap` iteration,
+//! so the same chainstate always renders to byte-identical JSON.
+
+use std::collections::BTreeMap;
+
+use rubin_consensus::{Outpoint, UtxoEntry};
+use serde::{Deserialize, Serialize};
+
+use crate::chainstate::ChainState;
+
+pub const CHAIN_STATE_SUMMARY_VERSION: u64 = 1;
+
+/// Default cap on UTXO entries listed when set hashes disagree.
+pub const DEFAULT_CHAIN_STATE_DIFF_LIMIT: usize = 16;
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChainStateSummaryUtxo {
+    pub txid: String,
+    pub vout: u32,
+    pub value: u64,
+    pub covenant_type: u16,
+    pub covenant_data: String,
+    pub creation_height: u64,
+    pub created_by_coinbase: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChainStateSummary {
+    pub summary_version: u64,
+    pub has_tip: bool,
+    pub tip_height: u64,
+    pub tip_hash_hex: String,
+    pub utxo_set_hash_hex: String,
+    pub utxo_count: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub utxos: Option<Vec<ChainStateSummaryUtxo>>,
+}
+
+impl ChainStateSummary {
+    pub fn from_chain_state(state: &ChainState, include_utxos: bool) -> Self {
+        let utxos = include_utxos.then(|| {
+            state
+                .iter_utxos()
+                .map(|(op, e)| summary_utxo(op, e))
+                .collect()
+        });
+        Self {
+            summary_version: CHAIN_STATE_SUMMARY_VERSION,
+            has_tip: state.has_tip,
+            tip_height: state.height,
+            tip_hash_hex: hex::encode(state.tip_hash),
+            utxo_set_hash_hex: hex::encode(state.utxo_set_hash()),
+            utxo_count: state.utxos.len() as u64,
+            utxos,
+        }
+    }
+}
+
+fn summary_utxo(outpoint: &Outpoint, entry: &UtxoEntry) -> ChainStateSummaryUtxo {
+    ChainStateSummaryUtxo {
+        txid: hex::encode(outpoint.txid),
+        vout: outpoint.vout,
+        value: entry.value,
+        covenant_type: entry.covenant_type,
+        covenant_data: hex::encode(&entry.covenant_data),
+        creation_height: entry.creation_height,
+        created_by_coinbase: entry.created_by_coinbase,
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChainStateCompareStatus {
+    Match,
+    TipMismatch,
+    UtxoMismatch,
+}
+
+impl ChainStateCompareStatus {
+    /// Process exit code for `rubin-node --compare-chainstate`. Divergence
+    /// codes sit above the failure classes (1-6) so a verdict is never read
+    /// as an input or consensus error.
+    pub fn exit_code(self) -> i32 {
+        match self {
+            Self::Match => 0,
+            Self::TipMismatch => 7,
+            Self::UtxoMismatch => 8,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct ChainStateUtxoDiff {
+    pub txid: String,
+    pub vout: u32,
+    pub local: Option<ChainStateSummaryUtxo>,
+    pub other: Option<ChainStateSummaryUtxo>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct ChainStateCompareReport {
+    pub status: ChainStateCompareStatus,
+    pub mismatched_fields: Vec<&'static str>,
+    pub local: ChainStateSummary,
+    pub other: ChainStateSummary,
+    /// `None` when hashes agree or the other summary carried no UTXO list.
+    pub utxo_diffs: Option<Vec<ChainStateUtxoDiff>>,
+    pub utxo_diffs_truncated: bool,
+}
+
+/// Diffs `state` against a summary produced by another client. When the UTXO
+/// set hashes disagree and `other` includes its UTXO list, up to `diff_limit`
+/// differing entries are reported in canonical (txid, vout) order.
+pub fn compare_chain_state(
+    state: &ChainState,
+    other: &ChainStateSummary,
+    diff_limit: usize,
+) -> ChainStateCompareReport {
+    let local = ChainStateSummary::from_chain_state(state, false);
+    let mut mismatched_fields = Vec::new();
+    if local.has_tip != other.has_tip {
+        mismatched_fields.push("has_tip");
+    }
+    if local.tip_height != other.tip_height {
+        mismatched_fields.push("tip_height");
+    }
+    if !local.tip_hash_hex.eq_ignore_ascii_case(&other.tip_hash_hex) {
+        mismatched_fields.push("tip_hash_hex");
+    }
+    let tip_mismatch = !mismatched_fields.is_empty();
+    let utxo_hash_mismatch = !local
+        .utxo_set_hash_hex
+        .eq_ignore_ascii_case(&other.utxo_set_hash_hex);
+    if utxo_hash_mismatch {
+        mismatched_fields.push("utxo_set_hash_hex");
+    }
+    if local.utxo_count != other.utxo_count {
+        mismatched_fields.push("utxo_count");
+    }
+
+    let status = if tip_mismatch {
+        ChainStateCompareStatus::TipMismatch
+    } else if utxo_hash_mismatch {
+        ChainStateCompareStatus::UtxoMismatch
+    } else {
+        ChainStateCompareStatus::Match
+    };
+
+    let (utxo_diffs, utxo_diffs_truncated) = match (&other.utxos, utxo_hash_mismatch) {
+        (Some(other_utxos), true) => {
+            let (diffs, truncated) = diff_utxos(state, other_utxos, diff_limit);
+            (Some(diffs), truncated)
+        }
+        _ => (None, false),
+    };
+
+    ChainStateCompareReport {
+        status,
+        mismatched_fields,
+        local,
+        other: ChainStateSummary {
+            utxos: None,
+            ..other.clone()
+        },
+        utxo_diffs,
+        utxo_diffs_truncated,
+    }
+}
+
+fn diff_utxos(
+    state: &ChainState,
+    other: &[ChainStateSummaryUtxo],
+    limit: usize,
+) -> (Vec<ChainStateUtxoDiff>, bool) {
+    type Key = (String, u32);
+    let mut merged: BTreeMap<Key, (Option<ChainStateSummaryUtxo>, Option<ChainStateSummaryUtxo>)> =
+        BTreeMap::new();
+    for (outpoint, entry) in state.iter_utxos() {
+        let row = summary_utxo(outpoint, entry);
+        let key = (row.txid.clone(), row.vout);
+        merged.entry(key).or_default().0 = Some(row);
+    }
+    for row in other {
+        let mut row = row.clone();
+        row.txid.make_ascii_lowercase();
+        row.covenant_data.make_ascii_lowercase();
+        let key = (row.txid.clone(), row.vout);
+        merged.entry(key).or_default().1 = Some(row);
+    }
+
+    let mut diffs = Vec::new();
+    let mut truncated = false;
+    for ((txid, vout), (local, other)) in merged {
+        if local == other {
+            continue;
+        }
+        if diffs.len() == limit {
+            truncated = true;
+            break;
+        }
+        diffs.push(ChainStateUtxoDiff {
+            txid,
+            vout,
+            local,
+            other,
+        });
+    }
+    (diffs, truncated)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rubin_consensus::constants::COV_TYPE_P2PK;
+
+    fn state_with(entries: &[(u8, u32, u64)]) -> ChainState {
+        let mut state = ChainState::new();
+        state.has_tip = true;
+        state.height = 7;
+        state.tip_hash = [0xab; 32];
+        for (fill, vout, value) in entries {
+            state.utxos.insert(
+                Outpoint {
+                    txid: [*fill; 32],
+                    vout: *vout,
+                },
+                UtxoEntry {
+                    value: *value,
+                    covenant_type: COV_TYPE_P2PK,
+                    covenant_data: vec![0x01; 33],
+                    creation_height: 1,
+                    created_by_coinbase: false,
+                },
+            );
+        }
+        state
+    }
+
+    #[test]
+    fn summary_json_is_byte_identical_across_insertion_order() {
+        // vout 1 vs 256 differ only in byte order, and 64 entries are enough
+        // for hash order to differ from canonical order.
+        let mut entries: Vec<(u8, u32, u64)> = (0..64u8)
+            .flat_map(|fill| [(fill, 1, u64::from(fill)), (fill, 256, 1000)])
+            .collect();
+        let forward = state_with(&entries);
+        entries.reverse();
+        entries.rotate_left(37);
+        let shuffled = state_with(&entries);
+
+        let render = |state: &ChainState| {
+            serde_json::to_vec(&ChainStateSummary::from_chain_state(state, true)).expect("json")
+        };
+        assert_eq!(render(&forward), render(&forward));
+        assert_eq!(render(&forward), render(&shuffled));
+    }
+
+    #[test]
+    fn identical_states_match() {
+        let state = state_with(&[(1, 0, 10), (2, 1, 20)]);
+        let other = ChainStateSummary::from_chain_state(&state, true);
+        let report = compare_chain_state(&state, &other, DEFAULT_CHAIN_STATE_DIFF_LIMIT);
+        assert_eq!(report.status, ChainStateCompareStatus::Match);
+        assert!(report.mismatched_fields.is_empty());
+        assert_eq!(report.utxo_diffs, None);
+        assert_eq!(report.status.exit_code(), 0);
+    }
+
+    #[test]
+    fn tip_divergence_classified_ahead_of_utxo_divergence() {
+        let state = state_with(&[(1, 0, 10)]);
+        let mut other_state = state_with(&[(1, 0, 11)]);
+        other_state.height = 8;
+        other_state.tip_hash = [0xcd; 32];
+        let other = ChainStateSummary::from_chain_state(&other_state, false);
+        let report = compare_chain_state(&state, &other, DEFAULT_CHAIN_STATE_DIFF_LIMIT);
+        assert_eq!(report.status, ChainStateCompareStatus::TipMismatch);
+        assert_eq!(
+            report.mismatched_fields,
+            vec!["tip_height", "tip_hash_hex", "utxo_set_hash_hex"]
+        );
+        assert_eq!(report.status.exit_code(), 7);
+    }
+
+    #[test]
+    fn utxo_divergence_lists_first_k_entries_in_canonical_order() {
+        let state = state_with(&[(1, 0, 10), (2, 0, 20), (3, 0, 30), (4, 0, 40)]);
+        // Other side: (1,0) matches, (2,0) value differs, (3,0) missing,
+        // (5,0) extra, (4,0) matches.
+        let other_state = state_with(&[(1, 0, 10), (2, 0, 21), (4, 0, 40), (5, 0, 50)]);
+        let other = ChainStateSummary::from_chain_state(&other_state, true);
+
+        let report = compare_chain_state(&state, &other, 2);
+        assert_eq!(report.status, ChainStateCompareStatus::UtxoMismatch);
+        assert_eq!(report.mismatched_fields, vec!["utxo_set_hash_hex"]);
+        assert_eq!(report.status.exit_code(), 8);
+        let diffs = report.utxo_diffs.expect("diffs");
+        assert!(report.utxo_diffs_truncated);
+        assert_eq!(diffs.len(), 2);
+        assert_eq!(diffs[0].txid, hex::encode([2u8; 32]));
+        assert_eq!(diffs[0].local.as_ref().map(|u| u.value), Some(20));
+        assert_eq!(diffs[0].other.as_ref().map(|u| u.value), Some(21));
+        assert_eq!(diffs[1].txid, hex::encode([3u8; 32]));
+        assert!(diffs[1].other.is_none());
+
+        let report = compare_chain_state(&state, &other, 16);
+        let diffs = report.utxo_diffs.expect("diffs");
+        assert!(!report.utxo_diffs_truncated);
+        assert_eq!(diffs.len(), 3);
+        assert!(diffs[2].local.is_none());
+        assert_eq!(diffs[2].txid, hex::encode([5u8; 32]));
+    }
+
+    #[test]
+    fn utxo_divergence_without_other_list_has_no_entry_diff() {
+        let state = state_with(&[(1, 0, 10)]);
+        let other = ChainStateSummary::from_chain_state(&state_with(&[(1, 0, 99)]), false);
+        let report = compare_chain_state(&state, &other, 16);
+        assert_eq!(report.status, ChainStateCompareStatus::UtxoMismatch);
+        assert_eq!(report.utxo_diffs, None);
+    }
+
+    #[test]
+    fn summary_round_trips_through_json_and_accepts_uppercase_hex() {
+        let state = state_with(&[(9, 3, 90)]);
+        let summary = ChainStateSummary::from_chain_state(&state, true);
+        let encoded = serde_json::to_string(&summary).expect("encode");
+        let decoded: ChainStateSummary = serde_json::from_str(&encoded).expect("decode");
+        assert_eq!(decoded, summary);
+
+        let mut upper = decoded;
+        upper.tip_hash_hex.make_ascii_uppercase();
+        upper.utxo_set_hash_hex.make_ascii_uppercase();
+        let report = compare_chain_state(&state, &upper, 16);
+        assert_eq!(report.status, ChainStateCompareStatus::Match);
+    }
+}
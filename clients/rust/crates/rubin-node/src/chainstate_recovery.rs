@@ -54,7 +54,6 @@
 use crate::blockstore::BlockStore;
 use crate::chainstate::{ChainState, ChainStateConnectSummary};
 use crate::sync::SyncConfig;
-use rubin_consensus::parse_block_header_bytes;
 
 /// Snapshot cadence: persist `ChainState` to disk on every block until
 /// the UTxO set crosses [`CHAIN_STATE_SNAPSHOT_SMALL_UTXO_CUTOFF`], then
@@ -357,13 +356,14 @@ pub fn reconcile_chain_state_with_block_store(
             ));
         }
         let prev_timestamps = prev_timestamps_from_store(store, height)?;
-        state.connect_block_with_suite_context(
+        state.connect_block_with_chain_params(
             &block_bytes,
             cfg.expected_target,
             prev_timestamps.as_deref(),
             cfg.chain_id,
             rotation,
             registry,
+            &cfg.chain_params,
         )?;
         changed = true;
     }
@@ -371,40 +371,30 @@ pub fn reconcile_chain_state_with_block_store(
 }
 
 /// Build the prev-timestamps window used by `connect_block` consensus
-/// validation during replay. Returns up to 11 timestamps (the BIP-113
-/// MTP window) for the canonical chain ending at `height - 1`. A
-/// `None` return means `height == 0` (genesis), which has no prev
-/// window.
-///
-/// Standalone helper instead of going through `SyncEngine.
-/// prev_timestamps_for_height` because reconcile runs before the
-/// sync engine exists; both implementations are functionally
-/// identical and both will diverge into a shared helper if a third
-/// caller appears.
+/// validation during replay, from the canonical chain ending at
+/// `height - 1`. A `None` return means `height == 0` (genesis), which has
+/// no prev window. Reconcile runs before the sync engine exists, so this
+/// goes to `BlockStore::validation_context` directly.
 fn prev_timestamps_from_store(store: &BlockStore, height: u64) -> Result<Option<Vec<u64>>, String> {
     if height == 0 {
         return Ok(None);
     }
-    let window_len = height.min(11);
-    let mut out = Vec::with_capacity(window_len as usize);
-    for offset in 0..window_len {
-        let h = height - 1 - offset;
-        let hash = store.canonical_hash(h)?.ok_or_else(|| {
-            format!(
-                "missing canonical hash at height {h} for timestamp context (next_height={height})"
-            )
-        })?;
-        let header_bytes = store.get_header_by_hash(hash)?;
-        let header = parse_block_header_bytes(&header_bytes).map_err(|e| e.to_string())?;
-        out.push(header.timestamp);
-    }
-    Ok(Some(out))
+    let parent_height = height - 1;
+    let parent_hash = store.canonical_hash(parent_height)?.ok_or_else(|| {
+        format!(
+            "missing canonical hash at height {parent_height} for timestamp context (next_height={height})"
+        )
+    })?;
+    Ok(store
+        .validation_context(parent_hash, height, None)?
+        .prev_timestamps())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::blockstore::block_store_path;
+    use crate::chainstate::BlockStats;
     use crate::genesis::{devnet_genesis_block_bytes, devnet_genesis_chain_id};
     use crate::io_utils::unique_temp_path;
     use crate::sync::{default_sync_config, SyncEngine};
@@ -551,10 +541,10 @@ mod tests {
         }
         if omit != "undo" {
             // Build a real `BlockUndo` and serialise via the
-            // production marshal helper so the on-disk JSON shape
-            // tracks the current `BlockUndoDisk` schema; using a
-            // hand-written JSON literal would silently drift on any
-            // future schema change and pass for the wrong reason.
+            // production marshal helper so the on-disk record tracks
+            // the current undo encoding; using a hand-written literal
+            // would silently drift on any future format change and
+            // pass for the wrong reason.
             let raw = crate::undo::marshal_block_undo(&crate::undo::BlockUndo {
                 block_height: 1,
                 previous_already_generated: 0,
@@ -937,6 +927,7 @@ mod tests {
                     already_generated: 0,
                     already_generated_n1: 0,
                     utxo_count: 0,
+                    stats: BlockStats::default(),
                     canonical_applied_blocks: Vec::new(),
                 }),
             ),
@@ -973,6 +964,7 @@ mod tests {
                     already_generated: 0,
                     already_generated_n1: 0,
                     utxo_count: small.utxos.len() as u64,
+                    stats: BlockStats::default(),
                     canonical_applied_blocks: Vec::new(),
                 }),
             ),
@@ -1011,6 +1003,7 @@ mod tests {
                     already_generated: 0,
                     already_generated_n1: 0,
                     utxo_count: large.utxos.len() as u64,
+                    stats: BlockStats::default(),
                     canonical_applied_blocks: Vec::new(),
                 }),
             ),
@@ -1027,6 +1020,7 @@ mod tests {
                     already_generated: 0,
                     already_generated_n1: 0,
                     utxo_count: large.utxos.len() as u64,
+                    stats: BlockStats::default(),
                     canonical_applied_blocks: Vec::new(),
                 }),
             ),
@@ -1043,6 +1037,7 @@ mod tests {
                     already_generated: 0,
                     already_generated_n1: 0,
                     utxo_count: large.utxos.len() as u64,
+                    stats: BlockStats::default(),
                     canonical_applied_blocks: Vec::new(),
                 }),
             ),
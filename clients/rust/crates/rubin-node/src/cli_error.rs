@@ -0,0 +1,124 @@
+//! Exit-code contract of `rubin-node`. Every mode returns
+//! `Result<Output, CliError>` to `run`, and the class of a failure alone
+//! picks the exit code:
+//!
+//! | code | class |
+//! |------|-------|
+//! | 0 | success |
+//! | 1 | runtime failure outside the classes below (signals, sockets, encoding) |
+//! | 2 | usage: unknown or malformed flags, missing or conflicting flags |
+//! | 3 | input: unparseable hex, JSON or genesis/input files |
+//! | 4 | consensus rejection; the canonical `TX_ERR_*` / `BLOCK_ERR_*` code is on stderr, or in `error_code` under `--json` |
+//! | 5 | store: datadir, chainstate or blockstore missing, locked or unreadable |
+//! | 6 | crypto provider: the OpenSSL ML-DSA backend failed to load |
+//!
+//! `--compare-chainstate` is the one report whose verdict has its own codes,
+//! outside the class range: 7 for tip divergence and 8 for UTXO divergence,
+//! after a successful comparison.
+
+use rubin_node::{OpError, OpErrorKind};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum ErrorClass {
+    Runtime,
+    Usage,
+    Input,
+    Consensus,
+    Store,
+    Crypto,
+}
+
+impl ErrorClass {
+    pub(crate) fn exit_code(self) -> i32 {
+        match self {
+            Self::Runtime => 1,
+            Self::Usage => 2,
+            Self::Input => 3,
+            Self::Consensus => 4,
+            Self::Store => 5,
+            Self::Crypto => 6,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct CliError {
+    pub(crate) class: ErrorClass,
+    /// Empty when the mode already wrote its diagnostics.
+    pub(crate) message: String,
+}
+
+impl CliError {
+    pub(crate) fn new(class: ErrorClass, message: impl Into<String>) -> Self {
+        Self {
+            class,
+            message: message.into(),
+        }
+    }
+
+    pub(crate) fn usage(message: impl Into<String>) -> Self {
+        Self::new(ErrorClass::Usage, message)
+    }
+
+    pub(crate) fn input(message: impl Into<String>) -> Self {
+        Self::new(ErrorClass::Input, message)
+    }
+
+    pub(crate) fn store(message: impl Into<String>) -> Self {
+        Self::new(ErrorClass::Store, message)
+    }
+
+    pub(crate) fn runtime(message: impl Into<String>) -> Self {
+        Self::new(ErrorClass::Runtime, message)
+    }
+
+    /// A failure whose diagnostics are already on stderr.
+    pub(crate) fn reported(class: ErrorClass) -> Self {
+        Self::new(class, String::new())
+    }
+
+    /// An [`OpError`] under `context`: the kind the operation assigned
+    /// picks the class, and `OpErrorKind::Other` falls back to `fallback`.
+    pub(crate) fn op(fallback: ErrorClass, context: &str, err: OpError) -> Self {
+        let class = match err.kind {
+            OpErrorKind::Input => ErrorClass::Input,
+            OpErrorKind::Consensus => ErrorClass::Consensus,
+            OpErrorKind::CryptoProvider => ErrorClass::Crypto,
+            OpErrorKind::Other => fallback,
+        };
+        Self::new(class, format!("{context}: {err}"))
+    }
+
+    pub(crate) fn exit_code(&self) -> i32 {
+        self.class.exit_code()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn op_errors_keep_their_kind_and_fall_back_for_other() {
+        let err = CliError::op(
+            ErrorClass::Runtime,
+            "devnet failed",
+            OpError::new(OpErrorKind::CryptoProvider, "devnet keys: openssl"),
+        );
+        assert_eq!(err.exit_code(), 6);
+        assert_eq!(err.message, "devnet failed: devnet keys: openssl");
+        let err = CliError::op(
+            ErrorClass::Store,
+            "importblocks failed",
+            OpError::new(OpErrorKind::Consensus, "BLOCK_ERR_POW_INVALID: bad pow"),
+        );
+        assert_eq!(err.exit_code(), 4);
+        // A code in the text of an untyped failure does not make it consensus.
+        let err = CliError::op(
+            ErrorClass::Store,
+            "importblocks failed",
+            OpError::from("read blocks-0.dat: BLOCK_ERR_PARSE".to_string()),
+        );
+        assert_eq!(err.exit_code(), 5);
+    }
+}
@@ -0,0 +1,248 @@
+//! Result emission for the `rubin-node` report modes. Reports print as
+//! pretty JSON on stdout and failures as text on stderr. Under `--json`
+//! every outcome is one line on stdout,
+//! `{"ok":..,"error_code":..,"message":..,"result":..}`, so automation never
+//! parses stderr. Exit codes are identical in both modes; see `cli_error`.
+
+use std::io::Write;
+
+use serde::Serialize;
+
+use crate::cli_error::{CliError, ErrorClass};
+
+#[derive(Serialize)]
+struct Envelope<'a, T: Serialize> {
+    ok: bool,
+    /// Canonical `TX_ERR_*` / `BLOCK_ERR_*` code carried by the failure, if any.
+    error_code: Option<&'a str>,
+    message: Option<&'a str>,
+    result: Option<&'a T>,
+}
+
+/// A report result with its type erased, so modes can hand it back to `run`.
+pub(crate) trait ReportBody {
+    fn write_pretty(&self, out: &mut dyn Write) -> serde_json::Result<()>;
+    fn write_envelope(
+        &self,
+        out: &mut dyn Write,
+        ok: bool,
+        error_code: Option<&str>,
+    ) -> serde_json::Result<()>;
+}
+
+impl<T: Serialize> ReportBody for T {
+    fn write_pretty(&self, out: &mut dyn Write) -> serde_json::Result<()> {
+        serde_json::to_writer_pretty(out, self)
+    }
+
+    fn write_envelope(
+        &self,
+        out: &mut dyn Write,
+        ok: bool,
+        error_code: Option<&str>,
+    ) -> serde_json::Result<()> {
+        let envelope = Envelope {
+            ok,
+            error_code,
+            message: None,
+            result: Some(self),
+        };
+        serde_json::to_writer(out, &envelope)
+    }
+}
+
+/// What a mode hands back to `run` on completion.
+pub(crate) enum Output {
+    /// A result to print. `exit_code` is non-zero for a negative verdict
+    /// (a rejected transaction, a chainstate divergence), which still
+    /// carries its report but reports `ok: false`.
+    Report {
+        label: &'static str,
+        result: Box<dyn ReportBody>,
+        exit_code: i32,
+        error_code: Option<String>,
+    },
+    /// The mode wrote its own output (usage, the node run loop).
+    Done,
+}
+
+impl Output {
+    pub(crate) fn report<T: Serialize + 'static>(label: &'static str, result: T) -> Self {
+        Self::verdict(label, result, 0)
+    }
+
+    pub(crate) fn verdict<T: Serialize + 'static>(
+        label: &'static str,
+        result: T,
+        exit_code: i32,
+    ) -> Self {
+        Self::Report {
+            label,
+            result: Box::new(result),
+            exit_code,
+            error_code: None,
+        }
+    }
+
+    /// A completed check that failed consensus: exit 4 with `error_code`.
+    pub(crate) fn rejected<T: Serialize + 'static>(
+        label: &'static str,
+        result: T,
+        error_code: Option<String>,
+    ) -> Self {
+        Self::Report {
+            label,
+            result: Box::new(result),
+            exit_code: ErrorClass::Consensus.exit_code(),
+            error_code,
+        }
+    }
+}
+
+pub(crate) struct CliOutput<'a> {
+    json: bool,
+    stdout: &'a mut dyn Write,
+    stderr: &'a mut dyn Write,
+}
+
+impl<'a> CliOutput<'a> {
+    pub(crate) fn new(json: bool, stdout: &'a mut dyn Write, stderr: &'a mut dyn Write) -> Self {
+        Self {
+            json,
+            stdout,
+            stderr,
+        }
+    }
+
+    /// The raw streams, for modes that print as they go.
+    pub(crate) fn streams(&mut self) -> (&mut dyn Write, &mut dyn Write) {
+        (&mut *self.stdout, &mut *self.stderr)
+    }
+
+    /// Prints the outcome of a mode and returns the process exit code.
+    pub(crate) fn finish(&mut self, outcome: Result<Output, CliError>) -> i32 {
+        match outcome {
+            Ok(Output::Done) => 0,
+            Ok(Output::Report {
+                label,
+                result,
+                exit_code,
+                error_code,
+            }) => self.report(label, result.as_ref(), exit_code, error_code.as_deref()),
+            Err(err) => self.fail(&err),
+        }
+    }
+
+    /// `label` names the mode if encoding fails (exit 1).
+    fn report(
+        &mut self,
+        label: &str,
+        result: &dyn ReportBody,
+        exit_code: i32,
+        error_code: Option<&str>,
+    ) -> i32 {
+        let written = if self.json {
+            result.write_envelope(&mut *self.stdout, exit_code == 0, error_code)
+        } else {
+            result.write_pretty(&mut *self.stdout)
+        };
+        if let Err(err) = written {
+            let _ = writeln!(self.stderr, "{label} encode failed: {err}");
+            return ErrorClass::Runtime.exit_code();
+        }
+        let _ = writeln!(self.stdout);
+        if let (false, Some(code)) = (self.json, error_code) {
+            let _ = writeln!(self.stderr, "{label} rejected: {code}");
+        }
+        exit_code
+    }
+
+    fn fail(&mut self, err: &CliError) -> i32 {
+        if err.message.is_empty() {
+            return err.exit_code();
+        }
+        if !self.json {
+            let _ = writeln!(self.stderr, "{}", err.message);
+            return err.exit_code();
+        }
+        let envelope = Envelope::<()> {
+            ok: false,
+            error_code: error_code(&err.message),
+            message: Some(&err.message),
+            result: None,
+        };
+        if serde_json::to_writer(&mut *self.stdout, &envelope).is_ok() {
+            let _ = writeln!(self.stdout);
+        }
+        err.exit_code()
+    }
+}
+
+/// First `TX_ERR_*` / `BLOCK_ERR_*` token in `message`; consensus errors
+/// render as `CODE: detail`, possibly behind a context prefix.
+pub(crate) fn error_code(message: &str) -> Option<&str> {
+    message
+        .split(|c: char| !(c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_'))
+        .find(|token| {
+            (token.starts_with("TX_ERR_") || token.starts_with("BLOCK_ERR_"))
+                && !token.ends_with('_')
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn error_code_finds_canonical_token() {
+        assert_eq!(
+            error_code("transaction rejected: TX_ERR_MISSING_UTXO: utxo not found"),
+            Some("TX_ERR_MISSING_UTXO")
+        );
+        assert_eq!(
+            error_code("block stats failed: BLOCK_ERR_PARSE"),
+            Some("BLOCK_ERR_PARSE")
+        );
+        assert_eq!(error_code("no stats recorded for block 00"), None);
+        assert_eq!(error_code("TX_ERR_"), None);
+    }
+
+    #[test]
+    fn json_mode_writes_one_line_envelopes() {
+        let (mut stdout, mut stderr) = (Vec::new(), Vec::new());
+        let mut out = CliOutput::new(true, &mut stdout, &mut stderr);
+        let report = Output::verdict("demo", serde_json::json!({"n": 1}), 3);
+        assert_eq!(out.finish(Ok(report)), 3);
+        let err = CliError::new(
+            ErrorClass::Consensus,
+            "bad: BLOCK_ERR_TIMESTAMP_OLD: too old",
+        );
+        assert_eq!(out.finish(Err(err)), 4);
+        let stdout = String::from_utf8(stdout).expect("utf8");
+        let lines: Vec<&str> = stdout.lines().collect();
+        assert_eq!(
+            lines,
+            [
+                r#"{"ok":false,"error_code":null,"message":null,"result":{"n":1}}"#,
+                r#"{"ok":false,"error_code":"BLOCK_ERR_TIMESTAMP_OLD","message":"bad: BLOCK_ERR_TIMESTAMP_OLD: too old","result":null}"#,
+            ]
+        );
+        assert!(stderr.is_empty());
+    }
+
+    #[test]
+    fn text_mode_names_the_rejection_code_on_stderr() {
+        let (mut stdout, mut stderr) = (Vec::new(), Vec::new());
+        let mut out = CliOutput::new(false, &mut stdout, &mut stderr);
+        let report = Output::rejected(
+            "verify tx",
+            serde_json::json!({"valid": false}),
+            Some("TX_ERR_MISSING_UTXO".to_string()),
+        );
+        assert_eq!(out.finish(Ok(report)), 4);
+        assert_eq!(
+            String::from_utf8(stderr).expect("utf8"),
+            "verify tx rejected: TX_ERR_MISSING_UTXO\n"
+        );
+    }
+}
@@ -1,7 +1,10 @@
 use rubin_consensus::constants::{
     COV_TYPE_ANCHOR, COV_TYPE_P2PK, MAX_P2PK_COVENANT_DATA, SUITE_ID_ML_DSA_87,
 };
-use rubin_consensus::{block_subsidy, encode_compact_size};
+use rubin_consensus::{
+    block_subsidy, cumulative_subsidy, encode_compact_size, parse_tx, validate_coinbase_tx,
+};
+use serde::Serialize;
 
 const MINE_ADDRESS_KEY_ID_BYTES: usize = 32;
 
@@ -114,13 +117,74 @@ pub fn build_coinbase_tx(
     Ok(tx)
 }
 
+/// Outcome of `--verify-coinbase`: the block-context scalars the value bound
+/// was computed from, and the first coinbase rule the tx breaks, if any.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct CoinbaseVerifyReport {
+    pub txid: String,
+    pub height: u64,
+    pub valid: bool,
+    pub already_generated: u128,
+    pub subsidy: u64,
+    pub fees: u64,
+    pub value_limit: u128,
+    pub output_sum: u128,
+    pub error_code: Option<String>,
+    pub error: Option<String>,
+}
+
+/// Verifies `tx_bytes` as the coinbase of a block at `height` collecting
+/// `fees`. `already_generated` defaults to [`cumulative_subsidy`] of `height`,
+/// i.e. a chain that paid the full subsidy at every earlier height. A
+/// malformed transaction is an error; consensus failures are reported.
+pub fn verify_coinbase_tx(
+    tx_bytes: &[u8],
+    height: u64,
+    fees: u64,
+    already_generated: Option<u128>,
+) -> Result<CoinbaseVerifyReport, String> {
+    let (tx, txid, _wtxid, consumed) =
+        parse_tx(tx_bytes).map_err(|err| format!("parse tx: {err}"))?;
+    if consumed != tx_bytes.len() {
+        return Err(format!(
+            "parse tx: {} trailing bytes",
+            tx_bytes.len() - consumed
+        ));
+    }
+    let already_generated = already_generated.unwrap_or_else(|| cumulative_subsidy(height));
+    let subsidy = block_subsidy(height, already_generated);
+    let output_sum = tx
+        .outputs
+        .iter()
+        .map(|out| u128::from(out.value))
+        .sum::<u128>();
+    let verdict = validate_coinbase_tx(&tx, height, already_generated, fees);
+    Ok(CoinbaseVerifyReport {
+        txid: hex::encode(txid),
+        height,
+        valid: verdict.is_ok(),
+        already_generated,
+        subsidy,
+        fees,
+        value_limit: u128::from(subsidy) + u128::from(fees),
+        output_sum,
+        error_code: verdict
+            .as_ref()
+            .err()
+            .map(|err| err.code.as_str().to_string()),
+        error: verdict.as_ref().err().map(|err| err.msg.to_string()),
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::{
         build_coinbase_tx, default_mine_address, parse_mine_address, validate_mine_address,
+        verify_coinbase_tx,
     };
     use rubin_consensus::{
-        block_subsidy, constants::COV_TYPE_ANCHOR, constants::COV_TYPE_P2PK, parse_tx,
+        block_subsidy, constants::COV_TYPE_ANCHOR, constants::COV_TYPE_P2PK, cumulative_subsidy,
+        marshal_tx, parse_tx, WitnessItem,
     };
 
     fn test_mine_address(byte: u8) -> Vec<u8> {
@@ -182,4 +246,78 @@ mod tests {
         assert_eq!(parsed[0], 0x01);
         assert!(parsed[1..].iter().all(|byte| *byte == 0x11));
     }
+
+    #[test]
+    fn verify_coinbase_tx_accepts_built_coinbase() {
+        let tx_bytes = build_coinbase_tx(
+            4,
+            u64::try_from(cumulative_subsidy(4)).expect("fits"),
+            &test_mine_address(0x42),
+            [0x11; 32],
+        )
+        .expect("build");
+        let report = verify_coinbase_tx(&tx_bytes, 4, 0, None).expect("verify");
+        assert!(report.valid, "{report:?}");
+        assert_eq!(report.already_generated, cumulative_subsidy(4));
+        assert_eq!(report.output_sum, u128::from(report.subsidy));
+        assert_eq!(report.error_code, None);
+    }
+
+    #[test]
+    fn verify_coinbase_tx_reports_excessive_value() {
+        let tx_bytes =
+            build_coinbase_tx(1, 0, &test_mine_address(0x42), [0x11; 32]).expect("build");
+        let (mut tx, _, _, _) = parse_tx(&tx_bytes).expect("parse");
+        tx.outputs[0].value += 1;
+        let tx_bytes = marshal_tx(&tx).expect("marshal");
+        let report = verify_coinbase_tx(&tx_bytes, 1, 0, Some(0)).expect("verify");
+        assert!(!report.valid);
+        assert_eq!(report.output_sum, report.value_limit + 1);
+        assert_eq!(
+            report.error_code.as_deref(),
+            Some("BLOCK_ERR_SUBSIDY_EXCEEDED")
+        );
+
+        let with_fee = verify_coinbase_tx(&tx_bytes, 1, 1, Some(0)).expect("verify");
+        assert!(with_fee.valid, "{with_fee:?}");
+    }
+
+    #[test]
+    fn verify_coinbase_tx_reports_witness_item() {
+        let tx_bytes =
+            build_coinbase_tx(1, 0, &test_mine_address(0x42), [0x11; 32]).expect("build");
+        let (mut tx, _, _, _) = parse_tx(&tx_bytes).expect("parse");
+        tx.witness.push(WitnessItem {
+            suite_id: 0,
+            pubkey: Vec::new(),
+            signature: Vec::new(),
+        });
+        let tx_bytes = marshal_tx(&tx).expect("marshal");
+        let report = verify_coinbase_tx(&tx_bytes, 1, 0, Some(0)).expect("verify");
+        assert!(!report.valid);
+        assert_eq!(
+            report.error_code.as_deref(),
+            Some("BLOCK_ERR_COINBASE_INVALID")
+        );
+        assert_eq!(
+            report.error.as_deref(),
+            Some("coinbase must not carry witness items")
+        );
+    }
+
+    #[test]
+    fn verify_coinbase_tx_reports_locktime_height_mismatch() {
+        let tx_bytes =
+            build_coinbase_tx(1, 0, &test_mine_address(0x42), [0x11; 32]).expect("build");
+        let report = verify_coinbase_tx(&tx_bytes, 2, 0, None).expect("verify");
+        assert!(!report.valid);
+        assert_eq!(
+            report.error_code.as_deref(),
+            Some("BLOCK_ERR_COINBASE_INVALID")
+        );
+        assert_eq!(
+            report.error.as_deref(),
+            Some("coinbase locktime must equal block height")
+        );
+    }
 }
@@ -0,0 +1,197 @@
+//! Local `sendcmpct` mode selection per peer: the rule the CV-COMPACT
+//! `compact_sendcmpct_modes` op models, driven by a rolling window of
+//! compact reconstruction outcomes. Mode 0 keeps block relay on full blocks,
+//! mode 1 asks for compact announcements, mode 2 (high-bandwidth) asks for
+//! unsolicited compact blocks.
+
+use std::collections::VecDeque;
+
+/// Recent relayed blocks the miss rate is measured over.
+pub const SENDCMPCT_MISS_WINDOW_BLOCKS: usize = 200;
+/// Blocks observed outside IBD before compact relay is requested at all.
+pub const SENDCMPCT_WARMUP_BLOCKS: u64 = 5;
+/// Miss rate above which compact relay is switched off...
+pub const SENDCMPCT_DISABLE_MISS_RATE_PCT: f64 = 10.0;
+/// ...once measured over at least this many blocks.
+pub const SENDCMPCT_DISABLE_MIN_BLOCKS: u64 = 5;
+/// Highest miss rate that still qualifies for high-bandwidth mode.
+pub const SENDCMPCT_HIGH_BANDWIDTH_MAX_MISS_RATE_PCT: f64 = 0.5;
+
+/// Inputs to one mode decision, in the shape of a `compact_sendcmpct_modes`
+/// phase.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct SendCmpctPhase {
+    pub in_ibd: bool,
+    pub warmup_done: bool,
+    pub miss_rate_pct: f64,
+    pub miss_rate_blocks: u64,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SendCmpctModeChange {
+    pub from: u8,
+    pub to: u8,
+    /// The phase that triggered the change.
+    pub phase: SendCmpctPhase,
+}
+
+/// Mode for `phase`. The gap between the mode-2 ceiling (0.5%) and the
+/// mode-0 floor (10% over >= 5 blocks) is the hysteresis band: a peer that
+/// dropped to mode 0 climbs back one step at a time as the window clears.
+pub fn select_sendcmpct_mode(phase: &SendCmpctPhase) -> u8 {
+    if phase.in_ibd {
+        return 0;
+    }
+    if phase.miss_rate_pct > SENDCMPCT_DISABLE_MISS_RATE_PCT
+        && phase.miss_rate_blocks >= SENDCMPCT_DISABLE_MIN_BLOCKS
+    {
+        return 0;
+    }
+    if phase.warmup_done && phase.miss_rate_pct <= SENDCMPCT_HIGH_BANDWIDTH_MAX_MISS_RATE_PCT {
+        return 2;
+    }
+    if phase.warmup_done {
+        return 1;
+    }
+    0
+}
+
+/// Per-peer controller. Starts at mode 0, matching the handshake
+/// advertisement, and is re-evaluated on every block the peer relays.
+#[derive(Clone, Debug, Default)]
+pub struct SendCmpctModeController {
+    mode: u8,
+    warmup_blocks: u64,
+    recent_misses: VecDeque<bool>,
+}
+
+impl SendCmpctModeController {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The mode last advertised to the peer.
+    pub fn mode(&self) -> u8 {
+        self.mode
+    }
+
+    pub fn phase(&self, in_ibd: bool) -> SendCmpctPhase {
+        let blocks = self.recent_misses.len() as u64;
+        let missed = self.recent_misses.iter().filter(|missed| **missed).count() as u64;
+        SendCmpctPhase {
+            in_ibd,
+            warmup_done: self.warmup_blocks >= SENDCMPCT_WARMUP_BLOCKS,
+            miss_rate_pct: if blocks == 0 {
+                0.0
+            } else {
+                missed as f64 * 100.0 / blocks as f64
+            },
+            miss_rate_blocks: blocks,
+        }
+    }
+
+    /// Record one relayed block and re-evaluate. `missed` is whether the
+    /// block could not be rebuilt from the local mempool alone. Blocks seen
+    /// during IBD restart the warmup and clear the window. Returns the
+    /// change, if any, that the caller must announce with `sendcmpct`.
+    pub fn observe_block(&mut self, in_ibd: bool, missed: bool) -> Option<SendCmpctModeChange> {
+        if in_ibd {
+            self.warmup_blocks = 0;
+            self.recent_misses.clear();
+        } else {
+            self.warmup_blocks = self.warmup_blocks.saturating_add(1);
+            if self.recent_misses.len() >= SENDCMPCT_MISS_WINDOW_BLOCKS {
+                self.recent_misses.pop_front();
+            }
+            self.recent_misses.push_back(missed);
+        }
+        let phase = self.phase(in_ibd);
+        let to = select_sendcmpct_mode(&phase);
+        if to == self.mode {
+            return None;
+        }
+        let from = std::mem::replace(&mut self.mode, to);
+        Some(SendCmpctModeChange { from, to, phase })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn phase(in_ibd: bool, warmup_done: bool, pct: f64, blocks: u64) -> SendCmpctPhase {
+        SendCmpctPhase {
+            in_ibd,
+            warmup_done,
+            miss_rate_pct: pct,
+            miss_rate_blocks: blocks,
+        }
+    }
+
+    #[test]
+    fn select_sendcmpct_mode_replays_cli_phases() {
+        // CV-C-15 sequence first, then the boundaries around each threshold.
+        let cases = [
+            (phase(true, false, 0.0, 0), 0),
+            (phase(false, true, 0.2, 1), 2),
+            (phase(false, true, 12.0, 5), 0),
+            (phase(true, true, 0.0, 50), 0),
+            (phase(false, false, 0.0, 0), 0),
+            (phase(false, true, 0.5, 200), 2),
+            (phase(false, true, 0.6, 200), 1),
+            (phase(false, true, 10.0, 200), 1),
+            (phase(false, true, 12.0, 4), 1),
+            (phase(false, false, 12.0, 4), 0),
+        ];
+        for (index, (phase, want)) in cases.iter().enumerate() {
+            assert_eq!(
+                select_sendcmpct_mode(phase),
+                *want,
+                "case {index}: {phase:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn sendcmpct_controller_walks_ibd_warmup_and_downgrade() {
+        let mut controller = SendCmpctModeController::new();
+        for _ in 0..3 {
+            assert_eq!(controller.observe_block(true, false), None);
+        }
+        for _ in 1..SENDCMPCT_WARMUP_BLOCKS {
+            assert_eq!(controller.observe_block(false, false), None);
+        }
+        let change = controller.observe_block(false, false).expect("warmup done");
+        assert_eq!((change.from, change.to), (0, 2));
+        assert!(change.phase.warmup_done);
+
+        // One miss in six blocks is above 10% over >= 5 blocks.
+        let change = controller.observe_block(false, true).expect("downgrade");
+        assert_eq!((change.from, change.to), (2, 0));
+        assert_eq!(change.phase.miss_rate_blocks, 6);
+
+        // Back to 10% (1 in 10) re-enables low-bandwidth relay only.
+        for _ in 0..3 {
+            assert_eq!(controller.observe_block(false, false), None);
+        }
+        let change = controller.observe_block(false, false).expect("recovered");
+        assert_eq!((change.from, change.to), (0, 1));
+
+        // High-bandwidth needs the miss to age to <= 0.5% of the window.
+        for _ in 10..SENDCMPCT_MISS_WINDOW_BLOCKS - 1 {
+            assert_eq!(controller.observe_block(false, false), None);
+        }
+        assert!(controller.phase(false).miss_rate_pct > 0.5);
+        let change = controller
+            .observe_block(false, false)
+            .expect("high bandwidth");
+        assert_eq!((change.from, change.to), (1, 2));
+        assert_eq!(change.phase.miss_rate_pct, 0.5);
+
+        // Falling back into IBD drops to mode 0 and restarts the warmup.
+        let change = controller.observe_block(true, false).expect("ibd");
+        assert_eq!((change.from, change.to), (2, 0));
+        assert!(!controller.phase(false).warmup_done);
+        assert_eq!(controller.phase(false).miss_rate_blocks, 0);
+    }
+}
@@ -17,6 +17,19 @@ const DA_PREFETCH_MAX_CONCURRENT_SETS: usize = 8;
 // `daPrefetchRequestTTL` = 1s; time is supplied by the caller in nanoseconds so
 // planning stays deterministic for tests.
 const DA_PREFETCH_REQUEST_TTL_NANOS: u64 = 1_000_000_000;
+const NANOS_PER_SECOND: u64 = 1_000_000_000;
+
+/// Default per-peer prefetch stream budget (`compact_prefetch_caps`
+/// `per_peer_bps` default, 4 MB/s).
+pub const DA_PREFETCH_PER_PEER_BPS: u64 = 4_000_000;
+/// Default prefetch budget across all peers (`compact_prefetch_caps`
+/// `global_bps` default, 32 MB/s).
+pub const DA_PREFETCH_GLOBAL_BPS: u64 = 32_000_000;
+/// Throttled admissions in a row after which the peer takes a quality penalty.
+pub const DA_PREFETCH_THROTTLE_PENALTY_STREAK: u32 = 3;
+/// Peer-quality delta of one penalty: the `prefetch_cap_exceeded` event of
+/// the `compact_peer_quality` model.
+pub const DA_PREFETCH_CAP_EXCEEDED_QUALITY_DELTA: i64 = -2;
 
 /// Per-peer prefetch request: chunk indexes reserved for `peer_key` of `da_id`
 /// (mirror of Go `daRelayPrefetchPlan`).
@@ -27,6 +40,174 @@ pub struct DaRelayPrefetchPlan {
     pub indexes: Vec<u16>,
 }
 
+/// Prefetch stream budgets in bytes per second; zero selects the default.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DaPrefetchRateConfig {
+    pub per_peer_bps: u64,
+    pub global_bps: u64,
+}
+
+impl Default for DaPrefetchRateConfig {
+    fn default() -> Self {
+        Self {
+            per_peer_bps: DA_PREFETCH_PER_PEER_BPS,
+            global_bps: DA_PREFETCH_GLOBAL_BPS,
+        }
+    }
+}
+
+impl DaPrefetchRateConfig {
+    fn normalized(self) -> Self {
+        Self {
+            per_peer_bps: if self.per_peer_bps == 0 {
+                DA_PREFETCH_PER_PEER_BPS
+            } else {
+                self.per_peer_bps
+            },
+            global_bps: if self.global_bps == 0 {
+                DA_PREFETCH_GLOBAL_BPS
+            } else {
+                self.global_bps
+            },
+        }
+    }
+}
+
+/// Outcome of charging one prefetch request against the stream budgets.
+/// Excess is only ever delayed: there is no drop and no disconnect.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct DaPrefetchAdmission {
+    /// How long to hold the request before sending it.
+    pub delay_nanos: u64,
+    pub peer_exceeded: bool,
+    pub global_exceeded: bool,
+    /// The peer reached `DA_PREFETCH_THROTTLE_PENALTY_STREAK` throttled
+    /// requests in a row.
+    pub quality_penalty: bool,
+}
+
+/// Byte bucket refilled at `rate_bps` and holding at most one second of
+/// budget. A take larger than the balance goes into debt, and the debt is
+/// the delay: requests are never refused.
+#[derive(Clone, Debug)]
+struct TokenBucket {
+    rate_bps: u64,
+    tokens: i128,
+    last_nanos: u64,
+}
+
+impl TokenBucket {
+    fn full(rate_bps: u64, now_nanos: u64) -> Self {
+        Self {
+            rate_bps,
+            tokens: i128::from(rate_bps),
+            last_nanos: now_nanos,
+        }
+    }
+
+    fn refill(&mut self, now_nanos: u64) {
+        let elapsed = now_nanos.saturating_sub(self.last_nanos);
+        if elapsed == 0 {
+            return;
+        }
+        let earned = i128::from(self.rate_bps) * i128::from(elapsed) / i128::from(NANOS_PER_SECOND);
+        self.tokens = (self.tokens + earned).min(i128::from(self.rate_bps));
+        self.last_nanos = now_nanos;
+    }
+
+    /// Takes `bytes` and returns the nanoseconds until the balance is back
+    /// to zero.
+    fn take(&mut self, bytes: u64, now_nanos: u64) -> u64 {
+        self.refill(now_nanos);
+        self.tokens -= i128::from(bytes);
+        if self.tokens >= 0 {
+            return 0;
+        }
+        let debt = -self.tokens * i128::from(NANOS_PER_SECOND);
+        let rate = i128::from(self.rate_bps);
+        u64::try_from((debt + rate - 1) / rate).unwrap_or(u64::MAX)
+    }
+
+    fn is_full(&mut self, now_nanos: u64) -> bool {
+        self.refill(now_nanos);
+        self.tokens >= i128::from(self.rate_bps)
+    }
+}
+
+#[derive(Clone, Debug)]
+struct PeerPrefetchRate {
+    bucket: TokenBucket,
+    throttle_streak: u32,
+}
+
+/// Token-bucket limiter for DA chunk prefetch streams, keyed by the same
+/// host-only peer quota keys as the planner.
+#[derive(Clone, Debug)]
+pub struct DaPrefetchRateLimiter {
+    cfg: DaPrefetchRateConfig,
+    global: Option<TokenBucket>,
+    peers: BTreeMap<String, PeerPrefetchRate>,
+}
+
+impl Default for DaPrefetchRateLimiter {
+    fn default() -> Self {
+        Self::new(DaPrefetchRateConfig::default())
+    }
+}
+
+impl DaPrefetchRateLimiter {
+    pub fn new(cfg: DaPrefetchRateConfig) -> Self {
+        Self {
+            cfg: cfg.normalized(),
+            global: None,
+            peers: BTreeMap::new(),
+        }
+    }
+
+    pub fn config(&self) -> DaPrefetchRateConfig {
+        self.cfg
+    }
+
+    /// Charges `bytes` requested from `peer_key` at `now_nanos` against the
+    /// peer and global budgets.
+    pub fn admit(&mut self, peer_key: &str, bytes: u64, now_nanos: u64) -> DaPrefetchAdmission {
+        // A peer whose bucket has refilled is indistinguishable from a new
+        // one, so idle peers are forgotten instead of accumulating.
+        self.peers
+            .retain(|key, peer| key == peer_key || !peer.bucket.is_full(now_nanos));
+        let global_delay = self
+            .global
+            .get_or_insert_with(|| TokenBucket::full(self.cfg.global_bps, now_nanos))
+            .take(bytes, now_nanos);
+        let per_peer_bps = self.cfg.per_peer_bps;
+        let peer = self
+            .peers
+            .entry(peer_key.to_string())
+            .or_insert_with(|| PeerPrefetchRate {
+                bucket: TokenBucket::full(per_peer_bps, now_nanos),
+                throttle_streak: 0,
+            });
+        let peer_delay = peer.bucket.take(bytes, now_nanos);
+        let delay_nanos = global_delay.max(peer_delay);
+        let mut quality_penalty = false;
+        if delay_nanos == 0 {
+            peer.throttle_streak = 0;
+        } else {
+            peer.throttle_streak += 1;
+            if peer.throttle_streak >= DA_PREFETCH_THROTTLE_PENALTY_STREAK {
+                peer.throttle_streak = 0;
+                quality_penalty = true;
+            }
+        }
+        DaPrefetchAdmission {
+            delay_nanos,
+            peer_exceeded: peer_delay > 0,
+            global_exceeded: global_delay > 0,
+            quality_penalty,
+        }
+    }
+}
+
 /// In-flight DA chunk prefetch reservations (mirror of Go `daRelayPrefetchState`).
 #[derive(Debug, Default)]
 pub struct DaRelayPrefetchState {
@@ -34,9 +215,77 @@ pub struct DaRelayPrefetchState {
     indexes: BTreeMap<[u8; 32], BTreeMap<u16, String>>,
     /// da_id -> reservation expiry (ns); absent means no live plan.
     expires: BTreeMap<[u8; 32], u64>,
+    rate: DaPrefetchRateLimiter,
+    /// Throttled plans and the time (ns) they may be sent.
+    deferred: Vec<(u64, DaRelayPrefetchPlan)>,
+    /// peer key -> quality penalties taken for forcing throttling.
+    quality_penalties: BTreeMap<String, u64>,
 }
 
 impl DaRelayPrefetchState {
+    pub fn with_rate_config(cfg: DaPrefetchRateConfig) -> Self {
+        Self {
+            rate: DaPrefetchRateLimiter::new(cfg),
+            ..Self::default()
+        }
+    }
+
+    /// Charges a freshly reserved plan against the stream budgets. Returns
+    /// it when it may be sent now; otherwise it is held, with its
+    /// reservation kept alive, until `take_ready_plans` releases it.
+    pub fn admit_plan(
+        &mut self,
+        plan: DaRelayPrefetchPlan,
+        now_nanos: u64,
+    ) -> Option<DaRelayPrefetchPlan> {
+        let bytes = plan.indexes.len() as u64 * CHUNK_BYTES;
+        let admission = self.rate.admit(&plan.peer_key, bytes, now_nanos);
+        if admission.quality_penalty {
+            *self
+                .quality_penalties
+                .entry(plan.peer_key.clone())
+                .or_default() += 1;
+        }
+        if admission.delay_nanos == 0 {
+            return Some(plan);
+        }
+        let ready_at = now_nanos.saturating_add(admission.delay_nanos);
+        let keep_until = ready_at.saturating_add(DA_PREFETCH_REQUEST_TTL_NANOS);
+        let expires = self.expires.entry(plan.da_id).or_default();
+        *expires = (*expires).max(keep_until);
+        self.deferred.push((ready_at, plan));
+        None
+    }
+
+    /// Held plans whose delay has passed, trimmed to the chunks still
+    /// reserved for their peer.
+    pub fn take_ready_plans(&mut self, now_nanos: u64) -> Vec<DaRelayPrefetchPlan> {
+        let (ready, held): (Vec<_>, Vec<_>) = std::mem::take(&mut self.deferred)
+            .into_iter()
+            .partition(|(ready_at, _)| *ready_at <= now_nanos);
+        self.deferred = held;
+        ready
+            .into_iter()
+            .filter_map(|(_, mut plan)| {
+                let set = self.indexes.get(&plan.da_id)?;
+                plan.indexes
+                    .retain(|index| set.get(index) == Some(&plan.peer_key));
+                (!plan.indexes.is_empty()).then_some(plan)
+            })
+            .collect()
+    }
+
+    pub fn deferred_len(&self) -> usize {
+        self.deferred.len()
+    }
+
+    /// Quality penalties `peer_key` has taken for forcing throttling; each
+    /// is worth `DA_PREFETCH_CAP_EXCEEDED_QUALITY_DELTA`. Throttling never
+    /// touches the ban score.
+    pub fn quality_penalties(&self, peer_key: &str) -> u64 {
+        self.quality_penalties.get(peer_key).copied().unwrap_or(0)
+    }
+
     /// Plan prefetch for the currently-missing chunks of `da_id` across
     /// `peer_keys` (mirror of Go `planDAPrefetch`). The runtime caller must
     /// supply host-only per-peer quota keys (as Go's allDAPrefetchPeersLocked
@@ -378,6 +627,156 @@ mod tests {
         assert!(!s.indexes.contains_key(&DA));
     }
 
+    /// Requests `offered_bps` per peer in whole chunks for `seconds`; a
+    /// throttled chunk is delivered once its delay has passed. Returns (peer
+    /// exceeded, global exceeded, penalty emitted, bytes delivered per peer
+    /// by the end of the run).
+    fn run_streams(
+        limiter: &mut DaPrefetchRateLimiter,
+        offered_bps: &[u64],
+        seconds: u64,
+    ) -> (bool, bool, bool, Vec<u64>) {
+        let end = seconds * NANOS_PER_SECOND;
+        let interval: Vec<u64> = offered_bps
+            .iter()
+            .map(|bps| CHUNK_BYTES * NANOS_PER_SECOND / bps)
+            .collect();
+        let mut next_at = vec![0u64; offered_bps.len()];
+        let mut delivered = vec![0u64; offered_bps.len()];
+        let (mut peer, mut global, mut penalty) = (false, false, false);
+        loop {
+            let (i, &at) = next_at
+                .iter()
+                .enumerate()
+                .min_by_key(|(i, at)| (**at, *i))
+                .expect("streams");
+            if at >= end {
+                break;
+            }
+            let got = limiter.admit(&format!("peer-{i}"), CHUNK_BYTES, at);
+            assert!(got.delay_nanos > 0 || !got.quality_penalty);
+            peer |= got.peer_exceeded;
+            global |= got.global_exceeded;
+            penalty |= got.quality_penalty;
+            if at + got.delay_nanos <= end {
+                delivered[i] += CHUNK_BYTES;
+            }
+            next_at[i] = at + interval[i];
+        }
+        (peer, global, penalty, delivered)
+    }
+
+    #[test]
+    fn rate_limiter_matches_compact_prefetch_caps_vectors() {
+        const FIXTURES: [&str; 2] = [
+            include_str!(concat!(
+                env!("CARGO_MANIFEST_DIR"),
+                "/../../../../conformance/fixtures/CV-COMPACT.json"
+            )),
+            include_str!(concat!(
+                env!("CARGO_MANIFEST_DIR"),
+                "/../../../../conformance/fixtures/CV-DA-STRESS.json"
+            )),
+        ];
+        let mut checked = 0;
+        for fixture in FIXTURES {
+            let fixture: serde_json::Value = serde_json::from_str(fixture).expect("fixture");
+            let vectors = fixture["vectors"].as_array().expect("vectors");
+            for v in vectors
+                .iter()
+                .filter(|v| v["op"] == "compact_prefetch_caps")
+            {
+                let streams: Vec<u64> = v["peer_streams_bps"]
+                    .as_array()
+                    .expect("streams")
+                    .iter()
+                    .map(|bps| bps.as_u64().expect("bps"))
+                    .collect();
+                let cfg = DaPrefetchRateConfig {
+                    per_peer_bps: v["per_peer_bps"].as_u64().unwrap_or(0),
+                    global_bps: v["global_bps"].as_u64().unwrap_or(0),
+                };
+                let mut limiter = DaPrefetchRateLimiter::new(cfg);
+                let cfg = limiter.config();
+                // Long enough for a 0.1 MB/s excess to drain a full bucket.
+                let seconds = 1_000;
+                let (peer, global, penalty, delivered) =
+                    run_streams(&mut limiter, &streams, seconds);
+                let id = v["id"].as_str().expect("id");
+                assert_eq!(peer, v["expect_peer_exceeded"], "{id}");
+                assert_eq!(global, v["expect_global_exceeded"], "{id}");
+                assert_eq!(penalty, v["expect_quality_penalty"], "{id}");
+                assert_eq!(v["expect_disconnect"], false, "{id}");
+                // Throughput: one second of burst on top of the rate.
+                for bytes in &delivered {
+                    assert!(
+                        *bytes <= cfg.per_peer_bps * (seconds + 1) + CHUNK_BYTES,
+                        "{id}"
+                    );
+                }
+                let total: u64 = delivered.iter().sum();
+                assert!(
+                    total <= cfg.global_bps * (seconds + 1) + CHUNK_BYTES,
+                    "{id}"
+                );
+                checked += 1;
+            }
+        }
+        assert_eq!(checked, 3);
+    }
+
+    #[test]
+    fn throttled_stream_is_held_to_its_rate_and_penalized_repeatedly() {
+        let mut limiter = DaPrefetchRateLimiter::default();
+        let (peer, global, penalty, delivered) =
+            run_streams(&mut limiter, &[2 * DA_PREFETCH_PER_PEER_BPS], 10);
+        assert!(peer && !global && penalty);
+        assert!(delivered[0] <= DA_PREFETCH_PER_PEER_BPS * 11);
+        assert!(delivered[0] >= DA_PREFETCH_PER_PEER_BPS * 10);
+
+        // Every throttled request counts toward the streak; an unthrottled
+        // one resets it.
+        let mut limiter = DaPrefetchRateLimiter::default();
+        let burst = DA_PREFETCH_PER_PEER_BPS;
+        assert_eq!(limiter.admit("p", burst, 0).delay_nanos, 0);
+        let first = limiter.admit("p", CHUNK_BYTES, 0);
+        assert!(first.peer_exceeded && !first.quality_penalty);
+        assert!(!limiter.admit("p", CHUNK_BYTES, 0).quality_penalty);
+        assert!(limiter.admit("p", CHUNK_BYTES, 0).quality_penalty);
+        let later = limiter.admit("p", CHUNK_BYTES, 10 * NANOS_PER_SECOND);
+        assert_eq!(later, DaPrefetchAdmission::default());
+    }
+
+    #[test]
+    fn throttled_plan_is_held_with_its_reservation_then_released() {
+        let mut s = DaRelayPrefetchState::with_rate_config(DaPrefetchRateConfig {
+            per_peer_bps: CHUNK_BYTES,
+            global_bps: 0,
+        });
+        let now = 1_000;
+        let plan = s
+            .plan_da_prefetch(DA, &[0, 1, 2], &keys(&["peer-a"]), now)
+            .0
+            .remove(0);
+        assert!(s.admit_plan(plan.clone(), now).is_none());
+        assert_eq!(s.deferred_len(), 1);
+        assert!(s.take_ready_plans(now).is_empty());
+
+        // Two chunks of debt at one chunk per second: ready after 2s, and
+        // the reservation outlives the usual 1s TTL so nothing is re-planned.
+        let ready_at = now + 2 * NANOS_PER_SECOND;
+        let (replan, _) = s.plan_da_prefetch(DA, &[0, 1, 2], &keys(&["peer-b"]), ready_at - 1);
+        assert!(replan.is_empty());
+        assert!(s.take_ready_plans(ready_at - 1).is_empty());
+        // Chunk 1 arrived meanwhile; only the rest is still requested.
+        s.plan_da_prefetch(DA, &[0, 2], &keys(&["peer-a"]), ready_at - 1);
+        let ready = s.take_ready_plans(ready_at);
+        assert_eq!(ready.len(), 1);
+        assert_eq!(ready[0].indexes, vec![0, 2]);
+        assert_eq!(s.deferred_len(), 0);
+        assert_eq!(s.quality_penalties("peer-a"), 0);
+    }
+
     #[test]
     fn no_peers_plans_nothing_without_reserving() {
         let mut s = DaRelayPrefetchState::default();
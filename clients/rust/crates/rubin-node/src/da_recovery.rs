@@ -0,0 +1,174 @@
+//! DA set recovery success rate: the measurement behind the rollback rule
+//! the CV-COMPACT `compact_orphan_storm` op models. A recovery is a partial
+//! DA set that either completes (success) or expires (failure); when the
+//! windowed success rate stays below 95% for 10 minutes of observation,
+//! orphan storm mode is rolled back so non-commit-bearing data is admitted
+//! again. Time is always passed in by the caller, so tests drive it directly.
+
+use std::collections::VecDeque;
+
+/// Success rate, in percent, below which the rollback clock runs.
+pub const DA_RECOVERY_SUCCESS_RATE_FLOOR_PCT: f64 = 95.0;
+/// How long the rate must stay below the floor before rollback.
+pub const DA_RECOVERY_ROLLBACK_OBSERVATION_MS: u64 = 10 * 60 * 1000;
+/// Recoveries older than this no longer count toward the rate.
+pub const DA_RECOVERY_WINDOW_MS: u64 = DA_RECOVERY_ROLLBACK_OBSERVATION_MS;
+// One bucket per observation instant; bounds memory if observations arrive
+// far faster than the window drains.
+const DA_RECOVERY_WINDOW_BUCKETS: usize = 1024;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct RecoveryBucket {
+    at_ms: u64,
+    completed: u64,
+    failed: u64,
+}
+
+/// Sliding window of set recovery outcomes plus the below-floor clock.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DaSetRecoveryWindow {
+    buckets: VecDeque<RecoveryBucket>,
+    below_floor_since_ms: Option<u64>,
+}
+
+impl DaSetRecoveryWindow {
+    pub const fn new() -> Self {
+        Self {
+            buckets: VecDeque::new(),
+            below_floor_since_ms: None,
+        }
+    }
+
+    /// Record `completed` and `failed` recoveries observed at `now_ms`.
+    pub fn record(&mut self, now_ms: u64, completed: u64, failed: u64) {
+        if completed == 0 && failed == 0 {
+            return;
+        }
+        if let Some(last) = self.buckets.back_mut() {
+            if last.at_ms == now_ms {
+                last.completed = last.completed.saturating_add(completed);
+                last.failed = last.failed.saturating_add(failed);
+                return;
+            }
+        }
+        if self.buckets.len() >= DA_RECOVERY_WINDOW_BUCKETS {
+            self.buckets.pop_front();
+        }
+        self.buckets.push_back(RecoveryBucket {
+            at_ms: now_ms,
+            completed,
+            failed,
+        });
+    }
+
+    /// Percent of windowed recoveries that completed; 100 with none, so an
+    /// idle relay never looks like it is failing.
+    pub fn success_rate_pct(&self) -> f64 {
+        let (completed, attempted) = self.buckets.iter().fold((0u64, 0u64), |(ok, all), b| {
+            (
+                ok.saturating_add(b.completed),
+                all.saturating_add(b.completed).saturating_add(b.failed),
+            )
+        });
+        if attempted == 0 {
+            100.0
+        } else {
+            completed as f64 * 100.0 / attempted as f64
+        }
+    }
+
+    /// Drop recoveries that left the window, restart or stop the below-floor
+    /// clock, and report whether the rollback rule holds at `now_ms`: below
+    /// the floor for at least the full observation period (inclusive).
+    pub fn observe(&mut self, now_ms: u64) -> bool {
+        while self
+            .buckets
+            .front()
+            .is_some_and(|b| now_ms.saturating_sub(b.at_ms) >= DA_RECOVERY_WINDOW_MS)
+        {
+            self.buckets.pop_front();
+        }
+        if self.success_rate_pct() >= DA_RECOVERY_SUCCESS_RATE_FLOOR_PCT {
+            self.below_floor_since_ms = None;
+            return false;
+        }
+        let since = *self.below_floor_since_ms.get_or_insert(now_ms);
+        now_ms.saturating_sub(since) >= DA_RECOVERY_ROLLBACK_OBSERVATION_MS
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MINUTE_MS: u64 = 60 * 1000;
+
+    /// One `compact_orphan_storm` rollback input: a window holding `rate`
+    /// percent successes over 100 recoveries, observed for `minutes`.
+    fn rollback_after(rate: u64, minutes: u64) -> bool {
+        let mut window = DaSetRecoveryWindow::new();
+        let mut rollback = false;
+        for minute in 0..=minutes {
+            // Keep the window populated at the target rate as time advances.
+            window.record(minute * MINUTE_MS, rate, 100 - rate);
+            rollback = window.observe(minute * MINUTE_MS);
+        }
+        rollback
+    }
+
+    #[test]
+    fn success_rate_counts_completed_over_attempted() {
+        let mut window = DaSetRecoveryWindow::new();
+        assert_eq!(window.success_rate_pct(), 100.0);
+        window.record(0, 0, 0);
+        assert_eq!(window, DaSetRecoveryWindow::new());
+        window.record(0, 3, 1);
+        window.record(0, 1, 0);
+        window.record(5, 0, 1);
+        assert_eq!(window.buckets.len(), 2);
+        assert!((window.success_rate_pct() - 4.0 * 100.0 / 6.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn rollback_replays_compact_orphan_storm_vectors() {
+        // CV-C-24 / CV-DA-STRESS-05: healthy rate never rolls back.
+        assert!(!rollback_after(98, 5));
+        assert!(!rollback_after(98, 8));
+        // CV-C-25 / CV-DA-STRESS-06: below 95% for 12 minutes rolls back.
+        assert!(rollback_after(92, 12));
+        assert!(rollback_after(93, 12));
+        // Exactly at the floor is not below it.
+        assert!(!rollback_after(95, 12));
+    }
+
+    #[test]
+    fn rollback_fires_exactly_at_the_ten_minute_boundary() {
+        let mut window = DaSetRecoveryWindow::new();
+        let start = 7 * MINUTE_MS;
+        window.record(start, 9, 1);
+        assert!(!window.observe(start));
+        // Keep the rate at 90% inside the window as the clock runs.
+        window.record(start + 5 * MINUTE_MS, 9, 1);
+        assert!(!window.observe(start + DA_RECOVERY_ROLLBACK_OBSERVATION_MS - 1));
+        assert!(window.observe(start + DA_RECOVERY_ROLLBACK_OBSERVATION_MS));
+
+        // Recovering to the floor stops the clock; the next dip restarts it.
+        window.record(start + 11 * MINUTE_MS, 1_000, 0);
+        assert!(!window.observe(start + 11 * MINUTE_MS));
+        window.record(start + 12 * MINUTE_MS, 0, 200);
+        assert!(!window.observe(start + 12 * MINUTE_MS));
+        assert!(!window.observe(start + 22 * MINUTE_MS - 1));
+    }
+
+    #[test]
+    fn old_recoveries_leave_the_window() {
+        let mut window = DaSetRecoveryWindow::new();
+        window.record(0, 0, 10);
+        assert!(!window.observe(0));
+        // The failures age out before the observation period ends, so the
+        // rate returns to 100% and the clock stops.
+        assert!(!window.observe(DA_RECOVERY_WINDOW_MS));
+        assert_eq!(window.success_rate_pct(), 100.0);
+        assert_eq!(window.below_floor_since_ms, None);
+    }
+}
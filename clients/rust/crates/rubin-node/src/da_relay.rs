@@ -5,17 +5,20 @@ use std::sync::{Arc, Mutex};
 
 use rubin_consensus::constants::{CHUNK_BYTES, MAX_DA_CHUNK_COUNT};
 use rubin_consensus::constants::{COV_TYPE_DA_COMMIT, TX_WIRE_VERSION};
-use rubin_consensus::{parse_block_bytes, parse_tx, Tx, TxError};
+use rubin_consensus::{check_da_chunk_count, parse_block_bytes, parse_tx, Tx, TxError};
 use sha3::{Digest, Sha3_256};
 
+use crate::da_recovery::DaSetRecoveryWindow;
+
 pub const DA_ORPHAN_POOL_BYTES: u64 = 64 << 20;
 pub const DA_ORPHAN_POOL_PER_PEER_BYTES: u64 = 4 << 20;
 pub const DA_ORPHAN_POOL_PER_DA_ID_BYTES: u64 = 8 << 20;
 pub const DA_ORPHAN_COMMIT_OVERHEAD_BYTES: u64 = 8 << 20;
 pub const DA_ORPHAN_TTL_BLOCKS: u64 = 3;
 pub const DA_PINNED_PAYLOAD_BYTES: u64 = 96_000_000;
-const DA_COMPLETE_SET_RECORD_FOOTPRINT: u64 = 256;
-const DA_COMPLETE_SET_CHUNK_FOOTPRINT: u64 = 128;
+pub const DA_ORPHAN_STORM_ENTER_FILL_PCT: u64 = 90;
+pub const DA_ORPHAN_STORM_EXIT_FILL_PCT: u64 = 70;
+pub const DA_ORPHAN_STORM_EXIT_BLOCKS: u64 = 3;
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct DaRelayCaps {
@@ -25,6 +28,7 @@ pub struct DaRelayCaps {
     orphan_commit_overhead_bytes: u64,
     orphan_ttl_blocks: u64,
     pinned_payload_bytes: u64,
+    max_commit_chunk_count: u64,
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
@@ -42,6 +46,7 @@ pub enum DaRelayError {
     ChunkPayloadSizeInvalid,
     ChunkHashMismatch,
     PayloadCommitmentMismatch,
+    StormModeRejected,
 }
 
 type DaRelayResult<T = ()> = Result<T, DaRelayError>;
@@ -59,6 +64,7 @@ pub(crate) struct DaRelayCommit {
     chunk_count: u16,
     wire_bytes: u64,
     tx_bytes: Arc<[u8]>,
+    fee: u64,
 }
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub(crate) struct DaRelayChunk {
@@ -69,6 +75,7 @@ pub(crate) struct DaRelayChunk {
     payload: Arc<[u8]>,
     wire_bytes: u64,
     tx_bytes: Arc<[u8]>,
+    fee: u64,
 }
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub(crate) struct DaRelaySetRecord {
@@ -119,6 +126,8 @@ pub struct CompleteDaSetChunkCandidate {
 
 pub trait CompleteDaSetProvider {
     fn complete_da_set_candidates(&self, max_payload_bytes: u64) -> Vec<CompleteDaSetCandidate>;
+    /// `checkblock` query: whether `da_id` has its commit and every chunk.
+    fn is_complete(&self, da_id: [u8; 32]) -> bool;
 }
 
 /// Foundation container; future mutation paths need exclusive access or owner-side synchronization.
@@ -130,8 +139,55 @@ pub struct DaRelayState {
     orphan_bytes_by_peer_quota_key: BTreeMap<PeerQuotaKey, u64>,
     orphan_bytes_by_da_id: BTreeMap<[u8; 32], u64>,
     orphan_commit_overhead_bytes: u64,
-    pinned_payload_bytes: u64,
+    pinned: PinnedDaAccounting,
     sets_by_da_id: BTreeMap<[u8; 32], DaRelaySetRecord>,
+    storm_mode: bool,
+    storm_calm_blocks: u64,
+    storm_rolled_back: bool,
+    recoveries_completed: u64,
+    recoveries_failed: u64,
+    recovery: DaSetRecoveryWindow,
+}
+
+/// Pinned DA budget (`compact_pinned_accounting`): once a set reaches
+/// COMPLETE_SET only its payload bytes count against
+/// `DaRelayCaps::pinned_payload_bytes`. The commit and chunk tx bytes around
+/// that payload are tallied in `ignored_overhead_bytes` and never counted.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PinnedDaAccounting {
+    counted_bytes: u64,
+    ignored_overhead_bytes: u64,
+}
+
+impl Default for PinnedDaAccounting {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PinnedDaAccounting {
+    pub const fn new() -> Self {
+        Self {
+            counted_bytes: 0,
+            ignored_overhead_bytes: 0,
+        }
+    }
+
+    pub fn counted_bytes(&self) -> u64 {
+        self.counted_bytes
+    }
+
+    pub fn ignored_overhead_bytes(&self) -> u64 {
+        self.ignored_overhead_bytes
+    }
+
+    /// Whether `incoming_payload_bytes` more fit under `cap_bytes`; landing
+    /// exactly on the cap is admitted.
+    pub fn admits(&self, incoming_payload_bytes: u64, cap_bytes: u64) -> bool {
+        self.counted_bytes
+            .checked_add(incoming_payload_bytes)
+            .is_some_and(|counted| counted <= cap_bytes)
+    }
 }
 
 impl Default for DaRelayCaps {
@@ -143,11 +199,29 @@ impl Default for DaRelayCaps {
             orphan_commit_overhead_bytes: DA_ORPHAN_COMMIT_OVERHEAD_BYTES,
             orphan_ttl_blocks: DA_ORPHAN_TTL_BLOCKS,
             pinned_payload_bytes: DA_PINNED_PAYLOAD_BYTES,
+            max_commit_chunk_count: MAX_DA_CHUNK_COUNT,
         }
     }
 }
 
 impl DaRelayCaps {
+    /// Caps with the pinned (COMPLETE_SET) payload budget set to `bytes`.
+    pub fn with_pinned_payload_bytes(self, bytes: u64) -> Self {
+        Self {
+            pinned_payload_bytes: bytes,
+            ..self
+        }
+    }
+
+    /// Caps admitting commits of at most `count` chunks; policy may only
+    /// tighten the consensus `MAX_DA_CHUNK_COUNT`.
+    pub fn with_max_commit_chunk_count(self, count: u64) -> Self {
+        Self {
+            max_commit_chunk_count: count,
+            ..self
+        }
+    }
+
     pub fn validate(self) -> Result<(), DaRelayError> {
         if [
             self.orphan_pool_bytes,
@@ -156,8 +230,10 @@ impl DaRelayCaps {
             self.orphan_commit_overhead_bytes,
             self.orphan_ttl_blocks,
             self.pinned_payload_bytes,
+            self.max_commit_chunk_count,
         ]
         .contains(&0)
+            || self.max_commit_chunk_count > MAX_DA_CHUNK_COUNT
             || self.orphan_pool_per_peer_bytes > self.orphan_pool_bytes
             || self.orphan_pool_per_da_id_bytes > self.orphan_pool_bytes
             || self.orphan_commit_overhead_bytes > self.orphan_pool_bytes
@@ -330,6 +406,13 @@ impl DaRelaySetRecord {
                 .try_fold(0u64, |total, bytes| checked_add(total, *bytes))
         }
     }
+    fn orphan_fee(&self) -> u64 {
+        self.chunks
+            .values()
+            .map(|chunk| chunk.fee)
+            .chain(self.commit.as_ref().map(|commit| commit.fee))
+            .fold(0u64, u64::saturating_add)
+    }
     fn orphan_commit_bytes(&self) -> u64 {
         if self.state == DaRelaySetState::CompleteSet {
             0
@@ -387,21 +470,24 @@ impl DaRelaySetRecord {
             && self.commit.is_none()
             && self.chunks.is_empty()
     }
-    fn pinned_payload_accounting_bytes(&self) -> DaRelayResult<u64> {
-        if self.state != DaRelaySetState::CompleteSet || self.payload_bytes == 0 {
-            return Ok(0);
-        }
-        let footprint = if self.wire_bytes == 0 {
+    fn pinned(&self) -> bool {
+        self.state == DaRelaySetState::CompleteSet && self.payload_bytes != 0
+    }
+    fn pinned_payload_accounting_bytes(&self) -> u64 {
+        if self.pinned() {
             self.payload_bytes
         } else {
-            self.wire_bytes
-        };
-        let footprint = checked_add(footprint, DA_COMPLETE_SET_RECORD_FOOTPRINT)?;
-        let chunk_count = self.commit.as_ref().map_or(0, |commit| commit.chunk_count);
-        let chunk_footprint = u64::from(chunk_count)
-            .checked_mul(DA_COMPLETE_SET_CHUNK_FOOTPRINT)
-            .ok_or(DaRelayError::AccountingOverflow)?;
-        checked_add(footprint, chunk_footprint)
+            0
+        }
+    }
+    /// Commit and chunk tx bytes retained beside a pinned payload; tracked,
+    /// never counted against the pinned cap.
+    fn pinned_overhead_bytes(&self) -> u64 {
+        if self.pinned() {
+            self.wire_bytes.saturating_sub(self.payload_bytes)
+        } else {
+            0
+        }
     }
 
     pub(crate) fn eviction_accounting(&self) -> Option<DaRelayEvictionAccounting> {
@@ -446,6 +532,37 @@ impl DaRelaySetRecord {
             chunks,
         })
     }
+
+    /// Completed payloads are dropped by `mark_complete`, so the set payload is
+    /// re-read from the retained chunk txs and re-verified against each chunk
+    /// hash and the commit's chunk_count and payload commitment.
+    fn set_payload(&self) -> Option<Vec<u8>> {
+        if self.state != DaRelaySetState::CompleteSet {
+            return None;
+        }
+        let commit = self.commit.as_ref()?;
+        if commit.chunk_count == 0 || self.chunks.len() != usize::from(commit.chunk_count) {
+            return None;
+        }
+        let mut payload = Vec::with_capacity(usize::try_from(self.payload_bytes).ok()?);
+        for index in 0..commit.chunk_count {
+            let chunk = self.chunks.get(&index)?;
+            let (tx, _txid, _wtxid, consumed) = parse_tx(&chunk.tx_bytes).ok()?;
+            let core = tx.da_chunk_core.as_ref()?;
+            if consumed != chunk.tx_bytes.len()
+                || core.da_id != self.da_id
+                || core.chunk_index != index
+                || sha3_256(&tx.da_payload) != chunk.chunk_hash
+            {
+                return None;
+            }
+            payload.extend_from_slice(&tx.da_payload);
+        }
+        if sha3_256(&payload) != commit.payload_commitment {
+            return None;
+        }
+        Some(payload)
+    }
 }
 
 #[derive(Clone)]
@@ -503,20 +620,128 @@ impl DaRelayState {
             orphan_bytes_by_peer_quota_key: BTreeMap::new(),
             orphan_bytes_by_da_id: BTreeMap::new(),
             orphan_commit_overhead_bytes: 0,
-            pinned_payload_bytes: 0,
+            pinned: PinnedDaAccounting::default(),
             sets_by_da_id: BTreeMap::new(),
+            storm_mode: false,
+            storm_calm_blocks: 0,
+            storm_rolled_back: false,
+            recoveries_completed: 0,
+            recoveries_failed: 0,
+            recovery: DaSetRecoveryWindow::new(),
         })
     }
 
+    /// Orphan storm mode (POLICY_DA_ANCHOR_ANTI_ABUSE §8): entered as soon as
+    /// orphan fill exceeds 90%, left after fill stays below 70% for 3
+    /// consecutive accepted blocks. While active only commit-bearing data
+    /// (a commit, or a chunk for a staged commit) is admitted.
+    pub fn storm_mode_active(&self) -> bool {
+        self.storm_mode
+    }
+
+    /// Percent of windowed DA set recoveries (partial sets that completed
+    /// rather than expired) as of the last [`Self::observe_set_recoveries`].
+    pub fn set_recovery_success_rate(&self) -> f64 {
+        self.recovery.success_rate_pct()
+    }
+
+    /// Feed the completions and expiries counted since the last call into the
+    /// recovery window at `now_ms` and apply the `compact_orphan_storm`
+    /// rollback rule: once the rate has stayed below 95% for 10 minutes, storm
+    /// mode is left and not re-entered until orphan fill drops below the exit
+    /// threshold. Returns whether the rollback rule holds.
+    pub(crate) fn observe_set_recoveries(&mut self, now_ms: u64) -> bool {
+        let (completed, failed) = (self.recoveries_completed, self.recoveries_failed);
+        self.recoveries_completed = 0;
+        self.recoveries_failed = 0;
+        self.recovery.record(now_ms, completed, failed);
+        let rollback = self.recovery.observe(now_ms);
+        if rollback && self.storm_mode {
+            self.storm_mode = false;
+            self.storm_calm_blocks = 0;
+            self.storm_rolled_back = true;
+        }
+        rollback
+    }
+
+    pub fn orphan_pool_fill_pct(&self) -> u64 {
+        let fill = u128::from(self.orphan_bytes) * 100 / u128::from(self.caps.orphan_pool_bytes);
+        u64::try_from(fill).unwrap_or(u64::MAX)
+    }
+
+    /// Sets still missing their commit or chunks.
+    pub fn partial_set_count(&self) -> u64 {
+        self.sets_by_da_id
+            .values()
+            .filter(|record| record.state != DaRelaySetState::CompleteSet)
+            .count() as u64
+    }
+
+    /// `Some(true)` for a relay-complete set, `Some(false)` for a partial one,
+    /// `None` when `da_id` is not tracked.
+    pub(crate) fn da_set_complete(&self, da_id: [u8; 32]) -> Option<bool> {
+        self.sets_by_da_id
+            .get(&da_id)
+            .map(|record| record.state == DaRelaySetState::CompleteSet)
+    }
+
+    /// `checkblock` (CV-COMPACT `compact_state_machine`): true once the set
+    /// has its commit and every declared chunk; false for partial, evicted or
+    /// unknown sets.
+    pub fn is_complete(&self, da_id: [u8; 32]) -> bool {
+        self.da_set_complete(da_id) == Some(true)
+    }
+
+    /// Ordered concatenation of a complete set's chunk payloads, `None` unless
+    /// [`Self::is_complete`] holds and the retained chunk txs still match the
+    /// commit's chunk_count and payload commitment.
+    pub fn set_payload(&self, da_id: [u8; 32]) -> Option<Vec<u8>> {
+        self.sets_by_da_id.get(&da_id)?.set_payload()
+    }
+
+    fn orphan_fill_cmp_pct(&self, pct: u64) -> std::cmp::Ordering {
+        (u128::from(self.orphan_bytes) * 100)
+            .cmp(&(u128::from(self.caps.orphan_pool_bytes) * u128::from(pct)))
+    }
+
+    fn note_storm_accepted_blocks(&mut self, blocks: u64) {
+        if self.storm_rolled_back
+            && self
+                .orphan_fill_cmp_pct(DA_ORPHAN_STORM_EXIT_FILL_PCT)
+                .is_lt()
+        {
+            self.storm_rolled_back = false;
+        }
+        if !self.storm_mode {
+            return;
+        }
+        if self
+            .orphan_fill_cmp_pct(DA_ORPHAN_STORM_EXIT_FILL_PCT)
+            .is_ge()
+        {
+            self.storm_calm_blocks = 0;
+            return;
+        }
+        self.storm_calm_blocks = self.storm_calm_blocks.saturating_add(blocks);
+        if self.storm_calm_blocks >= DA_ORPHAN_STORM_EXIT_BLOCKS {
+            self.storm_mode = false;
+            self.storm_calm_blocks = 0;
+        }
+    }
+
     pub fn is_empty(&self) -> bool {
         self.orphan_bytes == 0
             && self.orphan_bytes_by_peer_quota_key.is_empty()
             && self.orphan_bytes_by_da_id.is_empty()
             && self.orphan_commit_overhead_bytes == 0
-            && self.pinned_payload_bytes == 0
+            && self.pinned == PinnedDaAccounting::default()
             && self.sets_by_da_id.is_empty()
     }
 
+    pub fn pinned_accounting(&self) -> PinnedDaAccounting {
+        self.pinned
+    }
+
     pub(crate) fn validate_relay_da_tx_for_admission(tx_bytes: &[u8]) -> DaRelayResult {
         if relay_da_tx_kind_prefix(tx_bytes) != Some(0x02) {
             return Ok(());
@@ -542,16 +767,17 @@ impl DaRelayState {
     }
 
     #[rustfmt::skip]
-    pub(crate) fn stage_relay_da_tx_bytes(&mut self, peer_addr: &str, tx_bytes: Vec<u8>) -> DaRelayResult { self.stage_relay_da_tx_bytes_checked(peer_addr, tx_bytes, false).1 }
+    pub(crate) fn stage_relay_da_tx_bytes(&mut self, peer_addr: &str, tx_bytes: Vec<u8>) -> DaRelayResult { self.stage_relay_da_tx_bytes_checked(peer_addr, tx_bytes, false, 0).1 }
 
     /// Stage a relay DA tx, returning the schedulable da_id with the staging result
     /// from this single parse (so the caller drives `finish_da_prefetch` without a
     /// second `parse_tx`). The da_id is `Some` only for a DA chunk or a commit with a
     /// well-formed DA_COMMIT covenant (mirror of Go `stageRelayDACommitTx` gating
     /// `finishDAPrefetch`); it is returned even on a staging error (e.g. a payload-
-    /// commitment mismatch driving a snapshot reschedule).
+    /// commitment mismatch driving a snapshot reschedule). `fee` is the tx-pool
+    /// fee of the admitted tx and only feeds orphan fee-rate eviction ordering.
     #[rustfmt::skip]
-    pub(crate) fn stage_relay_da_tx_bytes_checked(&mut self, peer_addr: &str, tx_bytes: Vec<u8>, chunk_hash_prevalidated: bool) -> (Option<[u8; 32]>, DaRelayResult) {
+    pub(crate) fn stage_relay_da_tx_bytes_checked(&mut self, peer_addr: &str, tx_bytes: Vec<u8>, chunk_hash_prevalidated: bool, fee: u64) -> (Option<[u8; 32]>, DaRelayResult) {
         let Ok(wire_bytes) = u64::try_from(tx_bytes.len()) else { return (None, Err(DaRelayError::AccountingOverflow)); };
         let Ok((tx, _txid, _wtxid, consumed)) = parse_tx(&tx_bytes) else { return (None, Err(DaRelayError::InvalidWireBytes)); };
         if consumed != tx_bytes.len() {
@@ -571,6 +797,7 @@ impl DaRelayState {
                         chunk_count: core.chunk_count,
                         wire_bytes,
                         tx_bytes: Arc::from(tx_bytes.into_boxed_slice()),
+                        fee,
                     },
                 );
                 (Some(da_id), result)
@@ -588,6 +815,7 @@ impl DaRelayState {
                         payload: Arc::from(tx.da_payload.into_boxed_slice()),
                         wire_bytes,
                         tx_bytes: Arc::from(tx_bytes.into_boxed_slice()),
+                        fee,
                     },
                     chunk_hash_prevalidated,
                 );
@@ -618,6 +846,7 @@ impl DaRelayState {
                 chunk_count,
                 wire_bytes,
                 tx_bytes: Arc::from([]),
+                fee: 0,
             },
         )
     }
@@ -641,6 +870,7 @@ impl DaRelayState {
                 payload: Arc::from(payload),
                 wire_bytes,
                 tx_bytes: Arc::from([]),
+                fee: 0,
             },
         )
     }
@@ -740,10 +970,10 @@ impl DaRelayState {
                 .checked_sub(blocks)
                 .ok_or(DaRelayError::AccountingUnderflow)?;
         }
-        let Some(projection) = projection else {
-            return Ok(Vec::new());
-        };
-        self.apply_ttl_expiry_projection(projection, expiring_records);
+        if let Some(projection) = projection {
+            self.apply_ttl_expiry_projection(projection, expiring_records);
+        }
+        self.note_storm_accepted_blocks(blocks);
         Ok(expired)
     }
 
@@ -799,16 +1029,10 @@ impl DaRelayState {
         if record.state != DaRelaySetState::CompleteSet {
             return Ok(false);
         }
-        let pinned = record.pinned_payload_accounting_bytes()?;
-        // project_counter returns Err on underflow/overflow/cap; the counter and
-        // the record below are written only after it succeeds.
-        let pinned_payload_bytes = Self::project_counter(
-            self.pinned_payload_bytes,
-            pinned,
-            0,
-            self.caps.pinned_payload_bytes,
-        )?;
-        self.pinned_payload_bytes = pinned_payload_bytes;
+        // project_pinned returns Err on underflow/overflow/cap; the counters
+        // and the record below are written only after it succeeds.
+        let pinned = self.project_pinned(Some(record), None)?;
+        self.pinned = pinned;
         self.sets_by_da_id.remove(&da_id);
         Ok(true)
     }
@@ -818,7 +1042,14 @@ impl DaRelayState {
         peer_addr: &str,
         mut commit: DaRelayCommit,
     ) -> DaRelayResult {
-        if commit.chunk_count == 0 || u64::from(commit.chunk_count) > MAX_DA_CHUNK_COUNT {
+        // Checked before any record lookup so an out-of-range commit never
+        // gets tracking state.
+        if check_da_chunk_count(
+            u64::from(commit.chunk_count),
+            self.caps.max_commit_chunk_count,
+        )
+        .is_err()
+        {
             return Err(DaRelayError::InvalidCommitChunkCount);
         }
         if commit.wire_bytes == 0 {
@@ -848,6 +1079,9 @@ impl DaRelayState {
         if let Some(record) = current {
             record.validate_chunk_insert(chunk.chunk_index)?;
         }
+        if self.storm_mode && current.is_none_or(|record| record.commit.is_none()) {
+            return Err(DaRelayError::StormModeRejected);
+        }
         if !chunk_hash_prevalidated && sha3_256(chunk.payload.as_ref()) != chunk.chunk_hash {
             return Err(DaRelayError::ChunkHashMismatch);
         }
@@ -925,19 +1159,106 @@ impl DaRelayState {
                 return Err(DaRelayError::PayloadCommitmentMismatch);
             }
         }
-        self.apply_record(record)?;
+        self.apply_record_evicting(record)
+    }
+
+    /// Admit `record`, making room under the global orphan cap by evicting
+    /// whole incomplete sets lowest fee rate first (key `fee / wire_bytes`,
+    /// then `received_time`, then `da_id`, as `compact_eviction_tiebreak`).
+    /// Only sets with a strictly lower fee rate than `record` are eligible,
+    /// and nothing is evicted unless `record` then fits every cap.
+    fn apply_record_evicting(&mut self, record: DaRelaySetRecord) -> DaRelayResult {
+        match self.apply_record(record.clone()) {
+            Err(DaRelayError::AccountingCapExceeded) => {}
+            result => return result,
+        }
+        let old_bytes = self
+            .sets_by_da_id
+            .get(&record.da_id)
+            .map_or(Ok(0), DaRelaySetRecord::orphan_wire_bytes)?;
+        let projected = checked_add(
+            self.orphan_bytes
+                .checked_sub(old_bytes)
+                .ok_or(DaRelayError::AccountingUnderflow)?,
+            record.orphan_wire_bytes()?,
+        )?;
+        let needed = projected.saturating_sub(self.caps.orphan_pool_bytes);
+        if needed == 0 {
+            return Err(DaRelayError::AccountingCapExceeded);
+        }
+        let incoming = (record.orphan_fee(), record.orphan_wire_bytes()?);
+        let mut candidates = Vec::new();
+        for victim in self.sets_by_da_id.values() {
+            if victim.da_id == record.da_id || victim.state == DaRelaySetState::CompleteSet {
+                continue;
+            }
+            let rate = (victim.orphan_fee(), victim.orphan_wire_bytes()?);
+            if rate.1 != 0 && fee_rate_cmp(rate, incoming).is_lt() {
+                candidates.push((rate, victim));
+            }
+        }
+        candidates.sort_by(|(a_rate, a), (b_rate, b)| {
+            fee_rate_cmp(*a_rate, *b_rate)
+                .then(a.received_time.cmp(&b.received_time))
+                .then(a.da_id.cmp(&b.da_id))
+        });
+        let mut freed = 0u64;
+        let mut victims = Vec::new();
+        for ((_, bytes), victim) in candidates {
+            if freed >= needed {
+                break;
+            }
+            freed = checked_add(freed, bytes)?;
+            victims.push(victim.clone());
+        }
+        if freed < needed {
+            return Err(DaRelayError::AccountingCapExceeded);
+        }
+        let mut trial = self.clone();
+        let projection = trial.project_ttl_expiry(&victims)?;
+        trial.apply_ttl_expiry_projection(projection, victims);
+        trial.apply_record(record)?;
+        *self = trial;
         Ok(())
     }
 
+    /// Pinned accounting after `old` is replaced by `new`. A set reaching
+    /// COMPLETE_SET whose payload would push the counted bytes past the cap
+    /// fails with `AccountingCapExceeded` and stays unpinned.
+    fn project_pinned(
+        &self,
+        old: Option<&DaRelaySetRecord>,
+        new: Option<&DaRelaySetRecord>,
+    ) -> DaRelayResult<PinnedDaAccounting> {
+        let payload = |record: Option<&DaRelaySetRecord>| {
+            record.map_or(0, DaRelaySetRecord::pinned_payload_accounting_bytes)
+        };
+        let overhead = |record: Option<&DaRelaySetRecord>| {
+            record.map_or(0, DaRelaySetRecord::pinned_overhead_bytes)
+        };
+        Ok(PinnedDaAccounting {
+            counted_bytes: Self::project_counter(
+                self.pinned.counted_bytes,
+                payload(old),
+                payload(new),
+                self.caps.pinned_payload_bytes,
+            )?,
+            ignored_overhead_bytes: Self::project_counter(
+                self.pinned.ignored_overhead_bytes,
+                overhead(old),
+                overhead(new),
+                u64::MAX,
+            )?,
+        })
+    }
+
     fn apply_record(&mut self, record: DaRelaySetRecord) -> DaRelayResult {
         let old = self.sets_by_da_id.get(&record.da_id);
+        let old_state = old.map(|old| old.state);
         let old_bytes = old.map_or(Ok(0), DaRelaySetRecord::orphan_wire_bytes)?;
         let old_commit_bytes = old.map_or(0, DaRelaySetRecord::orphan_commit_bytes);
-        let old_pinned_bytes =
-            old.map_or(Ok(0), DaRelaySetRecord::pinned_payload_accounting_bytes)?;
         let new_bytes = record.orphan_wire_bytes()?;
         let new_commit_bytes = record.orphan_commit_bytes();
-        let new_pinned_bytes = record.pinned_payload_accounting_bytes()?;
         let peer_bytes = self.project_peer_bytes(old, &record)?;
         let da_id_current = self
             .orphan_bytes_by_da_id
@@ -962,15 +1283,10 @@ impl DaRelayState {
             new_commit_bytes,
             self.caps.orphan_commit_overhead_bytes,
         )?;
-        let pinned_payload_bytes = Self::project_counter(
-            self.pinned_payload_bytes,
-            old_pinned_bytes,
-            new_pinned_bytes,
-            self.caps.pinned_payload_bytes,
-        )?;
+        let pinned = self.project_pinned(old, Some(&record))?;
         self.orphan_bytes = orphan_bytes;
         self.orphan_commit_overhead_bytes = commit_overhead;
-        self.pinned_payload_bytes = pinned_payload_bytes;
+        self.pinned = pinned;
         if record.received_time > self.next_received_time {
             self.next_received_time = record.received_time;
         }
@@ -986,7 +1302,20 @@ impl DaRelayState {
         } else {
             self.orphan_bytes_by_da_id.insert(record.da_id, da_id_bytes);
         }
+        let completed = record.state == DaRelaySetState::CompleteSet
+            && old_state != Some(DaRelaySetState::CompleteSet);
         self.sets_by_da_id.insert(record.da_id, record);
+        if completed {
+            self.recoveries_completed = self.recoveries_completed.saturating_add(1);
+        }
+        if !self.storm_rolled_back
+            && self
+                .orphan_fill_cmp_pct(DA_ORPHAN_STORM_ENTER_FILL_PCT)
+                .is_gt()
+        {
+            self.storm_mode = true;
+            self.storm_calm_blocks = 0;
+        }
         Ok(())
     }
 
@@ -1112,6 +1441,7 @@ impl DaRelayState {
                 self.orphan_bytes_by_da_id.insert(da_id, bytes);
             }
         }
+        self.recoveries_failed = self.recoveries_failed.saturating_add(records.len() as u64);
         for record in records {
             self.sets_by_da_id.remove(&record.da_id);
         }
@@ -1122,6 +1452,9 @@ impl CompleteDaSetProvider for DaRelayState {
     fn complete_da_set_candidates(&self, max_payload_bytes: u64) -> Vec<CompleteDaSetCandidate> {
         DaRelayState::complete_da_set_candidates(self, max_payload_bytes)
     }
+    fn is_complete(&self, da_id: [u8; 32]) -> bool {
+        DaRelayState::is_complete(self, da_id)
+    }
 }
 
 fn validate_da_chunk(chunk: &DaRelayChunk) -> DaRelayResult {
@@ -1182,6 +1515,11 @@ fn relay_da_commit_payload_commitment(tx: &Tx) -> Option<[u8; 32]> {
     Some(commitment)
 }
 
+/// Orders `(fee, wire_bytes)` pairs by `fee / wire_bytes` without division.
+fn fee_rate_cmp(left: (u64, u64), right: (u64, u64)) -> std::cmp::Ordering {
+    (u128::from(left.0) * u128::from(right.1)).cmp(&(u128::from(right.0) * u128::from(left.1)))
+}
+
 fn checked_add(left: u64, right: u64) -> DaRelayResult<u64> {
     left.checked_add(right)
         .ok_or(DaRelayError::AccountingOverflow)
@@ -1339,6 +1677,8 @@ mod tests {
             orphan_commit_overhead_bytes = 0,
             orphan_ttl_blocks = 0,
             pinned_payload_bytes = 0,
+            max_commit_chunk_count = 0,
+            max_commit_chunk_count = MAX_DA_CHUNK_COUNT + 1,
             orphan_pool_per_peer_bytes = DA_ORPHAN_POOL_BYTES + 1,
             orphan_pool_per_da_id_bytes = DA_ORPHAN_POOL_BYTES + 1,
             orphan_commit_overhead_bytes = DA_ORPHAN_POOL_BYTES + 1,
@@ -1354,6 +1694,7 @@ mod tests {
         assert_eq!(caps.orphan_commit_overhead_bytes, 8 << 20);
         assert_eq!(caps.orphan_ttl_blocks, 3);
         assert_eq!(caps.pinned_payload_bytes, 96_000_000);
+        assert_eq!(caps.max_commit_chunk_count, 32_000_000 / 524_288);
         let mut state = DaRelayState::new(caps).expect("valid caps");
         assert!(state.is_empty());
         state.next_received_time = 1;
@@ -1385,18 +1726,63 @@ mod tests {
         let caps = DaRelayCaps { orphan_pool_per_peer_bytes: good_tx.len() as u64 + payload.len() as u64 - 1, ..DaRelayCaps::default() }; let mut cap_state = DaRelayState::new(caps).unwrap(); assert_eq!(cap_state.stage_relay_da_tx_bytes(peer, good_tx), Err(AccountingCapExceeded)); assert!(cap_state.is_empty());
     }
 
+    #[test]
+    fn da_relay_commit_chunk_count_cap_allocates_nothing_when_refused() {
+        let peer = "peer-a:8333";
+        let commit_tx = |da_id, chunk_count| {
+            relay_test_tx(
+                0x01,
+                vec![da_commit_output([0x44; 32])],
+                Some(relay_commit_core(da_id, chunk_count)),
+                None,
+                Vec::new(),
+            )
+        };
+        let max = MAX_DA_CHUNK_COUNT as u16;
+        let mut state = DaRelayState::new(DaRelayCaps::default()).unwrap();
+        state
+            .stage_relay_da_tx_bytes(peer, commit_tx([1; 32], max))
+            .unwrap();
+        assert_eq!(state.missing_chunk_indexes([1; 32]).len(), usize::from(max));
+
+        // max+1 and 0 never reach the pool: the parser turns them away.
+        let before = state.clone();
+        for count in [max + 1, 0] {
+            assert_eq!(
+                state.stage_relay_da_tx_bytes(peer, commit_tx([2; 32], count)),
+                Err(InvalidWireBytes)
+            );
+        }
+        assert_eq!(state, before);
+
+        // A tighter policy cap refuses a consensus-valid count before any
+        // record, byte counter or peer quota is touched.
+        let caps = DaRelayCaps::default().with_max_commit_chunk_count(4);
+        let mut state = DaRelayState::new(caps).unwrap();
+        state
+            .stage_relay_da_tx_bytes(peer, commit_tx([3; 32], 4))
+            .unwrap();
+        let before = state.clone();
+        assert_eq!(
+            state.stage_relay_da_tx_bytes(peer, commit_tx([4; 32], 5)),
+            Err(InvalidCommitChunkCount)
+        );
+        assert_eq!(state, before);
+        assert!(!state.sets_by_da_id.contains_key(&[4; 32]));
+    }
+
     #[test]
     #[rustfmt::skip]
     fn stage_returns_schedulable_da_id() {
         let peer = "peer-a:8333"; let mut state = DaRelayState::new(DaRelayCaps::default()).unwrap();
         // non-DA tx -> no schedulable da_id
-        let non_da = relay_test_tx(0x00, Vec::new(), None, None, Vec::new()); assert_eq!(state.stage_relay_da_tx_bytes_checked(peer, non_da, false).0, None);
+        let non_da = relay_test_tx(0x00, Vec::new(), None, None, Vec::new()); assert_eq!(state.stage_relay_da_tx_bytes_checked(peer, non_da, false, 0).0, None);
         // commit with a malformed (31-byte) DA_COMMIT covenant -> gated out, no da_id (mirror Go stageRelayDACommitTx)
-        let bad_out = rubin_consensus::TxOutput { value: 0, covenant_type: rubin_consensus::constants::COV_TYPE_DA_COMMIT, covenant_data: vec![4u8; 31] }; let bad_commit = relay_test_tx(0x01, vec![bad_out], Some(relay_commit_core([4u8; 32], 1)), None, Vec::new()); assert_eq!(state.stage_relay_da_tx_bytes_checked(peer, bad_commit, false).0, None);
+        let bad_out = rubin_consensus::TxOutput { value: 0, covenant_type: rubin_consensus::constants::COV_TYPE_DA_COMMIT, covenant_data: vec![4u8; 31] }; let bad_commit = relay_test_tx(0x01, vec![bad_out], Some(relay_commit_core([4u8; 32], 1)), None, Vec::new()); assert_eq!(state.stage_relay_da_tx_bytes_checked(peer, bad_commit, false, 0).0, None);
         // well-formed commit -> its da_id with an Ok stage
-        let commit_tx = relay_test_tx(0x01, vec![da_commit_output([2u8; 32])], Some(relay_commit_core([1u8; 32], 1)), None, Vec::new()); assert_eq!(state.stage_relay_da_tx_bytes_checked(peer, commit_tx, false), (Some([1u8; 32]), Ok(())));
+        let commit_tx = relay_test_tx(0x01, vec![da_commit_output([2u8; 32])], Some(relay_commit_core([1u8; 32], 1)), None, Vec::new()); assert_eq!(state.stage_relay_da_tx_bytes_checked(peer, commit_tx, false, 0), (Some([1u8; 32]), Ok(())));
         // DA chunk -> its da_id
-        let payload = b"relay chunk".to_vec(); let chunk_tx = relay_test_tx(0x02, Vec::new(), None, Some(relay_chunk_core([7u8; 32], 0, &payload)), payload.clone()); assert_eq!(state.stage_relay_da_tx_bytes_checked(peer, chunk_tx, false).0, Some([7u8; 32]));
+        let payload = b"relay chunk".to_vec(); let chunk_tx = relay_test_tx(0x02, Vec::new(), None, Some(relay_chunk_core([7u8; 32], 0, &payload)), payload.clone()); assert_eq!(state.stage_relay_da_tx_bytes_checked(peer, chunk_tx, false, 0).0, Some([7u8; 32]));
     }
 
     #[test]
@@ -1453,7 +1839,7 @@ mod tests {
     #[test]
     #[rustfmt::skip]
     fn da_relay_staged_mutation_matrix() {
-        let peer = "peer-a:8333"; let pk = || PeerQuotaKey::from_peer_addr(peer); let stage_commit = |state: &mut DaRelayState, commit| state.stage_incomplete_da_commit(peer, commit); let stage_chunk = |state: &mut DaRelayState, chunk| state.stage_incomplete_da_chunk(peer, chunk); let commit = |da_id, chunk_count, wire_bytes| DaRelayCommit { da_id, payload_commitment: [0; 32], peer_quota_key: pk(), chunk_count, wire_bytes, tx_bytes: Arc::from([]) }; let chunk = |da_id, chunk_index, payload: &[u8], wire_bytes| DaRelayChunk { da_id, chunk_hash: sha3_256(payload), peer_quota_key: pk(), chunk_index, payload: Arc::from(payload), wire_bytes, tx_bytes: Arc::from([]) }; let reject = |got: Result<(), DaRelayError>, want| assert_eq!(got, Err(want));
+        let peer = "peer-a:8333"; let pk = || PeerQuotaKey::from_peer_addr(peer); let stage_commit = |state: &mut DaRelayState, commit| state.stage_incomplete_da_commit(peer, commit); let stage_chunk = |state: &mut DaRelayState, chunk| state.stage_incomplete_da_chunk(peer, chunk); let commit = |da_id, chunk_count, wire_bytes| DaRelayCommit { da_id, payload_commitment: [0; 32], peer_quota_key: pk(), chunk_count, wire_bytes, tx_bytes: Arc::from([]), fee: 0 }; let chunk = |da_id, chunk_index, payload: &[u8], wire_bytes| DaRelayChunk { da_id, chunk_hash: sha3_256(payload), peer_quota_key: pk(), chunk_index, payload: Arc::from(payload), wire_bytes, tx_bytes: Arc::from([]), fee: 0 }; let reject = |got: Result<(), DaRelayError>, want| assert_eq!(got, Err(want));
         let mut state = DaRelayState::new(DaRelayCaps::default()).unwrap(); let mut forged_commit = commit([13; 32], 1, 2); forged_commit.peer_quota_key = PeerQuotaKey::from_peer_addr("peer-b:8333"); stage_commit(&mut state, forged_commit).unwrap(); let mut forged_chunk = chunk([14; 32], 0, b"owned", 6); forged_chunk.peer_quota_key = PeerQuotaKey::from_peer_addr("peer-b:8333"); stage_chunk(&mut state, forged_chunk).unwrap(); assert!(state.orphan_bytes_by_peer_quota_key.contains_key(&pk()) && !state.orphan_bytes_by_peer_quota_key.contains_key(&PeerQuotaKey::from_peer_addr("peer-b:8333")));
         stage_commit(&mut state, commit([1; 32], 3, 2)).unwrap(); assert!(state.sets_by_da_id[&[1; 32]].commit.is_some()); stage_chunk(&mut state, chunk([1; 32], 0, b"payload-a", 9)).unwrap(); stage_chunk(&mut state, chunk([1; 32], 1, b"payload-b", 9)).unwrap(); assert_eq!(state.sets_by_da_id[&[1; 32]].chunks.len(), 2);
         stage_chunk(&mut state, chunk([2; 32], 0, b"payload-a", 9)).unwrap(); stage_chunk(&mut state, chunk([2; 32], 1, b"payload-b", 9)).unwrap(); stage_commit(&mut state, commit([2; 32], 3, 1)).unwrap(); let record = &state.sets_by_da_id[&[2; 32]]; assert!(record.commit.is_some() && record.chunks.len() == 2); assert_eq!(state.orphan_bytes_by_da_id[&[2; 32]], record.wire_bytes);
@@ -1479,6 +1865,7 @@ mod tests {
             chunk_count: payloads.len() as u16,
             wire_bytes,
             tx_bytes: Arc::from([]),
+            fee: 0,
         };
         let chunk = |da_id, index, payload: &[u8], wire_bytes| DaRelayChunk {
             da_id,
@@ -1488,6 +1875,7 @@ mod tests {
             payload: Arc::from(payload),
             wire_bytes,
             tx_bytes: Arc::from([]),
+            fee: 0,
         };
 
         let mut state = DaRelayState::new(DaRelayCaps::default()).unwrap();
@@ -1553,6 +1941,7 @@ mod tests {
             chunk_count: payloads.len() as u16,
             wire_bytes,
             tx_bytes: Arc::from(tx_bytes),
+            fee: 0,
         };
         let chunk = |da_id, index, payload: &[u8], wire_bytes, tx_bytes: &[u8]| DaRelayChunk {
             da_id,
@@ -1562,6 +1951,7 @@ mod tests {
             payload: Arc::from(payload),
             wire_bytes,
             tx_bytes: Arc::from(tx_bytes),
+            fee: 0,
         };
 
         let mut state = DaRelayState::new(DaRelayCaps::default()).unwrap();
@@ -1637,12 +2027,12 @@ mod tests {
             .unwrap();
         let record = &state.sets_by_da_id[&[71; 32]];
         assert_eq!(record.state, DaRelaySetState::CompleteSet);
-        let chunk_count = u64::from(record.commit.as_ref().unwrap().chunk_count);
         assert!(record.chunks.values().all(|chunk| chunk.payload.is_empty()));
-        let pinned = retained_wire
-            + DA_COMPLETE_SET_RECORD_FOOTPRINT
-            + chunk_count * DA_COMPLETE_SET_CHUNK_FOOTPRINT;
-        assert_eq!(state.pinned_payload_bytes, pinned);
+        assert_eq!(state.pinned.counted_bytes(), payload.len() as u64);
+        assert_eq!(
+            state.pinned.ignored_overhead_bytes(),
+            retained_wire.saturating_sub(payload.len() as u64)
+        );
         assert_eq!(state.orphan_bytes, 0);
 
         let tight_caps = DaRelayCaps {
@@ -1698,14 +2088,14 @@ mod tests {
     #[rustfmt::skip]
     fn da_relay_ttl_expiry_matrix() {
         let peer = "peer-a:8333"; let pk = PeerQuotaKey::from_peer_addr(peer);
-        let commit = |da_id, payloads: &[&[u8]], wire_bytes| DaRelayCommit { da_id, payload_commitment: payload_commitment(payloads), peer_quota_key: pk.clone(), chunk_count: payloads.len() as u16, wire_bytes, tx_bytes: Arc::from([]) };
-        let chunk = |da_id, index, payload: &[u8], wire_bytes| DaRelayChunk { da_id, chunk_hash: sha3_256(payload), peer_quota_key: pk.clone(), chunk_index: index, payload: Arc::from(payload), wire_bytes, tx_bytes: Arc::from([]) };
+        let commit = |da_id, payloads: &[&[u8]], wire_bytes| DaRelayCommit { da_id, payload_commitment: payload_commitment(payloads), peer_quota_key: pk.clone(), chunk_count: payloads.len() as u16, wire_bytes, tx_bytes: Arc::from([]), fee: 0 };
+        let chunk = |da_id, index, payload: &[u8], wire_bytes| DaRelayChunk { da_id, chunk_hash: sha3_256(payload), peer_quota_key: pk.clone(), chunk_index: index, payload: Arc::from(payload), wire_bytes, tx_bytes: Arc::from([]), fee: 0 };
         let mut state = DaRelayState::new(DaRelayCaps::default()).unwrap(); state.stage_incomplete_da_chunk(peer, chunk([3; 32], 0, b"orphan", 6)).unwrap(); state.stage_incomplete_da_commit(peer, commit([2; 32], &[b"staged"], 7)).unwrap(); state.stage_incomplete_da_commit(peer, commit([1; 32], &[b"complete"], 8)).unwrap(); state.stage_incomplete_da_chunk(peer, chunk([1; 32], 0, b"complete", 8)).unwrap();
         let complete_before = state.sets_by_da_id[&[1; 32]].clone(); let orphan_before = state.orphan_bytes; assert!(state.advance_orphan_ttl().unwrap().is_empty()); assert_eq!(state.sets_by_da_id[&[3; 32]].ttl_blocks_remaining, 2); assert_eq!(state.sets_by_da_id[&[2; 32]].ttl_blocks_remaining, 2); assert_eq!(state.sets_by_da_id[&[1; 32]], complete_before); assert_eq!(state.orphan_bytes, orphan_before);
         let mut batch = DaRelayState::new(DaRelayCaps::default()).unwrap(); batch.stage_incomplete_da_chunk(peer, chunk([6; 32], 0, b"batch", 5)).unwrap(); assert!(batch.advance_orphan_ttl_by(0).unwrap().is_empty()); assert_eq!(batch.sets_by_da_id[&[6; 32]].ttl_blocks_remaining, 3); assert!(batch.advance_orphan_ttl_by(2).unwrap().is_empty()); assert_eq!(batch.sets_by_da_id[&[6; 32]].ttl_blocks_remaining, 1); assert_eq!(batch.advance_orphan_ttl_by(2).unwrap(), vec![[6; 32]]); assert!(batch.is_empty());
         state.sets_by_da_id.get_mut(&[3; 32]).unwrap().ttl_blocks_remaining = 1; state.sets_by_da_id.get_mut(&[2; 32]).unwrap().ttl_blocks_remaining = 0; let expired = state.advance_orphan_ttl().unwrap();
         assert_eq!(expired, vec![[2; 32], [3; 32]]);
-        assert_eq!(state.sets_by_da_id.get(&[1; 32]), Some(&complete_before)); assert!(!state.sets_by_da_id.contains_key(&[2; 32])); assert!(!state.sets_by_da_id.contains_key(&[3; 32])); assert_eq!(state.orphan_bytes, 0); assert!(state.orphan_bytes_by_da_id.is_empty()); assert!(state.orphan_bytes_by_peer_quota_key.is_empty()); assert_eq!(state.orphan_commit_overhead_bytes, 0); assert_eq!(state.pinned_payload_bytes, complete_before.pinned_payload_accounting_bytes().unwrap()); let before_noop = state.clone(); assert!(state.advance_orphan_ttl().unwrap().is_empty()); assert_eq!(state, before_noop);
+        assert_eq!(state.sets_by_da_id.get(&[1; 32]), Some(&complete_before)); assert!(!state.sets_by_da_id.contains_key(&[2; 32])); assert!(!state.sets_by_da_id.contains_key(&[3; 32])); assert_eq!(state.orphan_bytes, 0); assert!(state.orphan_bytes_by_da_id.is_empty()); assert!(state.orphan_bytes_by_peer_quota_key.is_empty()); assert_eq!(state.orphan_commit_overhead_bytes, 0); assert_eq!(state.pinned.counted_bytes(), complete_before.pinned_payload_accounting_bytes()); let before_noop = state.clone(); assert!(state.advance_orphan_ttl().unwrap().is_empty()); assert_eq!(state, before_noop);
         state.stage_incomplete_da_chunk(peer, chunk([4; 32], 0, b"corrupt", 7)).unwrap(); state.sets_by_da_id.get_mut(&[4; 32]).unwrap().ttl_blocks_remaining = 1; state.orphan_bytes = 0; let before = state.clone();
         assert_eq!(state.advance_orphan_ttl(), Err(AccountingUnderflow)); assert_eq!(state, before);
         let mut state = DaRelayState::new(DaRelayCaps::default()).unwrap(); state.stage_incomplete_da_chunk(peer, chunk([4; 32], 0, b"early", 5)).unwrap(); state.stage_incomplete_da_chunk(peer, chunk([5; 32], 0, b"late", 7)).unwrap(); state.sets_by_da_id.get_mut(&[4; 32]).unwrap().ttl_blocks_remaining = 1; state.sets_by_da_id.get_mut(&[5; 32]).unwrap().ttl_blocks_remaining = 1; state.orphan_bytes_by_da_id.insert([5; 32], 0); let before = state.clone();
@@ -1725,6 +2115,7 @@ mod tests {
             chunk_count: payloads.len() as u16,
             wire_bytes,
             tx_bytes: Arc::from([]),
+            fee: 0,
         };
         let chunk = |da_id, index, payload: &[u8], wire_bytes| DaRelayChunk {
             da_id,
@@ -1734,6 +2125,7 @@ mod tests {
             payload: Arc::from(payload),
             wire_bytes,
             tx_bytes: Arc::from([]),
+            fee: 0,
         };
 
         let mut state = DaRelayState::new(DaRelayCaps::default()).unwrap();
@@ -1811,11 +2203,11 @@ mod tests {
     #[test]
     #[rustfmt::skip]
     fn da_relay_complete_integrity_matrix() {
-        let peer = "peer-a:8333"; let pk = || PeerQuotaKey::from_peer_addr(peer); let commit = |da_id, payloads: &[&[u8]], wire_bytes| DaRelayCommit { da_id, payload_commitment: payload_commitment(payloads), peer_quota_key: pk(), chunk_count: payloads.len() as u16, wire_bytes, tx_bytes: Arc::from([]) }; let chunk = |da_id, index, payload: &[u8], wire_bytes| DaRelayChunk { da_id, chunk_hash: sha3_256(payload), peer_quota_key: pk(), chunk_index: index, payload: Arc::from(payload), wire_bytes, tx_bytes: Arc::from([]) };
-        let mut state = DaRelayState::new(DaRelayCaps::default()).unwrap(); state.stage_incomplete_da_commit(peer, commit([20; 32], &[b"aa", b"bb"], 2)).unwrap(); state.stage_incomplete_da_chunk(peer, chunk([20; 32], 0, b"aa", 2)).unwrap(); state.stage_incomplete_da_chunk(peer, chunk([20; 32], 1, b"bb", 2)).unwrap(); let record = &state.sets_by_da_id[&[20; 32]]; let chunk_count = u64::from(record.commit.as_ref().unwrap().chunk_count); assert_eq!(record.state, DaRelaySetState::CompleteSet); assert_eq!(record.payload_bytes, 4); assert_eq!(record.ttl_blocks_remaining, 0); assert!(record.chunks.values().all(|chunk| chunk.payload.is_empty())); assert_eq!(state.orphan_bytes, 0); assert!(!state.orphan_bytes_by_da_id.contains_key(&[20; 32])); assert_eq!(state.pinned_payload_bytes, record.wire_bytes + DA_COMPLETE_SET_RECORD_FOOTPRINT + chunk_count * DA_COMPLETE_SET_CHUNK_FOOTPRINT);
-        let mut state = DaRelayState::new(DaRelayCaps::default()).unwrap(); state.stage_incomplete_da_commit(peer, commit([21; 32], &[b"good"], 1)).unwrap(); assert_eq!(state.stage_incomplete_da_chunk(peer, chunk([21; 32], 0, b"bad", 3)), Err(PayloadCommitmentMismatch)); let record = &state.sets_by_da_id[&[21; 32]]; assert_eq!(record.state, DaRelaySetState::StagedCommit); assert_eq!(record.payload_bytes, 0); assert!(record.chunks.is_empty() && record.replaceable_chunks.is_empty()); assert_eq!(state.pinned_payload_bytes, 0); state.stage_incomplete_da_chunk(peer, chunk([21; 32], 0, b"good", 4)).unwrap(); let record = &state.sets_by_da_id[&[21; 32]]; assert_eq!(record.state, DaRelaySetState::CompleteSet); assert!(record.replaceable_chunks.is_empty());
-        let mut state = DaRelayState::new(DaRelayCaps::default()).unwrap(); state.stage_incomplete_da_chunk(peer, chunk([22; 32], 0, b"bad", 3)).unwrap(); assert_eq!(state.stage_incomplete_da_commit(peer, commit([22; 32], &[b"good"], 1)), Err(PayloadCommitmentMismatch)); let record = &state.sets_by_da_id[&[22; 32]]; assert_eq!(record.state, DaRelaySetState::StagedCommit); assert!(record.chunks.is_empty()); assert_eq!(state.pinned_payload_bytes, 0); state.stage_incomplete_da_chunk(peer, chunk([22; 32], 0, b"good", 4)).unwrap(); assert_eq!(state.sets_by_da_id[&[22; 32]].state, DaRelaySetState::CompleteSet);
-        let mut state = DaRelayState::new(DaRelayCaps::default()).unwrap(); state.stage_incomplete_da_commit(peer, commit([23; 32], &[b"aa", b"bb"], 1)).unwrap(); state.stage_incomplete_da_chunk(peer, chunk([23; 32], 0, b"aa", 2)).unwrap(); assert_eq!(state.stage_incomplete_da_chunk(peer, chunk([23; 32], 1, b"xx", 2)), Err(PayloadCommitmentMismatch)); let record = &state.sets_by_da_id[&[23; 32]]; assert_eq!(record.state, DaRelaySetState::StagedCommit); assert!(record.chunks.contains_key(&0) && !record.chunks.contains_key(&1)); assert!(record.replaceable_chunks.is_empty()); assert_eq!(state.pinned_payload_bytes, 0); state.stage_incomplete_da_chunk(peer, chunk([23; 32], 1, b"bb", 2)).unwrap(); assert_eq!(state.sets_by_da_id[&[23; 32]].state, DaRelaySetState::CompleteSet);
+        let peer = "peer-a:8333"; let pk = || PeerQuotaKey::from_peer_addr(peer); let commit = |da_id, payloads: &[&[u8]], wire_bytes| DaRelayCommit { da_id, payload_commitment: payload_commitment(payloads), peer_quota_key: pk(), chunk_count: payloads.len() as u16, wire_bytes, tx_bytes: Arc::from([]), fee: 0 }; let chunk = |da_id, index, payload: &[u8], wire_bytes| DaRelayChunk { da_id, chunk_hash: sha3_256(payload), peer_quota_key: pk(), chunk_index: index, payload: Arc::from(payload), wire_bytes, tx_bytes: Arc::from([]), fee: 0 };
+        let mut state = DaRelayState::new(DaRelayCaps::default()).unwrap(); state.stage_incomplete_da_commit(peer, commit([20; 32], &[b"aa", b"bb"], 2)).unwrap(); state.stage_incomplete_da_chunk(peer, chunk([20; 32], 0, b"aa", 2)).unwrap(); state.stage_incomplete_da_chunk(peer, chunk([20; 32], 1, b"bb", 2)).unwrap(); let record = &state.sets_by_da_id[&[20; 32]]; assert_eq!(record.state, DaRelaySetState::CompleteSet); assert_eq!(record.payload_bytes, 4); assert_eq!(record.ttl_blocks_remaining, 0); assert!(record.chunks.values().all(|chunk| chunk.payload.is_empty())); assert_eq!(state.orphan_bytes, 0); assert!(!state.orphan_bytes_by_da_id.contains_key(&[20; 32])); assert_eq!(state.pinned.counted_bytes(), 4);
+        let mut state = DaRelayState::new(DaRelayCaps::default()).unwrap(); state.stage_incomplete_da_commit(peer, commit([21; 32], &[b"good"], 1)).unwrap(); assert_eq!(state.stage_incomplete_da_chunk(peer, chunk([21; 32], 0, b"bad", 3)), Err(PayloadCommitmentMismatch)); let record = &state.sets_by_da_id[&[21; 32]]; assert_eq!(record.state, DaRelaySetState::StagedCommit); assert_eq!(record.payload_bytes, 0); assert!(record.chunks.is_empty() && record.replaceable_chunks.is_empty()); assert_eq!(state.pinned.counted_bytes(), 0); state.stage_incomplete_da_chunk(peer, chunk([21; 32], 0, b"good", 4)).unwrap(); let record = &state.sets_by_da_id[&[21; 32]]; assert_eq!(record.state, DaRelaySetState::CompleteSet); assert!(record.replaceable_chunks.is_empty());
+        let mut state = DaRelayState::new(DaRelayCaps::default()).unwrap(); state.stage_incomplete_da_chunk(peer, chunk([22; 32], 0, b"bad", 3)).unwrap(); assert_eq!(state.stage_incomplete_da_commit(peer, commit([22; 32], &[b"good"], 1)), Err(PayloadCommitmentMismatch)); let record = &state.sets_by_da_id[&[22; 32]]; assert_eq!(record.state, DaRelaySetState::StagedCommit); assert!(record.chunks.is_empty()); assert_eq!(state.pinned.counted_bytes(), 0); state.stage_incomplete_da_chunk(peer, chunk([22; 32], 0, b"good", 4)).unwrap(); assert_eq!(state.sets_by_da_id[&[22; 32]].state, DaRelaySetState::CompleteSet);
+        let mut state = DaRelayState::new(DaRelayCaps::default()).unwrap(); state.stage_incomplete_da_commit(peer, commit([23; 32], &[b"aa", b"bb"], 1)).unwrap(); state.stage_incomplete_da_chunk(peer, chunk([23; 32], 0, b"aa", 2)).unwrap(); assert_eq!(state.stage_incomplete_da_chunk(peer, chunk([23; 32], 1, b"xx", 2)), Err(PayloadCommitmentMismatch)); let record = &state.sets_by_da_id[&[23; 32]]; assert_eq!(record.state, DaRelaySetState::StagedCommit); assert!(record.chunks.contains_key(&0) && !record.chunks.contains_key(&1)); assert!(record.replaceable_chunks.is_empty()); assert_eq!(state.pinned.counted_bytes(), 0); state.stage_incomplete_da_chunk(peer, chunk([23; 32], 1, b"bb", 2)).unwrap(); assert_eq!(state.sets_by_da_id[&[23; 32]].state, DaRelaySetState::CompleteSet);
         let caps = DaRelayCaps { pinned_payload_bytes: 1, ..DaRelayCaps::default() }; let mut state = DaRelayState::new(caps).unwrap(); state.stage_incomplete_da_commit(peer, commit([24; 32], &[b"aa"], 1)).unwrap(); let before = state.clone(); assert_eq!(state.stage_incomplete_da_chunk(peer, chunk([24; 32], 0, b"aa", 2)), Err(AccountingCapExceeded)); assert_eq!(state, before);
     }
 
@@ -1830,6 +2222,7 @@ mod tests {
             chunk_count: payloads.len() as u16,
             wire_bytes: tx_bytes.len().max(1) as u64,
             tx_bytes: Arc::from(tx_bytes),
+            fee: 0,
         };
         let chunk = |da_id, index, payload: &[u8], tx_bytes: &[u8]| DaRelayChunk {
             da_id,
@@ -1839,6 +2232,7 @@ mod tests {
             payload: Arc::from(payload),
             wire_bytes: payload.len() as u64,
             tx_bytes: Arc::from(tx_bytes),
+            fee: 0,
         };
         let early_payload_0: &[u8] = b"early-0";
         let early_payload_1: &[u8] = b"early-1";
@@ -2006,6 +2400,7 @@ mod tests {
             chunk_count: payloads.len() as u16,
             wire_bytes,
             tx_bytes: Arc::from(tx_bytes),
+            fee: 0,
         };
         let chunk = |da_id, index, payload: &[u8], wire_bytes, tx_bytes: &[u8]| DaRelayChunk {
             da_id,
@@ -2015,6 +2410,7 @@ mod tests {
             payload: Arc::from(payload),
             wire_bytes,
             tx_bytes: Arc::from(tx_bytes),
+            fee: 0,
         };
 
         let da_id = [80u8; 32];
@@ -2035,7 +2431,7 @@ mod tests {
             state.sets_by_da_id[&da_id].state,
             DaRelaySetState::CompleteSet
         );
-        assert!(state.pinned_payload_bytes > 0);
+        assert!(state.pinned.counted_bytes() > 0);
 
         // A block whose DA txs reference the same da_id (one commit + chunk 0).
         let block = block_with_txs(
@@ -2065,7 +2461,7 @@ mod tests {
         consume_accepted_block_da_sets(&relay, &block).expect("consume");
         let relay = relay.lock().unwrap();
         assert!(!relay.sets_by_da_id.contains_key(&da_id));
-        assert_eq!(relay.pinned_payload_bytes, 0);
+        assert_eq!(relay.pinned.counted_bytes(), 0);
     }
 
     #[test]
@@ -2083,6 +2479,7 @@ mod tests {
             chunk_count: payloads.len() as u16,
             wire_bytes,
             tx_bytes: Arc::from(tx_bytes),
+            fee: 0,
         };
         let chunk = |da_id, index, payload: &[u8], wire_bytes, tx_bytes: &[u8]| DaRelayChunk {
             da_id,
@@ -2092,6 +2489,7 @@ mod tests {
             payload: Arc::from(payload),
             wire_bytes,
             tx_bytes: Arc::from(tx_bytes),
+            fee: 0,
         };
         let block_for = |da_id, ts| {
             block_with_txs(
@@ -2153,7 +2551,7 @@ mod tests {
         let guard = relay.lock().unwrap();
         assert!(!guard.sets_by_da_id.contains_key(&id_a));
         assert!(!guard.sets_by_da_id.contains_key(&id_b));
-        assert_eq!(guard.pinned_payload_bytes, 0);
+        assert_eq!(guard.pinned.counted_bytes(), 0);
         drop(guard);
 
         // Fail-closed: a malformed canonical block surfaces an error.
@@ -2210,7 +2608,7 @@ mod tests {
             !guard3.sets_by_da_id.contains_key(&id_d),
             "block after the malformed one is still consumed (best-effort)"
         );
-        assert_eq!(guard3.pinned_payload_bytes, 0);
+        assert_eq!(guard3.pinned.counted_bytes(), 0);
     }
 
     #[test]
@@ -2228,6 +2626,7 @@ mod tests {
             chunk_count: payloads.len() as u16,
             wire_bytes,
             tx_bytes: Arc::from(tx_bytes),
+            fee: 0,
         };
         let chunk = |da_id, index, payload: &[u8], wire_bytes, tx_bytes: &[u8]| DaRelayChunk {
             da_id,
@@ -2237,6 +2636,7 @@ mod tests {
             payload: Arc::from(payload),
             wire_bytes,
             tx_bytes: Arc::from(tx_bytes),
+            fee: 0,
         };
 
         let mut state = DaRelayState::new(DaRelayCaps::default()).unwrap();
@@ -2256,7 +2656,7 @@ mod tests {
             state.sets_by_da_id[&[71; 32]].state,
             DaRelaySetState::CompleteSet
         );
-        let pinned_one = state.pinned_payload_bytes;
+        let pinned_one = state.pinned.counted_bytes();
         assert!(pinned_one > 0);
 
         // Second, unrelated complete set with identical pinned footprint.
@@ -2272,7 +2672,7 @@ mod tests {
                 chunk([73; 32], 0, payload, payload_wire, chunk_tx),
             )
             .unwrap();
-        assert_eq!(state.pinned_payload_bytes, pinned_one * 2);
+        assert_eq!(state.pinned.counted_bytes(), pinned_one * 2);
 
         // Mirror of Go RUB-428: consume removes the matching COMPLETE_SET and
         // releases its pinned payload bytes; the unrelated set is untouched.
@@ -2282,11 +2682,11 @@ mod tests {
             state.sets_by_da_id[&[73; 32]].state,
             DaRelaySetState::CompleteSet
         );
-        assert_eq!(state.pinned_payload_bytes, pinned_one);
+        assert_eq!(state.pinned.counted_bytes(), pinned_one);
 
         // Second consume cannot underflow: no-op false, accounting unchanged.
         assert_eq!(state.consume_complete_set([71; 32]), Ok(false));
-        assert_eq!(state.pinned_payload_bytes, pinned_one);
+        assert_eq!(state.pinned.counted_bytes(), pinned_one);
 
         // Incomplete set (chunk only, no commit) is not consumed.
         state
@@ -2306,6 +2706,169 @@ mod tests {
         assert_eq!(state.consume_complete_set([99; 32]), Ok(false));
     }
 
+    #[test]
+    fn da_relay_pinned_accounting_counts_payload_only() {
+        let peer = "peer-a:8333";
+        let commit_tx = [0xc0; 200];
+        let chunk_tx = [0xc1; 64];
+        let commit = |da_id, payload: &[u8]| DaRelayCommit {
+            da_id,
+            payload_commitment: payload_commitment(&[payload]),
+            peer_quota_key: PeerQuotaKey::from_peer_addr(peer),
+            chunk_count: 1,
+            wire_bytes: commit_tx.len() as u64,
+            tx_bytes: Arc::from(&commit_tx[..]),
+            fee: 0,
+        };
+        let chunk = |da_id, payload: &[u8]| DaRelayChunk {
+            da_id,
+            chunk_hash: sha3_256(payload),
+            peer_quota_key: PeerQuotaKey::from_peer_addr(peer),
+            chunk_index: 0,
+            payload: Arc::from(payload),
+            wire_bytes: chunk_tx.len() as u64,
+            tx_bytes: Arc::from(&chunk_tx[..]),
+            fee: 0,
+        };
+        let overhead = (commit_tx.len() + chunk_tx.len()) as u64;
+        let caps = DaRelayCaps::default().with_pinned_payload_bytes(20);
+        let mut state = DaRelayState::new(caps).unwrap();
+
+        // Commit overhead (264 bytes here) never counts against the 20-byte cap.
+        state
+            .stage_incomplete_da_commit(peer, commit([1; 32], &[0xa1; 12]))
+            .unwrap();
+        state
+            .stage_incomplete_da_chunk(peer, chunk([1; 32], &[0xa1; 12]))
+            .unwrap();
+        assert_eq!(state.pinned_accounting().counted_bytes(), 12);
+        assert_eq!(
+            state.pinned_accounting().ignored_overhead_bytes(),
+            overhead - 12
+        );
+
+        // Landing exactly on the cap is admitted.
+        assert!(state.pinned_accounting().admits(8, 20));
+        state
+            .stage_incomplete_da_commit(peer, commit([2; 32], &[0xa2; 8]))
+            .unwrap();
+        state
+            .stage_incomplete_da_chunk(peer, chunk([2; 32], &[0xa2; 8]))
+            .unwrap();
+        assert_eq!(state.pinned_accounting().counted_bytes(), 20);
+        assert_eq!(
+            state.pinned_accounting().ignored_overhead_bytes(),
+            2 * overhead - 20
+        );
+
+        // One byte over is refused and the set stays unpinned.
+        assert!(!state.pinned_accounting().admits(1, 20));
+        state
+            .stage_incomplete_da_commit(peer, commit([3; 32], &[0xa3]))
+            .unwrap();
+        let before = state.clone();
+        assert_eq!(
+            state.stage_incomplete_da_chunk(peer, chunk([3; 32], &[0xa3])),
+            Err(AccountingCapExceeded)
+        );
+        assert_eq!(state, before);
+        assert_eq!(
+            state.sets_by_da_id[&[3; 32]].state,
+            DaRelaySetState::StagedCommit
+        );
+
+        // Confirmation releases both counters and makes room again.
+        assert_eq!(state.consume_complete_set([1; 32]), Ok(true));
+        assert_eq!(state.pinned_accounting().counted_bytes(), 8);
+        assert_eq!(
+            state.pinned_accounting().ignored_overhead_bytes(),
+            overhead - 8
+        );
+        state
+            .stage_incomplete_da_chunk(peer, chunk([3; 32], &[0xa3]))
+            .unwrap();
+        assert_eq!(state.pinned_accounting().counted_bytes(), 9);
+        assert_eq!(state.consume_complete_set([2; 32]), Ok(true));
+        assert_eq!(state.consume_complete_set([3; 32]), Ok(true));
+        assert!(state.is_empty());
+    }
+
+    #[test]
+    fn da_relay_checkblock_follows_state_machine_events() {
+        let peer = "peer-a:8333";
+        let payloads: [&[u8]; 2] = [b"first chunk", b"second chunk"];
+        let commit_tx = |da_id, chunk_count| {
+            relay_test_tx(
+                0x01,
+                vec![da_commit_output(payload_commitment(&payloads))],
+                Some(relay_commit_core(da_id, chunk_count)),
+                None,
+                Vec::new(),
+            )
+        };
+        let chunk_tx = |da_id, index: u16| {
+            let payload = payloads[usize::from(index)];
+            relay_test_tx(
+                0x02,
+                Vec::new(),
+                None,
+                Some(relay_chunk_core(da_id, index, payload)),
+                payload.to_vec(),
+            )
+        };
+        let mut state = DaRelayState::new(DaRelayCaps::default()).unwrap();
+
+        // CV-C-11: commit, checkblock, chunk 0, checkblock, chunk 1, checkblock.
+        let da_id = [0x31; 32];
+        let mut checkblock = Vec::new();
+        state
+            .stage_relay_da_tx_bytes(peer, commit_tx(da_id, 2))
+            .unwrap();
+        checkblock.push(state.is_complete(da_id));
+        state
+            .stage_relay_da_tx_bytes(peer, chunk_tx(da_id, 0))
+            .unwrap();
+        checkblock.push(state.is_complete(da_id));
+        assert_eq!(state.set_payload(da_id), None);
+        state
+            .stage_relay_da_tx_bytes(peer, chunk_tx(da_id, 1))
+            .unwrap();
+        checkblock.push(state.is_complete(da_id));
+        assert_eq!(checkblock, vec![false, false, true]);
+        assert_eq!(
+            state.set_payload(da_id),
+            Some([payloads[0], payloads[1]].concat())
+        );
+
+        // A retained chunk tx that no longer matches the commit is not served.
+        let mut tampered = state.clone();
+        let record = tampered.sets_by_da_id.get_mut(&da_id).unwrap();
+        let swapped = Arc::clone(&record.chunks[&1].tx_bytes);
+        record.chunks.get_mut(&0).unwrap().tx_bytes = swapped;
+        assert!(tampered.is_complete(da_id));
+        assert_eq!(tampered.set_payload(da_id), None);
+
+        // CV-C-10: a partial set expires after its TTL and never answers true.
+        let partial = [0x32; 32];
+        state
+            .stage_relay_da_tx_bytes(peer, chunk_tx(partial, 0))
+            .unwrap();
+        state
+            .stage_relay_da_tx_bytes(peer, commit_tx(partial, 2))
+            .unwrap();
+        assert!(!state.is_complete(partial));
+        assert_eq!(state.advance_orphan_ttl_by(3).unwrap(), vec![partial]);
+        assert!(!state.is_complete(partial));
+        assert_eq!(state.set_payload(partial), None);
+
+        // The pinned set survives ticks and stops answering once consumed.
+        assert!(state.is_complete(da_id));
+        assert_eq!(state.consume_complete_set(da_id), Ok(true));
+        assert!(!state.is_complete(da_id));
+        assert_eq!(state.set_payload(da_id), None);
+        assert!(!state.is_complete([0x33; 32]));
+    }
+
     #[test]
     fn da_relay_eviction_accounting_matrix() {
         let peer = "peer-a:8333";
@@ -2317,6 +2880,7 @@ mod tests {
             chunk_count: payloads.len() as u16,
             wire_bytes,
             tx_bytes: Arc::from([]),
+            fee: 0,
         };
         let chunk = |da_id, index, payload: &[u8], wire_bytes| DaRelayChunk {
             da_id,
@@ -2326,6 +2890,7 @@ mod tests {
             payload: Arc::from(payload),
             wire_bytes,
             tx_bytes: Arc::from([]),
+            fee: 0,
         };
         let mut state = DaRelayState::new(DaRelayCaps::default()).unwrap();
         state
@@ -2397,4 +2962,211 @@ mod tests {
         assert_eq!(zero_budget.size_hint(), (0, Some(0)));
         assert!(zero_budget.next().is_none());
     }
+
+    fn small_orphan_caps() -> DaRelayCaps {
+        DaRelayCaps {
+            orphan_pool_bytes: 100,
+            orphan_pool_per_peer_bytes: 100,
+            orphan_pool_per_da_id_bytes: 100,
+            orphan_commit_overhead_bytes: 100,
+            orphan_ttl_blocks: 10,
+            ..DaRelayCaps::default()
+        }
+    }
+
+    #[test]
+    fn da_relay_orphan_storm_mode_matrix() {
+        let (peer_a, peer_b) = ("peer-a:8333", "peer-b:8333");
+        let mut state = DaRelayState::new(small_orphan_caps()).unwrap();
+        state
+            .test_stage_incomplete_da_chunk(peer_b, [1; 32], 0, b"a", 50)
+            .unwrap();
+        state
+            .test_stage_incomplete_da_chunk(peer_a, [2; 32], 0, b"b", 40)
+            .unwrap();
+        // Exactly 90% fill does not trigger; the enter threshold is strict.
+        assert_eq!(state.orphan_pool_fill_pct(), 90);
+        assert!(!state.storm_mode_active());
+        state
+            .test_stage_incomplete_da_chunk(peer_a, [3; 32], 0, b"c", 1)
+            .unwrap();
+        assert!(state.storm_mode_active());
+
+        // Orphan chunks without a staged commit are refused during storm.
+        let before = state.clone();
+        assert_eq!(
+            state.test_stage_incomplete_da_chunk(peer_a, [4; 32], 0, b"d", 1),
+            Err(DaRelayError::StormModeRejected)
+        );
+        assert_eq!(
+            state.test_stage_incomplete_da_chunk(peer_a, [3; 32], 1, b"e", 1),
+            Err(DaRelayError::StormModeRejected)
+        );
+        assert_eq!(state, before);
+
+        // Commit-bearing data is still admitted.
+        state
+            .test_stage_incomplete_da_commit(peer_a, [5; 32], 2, 2)
+            .unwrap();
+        state
+            .test_stage_incomplete_da_chunk(peer_a, [5; 32], 0, b"f", 1)
+            .unwrap();
+        assert_eq!(state.test_record_summary([5; 32]), Some((true, 1, 3)));
+
+        // Exit needs fill below 70% for 3 consecutive accepted blocks.
+        state.advance_orphan_ttl_by(3).unwrap();
+        assert!(state.storm_mode_active());
+        state
+            .release_peer_quota_key(&PeerQuotaKey::from_peer_addr(peer_b))
+            .unwrap();
+        assert_eq!(state.orphan_pool_fill_pct(), 44);
+        state.advance_orphan_ttl_by(2).unwrap();
+        assert!(state.storm_mode_active());
+        state.advance_orphan_ttl().unwrap();
+        assert!(!state.storm_mode_active());
+        state
+            .test_stage_incomplete_da_chunk(peer_a, [4; 32], 0, b"d", 1)
+            .unwrap();
+    }
+
+    #[test]
+    fn da_relay_storm_rollback_on_low_recovery_rate() {
+        use crate::da_recovery::DA_RECOVERY_ROLLBACK_OBSERVATION_MS;
+        let peer = "peer-a:8333";
+        let mut state = DaRelayState::new(small_orphan_caps()).unwrap();
+
+        // Two partial sets expire (failures), one completes (success).
+        state
+            .test_stage_incomplete_da_chunk(peer, [1; 32], 0, b"a", 10)
+            .unwrap();
+        state
+            .test_stage_incomplete_da_chunk(peer, [2; 32], 0, b"b", 10)
+            .unwrap();
+        assert_eq!(state.advance_orphan_ttl_by(10).unwrap().len(), 2);
+        let payload: &[u8] = b"c";
+        state
+            .stage_incomplete_da_commit(
+                peer,
+                DaRelayCommit {
+                    da_id: [3; 32],
+                    payload_commitment: payload_commitment(&[payload]),
+                    peer_quota_key: PeerQuotaKey::from_peer_addr(peer),
+                    chunk_count: 1,
+                    wire_bytes: 1,
+                    tx_bytes: Arc::from([]),
+                    fee: 0,
+                },
+            )
+            .unwrap();
+        state
+            .test_stage_incomplete_da_chunk(peer, [3; 32], 0, payload, 1)
+            .unwrap();
+        assert_eq!(
+            (state.recoveries_completed, state.recoveries_failed),
+            (1, 2)
+        );
+        let start = 1_000;
+        assert!(!state.observe_set_recoveries(start));
+        assert_eq!(
+            (state.recoveries_completed, state.recoveries_failed),
+            (0, 0)
+        );
+        assert!((state.set_recovery_success_rate() - 100.0 / 3.0).abs() < 1e-9);
+
+        // Fill past 90% enters storm mode; non-commit chunks are refused.
+        state
+            .test_stage_incomplete_da_chunk(peer, [4; 32], 0, b"d", 91)
+            .unwrap();
+        assert!(state.storm_mode_active());
+        assert_eq!(
+            state.test_stage_incomplete_da_chunk(peer, [5; 32], 0, b"e", 1),
+            Err(DaRelayError::StormModeRejected)
+        );
+
+        // One more failure keeps the rate under 95% inside the window; the
+        // rollback fires exactly when 10 minutes of observation have passed.
+        state.recoveries_failed = 1;
+        assert!(!state.observe_set_recoveries(start + DA_RECOVERY_ROLLBACK_OBSERVATION_MS / 2));
+        assert!(!state.observe_set_recoveries(start + DA_RECOVERY_ROLLBACK_OBSERVATION_MS - 1));
+        assert!(state.storm_mode_active());
+        assert!(state.observe_set_recoveries(start + DA_RECOVERY_ROLLBACK_OBSERVATION_MS));
+        assert!(!state.storm_mode_active());
+
+        // Rolled back: non-commit-bearing data is admitted again even though
+        // fill is still above the entry threshold.
+        state
+            .test_stage_incomplete_da_chunk(peer, [5; 32], 0, b"e", 1)
+            .unwrap();
+        assert!(state
+            .orphan_fill_cmp_pct(DA_ORPHAN_STORM_ENTER_FILL_PCT)
+            .is_gt());
+        assert!(!state.storm_mode_active());
+
+        // Once fill drains below the exit threshold the latch clears and a
+        // fresh fill spike enters storm mode again.
+        state.advance_orphan_ttl_by(10).unwrap();
+        assert!(!state.storm_rolled_back);
+        state
+            .test_stage_incomplete_da_chunk(peer, [6; 32], 0, b"f", 92)
+            .unwrap();
+        assert!(state.storm_mode_active());
+    }
+
+    #[test]
+    fn da_relay_orphan_fee_rate_eviction_matrix() {
+        let peer = "peer-a:8333";
+        let chunk = |da_id, wire_bytes, fee| DaRelayChunk {
+            da_id,
+            chunk_hash: sha3_256(b"x"),
+            peer_quota_key: PeerQuotaKey::from_peer_addr(peer),
+            chunk_index: 0,
+            payload: Arc::from(&b"x"[..]),
+            wire_bytes,
+            tx_bytes: Arc::from([]),
+            fee,
+        };
+        let mut state = DaRelayState::new(small_orphan_caps()).unwrap();
+        for (da_id, fee) in [([1; 32], 30), ([2; 32], 15), ([3; 32], 15)] {
+            state
+                .stage_incomplete_da_chunk(peer, chunk(da_id, 30, fee))
+                .unwrap();
+        }
+
+        // A set paying no more than every resident set cannot displace any.
+        let before = state.clone();
+        assert_eq!(
+            state.stage_incomplete_da_chunk(peer, chunk([9; 32], 40, 0)),
+            Err(DaRelayError::AccountingCapExceeded)
+        );
+        assert_eq!(state, before);
+
+        // Lowest fee rate goes first; equal rates fall back to received_time.
+        state
+            .stage_incomplete_da_chunk(peer, chunk([8; 32], 40, 40))
+            .unwrap();
+        let resident =
+            |state: &DaRelayState| state.sets_by_da_id.keys().copied().collect::<Vec<_>>();
+        assert_eq!(resident(&state), vec![[1; 32], [3; 32], [8; 32]]);
+        assert_eq!(state.orphan_bytes, 100);
+        assert_eq!(state.orphan_bytes_by_da_id.get(&[2; 32]), None);
+        assert!(state.storm_mode_active());
+
+        // Storm still admits a commit, which evicts the next-cheapest set.
+        state
+            .stage_incomplete_da_commit(
+                peer,
+                DaRelayCommit {
+                    da_id: [7; 32],
+                    payload_commitment: [0; 32],
+                    peer_quota_key: PeerQuotaKey::from_peer_addr(peer),
+                    chunk_count: 1,
+                    wire_bytes: 25,
+                    tx_bytes: Arc::from([]),
+                    fee: 100,
+                },
+            )
+            .unwrap();
+        assert_eq!(resident(&state), vec![[1; 32], [7; 32], [8; 32]]);
+        assert_eq!(state.orphan_bytes, 95);
+    }
 }
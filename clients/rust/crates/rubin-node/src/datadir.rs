@@ -0,0 +1,138 @@
+//! The datadir a running node owns: its layout, its lock and the chain it
+//! was initialized for.
+//!
+//! `datadir.json` records the node version that created the datadir and
+//! its chain_id. Opening the datadir with a different chain_id is refused,
+//! so a node started with the wrong `--network` / `--chain` cannot mix two
+//! chains' blocks and UTXOs. A pre-existing datadir without the manifest is
+//! adopted on first open.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::blockstore::block_store_path;
+use crate::chainstate::chain_state_path;
+use crate::datadir_lock::DataDirLock;
+use crate::io_utils::{parse_hex32, write_file_atomic};
+
+pub const DATADIR_MANIFEST_FILE_NAME: &str = "datadir.json";
+pub const DATADIR_MANIFEST_VERSION: u32 = 1;
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+struct DatadirManifest {
+    version: u32,
+    node_version: String,
+    chain_id_hex: String,
+}
+
+/// An open, locked datadir. Runtime subsystems take their paths from here.
+#[derive(Debug)]
+pub struct Datadir {
+    root: PathBuf,
+    _lock: DataDirLock,
+}
+
+impl Datadir {
+    /// Creates `root` if needed, locks it for this process and checks (or
+    /// records) its chain_id.
+    pub fn open(root: &Path, chain_id: [u8; 32]) -> Result<Self, String> {
+        fs::create_dir_all(root)
+            .map_err(|err| format!("datadir create failed ({}): {err}", root.display()))?;
+        let lock = DataDirLock::acquire(root)?;
+        let manifest_path = root.join(DATADIR_MANIFEST_FILE_NAME);
+        match fs::read(&manifest_path) {
+            Ok(raw) => {
+                let manifest: DatadirManifest = serde_json::from_slice(&raw)
+                    .map_err(|err| format!("parse {}: {err}", manifest_path.display()))?;
+                if manifest.version != DATADIR_MANIFEST_VERSION {
+                    return Err(format!(
+                        "unsupported datadir manifest version {} in {}",
+                        manifest.version,
+                        manifest_path.display()
+                    ));
+                }
+                let recorded = parse_hex32("datadir chain_id", &manifest.chain_id_hex)?;
+                if recorded != chain_id {
+                    return Err(format!(
+                        "datadir {} was initialized for chain_id {}, not {}",
+                        root.display(),
+                        manifest.chain_id_hex,
+                        hex::encode(chain_id)
+                    ));
+                }
+            }
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+                let manifest = DatadirManifest {
+                    version: DATADIR_MANIFEST_VERSION,
+                    node_version: env!("CARGO_PKG_VERSION").to_string(),
+                    chain_id_hex: hex::encode(chain_id),
+                };
+                let raw = serde_json::to_vec_pretty(&manifest)
+                    .map_err(|err| format!("encode datadir manifest: {err}"))?;
+                write_file_atomic(&manifest_path, &raw)?;
+            }
+            Err(err) => {
+                return Err(format!("read {}: {err}", manifest_path.display()));
+            }
+        }
+        Ok(Self {
+            root: root.to_path_buf(),
+            _lock: lock,
+        })
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    pub fn chain_state_path(&self) -> PathBuf {
+        chain_state_path(&self.root)
+    }
+
+    pub fn block_store_path(&self) -> PathBuf {
+        block_store_path(&self.root)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::io_utils::unique_temp_path;
+
+    #[test]
+    fn datadir_open_records_chain_id_and_rejects_mismatch_and_second_open() {
+        let root = unique_temp_path("rubin-node-datadir");
+        let datadir = Datadir::open(&root, [0x11; 32]).expect("fresh init");
+        assert_eq!(datadir.chain_state_path(), root.join("chainstate.json"));
+        assert_eq!(datadir.block_store_path(), root.join("blockstore"));
+        let manifest = fs::read(root.join(DATADIR_MANIFEST_FILE_NAME)).expect("manifest");
+
+        let err = Datadir::open(&root, [0x11; 32]).expect_err("second open");
+        assert!(err.contains("is in use by another rubin-node"), "{err}");
+        drop(datadir);
+
+        // Reopening is idempotent: the manifest is left as written.
+        drop(Datadir::open(&root, [0x11; 32]).expect("reopen"));
+        assert_eq!(
+            fs::read(root.join(DATADIR_MANIFEST_FILE_NAME)).expect("manifest"),
+            manifest
+        );
+
+        let err = Datadir::open(&root, [0x22; 32]).expect_err("chain_id mismatch");
+        assert_eq!(
+            err,
+            format!(
+                "datadir {} was initialized for chain_id {}, not {}",
+                root.display(),
+                "11".repeat(32),
+                "22".repeat(32)
+            )
+        );
+        // The failed open released its lock.
+        drop(Datadir::open(&root, [0x11; 32]).expect("open after mismatch"));
+
+        fs::remove_dir_all(&root).expect("cleanup");
+    }
+}
@@ -0,0 +1,110 @@
+//! Exclusive ownership of a datadir by one running node.
+//!
+//! The lock is an advisory OS file lock on `<datadir>/rubin-node.lock`,
+//! which also records the holder's PID for the error message. The file is
+//! never deleted, only emptied on release: unlinking it could let two nodes
+//! lock different inodes under the same name. The OS drops the lock when
+//! the holder exits, however it exits, so a leftover PID from a crashed node
+//! never blocks a restart.
+
+use std::fs::{File, OpenOptions, TryLockError};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+pub const DATADIR_LOCK_FILE_NAME: &str = "rubin-node.lock";
+
+/// Held for as long as the node runs; dropping it releases the datadir.
+#[derive(Debug)]
+pub struct DataDirLock {
+    file: File,
+    path: PathBuf,
+}
+
+impl DataDirLock {
+    /// Locks `data_dir` (which must exist) and records this process's PID.
+    /// Fails if another instance, in this process or another, holds it.
+    pub fn acquire(data_dir: &Path) -> Result<Self, String> {
+        let path = data_dir.join(DATADIR_LOCK_FILE_NAME);
+        let mut file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(&path)
+            .map_err(|err| format!("datadir lock open failed ({}): {err}", path.display()))?;
+        match file.try_lock() {
+            Ok(()) => {}
+            Err(TryLockError::WouldBlock) => {
+                let mut holder = String::new();
+                let _ = file.read_to_string(&mut holder);
+                let holder = holder.trim();
+                let holder = if holder.is_empty() { "unknown" } else { holder };
+                return Err(format!(
+                    "datadir {} is in use by another rubin-node (pid {holder})",
+                    data_dir.display()
+                ));
+            }
+            Err(TryLockError::Error(err)) => {
+                return Err(format!("datadir lock failed ({}): {err}", path.display()));
+            }
+        }
+        file.set_len(0)
+            .and_then(|()| file.seek(SeekFrom::Start(0)))
+            .and_then(|_| writeln!(file, "{}", std::process::id()))
+            .and_then(|()| file.sync_all())
+            .map_err(|err| format!("datadir lock write failed ({}): {err}", path.display()))?;
+        Ok(Self { file, path })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for DataDirLock {
+    fn drop(&mut self) {
+        // Clear the PID while still locked so a reader never sees a stale
+        // holder.
+        let _ = self.file.set_len(0);
+        let _ = self.file.unlock();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::io_utils::unique_temp_path;
+    use std::fs;
+
+    #[test]
+    fn datadir_lock_excludes_second_holder_until_dropped() {
+        let dir = unique_temp_path("rubin-node-datadir-lock");
+        fs::create_dir_all(&dir).expect("mkdir");
+
+        let lock = DataDirLock::acquire(&dir).expect("first lock");
+        let recorded = fs::read_to_string(lock.path()).expect("read lock file");
+        assert_eq!(recorded.trim(), std::process::id().to_string());
+
+        let err = DataDirLock::acquire(&dir).expect_err("second lock");
+        assert_eq!(
+            err,
+            format!(
+                "datadir {} is in use by another rubin-node (pid {})",
+                dir.display(),
+                std::process::id()
+            )
+        );
+
+        drop(lock);
+        let released = fs::read_to_string(dir.join(DATADIR_LOCK_FILE_NAME)).expect("read");
+        assert!(released.is_empty());
+        let lock = DataDirLock::acquire(&dir).expect("relock after drop");
+        drop(lock);
+
+        // A leftover file from a crashed holder carries no OS lock.
+        fs::write(dir.join(DATADIR_LOCK_FILE_NAME), "4242\n").expect("stale lock file");
+        DataDirLock::acquire(&dir).expect("stale lock file is reclaimed");
+
+        fs::remove_dir_all(&dir).expect("cleanup");
+    }
+}
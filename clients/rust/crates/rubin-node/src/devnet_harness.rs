@@ -0,0 +1,456 @@
+//! Seeded devnet harness behind `rubin-node devnet`.
+//!
+//! Mines `blocks` blocks into a fresh devnet datadir with every key derived
+//! from one `u64` seed, block timestamps pinned to `MTP + 1` and signatures
+//! made with deterministic ML-DSA. Once the first coinbase matures each block
+//! also carries up to two spends from the harness key, and the outputs they
+//! create walk every covenant type a devnet tx may create. Two runs with the
+//! same seed therefore end on the same tip hash and `utxo_set_hash`, which is
+//! what cross-run and cross-client comparisons key on.
+//!
+//! Devnet-only: the keys are derived from a public label and the seed, so
+//! anything they control is spendable by anyone who knows the seed.
+
+use std::path::PathBuf;
+
+use rubin_consensus::constants::{
+    COINBASE_MATURITY, COV_TYPE_ANCHOR, COV_TYPE_CORE_STEALTH, COV_TYPE_HTLC, COV_TYPE_MULTISIG,
+    COV_TYPE_P2PK, COV_TYPE_VAULT, LOCK_MODE_HEIGHT, ML_KEM_1024_CT_BYTES, POW_LIMIT,
+    TX_WIRE_VERSION,
+};
+use rubin_consensus::{
+    marshal_tx, output_descriptor_hash, p2pk_covenant_data_for_pubkey, sign_transaction,
+    DigestSigner, Mldsa87Keypair, Outpoint, Tx, TxError, TxInput, TxOutput, UtxoEntry,
+};
+use serde::Serialize;
+use sha3::{Digest, Sha3_256};
+
+use crate::blockstore::BlockStore;
+use crate::chainstate::{load_chain_state, ChainState};
+use crate::datadir::Datadir;
+use crate::genesis::load_genesis_config;
+use crate::miner::{Miner, MinerConfig};
+use crate::op_error::OpError;
+use crate::sync::{default_sync_config, SyncEngine};
+
+/// Seed used when `--seed` is not given.
+pub const DEFAULT_DEVNET_HARNESS_SEED: u64 = 1;
+
+/// Value locked in each covenant-tour output (ANCHOR outputs carry 0).
+const TOUR_OUTPUT_VALUE: u64 = 1_000_000;
+/// Flat fee paid by every harness spend.
+const SPEND_FEE: u64 = 100_000;
+/// Blocks between an HTLC's creation and its refund height.
+const HTLC_REFUND_DELAY: u64 = 10;
+
+const SIGNER_LABEL: &[u8] = b"rubin-devnet-signer";
+const HTLC_PREIMAGE_LABEL: &[u8] = b"rubin-devnet-htlc-preimage";
+const STEALTH_CIPHERTEXT_LABEL: &[u8] = b"rubin-devnet-stealth-ciphertext";
+const ANCHOR_LABEL: &[u8] = b"rubin-devnet-anchor";
+
+/// Covenant types created by harness spends, in the order they are visited.
+const COVENANT_TOUR: [u16; 6] = [
+    COV_TYPE_P2PK,
+    COV_TYPE_MULTISIG,
+    COV_TYPE_VAULT,
+    COV_TYPE_HTLC,
+    COV_TYPE_CORE_STEALTH,
+    COV_TYPE_ANCHOR,
+];
+
+fn sha3_256(parts: &[&[u8]]) -> [u8; 32] {
+    let mut hasher = Sha3_256::new();
+    for part in parts {
+        hasher.update(part);
+    }
+    hasher.finalize().into()
+}
+
+/// ML-DSA-87 key derived from the harness seed. Signs deterministically so a
+/// seeded run reproduces byte-identical transactions.
+pub struct DevSigner {
+    keypair: Mldsa87Keypair,
+}
+
+impl DevSigner {
+    /// Key number `index` of `seed`: its keygen seed is
+    /// `sha3_256("rubin-devnet-signer" || seed:u64le || index:u32le)`.
+    pub fn from_seed(seed: u64, index: u32) -> Result<Self, TxError> {
+        let keygen_seed = sha3_256(&[SIGNER_LABEL, &seed.to_le_bytes(), &index.to_le_bytes()]);
+        let keypair = Mldsa87Keypair::from_seed(&keygen_seed)?;
+        Ok(Self { keypair })
+    }
+
+    pub fn key_id(&self) -> [u8; 32] {
+        sha3_256(&[&self.keypair.pubkey_bytes()])
+    }
+
+    pub fn p2pk_covenant_data(&self) -> Vec<u8> {
+        p2pk_covenant_data_for_pubkey(&self.keypair.pubkey_bytes())
+    }
+}
+
+impl DigestSigner for DevSigner {
+    fn pubkey_bytes(&self) -> Vec<u8> {
+        self.keypair.pubkey_bytes()
+    }
+
+    fn sign_digest32(&self, digest32: [u8; 32]) -> Result<Vec<u8>, TxError> {
+        self.keypair.sign_digest32_deterministic(digest32)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DevnetHarnessConfig {
+    pub blocks: u64,
+    /// Must not hold a chainstate yet; the harness only builds fresh chains.
+    pub data_dir: PathBuf,
+    pub seed: u64,
+}
+
+/// Outcome of a harness run, as printed by `rubin-node devnet`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct DevnetHarnessReport {
+    pub seed: u64,
+    pub height: u64,
+    pub tip_hash: String,
+    pub utxo_set_hash: String,
+    /// Non-coinbase transactions mined.
+    pub spends: u64,
+}
+
+/// The keys a run controls: `owner` mines and funds every spend, the
+/// others only appear as covenant counterparties.
+struct HarnessKeys {
+    owner: DevSigner,
+    claim: DevSigner,
+    stealth: DevSigner,
+}
+
+impl HarnessKeys {
+    fn new(seed: u64) -> Result<Self, TxError> {
+        Ok(Self {
+            owner: DevSigner::from_seed(seed, 0)?,
+            claim: DevSigner::from_seed(seed, 1)?,
+            stealth: DevSigner::from_seed(seed, 2)?,
+        })
+    }
+}
+
+/// Fails with `OpErrorKind::CryptoProvider` when the signer keys cannot be
+/// derived; every other failure is `OpErrorKind::Other`.
+pub fn run_devnet_harness(cfg: &DevnetHarnessConfig) -> Result<DevnetHarnessReport, OpError> {
+    let genesis = load_genesis_config(None, "devnet")?;
+    let chain_id = genesis.chain_id;
+    let datadir = Datadir::open(&cfg.data_dir, chain_id)?;
+    let chain_state_file = datadir.chain_state_path();
+    if chain_state_file.exists() {
+        return Err(format!(
+            "devnet harness needs a fresh --datadir, found chainstate at {}",
+            chain_state_file.display()
+        )
+        .into());
+    }
+    let chain_state = load_chain_state(&chain_state_file)?;
+    let block_store = BlockStore::open(datadir.block_store_path())?;
+    let mut sync_cfg = default_sync_config(None, chain_id, Some(chain_state_file.clone()));
+    sync_cfg.suite_context = genesis.suite_context.clone();
+    let mut sync_engine = SyncEngine::new(chain_state, Some(block_store), sync_cfg)?;
+
+    let keys =
+        HarnessKeys::new(cfg.seed).map_err(|err| OpError::from_tx_error("devnet keys", &err))?;
+    let miner_cfg = MinerConfig {
+        // A zero clock makes the miner stamp every block with MTP + 1.
+        timestamp_source: || 0,
+        target: POW_LIMIT,
+        mine_address: keys.owner.p2pk_covenant_data(),
+        // The tour creates a non-coinbase ANCHOR output.
+        policy_da_anchor_anti_abuse: false,
+        ..MinerConfig::default()
+    };
+
+    let mut spends = 0u64;
+    for _ in 0..cfg.blocks {
+        let txs = build_block_spends(&sync_engine.chain_state, &keys, chain_id, spends)?;
+        let mut miner = Miner::new(&mut sync_engine, None, miner_cfg.clone())?;
+        let mined = miner.mine_one(&txs)?;
+        if mined.tx_count != txs.len() + 1 {
+            return Err(format!(
+                "block {} dropped harness spends: mined {} of {} txs",
+                mined.height,
+                mined.tx_count.saturating_sub(1),
+                txs.len()
+            )
+            .into());
+        }
+        spends += txs.len() as u64;
+    }
+
+    let state = &sync_engine.chain_state;
+    state.save(&chain_state_file)?;
+    Ok(DevnetHarnessReport {
+        seed: cfg.seed,
+        height: state.height,
+        tip_hash: hex::encode(state.tip_hash),
+        utxo_set_hash: hex::encode(state.utxo_set_hash()),
+        spends,
+    })
+}
+
+/// Signed spends for the block after `state`'s tip: the oldest mature
+/// coinbase and the newest change output, each creating the next covenant of
+/// the tour. `tour_pos` counts the spends already mined.
+fn build_block_spends(
+    state: &ChainState,
+    keys: &HarnessKeys,
+    chain_id: [u8; 32],
+    tour_pos: u64,
+) -> Result<Vec<Vec<u8>>, String> {
+    if !state.has_tip {
+        return Ok(Vec::new());
+    }
+    let next_height = state
+        .height
+        .checked_add(1)
+        .ok_or_else(|| "chain height overflow".to_string())?;
+    let owner_data = keys.owner.p2pk_covenant_data();
+    let owned: Vec<(&Outpoint, &UtxoEntry)> = state
+        .utxos
+        .iter()
+        .filter(|(_, entry)| {
+            entry.covenant_type == COV_TYPE_P2PK && entry.covenant_data == owner_data
+        })
+        .collect();
+    let order = |a: &(&Outpoint, &UtxoEntry), b: &(&Outpoint, &UtxoEntry)| {
+        a.1.creation_height
+            .cmp(&b.1.creation_height)
+            .then(a.0.txid.cmp(&b.0.txid))
+            .then(a.0.vout.cmp(&b.0.vout))
+    };
+    let coinbase = owned
+        .iter()
+        .filter(|(_, entry)| {
+            entry.created_by_coinbase
+                && entry
+                    .creation_height
+                    .checked_add(COINBASE_MATURITY)
+                    .is_some_and(|mature_at| next_height >= mature_at)
+        })
+        .min_by(|a, b| order(a, b));
+    let change = owned
+        .iter()
+        .filter(|(_, entry)| !entry.created_by_coinbase)
+        .max_by(|a, b| order(a, b));
+
+    let mut txs = Vec::new();
+    for (slot, (outpoint, entry)) in coinbase.into_iter().chain(change).enumerate() {
+        let pos = tour_pos + slot as u64;
+        let covenant_type = COVENANT_TOUR[(pos % COVENANT_TOUR.len() as u64) as usize];
+        let tour_output = tour_output(covenant_type, keys, next_height)?;
+        let change_value = entry
+            .value
+            .checked_sub(tour_output.value)
+            .and_then(|value| value.checked_sub(SPEND_FEE))
+            .filter(|value| *value > 0)
+            .ok_or_else(|| format!("harness coin worth {} cannot fund a spend", entry.value))?;
+        let mut tx = Tx {
+            version: TX_WIRE_VERSION,
+            tx_kind: 0x00,
+            tx_nonce: next_height
+                .checked_mul(COVENANT_TOUR.len() as u64)
+                .and_then(|nonce| nonce.checked_add(slot as u64 + 1))
+                .ok_or_else(|| "tx nonce overflow".to_string())?,
+            inputs: vec![TxInput {
+                prev_txid: outpoint.txid,
+                prev_vout: outpoint.vout,
+                script_sig: Vec::new(),
+                sequence: 0,
+            }],
+            outputs: vec![
+                tour_output,
+                TxOutput {
+                    value: change_value,
+                    covenant_type: COV_TYPE_P2PK,
+                    covenant_data: owner_data.clone(),
+                },
+            ],
+            locktime: 0,
+            da_commit_core: None,
+            da_chunk_core: None,
+            witness: Vec::new(),
+            da_payload: Vec::new(),
+        };
+        sign_transaction(&mut tx, &state.utxos, chain_id, &keys.owner)
+            .map_err(|err| err.to_string())?;
+        txs.push(marshal_tx(&tx).map_err(|err| err.to_string())?);
+    }
+    Ok(txs)
+}
+
+/// The tour output of `covenant_type`, created at `height`. Every covenant
+/// the owner key can sign for is bound to it; the counterparties are the
+/// other harness keys.
+fn tour_output(covenant_type: u16, keys: &HarnessKeys, height: u64) -> Result<TxOutput, String> {
+    let owner_key_id = keys.owner.key_id();
+    let owner_lock_id = output_descriptor_hash(COV_TYPE_P2PK, &keys.owner.p2pk_covenant_data());
+    let height_bytes = height.to_le_bytes();
+    let (value, covenant_data) = match covenant_type {
+        COV_TYPE_P2PK => (TOUR_OUTPUT_VALUE, keys.claim.p2pk_covenant_data()),
+        COV_TYPE_MULTISIG => {
+            let mut data = vec![1, 1];
+            data.extend_from_slice(&owner_key_id);
+            (TOUR_OUTPUT_VALUE, data)
+        }
+        COV_TYPE_VAULT => {
+            // Owned by the owner's P2PK lock and sweepable only to the claim
+            // key's: consensus forbids whitelisting the owner lock itself.
+            let claim_lock_id =
+                output_descriptor_hash(COV_TYPE_P2PK, &keys.claim.p2pk_covenant_data());
+            let mut data = owner_lock_id.to_vec();
+            data.extend_from_slice(&[1, 1]);
+            data.extend_from_slice(&owner_key_id);
+            data.extend_from_slice(&1u16.to_le_bytes());
+            data.extend_from_slice(&claim_lock_id);
+            (TOUR_OUTPUT_VALUE, data)
+        }
+        COV_TYPE_HTLC => {
+            let preimage = sha3_256(&[HTLC_PREIMAGE_LABEL, &height_bytes]);
+            let mut data = sha3_256(&[&preimage]).to_vec();
+            data.push(LOCK_MODE_HEIGHT);
+            data.extend_from_slice(&(height + HTLC_REFUND_DELAY).to_le_bytes());
+            data.extend_from_slice(&keys.claim.key_id());
+            data.extend_from_slice(&owner_key_id);
+            (TOUR_OUTPUT_VALUE, data)
+        }
+        COV_TYPE_CORE_STEALTH => {
+            // Placeholder ciphertext: nothing decapsulates it on devnet.
+            let mut data = Vec::with_capacity(ML_KEM_1024_CT_BYTES as usize + 32);
+            let mut block = 0u32;
+            while data.len() < ML_KEM_1024_CT_BYTES as usize {
+                data.extend_from_slice(&sha3_256(&[
+                    STEALTH_CIPHERTEXT_LABEL,
+                    &height_bytes,
+                    &block.to_le_bytes(),
+                ]));
+                block += 1;
+            }
+            data.truncate(ML_KEM_1024_CT_BYTES as usize);
+            data.extend_from_slice(&keys.stealth.key_id());
+            (TOUR_OUTPUT_VALUE, data)
+        }
+        COV_TYPE_ANCHOR => (0, sha3_256(&[ANCHOR_LABEL, &height_bytes]).to_vec()),
+        other => return Err(format!("covenant type {other:#06x} is not in the tour")),
+    };
+    Ok(TxOutput {
+        value,
+        covenant_type,
+        covenant_data,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::genesis::devnet_genesis_chain_id;
+    use crate::op_error::OpErrorKind;
+    use rubin_consensus::parse_vault_covenant_data;
+    use std::fs;
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    static TMP_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+    struct TempDir {
+        path: PathBuf,
+    }
+
+    impl TempDir {
+        fn new() -> Self {
+            let n = TMP_COUNTER.fetch_add(1, Ordering::Relaxed);
+            let path = std::env::temp_dir().join(format!(
+                "rubin-devnet-harness-test-{}-{n}",
+                std::process::id()
+            ));
+            let _ = fs::remove_dir_all(&path);
+            Self { path }
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.path);
+        }
+    }
+
+    /// Enough blocks for the first coinbases to mature and the tour to
+    /// visit every covenant type.
+    const TEST_BLOCKS: u64 = COINBASE_MATURITY + 4;
+
+    fn run(seed: u64) -> DevnetHarnessReport {
+        let dir = TempDir::new();
+        run_devnet_harness(&DevnetHarnessConfig {
+            blocks: TEST_BLOCKS,
+            data_dir: dir.path.clone(),
+            seed,
+        })
+        .expect("harness run")
+    }
+
+    #[test]
+    fn same_seed_reproduces_tip_and_utxo_set() {
+        let first = run(7);
+        let second = run(7);
+        assert_eq!(first, second);
+        assert_eq!(first.height, TEST_BLOCKS - 1);
+        assert!(first.spends >= COVENANT_TOUR.len() as u64);
+
+        let other = run(8);
+        assert_ne!(other.tip_hash, first.tip_hash);
+        assert_ne!(other.utxo_set_hash, first.utxo_set_hash);
+    }
+
+    #[test]
+    fn vault_tour_spend_is_accepted() {
+        // ML-DSA keygen needs an OpenSSL build with the PQ provider.
+        let Ok(keys) = HarnessKeys::new(DEFAULT_DEVNET_HARNESS_SEED) else {
+            return;
+        };
+        let dir = TempDir::new();
+        let report = run_devnet_harness(&DevnetHarnessConfig {
+            blocks: TEST_BLOCKS,
+            data_dir: dir.path.clone(),
+            seed: DEFAULT_DEVNET_HARNESS_SEED,
+        })
+        .expect("harness run");
+        assert!(report.spends >= COVENANT_TOUR.len() as u64);
+
+        let datadir = Datadir::open(&dir.path, devnet_genesis_chain_id()).expect("datadir");
+        let state = load_chain_state(datadir.chain_state_path()).expect("chainstate");
+        let owner_lock_id = output_descriptor_hash(COV_TYPE_P2PK, &keys.owner.p2pk_covenant_data());
+        let vault = state
+            .utxos
+            .values()
+            .find(|entry| entry.covenant_type == COV_TYPE_VAULT)
+            .expect("vault output mined");
+        let parsed = parse_vault_covenant_data(&vault.covenant_data).expect("vault covenant");
+        assert_eq!(parsed.owner_lock_id, owner_lock_id);
+        assert!(!parsed.whitelist.contains(&owner_lock_id));
+    }
+
+    #[test]
+    fn refuses_a_datadir_with_a_chainstate() {
+        let dir = TempDir::new();
+        let cfg = DevnetHarnessConfig {
+            blocks: 1,
+            data_dir: dir.path.clone(),
+            seed: DEFAULT_DEVNET_HARNESS_SEED,
+        };
+        let datadir = Datadir::open(&cfg.data_dir, devnet_genesis_chain_id()).expect("datadir");
+        ChainState::new()
+            .save(datadir.chain_state_path())
+            .expect("save chainstate");
+        drop(datadir);
+        let err = run_devnet_harness(&cfg).unwrap_err();
+        assert_eq!(err.kind, OpErrorKind::Other);
+        assert!(err.message.contains("fresh --datadir"), "{err}");
+    }
+}
@@ -6,12 +6,20 @@ use std::sync::{mpsc, Arc, Mutex};
 use std::thread::{self, JoinHandle};
 use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
+use rubin_consensus::ChainParams;
 use serde::{Deserialize, Serialize};
 
+use crate::block_json::{block_to_json, chain_tips_to_json, parse_block_verbosity};
+use crate::block_submit::{submit_block, SubmittedBlock};
+use crate::broadcast::BroadcastManager;
+use crate::build_info::BuildInfo;
 use crate::da_relay::CompleteDaSetProvider;
+use crate::io_utils::parse_hex32;
 use crate::miner::{Miner, MinerConfig};
 use crate::p2p_runtime::{orphan_pool_metrics_snapshot, PeerManager};
-use crate::txpool::TxSource;
+use crate::relay_telemetry::{
+    peer_quality_score, with_relay_telemetry, RelayTelemetrySnapshot, RELAY_TELEMETRY_FIELDS,
+};
 use crate::{BlockStore, SyncEngine, TxPool, TxPoolAdmitErrorKind, TxPoolConfig};
 
 const MAX_HEADER_BYTES: usize = 64 * 1024;
@@ -62,11 +70,14 @@ pub struct DevnetRPCState {
     /// When set, POST `/mine_next` fail-closed consumes the mined block's
     /// complete DA sets after a successful mine+apply (RUB-435).
     accepted_block_da_consumer: Option<AcceptedBlockDaConsumerFn>,
-    /// Serializes mutating devnet RPC (submit_tx + mine_next).
+    /// Serializes mutating devnet RPC (submit_tx, submit_block, mine_next).
     rpc_op_lock: Arc<Mutex<()>>,
     /// When set, POST `/mine_next` mines one block using this config (devnet + loopback RPC only).
     live_mining_cfg: Option<MinerConfig>,
     live_complete_da_set_provider: Option<Arc<dyn CompleteDaSetProvider + Send + Sync>>,
+    /// When set, `/submit_tx` tracks accepted transactions for rebroadcast
+    /// and `/broadcast_status` reports them.
+    broadcast: Option<Arc<Mutex<BroadcastManager>>>,
     /// RUB-10 / GitHub #1151: readiness gate driving `/ready` semantics.
     /// Mirrors Go's `clients/go/cmd/rubin-node/http_rpc.go::readinessGate`
     /// (type at line 125; methods 143-219). Three-state machine:
@@ -336,20 +347,31 @@ struct GetTipResponse {
 }
 
 #[derive(Serialize)]
-struct GetBlockResponse {
-    hash: String,
-    height: u64,
-    canonical: bool,
-    block_hex: String,
+struct SubmitTxResponse {
+    accepted: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    txid: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
 }
 
+/// Final `/submit_tx` outcome: `SubmitTxResponse` plus what the
+/// acceptance pipeline reports about the admitted or refused tx.
 #[derive(Serialize)]
-struct SubmitTxResponse {
+struct SubmitTxOutcomeResponse {
     accepted: bool,
     #[serde(skip_serializing_if = "Option::is_none")]
     txid: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    fee: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    weight: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     error: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    reject_class: Option<&'static str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error_code: Option<String>,
 }
 
 #[derive(Deserialize)]
@@ -357,6 +379,36 @@ struct SubmitTxRequest {
     tx_hex: String,
 }
 
+#[derive(Deserialize)]
+struct SubmitBlockRequest {
+    block_hex: String,
+}
+
+#[derive(Serialize)]
+struct SubmitBlockResponse {
+    accepted: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    block_hash: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    height: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    canonical: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+impl SubmitBlockResponse {
+    fn rejected(error: String) -> Self {
+        Self {
+            accepted: false,
+            block_hash: None,
+            height: None,
+            canonical: None,
+            error: Some(error),
+        }
+    }
+}
+
 #[derive(Serialize)]
 struct MineNextResponse {
     mined: bool,
@@ -382,6 +434,14 @@ struct GetMempoolResponse {
     error: Option<String>,
 }
 
+#[derive(Serialize)]
+struct GetTelemetryResponse {
+    #[serde(flatten)]
+    telemetry: RelayTelemetrySnapshot,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
 #[derive(Serialize)]
 struct GetTxResponse {
     found: bool,
@@ -542,6 +602,7 @@ pub fn new_devnet_rpc_state_with_tx_pool(
         rpc_op_lock: Arc::new(Mutex::new(())),
         live_mining_cfg,
         live_complete_da_set_provider: None,
+        broadcast: None,
         // RUB-10 / GitHub #1151: gate starts in `NotReady`. The
         // `try_mark_ready_on_startup` transition runs inside
         // `start_devnet_rpc_server` after the listener is bound, so
@@ -566,6 +627,39 @@ impl DevnetRPCState {
     ) {
         self.live_complete_da_set_provider = Some(provider);
     }
+
+    pub fn set_broadcast_manager(&mut self, broadcast: Arc<Mutex<BroadcastManager>>) {
+        self.broadcast = Some(broadcast);
+    }
+
+    /// The `/submit_block` path: imports `block_bytes` through
+    /// `block_submit::submit_block` under `rpc_op_lock`, runs the
+    /// accepted-block hooks for a block that became canonical, then
+    /// announces it. The node's mining loop submits through here too.
+    pub fn submit_block(&self, block_bytes: &[u8]) -> Result<SubmittedBlock, String> {
+        let rpc_op = self
+            .rpc_op_lock
+            .lock()
+            .map_err(|_| "rpc unavailable".to_string())?;
+        let submitted = submit_block(&self.sync_engine, &self.tx_pool, block_bytes)?;
+        if submitted.canonical {
+            if let Some(ref accepted) = self.accepted_block {
+                if let Err(err) = accepted(submitted.hash) {
+                    eprintln!("rpc: accepted-block: {err}");
+                }
+            }
+            if let Some(consumer) = self.accepted_block_da_consumer.as_ref() {
+                consumer(block_bytes).map_err(|err| format!("consume accepted DA sets: {err}"))?;
+            }
+        }
+        drop(rpc_op);
+        if let Some(announce) = self.announce_block.as_ref() {
+            if let Err(err) = announce(block_bytes) {
+                eprintln!("rpc: announce-block: {err}");
+            }
+        }
+        Ok(submitted)
+    }
 }
 
 pub fn attach_shutdown_signal_to_devnet_rpc_state(
@@ -577,12 +671,13 @@ pub fn attach_shutdown_signal_to_devnet_rpc_state(
 }
 
 pub fn new_shared_runtime_tx_pool(sync_engine: &Arc<Mutex<SyncEngine>>) -> Arc<Mutex<TxPool>> {
-    let suite_context = sync_engine
+    let (suite_context, chain_params) = sync_engine
         .lock()
-        .map(|engine| engine.cfg.suite_context.clone())
-        .unwrap_or(None);
+        .map(|engine| (engine.cfg.suite_context.clone(), engine.cfg.chain_params))
+        .unwrap_or((None, ChainParams::V1));
     Arc::new(Mutex::new(TxPool::new_with_config(TxPoolConfig {
         suite_context,
+        chain_params,
         ..TxPoolConfig::default()
     })))
 }
@@ -916,13 +1011,18 @@ fn route_request(state: &DevnetRPCState, req: HttpRequest) -> HttpResponse {
         "/ready" => handle_ready(state, &req.method),
         "/peers" => handle_peers(state, &req.method),
         "/get_tip" => handle_get_tip(state, &req.method),
+        "/get_info" => handle_get_info(state, &req.method),
         "/get_block" => handle_get_block(state, &req.method, &query),
+        "/get_chain_tips" => handle_get_chain_tips(state, &req.method),
         "/submit_tx" => handle_submit_tx(state, &req.method, &req.body),
+        "/submit_block" => handle_submit_block(state, &req.method, &req.body),
         "/mine_next" => handle_mine_next(state, &req.method, &req.body),
         "/get_mempool" => handle_get_mempool(state, &req.method),
         "/get_tx" => handle_get_tx(state, &req.method, &query),
         "/tx_status" => handle_tx_status(state, &req.method, &query),
+        "/broadcast_status" => handle_broadcast_status(state, &req.method, &query),
         "/metrics" => handle_metrics(state, &req.method),
+        "/get_telemetry" => handle_get_telemetry(state, &req.method),
         _ => json_response(
             state,
             "/unknown",
@@ -1062,6 +1162,38 @@ fn handle_peers(state: &DevnetRPCState, method: &str) -> HttpResponse {
     json_response(state, ROUTE, 200, &PeersResponse { count, peers })
 }
 
+fn handle_get_info(state: &DevnetRPCState, method: &str) -> HttpResponse {
+    const ROUTE: &str = "/get_info";
+    if method != "GET" {
+        return json_response(
+            state,
+            ROUTE,
+            400,
+            &SubmitTxResponse {
+                accepted: false,
+                txid: None,
+                error: Some("GET required".to_string()),
+            },
+        );
+    }
+    let info = match state.sync_engine.lock() {
+        Ok(engine) => BuildInfo::new(engine.network(), engine.chain_id()),
+        Err(_) => {
+            return json_response(
+                state,
+                ROUTE,
+                503,
+                &SubmitTxResponse {
+                    accepted: false,
+                    txid: None,
+                    error: Some("sync engine unavailable".to_string()),
+                },
+            )
+        }
+    };
+    json_response(state, ROUTE, 200, &info)
+}
+
 fn handle_get_tip(state: &DevnetRPCState, method: &str) -> HttpResponse {
     const ROUTE: &str = "/get_tip";
     if method != "GET" {
@@ -1194,8 +1326,24 @@ fn handle_get_block(state: &DevnetRPCState, method: &str, query: &str) -> HttpRe
             },
         );
     }
+    let verbosity = match params.get("verbosity").map(|v| parse_block_verbosity(v)) {
+        None => 0,
+        Some(Ok(verbosity)) => verbosity,
+        Some(Err(err)) => {
+            return json_response(
+                state,
+                ROUTE,
+                400,
+                &SubmitTxResponse {
+                    accepted: false,
+                    txid: None,
+                    error: Some(err),
+                },
+            )
+        }
+    };
 
-    let (height, block_hash) = if !height_raw.is_empty() {
+    let block_hash = if !height_raw.is_empty() {
         let height = match height_raw.parse::<u64>() {
             Ok(height) => height,
             Err(_) => {
@@ -1211,7 +1359,7 @@ fn handle_get_block(state: &DevnetRPCState, method: &str, query: &str) -> HttpRe
                 )
             }
         };
-        let hash = match block_store.canonical_hash(height) {
+        match block_store.canonical_hash(height) {
             Ok(Some(hash)) => hash,
             Ok(None) => {
                 return json_response(
@@ -1237,10 +1385,9 @@ fn handle_get_block(state: &DevnetRPCState, method: &str, query: &str) -> HttpRe
                     },
                 )
             }
-        };
-        (height, hash)
+        }
     } else {
-        let hash = match parse_hex32(hash_raw) {
+        let hash = match parse_hex32("hash", hash_raw) {
             Ok(hash) => hash,
             Err(_) => {
                 return json_response(
@@ -1255,8 +1402,8 @@ fn handle_get_block(state: &DevnetRPCState, method: &str, query: &str) -> HttpRe
                 )
             }
         };
-        let height = match block_store.find_canonical_height(hash) {
-            Ok(Some(height)) => height,
+        match block_store.find_canonical_height(hash) {
+            Ok(Some(_)) => hash,
             Ok(None) => {
                 return json_response(
                     state,
@@ -1281,21 +1428,44 @@ fn handle_get_block(state: &DevnetRPCState, method: &str, query: &str) -> HttpRe
                     },
                 )
             }
-        };
-        (height, hash)
+        }
     };
-    match block_store.get_block_by_hash(block_hash) {
-        Ok(block_bytes) => json_response(
+    match block_to_json(&block_store, block_hash, verbosity) {
+        Ok(block) => json_response(state, ROUTE, 200, &block),
+        Err(err) => json_response(
             state,
             ROUTE,
-            200,
-            &GetBlockResponse {
-                hash: hex::encode(block_hash),
-                height,
-                canonical: true,
-                block_hex: hex::encode(block_bytes),
+            503,
+            &SubmitTxResponse {
+                accepted: false,
+                txid: None,
+                error: Some(err),
             },
         ),
+    }
+}
+
+fn handle_get_chain_tips(state: &DevnetRPCState, method: &str) -> HttpResponse {
+    const ROUTE: &str = "/get_chain_tips";
+    if method != "GET" {
+        return json_response(
+            state,
+            ROUTE,
+            400,
+            &SubmitTxResponse {
+                accepted: false,
+                txid: None,
+                error: Some("GET required".to_string()),
+            },
+        );
+    }
+    let tips = match fresh_block_store(state) {
+        Ok(Some(block_store)) => chain_tips_to_json(&block_store),
+        Ok(None) => Err("blockstore unavailable".to_string()),
+        Err(err) => Err(err),
+    };
+    match tips {
+        Ok(tips) => json_response(state, ROUTE, 200, &tips),
         Err(err) => json_response(
             state,
             ROUTE,
@@ -1408,20 +1578,19 @@ fn handle_submit_tx(state: &DevnetRPCState, method: &str, body: &[u8]) -> HttpRe
     // user-submitted transaction on the local node, mirroring Go
     // `handleSubmitTx` (`clients/go/cmd/rubin-node/http_rpc.go:924`) which
     // calls `mempool.AddTx` -> `addTxWithSource(_, mempoolTxSourceLocal)`
-    // (clients/go/node/mempool.go:411). Tagging the admission as
-    // `TxSource::Local` is observability metadata only — admission ordering,
+    // (clients/go/node/mempool.go:411). `TxPool::accept_tx` tags the
+    // admission as `TxSource::Local`, which is observability metadata only — admission ordering,
     // eviction priority and consensus semantics remain source-blind (see
     // `txpool.rs::compare_entries_for_mining` and the
     // `source_does_not_affect_admission_ordering` test from RUB-174). Source
     // is recorded on the resulting `TxPoolEntry` and surfaced via
     // `TxPool::entry_source` for downstream parity tests.
     let admit_result = match state.tx_pool.lock() {
-        Ok(mut pool) => pool.add_tx_with_source(
+        Ok(mut pool) => pool.accept_tx(
             &tx_bytes,
             &chain_state,
             fresh_block_store.as_ref(),
             chain_id,
-            TxSource::Local,
         ),
         Err(_) => Err(crate::TxPoolAdmitError {
             kind: TxPoolAdmitErrorKind::Unavailable,
@@ -1435,22 +1604,31 @@ fn handle_submit_tx(state: &DevnetRPCState, method: &str, body: &[u8]) -> HttpRe
     // `clients/go/cmd/rubin-node/http_rpc.go::handleSubmitTx`.
     drop(_rpc_op);
     match admit_result {
-        Ok((txid, relay_meta)) => {
+        Ok(accepted) => {
             // Relay tx to peers (fire-and-forget, matches Go behavior).
             if let Some(ref announce) = state.announce_tx {
-                if let Err(err) = announce(&tx_bytes, relay_meta) {
+                if let Err(err) = announce(&tx_bytes, accepted.relay_metadata()) {
                     eprintln!("rpc: announce-tx: {err}");
                 }
             }
+            if let Some(ref broadcast) = state.broadcast {
+                if let Ok(mut mgr) = broadcast.lock() {
+                    mgr.submit(accepted.txid, &tx_bytes, accepted.fee, (state.now_unix)());
+                }
+            }
             state.metrics.note_submit("accepted");
             json_response(
                 state,
                 ROUTE,
                 200,
-                &SubmitTxResponse {
+                &SubmitTxOutcomeResponse {
                     accepted: true,
-                    txid: Some(hex::encode(txid)),
+                    txid: Some(hex::encode(accepted.txid)),
+                    fee: Some(accepted.fee),
+                    weight: Some(accepted.weight),
                     error: None,
+                    reject_class: None,
+                    error_code: None,
                 },
             )
         }
@@ -1465,9 +1643,13 @@ fn handle_submit_tx(state: &DevnetRPCState, method: &str, body: &[u8]) -> HttpRe
                 state,
                 ROUTE,
                 status,
-                &SubmitTxResponse {
+                &SubmitTxOutcomeResponse {
                     accepted: false,
                     txid: None,
+                    fee: None,
+                    weight: None,
+                    reject_class: Some(err.reject_class().as_str()),
+                    error_code: err.consensus_code().map(str::to_string),
                     error: Some(err.message),
                 },
             )
@@ -1475,6 +1657,50 @@ fn handle_submit_tx(state: &DevnetRPCState, method: &str, body: &[u8]) -> HttpRe
     }
 }
 
+fn handle_submit_block(state: &DevnetRPCState, method: &str, body: &[u8]) -> HttpResponse {
+    const ROUTE: &str = "/submit_block";
+    if method != "POST" {
+        return json_response(
+            state,
+            ROUTE,
+            400,
+            &SubmitBlockResponse::rejected("POST required".to_string()),
+        );
+    }
+    let req: SubmitBlockRequest = match serde_json::from_slice(body) {
+        Ok(req) => req,
+        Err(_) => {
+            return json_response(
+                state,
+                ROUTE,
+                400,
+                &SubmitBlockResponse::rejected("invalid JSON body".to_string()),
+            );
+        }
+    };
+    let block_bytes = match decode_hex_field("block_hex", &req.block_hex) {
+        Ok(bytes) => bytes,
+        Err(err) => {
+            return json_response(state, ROUTE, 400, &SubmitBlockResponse::rejected(err));
+        }
+    };
+    match state.submit_block(&block_bytes) {
+        Ok(submitted) => json_response(
+            state,
+            ROUTE,
+            200,
+            &SubmitBlockResponse {
+                accepted: true,
+                block_hash: Some(hex::encode(submitted.hash)),
+                height: Some(submitted.height),
+                canonical: Some(submitted.canonical),
+                error: None,
+            },
+        ),
+        Err(err) => json_response(state, ROUTE, 422, &SubmitBlockResponse::rejected(err)),
+    }
+}
+
 fn handle_mine_next(state: &DevnetRPCState, method: &str, _body: &[u8]) -> HttpResponse {
     const ROUTE: &str = "/mine_next";
     if method != "POST" {
@@ -1816,11 +2042,8 @@ fn handle_get_mempool(state: &DevnetRPCState, method: &str) -> HttpResponse {
             );
         }
     };
-    let mut ids = pool.all_txids();
+    let ids = pool.all_txids();
     drop(pool);
-    // Sort for deterministic response ordering; HashMap iteration is not
-    // stable across calls.
-    ids.sort();
     let txids: Vec<String> = ids.iter().map(hex::encode).collect();
     json_response(
         state,
@@ -2005,6 +2228,76 @@ fn handle_tx_status(state: &DevnetRPCState, method: &str, query: &str) -> HttpRe
     )
 }
 
+/// GET `/broadcast_status?txid=<hex>`: the broadcast manager's view of a
+/// locally submitted transaction (pending, confirmed, evicted or expired).
+fn handle_broadcast_status(state: &DevnetRPCState, method: &str, query: &str) -> HttpResponse {
+    const ROUTE: &str = "/broadcast_status";
+    let error = |status: u16, txid: Option<[u8; 32]>, message: &str| {
+        json_response(
+            state,
+            ROUTE,
+            status,
+            &TxStatusResponse {
+                status: "unknown".to_string(),
+                txid: txid.map(hex::encode),
+                error: Some(message.to_string()),
+            },
+        )
+    };
+    if method != "GET" {
+        return error(400, None, "GET required");
+    }
+    let txid = match parse_txid_query(query) {
+        Ok(txid) => txid,
+        Err(err) => return error(400, None, &err),
+    };
+    let Some(broadcast) = state.broadcast.as_ref() else {
+        return error(503, Some(txid), "broadcast tracking unavailable");
+    };
+    let status = match broadcast.lock() {
+        Ok(mgr) => mgr.status(&txid),
+        Err(_) => return error(503, Some(txid), "broadcast tracking unavailable"),
+    };
+    match status {
+        Some(status) => json_response(state, ROUTE, 200, &status),
+        None => error(404, Some(txid), "transaction not tracked"),
+    }
+}
+
+fn handle_get_telemetry(state: &DevnetRPCState, method: &str) -> HttpResponse {
+    const ROUTE: &str = "/get_telemetry";
+    if method != "GET" {
+        return json_response(
+            state,
+            ROUTE,
+            400,
+            &GetTelemetryResponse {
+                telemetry: RelayTelemetrySnapshot::default(),
+                error: Some("GET required".to_string()),
+            },
+        );
+    }
+    json_response(
+        state,
+        ROUTE,
+        200,
+        &GetTelemetryResponse {
+            telemetry: relay_telemetry_snapshot(state),
+            error: None,
+        },
+    )
+}
+
+fn relay_telemetry_snapshot(state: &DevnetRPCState) -> RelayTelemetrySnapshot {
+    let da_mempool_fill_pct = match state.tx_pool.lock() {
+        Ok(pool) => pool.da_fill_pct(),
+        Err(_) => 0,
+    };
+    let peers = state.peer_manager.snapshot();
+    let peer_quality = peer_quality_score(peers.iter().map(|peer| peer.ban_score));
+    with_relay_telemetry(|telemetry| telemetry.snapshot(da_mempool_fill_pct, peer_quality))
+}
+
 fn handle_metrics(state: &DevnetRPCState, method: &str) -> HttpResponse {
     const ROUTE: &str = "/metrics";
     if method != "GET" {
@@ -2025,33 +2318,42 @@ fn handle_metrics(state: &DevnetRPCState, method: &str) -> HttpResponse {
 }
 
 fn render_prometheus_metrics(state: &DevnetRPCState) -> String {
-    let (tip_height, best_known_height, in_ibd, reorg_count, last_reorg_depth, pv_lines) =
-        match state.sync_engine.lock() {
-            Ok(engine) => {
-                let tip_height = match engine.tip() {
-                    Ok(Some((height, _))) => height,
-                    _ => 0,
-                };
-                let best_known_height = engine.best_known_height();
-                let in_ibd = if engine.is_in_ibd((state.now_unix)()) {
-                    1
-                } else {
-                    0
-                };
-                let reorg_count = engine.reorg_count();
-                let last_reorg_depth = engine.last_reorg_depth();
-                let pv_lines = engine.pv_telemetry_snapshot().prometheus_lines();
-                (
-                    tip_height,
-                    best_known_height,
-                    in_ibd,
-                    reorg_count,
-                    last_reorg_depth,
-                    pv_lines,
-                )
-            }
-            Err(_) => (0, 0, 1, 0, 0, Vec::new()),
-        };
+    let (
+        tip_height,
+        best_known_height,
+        in_ibd,
+        reorg_count,
+        last_reorg_depth,
+        refused_reorg_count,
+        pv_lines,
+    ) = match state.sync_engine.lock() {
+        Ok(engine) => {
+            let tip_height = match engine.tip() {
+                Ok(Some((height, _))) => height,
+                _ => 0,
+            };
+            let best_known_height = engine.best_known_height();
+            let in_ibd = if engine.is_in_ibd((state.now_unix)()) {
+                1
+            } else {
+                0
+            };
+            let reorg_count = engine.reorg_count();
+            let last_reorg_depth = engine.last_reorg_depth();
+            let refused_reorg_count = engine.refused_reorg_count();
+            let pv_lines = engine.pv_telemetry_snapshot().prometheus_lines();
+            (
+                tip_height,
+                best_known_height,
+                in_ibd,
+                reorg_count,
+                last_reorg_depth,
+                refused_reorg_count,
+                pv_lines,
+            )
+        }
+        Err(_) => (0, 0, 1, 0, 0, 0, Vec::new()),
+    };
     let mempool_txs = match state.tx_pool.lock() {
         Ok(pool) => pool.len() as u64,
         Err(_) => 0,
@@ -2080,6 +2382,10 @@ fn render_prometheus_metrics(state: &DevnetRPCState) -> String {
             .to_string(),
         "# TYPE rubin_node_last_reorg_depth gauge".to_string(),
         format!("rubin_node_last_reorg_depth {last_reorg_depth}"),
+        "# HELP rubin_node_reorg_refused_total Preferred branches held back for exceeding max_auto_reorg_depth."
+            .to_string(),
+        "# TYPE rubin_node_reorg_refused_total counter".to_string(),
+        format!("rubin_node_reorg_refused_total {refused_reorg_count}"),
         "# HELP rubin_node_peer_count Currently tracked peers.".to_string(),
         "# TYPE rubin_node_peer_count gauge".to_string(),
         format!("rubin_node_peer_count {peer_count}"),
@@ -2150,6 +2456,50 @@ fn render_prometheus_metrics(state: &DevnetRPCState) -> String {
         ));
     }
     lines.extend(pv_lines);
+    lines.push(
+        "# HELP rubin_node_relay_telemetry Compact/DA relay telemetry field set; list fields report their length."
+            .to_string(),
+    );
+    lines.push("# TYPE rubin_node_relay_telemetry gauge".to_string());
+    let telemetry = serde_json::to_value(relay_telemetry_snapshot(state)).unwrap_or_default();
+    for field in RELAY_TELEMETRY_FIELDS {
+        let value = match &telemetry[field] {
+            serde_json::Value::Array(items) => items.len().to_string(),
+            value => value.to_string(),
+        };
+        lines.push(format!(
+            "rubin_node_relay_telemetry{{field=\"{field}\"}} {value}"
+        ));
+    }
+    lines.push(
+        "# HELP rubin_node_sendcmpct_mode_changes_total Local sendcmpct mode changes announced to peers."
+            .to_string(),
+    );
+    lines.push("# TYPE rubin_node_sendcmpct_mode_changes_total counter".to_string());
+    lines.push(format!(
+        "rubin_node_sendcmpct_mode_changes_total {}",
+        with_relay_telemetry(|telemetry| telemetry.sendcmpct_mode_changes())
+    ));
+    let (storm_mode, set_recovery_rate) = with_relay_telemetry(|telemetry| {
+        (
+            telemetry.da_storm_mode(),
+            telemetry.da_set_recovery_success_rate(),
+        )
+    });
+    lines.push(
+        "# HELP rubin_node_da_storm_mode DA orphan storm mode (1 while only commit-bearing data is admitted)."
+            .to_string(),
+    );
+    lines.push("# TYPE rubin_node_da_storm_mode gauge".to_string());
+    lines.push(format!("rubin_node_da_storm_mode {}", u8::from(storm_mode)));
+    lines.push(
+        "# HELP rubin_node_da_set_recovery_success_rate Percent of windowed DA set recoveries that completed."
+            .to_string(),
+    );
+    lines.push("# TYPE rubin_node_da_set_recovery_success_rate gauge".to_string());
+    lines.push(format!(
+        "rubin_node_da_set_recovery_success_rate {set_recovery_rate}"
+    ));
     lines.join("\n") + "\n"
 }
 
@@ -2728,27 +3078,21 @@ fn parse_query_map(query: &str) -> HashMap<String, String> {
 }
 
 fn decode_hex_payload(value: &str) -> Result<Vec<u8>, String> {
+    decode_hex_field("tx_hex", value)
+}
+
+fn decode_hex_field(field: &str, value: &str) -> Result<Vec<u8>, String> {
     let trimmed = value
         .trim()
         .trim_start_matches("0x")
         .trim_start_matches("0X");
     if trimmed.is_empty() {
-        return Err("tx_hex is required".to_string());
+        return Err(format!("{field} is required"));
     }
     if !trimmed.len().is_multiple_of(2) {
-        return Err("tx_hex must be even-length hex".to_string());
+        return Err(format!("{field} must be even-length hex"));
     }
-    hex::decode(trimmed).map_err(|_| "tx_hex must be valid hex".to_string())
-}
-
-fn parse_hex32(value: &str) -> Result<[u8; 32], String> {
-    let raw = decode_hex_payload(value)?;
-    if raw.len() != 32 {
-        return Err(format!("expected 32-byte hex, got {} bytes", raw.len()));
-    }
-    let mut out = [0u8; 32];
-    out.copy_from_slice(&raw);
-    Ok(out)
+    hex::decode(trimmed).map_err(|_| format!("{field} must be valid hex"))
 }
 
 fn current_unix() -> u64 {
@@ -2839,7 +3183,6 @@ impl HttpResponse {
 
 #[cfg(test)]
 mod tests {
-    use std::collections::HashMap;
     use std::fs;
     use std::io::{Read, Write};
     use std::net::{TcpListener, TcpStream};
@@ -2848,7 +3191,9 @@ mod tests {
     use std::sync::{Arc, Mutex};
     use std::time::{Duration, Instant};
 
-    use rubin_consensus::{block_hash, parse_block_bytes, parse_tx, Outpoint, UtxoEntry};
+    use rubin_consensus::{
+        block_hash, parse_block_bytes, parse_tx, Outpoint, OutpointMap, UtxoEntry,
+    };
     use serde_json::Value;
 
     use crate::io_utils::unique_temp_path;
@@ -2879,6 +3224,10 @@ mod tests {
             self.fetch_add(1, Ordering::SeqCst);
             Vec::new()
         }
+
+        fn is_complete(&self, _da_id: [u8; 32]) -> bool {
+            false
+        }
     }
 
     fn build_state(with_genesis: bool) -> (super::DevnetRPCState, PathBuf) {
@@ -3097,8 +3446,8 @@ mod tests {
         out
     }
 
-    fn fixture_utxos_to_map(items: &[FixtureUtxo]) -> HashMap<Outpoint, UtxoEntry> {
-        let mut out = HashMap::with_capacity(items.len());
+    fn fixture_utxos_to_map(items: &[FixtureUtxo]) -> OutpointMap<UtxoEntry> {
+        let mut out = OutpointMap::with_capacity_and_hasher(items.len(), Default::default());
         for item in items {
             out.insert(
                 Outpoint {
@@ -3185,6 +3534,7 @@ mod tests {
             rpc_op_lock: Arc::new(Mutex::new(())),
             live_mining_cfg: None,
             live_complete_da_set_provider: None,
+            broadcast: None,
             // RUB-10 / GitHub #1151: this helper bypasses the public
             // constructor (`new_devnet_rpc_state*`) so it does not
             // benefit from the default-NotReady wiring there. Keep
@@ -3230,6 +3580,40 @@ mod tests {
         fs::remove_dir_all(dir).expect("cleanup");
     }
 
+    #[test]
+    fn get_info_reports_build_and_chain() {
+        let (state, dir) = build_state(true);
+        let chain_id = state.sync_engine.lock().expect("engine").chain_id();
+        let response = route_request(
+            &state,
+            HttpRequest {
+                method: "GET".to_string(),
+                target: "/get_info".to_string(),
+                body: Vec::new(),
+            },
+        );
+        assert_eq!(response.status, 200);
+        let json = response_json(&response);
+        assert_eq!(
+            json["build_info_version"].as_u64(),
+            Some(crate::build_info::BUILD_INFO_VERSION)
+        );
+        assert_eq!(json["chain_id_hex"], hex::encode(chain_id));
+        assert!(json["network"].is_string());
+        assert_eq!(json["crypto_provider"]["name"], "openssl");
+
+        let response = route_request(
+            &state,
+            HttpRequest {
+                method: "POST".to_string(),
+                target: "/get_info".to_string(),
+                body: Vec::new(),
+            },
+        );
+        assert_eq!(response.status, 400);
+        fs::remove_dir_all(dir).expect("cleanup");
+    }
+
     #[test]
     fn rpc_bind_host_is_loopback_accepts_loopback_hosts_only() {
         assert!(super::rpc_bind_host_is_loopback("127.0.0.1:19112"));
@@ -3719,6 +4103,138 @@ mod tests {
         fs::remove_dir_all(dir).expect("cleanup");
     }
 
+    #[test]
+    fn get_block_honours_verbosity() {
+        let (state, dir) = build_state(true);
+        let get = |target: &str| {
+            route_request(
+                &state,
+                HttpRequest {
+                    method: "GET".to_string(),
+                    target: target.to_string(),
+                    body: Vec::new(),
+                },
+            )
+        };
+        let level0 = response_json(&get("/get_block?height=0&verbosity=0"));
+        assert!(level0["block_hex"].as_str().is_some());
+        let level1 = get("/get_block?height=0&verbosity=1");
+        assert_eq!(level1.status, 200);
+        let level1 = response_json(&level1);
+        assert!(level1.get("block_hex").is_none());
+        assert_eq!(level1["tx_count"].as_u64(), Some(1));
+        assert_eq!(level1["txids"].as_array().map(Vec::len), Some(1));
+        let level2 = response_json(&get("/get_block?height=0&verbosity=2"));
+        assert_eq!(level2["tx"][0]["txid"], level1["txids"][0]);
+
+        let bad = get("/get_block?height=0&verbosity=3");
+        assert_eq!(bad.status, 400);
+        assert_eq!(
+            response_json(&bad)["error"].as_str(),
+            Some("invalid verbosity")
+        );
+        fs::remove_dir_all(dir).expect("cleanup");
+    }
+
+    #[test]
+    fn submit_block_imports_and_announces_an_externally_mined_block() {
+        let (mut state, dir) = build_state(true);
+        let announced = Arc::new(Mutex::new(Vec::new()));
+        let announced_sink = Arc::clone(&announced);
+        state.announce_block = Some(Arc::new(move |block_bytes: &[u8]| {
+            announced_sink
+                .lock()
+                .expect("lock")
+                .push(block_bytes.to_vec());
+            Ok(())
+        }));
+        let template = {
+            let mut engine = state.sync_engine.lock().expect("engine");
+            super::Miner::new(&mut engine, None, MinerConfig::default())
+                .and_then(|miner| miner.block_template(&[]))
+                .expect("template")
+        };
+        let nonce = template.search_nonces(0, u64::MAX).expect("nonce");
+        let block_bytes = template.block_bytes(nonce);
+
+        let response = route_request(
+            &state,
+            HttpRequest {
+                method: "POST".to_string(),
+                target: "/submit_block".to_string(),
+                body: serde_json::to_vec(&serde_json::json!({
+                    "block_hex": hex::encode(&block_bytes),
+                }))
+                .expect("body"),
+            },
+        );
+        assert_eq!(response.status, 200);
+        let body = response_json(&response);
+        assert_eq!(body["accepted"], true);
+        assert_eq!(body["height"].as_u64(), Some(1));
+        assert_eq!(body["canonical"], true);
+        let engine = state.sync_engine.lock().expect("engine");
+        assert_eq!(engine.chain_state.height, 1);
+        assert_eq!(
+            body["block_hash"].as_str(),
+            Some(hex::encode(engine.chain_state.tip_hash).as_str())
+        );
+        drop(engine);
+        assert_eq!(*announced.lock().expect("lock"), vec![block_bytes]);
+        fs::remove_dir_all(dir).expect("cleanup");
+    }
+
+    #[test]
+    fn submit_block_rejects_bad_hex_and_invalid_blocks() {
+        let (state, dir) = build_state(true);
+        let submit = |body: &[u8]| {
+            route_request(
+                &state,
+                HttpRequest {
+                    method: "POST".to_string(),
+                    target: "/submit_block".to_string(),
+                    body: body.to_vec(),
+                },
+            )
+        };
+        let bad_hex = submit(br#"{"block_hex":"zz"}"#);
+        assert_eq!(bad_hex.status, 400);
+        assert_eq!(
+            response_json(&bad_hex)["error"].as_str(),
+            Some("block_hex must be valid hex")
+        );
+        let garbage = submit(br#"{"block_hex":"00"}"#);
+        assert_eq!(garbage.status, 422);
+        assert_eq!(response_json(&garbage)["accepted"], false);
+        assert_eq!(
+            state.sync_engine.lock().expect("engine").chain_state.height,
+            0
+        );
+        fs::remove_dir_all(dir).expect("cleanup");
+    }
+
+    #[test]
+    fn get_chain_tips_lists_the_active_tip() {
+        let (state, dir) = build_state(true);
+        let response = route_request(
+            &state,
+            HttpRequest {
+                method: "GET".to_string(),
+                target: "/get_chain_tips".to_string(),
+                body: Vec::new(),
+            },
+        );
+        assert_eq!(response.status, 200);
+        let tips = response_json(&response);
+        let tips = tips.as_array().expect("array");
+        assert_eq!(tips.len(), 1);
+        assert_eq!(tips[0]["status"], "active");
+        assert_eq!(tips[0]["height"].as_u64(), Some(0));
+        assert_eq!(tips[0]["branch_len"].as_u64(), Some(0));
+        assert!(tips[0].get("invalid_reason").is_none());
+        fs::remove_dir_all(dir).expect("cleanup");
+    }
+
     #[test]
     fn get_block_rejects_invalid_hash() {
         let (state, dir) = build_state(true);
@@ -5137,6 +5653,7 @@ mod tests {
             rpc_op_lock: Arc::new(Mutex::new(())),
             live_mining_cfg: None,
             live_complete_da_set_provider: None,
+            broadcast: None,
             // RUB-10 / GitHub #1151: render_prometheus_metrics test does
             // not exercise `/ready`; default `NotReady` is fine.
             readiness: Arc::new(super::ReadinessGate::default()),
@@ -5176,6 +5693,7 @@ mod tests {
             "rubin_node_in_ibd",
             "rubin_node_reorg_total",
             "rubin_node_last_reorg_depth",
+            "rubin_node_reorg_refused_total",
             "rubin_node_peer_count",
             "rubin_node_p2p_orphan_pool_blocks",
             "rubin_node_p2p_orphan_pool_bytes",
@@ -5437,7 +5955,10 @@ mod tests {
 
     #[test]
     fn parse_hex32_rejects_wrong_length() {
-        assert!(parse_hex32("00").is_err());
+        assert_eq!(
+            parse_hex32("hash", "00").unwrap_err(),
+            "hash: expected 32 bytes, got 1"
+        );
     }
 
     #[test]
@@ -5948,6 +6469,49 @@ mod tests {
         fs::remove_dir_all(dir).expect("cleanup");
     }
 
+    #[test]
+    fn get_telemetry_exposes_relay_field_set() {
+        let (state, dir) = build_state(true);
+        let get = |target: &str, method: &str| {
+            route_request(
+                &state,
+                HttpRequest {
+                    method: method.to_string(),
+                    target: target.to_string(),
+                    body: Vec::new(),
+                },
+            )
+        };
+        let response = get("/get_telemetry", "GET");
+        assert_eq!(response.status, 200);
+        let body = response_json(&response);
+        for field in crate::relay_telemetry::RELAY_TELEMETRY_FIELDS {
+            assert!(body.get(field).is_some(), "missing {field}: {body}");
+        }
+        assert!(body["shortid_collision_blocks"].is_array());
+        assert_eq!(body["peer_quality_score"].as_u64(), Some(0));
+        assert!(body.get("error").is_none());
+
+        let response = get("/get_telemetry", "POST");
+        assert_eq!(response.status, 400);
+        assert_eq!(
+            response_json(&response)["error"].as_str(),
+            Some("GET required")
+        );
+
+        let metrics = String::from_utf8(get("/metrics", "GET").body).expect("utf8 metrics");
+        for field in crate::relay_telemetry::RELAY_TELEMETRY_FIELDS {
+            assert!(
+                metrics.contains(&format!("rubin_node_relay_telemetry{{field=\"{field}\"}} ")),
+                "{metrics}"
+            );
+        }
+        assert!(metrics.contains("rubin_node_sendcmpct_mode_changes_total "));
+        assert!(metrics.contains("rubin_node_da_storm_mode "));
+        assert!(metrics.contains("rubin_node_da_set_recovery_success_rate "));
+        fs::remove_dir_all(dir).expect("cleanup");
+    }
+
     #[test]
     fn get_mempool_rejects_post() {
         let (state, dir) = build_state(true);
@@ -6113,6 +6677,36 @@ mod tests {
         fs::remove_dir_all(dir).expect("cleanup");
     }
 
+    #[test]
+    fn broadcast_status_reports_tracked_transactions() {
+        let (mut state, dir) = build_state(true);
+        let get = |state: &super::DevnetRPCState, txid: [u8; 32]| {
+            route_request(
+                state,
+                HttpRequest {
+                    method: "GET".to_string(),
+                    target: format!("/broadcast_status?txid={}", hex::encode(txid)),
+                    body: Vec::new(),
+                },
+            )
+        };
+        assert_eq!(get(&state, [0x44; 32]).status, 503);
+
+        let mut mgr = crate::BroadcastManager::new(crate::BroadcastConfig::default());
+        mgr.submit([0x44; 32], &[1], 5, 1_000);
+        state.set_broadcast_manager(Arc::new(Mutex::new(mgr)));
+        let response = get(&state, [0x44; 32]);
+        assert_eq!(response.status, 200);
+        let body = response_json(&response);
+        assert_eq!(body["state"].as_str(), Some("pending"));
+        assert_eq!(body["submitted_at"].as_u64(), Some(1_000));
+
+        let response = get(&state, [0x55; 32]);
+        assert_eq!(response.status, 404);
+        assert_eq!(response_json(&response)["status"].as_str(), Some("unknown"));
+        fs::remove_dir_all(dir).expect("cleanup");
+    }
+
     #[test]
     fn get_tx_empty_txid_value_is_classified_as_missing() {
         // Go/Rust parity: ?txid= (present but empty value) must classify as
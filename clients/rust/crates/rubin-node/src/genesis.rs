@@ -1,19 +1,21 @@
 use std::fs;
 use std::path::Path;
 
+use crate::io_utils::parse_hex32;
 use crate::production_rotation_schedule::production_rotation_descriptor_for_network;
+use num_bigint::BigUint;
 use rubin_consensus::constants::{
     MAX_WITNESS_BYTES_PER_TX, ML_DSA_87_PUBKEY_BYTES, ML_DSA_87_SIG_BYTES, SUITE_ID_ML_DSA_87,
     SUITE_ID_SENTINEL, VERIFY_COST_ML_DSA_87,
 };
-use rubin_consensus::encode_compact_size;
 use rubin_consensus::{
     block_hash, canonical_rotation_network_name_normalized,
     is_v1_production_rotation_network_normalized,
-    validate_rotation_descriptor_for_normalized_network, CryptoRotationDescriptor,
+    validate_rotation_descriptor_for_normalized_network, ChainParams, CryptoRotationDescriptor,
     DefaultRotationProvider, DescriptorRotationProvider, SuiteParams, SuiteRegistry,
     BLOCK_HEADER_BYTES, SUPPORTED_ROTATION_NETWORK_NAMES_CSV,
 };
+use rubin_consensus::{encode_compact_size, hex_decode_exact};
 use serde::Deserialize;
 use std::collections::{BTreeMap, BTreeSet};
 
@@ -24,6 +26,8 @@ const GENESIS_CHAIN_ID_HEX: &str =
 const MAX_SUITE_REGISTRY_PARAM_LEN: u64 = MAX_WITNESS_BYTES_PER_TX as u64;
 pub const PRODUCTION_LOCAL_ROTATION_DESCRIPTOR_ERR: &str =
     "rotation_descriptor: production networks forbid local rotation_descriptor";
+pub const PRODUCTION_CONSENSUS_OVERRIDES_ERR: &str =
+    "consensus_overrides: production networks forbid chain parameter overrides";
 #[cfg(test)]
 const GENESIS_MAGIC_SEPARATOR: &[u8] = b"RUBIN-GENESIS-v1";
 
@@ -36,6 +40,24 @@ struct GenesisPack {
     rotation_descriptor: Option<GenesisRotationDescriptor>,
     #[serde(default)]
     suite_registry: Vec<GenesisSuiteParams>,
+    /// Big-endian hex; empty leaves the side-branch work gate off.
+    #[serde(default)]
+    minimum_chainwork_hex: String,
+    #[serde(default)]
+    consensus_overrides: Option<GenesisConsensusOverrides>,
+}
+
+/// Optional replacements for the v1 retarget and maturity constants, for
+/// developer chains only. Omitted fields keep their v1 value.
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+struct GenesisConsensusOverrides {
+    #[serde(default)]
+    window_size: Option<u64>,
+    #[serde(default)]
+    target_block_interval: Option<u64>,
+    #[serde(default)]
+    coinbase_maturity: Option<u64>,
 }
 
 /// JSON-serializable rotation descriptor for genesis/config.
@@ -104,6 +126,12 @@ pub struct LoadedGenesisConfig {
     /// explicit overlay keep this as None; production empty slots use an
     /// explicit default pre-rotation context.
     pub suite_context: Option<crate::sync::SuiteContext>,
+    /// Forwarded to `SyncConfig::minimum_chainwork`; zero unless the genesis
+    /// pack sets `minimum_chainwork_hex`.
+    pub minimum_chainwork: BigUint,
+    /// Forwarded to `SyncConfig::chain_params`; `ChainParams::V1` unless the
+    /// pack sets `consensus_overrides` on a non-production network.
+    pub chain_params: ChainParams,
 }
 
 pub fn devnet_genesis_block_bytes() -> Vec<u8> {
@@ -125,6 +153,58 @@ pub fn devnet_genesis_hash() -> [u8; 32] {
     block_hash(&bytes[..BLOCK_HEADER_BYTES]).expect("devnet genesis hash")
 }
 
+/// A chain instance compiled into the binary, selectable with
+/// `--chain <name>` without a genesis file on disk.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ChainPreset {
+    pub name: &'static str,
+    pub network: &'static str,
+    chain_id_hex: &'static str,
+}
+
+impl ChainPreset {
+    pub fn chain_id(&self) -> [u8; 32] {
+        decode_hex32("chain_preset_chain_id", self.chain_id_hex)
+    }
+}
+
+/// Only devnet ships an embedded instance; production instances are loaded
+/// from an operator-supplied genesis file via `custom:<path>`.
+pub const CHAIN_PRESETS: [ChainPreset; 1] = [ChainPreset {
+    name: "devnet",
+    network: "devnet",
+    chain_id_hex: GENESIS_CHAIN_ID_HEX,
+}];
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ChainSelection {
+    Preset(&'static ChainPreset),
+    Custom(std::path::PathBuf),
+}
+
+/// Parses a `--chain` value: a preset name or `custom:<genesis path>`.
+pub fn resolve_chain(value: &str) -> Result<ChainSelection, String> {
+    if let Some(path) = value.strip_prefix("custom:") {
+        if path.trim().is_empty() {
+            return Err("custom chain requires a genesis file path".to_string());
+        }
+        return Ok(ChainSelection::Custom(path.into()));
+    }
+    let name = value.trim().to_ascii_lowercase();
+    CHAIN_PRESETS
+        .iter()
+        .find(|preset| preset.name == name)
+        .map(ChainSelection::Preset)
+        .ok_or_else(|| {
+            let names: Vec<&str> = CHAIN_PRESETS.iter().map(|preset| preset.name).collect();
+            format!(
+                "unknown chain '{}' (expected: {}, custom:<path>)",
+                name,
+                names.join(", ")
+            )
+        })
+}
+
 pub fn load_genesis_config(
     path: Option<&Path>,
     network: &str,
@@ -134,6 +214,8 @@ pub fn load_genesis_config(
             chain_id: devnet_genesis_chain_id(),
             genesis_hash: Some(devnet_genesis_hash()),
             suite_context: None,
+            minimum_chainwork: BigUint::ZERO,
+            chain_params: ChainParams::V1,
         });
     };
     let raw = fs::read_to_string(path)
@@ -143,16 +225,10 @@ pub fn load_genesis_config(
     reject_removed_genesis_core_ext_keys(&raw_json)?;
     let payload: GenesisPack = serde_json::from_value(raw_json)
         .map_err(|e| format!("parse genesis file {}: {e}", path.display()))?;
-    let mut trimmed = payload.chain_id_hex.trim();
-    if trimmed.is_empty() {
+    if payload.chain_id_hex.trim().is_empty() {
         return Err("chain_id_hex missing".to_string());
     }
-    if let Some(rest) = trimmed.strip_prefix("0x") {
-        trimmed = rest;
-    } else if let Some(rest) = trimmed.strip_prefix("0X") {
-        trimmed = rest;
-    }
-    let chain_id = parse_hex32("chain_id", trimmed)?;
+    let chain_id = parse_hex32("chain_id", &payload.chain_id_hex)?;
     let genesis_hash = if payload.genesis_hash_hex.trim().is_empty() {
         if chain_id == devnet_genesis_chain_id() {
             Some(devnet_genesis_hash())
@@ -160,10 +236,7 @@ pub fn load_genesis_config(
             None
         }
     } else {
-        Some(parse_hex32(
-            "genesis_hash",
-            payload.genesis_hash_hex.trim(),
-        )?)
+        Some(parse_hex32("genesis_hash", &payload.genesis_hash_hex)?)
     };
     Ok(LoadedGenesisConfig {
         chain_id,
@@ -173,9 +246,58 @@ pub fn load_genesis_config(
             &payload.suite_registry,
             network,
         )?,
+        minimum_chainwork: parse_minimum_chainwork(&payload.minimum_chainwork_hex)?,
+        chain_params: resolve_chain_params(payload.consensus_overrides.as_ref(), network)?,
     })
 }
 
+/// Chain parameters declared by a genesis pack's `consensus_overrides`,
+/// `ChainParams::V1` when it has none. Production networks never take
+/// overrides: a pack that sets them is rejected.
+pub fn chain_params_from_genesis_text(raw: &str, network: &str) -> Result<ChainParams, String> {
+    let payload: GenesisPack =
+        serde_json::from_str(raw).map_err(|e| format!("parse genesis: {e}"))?;
+    resolve_chain_params(payload.consensus_overrides.as_ref(), network)
+}
+
+fn resolve_chain_params(
+    overrides: Option<&GenesisConsensusOverrides>,
+    network: &str,
+) -> Result<ChainParams, String> {
+    let Some(overrides) = overrides else {
+        return Ok(ChainParams::V1);
+    };
+    let normalized_network = canonical_config_network_name(network)?;
+    if is_v1_production_rotation_network_normalized(normalized_network.as_str()) {
+        return Err(PRODUCTION_CONSENSUS_OVERRIDES_ERR.to_string());
+    }
+    let v1 = ChainParams::V1;
+    let params = ChainParams {
+        target_block_interval: overrides
+            .target_block_interval
+            .unwrap_or(v1.target_block_interval),
+        window_size: overrides.window_size.unwrap_or(v1.window_size),
+        coinbase_maturity: overrides.coinbase_maturity.unwrap_or(v1.coinbase_maturity),
+    };
+    params
+        .validate()
+        .map_err(|e| format!("consensus_overrides: {e}"))?;
+    Ok(params)
+}
+
+fn parse_minimum_chainwork(value: &str) -> Result<BigUint, String> {
+    let trimmed = value.trim();
+    let trimmed = trimmed.strip_prefix("0x").unwrap_or(trimmed);
+    if trimmed.is_empty() {
+        return Ok(BigUint::ZERO);
+    }
+    if trimmed.len() > 64 {
+        return Err("minimum_chainwork_hex longer than 32 bytes".to_string());
+    }
+    BigUint::parse_bytes(trimmed.as_bytes(), 16)
+        .ok_or_else(|| "minimum_chainwork_hex is not hex".to_string())
+}
+
 fn reject_removed_genesis_core_ext_keys(raw_json: &serde_json::Value) -> Result<(), String> {
     let Some(fields) = raw_json.as_object() else {
         return Ok(());
@@ -364,16 +486,10 @@ pub fn load_chain_id_from_genesis_file(path: Option<&Path>) -> Result<[u8; 32],
         .map_err(|e| format!("read genesis file {}: {e}", path.display()))?;
     let payload: GenesisPack = serde_json::from_str(&raw)
         .map_err(|e| format!("parse genesis file {}: {e}", path.display()))?;
-    let mut trimmed = payload.chain_id_hex.trim();
-    if trimmed.is_empty() {
+    if payload.chain_id_hex.trim().is_empty() {
         return Err("chain_id_hex missing".to_string());
     }
-    if let Some(rest) = trimmed.strip_prefix("0x") {
-        trimmed = rest;
-    } else if let Some(rest) = trimmed.strip_prefix("0X") {
-        trimmed = rest;
-    }
-    parse_hex32("chain_id", trimmed)
+    parse_hex32("chain_id", &payload.chain_id_hex)
 }
 
 pub fn validate_incoming_chain_id(block_height: u64, chain_id: [u8; 32]) -> Result<(), String> {
@@ -404,25 +520,7 @@ fn decode_hex32(name: &str, value: &str) -> [u8; 32] {
 }
 
 fn decode_hex_exact(name: &str, value: &str, expected_len: usize) -> Vec<u8> {
-    let bytes = hex::decode(value).unwrap_or_else(|e| panic!("{name}: {e}"));
-    if bytes.len() != expected_len {
-        panic!("{name}: expected {expected_len} bytes, got {}", bytes.len());
-    }
-    bytes
-}
-
-fn parse_hex32(name: &str, value: &str) -> Result<[u8; 32], String> {
-    let value = value
-        .strip_prefix("0x")
-        .or_else(|| value.strip_prefix("0X"))
-        .unwrap_or(value);
-    let bytes = hex::decode(value).map_err(|e| format!("{name}: {e}"))?;
-    if bytes.len() != 32 {
-        return Err(format!("{name}: expected 32 bytes, got {}", bytes.len()));
-    }
-    let mut out = [0u8; 32];
-    out.copy_from_slice(&bytes);
-    Ok(out)
+    hex_decode_exact(value, expected_len, name).unwrap_or_else(|e| panic!("{e}"))
 }
 
 #[cfg(test)]
@@ -433,11 +531,14 @@ mod tests {
     use rubin_consensus::SuiteRegistry;
 
     use super::{
-        build_suite_context_from_descriptor_with_production_lookup, derive_devnet_genesis_chain_id,
-        devnet_genesis_block_bytes, devnet_genesis_chain_id, load_chain_id_from_genesis_file,
-        load_genesis_config, validate_incoming_chain_id, CryptoRotationDescriptor,
-        GenesisRotationDescriptor, GenesisSuiteParams, PRODUCTION_LOCAL_ROTATION_DESCRIPTOR_ERR,
+        build_suite_context_from_descriptor_with_production_lookup, chain_params_from_genesis_text,
+        derive_devnet_genesis_chain_id, devnet_genesis_block_bytes, devnet_genesis_chain_id,
+        load_chain_id_from_genesis_file, load_genesis_config, resolve_chain,
+        validate_incoming_chain_id, BigUint, ChainSelection, CryptoRotationDescriptor,
+        GenesisRotationDescriptor, GenesisSuiteParams, CHAIN_PRESETS, GENESIS_CHAIN_ID_HEX,
+        PRODUCTION_CONSENSUS_OVERRIDES_ERR, PRODUCTION_LOCAL_ROTATION_DESCRIPTOR_ERR,
     };
+    use rubin_consensus::ChainParams;
     use std::collections::BTreeMap;
 
     fn suite_registry_entry_json(
@@ -496,6 +597,30 @@ mod tests {
         assert_eq!(derive_devnet_genesis_chain_id(), devnet_genesis_chain_id());
     }
 
+    #[test]
+    fn chain_presets_match_live_derivation_and_resolve() {
+        for preset in &CHAIN_PRESETS {
+            assert_eq!(
+                resolve_chain(&preset.name.to_ascii_uppercase()).expect("resolve"),
+                ChainSelection::Preset(preset)
+            );
+        }
+        let ChainSelection::Preset(devnet) = resolve_chain("devnet").expect("devnet") else {
+            panic!("devnet must be a preset");
+        };
+        assert_eq!(devnet.chain_id(), derive_devnet_genesis_chain_id());
+        assert_eq!(
+            resolve_chain("custom:rel/genesis.json").expect("custom"),
+            ChainSelection::Custom("rel/genesis.json".into())
+        );
+        assert!(resolve_chain("custom:").is_err());
+        let err = resolve_chain("testnet").expect_err("no embedded testnet");
+        assert_eq!(
+            err,
+            "unknown chain 'testnet' (expected: devnet, custom:<path>)"
+        );
+    }
+
     #[test]
     fn devnet_genesis_block_bytes_have_expected_frame() {
         let block = devnet_genesis_block_bytes();
@@ -661,6 +786,44 @@ mod tests {
         }
     }
 
+    #[test]
+    fn load_genesis_config_reads_minimum_chainwork() {
+        let dir = std::env::temp_dir().join(format!(
+            "rubin-node-genesis-minimum-chainwork-{}",
+            std::process::id()
+        ));
+        std::fs::create_dir_all(&dir).expect("mkdir");
+        let path = dir.join("genesis.json");
+        let write = |work: &str| {
+            std::fs::write(
+                &path,
+                format!(
+                    "{{\
+                      \"chain_id_hex\":\"{GENESIS_CHAIN_ID_HEX}\",\
+                      \"minimum_chainwork_hex\":\"{work}\"\
+                    }}"
+                ),
+            )
+            .expect("write");
+        };
+
+        write("0x0100");
+        let cfg = load_genesis_config(Some(&path), "devnet").expect("load");
+        assert_eq!(cfg.minimum_chainwork, BigUint::from(256u32));
+        assert_eq!(
+            load_genesis_config(None, "devnet")
+                .expect("devnet")
+                .minimum_chainwork,
+            BigUint::ZERO
+        );
+
+        write("zz");
+        let err = load_genesis_config(Some(&path), "devnet").unwrap_err();
+        assert_eq!(err, "minimum_chainwork_hex is not hex");
+
+        std::fs::remove_dir_all(&dir).expect("cleanup");
+    }
+
     #[test]
     fn load_genesis_config_accepts_explicit_suite_registry_without_rotation_descriptor() {
         let dir = std::env::temp_dir().join(format!(
@@ -1568,4 +1731,84 @@ mod tests {
 
         std::fs::remove_dir_all(&dir).expect("cleanup");
     }
+
+    const FAST_DEVNET_PACK: &str = "{\
+        \"chain_id_hex\":\"0x1111111111111111111111111111111111111111111111111111111111111111\",\
+        \"consensus_overrides\":{\"window_size\":16,\"coinbase_maturity\":4,\"target_block_interval\":2}\
+    }";
+
+    #[test]
+    fn chain_params_default_to_v1_without_overrides() {
+        let cfg = load_genesis_config(None, "devnet").expect("embedded devnet");
+        assert_eq!(cfg.chain_params, ChainParams::V1);
+
+        let plain = format!("{{\"chain_id_hex\":\"0x{GENESIS_CHAIN_ID_HEX}\"}}");
+        for network in ["devnet", "mainnet"] {
+            assert_eq!(
+                chain_params_from_genesis_text(&plain, network),
+                Ok(ChainParams::V1)
+            );
+        }
+    }
+
+    #[test]
+    fn chain_params_from_genesis_text_applies_overrides_off_production() {
+        let params = chain_params_from_genesis_text(FAST_DEVNET_PACK, "devnet").expect("params");
+        assert_eq!(
+            params,
+            ChainParams {
+                target_block_interval: 2,
+                window_size: 16,
+                coinbase_maturity: 4,
+            }
+        );
+
+        let partial =
+            "{\"chain_id_hex\":\"0x11\",\"consensus_overrides\":{\"coinbase_maturity\":4}}";
+        let params = chain_params_from_genesis_text(partial, "devnet").expect("partial");
+        assert_eq!(
+            params,
+            ChainParams {
+                coinbase_maturity: 4,
+                ..ChainParams::V1
+            }
+        );
+
+        for bad in [
+            "{\"chain_id_hex\":\"0x11\",\"consensus_overrides\":{\"window_size\":0}}",
+            "{\"chain_id_hex\":\"0x11\",\"consensus_overrides\":{\"retarget\":16}}",
+        ] {
+            assert!(
+                chain_params_from_genesis_text(bad, "devnet").is_err(),
+                "{bad}"
+            );
+        }
+    }
+
+    #[test]
+    fn chain_params_overrides_are_rejected_on_production_networks() {
+        for network in ["mainnet", " TestNet "] {
+            assert_eq!(
+                chain_params_from_genesis_text(FAST_DEVNET_PACK, network),
+                Err(PRODUCTION_CONSENSUS_OVERRIDES_ERR.to_string())
+            );
+        }
+    }
+
+    #[test]
+    fn load_genesis_config_reads_consensus_overrides() {
+        let path = crate::io_utils::unique_temp_path("rubin-node-genesis-overrides");
+        std::fs::write(&path, FAST_DEVNET_PACK).expect("write");
+
+        let cfg = load_genesis_config(Some(&path), "devnet").expect("load");
+        assert_eq!(cfg.chain_params.window_size, 16);
+        assert_eq!(cfg.chain_params.coinbase_maturity, 4);
+        assert_eq!(cfg.chain_params.target_block_interval, 2);
+        assert_eq!(
+            load_genesis_config(Some(&path), "mainnet").unwrap_err(),
+            PRODUCTION_CONSENSUS_OVERRIDES_ERR
+        );
+
+        std::fs::remove_file(&path).expect("cleanup");
+    }
 }
@@ -0,0 +1,225 @@
+//! Rolling window of best-chain headers ending at the tip.
+//!
+//! `SyncEngine` pushes each connected header and pops each disconnected one,
+//! so building a block's validation context (up to the chain's
+//! `window_size` ancestors at a retarget boundary) does not walk the
+//! blockstore. The window is keyed by
+//! its tip hash: a caller only trusts it while that hash equals the chain
+//! tip, so a rollback path that forgets to update it costs a store read,
+//! never a wrong answer. A push that does not extend the tip (cold start,
+//! or a reorg deeper than the window) refills from the block index.
+
+use std::collections::VecDeque;
+
+use rubin_consensus::{BlockHeader, ChainParams};
+
+use crate::blockstore::{BlockStore, MTP_WINDOW_BLOCKS};
+
+#[derive(Clone, Debug)]
+pub(crate) struct AncestorHeaderCache {
+    /// Oldest first; the last entry is the header of `tip_hash`.
+    headers: VecDeque<BlockHeader>,
+    tip_hash: Option<[u8; 32]>,
+    tip_height: u64,
+    capacity: usize,
+}
+
+impl AncestorHeaderCache {
+    /// A window deep enough for the largest context `params` asks for: the
+    /// retarget window, or the MTP window when that is longer.
+    pub(crate) fn new(params: &ChainParams) -> Self {
+        Self {
+            headers: VecDeque::new(),
+            tip_hash: None,
+            tip_height: 0,
+            capacity: params.window_size.max(MTP_WINDOW_BLOCKS) as usize,
+        }
+    }
+
+    #[cfg(test)]
+    pub(crate) fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub(crate) fn tip_hash(&self) -> Option<[u8; 32]> {
+        self.tip_hash
+    }
+
+    #[cfg(test)]
+    pub(crate) fn len(&self) -> usize {
+        self.headers.len()
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.headers.clear();
+        self.tip_hash = None;
+        self.tip_height = 0;
+    }
+
+    /// Appends the header of the block just connected at `height`. Returns
+    /// false, leaving the window untouched, when it does not extend the
+    /// current tip.
+    pub(crate) fn push(&mut self, height: u64, hash: [u8; 32], header: BlockHeader) -> bool {
+        let extends = match self.tip_hash {
+            Some(tip) => tip == header.prev_block_hash && self.tip_height + 1 == height,
+            None => height == 0,
+        };
+        if !extends {
+            return false;
+        }
+        if self.headers.len() == self.capacity {
+            self.headers.pop_front();
+        }
+        self.headers.push_back(header);
+        self.tip_hash = Some(hash);
+        self.tip_height = height;
+        true
+    }
+
+    /// Drops the tip header after `hash` was disconnected. A window that
+    /// does not end at `hash` is cleared instead.
+    pub(crate) fn pop(&mut self, hash: [u8; 32]) {
+        if self.tip_hash != Some(hash) {
+            self.clear();
+            return;
+        }
+        let Some(header) = self.headers.pop_back() else {
+            self.clear();
+            return;
+        };
+        if self.headers.is_empty() || self.tip_height == 0 {
+            self.clear();
+            return;
+        }
+        self.tip_hash = Some(header.prev_block_hash);
+        self.tip_height -= 1;
+    }
+
+    /// The `count` headers directly below `height`, oldest first, when all
+    /// of them are in the window.
+    pub(crate) fn ancestors_for(&self, height: u64, count: u64) -> Option<Vec<BlockHeader>> {
+        self.tip_hash?;
+        if height == 0 || height - 1 > self.tip_height || count > height {
+            return None;
+        }
+        let first_height = self.tip_height + 1 - self.headers.len() as u64;
+        let from = height - count;
+        if from < first_height {
+            return None;
+        }
+        let start = (from - first_height) as usize;
+        Some(
+            self.headers
+                .range(start..start + count as usize)
+                .cloned()
+                .collect(),
+        )
+    }
+
+    /// Reloads the window ending at `tip_hash` (at `tip_height`) from the
+    /// block index.
+    pub(crate) fn refill(
+        &mut self,
+        store: &BlockStore,
+        tip_hash: [u8; 32],
+        tip_height: u64,
+    ) -> Result<(), String> {
+        self.clear();
+        let count = (tip_height + 1).min(self.capacity as u64);
+        let headers = store.ancestor_headers(tip_hash, count)?;
+        self.headers = headers.into();
+        self.tip_hash = Some(tip_hash);
+        self.tip_height = tip_height;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::AncestorHeaderCache;
+    use rubin_consensus::constants::WINDOW_SIZE;
+    use rubin_consensus::{block_hash, BlockHeader, ChainParams, BLOCK_HEADER_BYTES};
+
+    fn chain(len: u64) -> Vec<([u8; 32], BlockHeader)> {
+        let mut prev = [0u8; 32];
+        (0..len)
+            .map(|height| {
+                let header = BlockHeader {
+                    version: 1,
+                    prev_block_hash: prev,
+                    merkle_root: [0x5a; 32],
+                    timestamp: 1_000 + height,
+                    target: [0xff; 32],
+                    nonce: height,
+                };
+                prev = block_hash(&header.to_bytes()).expect("hash");
+                (prev, header)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn window_is_bounded_and_serves_ancestors_by_height() {
+        let blocks = chain(WINDOW_SIZE + 5);
+        let mut cache = AncestorHeaderCache::new(&ChainParams::V1);
+        for (height, (hash, header)) in blocks.iter().enumerate() {
+            assert!(cache.push(height as u64, *hash, header.clone()));
+            assert!(cache.len() <= cache.capacity());
+        }
+        assert_eq!(cache.len(), WINDOW_SIZE as usize);
+        // 10_080 headers of 116 wire bytes: ~1.17 MB of header data at most.
+        assert_eq!(cache.len() * BLOCK_HEADER_BYTES, 1_169_280);
+
+        let tip = WINDOW_SIZE + 4;
+        let got = cache.ancestors_for(tip + 1, 11).expect("mtp window");
+        let want: Vec<BlockHeader> = blocks[(tip - 10) as usize..=tip as usize]
+            .iter()
+            .map(|(_, header)| header.clone())
+            .collect();
+        assert_eq!(got, want);
+        assert!(cache.ancestors_for(tip + 1, WINDOW_SIZE).is_some());
+        assert!(cache.ancestors_for(tip + 1, WINDOW_SIZE + 1).is_none());
+        assert!(cache.ancestors_for(tip + 2, 1).is_none());
+    }
+
+    #[test]
+    fn push_off_tip_is_refused_and_pop_walks_back() {
+        let blocks = chain(4);
+        let mut cache = AncestorHeaderCache::new(&ChainParams::V1);
+        assert!(!cache.push(1, blocks[1].0, blocks[1].1.clone()));
+        for (height, (hash, header)) in blocks.iter().enumerate() {
+            assert!(cache.push(height as u64, *hash, header.clone()));
+        }
+        assert!(!cache.push(5, [0x11; 32], blocks[0].1.clone()));
+
+        cache.pop(blocks[3].0);
+        assert_eq!(cache.tip_hash(), Some(blocks[2].0));
+        assert_eq!(cache.ancestors_for(3, 3).expect("window").len(), 3);
+        cache.pop([0x22; 32]);
+        assert_eq!(cache.tip_hash(), None);
+        assert!(cache.ancestors_for(1, 1).is_none());
+    }
+
+    #[test]
+    fn window_follows_the_chain_params() {
+        let fast = ChainParams {
+            target_block_interval: 2,
+            window_size: 16,
+            coinbase_maturity: 4,
+        };
+        let blocks = chain(40);
+        let mut cache = AncestorHeaderCache::new(&fast);
+        for (height, (hash, header)) in blocks.iter().enumerate() {
+            assert!(cache.push(height as u64, *hash, header.clone()));
+        }
+        assert_eq!(cache.len(), 16);
+        assert!(cache.ancestors_for(40, 16).is_some());
+        assert!(cache.ancestors_for(40, 17).is_none());
+
+        let tiny = ChainParams {
+            window_size: 2,
+            ..fast
+        };
+        assert_eq!(AncestorHeaderCache::new(&tiny).capacity(), 11);
+    }
+}
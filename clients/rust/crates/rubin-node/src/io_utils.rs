@@ -2,6 +2,8 @@ use std::fs;
 use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicU64, Ordering};
 
+use rubin_consensus::hex_decode_32;
+
 /// Process-wide monotonic sequence counter appended to every temp-file
 /// name so two threads writing to the same destination within the same
 /// process can never collide on a shared `.tmp.<pid>` path (audit
@@ -474,14 +476,10 @@ pub fn normalize_data_dir(path: &Path) -> Result<PathBuf, String> {
 // tests lived in git history; the CLI-level approach is the single
 // resolution strategy the whole node now uses.
 
+/// Decodes a 32-byte hex field per the shared `hex_decode_32` convention;
+/// the error names `name` and the decoded length.
 pub fn parse_hex32(name: &str, value: &str) -> Result<[u8; 32], String> {
-    let bytes = hex::decode(value).map_err(|e| format!("{name}: {e}"))?;
-    if bytes.len() != 32 {
-        return Err(format!("{name}: expected 32 bytes, got {}", bytes.len()));
-    }
-    let mut out = [0u8; 32];
-    out.copy_from_slice(&bytes);
-    Ok(out)
+    hex_decode_32(value, name).map_err(|e| e.to_string())
 }
 
 /// Atomically write `data` to `path` with an honest fsync durability
@@ -1,59 +1,120 @@
+pub mod address;
+pub mod block_files;
+pub mod block_json;
+pub mod block_preverify;
+pub mod block_submit;
 pub mod blockstore;
+pub mod broadcast;
+pub mod build_info;
 pub mod chainstate;
+pub mod chainstate_compare;
 mod chainstate_recovery;
 pub mod coinbase;
+pub mod compact_mode;
 pub mod da_prefetch;
+pub mod da_recovery;
 pub mod da_relay;
 pub mod da_txgen;
+pub mod datadir;
+pub mod datadir_lock;
+pub mod devnet_harness;
 pub mod devnet_rpc;
 pub mod genesis;
+mod header_cache;
 pub mod interop;
 mod io_utils;
 pub mod miner;
+pub mod mining_loop;
+pub mod op_error;
 pub mod p2p_runtime;
 pub mod p2p_service;
+pub mod policy_dust;
 mod production_rotation_schedule;
+pub mod relay_announce;
 pub mod relay_pool;
+pub mod relay_telemetry;
+pub mod rpst;
+pub mod store_keys;
 pub mod sync;
 pub mod sync_disconnect;
+pub mod sync_low_work;
+pub mod sync_peer;
 pub mod sync_reorg;
+pub mod tx_orphans;
 pub mod tx_relay;
 pub mod tx_seen;
+pub mod tx_verify;
 pub mod txpool;
+mod txpool_graph;
 pub mod undo;
+pub mod verify_chain;
 
 #[cfg(test)]
 mod test_helpers;
 
-pub use blockstore::{block_store_path, BlockStore, BLOCK_STORE_DIR_NAME};
+pub use address::{address_hrp, decode_address, encode_address, encode_tx_output_address, Address};
+pub use block_json::{
+    block_to_json, chain_tips_to_json, parse_block_verbosity, BlockJson, ChainTipJson,
+};
+pub use block_preverify::{check_block_structure, PreparedBlock};
+pub use blockstore::{
+    block_store_path, BlockIndexEntry, BlockInvalidReason, BlockStatus, BlockStore,
+    BlockValidationContext, ChainAnchor, ChainAnchors, ChainTip, ChainTipStatus,
+    BLOCK_INDEX_ENTRY_VERSION, BLOCK_STORE_DIR_NAME,
+};
+pub use broadcast::{
+    broadcast_state_path, load_broadcast_manager, BroadcastConfig, BroadcastEntry,
+    BroadcastManager, BroadcastState, BroadcastStatus, BROADCAST_STATE_FILE_NAME,
+    DEFAULT_BROADCAST_EXPIRY_SECS, DEFAULT_REBROADCAST_INTERVAL_SECS,
+};
+pub use build_info::{BuildInfo, BUILD_INFO_VERSION};
 pub use chainstate::{
-    chain_state_path, load_chain_state, CanonicalAppliedBlock, ChainState,
-    ChainStateConnectSummary, CHAIN_STATE_FILE_NAME, UTXO_SET_HASH_DST,
+    chain_state_path, load_chain_state, load_chain_state_with_anchors, BlockStats,
+    CanonicalAppliedBlock, ChainState, ChainStateConnectSummary, CHAIN_STATE_FILE_NAME,
+    UTXO_SET_HASH_DST,
+};
+pub use chainstate_compare::{
+    compare_chain_state, ChainStateCompareReport, ChainStateCompareStatus, ChainStateSummary,
+    DEFAULT_CHAIN_STATE_DIFF_LIMIT,
 };
 pub use chainstate_recovery::reconcile_chain_state_with_block_store;
 pub use coinbase::{
     build_coinbase_tx, default_mine_address, normalize_mine_address, parse_mine_address,
-    validate_mine_address,
+    validate_mine_address, verify_coinbase_tx, CoinbaseVerifyReport,
 };
 pub use da_txgen::{
     build_signed_da_set, mine_and_generate, select_mature_p2pk_coinbases, SignedDaSet, SignedDaTx,
     DA_RELAY_BASE_HEIGHT,
 };
+pub use datadir::{Datadir, DATADIR_MANIFEST_FILE_NAME, DATADIR_MANIFEST_VERSION};
+pub use datadir_lock::{DataDirLock, DATADIR_LOCK_FILE_NAME};
 pub use devnet_rpc::{
     new_devnet_rpc_state, new_devnet_rpc_state_with_tx_pool, new_shared_runtime_tx_pool,
     rpc_bind_host_is_loopback, start_devnet_rpc_server, DevnetRPCState, RunningDevnetRPCServer,
 };
 pub use genesis::{
     devnet_genesis_block_bytes, devnet_genesis_chain_id, load_chain_id_from_genesis_file,
-    load_genesis_config, validate_incoming_chain_id, LoadedGenesisConfig,
-    PRODUCTION_LOCAL_ROTATION_DESCRIPTOR_ERR,
+    load_genesis_config, resolve_chain, validate_incoming_chain_id, ChainPreset, ChainSelection,
+    LoadedGenesisConfig, CHAIN_PRESETS, PRODUCTION_LOCAL_ROTATION_DESCRIPTOR_ERR,
 };
 pub use io_utils::normalize_data_dir;
-pub use miner::{parse_mine_address_arg, MinedBlock, Miner, MinerConfig};
+pub use miner::{parse_mine_address_arg, BlockTemplate, MinedBlock, Miner, MinerConfig};
+pub use op_error::{OpError, OpErrorKind};
 pub use p2p_runtime::{default_peer_runtime_config, PeerManager};
 pub use p2p_service::{start_node_p2p_service, NodeP2PServiceConfig, RunningNodeP2PService};
+pub use rpst::{Rpst, RpstInput, RpstRole};
 pub use sync::{
     default_sync_config, validate_mainnet_genesis_guard, HeaderRequest, PVTelemetrySnapshot,
     SyncConfig, SyncEngine, DEFAULT_IBD_LAG_SECONDS,
 };
-pub use txpool::{TxPool, TxPoolAdmitError, TxPoolAdmitErrorKind, TxPoolConfig};
+pub use sync_peer::{BlockImportSummary, BlockSyncPeer};
+pub use tx_verify::{
+    parse_tx_verify_prevouts, verify_tx, TxVerifyInput, TxVerifyInputStatus, TxVerifyReport,
+};
+pub use txpool::{
+    AcceptedTx, TxPool, TxPoolAdmitError, TxPoolAdmitErrorKind, TxPoolConfig, TxRejectClass,
+    MAX_TX_BYTES,
+};
+pub use verify_chain::{
+    verify_chain, VerifyChainReport, VERIFY_CHAIN_DEFAULT_LEVEL, VERIFY_CHAIN_MAX_LEVEL,
+};
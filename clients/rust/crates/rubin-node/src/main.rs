@@ -1,28 +1,52 @@
 use std::env;
 use std::fs;
 use std::io::{self, Write};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{mpsc, Arc, Mutex};
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
 use rubin_consensus::{
-    canonical_rotation_network_name_normalized, normalized_rotation_network_name,
-    SUPPORTED_ROTATION_NETWORK_NAMES_CSV,
+    canonical_rotation_network_name_normalized, featurebit_state_at_height_from_window_counts,
+    flagday_active_at_height, hex_decode_32, normalized_rotation_network_name, ChainParams,
+    FeatureBitDeployment, FeatureBitState, FlagDayDeployment, SUPPORTED_ROTATION_NETWORK_NAMES_CSV,
+};
+use rubin_node::block_files::{
+    export_block_files, import_block_files, DEFAULT_EXPORT_BLOCKS_PER_FILE,
+    DEFAULT_IMPORT_MAX_BUFFERED_BLOCKS,
+};
+use rubin_node::devnet_harness::{
+    run_devnet_harness, DevnetHarnessConfig, DEFAULT_DEVNET_HARNESS_SEED,
 };
 use rubin_node::devnet_rpc::{
-    attach_shutdown_signal_to_devnet_rpc_state, RPC_READINESS_TRANSITION_FAILED,
+    attach_shutdown_signal_to_devnet_rpc_state, DevnetRPCState, RPC_READINESS_TRANSITION_FAILED,
 };
+use rubin_node::mining_loop::{
+    run_mining_loop, MiningLoopConfig, MiningLoopReport, SystemMiningClock,
+    DEFAULT_TEMPLATE_FEE_REFRESH_DELTA,
+};
+use rubin_node::policy_dust::PolicyInfo;
 use rubin_node::{
-    block_store_path, chain_state_path, default_peer_runtime_config, default_sync_config,
-    load_chain_state, load_genesis_config, new_devnet_rpc_state_with_tx_pool,
-    new_shared_runtime_tx_pool, parse_mine_address_arg, reconcile_chain_state_with_block_store,
-    rpc_bind_host_is_loopback, start_devnet_rpc_server, start_node_p2p_service,
-    validate_mainnet_genesis_guard, BlockStore, LoadedGenesisConfig, Miner, MinerConfig,
-    NodeP2PServiceConfig, PeerManager, RunningDevnetRPCServer, RunningNodeP2PService, SyncEngine,
+    block_store_path, block_to_json, broadcast_state_path, chain_state_path, chain_tips_to_json,
+    compare_chain_state, default_peer_runtime_config, default_sync_config, load_broadcast_manager,
+    load_chain_id_from_genesis_file, load_chain_state, load_chain_state_with_anchors,
+    load_genesis_config, new_devnet_rpc_state_with_tx_pool, new_shared_runtime_tx_pool,
+    parse_block_verbosity, parse_mine_address_arg, parse_tx_verify_prevouts,
+    reconcile_chain_state_with_block_store, resolve_chain, rpc_bind_host_is_loopback,
+    start_devnet_rpc_server, start_node_p2p_service, validate_mainnet_genesis_guard, verify_chain,
+    verify_coinbase_tx, verify_tx, BlockIndexEntry, BlockStats, BlockStore, BroadcastConfig,
+    BroadcastManager, BuildInfo, ChainSelection, ChainStateSummary, Datadir, LoadedGenesisConfig,
+    Miner, MinerConfig, NodeP2PServiceConfig, OpError, PeerManager, RunningDevnetRPCServer,
+    RunningNodeP2PService, SyncEngine, TxPool, DEFAULT_CHAIN_STATE_DIFF_LIMIT,
+    VERIFY_CHAIN_DEFAULT_LEVEL, VERIFY_CHAIN_MAX_LEVEL,
 };
 use serde::{Deserialize, Serialize};
 
+mod cli_error;
+mod cli_output;
+use cli_error::{CliError, ErrorClass};
+use cli_output::{CliOutput, Output};
+
 const PRODUCTION_STOP_SIGNAL_SET: &str = "SIGINT/SIGTERM";
 
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -37,12 +61,93 @@ struct CliConfig {
     mine_address: Option<String>,
     mine_blocks: usize,
     mine_exit: bool,
+    mine_loop: MineLoopArgs,
+    broadcast: BroadcastConfig,
     pv_mode: String,
     pv_shadow_max: u64,
+    max_auto_reorg_depth: u64,
     legacy_exposure_scan: bool,
     legacy_suite_ids: Vec<u8>,
     legacy_exposure_include_outpoints: bool,
+    chainstate_summary: bool,
+    chainstate_summary_utxos: bool,
+    compare_chainstate: Option<PathBuf>,
+    compare_chainstate_max_diffs: Option<usize>,
+    block_stats: Option<[u8; 32]>,
+    get_block: Option<[u8; 32]>,
+    get_block_verbosity: Option<u8>,
+    get_block_index: Option<[u8; 32]>,
+    force_reorg: Option<[u8; 32]>,
+    verifychain: bool,
+    verifychain_level: Option<u8>,
     dry_run: bool,
+    featurebits: Option<String>,
+    featurebits_deployments: Option<PathBuf>,
+    verify_tx: VerifyTxArgs,
+    verify_coinbase: VerifyCoinbaseArgs,
+    version: bool,
+    policyinfo: bool,
+    json: bool,
+}
+
+/// `--mine-loop` and its pacing and template-refresh knobs.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct MineLoopArgs {
+    enabled: bool,
+    /// `--mine-interval`; `None` paces at the chain's target block interval.
+    interval_secs: Option<u64>,
+    fee_refresh_delta: u64,
+}
+
+impl MineLoopArgs {
+    /// Pacing for `params`: the explicit `--mine-interval` when given,
+    /// otherwise the target block interval, so a devnet with consensus
+    /// overrides mines at its own cadence rather than the v1 one.
+    fn interval_secs(&self, params: &ChainParams) -> u64 {
+        self.interval_secs.unwrap_or(params.target_block_interval)
+    }
+}
+
+impl Default for MineLoopArgs {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            interval_secs: None,
+            fee_refresh_delta: DEFAULT_TEMPLATE_FEE_REFRESH_DELTA,
+        }
+    }
+}
+
+/// `--verify-tx` / `--verify-tx-file` and their companions.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+struct VerifyTxArgs {
+    tx_hex: Option<String>,
+    tx_file: Option<PathBuf>,
+    prevouts: Option<PathBuf>,
+    height: Option<u64>,
+    mtp: Option<u64>,
+}
+
+impl VerifyTxArgs {
+    fn enabled(&self) -> bool {
+        self.tx_hex.is_some() || self.tx_file.is_some()
+    }
+}
+
+/// `--verify-coinbase` / `--verify-coinbase-file` and their companions.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+struct VerifyCoinbaseArgs {
+    tx_hex: Option<String>,
+    tx_file: Option<PathBuf>,
+    height: Option<u64>,
+    fees: Option<u64>,
+    already_generated: Option<u128>,
+}
+
+impl VerifyCoinbaseArgs {
+    fn enabled(&self) -> bool {
+        self.tx_hex.is_some() || self.tx_file.is_some()
+    }
 }
 
 #[derive(Serialize)]
@@ -60,6 +165,7 @@ struct EffectiveConfig {
     mine_exit: bool,
     pv_mode: String,
     pv_shadow_max: u64,
+    max_auto_reorg_depth: u64,
 }
 
 #[derive(Deserialize, Serialize)]
@@ -93,133 +199,718 @@ const LEGACY_EXPOSURE_REPORT_VERSION: u64 = 1;
 
 fn main() {
     let args: Vec<String> = env::args().skip(1).collect();
-    let exit_code = run(&args, &mut io::stdout(), &mut io::stderr());
+    let exit_code = run_cli(&args, &mut io::stdout(), &mut io::stderr());
     std::process::exit(exit_code);
 }
 
-fn load_legacy_exposure_scan_chain_state(
+/// Loads the chainstate for a read-only offline mode (`label` names the mode
+/// in diagnostics); the file must exist and carry a tip.
+fn load_offline_chain_state(
     chain_state_file: &PathBuf,
-    stderr: &mut dyn Write,
-) -> Result<rubin_node::ChainState, i32> {
+    label: &str,
+) -> Result<rubin_node::ChainState, CliError> {
     if let Err(err) = fs::metadata(chain_state_file) {
-        if err.kind() == io::ErrorKind::NotFound {
-            let _ = writeln!(
-                stderr,
-                "legacy exposure scan requires an existing chainstate file with a tip: {}",
+        let message = if err.kind() == io::ErrorKind::NotFound {
+            format!(
+                "{label} requires an existing chainstate file with a tip: {}",
                 chain_state_file.display()
-            );
+            )
         } else {
-            let _ = writeln!(
-                stderr,
-                "legacy exposure scan chainstate stat failed ({}): {err}",
+            format!(
+                "{label} chainstate stat failed ({}): {err}",
                 chain_state_file.display()
-            );
-        }
-        return Err(2);
+            )
+        };
+        return Err(CliError::store(message));
     }
-    let chain_state = match load_chain_state(chain_state_file) {
-        Ok(chain_state) => chain_state,
-        Err(err) => {
-            let _ = writeln!(
-                stderr,
-                "chainstate load failed ({}): {err}",
-                chain_state_file.display()
-            );
-            return Err(2);
-        }
-    };
+    let chain_state = load_chain_state(chain_state_file).map_err(|err| {
+        CliError::store(format!(
+            "chainstate load failed ({}): {err}",
+            chain_state_file.display()
+        ))
+    })?;
     if !chain_state.has_tip {
-        let _ = writeln!(
-            stderr,
-            "legacy exposure scan requires a chainstate with a tip: {}",
+        return Err(CliError::store(format!(
+            "{label} requires a chainstate with a tip: {}",
             chain_state_file.display()
-        );
-        return Err(2);
+        )));
     }
     Ok(chain_state)
 }
 
-fn run(args: &[String], stdout: &mut dyn Write, stderr: &mut dyn Write) -> i32 {
-    if args.iter().any(|arg| arg == "-h" || arg == "--help") {
-        usage(stdout);
-        return 0;
+/// Diffs the local chainstate against another client's `--chainstate-summary`
+/// output. Exit code is 0 on match, 3 on tip divergence, 4 on UTXO divergence.
+fn run_compare_chainstate(
+    cfg: &CliConfig,
+    other_path: &Path,
+    chain_state_file: &PathBuf,
+) -> Result<Output, CliError> {
+    let other: ChainStateSummary = fs::read(other_path)
+        .map_err(|err| err.to_string())
+        .and_then(|raw| serde_json::from_slice(&raw).map_err(|err| err.to_string()))
+        .map_err(|err| {
+            CliError::input(format!(
+                "compare chainstate: invalid other summary ({}): {err}",
+                other_path.display()
+            ))
+        })?;
+    let chain_state = load_offline_chain_state(chain_state_file, "compare chainstate")?;
+    let max_diffs = cfg
+        .compare_chainstate_max_diffs
+        .unwrap_or(DEFAULT_CHAIN_STATE_DIFF_LIMIT);
+    let report = compare_chain_state(&chain_state, &other, max_diffs);
+    let exit_code = report.status.exit_code();
+    Ok(Output::verdict("compare chainstate", report, exit_code))
+}
+
+#[derive(Serialize)]
+struct BlockStatsReport {
+    block_hash_hex: String,
+    /// Canonical height, or `None` for a stored side-chain block.
+    height: Option<u64>,
+    stats: BlockStats,
+    /// Totals over canonical heights `0..=height`; absent off the main chain.
+    cumulative: Option<BlockStats>,
+}
+
+/// Prints stored per-block stats for `hash` and, when the block is canonical,
+/// the cumulative totals up to it.
+fn run_block_stats(cfg: &CliConfig, hash: [u8; 32]) -> Result<Output, CliError> {
+    let store_path = require_block_store(&cfg.data_dir, "block stats")?;
+    let report = BlockStore::open(store_path).and_then(|store| {
+        let stats = store
+            .get_block_stats(hash)?
+            .ok_or_else(|| format!("no stats recorded for block {}", hex::encode(hash)))?;
+        let height = store.find_canonical_height(hash)?;
+        let cumulative = match height {
+            Some(height) => Some(store.cumulative_block_stats(height)?),
+            None => None,
+        };
+        Ok(BlockStatsReport {
+            block_hash_hex: hex::encode(hash),
+            height,
+            stats,
+            cumulative,
+        })
+    });
+    let report = report.map_err(|err| CliError::store(format!("block stats failed: {err}")))?;
+    Ok(Output::report("block stats", report))
+}
+
+#[derive(Serialize)]
+struct BlockIndexReport {
+    block_hash_hex: String,
+    /// Canonical height, or `None` off the main chain.
+    height: Option<u64>,
+    /// Whether the block bytes are in the store (invalid blocks are not).
+    stored: bool,
+    entry: BlockIndexEntry,
+}
+
+/// Prints everything the store records about `hash`: canonical height,
+/// status (including why it was rejected) and first receive time.
+fn run_get_block_index(cfg: &CliConfig, hash: [u8; 32]) -> Result<Output, CliError> {
+    let store_path = require_block_store(&cfg.data_dir, "get block index")?;
+    let report = BlockStore::open(store_path).and_then(|store| {
+        let stored = store.try_has_block(hash)?;
+        let entry = store.get_block_index_entry(hash)?;
+        if !stored && entry.is_none() {
+            return Err(format!("unknown block {}", hex::encode(hash)));
+        }
+        Ok(BlockIndexReport {
+            block_hash_hex: hex::encode(hash),
+            height: store.find_canonical_height(hash)?,
+            stored,
+            entry: entry.unwrap_or_default(),
+        })
+    });
+    report
+        .map(|report| Output::report("block index", report))
+        .map_err(|err| CliError::store(format!("get block index failed: {err}")))
+}
+
+/// Checks the canonical chain at `--verifychain-level` (default 3, which
+/// validates every undo record's length frame and checksum).
+fn run_verifychain(cfg: &CliConfig) -> Result<Output, CliError> {
+    let store_path = require_block_store(&cfg.data_dir, "verifychain")?;
+    let level = cfg.verifychain_level.unwrap_or(VERIFY_CHAIN_DEFAULT_LEVEL);
+    BlockStore::open(store_path)
+        .and_then(|store| verify_chain(&store, level))
+        .map(|report| Output::report("verifychain", report))
+        .map_err(|err| CliError::store(format!("verifychain failed: {err}")))
+}
+
+/// Prints a stored block at `--get-block-verbosity` (default 0), using the
+/// same renderer as the `/get_block` RPC.
+fn run_get_block(cfg: &CliConfig, hash: [u8; 32]) -> Result<Output, CliError> {
+    let store_path = require_block_store(&cfg.data_dir, "get block")?;
+    let verbosity = cfg.get_block_verbosity.unwrap_or(0);
+    BlockStore::open(store_path)
+        .and_then(|store| block_to_json(&store, hash, verbosity))
+        .map(|block| Output::report("get block", block))
+        .map_err(|err| CliError::store(format!("get block failed: {err}")))
+}
+
+/// The blockstore directory under `data_dir`, which read-only `mode`s
+/// require to exist already.
+fn require_block_store(data_dir: &Path, mode: &str) -> Result<PathBuf, CliError> {
+    let store_path = block_store_path(data_dir);
+    if !store_path.is_dir() {
+        return Err(CliError::store(format!(
+            "{mode} requires a blockstore: {}",
+            store_path.display()
+        )));
+    }
+    Ok(store_path)
+}
+
+/// One entry of the `--featurebits-deployments` file; same shape as the Go
+/// node's deployments file.
+#[derive(Deserialize)]
+struct FeatureBitDeploymentFile {
+    name: String,
+    bit: u8,
+    start_height: u64,
+    timeout_height: u64,
+    #[serde(default)]
+    activation_height: Option<u64>,
+}
+
+/// Field set of the `featurebits_state` CLI op, evaluated for the block
+/// after the canonical tip.
+#[derive(Serialize)]
+struct FeatureBitsReport {
+    name: String,
+    bit: u8,
+    height: u64,
+    state: &'static str,
+    boundary_height: u64,
+    prev_window_signal_count: u32,
+    signal_window: u64,
+    signal_threshold: u32,
+    estimated_activation_height: Option<u64>,
+    activation_height: Option<u64>,
+    consensus_active: Option<bool>,
+}
+
+fn featurebits_report(
+    store: &mut BlockStore,
+    deployment: &FeatureBitDeploymentFile,
+) -> Result<FeatureBitsReport, String> {
+    let d = FeatureBitDeployment {
+        name: deployment.name.clone(),
+        bit: deployment.bit,
+        start_height: deployment.start_height,
+        timeout_height: deployment.timeout_height,
+    };
+    let height = store.tip()?.map_or(0, |(tip_height, _)| tip_height + 1);
+    let counts = store.window_signal_counts(&d, height)?;
+    let ev = featurebit_state_at_height_from_window_counts(&d, height, &counts)?;
+    let consensus_active = match deployment.activation_height {
+        Some(activation_height) => Some(flagday_active_at_height(
+            &FlagDayDeployment {
+                name: d.name.clone(),
+                activation_height,
+                bit: Some(d.bit),
+            },
+            height,
+        )?),
+        None => None,
+    };
+    Ok(FeatureBitsReport {
+        name: d.name,
+        bit: d.bit,
+        height,
+        state: ev.state.as_str(),
+        boundary_height: ev.boundary_height,
+        prev_window_signal_count: ev.prev_window_signal_count,
+        signal_window: ev.signal_window,
+        signal_threshold: ev.signal_threshold,
+        estimated_activation_height: (ev.state == FeatureBitState::LockedIn)
+            .then_some(ev.boundary_height + ev.signal_window),
+        activation_height: deployment.activation_height,
+        consensus_active,
+    })
+}
+
+/// Prints the state of deployment `name` from the deployments file, counting
+/// version-bit signals from the stored canonical headers.
+fn run_featurebits(
+    cfg: &CliConfig,
+    name: &str,
+    deployments_path: &Path,
+) -> Result<Output, CliError> {
+    let deployments: Vec<FeatureBitDeploymentFile> = fs::read(deployments_path)
+        .map_err(|err| err.to_string())
+        .and_then(|raw| serde_json::from_slice(&raw).map_err(|err| err.to_string()))
+        .map_err(|err| {
+            CliError::input(format!(
+                "invalid featurebits deployments {}: {err}",
+                deployments_path.display()
+            ))
+        })?;
+    let Some(deployment) = deployments.iter().find(|d| d.name == name) else {
+        return Err(CliError::usage(format!(
+            "unknown featurebits deployment: {name}"
+        )));
+    };
+    let store_path = require_block_store(&cfg.data_dir, "featurebits")?;
+    BlockStore::open(store_path)
+        .and_then(|mut store| featurebits_report(&mut store, deployment))
+        .map(|report| Output::report("featurebits", report))
+        .map_err(|err| CliError::store(format!("featurebits failed: {err}")))
+}
+
+/// Verifies every input of one transaction against prevouts read from a
+/// JSON file. Exit 4 when the transaction is invalid; the report still
+/// carries the per-input results.
+fn run_verify_tx(cfg: &CliConfig) -> Result<Output, CliError> {
+    let args = &cfg.verify_tx;
+    let tx_bytes = read_tx_hex_arg(args.tx_hex.as_ref(), args.tx_file.as_ref(), "--verify-tx")?;
+    let (Some(prevouts_path), Some(height)) = (args.prevouts.as_ref(), args.height) else {
+        return Err(CliError::usage(
+            "--verify-tx requires --verify-prevouts and --verify-height",
+        ));
+    };
+    let prevouts = fs::read_to_string(prevouts_path)
+        .map_err(|err| err.to_string())
+        .and_then(|raw| parse_tx_verify_prevouts(&raw))
+        .map_err(|err| {
+            CliError::input(format!(
+                "invalid prevouts {}: {err}",
+                prevouts_path.display()
+            ))
+        })?;
+    let chain_id =
+        load_chain_id_from_genesis_file(cfg.genesis_file.as_deref()).map_err(CliError::input)?;
+    let report = verify_tx(
+        &tx_bytes,
+        &prevouts,
+        chain_id,
+        height,
+        args.mtp.unwrap_or(0),
+    )
+    .map_err(|err| CliError::op(ErrorClass::Input, "verify tx failed", err))?;
+    if report.valid {
+        return Ok(Output::report("verify tx", report));
+    }
+    let error_code = report.error_code.clone();
+    Ok(Output::rejected("verify tx", report, error_code))
+}
+
+fn run_verify_coinbase(cfg: &CliConfig) -> Result<Output, CliError> {
+    let args = &cfg.verify_coinbase;
+    let tx_bytes = read_tx_hex_arg(
+        args.tx_hex.as_ref(),
+        args.tx_file.as_ref(),
+        "--verify-coinbase",
+    )?;
+    let Some(height) = args.height else {
+        return Err(CliError::usage(
+            "--verify-coinbase requires --coinbase-height",
+        ));
+    };
+    let report = verify_coinbase_tx(
+        &tx_bytes,
+        height,
+        args.fees.unwrap_or(0),
+        args.already_generated,
+    )
+    .map_err(|err| CliError::input(format!("verify coinbase failed: {err}")))?;
+    if report.valid {
+        return Ok(Output::report("verify coinbase", report));
+    }
+    let error_code = report.error_code.clone();
+    Ok(Output::rejected("verify coinbase", report, error_code))
+}
+
+/// Transaction bytes from an inline hex flag or a file holding the hex.
+fn read_tx_hex_arg(
+    tx_hex: Option<&String>,
+    tx_file: Option<&PathBuf>,
+    flag: &str,
+) -> Result<Vec<u8>, CliError> {
+    let tx_hex = match (tx_hex, tx_file) {
+        (Some(tx_hex), _) => tx_hex.clone(),
+        (None, Some(path)) => fs::read_to_string(path)
+            .map_err(|err| CliError::input(format!("read tx file {}: {err}", path.display())))?,
+        (None, None) => return Err(CliError::usage(format!("{flag} is required"))),
+    };
+    let tx_hex: String = tx_hex.split_whitespace().collect();
+    hex::decode(&tx_hex).map_err(|_| CliError::input("invalid transaction hex"))
+}
+
+#[derive(Serialize)]
+struct ForceReorgReport {
+    tip_hash: String,
+    tip_height: u64,
+    common_ancestor_height: u64,
+    fork_depth: u64,
+}
+
+/// Operator confirmation for a reorg held back by `--max-auto-reorg-depth`.
+/// Fork choice is re-checked against the current tip before switching.
+fn run_force_reorg(engine: &mut SyncEngine, hash: [u8; 32]) -> Result<Output, CliError> {
+    let preview = engine
+        .preview_reorg(hash)
+        .and_then(|preview| {
+            engine.force_reorg_to(hash)?;
+            Ok(preview)
+        })
+        .map_err(|err| CliError::store(format!("force reorg failed: {err}")))?;
+    let report = ForceReorgReport {
+        tip_hash: hex::encode(hash),
+        tip_height: preview.candidate_height,
+        common_ancestor_height: preview.common_ancestor_height,
+        fork_depth: preview.fork_depth,
+    };
+    Ok(Output::report("force reorg", report))
+}
+
+/// Parses the flags of the `devnet` subcommand (everything after `devnet`).
+fn parse_devnet_args(args: &[String]) -> Result<DevnetHarnessConfig, String> {
+    let mut blocks = None;
+    let mut data_dir = None;
+    let mut seed = DEFAULT_DEVNET_HARNESS_SEED;
+    let mut idx = 0;
+    while idx < args.len() {
+        match args[idx].as_str() {
+            "--blocks" => {
+                idx += 1;
+                let value = args
+                    .get(idx)
+                    .ok_or_else(|| "missing value for --blocks".to_string())?;
+                blocks = Some(
+                    value
+                        .parse::<u64>()
+                        .map_err(|_| "invalid value for --blocks".to_string())?,
+                );
+            }
+            "--datadir" => {
+                idx += 1;
+                let value = args
+                    .get(idx)
+                    .ok_or_else(|| "missing value for --datadir".to_string())?;
+                data_dir = Some(PathBuf::from(value));
+            }
+            "--seed" => {
+                idx += 1;
+                let value = args
+                    .get(idx)
+                    .ok_or_else(|| "missing value for --seed".to_string())?;
+                seed = value
+                    .parse::<u64>()
+                    .map_err(|_| "invalid value for --seed".to_string())?;
+            }
+            "--json" => {}
+            other => return Err(format!("unknown devnet flag: {other}")),
+        }
+        idx += 1;
     }
+    Ok(DevnetHarnessConfig {
+        blocks: blocks.ok_or_else(|| "devnet requires --blocks <n>".to_string())?,
+        data_dir: data_dir.ok_or_else(|| "devnet requires --datadir <path>".to_string())?,
+        seed,
+    })
+}
+
+/// `rubin-node devnet`: mines a seeded devnet chain and reports its tip.
+fn run_devnet(args: &[String]) -> Result<Output, CliError> {
+    let cfg = parse_devnet_args(args).map_err(CliError::usage)?;
+    run_devnet_harness(&cfg)
+        .map(|report| Output::report("devnet", report))
+        .map_err(|err| CliError::op(ErrorClass::Runtime, "devnet failed", err))
+}
 
-    let mut cfg = match parse_args(args) {
-        Ok(cfg) => cfg,
-        Err(err) => {
-            let _ = writeln!(stderr, "{err}");
-            return 2;
+/// Flags of the `exportblocks` / `importblocks` subcommands.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct BlockFilesArgs {
+    network: String,
+    data_dir: PathBuf,
+    genesis_file: Option<PathBuf>,
+    /// `--to` for export, `--from` for import.
+    dir: PathBuf,
+    blocks_per_file: u64,
+    max_buffered: usize,
+}
+
+fn parse_block_files_args(command: &str, args: &[String]) -> Result<BlockFilesArgs, String> {
+    let dir_flag = if command == "exportblocks" {
+        "--to"
+    } else {
+        "--from"
+    };
+    let mut cfg = BlockFilesArgs {
+        network: "devnet".to_string(),
+        data_dir: default_data_dir(),
+        genesis_file: None,
+        dir: PathBuf::new(),
+        blocks_per_file: DEFAULT_EXPORT_BLOCKS_PER_FILE,
+        max_buffered: DEFAULT_IMPORT_MAX_BUFFERED_BLOCKS,
+    };
+    let mut dir = None;
+    let mut idx = 0;
+    while idx < args.len() {
+        let flag = args[idx].as_str();
+        if flag == "--json" {
+            idx += 1;
+            continue;
+        }
+        idx += 1;
+        let value = args
+            .get(idx)
+            .ok_or_else(|| format!("missing value for {flag}"))?;
+        match flag {
+            "--network" => cfg.network = value.clone(),
+            "--datadir" => cfg.data_dir = PathBuf::from(value),
+            "--genesis-file" => cfg.genesis_file = Some(PathBuf::from(value)),
+            "--blocks-per-file" if command == "exportblocks" => {
+                cfg.blocks_per_file = value
+                    .parse::<u64>()
+                    .ok()
+                    .filter(|n| *n > 0)
+                    .ok_or_else(|| "invalid value for --blocks-per-file".to_string())?;
+            }
+            "--max-buffered" if command == "importblocks" => {
+                cfg.max_buffered = value
+                    .parse::<usize>()
+                    .map_err(|_| "invalid value for --max-buffered".to_string())?;
+            }
+            _ if flag == dir_flag => dir = Some(PathBuf::from(value)),
+            other => return Err(format!("unknown {command} flag: {other}")),
         }
+        idx += 1;
+    }
+    cfg.dir = dir.ok_or_else(|| format!("{command} requires {dir_flag} <dir>"))?;
+    Ok(cfg)
+}
+
+/// `rubin-node exportblocks` / `importblocks`: moves the chain of a datadir
+/// to or from raw block files (format in `rubin_node::block_files`).
+fn run_block_files(command: &'static str, args: &[String]) -> Result<Output, CliError> {
+    let cfg = parse_block_files_args(command, args).map_err(CliError::usage)?;
+    let genesis_cfg = load_genesis_config(cfg.genesis_file.as_deref(), &cfg.network)
+        .map_err(|err| CliError::input(format!("invalid genesis file: {err}")))?;
+    // Held until the command returns.
+    let datadir = Datadir::open(&cfg.data_dir, genesis_cfg.chain_id).map_err(CliError::store)?;
+    let chain_state_file = datadir.chain_state_path();
+    let opened = load_chain_state(&chain_state_file)
+        .map_err(|err| {
+            format!(
+                "chainstate load failed ({}): {err}",
+                chain_state_file.display()
+            )
+        })
+        .and_then(|chain_state| {
+            let block_store = BlockStore::open(datadir.block_store_path())
+                .map_err(|err| format!("blockstore open failed: {err}"))?;
+            let mut sync_cfg =
+                default_sync_config(None, genesis_cfg.chain_id, Some(chain_state_file.clone()));
+            sync_cfg.network = cfg.network.clone();
+            sync_cfg.suite_context = genesis_cfg.suite_context.clone();
+            sync_cfg.minimum_chainwork = genesis_cfg.minimum_chainwork.clone();
+            sync_cfg.chain_params = genesis_cfg.chain_params;
+            SyncEngine::new(chain_state, Some(block_store), sync_cfg)
+        });
+    let mut engine = opened.map_err(CliError::store)?;
+    let result = if command == "exportblocks" {
+        export_block_files(&engine, &cfg.dir, cfg.blocks_per_file).map_err(OpError::from)
+    } else {
+        import_block_files(&mut engine, &cfg.dir, cfg.max_buffered)
     };
-    if let Err(err) = validate_config(&mut cfg) {
-        let _ = writeln!(stderr, "{err}");
-        return 2;
+    result
+        .map(|report| Output::report(command, report))
+        .map_err(|err| CliError::op(ErrorClass::Store, &format!("{command} failed"), err))
+}
+
+fn parse_chain_tips_args(args: &[String]) -> Result<PathBuf, String> {
+    let mut data_dir = default_data_dir();
+    let mut idx = 0;
+    while idx < args.len() {
+        let flag = args[idx].as_str();
+        idx += 1;
+        match flag {
+            "--json" => continue,
+            "--datadir" => {
+                let value = args
+                    .get(idx)
+                    .ok_or_else(|| format!("missing value for {flag}"))?;
+                data_dir = PathBuf::from(value);
+                idx += 1;
+            }
+            other => return Err(format!("unknown getchaintips flag: {other}")),
+        }
+    }
+    Ok(data_dir)
+}
+
+/// `rubin-node getchaintips`: every branch tip in the datadir's blockstore,
+/// with the same fields as the `/get_chain_tips` RPC.
+fn run_get_chain_tips(args: &[String]) -> Result<Output, CliError> {
+    let data_dir = parse_chain_tips_args(args).map_err(CliError::usage)?;
+    let store_path = require_block_store(&data_dir, "getchaintips")?;
+    BlockStore::open(store_path)
+        .and_then(|store| chain_tips_to_json(&store))
+        .map(|tips| Output::report("chain tips", tips))
+        .map_err(|err| CliError::store(format!("getchaintips failed: {err}")))
+}
+
+fn parse_tx_status_args(args: &[String]) -> Result<(PathBuf, [u8; 32]), String> {
+    let mut data_dir = default_data_dir();
+    let mut txid = None;
+    let mut idx = 0;
+    while idx < args.len() {
+        let flag = args[idx].as_str();
+        idx += 1;
+        if flag == "--json" {
+            continue;
+        }
+        let value = args
+            .get(idx)
+            .ok_or_else(|| format!("missing value for {flag}"))?;
+        match flag {
+            "--datadir" => data_dir = PathBuf::from(value),
+            "--txid" => txid = Some(hex_decode_32(value, "txid").map_err(|e| e.to_string())?),
+            other => return Err(format!("unknown gettxstatus flag: {other}")),
+        }
+        idx += 1;
     }
+    let txid = txid.ok_or_else(|| "gettxstatus requires --txid <hex>".to_string())?;
+    Ok((data_dir, txid))
+}
+
+/// `rubin-node gettxstatus`: the broadcast state of a locally submitted
+/// transaction, as the node last saved it; same fields as `/broadcast_status`.
+fn run_get_tx_status(args: &[String]) -> Result<Output, CliError> {
+    let (data_dir, txid) = parse_tx_status_args(args).map_err(CliError::usage)?;
+    let mgr = load_broadcast_manager(broadcast_state_path(&data_dir), BroadcastConfig::default())
+        .map_err(|err| CliError::store(format!("gettxstatus failed: {err}")))?;
+    mgr.status(&txid)
+        .map(|status| Output::report("tx status", status))
+        .ok_or_else(|| {
+            CliError::input(format!(
+                "gettxstatus: transaction not tracked: {}",
+                hex::encode(txid)
+            ))
+        })
+}
+
+/// Entry point behind `main`: runs one mode and prints its outcome.
+fn run_cli(args: &[String], stdout: &mut dyn Write, stderr: &mut dyn Write) -> i32 {
+    // Scanned before parsing so argument errors honour `--json` too.
+    let json = args.iter().any(|arg| arg == "--json");
+    let mut out = CliOutput::new(json, stdout, stderr);
+    let outcome = run(args, &mut out);
+    out.finish(outcome)
+}
 
+/// Dispatches to the mode selected by `args`. Every failure leaves through
+/// a `CliError`, whose class sets the exit code (see `cli_error`).
+fn run(args: &[String], out: &mut CliOutput<'_>) -> Result<Output, CliError> {
+    if args.iter().any(|arg| arg == "-h" || arg == "--help") {
+        usage(out.streams().0);
+        return Ok(Output::Done);
+    }
+    match args.first().map(String::as_str) {
+        Some("devnet") => return run_devnet(&args[1..]),
+        Some("exportblocks") => return run_block_files("exportblocks", &args[1..]),
+        Some("importblocks") => return run_block_files("importblocks", &args[1..]),
+        Some("getchaintips") => return run_get_chain_tips(&args[1..]),
+        Some("gettxstatus") => return run_get_tx_status(&args[1..]),
+        _ => {}
+    }
+    let mut cfg = parse_args(args).map_err(CliError::usage)?;
+    validate_config(&mut cfg).map_err(CliError::usage)?;
+
+    if cfg.version {
+        let chain_id = load_chain_id_from_genesis_file(cfg.genesis_file.as_deref())
+            .map_err(CliError::input)?;
+        return Ok(Output::report(
+            "version",
+            BuildInfo::new(&cfg.network, chain_id),
+        ));
+    }
+    if cfg.policyinfo {
+        return Ok(Output::report("policy info", PolicyInfo::defaults()));
+    }
     let chain_state_file = chain_state_path(&cfg.data_dir);
     if cfg.legacy_exposure_scan {
-        let chain_state = match load_legacy_exposure_scan_chain_state(&chain_state_file, stderr) {
-            Ok(chain_state) => chain_state,
-            Err(code) => return code,
-        };
+        let chain_state = load_offline_chain_state(&chain_state_file, "legacy exposure scan")?;
         let report = build_legacy_exposure_report(&cfg, &chain_state);
-        if let Err(err) = serde_json::to_writer_pretty(&mut *stdout, &report) {
-            let _ = writeln!(stderr, "legacy exposure encode failed: {err}");
-            return 1;
-        }
-        let _ = writeln!(stdout);
-        return 0;
+        return Ok(Output::report("legacy exposure", report));
+    }
+    if cfg.chainstate_summary {
+        let chain_state = load_offline_chain_state(&chain_state_file, "chainstate summary")?;
+        let summary =
+            ChainStateSummary::from_chain_state(&chain_state, cfg.chainstate_summary_utxos);
+        return Ok(Output::report("chainstate summary", summary));
+    }
+    if let Some(other_path) = cfg.compare_chainstate.as_ref() {
+        return run_compare_chainstate(&cfg, other_path, &chain_state_file);
+    }
+    if let Some(hash) = cfg.block_stats {
+        return run_block_stats(&cfg, hash);
+    }
+    if let Some(hash) = cfg.get_block {
+        return run_get_block(&cfg, hash);
+    }
+    if let Some(hash) = cfg.get_block_index {
+        return run_get_block_index(&cfg, hash);
+    }
+    if cfg.verifychain {
+        return run_verifychain(&cfg);
     }
+    if let (Some(name), Some(path)) = (
+        cfg.featurebits.as_ref(),
+        cfg.featurebits_deployments.as_ref(),
+    ) {
+        return run_featurebits(&cfg, name, path);
+    }
+    if cfg.verify_tx.enabled() {
+        return run_verify_tx(&cfg);
+    }
+    if cfg.verify_coinbase.enabled() {
+        return run_verify_coinbase(&cfg);
+    }
+    run_node(&cfg, out)
+}
+
+/// The long-running node, plus the modes that need its opened and
+/// reconciled datadir (`--force-reorg`, `--dry-run`, `--mine-blocks`).
+fn run_node(cfg: &CliConfig, out: &mut CliOutput<'_>) -> Result<Output, CliError> {
     if cfg.network != "devnet" && cfg.genesis_file.is_none() {
-        let _ = writeln!(
-            stderr,
+        return Err(CliError::usage(format!(
             "error: --network {} requires a genesis file (--genesis-file) with chain_id and genesis_hash",
             cfg.network
-        );
-        return 2;
-    }
-    let genesis_cfg = match load_genesis_config(cfg.genesis_file.as_deref(), cfg.network.as_str()) {
-        Ok(cfg) => cfg,
-        Err(err) => {
-            let _ = writeln!(stderr, "invalid genesis file: {err}");
-            return 2;
-        }
-    };
-    if let Err(err) = fs::create_dir_all(&cfg.data_dir) {
-        let _ = writeln!(
-            stderr,
-            "datadir create failed ({}): {err}",
-            cfg.data_dir.display()
-        );
-        return 2;
-    }
-    let mut chain_state = match load_chain_state(&chain_state_file) {
-        Ok(chain_state) => chain_state,
-        Err(err) => {
-            let _ = writeln!(
-                stderr,
+        )));
+    }
+    let genesis_cfg = load_genesis_config(cfg.genesis_file.as_deref(), cfg.network.as_str())
+        .map_err(|err| CliError::input(format!("invalid genesis file: {err}")))?;
+    // Held until `run_node` returns; everything below may write the datadir.
+    let datadir = Datadir::open(&cfg.data_dir, genesis_cfg.chain_id).map_err(CliError::store)?;
+    let chain_state_file = datadir.chain_state_path();
+    let mut block_store = BlockStore::open(datadir.block_store_path())
+        .map_err(|err| CliError::store(format!("blockstore open failed: {err}")))?;
+    let anchors = block_store
+        .chain_anchors()
+        .map_err(|err| CliError::store(format!("blockstore anchors: {err}")))?;
+    let mut chain_state = load_chain_state_with_anchors(&chain_state_file, anchors.as_ref())
+        .map_err(|err| {
+            CliError::store(format!(
                 "chainstate load failed ({}): {err}",
                 chain_state_file.display()
-            );
-            return 2;
-        }
-    };
+            ))
+        })?;
     let chain_id = genesis_cfg.chain_id;
 
-    let mut block_store = match BlockStore::open(block_store_path(&cfg.data_dir)) {
-        Ok(block_store) => block_store,
-        Err(err) => {
-            let _ = writeln!(stderr, "blockstore open failed: {err}");
-            return 2;
-        }
-    };
-
     let mut sync_cfg = default_sync_config(None, chain_id, Some(chain_state_file.clone()));
     sync_cfg.network = cfg.network.clone();
     sync_cfg.suite_context = genesis_cfg.suite_context.clone();
+    sync_cfg.minimum_chainwork = genesis_cfg.minimum_chainwork.clone();
+    sync_cfg.chain_params = genesis_cfg.chain_params;
     sync_cfg.parallel_validation_mode = cfg.pv_mode.clone();
     sync_cfg.pv_shadow_max_samples = cfg.pv_shadow_max;
+    sync_cfg.max_auto_reorg_depth = cfg.max_auto_reorg_depth;
 
     // Mainnet target / genesis guard runs BEFORE reconcile so a
     // misconfigured `--network mainnet` startup is rejected before
@@ -232,10 +923,8 @@ fn run(args: &[String], stdout: &mut dyn Write, stderr: &mut dyn Write) -> i32 {
     // callers that construct `SyncEngine` directly (tests, embedded uses)
     // it is the ONLY guard. Do not remove the inner call as a perceived
     // duplicate. Devnet / test networks no-op.
-    if let Err(err) = validate_mainnet_genesis_guard(&sync_cfg) {
-        let _ = writeln!(stderr, "mainnet genesis guard failed: {err}");
-        return 2;
-    }
+    validate_mainnet_genesis_guard(&sync_cfg)
+        .map_err(|err| CliError::input(format!("mainnet genesis guard failed: {err}")))?;
 
     // Startup reconcile (E.2): repair any chainstate ↔ blockstore
     // mismatch left by a crash (incomplete canonical suffix, stale
@@ -245,20 +934,14 @@ fn run(args: &[String], stdout: &mut dyn Write, stderr: &mut dyn Write) -> i32 {
     // A reconcile error is fatal: continuing would let the engine run
     // with a chainstate tip that no longer points at any canonical
     // block on disk.
-    if let Err(err) =
-        reconcile_chain_state_with_block_store(&mut chain_state, &mut block_store, &sync_cfg)
-    {
-        let _ = writeln!(stderr, "chainstate reconcile failed: {err}");
-        return 2;
-    }
-    if let Err(err) = chain_state.save(&chain_state_file) {
-        let _ = writeln!(
-            stderr,
+    reconcile_chain_state_with_block_store(&mut chain_state, &mut block_store, &sync_cfg)
+        .map_err(|err| CliError::store(format!("chainstate reconcile failed: {err}")))?;
+    chain_state.save(&chain_state_file).map_err(|err| {
+        CliError::store(format!(
             "chainstate save failed ({}): {err}",
             chain_state_file.display()
-        );
-        return 2;
-    }
+        ))
+    })?;
 
     // NOTE: `block_store.clone()` here mirrors the pre-existing
     // pattern in `main.rs` (the RPC handoff at `Some(block_store)`
@@ -272,16 +955,15 @@ fn run(args: &[String], stdout: &mut dyn Write, stderr: &mut dyn Write) -> i32 {
     // BLOCKSTORE-SHARING follow-up for the proper Arc<BlockStore>
     // fix that touches both `SyncEngine::new` and
     // `new_devnet_rpc_state_with_tx_pool` signatures.
-    let mut sync_engine = match SyncEngine::new(chain_state, Some(block_store.clone()), sync_cfg) {
-        Ok(engine) => engine,
-        Err(err) => {
-            let _ = writeln!(stderr, "sync engine init failed: {err}");
-            return 2;
-        }
-    };
+    let mut sync_engine = SyncEngine::new(chain_state, Some(block_store.clone()), sync_cfg)
+        .map_err(|err| CliError::store(format!("sync engine init failed: {err}")))?;
     if let Ok(Some((height, _))) = sync_engine.tip() {
         sync_engine.record_best_known_height(height);
     }
+    if let Some(hash) = cfg.force_reorg {
+        return run_force_reorg(&mut sync_engine, hash);
+    }
+    let (stdout, stderr) = out.streams();
 
     let effective = EffectiveConfig {
         network: cfg.network.clone(),
@@ -304,10 +986,10 @@ fn run(args: &[String], stdout: &mut dyn Write, stderr: &mut dyn Write) -> i32 {
         mine_exit: cfg.mine_exit,
         pv_mode: cfg.pv_mode.clone(),
         pv_shadow_max: cfg.pv_shadow_max,
+        max_auto_reorg_depth: cfg.max_auto_reorg_depth,
     };
     if serde_json::to_writer_pretty(&mut *stdout, &effective).is_err() {
-        let _ = writeln!(stderr, "config encode failed");
-        return 1;
+        return Err(CliError::runtime("config encode failed"));
     }
     let _ = writeln!(stdout);
 
@@ -345,37 +1027,25 @@ fn run(args: &[String], stdout: &mut dyn Write, stderr: &mut dyn Write) -> i32 {
         "{}",
         format_peer_slots_banner(cfg.max_peers, peer_manager.snapshot().len())
     );
+    if let Some(banner) = format_consensus_overrides_banner(&genesis_cfg.chain_params) {
+        let _ = writeln!(stderr, "{banner}");
+    }
 
     if cfg.dry_run {
-        return 0;
+        return Ok(Output::Done);
     }
     if cfg.mine_blocks > 0 {
         let mut miner_cfg = MinerConfig::default();
         if let Some(ref value) = cfg.mine_address {
-            let parsed = match parse_mine_address_arg(value) {
-                Ok(Some(addr)) => addr,
-                Ok(None) => Vec::new(),
-                Err(err) => {
-                    let _ = writeln!(stderr, "invalid mine-address: {err}");
-                    return 2;
-                }
-            };
-            miner_cfg.mine_address = parsed;
+            let parsed = parse_mine_address_arg(value)
+                .map_err(|err| CliError::usage(format!("invalid mine-address: {err}")))?;
+            miner_cfg.mine_address = parsed.unwrap_or_default();
         }
-        let mut miner = match Miner::new(&mut sync_engine, None, miner_cfg) {
-            Ok(miner) => miner,
-            Err(err) => {
-                let _ = writeln!(stderr, "miner init failed: {err}");
-                return 2;
-            }
-        };
-        let mined = match miner.mine_n(cfg.mine_blocks, &[]) {
-            Ok(mined) => mined,
-            Err(err) => {
-                let _ = writeln!(stderr, "mining failed: {err}");
-                return 2;
-            }
-        };
+        let mut miner = Miner::new(&mut sync_engine, None, miner_cfg)
+            .map_err(|err| CliError::store(format!("miner init failed: {err}")))?;
+        let mined = miner
+            .mine_n(cfg.mine_blocks, &[])
+            .map_err(|err| CliError::store(format!("mining failed: {err}")))?;
         for block in mined {
             let _ = writeln!(
                 stdout,
@@ -388,11 +1058,11 @@ fn run(args: &[String], stdout: &mut dyn Write, stderr: &mut dyn Write) -> i32 {
             );
         }
         if cfg.mine_exit {
-            return 0;
+            return Ok(Output::Done);
         }
     }
 
-    let live_mining_cfg = if live_devnet_loopback_mining_allowed(&cfg) {
+    let live_mining_cfg = if live_devnet_loopback_mining_allowed(cfg) {
         let mut miner_cfg = MinerConfig::default();
         let mut addr_invalid = false;
         if let Some(ref value) = cfg.mine_address {
@@ -423,22 +1093,15 @@ fn run(args: &[String], stdout: &mut dyn Write, stderr: &mut dyn Write) -> i32 {
         None
     };
 
-    let genesis_hash = match runtime_genesis_hash(&genesis_cfg) {
-        Ok(hash) => hash,
-        Err(err) => {
-            let _ = writeln!(stderr, "{err}");
-            return 2;
-        }
-    };
+    let genesis_hash = runtime_genesis_hash(&genesis_cfg).map_err(CliError::input)?;
     let sync_engine = Arc::new(Mutex::new(sync_engine));
     let tx_pool = new_shared_runtime_tx_pool(&sync_engine);
-    let stop_signal = match install_production_stop_signal() {
-        Ok(stop_signal) => stop_signal,
-        Err(err) => {
-            let _ = writeln!(stderr, "signal handler install failed: {err}");
-            return 2;
-        }
-    };
+    let broadcast_path = broadcast_state_path(&cfg.data_dir);
+    let broadcast = load_broadcast_manager(&broadcast_path, cfg.broadcast)
+        .map_err(|err| CliError::store(format!("broadcast state load failed: {err}")))?;
+    let broadcast = Arc::new(Mutex::new(broadcast));
+    let stop_signal = install_production_stop_signal()
+        .map_err(|err| CliError::runtime(format!("signal handler install failed: {err}")))?;
     // peer_runtime_cfg / peer_manager were constructed earlier (above
     // the dry-run early-exit) so the RUB-13 peer-slots banner could
     // render in the dry-run path matching the upstream sequencing at
@@ -447,7 +1110,7 @@ fn run(args: &[String], stdout: &mut dyn Write, stderr: &mut dyn Write) -> i32 {
     // banner below stays because the post-bind address is operator-
     // useful and the upstream client cannot emit it symmetrically
     // (its bind happens inside `p2pService.Start(ctx)`).
-    let mut p2p_service = match start_node_p2p_service(NodeP2PServiceConfig {
+    let mut p2p_service = start_node_p2p_service(NodeP2PServiceConfig {
         bind_addr: cfg.bind_addr.clone(),
         bootstrap_peers: cfg.peers.clone(),
         runtime_cfg: peer_runtime_cfg,
@@ -456,19 +1119,14 @@ fn run(args: &[String], stdout: &mut dyn Write, stderr: &mut dyn Write) -> i32 {
         tx_pool: Arc::clone(&tx_pool),
         chain_id,
         genesis_hash,
-    }) {
-        Ok(service) => service,
-        Err(err) => {
-            let _ = writeln!(stderr, "p2p start failed: {err}");
-            return 2;
-        }
-    };
+    })
+    .map_err(|err| CliError::runtime(format!("p2p start failed: {err}")))?;
     let _ = writeln!(stdout, "p2p: listening={}", p2p_service.addr());
     let mut server: Option<RunningDevnetRPCServer> = None;
     if let Some(code) =
         maybe_shutdown_if_requested(&stop_signal, &mut server, &mut p2p_service, stdout, stderr)
     {
-        return code;
+        return lifecycle_outcome(code);
     }
 
     let announce_tx: Option<rubin_node::devnet_rpc::AnnounceTxFn> = {
@@ -489,6 +1147,24 @@ fn run(args: &[String], stdout: &mut dyn Write, stderr: &mut dyn Write) -> i32 {
             )
         }))
     };
+    let rebroadcast: RebroadcastFn = {
+        let relay_state = p2p_service.relay_state();
+        let pm = Arc::clone(&peer_manager);
+        let pw = p2p_service.peer_outboxes();
+        let local = p2p_service.addr().to_string();
+        Box::new(move |txid, tx_bytes: &[u8], fee, only_addrs| {
+            rubin_node::tx_relay::rebroadcast_tx(
+                txid,
+                tx_bytes,
+                fee,
+                only_addrs,
+                &relay_state,
+                &pm,
+                &local,
+                &pw,
+            )
+        })
+    };
     let da_ttl_relay = p2p_service.da_relay_state();
     let da_consume_relay = p2p_service.da_relay_state();
     let da_ttl_seen = Arc::new(rubin_node::tx_seen::BoundedHashSet::new(
@@ -524,20 +1200,22 @@ fn run(args: &[String], stdout: &mut dyn Write, stderr: &mut dyn Write) -> i32 {
     state.set_accepted_block_hook(Arc::new(move |hash| {
         advance_da_ttl_for_block(hash, &da_ttl_relay, &da_ttl_seen)
     }));
+    state.set_broadcast_manager(Arc::clone(&broadcast));
     let state =
         attach_shutdown_signal_to_devnet_rpc_state(state, stop_signal.shutdown_requested_flag());
+    let mining_loop_state = cfg.mine_loop.enabled.then(|| state.clone());
     if !cfg.rpc_bind_addr.trim().is_empty() {
         server = match start_devnet_rpc_server(&cfg.rpc_bind_addr, state) {
             Ok(server) => Some(server),
             Err(err) => {
-                return handle_rpc_start_error_after_maybe_stop(
+                return lifecycle_outcome(handle_rpc_start_error_after_maybe_stop(
                     &stop_signal,
                     &mut server,
                     &mut p2p_service,
                     err,
                     stdout,
                     stderr,
-                );
+                ));
             }
         };
     }
@@ -547,12 +1225,220 @@ fn run(args: &[String], stdout: &mut dyn Write, stderr: &mut dyn Write) -> i32 {
     if let Some(code) =
         maybe_shutdown_if_requested(&stop_signal, &mut server, &mut p2p_service, stdout, stderr)
     {
-        return code;
+        return lifecycle_outcome(code);
     }
+    let mining_loop = mining_loop_state.map(|state| {
+        let interval_secs = cfg.mine_loop.interval_secs(&genesis_cfg.chain_params);
+        let _ = writeln!(
+            stdout,
+            "mining: loop started interval={}s fee_delta={}",
+            interval_secs, cfg.mine_loop.fee_refresh_delta
+        );
+        spawn_mining_loop(
+            cfg,
+            interval_secs,
+            Arc::clone(&sync_engine),
+            Arc::clone(&tx_pool),
+            state,
+            stop_signal.shutdown_requested_flag(),
+        )
+    });
+    let broadcast_loop = spawn_broadcast_loop(
+        broadcast,
+        Arc::clone(&sync_engine),
+        Arc::clone(&tx_pool),
+        Arc::clone(&peer_manager),
+        rebroadcast,
+        broadcast_path,
+        stop_signal.shutdown_requested_flag(),
+    );
     let _ = writeln!(stdout, "rubin-node skeleton running");
     let _ = stdout.flush();
 
-    wait_for_stop_and_shutdown(&stop_signal, &mut server, &mut p2p_service, stdout, stderr)
+    let code =
+        wait_for_stop_and_shutdown(&stop_signal, &mut server, &mut p2p_service, stdout, stderr);
+    if let Some(handle) = mining_loop {
+        match handle.join() {
+            Ok(Ok(report)) => {
+                let _ = writeln!(
+                    stdout,
+                    "mining: loop stopped blocks={} templates={}",
+                    report.blocks.len(),
+                    report.templates
+                );
+            }
+            Ok(Err(err)) => {
+                let _ = writeln!(stderr, "mining: loop failed: {err}");
+            }
+            Err(_) => {
+                let _ = writeln!(stderr, "mining: loop panicked");
+            }
+        }
+    }
+    if broadcast_loop.join().is_err() {
+        let _ = writeln!(stderr, "broadcast: loop panicked");
+    }
+    lifecycle_outcome(code)
+}
+
+/// Maps a node lifecycle exit code, whose diagnostics are already on
+/// stderr, onto the CLI contract.
+fn lifecycle_outcome(code: i32) -> Result<Output, CliError> {
+    if code == 0 {
+        Ok(Output::Done)
+    } else {
+        Err(CliError::reported(ErrorClass::Runtime))
+    }
+}
+
+/// Runs `--mine-loop` on its own thread until `stop` is set. Solved blocks
+/// go through the RPC state's `submit_block`, the same path `/submit_block`
+/// serves to external miners, so they are validated and announced like
+/// any other block.
+fn spawn_mining_loop(
+    cfg: &CliConfig,
+    interval_secs: u64,
+    sync_engine: Arc<Mutex<SyncEngine>>,
+    tx_pool: Arc<Mutex<TxPool>>,
+    state: DevnetRPCState,
+    stop: Arc<AtomicBool>,
+) -> std::thread::JoinHandle<Result<MiningLoopReport, String>> {
+    let mut miner_cfg = MinerConfig::default();
+    if let Some(Ok(Some(addr))) = cfg.mine_address.as_deref().map(parse_mine_address_arg) {
+        miner_cfg.mine_address = addr;
+    }
+    let loop_cfg = MiningLoopConfig {
+        miner: miner_cfg,
+        interval_secs,
+        fee_refresh_delta: cfg.mine_loop.fee_refresh_delta,
+        ..MiningLoopConfig::default()
+    };
+    std::thread::spawn(move || {
+        run_mining_loop(
+            &sync_engine,
+            &tx_pool,
+            &loop_cfg,
+            &SystemMiningClock,
+            &stop,
+            &|block_bytes: &[u8]| state.submit_block(block_bytes),
+        )
+    })
+}
+
+type RebroadcastFn =
+    Box<dyn Fn([u8; 32], &[u8], u64, Option<&[String]>) -> Result<(), String> + Send>;
+
+/// How often the broadcast loop reconciles and rebroadcasts; how often a
+/// single transaction is rebroadcast is `--rebroadcast-interval`.
+const BROADCAST_TICK: Duration = Duration::from_secs(5);
+
+/// Keeps locally submitted transactions moving until they confirm or
+/// expire (see `rubin_node::broadcast`), saving the manager's state for
+/// `gettxstatus` whenever it changes.
+fn spawn_broadcast_loop(
+    broadcast: Arc<Mutex<BroadcastManager>>,
+    sync_engine: Arc<Mutex<SyncEngine>>,
+    tx_pool: Arc<Mutex<TxPool>>,
+    peer_manager: Arc<PeerManager>,
+    rebroadcast: RebroadcastFn,
+    path: PathBuf,
+    stop: Arc<AtomicBool>,
+) -> std::thread::JoinHandle<()> {
+    std::thread::spawn(move || {
+        let mut saved = Vec::new();
+        let mut next_tick = Instant::now();
+        while !stop.load(Ordering::SeqCst) {
+            if Instant::now() >= next_tick {
+                next_tick = Instant::now() + BROADCAST_TICK;
+                let ticked = broadcast_tick(
+                    &broadcast,
+                    &sync_engine,
+                    &tx_pool,
+                    &peer_manager,
+                    &rebroadcast,
+                )
+                .and_then(|()| save_broadcast_state(&broadcast, &path, &mut saved));
+                if let Err(err) = ticked {
+                    eprintln!("broadcast: {err}");
+                }
+            }
+            std::thread::sleep(Duration::from_millis(200));
+        }
+        if let Err(err) = save_broadcast_state(&broadcast, &path, &mut saved) {
+            eprintln!("broadcast: {err}");
+        }
+    })
+}
+
+/// One pass of the broadcast loop: follow the chain, note which tracked
+/// transactions the mempool still holds, rebroadcast the due ones and
+/// announce every pending one to peers that connected since the last pass.
+fn broadcast_tick(
+    broadcast: &Mutex<BroadcastManager>,
+    sync_engine: &Mutex<SyncEngine>,
+    tx_pool: &Mutex<TxPool>,
+    peer_manager: &PeerManager,
+    rebroadcast: &RebroadcastFn,
+) -> Result<(), String> {
+    let store = sync_engine
+        .lock()
+        .map_err(|_| "sync engine unavailable".to_string())?
+        .block_store_snapshot();
+    let mut mgr = broadcast
+        .lock()
+        .map_err(|_| "broadcast manager unavailable".to_string())?;
+    if let Some(store) = store {
+        mgr.sync_with_chain(&store)?;
+    }
+    let unconfirmed = mgr.unconfirmed_txids();
+    {
+        let pool = tx_pool
+            .lock()
+            .map_err(|_| "tx pool unavailable".to_string())?;
+        for txid in &unconfirmed {
+            mgr.note_mempool(txid, pool.contains(txid));
+        }
+    }
+    let peers: Vec<String> = peer_manager
+        .snapshot()
+        .into_iter()
+        .map(|peer| peer.addr)
+        .collect();
+    let new_peers = mgr.new_peers(&peers);
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    let due = mgr.due_for_rebroadcast(now);
+    let pending = if new_peers.is_empty() {
+        Vec::new()
+    } else {
+        mgr.pending_txs()
+    };
+    drop(mgr);
+    for (txid, tx_bytes, fee) in &due {
+        rebroadcast(*txid, tx_bytes, *fee, None)?;
+    }
+    for (txid, tx_bytes, fee) in &pending {
+        rebroadcast(*txid, tx_bytes, *fee, Some(&new_peers))?;
+    }
+    Ok(())
+}
+
+fn save_broadcast_state(
+    broadcast: &Mutex<BroadcastManager>,
+    path: &Path,
+    saved: &mut Vec<u8>,
+) -> Result<(), String> {
+    let mgr = broadcast
+        .lock()
+        .map_err(|_| "broadcast manager unavailable".to_string())?;
+    let encoded = mgr.encode()?;
+    if encoded != *saved {
+        mgr.save(path)?;
+        *saved = encoded;
+    }
+    Ok(())
 }
 
 trait StopSource {
@@ -677,7 +1563,7 @@ where
     }
     let _ = writeln!(stderr, "rpc start failed: {err}");
     p2p_service.close_p2p();
-    2
+    1
 }
 
 fn maybe_shutdown_if_requested<S, R, P>(
@@ -769,6 +1655,24 @@ fn format_peer_slots_banner(max_peers: usize, connected: usize) -> String {
     format!("p2p: peer_slots={max_peers} connected={connected}")
 }
 
+/// Startup warning for a chain running non-v1 consensus parameters, so a
+/// fast devnet is never mistaken for a production-parameter chain. `None`
+/// for `ChainParams::V1`.
+fn format_consensus_overrides_banner(params: &ChainParams) -> Option<String> {
+    if *params == ChainParams::V1 {
+        return None;
+    }
+    Some(format!(
+        "WARNING: consensus_overrides active: window_size={} target_block_interval={} coinbase_maturity={} (v1: {} / {} / {})",
+        params.window_size,
+        params.target_block_interval,
+        params.coinbase_maturity,
+        ChainParams::V1.window_size,
+        ChainParams::V1.target_block_interval,
+        ChainParams::V1.coinbase_maturity,
+    ))
+}
+
 fn runtime_genesis_hash(genesis_cfg: &LoadedGenesisConfig) -> Result<[u8; 32], String> {
     genesis_cfg.genesis_hash.ok_or_else(|| {
         "runtime p2p requires genesis_hash_hex in the genesis file when chain_id is not devnet"
@@ -788,14 +1692,37 @@ fn parse_args(args: &[String]) -> Result<CliConfig, String> {
         mine_address: None,
         mine_blocks: 0,
         mine_exit: false,
+        mine_loop: MineLoopArgs::default(),
+        broadcast: BroadcastConfig::default(),
         pv_mode: "off".to_string(),
         pv_shadow_max: 3,
+        max_auto_reorg_depth: 0,
         legacy_exposure_scan: false,
         legacy_suite_ids: Vec::new(),
         legacy_exposure_include_outpoints: false,
+        chainstate_summary: false,
+        chainstate_summary_utxos: false,
+        compare_chainstate: None,
+        compare_chainstate_max_diffs: None,
+        block_stats: None,
+        get_block: None,
+        get_block_verbosity: None,
+        get_block_index: None,
+        force_reorg: None,
+        verifychain: false,
+        verifychain_level: None,
         dry_run: false,
+        featurebits: None,
+        featurebits_deployments: None,
+        verify_tx: VerifyTxArgs::default(),
+        verify_coinbase: VerifyCoinbaseArgs::default(),
+        version: false,
+        policyinfo: false,
+        json: false,
     };
     let mut peer_tokens = Vec::new();
+    let mut chain = None;
+    let mut network_set = false;
 
     let mut idx = 0usize;
     while idx < args.len() {
@@ -806,6 +1733,14 @@ fn parse_args(args: &[String]) -> Result<CliConfig, String> {
                     .get(idx)
                     .ok_or_else(|| "missing value for --network".to_string())?;
                 cfg.network = value.clone();
+                network_set = true;
+            }
+            "--chain" => {
+                idx += 1;
+                let value = args
+                    .get(idx)
+                    .ok_or_else(|| "missing value for --chain".to_string())?;
+                chain = Some(value.clone());
             }
             "--datadir" => {
                 idx += 1;
@@ -877,6 +1812,51 @@ fn parse_args(args: &[String]) -> Result<CliConfig, String> {
             "--mine-exit" => {
                 cfg.mine_exit = true;
             }
+            "--mine-loop" => {
+                cfg.mine_loop.enabled = true;
+            }
+            "--mine-interval" => {
+                idx += 1;
+                let value = args
+                    .get(idx)
+                    .ok_or_else(|| "missing value for --mine-interval".to_string())?;
+                cfg.mine_loop.interval_secs = Some(
+                    value
+                        .parse::<u64>()
+                        .map_err(|_| "invalid value for --mine-interval".to_string())?,
+                );
+            }
+            "--mine-fee-delta" => {
+                idx += 1;
+                let value = args
+                    .get(idx)
+                    .ok_or_else(|| "missing value for --mine-fee-delta".to_string())?;
+                cfg.mine_loop.fee_refresh_delta = value
+                    .parse::<u64>()
+                    .map_err(|_| "invalid value for --mine-fee-delta".to_string())?;
+            }
+            "--rebroadcast-interval" => {
+                idx += 1;
+                let value = args
+                    .get(idx)
+                    .ok_or_else(|| "missing value for --rebroadcast-interval".to_string())?;
+                cfg.broadcast.rebroadcast_interval_secs = value
+                    .parse::<u64>()
+                    .ok()
+                    .filter(|n| *n > 0)
+                    .ok_or_else(|| "invalid value for --rebroadcast-interval".to_string())?;
+            }
+            "--broadcast-expiry" => {
+                idx += 1;
+                let value = args
+                    .get(idx)
+                    .ok_or_else(|| "missing value for --broadcast-expiry".to_string())?;
+                cfg.broadcast.expiry_secs = value
+                    .parse::<u64>()
+                    .ok()
+                    .filter(|n| *n > 0)
+                    .ok_or_else(|| "invalid value for --broadcast-expiry".to_string())?;
+            }
             "--pv-mode" => {
                 idx += 1;
                 let value = args
@@ -893,6 +1873,15 @@ fn parse_args(args: &[String]) -> Result<CliConfig, String> {
                     .parse::<u64>()
                     .map_err(|_| "invalid value for --pv-shadow-max".to_string())?;
             }
+            "--max-auto-reorg-depth" => {
+                idx += 1;
+                let value = args
+                    .get(idx)
+                    .ok_or_else(|| "missing value for --max-auto-reorg-depth".to_string())?;
+                cfg.max_auto_reorg_depth = value
+                    .parse::<u64>()
+                    .map_err(|_| "invalid value for --max-auto-reorg-depth".to_string())?;
+            }
             "--legacy-exposure-scan" => {
                 cfg.legacy_exposure_scan = true;
             }
@@ -906,6 +1895,203 @@ fn parse_args(args: &[String]) -> Result<CliConfig, String> {
             "--legacy-exposure-include-outpoints" => {
                 cfg.legacy_exposure_include_outpoints = true;
             }
+            "--chainstate-summary" => {
+                cfg.chainstate_summary = true;
+            }
+            "--chainstate-summary-utxos" => {
+                cfg.chainstate_summary_utxos = true;
+            }
+            "--compare-chainstate" => {
+                idx += 1;
+                let value = args
+                    .get(idx)
+                    .ok_or_else(|| "missing value for --compare-chainstate".to_string())?;
+                cfg.compare_chainstate = Some(PathBuf::from(value));
+            }
+            "--compare-chainstate-max-diffs" => {
+                idx += 1;
+                let value = args.get(idx).ok_or_else(|| {
+                    "missing value for --compare-chainstate-max-diffs".to_string()
+                })?;
+                cfg.compare_chainstate_max_diffs =
+                    Some(value.parse::<usize>().map_err(|_| {
+                        "invalid value for --compare-chainstate-max-diffs".to_string()
+                    })?);
+            }
+            "--block-stats" => {
+                idx += 1;
+                let value = args
+                    .get(idx)
+                    .ok_or_else(|| "missing value for --block-stats".to_string())?;
+                let hash = hex_decode_32(value, "--block-stats")
+                    .map_err(|err| format!("invalid value for {err}"))?;
+                cfg.block_stats = Some(hash);
+            }
+            "--get-block" => {
+                idx += 1;
+                let value = args
+                    .get(idx)
+                    .ok_or_else(|| "missing value for --get-block".to_string())?;
+                let hash = hex_decode_32(value, "--get-block")
+                    .map_err(|err| format!("invalid value for {err}"))?;
+                cfg.get_block = Some(hash);
+            }
+            "--json" => {
+                cfg.json = true;
+            }
+            "--version" => {
+                cfg.version = true;
+            }
+            "--policyinfo" => {
+                cfg.policyinfo = true;
+            }
+            "--featurebits" => {
+                idx += 1;
+                let value = args
+                    .get(idx)
+                    .ok_or_else(|| "missing value for --featurebits".to_string())?;
+                cfg.featurebits = Some(value.clone());
+            }
+            "--featurebits-deployments" => {
+                idx += 1;
+                let value = args
+                    .get(idx)
+                    .ok_or_else(|| "missing value for --featurebits-deployments".to_string())?;
+                cfg.featurebits_deployments = Some(PathBuf::from(value));
+            }
+            "--verify-tx" => {
+                idx += 1;
+                let value = args
+                    .get(idx)
+                    .ok_or_else(|| "missing value for --verify-tx".to_string())?;
+                cfg.verify_tx.tx_hex = Some(value.clone());
+            }
+            "--verify-tx-file" => {
+                idx += 1;
+                let value = args
+                    .get(idx)
+                    .ok_or_else(|| "missing value for --verify-tx-file".to_string())?;
+                cfg.verify_tx.tx_file = Some(PathBuf::from(value));
+            }
+            "--verify-prevouts" => {
+                idx += 1;
+                let value = args
+                    .get(idx)
+                    .ok_or_else(|| "missing value for --verify-prevouts".to_string())?;
+                cfg.verify_tx.prevouts = Some(PathBuf::from(value));
+            }
+            "--verify-height" => {
+                idx += 1;
+                let value = args
+                    .get(idx)
+                    .ok_or_else(|| "missing value for --verify-height".to_string())?;
+                cfg.verify_tx.height = Some(
+                    value
+                        .parse::<u64>()
+                        .map_err(|_| "invalid value for --verify-height".to_string())?,
+                );
+            }
+            "--verify-mtp" => {
+                idx += 1;
+                let value = args
+                    .get(idx)
+                    .ok_or_else(|| "missing value for --verify-mtp".to_string())?;
+                cfg.verify_tx.mtp = Some(
+                    value
+                        .parse::<u64>()
+                        .map_err(|_| "invalid value for --verify-mtp".to_string())?,
+                );
+            }
+            "--verify-coinbase" => {
+                idx += 1;
+                let value = args
+                    .get(idx)
+                    .ok_or_else(|| "missing value for --verify-coinbase".to_string())?;
+                cfg.verify_coinbase.tx_hex = Some(value.clone());
+            }
+            "--verify-coinbase-file" => {
+                idx += 1;
+                let value = args
+                    .get(idx)
+                    .ok_or_else(|| "missing value for --verify-coinbase-file".to_string())?;
+                cfg.verify_coinbase.tx_file = Some(PathBuf::from(value));
+            }
+            "--coinbase-height" => {
+                idx += 1;
+                let value = args
+                    .get(idx)
+                    .ok_or_else(|| "missing value for --coinbase-height".to_string())?;
+                cfg.verify_coinbase.height = Some(
+                    value
+                        .parse::<u64>()
+                        .map_err(|_| "invalid value for --coinbase-height".to_string())?,
+                );
+            }
+            "--coinbase-fees" => {
+                idx += 1;
+                let value = args
+                    .get(idx)
+                    .ok_or_else(|| "missing value for --coinbase-fees".to_string())?;
+                cfg.verify_coinbase.fees = Some(
+                    value
+                        .parse::<u64>()
+                        .map_err(|_| "invalid value for --coinbase-fees".to_string())?,
+                );
+            }
+            "--coinbase-already-generated" => {
+                idx += 1;
+                let value = args
+                    .get(idx)
+                    .ok_or_else(|| "missing value for --coinbase-already-generated".to_string())?;
+                cfg.verify_coinbase.already_generated =
+                    Some(value.parse::<u128>().map_err(|_| {
+                        "invalid value for --coinbase-already-generated".to_string()
+                    })?);
+            }
+            "--get-block-index" => {
+                idx += 1;
+                let value = args
+                    .get(idx)
+                    .ok_or_else(|| "missing value for --get-block-index".to_string())?;
+                let hash = hex_decode_32(value, "--get-block-index")
+                    .map_err(|err| format!("invalid value for {err}"))?;
+                cfg.get_block_index = Some(hash);
+            }
+            "--get-block-verbosity" => {
+                idx += 1;
+                let value = args
+                    .get(idx)
+                    .ok_or_else(|| "missing value for --get-block-verbosity".to_string())?;
+                cfg.get_block_verbosity = Some(
+                    parse_block_verbosity(value)
+                        .map_err(|_| "invalid value for --get-block-verbosity".to_string())?,
+                );
+            }
+            "--force-reorg" => {
+                idx += 1;
+                let value = args
+                    .get(idx)
+                    .ok_or_else(|| "missing value for --force-reorg".to_string())?;
+                let hash = hex_decode_32(value, "--force-reorg")
+                    .map_err(|err| format!("invalid value for {err}"))?;
+                cfg.force_reorg = Some(hash);
+            }
+            "--verifychain" => {
+                cfg.verifychain = true;
+            }
+            "--verifychain-level" => {
+                idx += 1;
+                let value = args
+                    .get(idx)
+                    .ok_or_else(|| "missing value for --verifychain-level".to_string())?;
+                cfg.verifychain_level = Some(
+                    value
+                        .parse::<u8>()
+                        .ok()
+                        .filter(|level| *level <= VERIFY_CHAIN_MAX_LEVEL)
+                        .ok_or_else(|| "invalid value for --verifychain-level".to_string())?,
+                );
+            }
             "--dry-run" => {
                 cfg.dry_run = true;
             }
@@ -915,6 +2101,18 @@ fn parse_args(args: &[String]) -> Result<CliConfig, String> {
         }
         idx += 1;
     }
+    if let Some(chain) = chain {
+        if cfg.genesis_file.is_some() {
+            return Err("--chain and --genesis-file are mutually exclusive".to_string());
+        }
+        match resolve_chain(&chain)? {
+            ChainSelection::Preset(_) if network_set => {
+                return Err("--chain <name> sets the network; drop --network".to_string());
+            }
+            ChainSelection::Preset(preset) => cfg.network = preset.network.to_string(),
+            ChainSelection::Custom(path) => cfg.genesis_file = Some(path),
+        }
+    }
     cfg.peers = normalize_peers(&peer_tokens);
     cfg.legacy_suite_ids.sort_unstable();
     cfg.legacy_suite_ids.dedup();
@@ -932,7 +2130,27 @@ fn default_data_dir() -> PathBuf {
 fn usage(stdout: &mut dyn Write) {
     let _ = writeln!(
         stdout,
-        "usage: rubin-node [--network <name>] [--datadir <path>] [--genesis-file <path>] [--bind <host:port>] [--peer <host:port>]... [--peers <csv>] [--max-peers <n>] [--rpc-bind <host:port>] [--mine-address <hex>] [--mine-blocks <n>] [--mine-exit] [--pv-mode <off|shadow|on>] [--pv-shadow-max <n>] [--legacy-exposure-scan] [--legacy-suite-id <id>]... [--legacy-exposure-include-outpoints] [--dry-run]"
+        "usage: rubin-node [--network <name>] [--chain <devnet|custom:<path>>] [--datadir <path>] [--genesis-file <path>] [--bind <host:port>] [--peer <host:port>]... [--peers <csv>] [--max-peers <n>] [--rpc-bind <host:port>] [--mine-address <hex>] [--mine-blocks <n>] [--mine-exit] [--mine-loop [--mine-interval <secs>] [--mine-fee-delta <n>]] [--rebroadcast-interval <secs>] [--broadcast-expiry <secs>] [--pv-mode <off|shadow|on>] [--pv-shadow-max <n>] [--max-auto-reorg-depth <n>] [--legacy-exposure-scan] [--legacy-suite-id <id>]... [--legacy-exposure-include-outpoints] [--chainstate-summary] [--chainstate-summary-utxos] [--compare-chainstate <summary.json>] [--compare-chainstate-max-diffs <n>] [--block-stats <hash>] [--get-block <hash>] [--get-block-verbosity <0|1|2>] [--get-block-index <hash>] [--force-reorg <hash>] [--verifychain] [--verifychain-level <0-3>] [--dry-run] [--featurebits <name> --featurebits-deployments <path>] [--verify-tx <hex> | --verify-tx-file <path>] [--verify-prevouts <path>] [--verify-height <n>] [--verify-mtp <n>] [--verify-coinbase <hex> | --verify-coinbase-file <path>] [--coinbase-height <n>] [--coinbase-fees <n>] [--coinbase-already-generated <n>] [--version] [--policyinfo] [--json]"
+    );
+    let _ = writeln!(
+        stdout,
+        "       rubin-node devnet --blocks <n> --datadir <path> [--seed <u64>] [--json]"
+    );
+    let _ = writeln!(
+        stdout,
+        "       rubin-node exportblocks --to <dir> [--datadir <path>] [--network <name>] [--genesis-file <path>] [--blocks-per-file <n>] [--json]"
+    );
+    let _ = writeln!(
+        stdout,
+        "       rubin-node importblocks --from <dir> [--datadir <path>] [--network <name>] [--genesis-file <path>] [--max-buffered <n>] [--json]"
+    );
+    let _ = writeln!(
+        stdout,
+        "       rubin-node getchaintips [--datadir <path>] [--json]"
+    );
+    let _ = writeln!(
+        stdout,
+        "       rubin-node gettxstatus --txid <hex> [--datadir <path>] [--json]"
     );
 }
 
@@ -1131,6 +2349,9 @@ fn validate_config(cfg: &mut CliConfig) -> Result<(), String> {
             return Err(format!("invalid mine_address: {err}"));
         }
     }
+    if cfg.mine_loop.enabled && cfg.network != "devnet" {
+        return Err("--mine-loop is only available on devnet".to_string());
+    }
     let pv_mode = cfg.pv_mode.trim().to_ascii_lowercase();
     if !["off", "shadow", "on"].contains(&pv_mode.as_str()) {
         return Err("pv_mode must be one of: off, shadow, on".to_string());
@@ -1146,6 +2367,73 @@ fn validate_config(cfg: &mut CliConfig) -> Result<(), String> {
     } else if !cfg.legacy_suite_ids.is_empty() || cfg.legacy_exposure_include_outpoints {
         return Err("legacy exposure flags require --legacy-exposure-scan".to_string());
     }
+    if cfg.chainstate_summary_utxos && !cfg.chainstate_summary {
+        return Err("--chainstate-summary-utxos requires --chainstate-summary".to_string());
+    }
+    if cfg.compare_chainstate_max_diffs.is_some() && cfg.compare_chainstate.is_none() {
+        return Err("--compare-chainstate-max-diffs requires --compare-chainstate".to_string());
+    }
+    if cfg.get_block_verbosity.is_some() && cfg.get_block.is_none() {
+        return Err("--get-block-verbosity requires --get-block".to_string());
+    }
+    if cfg.verifychain_level.is_some() && !cfg.verifychain {
+        return Err("--verifychain-level requires --verifychain".to_string());
+    }
+    if cfg.featurebits.is_some() != cfg.featurebits_deployments.is_some() {
+        return Err(
+            "--featurebits and --featurebits-deployments must be used together".to_string(),
+        );
+    }
+    let verify = &cfg.verify_tx;
+    if verify.tx_hex.is_some() && verify.tx_file.is_some() {
+        return Err("--verify-tx and --verify-tx-file are mutually exclusive".to_string());
+    }
+    if verify.enabled() != (verify.prevouts.is_some() && verify.height.is_some()) {
+        return Err(
+            "--verify-tx requires --verify-prevouts and --verify-height, and they require --verify-tx"
+                .to_string(),
+        );
+    }
+    if verify.mtp.is_some() && !verify.enabled() {
+        return Err("--verify-mtp requires --verify-tx".to_string());
+    }
+    let coinbase = &cfg.verify_coinbase;
+    if coinbase.tx_hex.is_some() && coinbase.tx_file.is_some() {
+        return Err(
+            "--verify-coinbase and --verify-coinbase-file are mutually exclusive".to_string(),
+        );
+    }
+    if coinbase.enabled() != coinbase.height.is_some() {
+        return Err(
+            "--verify-coinbase requires --coinbase-height, and it requires --verify-coinbase"
+                .to_string(),
+        );
+    }
+    if (coinbase.fees.is_some() || coinbase.already_generated.is_some()) && !coinbase.enabled() {
+        return Err(
+            "--coinbase-fees and --coinbase-already-generated require --verify-coinbase"
+                .to_string(),
+        );
+    }
+    let offline_modes = [
+        cfg.legacy_exposure_scan,
+        cfg.chainstate_summary,
+        cfg.compare_chainstate.is_some(),
+        cfg.block_stats.is_some(),
+        cfg.get_block.is_some(),
+        cfg.get_block_index.is_some(),
+        cfg.force_reorg.is_some(),
+        cfg.verifychain,
+        cfg.featurebits.is_some(),
+        cfg.verify_tx.enabled(),
+        cfg.verify_coinbase.enabled(),
+    ];
+    if offline_modes.iter().filter(|enabled| **enabled).count() > 1 {
+        return Err(
+            "--legacy-exposure-scan, --chainstate-summary, --compare-chainstate, --block-stats, --get-block, --get-block-index, --force-reorg, --verifychain, --featurebits, --verify-tx and --verify-coinbase are mutually exclusive"
+                .to_string(),
+        );
+    }
     Ok(())
 }
 
@@ -1303,26 +2591,33 @@ mod tests {
     use std::collections::HashMap;
     use std::fs;
     use std::io;
-    use std::path::PathBuf;
+    use std::path::{Path, PathBuf};
     use std::sync::{Arc, Mutex};
     use std::{cell::RefCell, rc::Rc};
 
     use super::{
-        advance_da_ttl_for_block, announce_tx_after_local_admission, format_peer_slots_banner,
+        advance_da_ttl_for_block, announce_tx_after_local_admission,
+        format_consensus_overrides_banner, format_peer_slots_banner,
         handle_rpc_start_error_after_maybe_stop, legacy_exposure_hooks,
-        live_devnet_loopback_mining_allowed, maybe_shutdown_if_requested, parse_args, run,
-        runtime_genesis_hash, stop_signal_pair, validate_config, wait_for_stop_and_shutdown,
-        LegacyExposureReport, PRODUCTION_STOP_SIGNAL_SET, RPC_READINESS_TRANSITION_FAILED,
+        live_devnet_loopback_mining_allowed, maybe_shutdown_if_requested, parse_args,
+        parse_devnet_args, run_cli, runtime_genesis_hash, stop_signal_pair, validate_config,
+        wait_for_stop_and_shutdown, LegacyExposureReport, DEFAULT_DEVNET_HARNESS_SEED,
+        PRODUCTION_STOP_SIGNAL_SET, RPC_READINESS_TRANSITION_FAILED,
     };
     use rubin_consensus::constants::{
         COV_TYPE_DA_COMMIT, ML_DSA_87_PUBKEY_BYTES, ML_DSA_87_SIG_BYTES, SUITE_ID_ML_DSA_87,
-        TX_WIRE_VERSION, VERIFY_COST_ML_DSA_87,
+        TARGET_BLOCK_INTERVAL, TX_WIRE_VERSION, VERIFY_COST_ML_DSA_87,
+    };
+    use rubin_consensus::{
+        marshal_tx, parse_tx, ChainParams, DaChunkCore, DaCommitCore, Tx, TxOutput,
     };
-    use rubin_consensus::{marshal_tx, parse_tx, DaChunkCore, DaCommitCore, Tx, TxOutput};
     use rubin_node::da_relay::{DaRelayCaps, DaRelayState};
     use rubin_node::tx_relay::{PeerOutbox, TxRelayState};
     use rubin_node::txpool::RelayTxMetadata;
-    use rubin_node::{load_genesis_config, PRODUCTION_LOCAL_ROTATION_DESCRIPTOR_ERR};
+    use rubin_node::{
+        broadcast_state_path, load_genesis_config, BroadcastConfig, BroadcastManager,
+        PRODUCTION_LOCAL_ROTATION_DESCRIPTOR_ERR,
+    };
     use serde_json::Value;
     use sha3::{Digest, Sha3_256};
 
@@ -1826,7 +3121,7 @@ mod tests {
             &mut stderr,
         );
 
-        assert_eq!(code, 2);
+        assert_eq!(code, 1);
         assert_eq!(events.snapshot(), vec!["p2p"]);
         assert!(stdout.is_empty());
         assert_eq!(
@@ -1855,7 +3150,7 @@ mod tests {
             &mut stderr,
         );
 
-        assert_eq!(code, 2);
+        assert_eq!(code, 1);
         assert_eq!(events.snapshot(), vec!["p2p"]);
         assert!(stdout.is_empty());
         assert_eq!(
@@ -1992,7 +3287,7 @@ mod tests {
         let mut stdout = Vec::new();
         let mut stderr = Vec::new();
 
-        let code = run(&args, &mut stdout, &mut stderr);
+        let code = run_cli(&args, &mut stdout, &mut stderr);
         assert_eq!(code, 0, "stderr={}", String::from_utf8_lossy(&stderr));
 
         let json: Value = parse_effective_config_json(&stdout);
@@ -2005,38 +3300,155 @@ mod tests {
     }
 
     #[test]
-    fn dry_run_loads_chain_id_from_genesis_file() {
-        let dir = unique_temp_dir("rubin-node-bin-genesis");
+    fn startup_refuses_datadir_locked_by_another_instance() {
+        let dir = unique_temp_dir("rubin-node-bin-datadir-lock");
         fs::create_dir_all(&dir).expect("mkdir");
-        let genesis_file = dir.join("genesis.json");
-        fs::write(
-            &genesis_file,
-            "{\"chain_id_hex\":\"0x1111111111111111111111111111111111111111111111111111111111111111\"}",
-        )
-        .expect("write genesis");
-
         let args = vec![
             "--dry-run".to_string(),
             "--datadir".to_string(),
             dir.display().to_string(),
-            "--genesis-file".to_string(),
-            genesis_file.display().to_string(),
         ];
+        let held = rubin_node::DataDirLock::acquire(&dir).expect("hold lock");
         let mut stdout = Vec::new();
         let mut stderr = Vec::new();
-
-        let code = run(&args, &mut stdout, &mut stderr);
-        assert_eq!(code, 0, "stderr={}", String::from_utf8_lossy(&stderr));
-
-        let json: Value = parse_effective_config_json(&stdout);
-        assert_eq!(
-            json["chain_id_hex"].as_str(),
-            Some("1111111111111111111111111111111111111111111111111111111111111111")
+        assert_eq!(run_cli(&args, &mut stdout, &mut stderr), 5);
+        let stderr = String::from_utf8_lossy(&stderr);
+        assert!(
+            stderr.contains("is in use by another rubin-node (pid "),
+            "{stderr}"
+        );
+        assert!(stdout.is_empty());
+
+        drop(held);
+        let mut stdout = Vec::new();
+        let mut stderr = Vec::new();
+        assert_eq!(
+            run_cli(&args, &mut stdout, &mut stderr),
+            0,
+            "stderr={}",
+            String::from_utf8_lossy(&stderr)
         );
 
         fs::remove_dir_all(&dir).expect("cleanup");
     }
 
+    #[test]
+    fn dry_run_loads_chain_id_from_genesis_file() {
+        let dir = unique_temp_dir("rubin-node-bin-genesis");
+        fs::create_dir_all(&dir).expect("mkdir");
+        let genesis_file = dir.join("genesis.json");
+        fs::write(
+            &genesis_file,
+            "{\"chain_id_hex\":\"0x1111111111111111111111111111111111111111111111111111111111111111\"}",
+        )
+        .expect("write genesis");
+
+        let args = vec![
+            "--dry-run".to_string(),
+            "--datadir".to_string(),
+            dir.display().to_string(),
+            "--genesis-file".to_string(),
+            genesis_file.display().to_string(),
+        ];
+        let mut stdout = Vec::new();
+        let mut stderr = Vec::new();
+
+        let code = run_cli(&args, &mut stdout, &mut stderr);
+        assert_eq!(code, 0, "stderr={}", String::from_utf8_lossy(&stderr));
+
+        let json: Value = parse_effective_config_json(&stdout);
+        assert_eq!(
+            json["chain_id_hex"].as_str(),
+            Some("1111111111111111111111111111111111111111111111111111111111111111")
+        );
+
+        fs::remove_dir_all(&dir).expect("cleanup");
+    }
+
+    #[test]
+    fn chain_flag_selects_embedded_preset_or_custom_genesis() {
+        let dir = unique_temp_dir("rubin-node-bin-chain");
+        fs::create_dir_all(&dir).expect("mkdir");
+        let genesis_file = dir.join("genesis.json");
+        fs::write(
+            &genesis_file,
+            "{\"chain_id_hex\":\"0x1111111111111111111111111111111111111111111111111111111111111111\"}",
+        )
+        .expect("write genesis");
+        let run_dry = |extra: &[String]| {
+            let mut args = vec![
+                "--dry-run".to_string(),
+                "--datadir".to_string(),
+                dir.display().to_string(),
+            ];
+            args.extend_from_slice(extra);
+            let mut stdout = Vec::new();
+            let mut stderr = Vec::new();
+            let code = run_cli(&args, &mut stdout, &mut stderr);
+            (code, stdout, String::from_utf8(stderr).expect("utf8"))
+        };
+
+        // The preset is compiled in: nothing is read relative to the cwd.
+        let (code, stdout, stderr) = run_dry(&["--chain".to_string(), "devnet".to_string()]);
+        assert_eq!(code, 0, "stderr={stderr}");
+        let json: Value = parse_effective_config_json(&stdout);
+        assert_eq!(json["network"].as_str(), Some("devnet"));
+        assert!(json["genesis_file"].is_null());
+        assert_eq!(
+            json["chain_id_hex"].as_str(),
+            Some(hex::encode(rubin_node::devnet_genesis_chain_id()).as_str())
+        );
+
+        let custom = format!("custom:{}", genesis_file.display());
+        // A datadir stays bound to the chain it was initialized for.
+        let (code, _, stderr) = run_dry(&["--chain".to_string(), custom.clone()]);
+        assert_eq!(code, 5);
+        assert!(stderr.contains("was initialized for chain_id"), "{stderr}");
+        let (code, stdout, stderr) = run_dry(&[
+            "--chain".to_string(),
+            custom.clone(),
+            "--datadir".to_string(),
+            dir.join("custom").display().to_string(),
+        ]);
+        assert_eq!(code, 0, "stderr={stderr}");
+        let json: Value = parse_effective_config_json(&stdout);
+        assert_eq!(
+            json["chain_id_hex"].as_str(),
+            Some("1111111111111111111111111111111111111111111111111111111111111111")
+        );
+
+        for (extra, want) in [
+            (
+                vec!["--chain".to_string(), "testnet".to_string()],
+                "unknown chain 'testnet' (expected: devnet, custom:<path>)",
+            ),
+            (
+                vec![
+                    "--network".to_string(),
+                    "devnet".to_string(),
+                    "--chain".to_string(),
+                    "devnet".to_string(),
+                ],
+                "--chain <name> sets the network; drop --network",
+            ),
+            (
+                vec![
+                    "--genesis-file".to_string(),
+                    genesis_file.display().to_string(),
+                    "--chain".to_string(),
+                    custom.clone(),
+                ],
+                "--chain and --genesis-file are mutually exclusive",
+            ),
+        ] {
+            let (code, _, stderr) = run_dry(&extra);
+            assert_eq!(code, 2, "{extra:?}");
+            assert!(stderr.contains(want), "{extra:?}: {stderr}");
+        }
+
+        fs::remove_dir_all(&dir).expect("cleanup");
+    }
+
     #[test]
     fn dry_run_rejects_production_local_rotation_descriptor() {
         for network in production_rotation_networks() {
@@ -2076,8 +3488,8 @@ mod tests {
             let mut stdout = Vec::new();
             let mut stderr = Vec::new();
 
-            let code = run(&args, &mut stdout, &mut stderr);
-            assert_eq!(code, 2, "stdout={}", String::from_utf8_lossy(&stdout));
+            let code = run_cli(&args, &mut stdout, &mut stderr);
+            assert_eq!(code, 3, "stdout={}", String::from_utf8_lossy(&stdout));
             assert!(
                 String::from_utf8_lossy(&stderr).contains(PRODUCTION_LOCAL_ROTATION_DESCRIPTOR_ERR),
                 "network={network} stderr={}",
@@ -2240,7 +3652,7 @@ mod tests {
         let mut stdout = Vec::new();
         let mut stderr = Vec::new();
 
-        let code = run(&args, &mut stdout, &mut stderr);
+        let code = run_cli(&args, &mut stdout, &mut stderr);
         assert_eq!(code, 2);
         let stderr = String::from_utf8_lossy(&stderr);
         assert!(stderr.contains("requires a genesis file (--genesis-file)"));
@@ -2265,6 +3677,44 @@ mod tests {
         assert!(cfg.mine_exit);
     }
 
+    #[test]
+    fn parse_args_accepts_mine_loop_flags() {
+        let defaults = parse_args(&["--mine-loop".to_string()]).expect("parse");
+        assert!(defaults.mine_loop.enabled);
+        assert_eq!(defaults.mine_loop.interval_secs, None);
+        assert_eq!(
+            defaults.mine_loop.interval_secs(&ChainParams::V1),
+            TARGET_BLOCK_INTERVAL
+        );
+
+        let mut cfg = parse_args(&[
+            "--mine-loop".to_string(),
+            "--mine-interval".to_string(),
+            "0".to_string(),
+            "--mine-fee-delta".to_string(),
+            "5000".to_string(),
+        ])
+        .expect("parse");
+        validate_config(&mut cfg).expect("devnet mine loop");
+        assert_eq!(cfg.mine_loop.interval_secs, Some(0));
+        assert_eq!(cfg.mine_loop.fee_refresh_delta, 5000);
+
+        let err = parse_args(&["--mine-interval".to_string(), "soon".to_string()]).unwrap_err();
+        assert_eq!(err, "invalid value for --mine-interval");
+
+        let mut non_devnet = parse_args(&[
+            "--network".to_string(),
+            "testnet".to_string(),
+            "--mine-loop".to_string(),
+        ])
+        .expect("parse");
+        non_devnet.genesis_file = Some(PathBuf::from("genesis.json"));
+        assert_eq!(
+            validate_config(&mut non_devnet).unwrap_err(),
+            "--mine-loop is only available on devnet"
+        );
+    }
+
     #[test]
     fn parse_args_accepts_pv_flags() {
         let cfg = parse_args(&[
@@ -2278,6 +3728,46 @@ mod tests {
         assert_eq!(cfg.pv_shadow_max, 7);
     }
 
+    #[test]
+    fn dry_run_reports_max_auto_reorg_depth() {
+        let dir = unique_temp_dir("rubin-node-bin-max-reorg-depth");
+        let args = vec![
+            "--dry-run".to_string(),
+            "--datadir".to_string(),
+            dir.display().to_string(),
+            "--max-auto-reorg-depth".to_string(),
+            "6".to_string(),
+        ];
+        let mut stdout = Vec::new();
+        let mut stderr = Vec::new();
+        let code = run_cli(&args, &mut stdout, &mut stderr);
+        assert_eq!(code, 0, "stderr={}", String::from_utf8_lossy(&stderr));
+        let json = parse_effective_config_json(&stdout);
+        assert_eq!(json["max_auto_reorg_depth"].as_u64(), Some(6));
+
+        fs::remove_dir_all(&dir).expect("cleanup");
+    }
+
+    #[test]
+    fn force_reorg_fails_for_unknown_block() {
+        let dir = unique_temp_dir("rubin-node-bin-force-reorg");
+        let args = vec![
+            "--datadir".to_string(),
+            dir.display().to_string(),
+            "--force-reorg".to_string(),
+            "33".repeat(32),
+        ];
+        let mut stdout = Vec::new();
+        let mut stderr = Vec::new();
+        let code = run_cli(&args, &mut stdout, &mut stderr);
+        assert_eq!(code, 5);
+        assert!(stdout.is_empty());
+        let stderr = String::from_utf8_lossy(&stderr);
+        assert!(stderr.contains("force reorg failed"), "{stderr}");
+
+        fs::remove_dir_all(&dir).expect("cleanup");
+    }
+
     #[test]
     fn validate_config_rejects_invalid_pv_mode() {
         let mut cfg =
@@ -2393,7 +3883,7 @@ mod tests {
         let mut stdout = Vec::new();
         let mut stderr = Vec::new();
 
-        let code = run(&args, &mut stdout, &mut stderr);
+        let code = run_cli(&args, &mut stdout, &mut stderr);
         assert_eq!(code, 0, "stderr={}", String::from_utf8_lossy(&stderr));
         let json: Value = parse_effective_config_json(&stdout);
         assert_eq!(json["rpc_bind_addr"].as_str(), Some("127.0.0.1:19112"));
@@ -2418,7 +3908,7 @@ mod tests {
         let mut stdout = Vec::new();
         let mut stderr = Vec::new();
 
-        let code = run(&args, &mut stdout, &mut stderr);
+        let code = run_cli(&args, &mut stdout, &mut stderr);
         assert_eq!(code, 0, "stderr={}", String::from_utf8_lossy(&stderr));
         let json: Value = parse_effective_config_json(&stdout);
         assert_eq!(json["bind_addr"].as_str(), Some("127.0.0.1:19111"));
@@ -2443,7 +3933,7 @@ mod tests {
         let mut stdout = Vec::new();
         let mut stderr = Vec::new();
 
-        let code = run(&args, &mut stdout, &mut stderr);
+        let code = run_cli(&args, &mut stdout, &mut stderr);
         assert_eq!(code, 0, "stderr={}", String::from_utf8_lossy(&stderr));
         let json: Value = parse_effective_config_json(&stdout);
         assert_eq!(json["pv_mode"].as_str(), Some("on"));
@@ -2480,7 +3970,7 @@ mod tests {
         let mut stdout = Vec::new();
         let mut stderr = Vec::new();
 
-        let code = run(&args, &mut stdout, &mut stderr);
+        let code = run_cli(&args, &mut stdout, &mut stderr);
         assert_eq!(code, 0, "stderr={}", String::from_utf8_lossy(&stderr));
         let stdout_str = String::from_utf8(stdout).expect("stdout utf8");
 
@@ -2615,6 +4105,42 @@ mod tests {
         );
     }
 
+    #[test]
+    fn consensus_overrides_banner_only_for_non_v1_params() {
+        assert_eq!(format_consensus_overrides_banner(&ChainParams::V1), None);
+        let fast = ChainParams {
+            target_block_interval: 2,
+            window_size: 16,
+            coinbase_maturity: 4,
+        };
+        assert_eq!(
+            format_consensus_overrides_banner(&fast).as_deref(),
+            Some(
+                "WARNING: consensus_overrides active: window_size=16 target_block_interval=2 \
+                 coinbase_maturity=4 (v1: 10080 / 120 / 100)"
+            )
+        );
+    }
+
+    #[test]
+    fn mine_loop_paces_at_the_overridden_target_interval() {
+        let fast = ChainParams {
+            target_block_interval: 2,
+            window_size: 16,
+            coinbase_maturity: 4,
+        };
+        let defaults = parse_args(&["--mine-loop".to_string()]).expect("parse");
+        assert_eq!(defaults.mine_loop.interval_secs(&fast), 2);
+
+        let explicit = parse_args(&[
+            "--mine-loop".to_string(),
+            "--mine-interval".to_string(),
+            "7".to_string(),
+        ])
+        .expect("parse");
+        assert_eq!(explicit.mine_loop.interval_secs(&fast), 7);
+    }
+
     #[test]
     fn legacy_exposure_scan_emits_deterministic_json() {
         let dir = unique_temp_dir("rubin-node-bin-legacy-exposure");
@@ -2685,7 +4211,7 @@ mod tests {
         let mut stdout = Vec::new();
         let mut stderr = Vec::new();
 
-        let code = run(&args, &mut stdout, &mut stderr);
+        let code = run_cli(&args, &mut stdout, &mut stderr);
         assert_eq!(code, 0, "stderr={}", String::from_utf8_lossy(&stderr));
         let json: Value = parse_effective_config_json(&stdout);
         assert!(json["indexed_suite_ids"].is_array());
@@ -2776,7 +4302,7 @@ mod tests {
         let mut stdout = Vec::new();
         let mut stderr = Vec::new();
 
-        let code = run(&args, &mut stdout, &mut stderr);
+        let code = run_cli(&args, &mut stdout, &mut stderr);
         assert_eq!(code, 0, "stderr={}", String::from_utf8_lossy(&stderr));
         let json: Value = parse_effective_config_json(&stdout);
         assert_eq!(json["include_outpoints"].as_bool(), Some(true));
@@ -2826,7 +4352,7 @@ mod tests {
         let mut stdout = Vec::new();
         let mut stderr = Vec::new();
 
-        let code = run(&args, &mut stdout, &mut stderr);
+        let code = run_cli(&args, &mut stdout, &mut stderr);
         assert_eq!(code, 0, "stderr={}", String::from_utf8_lossy(&stderr));
         let json: Value = parse_effective_config_json(&stdout);
         assert_eq!(json["include_outpoints"].as_bool(), Some(true));
@@ -2858,7 +4384,7 @@ mod tests {
         let mut stdout = Vec::new();
         let mut stderr = Vec::new();
 
-        let code = run(&args, &mut stdout, &mut stderr);
+        let code = run_cli(&args, &mut stdout, &mut stderr);
         assert_eq!(code, 2);
         assert!(String::from_utf8_lossy(&stderr)
             .contains("legacy exposure scan requires at least one --legacy-suite-id"));
@@ -2877,8 +4403,8 @@ mod tests {
         let mut stdout = Vec::new();
         let mut stderr = Vec::new();
 
-        let code = run(&args, &mut stdout, &mut stderr);
-        assert_eq!(code, 2);
+        let code = run_cli(&args, &mut stdout, &mut stderr);
+        assert_eq!(code, 5);
         assert!(String::from_utf8_lossy(&stderr)
             .contains("legacy exposure scan requires an existing chainstate file with a tip"));
     }
@@ -2900,8 +4426,8 @@ mod tests {
         let mut stdout = Vec::new();
         let mut stderr = Vec::new();
 
-        let code = run(&args, &mut stdout, &mut stderr);
-        assert_eq!(code, 2);
+        let code = run_cli(&args, &mut stdout, &mut stderr);
+        assert_eq!(code, 5);
         assert!(String::from_utf8_lossy(&stderr)
             .contains("legacy exposure scan requires a chainstate with a tip"));
     }
@@ -2922,7 +4448,7 @@ mod tests {
         let mut stdout = Vec::new();
         let mut stderr = Vec::new();
 
-        let code = run(&args, &mut stdout, &mut stderr);
+        let code = run_cli(&args, &mut stdout, &mut stderr);
         assert_eq!(code, 2);
         assert!(String::from_utf8_lossy(&stderr).contains("invalid legacy suite_id"));
     }
@@ -2941,7 +4467,7 @@ mod tests {
         let mut stdout = Vec::new();
         let mut stderr = Vec::new();
 
-        let code = run(&args, &mut stdout, &mut stderr);
+        let code = run_cli(&args, &mut stdout, &mut stderr);
         assert_eq!(code, 2);
         let stderr = String::from_utf8_lossy(&stderr);
         assert!(stderr.contains("legacy exposure scan requires at least one --legacy-suite-id"));
@@ -2971,7 +4497,7 @@ mod tests {
         let mut stdout = Vec::new();
         let mut stderr = Vec::new();
 
-        let code = run(&args, &mut stdout, &mut stderr);
+        let code = run_cli(&args, &mut stdout, &mut stderr);
         assert_eq!(code, 0, "stderr={}", String::from_utf8_lossy(&stderr));
         let json: Value = parse_effective_config_json(&stdout);
         assert_eq!(json["network"].as_str(), Some("mainnet"));
@@ -3001,7 +4527,7 @@ mod tests {
         let mut stdout = FailingWriter;
         let mut stderr = Vec::new();
 
-        let code = run(&args, &mut stdout, &mut stderr);
+        let code = run_cli(&args, &mut stdout, &mut stderr);
         assert_eq!(code, 1);
         let stderr = String::from_utf8_lossy(&stderr);
         assert!(stderr.contains("legacy exposure encode failed:"));
@@ -3026,7 +4552,7 @@ mod tests {
         let mut stdout = Vec::new();
         let mut stderr = Vec::new();
 
-        let code = run(&args, &mut stdout, &mut stderr);
+        let code = run_cli(&args, &mut stdout, &mut stderr);
         assert_eq!(code, 2);
         let stderr = String::from_utf8_lossy(&stderr);
         assert!(stderr.contains("legacy exposure flags require --legacy-exposure-scan"));
@@ -3035,6 +4561,703 @@ mod tests {
         fs::remove_dir_all(&dir).expect("cleanup");
     }
 
+    fn save_compare_fixture_state(dir: &Path, values: &[(u8, u64)]) -> rubin_node::ChainState {
+        fs::create_dir_all(dir).expect("mkdir");
+        let mut state = rubin_node::ChainState::new();
+        state.has_tip = true;
+        state.height = 5;
+        state.tip_hash = [0x55; 32];
+        for (fill, value) in values {
+            state.utxos.insert(
+                rubin_consensus::Outpoint {
+                    txid: [*fill; 32],
+                    vout: 0,
+                },
+                rubin_consensus::UtxoEntry {
+                    value: *value,
+                    covenant_type: rubin_consensus::constants::COV_TYPE_P2PK,
+                    covenant_data: test_legacy_exposure_p2pk_covenant_data(0x01),
+                    creation_height: 1,
+                    created_by_coinbase: false,
+                },
+            );
+        }
+        state
+            .save(rubin_node::chain_state_path(dir))
+            .expect("save chainstate");
+        state
+    }
+
+    fn write_compare_other_summary(dir: &Path, state: &rubin_node::ChainState) -> PathBuf {
+        let path = dir.join("other-summary.json");
+        let summary = rubin_node::ChainStateSummary::from_chain_state(state, true);
+        fs::write(&path, serde_json::to_vec(&summary).expect("encode")).expect("write summary");
+        path
+    }
+
+    fn run_compare(dir: &Path, other: &Path, extra: &[&str]) -> (i32, Value, String) {
+        let mut args = vec![
+            "--datadir".to_string(),
+            dir.display().to_string(),
+            "--compare-chainstate".to_string(),
+            other.display().to_string(),
+        ];
+        args.extend(extra.iter().map(|arg| arg.to_string()));
+        let mut stdout = Vec::new();
+        let mut stderr = Vec::new();
+        let code = run_cli(&args, &mut stdout, &mut stderr);
+        let stderr = String::from_utf8_lossy(&stderr).into_owned();
+        let json = if stdout.is_empty() {
+            Value::Null
+        } else {
+            parse_effective_config_json(&stdout)
+        };
+        (code, json, stderr)
+    }
+
+    #[test]
+    fn devnet_subcommand_parses_flags_and_requires_blocks_and_datadir() {
+        let args: Vec<String> = ["--blocks", "5", "--datadir", "/tmp/devnet", "--json"]
+            .iter()
+            .map(|arg| arg.to_string())
+            .collect();
+        let cfg = parse_devnet_args(&args).expect("parse");
+        assert_eq!(cfg.blocks, 5);
+        assert_eq!(cfg.data_dir, PathBuf::from("/tmp/devnet"));
+        assert_eq!(cfg.seed, DEFAULT_DEVNET_HARNESS_SEED);
+
+        let args = vec![
+            "devnet".to_string(),
+            "--datadir".to_string(),
+            "/tmp/devnet".to_string(),
+            "--json".to_string(),
+        ];
+        let mut stdout = Vec::new();
+        let mut stderr = Vec::new();
+        let code = run_cli(&args, &mut stdout, &mut stderr);
+        assert_eq!(code, 2);
+        let envelope: Value = serde_json::from_slice(&stdout).expect("json envelope");
+        assert_eq!(
+            envelope["message"].as_str(),
+            Some("devnet requires --blocks <n>")
+        );
+    }
+
+    #[test]
+    fn policyinfo_json_mode_reports_fee_and_dust_defaults() {
+        let args = vec!["--policyinfo".to_string(), "--json".to_string()];
+        let mut stdout = Vec::new();
+        let mut stderr = Vec::new();
+
+        let code = run_cli(&args, &mut stdout, &mut stderr);
+        assert_eq!(code, 0, "stderr={}", String::from_utf8_lossy(&stderr));
+        let envelope: Value = serde_json::from_slice(&stdout).expect("json envelope");
+        assert_eq!(envelope["ok"].as_bool(), Some(true));
+        let info = &envelope["result"];
+        assert_eq!(
+            info["min_relay_fee_rate"].as_u64(),
+            Some(rubin_node::txpool::DEFAULT_MEMPOOL_MIN_FEE_RATE)
+        );
+        assert_eq!(info["dust_enforced"].as_bool(), Some(false));
+        let thresholds = info["dust_thresholds"].as_array().expect("thresholds");
+        assert_eq!(thresholds.len(), 4);
+        assert_eq!(thresholds[0]["covenant_type"].as_u64(), Some(0));
+        assert_eq!(
+            thresholds[0]["threshold"].as_u64(),
+            Some(rubin_node::policy_dust::dust_threshold(
+                0,
+                rubin_node::policy_dust::DEFAULT_DUST_RELAY_FEE_RATE
+            ))
+        );
+    }
+
+    #[test]
+    fn version_json_mode_emits_structured_build_info() {
+        let args = vec!["--version".to_string(), "--json".to_string()];
+        let mut stdout = Vec::new();
+        let mut stderr = Vec::new();
+
+        let code = run_cli(&args, &mut stdout, &mut stderr);
+        assert_eq!(code, 0, "stderr={}", String::from_utf8_lossy(&stderr));
+        assert!(stderr.is_empty());
+        let envelope: Value = serde_json::from_slice(&stdout).expect("json envelope");
+        assert_eq!(envelope["ok"].as_bool(), Some(true));
+        let info = &envelope["result"];
+        assert_eq!(
+            info["build_info_version"].as_u64(),
+            Some(rubin_node::BUILD_INFO_VERSION)
+        );
+        assert_eq!(
+            info["node_version"].as_str(),
+            Some(env!("CARGO_PKG_VERSION"))
+        );
+        assert!(info["consensus_version"].is_string());
+        assert!(info["profile"].is_string());
+        assert_eq!(info["crypto_provider"]["name"].as_str(), Some("openssl"));
+        assert_eq!(info["network"].as_str(), Some("devnet"));
+        assert_eq!(
+            info["chain_id_hex"].as_str(),
+            Some(hex::encode(rubin_node::devnet_genesis_chain_id()).as_str())
+        );
+        assert_eq!(
+            info["datadir_manifest_version"].as_u64(),
+            Some(u64::from(rubin_node::DATADIR_MANIFEST_VERSION))
+        );
+    }
+
+    #[test]
+    fn chainstate_summary_emits_tip_and_sorted_utxos() {
+        let dir = unique_temp_dir("rubin-node-bin-chainstate-summary");
+        let state = save_compare_fixture_state(&dir, &[(0x02, 20), (0x01, 10)]);
+        let args = vec![
+            "--datadir".to_string(),
+            dir.display().to_string(),
+            "--chainstate-summary".to_string(),
+            "--chainstate-summary-utxos".to_string(),
+        ];
+        let mut stdout = Vec::new();
+        let mut stderr = Vec::new();
+
+        let code = run_cli(&args, &mut stdout, &mut stderr);
+        assert_eq!(code, 0, "stderr={}", String::from_utf8_lossy(&stderr));
+        let json: Value = parse_effective_config_json(&stdout);
+        assert_eq!(json["tip_height"].as_u64(), Some(5));
+        assert_eq!(
+            json["tip_hash_hex"].as_str(),
+            Some(hex::encode([0x55; 32]).as_str())
+        );
+        assert_eq!(
+            json["utxo_set_hash_hex"].as_str(),
+            Some(hex::encode(state.utxo_set_hash()).as_str())
+        );
+        assert_eq!(json["utxo_count"].as_u64(), Some(2));
+        assert_eq!(json["utxos"][0]["value"].as_u64(), Some(10));
+        assert_eq!(json["utxos"][1]["value"].as_u64(), Some(20));
+
+        fs::remove_dir_all(&dir).expect("cleanup");
+    }
+
+    #[test]
+    fn compare_chainstate_exit_codes_distinguish_divergence() {
+        let dir = unique_temp_dir("rubin-node-bin-compare-chainstate");
+        let local = save_compare_fixture_state(&dir, &[(0x01, 10), (0x02, 20)]);
+
+        let other = write_compare_other_summary(&dir, &local);
+        let (code, json, stderr) = run_compare(&dir, &other, &[]);
+        assert_eq!(code, 0, "stderr={stderr}");
+        assert_eq!(json["status"].as_str(), Some("match"));
+
+        let mut diverged = local.clone();
+        diverged
+            .utxos
+            .values_mut()
+            .for_each(|entry| entry.value += 1);
+        let other = write_compare_other_summary(&dir, &diverged);
+        let (code, json, stderr) =
+            run_compare(&dir, &other, &["--compare-chainstate-max-diffs", "1"]);
+        assert_eq!(code, 8, "stderr={stderr}");
+        assert_eq!(json["status"].as_str(), Some("utxo_mismatch"));
+        assert_eq!(json["utxo_diffs"].as_array().map(Vec::len), Some(1));
+        assert_eq!(json["utxo_diffs"][0]["local"]["value"].as_u64(), Some(10));
+        assert_eq!(json["utxo_diffs"][0]["other"]["value"].as_u64(), Some(11));
+        assert_eq!(json["utxo_diffs_truncated"].as_bool(), Some(true));
+
+        diverged.height += 1;
+        let other = write_compare_other_summary(&dir, &diverged);
+        let (code, json, stderr) = run_compare(&dir, &other, &[]);
+        assert_eq!(code, 7, "stderr={stderr}");
+        assert_eq!(json["status"].as_str(), Some("tip_mismatch"));
+        assert_eq!(json["mismatched_fields"][0].as_str(), Some("tip_height"));
+
+        fs::remove_dir_all(&dir).expect("cleanup");
+    }
+
+    #[test]
+    fn compare_chainstate_rejects_malformed_other_summary() {
+        let dir = unique_temp_dir("rubin-node-bin-compare-chainstate-bad");
+        save_compare_fixture_state(&dir, &[(0x01, 10)]);
+        let other = dir.join("other-summary.json");
+        fs::write(&other, b"{\"tip_height\":1}").expect("write summary");
+        let (code, _, stderr) = run_compare(&dir, &other, &[]);
+        assert_eq!(code, 3);
+        assert!(stderr.contains("compare chainstate: invalid other summary"));
+
+        fs::remove_dir_all(&dir).expect("cleanup");
+    }
+
+    #[test]
+    fn chainstate_tool_flags_validate_parent_flag_and_exclusivity() {
+        let cases: [(&[&str], &str); 20] = [
+            (
+                &["--get-block-index", "0x11"],
+                "invalid value for --get-block-index",
+            ),
+            (
+                &["--get-block-index", &"11".repeat(32), "--verifychain"],
+                "are mutually exclusive",
+            ),
+            (
+                &["--verifychain-level", "3"],
+                "--verifychain-level requires --verifychain",
+            ),
+            (
+                &["--verifychain", "--verifychain-level", "4"],
+                "invalid value for --verifychain-level",
+            ),
+            (
+                &["--verifychain", "--chainstate-summary"],
+                "are mutually exclusive",
+            ),
+            (
+                &["--chainstate-summary-utxos"],
+                "--chainstate-summary-utxos requires --chainstate-summary",
+            ),
+            (
+                &["--compare-chainstate-max-diffs", "3"],
+                "--compare-chainstate-max-diffs requires --compare-chainstate",
+            ),
+            (
+                &["--chainstate-summary", "--compare-chainstate", "x.json"],
+                "are mutually exclusive",
+            ),
+            (
+                &["--chainstate-summary", "--block-stats", &"11".repeat(32)],
+                "are mutually exclusive",
+            ),
+            (
+                &["--block-stats", "0x11"],
+                "invalid value for --block-stats",
+            ),
+            (
+                &["--get-block-verbosity", "1"],
+                "--get-block-verbosity requires --get-block",
+            ),
+            (
+                &[
+                    "--get-block",
+                    &"11".repeat(32),
+                    "--get-block-verbosity",
+                    "3",
+                ],
+                "invalid value for --get-block-verbosity",
+            ),
+            (
+                &[
+                    "--get-block",
+                    &"11".repeat(32),
+                    "--force-reorg",
+                    &"22".repeat(32),
+                ],
+                "are mutually exclusive",
+            ),
+            (
+                &["--force-reorg", "0x11"],
+                "invalid value for --force-reorg",
+            ),
+            (
+                &["--featurebits", "X"],
+                "--featurebits and --featurebits-deployments must be used together",
+            ),
+            (
+                &[
+                    "--featurebits",
+                    "X",
+                    "--featurebits-deployments",
+                    "d.json",
+                    "--get-block",
+                    &"11".repeat(32),
+                ],
+                "are mutually exclusive",
+            ),
+            (
+                &["--verify-tx", "00"],
+                "--verify-tx requires --verify-prevouts and --verify-height",
+            ),
+            (
+                &[
+                    "--verify-tx",
+                    "00",
+                    "--verify-tx-file",
+                    "tx.hex",
+                    "--verify-prevouts",
+                    "p.json",
+                    "--verify-height",
+                    "1",
+                ],
+                "--verify-tx and --verify-tx-file are mutually exclusive",
+            ),
+            (
+                &["--verify-coinbase", "00"],
+                "--verify-coinbase requires --coinbase-height",
+            ),
+            (
+                &["--coinbase-fees", "1"],
+                "--coinbase-fees and --coinbase-already-generated require --verify-coinbase",
+            ),
+        ];
+        for (extra, want) in cases {
+            let args: Vec<String> = extra.iter().map(|arg| arg.to_string()).collect();
+            let mut stdout = Vec::new();
+            let mut stderr = Vec::new();
+            let code = run_cli(&args, &mut stdout, &mut stderr);
+            assert_eq!(code, 2, "{extra:?}");
+            let stderr = String::from_utf8_lossy(&stderr);
+            assert!(stderr.contains(want), "{extra:?}: {stderr}");
+        }
+    }
+
+    #[test]
+    fn block_stats_reports_canonical_cumulative_and_side_blocks() {
+        let dir = unique_temp_dir("rubin-node-bin-block-stats");
+        let mut store = rubin_node::BlockStore::open(rubin_node::block_store_path(&dir))
+            .expect("open blockstore");
+        let stats = |fees: u64| rubin_node::BlockStats {
+            total_fees: fees,
+            total_weight: 100 + fees,
+            anchor_bytes: 0,
+            tx_count: 2,
+            coinbase_value: 50 + fees,
+        };
+        for (height, fill) in [(0u64, 0xA0u8), (1, 0xA1)] {
+            store.set_canonical_tip(height, [fill; 32]).expect("tip");
+            store
+                .put_block_stats([fill; 32], &stats(height + 1))
+                .expect("put stats");
+        }
+        store
+            .put_block_stats([0xB1; 32], &stats(9))
+            .expect("put side stats");
+        let run_stats = |hash: [u8; 32]| {
+            let args = vec![
+                "--datadir".to_string(),
+                dir.display().to_string(),
+                "--block-stats".to_string(),
+                hex::encode(hash),
+            ];
+            let mut stdout = Vec::new();
+            let mut stderr = Vec::new();
+            let code = run_cli(&args, &mut stdout, &mut stderr);
+            (code, stdout, String::from_utf8_lossy(&stderr).into_owned())
+        };
+
+        let (code, stdout, stderr) = run_stats([0xA1; 32]);
+        assert_eq!(code, 0, "stderr={stderr}");
+        let json = parse_effective_config_json(&stdout);
+        assert_eq!(json["height"].as_u64(), Some(1));
+        assert_eq!(json["stats"]["total_fees"].as_u64(), Some(2));
+        assert_eq!(json["cumulative"]["total_fees"].as_u64(), Some(3));
+        assert_eq!(json["cumulative"]["tx_count"].as_u64(), Some(4));
+        assert_eq!(json["cumulative"]["coinbase_value"].as_u64(), Some(103));
+
+        let (code, stdout, stderr) = run_stats([0xB1; 32]);
+        assert_eq!(code, 0, "stderr={stderr}");
+        let json = parse_effective_config_json(&stdout);
+        assert!(json["height"].is_null());
+        assert!(json["cumulative"].is_null());
+        assert_eq!(json["stats"]["total_fees"].as_u64(), Some(9));
+
+        let (code, _, stderr) = run_stats([0xCC; 32]);
+        assert_eq!(code, 5);
+        assert!(stderr.contains("no stats recorded for block"), "{stderr}");
+
+        fs::remove_dir_all(&dir).expect("cleanup");
+    }
+
+    #[test]
+    fn verify_tx_reports_missing_prevout_by_input_index() {
+        use rubin_consensus::constants::{
+            COV_TYPE_P2PK, ML_DSA_87_PUBKEY_BYTES, ML_DSA_87_SIG_BYTES, SUITE_ID_ML_DSA_87,
+            TX_WIRE_VERSION,
+        };
+        use rubin_consensus::{p2pk_covenant_data_for_pubkey, Tx, TxInput, TxOutput, WitnessItem};
+
+        let dir = unique_temp_dir("rubin-node-bin-verify-tx");
+        fs::create_dir_all(&dir).expect("mkdir");
+        let p2pk_cov = p2pk_covenant_data_for_pubkey(&[0x42; ML_DSA_87_PUBKEY_BYTES as usize]);
+        let input = |seed: u8| TxInput {
+            prev_txid: [seed; 32],
+            prev_vout: 0,
+            script_sig: Vec::new(),
+            sequence: 0,
+        };
+        let witness = WitnessItem {
+            suite_id: SUITE_ID_ML_DSA_87,
+            pubkey: vec![0x42; ML_DSA_87_PUBKEY_BYTES as usize],
+            signature: vec![0; ML_DSA_87_SIG_BYTES as usize + 1],
+        };
+        let tx = Tx {
+            version: TX_WIRE_VERSION,
+            tx_kind: 0x00,
+            tx_nonce: 1,
+            inputs: vec![input(0x01), input(0x02)],
+            outputs: vec![TxOutput {
+                value: 50,
+                covenant_type: COV_TYPE_P2PK,
+                covenant_data: p2pk_cov.clone(),
+            }],
+            locktime: 0,
+            da_commit_core: None,
+            da_chunk_core: None,
+            witness: vec![witness.clone(), witness],
+            da_payload: Vec::new(),
+        };
+        let tx_file = dir.join("tx.hex");
+        fs::write(
+            &tx_file,
+            format!(
+                "{}\n",
+                hex::encode(rubin_consensus::marshal_tx(&tx).expect("marshal"))
+            ),
+        )
+        .expect("write tx");
+        let prevouts = dir.join("prevouts.json");
+        fs::write(
+            &prevouts,
+            format!(
+                "{{\"0\":{{\"value\":100,\"covenant_type\":{COV_TYPE_P2PK},\"covenant_data\":\"{}\",\"creation_height\":1,\"created_by_coinbase\":false}}}}",
+                hex::encode(&p2pk_cov)
+            ),
+        )
+        .expect("write prevouts");
+
+        let args: Vec<String> = [
+            "--json",
+            "--verify-tx-file",
+            &tx_file.display().to_string(),
+            "--verify-prevouts",
+            &prevouts.display().to_string(),
+            "--verify-height",
+            "10",
+        ]
+        .iter()
+        .map(|arg| arg.to_string())
+        .collect();
+        let mut stdout = Vec::new();
+        let mut stderr = Vec::new();
+        let code = run_cli(&args, &mut stdout, &mut stderr);
+        assert_eq!(code, 4, "stderr={}", String::from_utf8_lossy(&stderr));
+        let json: Value = serde_json::from_slice(&stdout).expect("envelope");
+        assert_eq!(json["ok"], false);
+        assert_eq!(json["error_code"], "TX_ERR_MISSING_UTXO");
+        let report = &json["result"];
+        assert_eq!(report["valid"], false);
+        assert_eq!(report["error_code"], "TX_ERR_MISSING_UTXO");
+        assert_eq!(report["error_input"], 1);
+        assert_eq!(report["inputs"][1]["status"], "failed");
+        assert_eq!(report["inputs"][1]["error_code"], "TX_ERR_MISSING_UTXO");
+
+        fs::remove_dir_all(&dir).expect("cleanup");
+    }
+
+    #[test]
+    fn verify_coinbase_json_mode_names_failing_rule() {
+        let tx_bytes =
+            rubin_node::build_coinbase_tx(2, 0, &rubin_node::default_mine_address(), [0x11; 32])
+                .expect("build coinbase");
+        let run_json = |height: &str| {
+            let args: Vec<String> = [
+                "--json",
+                "--verify-coinbase",
+                &hex::encode(&tx_bytes),
+                "--coinbase-height",
+                height,
+                "--coinbase-already-generated",
+                "0",
+            ]
+            .iter()
+            .map(|arg| arg.to_string())
+            .collect();
+            let mut stdout = Vec::new();
+            let mut stderr = Vec::new();
+            let code = run_cli(&args, &mut stdout, &mut stderr);
+            let json: Value = serde_json::from_slice(&stdout).expect("envelope");
+            (code, json)
+        };
+
+        let (code, json) = run_json("2");
+        assert_eq!(code, 0, "{json}");
+        assert_eq!(json["result"]["valid"], true);
+        assert_eq!(json["result"]["already_generated"], 0);
+        assert_eq!(json["result"]["output_sum"], json["result"]["value_limit"]);
+
+        let (code, json) = run_json("3");
+        assert_eq!(code, 4);
+        assert_eq!(json["ok"], false);
+        assert_eq!(json["result"]["error_code"], "BLOCK_ERR_COINBASE_INVALID");
+        assert_eq!(
+            json["result"]["error"],
+            "coinbase locktime must equal block height"
+        );
+    }
+
+    #[test]
+    fn featurebits_reports_cli_op_fields_from_stored_headers() {
+        let dir = unique_temp_dir("rubin-node-bin-featurebits");
+        let mut store = rubin_node::BlockStore::open(rubin_node::block_store_path(&dir))
+            .expect("open blockstore");
+        let mut prev_block_hash = [0u8; 32];
+        for height in 0..3u64 {
+            let header = rubin_consensus::BlockHeader {
+                version: 1 | 1 << 4,
+                prev_block_hash,
+                merkle_root: [0x5a; 32],
+                timestamp: 1_000_000 + height * 120,
+                target: rubin_consensus::constants::POW_LIMIT,
+                nonce: height,
+            }
+            .to_bytes();
+            prev_block_hash = rubin_consensus::block_hash(&header).expect("hash");
+            store
+                .put_block(height, prev_block_hash, &header, &header)
+                .expect("put header");
+        }
+        let deployments = dir.join("deployments.json");
+        fs::write(
+            &deployments,
+            br#"[{"name":"X","bit":4,"start_height":0,"timeout_height":4032,"activation_height":10}]"#,
+        )
+        .expect("write deployments");
+        let run_featurebits = |name: &str| {
+            let args = vec![
+                "--datadir".to_string(),
+                dir.display().to_string(),
+                "--featurebits".to_string(),
+                name.to_string(),
+                "--featurebits-deployments".to_string(),
+                deployments.display().to_string(),
+            ];
+            let mut stdout = Vec::new();
+            let mut stderr = Vec::new();
+            let code = run_cli(&args, &mut stdout, &mut stderr);
+            (code, stdout, String::from_utf8_lossy(&stderr).into_owned())
+        };
+
+        let (code, stdout, stderr) = run_featurebits("X");
+        assert_eq!(code, 0, "stderr={stderr}");
+        let json = parse_effective_config_json(&stdout);
+        assert_eq!(json["height"].as_u64(), Some(3));
+        assert_eq!(json["state"].as_str(), Some("STARTED"));
+        assert_eq!(json["boundary_height"].as_u64(), Some(0));
+        assert_eq!(json["prev_window_signal_count"].as_u64(), Some(0));
+        assert_eq!(json["signal_window"].as_u64(), Some(2016));
+        assert_eq!(json["signal_threshold"].as_u64(), Some(1815));
+        assert!(json["estimated_activation_height"].is_null());
+        assert_eq!(json["activation_height"].as_u64(), Some(10));
+        assert_eq!(json["consensus_active"].as_bool(), Some(false));
+
+        let (code, _, stderr) = run_featurebits("Y");
+        assert_eq!(code, 2);
+        assert!(
+            stderr.contains("unknown featurebits deployment: Y"),
+            "{stderr}"
+        );
+
+        fs::remove_dir_all(&dir).expect("cleanup");
+    }
+
+    #[test]
+    fn json_flag_wraps_report_and_failures_in_one_line_envelope() {
+        let dir = unique_temp_dir("rubin-node-bin-json-output");
+        let mut store = rubin_node::BlockStore::open(rubin_node::block_store_path(&dir))
+            .expect("open blockstore");
+        store.set_canonical_tip(0, [0xA0; 32]).expect("tip");
+        store
+            .put_block_stats(
+                [0xA0; 32],
+                &rubin_node::BlockStats {
+                    total_fees: 7,
+                    total_weight: 100,
+                    anchor_bytes: 0,
+                    tx_count: 1,
+                    coinbase_value: 57,
+                },
+            )
+            .expect("put stats");
+        let run_json = |extra: &[&str]| {
+            let mut args = vec![
+                "--datadir".to_string(),
+                dir.display().to_string(),
+                "--json".to_string(),
+            ];
+            args.extend(extra.iter().map(|arg| arg.to_string()));
+            let mut stdout = Vec::new();
+            let mut stderr = Vec::new();
+            let code = run_cli(&args, &mut stdout, &mut stderr);
+            let stdout = String::from_utf8(stdout).expect("utf8");
+            assert!(stderr.is_empty(), "{}", String::from_utf8_lossy(&stderr));
+            assert_eq!(stdout.lines().count(), 1, "{stdout}");
+            let json: serde_json::Value = serde_json::from_str(&stdout).expect("envelope");
+            (code, json)
+        };
+
+        let (code, json) = run_json(&["--block-stats", &"a0".repeat(32)]);
+        assert_eq!(code, 0);
+        assert_eq!(json["ok"], true);
+        assert!(json["error_code"].is_null());
+        assert_eq!(json["result"]["height"].as_u64(), Some(0));
+        assert_eq!(json["result"]["stats"]["total_fees"].as_u64(), Some(7));
+
+        let (code, json) = run_json(&["--block-stats", &"cc".repeat(32)]);
+        assert_eq!(code, 5);
+        assert_eq!(json["ok"], false);
+        assert!(json["result"].is_null());
+        assert!(json["message"]
+            .as_str()
+            .is_some_and(|message| message.contains("no stats recorded for block")));
+
+        let (code, json) = run_json(&["--block-stats", "0x11"]);
+        assert_eq!(code, 2);
+        assert_eq!(
+            json["message"],
+            "invalid value for --block-stats: expected 32 bytes, got 1"
+        );
+
+        fs::remove_dir_all(&dir).expect("cleanup");
+    }
+
+    #[test]
+    fn get_block_renders_requested_verbosity() {
+        let dir = unique_temp_dir("rubin-node-bin-get-block");
+        let mut store = rubin_node::BlockStore::open(rubin_node::block_store_path(&dir))
+            .expect("open blockstore");
+        let genesis = rubin_node::devnet_genesis_block_bytes();
+        let header = &genesis[..rubin_consensus::BLOCK_HEADER_BYTES];
+        let hash = rubin_consensus::block_hash(header).expect("hash");
+        store
+            .put_block(0, hash, header, &genesis)
+            .expect("put genesis");
+        let run_get_block = |extra: &[&str]| {
+            let mut args = vec![
+                "--datadir".to_string(),
+                dir.display().to_string(),
+                "--get-block".to_string(),
+                hex::encode(hash),
+            ];
+            args.extend(extra.iter().map(|arg| arg.to_string()));
+            let mut stdout = Vec::new();
+            let mut stderr = Vec::new();
+            let code = run_cli(&args, &mut stdout, &mut stderr);
+            assert_eq!(code, 0, "stderr={}", String::from_utf8_lossy(&stderr));
+            parse_effective_config_json(&stdout)
+        };
+
+        let level0 = run_get_block(&[]);
+        assert_eq!(
+            level0["block_hex"].as_str(),
+            Some(hex::encode(&genesis).as_str())
+        );
+        let level1 = run_get_block(&["--get-block-verbosity", "1"]);
+        assert_eq!(level1["height"].as_u64(), Some(0));
+        assert_eq!(level1["size"].as_u64(), Some(genesis.len() as u64));
+        assert!(level1.get("tx").is_none());
+        // Genesis has no undo record, so level 2 decodes txs without fees.
+        let level2 = run_get_block(&["--get-block-verbosity", "2"]);
+        assert_eq!(level2["tx"][0]["txid"], level1["txids"][0]);
+        assert!(level2["tx"][0].get("fee").is_none());
+
+        fs::remove_dir_all(&dir).expect("cleanup");
+    }
+
     #[test]
     fn invalid_genesis_file_is_rejected_before_datadir_create() {
         let dir = unique_temp_dir("rubin-node-bin-invalid-genesis-before-datadir");
@@ -3054,8 +5277,8 @@ mod tests {
         let mut stdout = Vec::new();
         let mut stderr = Vec::new();
 
-        let code = run(&args, &mut stdout, &mut stderr);
-        assert_eq!(code, 2);
+        let code = run_cli(&args, &mut stdout, &mut stderr);
+        assert_eq!(code, 3);
         let stderr = String::from_utf8_lossy(&stderr);
         assert!(stderr.contains("invalid genesis file:"));
         assert!(!stderr.contains("datadir create failed"));
@@ -3076,7 +5299,7 @@ mod tests {
         let mut stdout = Vec::new();
         let mut stderr = Vec::new();
 
-        let code = run(&args, &mut stdout, &mut stderr);
+        let code = run_cli(&args, &mut stdout, &mut stderr);
         assert_eq!(code, 0, "stderr={}", String::from_utf8_lossy(&stderr));
         let out = String::from_utf8(stdout).expect("utf8");
         assert!(out.contains("\"mine_blocks\": 2"), "stdout={out}");
@@ -3086,6 +5309,143 @@ mod tests {
         fs::remove_dir_all(&dir).expect("cleanup");
     }
 
+    #[test]
+    fn exportblocks_then_importblocks_into_wiped_datadir_restores_utxo_set() {
+        let dir = unique_temp_dir("rubin-node-bin-block-files");
+        let datadir = dir.join("node").display().to_string();
+        let files = dir.join("files").display().to_string();
+        let run_json = |args: &[&str]| -> Value {
+            let mut args: Vec<String> = args.iter().map(|arg| arg.to_string()).collect();
+            args.push("--json".to_string());
+            let mut stdout = Vec::new();
+            let mut stderr = Vec::new();
+            let code = run_cli(&args, &mut stdout, &mut stderr);
+            assert_eq!(code, 0, "stdout={}", String::from_utf8_lossy(&stdout));
+            serde_json::from_slice(&stdout).expect("json envelope")
+        };
+        let mut stdout = Vec::new();
+        let mut stderr = Vec::new();
+        let mine = ["--datadir", &datadir, "--mine-blocks", "5", "--mine-exit"];
+        let mine: Vec<String> = mine.iter().map(|arg| arg.to_string()).collect();
+        assert_eq!(run_cli(&mine, &mut stdout, &mut stderr), 0);
+
+        let exported = run_json(&[
+            "exportblocks",
+            "--datadir",
+            &datadir,
+            "--to",
+            &files,
+            "--blocks-per-file",
+            "2",
+        ]);
+        assert_eq!(exported["result"]["blocks"].as_u64(), Some(5));
+        assert_eq!(
+            exported["result"]["files"].as_array().map(Vec::len),
+            Some(3)
+        );
+
+        fs::remove_dir_all(dir.join("node")).expect("wipe");
+        let imported = run_json(&["importblocks", "--datadir", &datadir, "--from", &files]);
+        assert_eq!(imported["result"]["tip_height"].as_u64(), Some(4));
+        assert_eq!(
+            imported["result"]["utxo_set_hash"],
+            exported["result"]["utxo_set_hash"]
+        );
+        assert_eq!(
+            imported["result"]["tip_hash"],
+            exported["result"]["tip_hash"]
+        );
+
+        fs::remove_dir_all(&dir).expect("cleanup");
+    }
+
+    #[test]
+    fn getchaintips_reports_the_mined_tip() {
+        let dir = unique_temp_dir("rubin-node-bin-chain-tips");
+        let datadir = dir.display().to_string();
+        let mut stdout = Vec::new();
+        let mut stderr = Vec::new();
+        let mine = ["--datadir", &datadir, "--mine-blocks", "3", "--mine-exit"];
+        let mine: Vec<String> = mine.iter().map(|arg| arg.to_string()).collect();
+        assert_eq!(run_cli(&mine, &mut stdout, &mut stderr), 0);
+
+        let args: Vec<String> = ["getchaintips", "--datadir", &datadir, "--json"]
+            .iter()
+            .map(|arg| arg.to_string())
+            .collect();
+        let mut stdout = Vec::new();
+        assert_eq!(run_cli(&args, &mut stdout, &mut stderr), 0);
+        let envelope: Value = serde_json::from_slice(&stdout).expect("json envelope");
+        let tips = envelope["result"].as_array().expect("tips");
+        assert_eq!(tips.len(), 1);
+        assert_eq!(tips[0]["status"], "active");
+        assert_eq!(tips[0]["height"].as_u64(), Some(2));
+
+        let args: Vec<String> = ["getchaintips", "--bogus"]
+            .iter()
+            .map(|arg| arg.to_string())
+            .collect();
+        assert_eq!(run_cli(&args, &mut Vec::new(), &mut Vec::new()), 2);
+
+        fs::remove_dir_all(&dir).expect("cleanup");
+    }
+
+    #[test]
+    fn gettxstatus_reports_the_saved_broadcast_state() {
+        let dir = unique_temp_dir("rubin-node-bin-tx-status");
+        let datadir = dir.display().to_string();
+        let mut mgr = BroadcastManager::new(BroadcastConfig::default());
+        mgr.submit([0x11; 32], &[0x01], 3, 1_000);
+        mgr.on_block_connected(4, [0x22; 32], &[[0x11; 32]]);
+        mgr.save(broadcast_state_path(&dir)).expect("save");
+
+        let run = |txid: &str| {
+            let args: Vec<String> = [
+                "gettxstatus",
+                "--datadir",
+                &datadir,
+                "--txid",
+                txid,
+                "--json",
+            ]
+            .iter()
+            .map(|arg| arg.to_string())
+            .collect();
+            let mut stdout = Vec::new();
+            let code = run_cli(&args, &mut stdout, &mut Vec::new());
+            (code, stdout)
+        };
+        let (code, stdout) = run(&"11".repeat(32));
+        assert_eq!(code, 0);
+        let envelope: Value = serde_json::from_slice(&stdout).expect("json envelope");
+        assert_eq!(envelope["result"]["state"], "confirmed");
+        assert_eq!(envelope["result"]["height"].as_u64(), Some(4));
+
+        assert_eq!(run(&"33".repeat(32)).0, 3);
+        assert_eq!(run("not-hex").0, 2);
+        fs::remove_dir_all(&dir).expect("cleanup");
+    }
+
+    #[test]
+    fn parse_args_accepts_broadcast_flags() {
+        let cfg = parse_args(&[
+            "--rebroadcast-interval".to_string(),
+            "30".to_string(),
+            "--broadcast-expiry".to_string(),
+            "600".to_string(),
+        ])
+        .expect("parse");
+        assert_eq!(
+            cfg.broadcast,
+            BroadcastConfig {
+                rebroadcast_interval_secs: 30,
+                expiry_secs: 600,
+            }
+        );
+        let err = parse_args(&["--broadcast-expiry".to_string(), "0".to_string()]).unwrap_err();
+        assert_eq!(err, "invalid value for --broadcast-expiry");
+    }
+
     #[test]
     fn validate_addr_rejects_extra_colons() {
         let r = super::validate_addr("test", "foo:bar:80");
@@ -1,15 +1,15 @@
-use std::collections::{HashMap, HashSet};
+use std::collections::HashSet;
 use std::time::{SystemTime, UNIX_EPOCH};
 
 use rubin_consensus::constants::{
     COV_TYPE_DA_COMMIT, MAX_BLOCK_WEIGHT, MAX_DA_BATCHES_PER_BLOCK, MAX_DA_BYTES_PER_BLOCK,
-    MAX_DA_CHUNK_COUNT, MAX_FUTURE_DRIFT, POW_LIMIT,
+    MAX_DA_CHUNK_COUNT, POW_LIMIT,
 };
 use rubin_consensus::merkle::{witness_commitment_hash, witness_merkle_root_wtxids};
 use rubin_consensus::{
-    apply_non_coinbase_tx_basic_update_with_mtp_and_core_ext_profiles_and_suite_context as apply_basic_non_coinbase_update,
-    encode_compact_size, merkle_root_txids, parse_tx, pow_check, tx_weight_and_stats_public,
-    Outpoint, Tx, UtxoEntry,
+    apply_non_coinbase_tx_basic_update_with_mtp_and_core_ext_profiles_and_suite_context_and_maturity as apply_basic_non_coinbase_update,
+    check_block_timestamp, classify_tx, encode_compact_size, merkle_root_txids, parse_tx,
+    pow_check, tx_weight_and_stats_public, Outpoint, OutpointMap, Tx, UtxoEntry,
 };
 use sha3::{Digest, Sha3_256};
 
@@ -75,6 +75,51 @@ pub struct MinedBlock {
     pub tx_count: usize,
 }
 
+/// A block with everything but its nonce fixed, built by
+/// `Miner::block_template`. The nonce search only reads the template, so a
+/// caller can hand it to a search and replace it wholesale when it goes
+/// stale.
+#[derive(Clone, Debug)]
+pub struct BlockTemplate {
+    pub height: u64,
+    pub prev_hash: [u8; 32],
+    pub timestamp: u64,
+    pub target: [u8; 32],
+    /// Mempool fees of the selected transactions; 0 without a pool.
+    pub fees: u64,
+    header_prefix: Vec<u8>,
+    coinbase: Vec<u8>,
+    candidates: Vec<MinedCandidate>,
+    prev_timestamps: Option<Vec<u64>>,
+}
+
+impl BlockTemplate {
+    pub fn tx_count(&self) -> usize {
+        1 + self.candidates.len()
+    }
+
+    /// Tries nonces `start..start + count` and returns the first one whose
+    /// header meets the target.
+    pub fn search_nonces(&self, start: u64, count: u64) -> Option<u64> {
+        let mut header = self.header_prefix.clone();
+        header.extend_from_slice(&[0u8; 8]);
+        let nonce_at = self.header_prefix.len();
+        (0..count)
+            .map_while(|offset| start.checked_add(offset))
+            .find(|nonce| {
+                header[nonce_at..].copy_from_slice(&nonce.to_le_bytes());
+                pow_check(&header, self.target).is_ok()
+            })
+    }
+
+    /// The full block with `nonce` filled in.
+    pub fn block_bytes(&self, nonce: u64) -> Vec<u8> {
+        let mut header = self.header_prefix.clone();
+        header.extend_from_slice(&nonce.to_le_bytes());
+        assemble_block_bytes(&header, &self.coinbase, &self.candidates)
+    }
+}
+
 #[derive(Clone, Debug)]
 pub(crate) struct MinedCandidate {
     raw: Vec<u8>,
@@ -155,6 +200,26 @@ impl<'a> Miner<'a> {
     }
 
     pub fn mine_one(&mut self, txs: &[Vec<u8>]) -> Result<MinedBlock, String> {
+        let template = self.block_template(txs)?;
+        let (header_bytes, nonce) = mine_header_nonce(&template.header_prefix, template.target)?;
+        let block_bytes =
+            assemble_block_bytes(&header_bytes, &template.coinbase, &template.candidates);
+        let summary = self
+            .sync
+            .apply_block(&block_bytes, template.prev_timestamps.as_deref())?;
+        self.evict_confirmed_from_pool(&template.candidates);
+        Ok(MinedBlock {
+            height: summary.block_height,
+            hash: summary.block_hash,
+            timestamp: template.timestamp,
+            nonce,
+            tx_count: template.tx_count(),
+        })
+    }
+
+    /// Builds the next block on the current tip, leaving only the nonce to
+    /// search. Nothing is applied; see `BlockTemplate`.
+    pub fn block_template(&self, txs: &[Vec<u8>]) -> Result<BlockTemplate, String> {
         let next_height = if self.sync.chain_state.has_tip {
             self.sync
                 .chain_state
@@ -201,20 +266,22 @@ impl<'a> Miner<'a> {
             txids.push(candidate.txid);
         }
         let merkle_root = merkle_root_txids(&txids).map_err(|e| e.to_string())?;
-        let block_without_nonce =
-            make_header_prefix(prev_hash, merkle_root, timestamp, self.cfg.target);
-        let (header_bytes, nonce) = mine_header_nonce(&block_without_nonce, self.cfg.target)?;
-        let block_bytes = assemble_block_bytes(&header_bytes, &coinbase, &parsed);
-        let summary = self
-            .sync
-            .apply_block(&block_bytes, prev_timestamps.as_deref())?;
-        self.evict_confirmed_from_pool(&parsed);
-        Ok(MinedBlock {
-            height: summary.block_height,
-            hash: summary.block_hash,
+        let fees = self.tx_pool.as_deref().map_or(0, |pool| {
+            parsed
+                .iter()
+                .filter_map(|candidate| pool.entry_fee(&candidate.txid))
+                .sum()
+        });
+        Ok(BlockTemplate {
+            height: next_height,
+            prev_hash,
             timestamp,
-            nonce,
-            tx_count: 1 + parsed.len(),
+            target: self.cfg.target,
+            fees,
+            header_prefix: make_header_prefix(prev_hash, merkle_root, timestamp, self.cfg.target),
+            coinbase,
+            candidates: parsed,
+            prev_timestamps,
         })
     }
 
@@ -279,13 +346,25 @@ impl<'a> Miner<'a> {
         let mut selected_da_ids = HashSet::new();
         let mut selected_nonces = HashSet::new();
         let mut selected_inputs = HashSet::new();
+        // Candidates seen but not (yet) selected. Pool selection emits
+        // parents before children, so a child whose parent is in here
+        // would spend an output the block does not create.
+        let mut unselected = HashSet::new();
         let provider_enabled = self.complete_da_set_provider.is_some();
         for raw in candidate_txs {
             if parsed.len() >= max_selected {
                 break;
             }
             let candidate = parse_mining_candidate(&raw)?;
-            if provider_enabled && matches!(candidate.tx.tx_kind, 0x01 | 0x02) {
+            unselected.insert(candidate.txid);
+            let inputs = &candidate.tx.inputs;
+            if inputs
+                .iter()
+                .any(|input| unselected.contains(&input.prev_txid))
+            {
+                continue;
+            }
+            if provider_enabled && classify_tx(&candidate.tx) != Ok(None) {
                 continue;
             }
             let (reject, next_da_included) =
@@ -305,6 +384,7 @@ impl<'a> Miner<'a> {
             policy_da_included = next_da_included;
             selected_nonces.insert(candidate.tx.tx_nonce);
             selected_inputs.extend(candidate_inputs);
+            unselected.remove(&candidate.txid);
             parsed.push(candidate);
         }
         let Some(provider) = self.complete_da_set_provider else {
@@ -326,7 +406,9 @@ impl<'a> Miner<'a> {
             if parsed.len() >= max_selected || selected_da_batches >= MAX_DA_BATCHES_PER_BLOCK {
                 break;
             }
-            if selected_da_ids.contains(&set.da_id) {
+            // checkblock: a set the provider no longer reports complete (commit
+            // plus every chunk) is never included, even if it was listed.
+            if selected_da_ids.contains(&set.da_id) || !provider.is_complete(set.da_id) {
                 continue;
             }
             let group_len = set.chunks.len().saturating_add(1);
@@ -383,7 +465,7 @@ impl<'a> Miner<'a> {
     fn reject_candidate_with_utxos(
         &self,
         tx: &Tx,
-        utxos: &HashMap<Outpoint, UtxoEntry>,
+        utxos: &OutpointMap<UtxoEntry>,
         next_height: u64,
         policy_da_included: u64,
     ) -> Result<(bool, u64), String> {
@@ -405,6 +487,7 @@ impl<'a> Miner<'a> {
                 .cfg
                 .policy_reject_simplicity_pre_activation,
             suite_context: self.sync.cfg.suite_context.clone(),
+            chain_params: self.sync.cfg.chain_params,
             policy_current_mempool_min_fee_rate: if self.cfg.policy_da_anchor_anti_abuse {
                 self.cfg.policy_current_mempool_min_fee_rate
             } else {
@@ -415,6 +498,17 @@ impl<'a> Miner<'a> {
             } else {
                 0
             },
+            // Relay-only anchor, dust, package and script_sig caps and the
+            // ranking surcharge are mempool concerns; `apply_policy` does
+            // not read them.
+            policy_max_relay_anchor_bytes_per_tx: 0,
+            policy_max_anchors_per_tx: 0,
+            policy_anchor_fee_rate_multiplier: 0,
+            policy_dust_relay_fee_rate: 0,
+            policy_max_ancestors: 0,
+            policy_max_descendants: 0,
+            policy_max_package_weight: 0,
+            policy_max_script_sig_bytes: 0,
         };
         // RUB-167 single-walk invariant: extract weight + da_bytes once
         // here and reuse via `apply_policy` (which forwards into
@@ -497,10 +591,7 @@ impl<'a> Miner<'a> {
         group_inputs: &[Outpoint],
         next_height: u64,
         block_mtp: u64,
-        mut before_apply: impl FnMut(
-            &MinedCandidate,
-            &HashMap<Outpoint, UtxoEntry>,
-        ) -> Result<bool, String>,
+        mut before_apply: impl FnMut(&MinedCandidate, &OutpointMap<UtxoEntry>) -> Result<bool, String>,
     ) -> Result<bool, String> {
         let (rotation, registry) = self.sync.suite_context();
         let mut work_utxos = copy_selected_utxo_set(&self.sync.chain_state.utxos, group_inputs);
@@ -518,6 +609,7 @@ impl<'a> Miner<'a> {
                 self.sync.cfg.chain_id,
                 rotation,
                 registry,
+                self.sync.cfg.chain_params.coinbase_maturity,
             );
             let Ok((next_utxos, _summary)) = next else {
                 return Ok(false);
@@ -558,10 +650,10 @@ pub(crate) fn collect_complete_da_set_group_inputs(
 
 #[allow(dead_code)]
 pub(crate) fn copy_selected_utxo_set(
-    utxos: &HashMap<Outpoint, UtxoEntry>,
+    utxos: &OutpointMap<UtxoEntry>,
     inputs: &[Outpoint],
-) -> HashMap<Outpoint, UtxoEntry> {
-    let mut selected = HashMap::new();
+) -> OutpointMap<UtxoEntry> {
+    let mut selected = OutpointMap::default();
     for input in inputs {
         if let Some(entry) = utxos.get(input) {
             selected.insert(input.clone(), entry.clone());
@@ -695,9 +787,8 @@ fn updated_policy_da_bytes(current: u64, da_bytes: u64, max_per_block: u64) -> O
 
 fn is_mining_da_tx_raw(raw: &[u8]) -> bool {
     matches!(raw.get(4).copied(), Some(0x01) | Some(0x02))
-        && parse_tx(raw).is_ok_and(|(tx, _, _, consumed)| {
-            consumed == raw.len() && matches!(tx.tx_kind, 0x01 | 0x02)
-        })
+        && parse_tx(raw)
+            .is_ok_and(|(tx, _, _, consumed)| consumed == raw.len() && classify_tx(&tx) != Ok(None))
 }
 
 /// Select flat candidate raw txs, skipping individual DA commit/chunk txs before
@@ -725,7 +816,7 @@ fn choose_valid_timestamp(next_height: u64, prev_timestamps: &[u64], now: u64) -
         return if now == 0 { 1 } else { now };
     }
     let median = mtp_median(next_height, prev_timestamps);
-    if now > median && now <= median.saturating_add(MAX_FUTURE_DRIFT) {
+    if check_block_timestamp(now, median, None).is_ok() {
         return now;
     }
     median.saturating_add(1)
@@ -806,7 +897,7 @@ pub fn parse_mine_address_arg(value: &str) -> Result<Option<Vec<u8>>, String> {
 
 #[cfg(test)]
 mod tests {
-    use std::collections::{HashMap, HashSet};
+    use std::collections::HashSet;
     use std::fs;
     use std::path::PathBuf;
 
@@ -817,8 +908,8 @@ mod tests {
     use rubin_consensus::merkle::{witness_commitment_hash, witness_merkle_root_wtxids};
     use rubin_consensus::{
         encode_compact_size, marshal_tx, p2pk_covenant_data_for_pubkey, parse_tx, sign_transaction,
-        tx_weight_and_stats_public, DaChunkCore, DaCommitCore, Mldsa87Keypair, Outpoint, Tx,
-        TxInput, TxOutput, UtxoEntry,
+        tx_weight_and_stats_public, DaChunkCore, DaCommitCore, Mldsa87Keypair, Outpoint,
+        OutpointMap, Tx, TxInput, TxOutput, UtxoEntry,
     };
     use sha3::{Digest, Sha3_256};
 
@@ -833,10 +924,10 @@ mod tests {
 
     use super::{
         assemble_block_bytes, build_witness_commitment, canonical_tx_weight,
-        choose_valid_timestamp, default_mine_address, make_header_prefix, mtp_median,
-        parse_complete_da_set_candidate, parse_mine_address_arg, parse_mining_candidate,
-        pick_flat_candidate_raw, updated_policy_da_bytes, validate_complete_da_set_candidate_shape,
-        Miner, MinerConfig,
+        choose_valid_timestamp, default_mine_address, is_mining_da_tx_raw, make_header_prefix,
+        mtp_median, parse_complete_da_set_candidate, parse_mine_address_arg,
+        parse_mining_candidate, pick_flat_candidate_raw, updated_policy_da_bytes,
+        validate_complete_da_set_candidate_shape, Miner, MinerConfig,
     };
 
     use std::path::Path;
@@ -896,9 +987,9 @@ mod tests {
         super::build_coinbase_tx(height, 0, &default_mine_address(), [0u8; 32]).expect("coinbase")
     }
 
-    fn p2pk_utxos(marker: u8, value: u64) -> ([u8; 32], HashMap<Outpoint, UtxoEntry>) {
+    fn p2pk_utxos(marker: u8, value: u64) -> ([u8; 32], OutpointMap<UtxoEntry>) {
         let prev = [marker; 32];
-        let mut utxos = HashMap::new();
+        let mut utxos = OutpointMap::default();
         utxos.insert(
             Outpoint {
                 txid: prev,
@@ -920,7 +1011,7 @@ mod tests {
         out_value: u64,
         covenant_type: u16,
         covenant_data: Vec<u8>,
-    ) -> (Tx, HashMap<Outpoint, UtxoEntry>) {
+    ) -> (Tx, OutpointMap<UtxoEntry>) {
         let (prev, utxos) = p2pk_utxos(marker, 100);
         let tx = Tx {
             version: TX_WIRE_VERSION,
@@ -947,17 +1038,17 @@ mod tests {
         (tx, utxos)
     }
 
-    fn anchor_policy_tx(marker: u8) -> (Tx, HashMap<Outpoint, UtxoEntry>) {
+    fn anchor_policy_tx(marker: u8) -> (Tx, OutpointMap<UtxoEntry>) {
         one_input_policy_tx(marker, 0, COV_TYPE_ANCHOR, vec![marker; 32])
     }
 
-    fn core_ext_policy_tx(marker: u8) -> (Tx, HashMap<Outpoint, UtxoEntry>) {
+    fn core_ext_policy_tx(marker: u8) -> (Tx, OutpointMap<UtxoEntry>) {
         let mut cov = 7u16.to_le_bytes().to_vec();
         encode_compact_size(0, &mut cov);
         one_input_policy_tx(marker, 1, COV_TYPE_CORE_EXT, cov)
     }
 
-    fn core_simplicity_policy_tx(marker: u8) -> (Tx, HashMap<Outpoint, UtxoEntry>) {
+    fn core_simplicity_policy_tx(marker: u8) -> (Tx, OutpointMap<UtxoEntry>) {
         // Mirror of Go `simplicityCovenantDataForNodeTest`: 32-byte CMR +
         // compactSize(0) empty state.
         let mut cov = vec![marker; 32];
@@ -965,7 +1056,7 @@ mod tests {
         one_input_policy_tx(marker, 1, COV_TYPE_CORE_SIMPLICITY, cov)
     }
 
-    fn da_budget_policy_tx(marker: u8) -> (Tx, HashMap<Outpoint, UtxoEntry>) {
+    fn da_budget_policy_tx(marker: u8) -> (Tx, OutpointMap<UtxoEntry>) {
         let (prev, utxos) = p2pk_utxos(marker, 1_000_000);
         let tx = Tx {
             version: TX_WIRE_VERSION,
@@ -1085,7 +1176,7 @@ mod tests {
         da_id: [u8; 32],
         input_marker: u8,
         payload: &[u8],
-    ) -> (ProviderSet, HashMap<Outpoint, UtxoEntry>) {
+    ) -> (ProviderSet, OutpointMap<UtxoEntry>) {
         let mut set = miner_da_provider_shape_set(da_id, &[payload]);
         let keypair = Mldsa87Keypair::generate().expect("provider keypair");
         let covenant_data = p2pk_covenant_data_for_pubkey(&keypair.pubkey_bytes());
@@ -1112,11 +1203,11 @@ mod tests {
             creation_height: 0,
             created_by_coinbase: false,
         };
-        let utxos = HashMap::from([
+        let utxos = OutpointMap::from_iter([
             (commit_input, entry(2_000_000)),
             (chunk_input.clone(), entry(1_000_000)),
         ]);
-        let sign_raw = |raw: &mut Vec<u8>, utxos: &HashMap<Outpoint, UtxoEntry>| {
+        let sign_raw = |raw: &mut Vec<u8>, utxos: &OutpointMap<UtxoEntry>| {
             let (mut tx, _, _, _) = parse_tx(raw).expect("parse provider tx");
             sign_transaction(&mut tx, utxos, devnet_genesis_chain_id(), &keypair)
                 .expect("sign provider tx");
@@ -1134,10 +1225,23 @@ mod tests {
         fn complete_da_set_candidates(&self, _: u64) -> Vec<ProviderSet> {
             self.clone()
         }
+        fn is_complete(&self, da_id: [u8; 32]) -> bool {
+            self.iter().any(|set| set.da_id == da_id)
+        }
+    }
+    /// Lists its sets as candidates but answers `checkblock` with false.
+    struct IncompleteSets(Vec<ProviderSet>);
+    impl crate::da_relay::CompleteDaSetProvider for IncompleteSets {
+        fn complete_da_set_candidates(&self, _: u64) -> Vec<ProviderSet> {
+            self.0.clone()
+        }
+        fn is_complete(&self, _: [u8; 32]) -> bool {
+            false
+        }
     }
     fn s(
         sets: Vec<ProviderSet>,
-        utxos: HashMap<Outpoint, UtxoEntry>,
+        utxos: OutpointMap<UtxoEntry>,
         cfg: MinerConfig,
     ) -> Result<usize, String> {
         let (_dir, _block_store, mut sync) = test_sync("rubin-rust-miner-da-provider-selection");
@@ -1208,7 +1312,12 @@ mod tests {
         let mut malformed = miner_da_provider_shape_set([0x82; 32], &[b"chunk-0"]);
         malformed.commit_tx.push(0); // trailing byte -> non-canonical parse error
         assert_eq!(
-            s(vec![malformed], HashMap::new(), MinerConfig::default()).unwrap_err(),
+            s(
+                vec![malformed],
+                OutpointMap::default(),
+                MinerConfig::default()
+            )
+            .unwrap_err(),
             "non-canonical tx bytes in miner input",
         );
     }
@@ -1291,7 +1400,7 @@ mod tests {
         assert_eq!(selected_len(vec![set.clone()], low_provider_budget), 0);
         assert_eq!(selected_len(vec![set.clone(), set.clone()], cfg.clone()), 2);
         let mut capped_sets = Vec::new();
-        let mut capped_utxos = HashMap::new();
+        let mut capped_utxos = OutpointMap::default();
         for i in 0..=MDB as u8 {
             let marker = i.wrapping_mul(2).wrapping_add(1);
             let (candidate, candidate_utxos) =
@@ -1299,7 +1408,19 @@ mod tests {
             capped_sets.push(candidate);
             capped_utxos.extend(candidate_utxos);
         }
-        assert_eq!(s(capped_sets, capped_utxos, cfg).unwrap(), 2 * MDB as usize);
+        assert_eq!(
+            s(capped_sets, capped_utxos, cfg.clone()).unwrap(),
+            2 * MDB as usize
+        );
+
+        // The same signed set is never included once checkblock reports it incomplete.
+        let (_dir, _block_store, mut sync) = test_sync("rubin-rust-miner-da-provider-incomplete");
+        sync.chain_state.utxos = utxos;
+        let incomplete = IncompleteSets(vec![set]);
+        let mut miner = Miner::new(&mut sync, None, cfg).expect("miner");
+        miner.set_complete_da_set_provider(&incomplete);
+        let selected = miner.select_candidate_transactions(vec![], 1, MAX_BLOCK_WEIGHT, 0);
+        assert_eq!(selected.unwrap().len(), 0);
     }
     #[test]
     fn miner_da_anchor_master_switch_off_ignores_anchor_subflag() {
@@ -1565,6 +1686,55 @@ mod tests {
         let _ = fs::remove_dir_all(&dir);
     }
 
+    #[test]
+    fn candidate_transactions_da_filter_preserves_non_da_order() {
+        // Interleaved DA and ordinary txs: the filtered selection must equal the
+        // unfiltered one with the DA entries removed, for explicit and pool input.
+        let (dir, _block_store, mut sync) = test_sync("rubin-rust-miner-flat-da-order");
+        let first = miner_da_provider_shape_set([0x67; 32], &[b"one", b"two"]);
+        let second = miner_da_provider_shape_set([0x68; 32], &[b"three"]);
+        let ordinary = |seed: u8| vec![seed; first.commit_tx.len() + usize::from(seed)];
+        let explicit = vec![
+            ordinary(1),
+            first.commit_tx.clone(),
+            ordinary(2),
+            first.chunks[0].tx.clone(),
+            second.chunks[0].tx.clone(),
+            ordinary(3),
+            first.chunks[1].tx.clone(),
+            second.commit_tx.clone(),
+            ordinary(4),
+        ];
+        let non_da: Vec<Vec<u8>> = explicit
+            .iter()
+            .filter(|raw| !is_mining_da_tx_raw(raw))
+            .cloned()
+            .collect();
+        assert_eq!(
+            non_da,
+            vec![ordinary(1), ordinary(2), ordinary(3), ordinary(4)]
+        );
+
+        let mut pool = TxPool::new();
+        for (index, raw) in explicit.iter().enumerate() {
+            pool.inject_test_entry([index as u8 + 1; 32], raw.clone());
+        }
+        let unfiltered: Vec<Vec<u8>> = pool
+            .select_transactions(explicit.len(), MAX_BLOCK_WEIGHT as usize)
+            .into_iter()
+            .filter(|raw| !is_mining_da_tx_raw(raw))
+            .collect();
+        assert_eq!(unfiltered.len(), non_da.len());
+        let cfg = MinerConfig {
+            max_tx_per_block: explicit.len() + 1,
+            ..MinerConfig::default()
+        };
+        let miner = Miner::new(&mut sync, Some(&mut pool), cfg).expect("miner");
+        assert_eq!(miner.candidate_transactions(&explicit), non_da);
+        assert_eq!(miner.candidate_transactions(&[]), unfiltered);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
     #[test]
     fn flat_candidate_selection_handles_zero_and_empty_guards() {
         // Defensive guards: zero caps and an empty/absent candidate source.
@@ -1604,7 +1774,7 @@ mod tests {
         assert_eq!(updated_policy_da_bytes(u64::MAX, 1, u64::MAX), None);
         assert_eq!(choose_valid_timestamp(0, &[], 0), 1);
         assert_eq!(choose_valid_timestamp(1, &[10, 11, 12], 12), 12);
-        let future = 12 + super::MAX_FUTURE_DRIFT + 1;
+        let future = 12 + rubin_consensus::constants::MAX_FUTURE_DRIFT + 1;
         assert_eq!(choose_valid_timestamp(1, &[10, 11, 12], future), 11);
     }
 
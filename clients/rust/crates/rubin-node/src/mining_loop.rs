@@ -0,0 +1,399 @@
+//! Continuous devnet block production.
+//!
+//! The loop keeps one `BlockTemplate` in a `TemplateSlot` and searches its
+//! nonces in batches without holding the engine or pool locks. Between
+//! batches it rebuilds the template when the tip moved or the mempool's
+//! total fees drifted by `fee_refresh_delta`; a rebuild swaps the whole
+//! template and bumps the slot's generation, and a solution is only
+//! submitted if the generation it was found under is still current, so a
+//! block is never assembled from a half-replaced template. Solved blocks go
+//! through the caller's submit hook (normally `block_submit::submit_block`)
+//! like any external miner's. With `interval_secs` set, the loop waits
+//! that long after each block before searching again.
+
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::block_submit::SubmittedBlock;
+use crate::miner::{BlockTemplate, Miner, MinerConfig};
+use crate::sync::SyncEngine;
+use crate::txpool::TxPool;
+
+/// Nonces tried between checks for shutdown and stale templates.
+pub const DEFAULT_NONCE_BATCH: u64 = 4096;
+/// Total mempool fee movement that triggers a template rebuild by default.
+pub const DEFAULT_TEMPLATE_FEE_REFRESH_DELTA: u64 = 1_000;
+/// Longest single sleep while pacing, so shutdown is noticed promptly.
+const PACING_POLL_MS: u64 = 250;
+
+/// Time source for pacing; tests substitute a mock.
+pub trait MiningClock {
+    fn now_unix_ms(&self) -> u64;
+    fn sleep_ms(&self, ms: u64);
+}
+
+pub struct SystemMiningClock;
+
+impl MiningClock for SystemMiningClock {
+    fn now_unix_ms(&self) -> u64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_millis() as u64)
+    }
+
+    fn sleep_ms(&self, ms: u64) {
+        std::thread::sleep(Duration::from_millis(ms));
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct MiningLoopConfig {
+    pub miner: MinerConfig,
+    /// Seconds from one mined block to the next search; 0 mines back to
+    /// back.
+    pub interval_secs: u64,
+    /// Change in total mempool fees that triggers a template rebuild; 0
+    /// only rebuilds on tip changes.
+    pub fee_refresh_delta: u64,
+    /// Stop after this many blocks; `None` runs until stopped.
+    pub max_blocks: Option<u64>,
+    pub nonce_batch: u64,
+}
+
+impl Default for MiningLoopConfig {
+    fn default() -> Self {
+        Self {
+            miner: MinerConfig::default(),
+            interval_secs: 0,
+            fee_refresh_delta: DEFAULT_TEMPLATE_FEE_REFRESH_DELTA,
+            max_blocks: None,
+            nonce_batch: DEFAULT_NONCE_BATCH,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct MiningLoopReport {
+    pub blocks: Vec<SubmittedBlock>,
+    /// Templates built, the first one included.
+    pub templates: u64,
+    /// Solutions dropped because the submit hook rejected them.
+    pub rejected: u64,
+}
+
+/// The template the nonce search works on, replaced as a whole.
+#[derive(Default)]
+pub struct TemplateSlot {
+    current: Mutex<Option<Arc<BlockTemplate>>>,
+    generation: AtomicU64,
+}
+
+impl TemplateSlot {
+    /// Installs `template`, returning its generation.
+    pub fn swap(&self, template: BlockTemplate) -> u64 {
+        let mut current = self.current.lock().unwrap_or_else(|e| e.into_inner());
+        *current = Some(Arc::new(template));
+        self.generation.fetch_add(1, Ordering::SeqCst) + 1
+    }
+
+    pub fn clear(&self) {
+        let mut current = self.current.lock().unwrap_or_else(|e| e.into_inner());
+        *current = None;
+        self.generation.fetch_add(1, Ordering::SeqCst);
+    }
+
+    /// The current template and its generation.
+    pub fn snapshot(&self) -> Option<(u64, Arc<BlockTemplate>)> {
+        let current = self.current.lock().unwrap_or_else(|e| e.into_inner());
+        current
+            .as_ref()
+            .map(|template| (self.generation.load(Ordering::SeqCst), Arc::clone(template)))
+    }
+
+    pub fn generation(&self) -> u64 {
+        self.generation.load(Ordering::SeqCst)
+    }
+}
+
+/// Submits a solved block; `block_submit::submit_block` plus whatever
+/// announcing the caller does.
+pub type SubmitBlockFn<'a> = dyn Fn(&[u8]) -> Result<SubmittedBlock, String> + 'a;
+
+/// Mines until `stop` is set or `cfg.max_blocks` blocks were accepted.
+pub fn run_mining_loop(
+    sync_engine: &Mutex<SyncEngine>,
+    tx_pool: &Mutex<TxPool>,
+    cfg: &MiningLoopConfig,
+    clock: &dyn MiningClock,
+    stop: &AtomicBool,
+    submit: &SubmitBlockFn<'_>,
+) -> Result<MiningLoopReport, String> {
+    let slot = TemplateSlot::default();
+    let mut report = MiningLoopReport::default();
+    // Total mempool fees when the current template was built.
+    let mut template_pool_fees = 0u64;
+    let mut next_nonce = 0u64;
+    let mut last_block_ms: Option<u64> = None;
+    let batch = cfg.nonce_batch.max(1);
+    while !stop.load(Ordering::SeqCst) {
+        if cfg
+            .max_blocks
+            .is_some_and(|max| report.blocks.len() as u64 >= max)
+        {
+            break;
+        }
+        if let (Some(last), true) = (last_block_ms, cfg.interval_secs > 0) {
+            let due = last.saturating_add(cfg.interval_secs.saturating_mul(1000));
+            let now = clock.now_unix_ms();
+            if now < due {
+                clock.sleep_ms((due - now).min(PACING_POLL_MS));
+                continue;
+            }
+        }
+
+        let (tip, pool_fees) = observe_tip_and_pool_fees(sync_engine, tx_pool)?;
+        let stale = match slot.snapshot() {
+            None => true,
+            Some((_, template)) => {
+                template.prev_hash != tip
+                    || (cfg.fee_refresh_delta > 0
+                        && pool_fees.abs_diff(template_pool_fees) >= cfg.fee_refresh_delta)
+            }
+        };
+        if stale {
+            slot.swap(build_template(sync_engine, tx_pool, &cfg.miner)?);
+            template_pool_fees = pool_fees;
+            next_nonce = 0;
+            report.templates += 1;
+        }
+        let Some((generation, template)) = slot.snapshot() else {
+            continue;
+        };
+        let Some(nonce) = template.search_nonces(next_nonce, batch) else {
+            next_nonce = match next_nonce.checked_add(batch) {
+                Some(next) => next,
+                None => {
+                    // Nonce space exhausted: a fresh template gets a fresh
+                    // timestamp.
+                    slot.clear();
+                    0
+                }
+            };
+            continue;
+        };
+        if slot.generation() != generation {
+            continue;
+        }
+        match submit(&template.block_bytes(nonce)) {
+            Ok(block) => report.blocks.push(block),
+            Err(err) => {
+                eprintln!("miner: submit rejected block: {err}");
+                report.rejected += 1;
+            }
+        }
+        slot.clear();
+        last_block_ms = Some(clock.now_unix_ms());
+    }
+    Ok(report)
+}
+
+fn observe_tip_and_pool_fees(
+    sync_engine: &Mutex<SyncEngine>,
+    tx_pool: &Mutex<TxPool>,
+) -> Result<([u8; 32], u64), String> {
+    let engine = sync_engine
+        .lock()
+        .map_err(|_| "sync engine unavailable".to_string())?;
+    let tip = if engine.chain_state.has_tip {
+        engine.chain_state.tip_hash
+    } else {
+        [0u8; 32]
+    };
+    let pool = tx_pool
+        .lock()
+        .map_err(|_| "tx pool unavailable".to_string())?;
+    let fees = pool
+        .all_txids()
+        .iter()
+        .filter_map(|txid| pool.entry_fee(txid))
+        .fold(0u64, u64::saturating_add);
+    Ok((tip, fees))
+}
+
+fn build_template(
+    sync_engine: &Mutex<SyncEngine>,
+    tx_pool: &Mutex<TxPool>,
+    miner_cfg: &MinerConfig,
+) -> Result<BlockTemplate, String> {
+    let mut engine = sync_engine
+        .lock()
+        .map_err(|_| "sync engine unavailable".to_string())?;
+    let mut pool = tx_pool
+        .lock()
+        .map_err(|_| "tx pool unavailable".to_string())?;
+    Miner::new(&mut engine, Some(&mut pool), miner_cfg.clone())?.block_template(&[])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::block_submit::submit_block;
+    use crate::blockstore::{block_store_path, BlockStore};
+    use crate::chainstate::ChainState;
+    use crate::genesis::devnet_genesis_chain_id;
+    use crate::io_utils::unique_temp_path;
+    use crate::sync::default_sync_config;
+    use std::cell::{Cell, RefCell};
+
+    /// A clock that only moves when the loop sleeps.
+    #[derive(Default)]
+    struct MockClock {
+        now_ms: Cell<u64>,
+        sleeps: RefCell<Vec<u64>>,
+    }
+
+    impl MiningClock for MockClock {
+        fn now_unix_ms(&self) -> u64 {
+            self.now_ms.get()
+        }
+
+        fn sleep_ms(&self, ms: u64) {
+            self.sleeps.borrow_mut().push(ms);
+            self.now_ms.set(self.now_ms.get() + ms);
+        }
+    }
+
+    fn engine_and_pool(dir: &std::path::Path) -> (Mutex<SyncEngine>, Mutex<TxPool>) {
+        let store = BlockStore::open(block_store_path(dir)).expect("blockstore");
+        let cfg = default_sync_config(None, devnet_genesis_chain_id(), None);
+        let engine = SyncEngine::new(ChainState::new(), Some(store), cfg).expect("sync");
+        (Mutex::new(engine), Mutex::new(TxPool::new()))
+    }
+
+    fn easy_cfg(max_blocks: u64, interval_secs: u64) -> MiningLoopConfig {
+        MiningLoopConfig {
+            miner: MinerConfig {
+                timestamp_source: || 1,
+                ..MinerConfig::default()
+            },
+            interval_secs,
+            max_blocks: Some(max_blocks),
+            ..MiningLoopConfig::default()
+        }
+    }
+
+    #[test]
+    fn paces_blocks_by_interval_on_the_mock_clock() {
+        let dir = unique_temp_path("rubin-mining-loop-pacing");
+        let (engine, pool) = engine_and_pool(&dir);
+        let clock = MockClock::default();
+        let stop = AtomicBool::new(false);
+        let submit = |block: &[u8]| submit_block(&engine, &pool, block);
+
+        let report =
+            run_mining_loop(&engine, &pool, &easy_cfg(3, 2), &clock, &stop, &submit).expect("mine");
+
+        let heights: Vec<u64> = report.blocks.iter().map(|b| b.height).collect();
+        assert_eq!(heights, vec![0, 1, 2]);
+        assert!(report.blocks.iter().all(|b| b.canonical));
+        assert_eq!(report.templates, 3);
+        // Two gaps of 2s, paced in polls no longer than PACING_POLL_MS.
+        assert_eq!(clock.now_ms.get(), 4_000);
+        assert!(clock.sleeps.borrow().iter().all(|ms| *ms <= PACING_POLL_MS));
+        assert_eq!(engine.lock().unwrap().chain_state.height, 2);
+
+        std::fs::remove_dir_all(&dir).expect("cleanup");
+    }
+
+    #[test]
+    fn refreshes_the_template_when_the_tip_moves_under_it() {
+        let dir = unique_temp_path("rubin-mining-loop-tip-change");
+        let (engine, pool) = engine_and_pool(&dir);
+        Miner::new(&mut engine.lock().unwrap(), None, easy_cfg(1, 0).miner)
+            .and_then(|mut miner| miner.mine_one(&[]))
+            .expect("genesis");
+        let clock = MockClock::default();
+        let stop = AtomicBool::new(false);
+        // Another miner's block at height 1 lands before ours is
+        // submitted, so ours ends up on a side branch and the next
+        // template must build on the competitor's tip.
+        let competitor_cfg = MinerConfig {
+            timestamp_source: || 2,
+            ..MinerConfig::default()
+        };
+        let competitor = RefCell::new(None);
+        let submit = |block: &[u8]| {
+            if competitor.borrow().is_none() {
+                let mut engine = engine.lock().unwrap();
+                let mined = Miner::new(&mut engine, None, competitor_cfg.clone())
+                    .and_then(|mut miner| miner.mine_one(&[]))
+                    .expect("competitor");
+                *competitor.borrow_mut() = Some(mined.hash);
+            }
+            submit_block(&engine, &pool, block)
+        };
+
+        let report =
+            run_mining_loop(&engine, &pool, &easy_cfg(2, 0), &clock, &stop, &submit).expect("mine");
+
+        let engine = engine.lock().unwrap();
+        assert_eq!(report.templates, 2);
+        assert_eq!(report.blocks.len(), 2);
+        assert_eq!(report.blocks[0].height, 1);
+        assert!(!report.blocks[0].canonical);
+        assert_eq!(report.blocks[1].height, 2);
+        assert!(report.blocks[1].canonical);
+        let competitor = competitor.borrow().expect("competitor mined");
+        assert_eq!(
+            engine
+                .block_store
+                .as_ref()
+                .expect("store")
+                .canonical_hash(1)
+                .expect("index"),
+            Some(competitor)
+        );
+        assert_eq!(engine.chain_state.tip_hash, report.blocks[1].hash);
+
+        std::fs::remove_dir_all(&dir).expect("cleanup");
+    }
+
+    #[test]
+    fn stale_solution_is_not_submitted_after_a_swap() {
+        let dir = unique_temp_path("rubin-mining-loop-swap");
+        let (engine, pool) = engine_and_pool(&dir);
+        let slot = TemplateSlot::default();
+        let template = build_template(&engine, &pool, &easy_cfg(1, 0).miner).expect("template");
+        let first = slot.swap(template.clone());
+        let (generation, held) = slot.snapshot().expect("template");
+        assert_eq!(generation, first);
+        let second = slot.swap(template);
+        assert_ne!(slot.generation(), generation);
+        assert_eq!(slot.snapshot().expect("template").0, second);
+        // The in-flight search keeps its own copy.
+        assert!(held.search_nonces(0, DEFAULT_NONCE_BATCH).is_some());
+
+        std::fs::remove_dir_all(&dir).expect("cleanup");
+    }
+
+    #[test]
+    fn stop_flag_ends_the_loop_before_mining() {
+        let dir = unique_temp_path("rubin-mining-loop-stop");
+        let (engine, pool) = engine_and_pool(&dir);
+        let stop = AtomicBool::new(true);
+        let submit = |block: &[u8]| submit_block(&engine, &pool, block);
+        let report = run_mining_loop(
+            &engine,
+            &pool,
+            &MiningLoopConfig::default(),
+            &MockClock::default(),
+            &stop,
+            &submit,
+        )
+        .expect("mine");
+        assert_eq!(report, MiningLoopReport::default());
+
+        std::fs::remove_dir_all(&dir).expect("cleanup");
+    }
+}
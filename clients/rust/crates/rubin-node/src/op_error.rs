@@ -0,0 +1,90 @@
+//! Typed failures of the node operations the CLI drives (verification,
+//! block-file import, the devnet harness). The kind is fixed where the
+//! failure is raised, so callers mapping failures to outcomes never have to
+//! recover it from the message text.
+
+use rubin_consensus::TxError;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OpErrorKind {
+    /// Malformed caller-supplied data: hex, transaction bytes, prevouts.
+    Input,
+    /// A consensus rule rejected the data; the message carries the
+    /// canonical `TX_ERR_*` / `BLOCK_ERR_*` code.
+    Consensus,
+    /// The OpenSSL ML-DSA provider failed; says nothing about the data.
+    CryptoProvider,
+    /// Anything else: I/O, store or engine state.
+    Other,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct OpError {
+    pub kind: OpErrorKind,
+    pub message: String,
+}
+
+impl OpError {
+    pub fn new(kind: OpErrorKind, message: impl Into<String>) -> Self {
+        Self {
+            kind,
+            message: message.into(),
+        }
+    }
+
+    pub fn input(message: impl Into<String>) -> Self {
+        Self::new(OpErrorKind::Input, message)
+    }
+
+    /// A consensus-crate error under `context`: a provider failure when
+    /// [`TxError::is_crypto_provider`] says so, a consensus rejection
+    /// otherwise.
+    pub fn from_tx_error(context: &str, err: &TxError) -> Self {
+        let kind = if err.is_crypto_provider() {
+            OpErrorKind::CryptoProvider
+        } else {
+            OpErrorKind::Consensus
+        };
+        Self::new(kind, format!("{context}: {err}"))
+    }
+}
+
+impl From<String> for OpError {
+    fn from(message: String) -> Self {
+        Self::new(OpErrorKind::Other, message)
+    }
+}
+
+impl std::fmt::Display for OpError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl std::error::Error for OpError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rubin_consensus::ErrorCode;
+
+    #[test]
+    fn tx_errors_split_provider_failures_from_rejections() {
+        let err = OpError::from_tx_error(
+            "devnet keys",
+            &TxError::new(
+                ErrorCode::TxErrParse,
+                "openssl bootstrap: invalid RUBIN_OPENSSL_FIPS_MODE",
+            ),
+        );
+        assert_eq!(err.kind, OpErrorKind::CryptoProvider);
+        let err = OpError::from_tx_error(
+            "block 00",
+            &TxError::new(ErrorCode::BlockErrPowInvalid, "bad pow"),
+        );
+        assert_eq!(err.kind, OpErrorKind::Consensus);
+        assert_eq!(err.message, "block 00: BLOCK_ERR_POW_INVALID: bad pow");
+        let err = OpError::from("read blocks-0.dat: not found".to_string());
+        assert_eq!(err.kind, OpErrorKind::Other);
+    }
+}
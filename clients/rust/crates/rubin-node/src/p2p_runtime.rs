@@ -12,7 +12,10 @@ use rubin_consensus::{
 };
 use sha3::{Digest, Sha3_256};
 
+use crate::compact_mode::SendCmpctModeController;
+use crate::relay_telemetry::with_relay_telemetry;
 use crate::sync::SyncEngine;
+use crate::sync_low_work::{is_low_work_side_block_err, LOW_WORK_SIDE_BLOCK_BAN_SCORE};
 use crate::sync_reorg::{TxPoolCleanupPlan, PARENT_BLOCK_NOT_FOUND_ERR};
 
 /// Maximum reasonable best_height delta before clamping peer claims.
@@ -111,7 +114,7 @@ pub struct WireMessage {
     pub payload: Vec<u8>,
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub struct InventoryVector {
     pub kind: u8,
     pub hash: [u8; 32],
@@ -162,6 +165,8 @@ pub struct CompactReconstructionResult {
     pub partial_transactions: Vec<Option<Vec<u8>>>,
     pub missing_indexes: Vec<u64>,
     pub missing_short_ids: Vec<CompactShortId>,
+    /// Short ids that matched more than one local tx or repeat within the block.
+    pub shortid_collisions: u64,
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
@@ -183,6 +188,9 @@ pub struct PeerRuntimeConfig {
     pub write_deadline: Duration,
     pub ban_threshold: i32,
     pub enable_compact_receive: bool,
+    /// DA chunk prefetch stream budgets (4 MB/s per peer, 32 MB/s global by
+    /// default). Excess is delayed, never dropped or disconnected.
+    pub da_prefetch_rate: crate::da_prefetch::DaPrefetchRateConfig,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Default)]
@@ -211,6 +219,78 @@ impl PeerState {
     }
 }
 
+/// Admits orphans waiting on `parent`, now in `pool`, and relays them.
+fn promote_and_relay_tx_orphans(
+    pool: &mut crate::txpool::TxPool,
+    parent: [u8; 32],
+    sync_engine: &SyncEngine,
+    ctx: &PeerRelayContext<'_>,
+) {
+    let promoted = crate::tx_orphans::promote_tx_orphans(
+        &ctx.relay_state.tx_orphans,
+        pool,
+        [parent],
+        &sync_engine.chain_state,
+        sync_engine.block_store.as_ref(),
+        sync_engine.cfg.chain_id,
+    );
+    crate::tx_relay::relay_promoted_tx_orphans(
+        &promoted,
+        ctx.relay_state,
+        ctx.peer_manager,
+        ctx.local_addr,
+        ctx.peer_writers,
+    );
+}
+
+/// A peer tx whose inputs are not all confirmed may still spend a pool
+/// entry (the relay-metadata check only sees confirmed outputs), so try
+/// the pool first; if an input is still unknown, keep it as an orphan.
+fn admit_or_store_tx_orphan(
+    pool: &mut crate::txpool::TxPool,
+    txid: [u8; 32],
+    tx_bytes: &[u8],
+    sync_engine: &SyncEngine,
+    ctx: &PeerRelayContext<'_>,
+) {
+    let chain_state = &sync_engine.chain_state;
+    match pool.add_tx_with_source(
+        tx_bytes,
+        chain_state,
+        sync_engine.block_store.as_ref(),
+        sync_engine.cfg.chain_id,
+        crate::txpool::TxSource::Remote,
+    ) {
+        Ok((_, meta)) => {
+            let admitted = crate::tx_orphans::PromotedTxOrphan {
+                txid,
+                raw: tx_bytes.to_vec(),
+                peer: ctx.peer_registered_addr.to_string(),
+                meta,
+            };
+            crate::tx_relay::relay_promoted_tx_orphans(
+                &[admitted],
+                ctx.relay_state,
+                ctx.peer_manager,
+                ctx.local_addr,
+                ctx.peer_writers,
+            );
+            promote_and_relay_tx_orphans(pool, txid, sync_engine, ctx);
+        }
+        Err(err) if err.is_missing_utxo() => {
+            let missing = crate::tx_orphans::missing_prevouts(tx_bytes, chain_state, pool);
+            let _ = ctx.relay_state.tx_orphans.add(
+                txid,
+                tx_bytes,
+                missing,
+                ctx.peer_registered_addr,
+                chain_state.height,
+            );
+        }
+        Err(_) => {}
+    }
+}
+
 /// Context for TX relay operations, passed through the message loop.
 /// Optional — tests and block-only peers can omit it.
 pub struct PeerRelayContext<'a> {
@@ -250,7 +330,7 @@ pub struct PeerRelayContext<'a> {
     pub prefetch: &'a std::sync::Mutex<crate::da_prefetch::DaRelayPrefetchState>,
 }
 
-pub(crate) type PendingDaRelayStaging = (String, Vec<u8>, bool);
+pub(crate) type PendingDaRelayStaging = (String, Vec<u8>, bool, u64);
 
 #[rustfmt::skip]
 fn skip_da(
@@ -338,6 +418,7 @@ pub struct PeerSession {
     compact_outstanding: Option<CompactOutstandingRequest>,
     late_blocktxn: Option<LateBlockTxnContext>,
     compact_announced: Vec<[u8; 32]>,
+    local_compact_mode: SendCmpctModeController,
 }
 
 pub struct PeerManager {
@@ -395,6 +476,7 @@ pub fn default_peer_runtime_config(network: &str, max_peers: usize) -> PeerRunti
         write_deadline: DEFAULT_WRITE_DEADLINE,
         ban_threshold: DEFAULT_BAN_THRESHOLD,
         enable_compact_receive: false,
+        da_prefetch_rate: crate::da_prefetch::DaPrefetchRateConfig::default(),
     }
 }
 
@@ -475,6 +557,7 @@ impl PeerSession {
             compact_outstanding: None,
             late_blocktxn: None,
             compact_announced: Vec::new(),
+            local_compact_mode: SendCmpctModeController::new(),
         })
     }
 
@@ -508,8 +591,8 @@ impl PeerSession {
     }
 
     #[rustfmt::skip]
-    fn stash_da_staging(&mut self, peer_addr: &str, tx_bytes: Vec<u8>, chunk_hash_prevalidated: bool) {
-        self.pending_da_relay_staging = Some((peer_addr.to_string(), tx_bytes, chunk_hash_prevalidated));
+    fn stash_da_staging(&mut self, peer_addr: &str, tx_bytes: Vec<u8>, chunk_hash_prevalidated: bool, fee: u64) {
+        self.pending_da_relay_staging = Some((peer_addr.to_string(), tx_bytes, chunk_hash_prevalidated, fee));
     }
 
     #[rustfmt::skip]
@@ -518,7 +601,7 @@ impl PeerSession {
         da_relay: &std::sync::Mutex<crate::da_relay::DaRelayState>,
         pending: Option<PendingDaRelayStaging>,
     ) -> DaPrefetchFollowup {
-        let Some((peer_addr, tx_bytes, chunk_hash_prevalidated)) = pending else {
+        let Some((peer_addr, tx_bytes, chunk_hash_prevalidated, fee)) = pending else {
             return DaPrefetchFollowup::None;
         };
         let Ok(mut da_relay) = da_relay.lock() else {
@@ -528,7 +611,12 @@ impl PeerSession {
         // Single parse: the staging call returns the schedulable da_id (commit-
         // commitment gated) so we feed finish_da_prefetch without re-parsing.
         let (da_id, staging) =
-            da_relay.stage_relay_da_tx_bytes_checked(&peer_addr, tx_bytes, chunk_hash_prevalidated);
+            da_relay.stage_relay_da_tx_bytes_checked(&peer_addr, tx_bytes, chunk_hash_prevalidated, fee);
+        if let (Some(id), Ok(())) = (da_id, &staging) {
+            let now_ms = now_nanos() / 1_000_000;
+            da_relay.observe_set_recoveries(now_ms);
+            with_relay_telemetry(|telemetry| telemetry.record_da_staged(&da_relay, id, now_ms));
+        }
         drop(da_relay);
         // A payload-commitment mismatch is the recoverable snapshot-reschedule case
         // (Go does not surface it as a peer error); only genuine failures record it.
@@ -586,15 +674,66 @@ impl PeerSession {
         if !diagnostic.is_empty() {
             self.peer.last_error = diagnostic;
         }
+        let now = now_nanos();
         for plan in plans {
-            if let Err(err) = send_da_prefetch_plan(
+            // Over-budget plans stay reserved and go out from
+            // send_ready_da_prefetch_plans once their delay has passed.
+            let Some(plan) = prefetch.admit_plan(plan, now) else {
+                continue;
+            };
+            match send_da_prefetch_plan(
                 &mut prefetch,
                 plan,
                 &self.cfg.network,
                 &quota_key_to_addr,
                 peer_writers,
             ) {
-                self.peer.last_error = err;
+                Ok(()) => with_relay_telemetry(|telemetry| {
+                    telemetry.record_prefetch_sent(da_id, now / 1_000_000)
+                }),
+                Err(err) => self.peer.last_error = err,
+            }
+        }
+    }
+
+    /// Send the throttled prefetch plans whose delay has passed. Called from
+    /// the live loop of every session, so a held plan goes out even if its
+    /// own peer is idle.
+    pub(crate) fn send_ready_da_prefetch_plans(
+        &mut self,
+        prefetch: &Mutex<crate::da_prefetch::DaRelayPrefetchState>,
+        peer_manager: &PeerManager,
+        peer_writers: &Mutex<HashMap<String, crate::tx_relay::PeerOutbox>>,
+    ) {
+        let Ok(mut prefetch) = prefetch.lock() else {
+            return;
+        };
+        if prefetch.deferred_len() == 0 {
+            return;
+        }
+        let now = now_nanos();
+        let plans = prefetch.take_ready_plans(now);
+        if plans.is_empty() {
+            return;
+        }
+        let (quota_key_to_addr, _) = da_prefetch_peers(
+            &peer_manager.snapshot(),
+            self.cfg.enable_compact_receive,
+            "",
+        );
+        for plan in plans {
+            let da_id = plan.da_id;
+            match send_da_prefetch_plan(
+                &mut prefetch,
+                plan,
+                &self.cfg.network,
+                &quota_key_to_addr,
+                peer_writers,
+            ) {
+                Ok(()) => with_relay_telemetry(|telemetry| {
+                    telemetry.record_prefetch_sent(da_id, now / 1_000_000)
+                }),
+                Err(err) => self.peer.last_error = err,
             }
         }
     }
@@ -865,16 +1004,22 @@ impl PeerSession {
                 }
             }
             MESSAGE_BLOCK => {
+                let compact_miss =
+                    self.full_block_compact_miss(&msg.payload, sync_engine, relay_ctx);
                 let block =
                     self.handle_block_with_acceptance(&msg.payload, sync_engine, relay_ctx)?;
                 self.advance_da_orphan_ttl_for_accepted_blocks(relay_ctx, block.accepted_blocks);
-                Ok(LiveMessageOutcome {
+                let mut outcome = LiveMessageOutcome {
                     responses: self
                         .prepare_block_request_if_behind(sync_engine)?
                         .into_iter()
                         .collect(),
                     tx_pool_cleanup: block.tx_pool_cleanup,
-                })
+                };
+                if let Some(missed) = compact_miss {
+                    self.observe_compact_mode_block(sync_engine, missed, &mut outcome);
+                }
+                Ok(outcome)
             }
             "cmpctblock" => self.handle_cmpctblock(&msg.payload, sync_engine, relay_ctx),
             MESSAGE_GETBLOCKTXN => self.handle_getblocktxn(&msg.payload, sync_engine),
@@ -910,7 +1055,7 @@ impl PeerSession {
                         ctx.local_addr,
                         ctx.peer_writers,
                     )?;
-                    use crate::tx_relay::RelayTxOutcome::{DuplicateSeen, Relayed};
+                    use crate::tx_relay::RelayTxOutcome::{DuplicateSeen, MissingInputs, Relayed};
                     let relay_da_tx = matches!(
                         crate::da_relay::relay_da_tx_kind_prefix(&msg.payload),
                         Some(0x01) | Some(0x02)
@@ -923,16 +1068,26 @@ impl PeerSession {
                             Ok(mut pool) => {
                                 if relay_da_tx {
                                     if let Relayed { txid } | DuplicateSeen { txid } = &outcome {
-                                        admitted_tx = pool.tx_by_id(txid);
+                                        admitted_tx = pool.tx_by_id(txid).zip(pool.entry_fee(txid));
                                     }
                                 }
                                 if admitted_tx.is_none() && matches!(&outcome, Relayed { .. }) {
                                     #[rustfmt::skip]
-                                    let add_remote = pool.add_tx_with_source(&msg.payload, &sync_engine.chain_state, sync_engine.block_store.as_ref(), sync_engine.cfg.chain_id, crate::txpool::TxSource::Remote).ok().and_then(|(txid, _meta)| pool.tx_by_id(&txid));
+                                    let add_remote = pool.add_tx_with_source(&msg.payload, &sync_engine.chain_state, sync_engine.block_store.as_ref(), sync_engine.cfg.chain_id, crate::txpool::TxSource::Remote).ok().and_then(|(txid, meta)| pool.tx_by_id(&txid).map(|raw| (raw, meta.fee)));
                                     if relay_da_tx {
                                         admitted_tx = add_remote;
                                     }
                                 }
+                                if let Relayed { txid } = &outcome {
+                                    if pool.contains(txid) {
+                                        promote_and_relay_tx_orphans(
+                                            &mut pool,
+                                            *txid,
+                                            sync_engine,
+                                            ctx,
+                                        );
+                                    }
+                                }
                             }
                             Err(_) => {
                                 self.peer.last_error =
@@ -941,13 +1096,43 @@ impl PeerSession {
                             }
                         }
                         if relay_da_tx {
-                            if let Some(tx_bytes) = admitted_tx {
+                            if let Some((tx_bytes, fee)) = admitted_tx {
                                 let prevalid = hash_checked && tx_bytes == msg.payload;
                                 let peer_addr = ctx.peer_registered_addr;
-                                self.stash_da_staging(peer_addr, tx_bytes, prevalid);
+                                self.stash_da_staging(peer_addr, tx_bytes, prevalid, fee);
                             }
                         }
                     }
+                    if let MissingInputs { txid } = &outcome {
+                        if !relay_da_tx {
+                            match ctx.tx_pool.lock() {
+                                Ok(mut pool) => admit_or_store_tx_orphan(
+                                    &mut pool,
+                                    *txid,
+                                    &msg.payload,
+                                    sync_engine,
+                                    ctx,
+                                ),
+                                Err(_) => {
+                                    self.peer.last_error =
+                                        "canonical tx_pool poisoned; peer-tx admission skipped"
+                                            .to_string();
+                                }
+                            }
+                        }
+                    }
+                    // Orphans this peer sent that proved invalid once their
+                    // parents arrived (possibly via another peer or a block).
+                    for reason in ctx
+                        .relay_state
+                        .tx_orphans
+                        .take_penalties(ctx.peer_registered_addr)
+                    {
+                        self.bump_ban(crate::tx_orphans::TX_ORPHAN_INVALID_BAN_SCORE, &reason);
+                        if self.peer.ban_score >= self.cfg.ban_threshold {
+                            return Err(io::Error::new(io::ErrorKind::InvalidData, reason));
+                        }
+                    }
                     // Mirror Go's `peer.handleTx` parse-fail policy: parse
                     // failures bump the peer ban score by 10 and fail the
                     // session only when the cumulative score crosses the
@@ -960,10 +1145,7 @@ impl PeerSession {
                         let reason = match &outcome {
                             crate::tx_relay::RelayTxOutcome::MalformedParse(r) => r.clone(),
                             crate::tx_relay::RelayTxOutcome::Oversized => {
-                                format!(
-                                    "tx payload exceeds MAX_RELAY_MSG_BYTES: {}",
-                                    msg.payload.len()
-                                )
+                                format!("tx payload exceeds MAX_TX_BYTES: {}", msg.payload.len())
                             }
                             _ => String::new(),
                         };
@@ -1078,12 +1260,24 @@ impl PeerSession {
         }
         let block_hash = block_hash(&block.header).map_err(io::Error::other)?;
         if sync_engine
-            .has_block(block_hash)
+            .has_block_or_low_work(block_hash)
             .map_err(io::Error::other)?
         {
             self.clear_compact_outstanding_request_for_block(block_hash);
             return Ok(LiveMessageOutcome::default());
         }
+        match sync_engine.precheck_header_timestamp(&parsed_header) {
+            Ok(Ok(())) => {}
+            Ok(Err(err)) => {
+                self.bump_ban(100, &err.to_string());
+                return Err(io::Error::new(io::ErrorKind::InvalidData, err.to_string()));
+            }
+            // Our own timestamp context is unavailable: not the peer's fault.
+            Err(err) => {
+                self.peer.last_error = format!("compact block timestamp precheck skipped: {err}");
+                return Ok(LiveMessageOutcome::default());
+            }
+        }
         let local_txs = match relay_ctx.and_then(|ctx| ctx.tx_pool.lock().ok()) {
             Some(pool) => pool.select_transactions(
                 COMPACT_LOCAL_TX_CANDIDATE_LIMIT,
@@ -1094,21 +1288,83 @@ impl PeerSession {
         let result = match reconstruct_compact_block(&block, &local_txs) {
             Ok(result) => result,
             Err(_err) if !block.short_ids.is_empty() => {
-                return self.request_compact_full_block_fallback(block_hash);
+                let mut outcome = self.request_compact_full_block_fallback(block_hash)?;
+                self.observe_compact_mode_block(sync_engine, true, &mut outcome);
+                return Ok(outcome);
             }
             Err(err) => return Err(err),
         };
-        if !result.transactions.is_empty() {
-            return self.process_compact_transactions(
+        with_relay_telemetry(|telemetry| {
+            telemetry.record_shortid_collisions(
+                block_hash,
+                &self.peer.addr,
+                result.shortid_collisions,
+            );
+            if !result.transactions.is_empty() {
+                telemetry.record_compact_block_bytes(&result.transactions, &[]);
+            }
+        });
+        let missed = result.transactions.is_empty();
+        let mut outcome = if missed {
+            self.request_missing_compact_transactions(block, block_hash, result)?
+        } else {
+            self.process_compact_transactions(
                 block_hash,
                 block.header,
                 &result.transactions,
                 sync_engine,
                 !block.short_ids.is_empty(),
                 relay_ctx,
-            );
+            )?
+        };
+        self.observe_compact_mode_block(sync_engine, missed, &mut outcome);
+        Ok(outcome)
+    }
+
+    /// Feed one relayed block into the local sendcmpct controller and queue a
+    /// `sendcmpct` when the selected mode changes. A no-op while local compact
+    /// receive is disabled, so mode 0 stays the only thing ever advertised.
+    fn observe_compact_mode_block(
+        &mut self,
+        sync_engine: &SyncEngine,
+        missed: bool,
+        outcome: &mut LiveMessageOutcome,
+    ) {
+        if !self.cfg.enable_compact_receive {
+            return;
         }
-        self.request_missing_compact_transactions(block, block_hash, result)
+        let in_ibd = sync_engine.is_in_ibd_unchecked();
+        let Some(change) = self.local_compact_mode.observe_block(in_ibd, missed) else {
+            return;
+        };
+        with_relay_telemetry(|telemetry| {
+            telemetry.record_sendcmpct_mode_change(&self.peer.addr, &change)
+        });
+        outcome.responses.push(sendcmpct_message(change.to));
+    }
+
+    /// Miss signal for a full block relayed while the local mode is 0: whether
+    /// it carried a non-coinbase tx the local pool lacks, i.e. whether compact
+    /// reconstruction would have needed `getblocktxn`. `None` for known or
+    /// unparseable blocks, blocks arriving as compact fallbacks (mode > 0), and
+    /// sessions without a pool to compare against.
+    fn full_block_compact_miss(
+        &self,
+        block_bytes: &[u8],
+        sync_engine: &SyncEngine,
+        relay_ctx: Option<&PeerRelayContext<'_>>,
+    ) -> Option<bool> {
+        if !self.cfg.enable_compact_receive || self.local_compact_mode.mode() != 0 {
+            return None;
+        }
+        let ctx = relay_ctx?;
+        let parsed = parse_block_bytes(block_bytes).ok()?;
+        let hash = block_hash(&parsed.header_bytes).ok()?;
+        if sync_engine.has_block(hash).unwrap_or(true) {
+            return None;
+        }
+        let pool = ctx.tx_pool.lock().ok()?;
+        Some(parsed.txids.iter().skip(1).any(|txid| !pool.contains(txid)))
     }
     fn request_missing_compact_transactions(
         &mut self,
@@ -1266,6 +1522,7 @@ impl PeerSession {
                 return Err(err);
             }
         };
+        let missing_indexes = req.missing_indexes.clone();
         let txs = match compact_fill_response_transactions(&req, response) {
             Ok(txs) => txs,
             Err(err)
@@ -1273,6 +1530,7 @@ impl PeerSession {
                     .to_string()
                     .contains("blocktxn transaction short id mismatch") =>
             {
+                with_relay_telemetry(|telemetry| telemetry.record_compact_recovery(false));
                 return self.request_compact_full_block_fallback(req.block_hash);
             }
             Err(err) => {
@@ -1280,6 +1538,10 @@ impl PeerSession {
                 return Err(err);
             }
         };
+        with_relay_telemetry(|telemetry| {
+            telemetry.record_compact_recovery(true);
+            telemetry.record_compact_block_bytes(&txs, &missing_indexes);
+        });
         self.process_compact_transactions(
             req.block_hash,
             req.header,
@@ -1455,7 +1717,7 @@ impl PeerSession {
             match vector.kind {
                 MSG_BLOCK
                     if !sync_engine
-                        .has_block(vector.hash)
+                        .has_block_or_low_work(vector.hash)
                         .map_err(io::Error::other)? =>
                 {
                     requests.push(vector);
@@ -1494,14 +1756,14 @@ impl PeerSession {
         let block_hash_bytes = block_hash(&parsed.header_bytes).map_err(io::Error::other)?;
         self.clear_compact_outstanding_request_for_block(block_hash_bytes);
         if sync_engine
-            .has_block(block_hash_bytes)
+            .has_block_or_low_work(block_hash_bytes)
             .map_err(io::Error::other)?
         {
             return Ok(RelayedBlockOutcome::default());
         }
         if parsed.header.prev_block_hash != [0u8; 32]
             && !sync_engine
-                .has_block(parsed.header.prev_block_hash)
+                .has_block_or_low_work(parsed.header.prev_block_hash)
                 .map_err(io::Error::other)?
         {
             return self.retain_or_resolve_orphan(
@@ -1539,6 +1801,26 @@ impl PeerSession {
                     accepted_blocks,
                 })
             }
+            Err(err) if is_low_work_side_block_err(&err) => {
+                self.bump_ban(LOW_WORK_SIDE_BLOCK_BAN_SCORE, &err);
+                let mut tx_pool_cleanup = TxPoolCleanupPlan::default();
+                let mut accepted_blocks = 0;
+                if let Err(err) = self.resolve_orphans(
+                    block_hash_bytes,
+                    sync_engine,
+                    &mut tx_pool_cleanup,
+                    &mut accepted_blocks,
+                    relay_ctx,
+                ) {
+                    self.stash_pending_tx_pool_cleanup(tx_pool_cleanup);
+                    self.advance_da_orphan_ttl_for_accepted_blocks(relay_ctx, accepted_blocks);
+                    return Err(err);
+                }
+                Ok(RelayedBlockOutcome {
+                    tx_pool_cleanup,
+                    accepted_blocks,
+                })
+            }
             Err(err) if is_parent_not_found_err(&err) => Err(io::Error::other(format!(
                 "unexpected missing-parent after precheck: {err}"
             ))),
@@ -1561,7 +1843,7 @@ impl PeerSession {
             global_orphan_byte_limit(),
         );
         if sync_engine
-            .has_block(parent_hash)
+            .has_block_or_low_work(parent_hash)
             .map_err(io::Error::other)?
         {
             let mut tx_pool_cleanup = TxPoolCleanupPlan::default();
@@ -1610,6 +1892,10 @@ impl PeerSession {
                     *accepted_blocks = accepted_blocks.saturating_add(1);
                     ready.extend(self.orphans.take_children(child.block_hash));
                 }
+                Err(err) if is_low_work_side_block_err(&err) => {
+                    self.bump_ban(LOW_WORK_SIDE_BLOCK_BAN_SCORE, &err);
+                    ready.extend(self.orphans.take_children(child.block_hash));
+                }
                 Err(err) if is_parent_not_found_err(&err) => {
                     self.orphans.add(
                         child.block_hash,
@@ -1640,8 +1926,15 @@ impl PeerSession {
                 "DA relay lock poisoned during accepted-block TTL advance".into();
             return;
         };
-        if let Err(err) = da_relay.advance_orphan_ttl_by(accepted_blocks) {
-            self.peer.last_error = format!("DA relay TTL advance failed: {err:?}");
+        match da_relay.advance_orphan_ttl_by(accepted_blocks) {
+            Ok(expired) => {
+                let now_ms = now_nanos() / 1_000_000;
+                da_relay.observe_set_recoveries(now_ms);
+                with_relay_telemetry(|telemetry| {
+                    telemetry.record_da_expired(&da_relay, &expired, now_ms)
+                })
+            }
+            Err(err) => self.peer.last_error = format!("DA relay TTL advance failed: {err:?}"),
         }
     }
 
@@ -2013,6 +2306,7 @@ fn pop_expired_compact_outstanding(
     {
         return None;
     }
+    with_relay_telemetry(|telemetry| telemetry.record_compact_recovery(false));
     compact_outstanding
         .take()
         .map(|req| (req.block_hash, req.blocktxn_payload_cap))
@@ -2725,7 +3019,14 @@ pub fn reconstruct_compact_block(
         });
     }
 
-    let mut local_index = compact_local_tx_index(local_txs, payload.nonce1, payload.nonce2)?;
+    let (mut local_index, local_collisions) =
+        compact_local_tx_index(local_txs, payload.nonce1, payload.nonce2)?;
+    let mut block_short_ids = HashMap::with_capacity(payload.short_ids.len());
+    for short_id in &payload.short_ids {
+        *block_short_ids.entry(*short_id).or_insert(0u32) += 1;
+    }
+    let shortid_collisions =
+        local_collisions + block_short_ids.values().filter(|&&n| n > 1).count() as u64;
     for short_id in prefilled_short_ids {
         local_index.insert(short_id, None);
     }
@@ -2742,6 +3043,7 @@ pub fn reconstruct_compact_block(
                 .into_iter()
                 .map(|tx| tx.ok_or_else(|| invalid_data("compact block transaction missing")))
                 .collect::<io::Result<Vec<_>>>()?,
+            shortid_collisions,
             ..Default::default()
         });
     }
@@ -2749,6 +3051,7 @@ pub fn reconstruct_compact_block(
         partial_transactions: partial,
         missing_indexes: missing.0,
         missing_short_ids: missing.1,
+        shortid_collisions,
         ..Default::default()
     })
 }
@@ -2774,12 +3077,13 @@ fn compact_local_tx_index(
     local_txs: &[Vec<u8>],
     nonce1: u64,
     nonce2: u64,
-) -> io::Result<CompactLocalIndex> {
+) -> io::Result<(CompactLocalIndex, u64)> {
     if local_txs.len() > COMPACT_LOCAL_TX_CANDIDATE_LIMIT {
         return Err(invalid_data("too many compact relay local candidates"));
     }
     let mut total_tx_bytes = 0usize;
-    let mut out = HashMap::with_capacity(local_txs.len());
+    let mut collisions = 0u64;
+    let mut out: CompactLocalIndex = HashMap::with_capacity(local_txs.len());
     for tx in local_txs {
         if validate_blocktxn_transaction_size(tx.len() as u64, 0).is_err() {
             continue;
@@ -2795,13 +3099,15 @@ fn compact_local_tx_index(
         }
         let short_id = compact_shortid(wtxid, nonce1, nonce2);
         if let Some(slot) = out.get_mut(&short_id) {
-            *slot = None;
+            if slot.take().is_some() {
+                collisions += 1;
+            }
             continue;
         }
         total_tx_bytes += tx.len();
         out.insert(short_id, Some(tx.clone()));
     }
-    Ok(out)
+    Ok((out, collisions))
 }
 
 fn compact_missing(
@@ -3532,7 +3838,8 @@ fn runtime_payload_cap(command: &str) -> u64 {
         MESSAGE_SENDCMPCT => SENDCMPCT_PAYLOAD_BYTES,
         MESSAGE_INV | MESSAGE_GETDATA | MESSAGE_GETBLOCKS => MAX_INVENTORY_PAYLOAD_BYTES,
         MESSAGE_ADDR => MAX_ADDR_PAYLOAD_BYTES,
-        MESSAGE_BLOCK | MESSAGE_TX => MAX_BLOCK_BYTES,
+        MESSAGE_BLOCK => MAX_BLOCK_BYTES,
+        MESSAGE_TX => crate::txpool::MAX_TX_BYTES,
         "headers" => MAX_HEADERS_PAYLOAD_BYTES,
         _ => 0,
     }
@@ -3545,8 +3852,14 @@ fn runtime_payload_cap(command: &str) -> u64 {
 /// requesting compact-block announcements, so block relay stays on the
 /// full-block path; the peer records this via `handle_sendcmpct`.
 pub(crate) fn sendcmpct_advertisement_message() -> WireMessage {
+    sendcmpct_message(0)
+}
+
+/// `sendcmpct` for `mode` at `COMPACT_RELAY_VERSION`; mode changes chosen by
+/// the per-session `SendCmpctModeController` go out through this.
+pub(crate) fn sendcmpct_message(mode: u8) -> WireMessage {
     let mut payload = Vec::with_capacity(SENDCMPCT_PAYLOAD_BYTES as usize);
-    payload.push(0u8); // mode 0
+    payload.push(mode);
     payload.extend_from_slice(&COMPACT_RELAY_VERSION.to_le_bytes());
     WireMessage {
         command: MESSAGE_SENDCMPCT.to_string(),
@@ -5629,7 +5942,7 @@ mod tests {
         let mut header = [0u8; WIRE_HEADER_SIZE];
         header[0..4].copy_from_slice(&network_magic("devnet"));
         header[4..16].copy_from_slice(&encode_wire_command(MESSAGE_TX).expect("command"));
-        let oversize = (MAX_BLOCK_BYTES + 1) as u32;
+        let oversize = (crate::txpool::MAX_TX_BYTES + 1) as u32;
         header[16..20].copy_from_slice(&oversize.to_le_bytes());
 
         let mut reader = std::io::Cursor::new(header);
@@ -6480,6 +6793,8 @@ mod tests {
         assert!(runtime_payload_cap(MESSAGE_GETDATA) > 0);
         assert!(runtime_payload_cap(MESSAGE_ADDR) > 0);
         assert!(runtime_payload_cap(MESSAGE_SENDCMPCT) == SENDCMPCT_PAYLOAD_BYTES);
+        assert_eq!(runtime_payload_cap(MESSAGE_BLOCK), MAX_BLOCK_BYTES);
+        assert_eq!(runtime_payload_cap(MESSAGE_TX), crate::txpool::MAX_TX_BYTES);
 
         // headers gets an explicit cap matching MAX_HEADERS_PAYLOAD_BYTES.
         assert_eq!(runtime_payload_cap("headers"), MAX_HEADERS_PAYLOAD_BYTES);
@@ -6491,6 +6806,112 @@ mod tests {
         assert_eq!(runtime_payload_cap(""), 0);
     }
 
+    #[test]
+    fn sendcmpct_controller_downgrades_after_reconstruction_misses() {
+        let (mut session, _client) = test_peer_session();
+        let mut engine = test_sync_engine_with_genesis();
+        engine.cfg.ibd_lag_seconds = u64::MAX;
+        for _ in 0..crate::compact_mode::SENDCMPCT_WARMUP_BLOCKS {
+            session.local_compact_mode.observe_block(false, false);
+        }
+        assert_eq!(session.local_compact_mode.mode(), 2);
+
+        // Unknown short ids: the first block needs getblocktxn, the second
+        // falls back to a full block while the first is still outstanding.
+        let mut announced = Vec::new();
+        for n in 1..=2u8 {
+            let block = build_block_bytes([n; 32], [0u8; 32], POW_LIMIT, 1, &[]);
+            let mut header = [0u8; BLOCK_HEADER_BYTES];
+            header.copy_from_slice(&block[..BLOCK_HEADER_BYTES]);
+            let payload = encode_cmpctblock_payload(CmpctBlockPayload {
+                header,
+                nonce1: 0,
+                nonce2: 0,
+                short_ids: vec![[0x99; COMPACT_SHORT_ID_BYTES]],
+                prefilled: Vec::new(),
+            })
+            .expect("encode cmpctblock");
+            let outcome = session
+                .collect_live_responses(
+                    WireMessage {
+                        command: "cmpctblock".to_string(),
+                        payload,
+                    },
+                    &mut engine,
+                    None,
+                )
+                .expect("cmpctblock");
+            assert_eq!(
+                outcome.responses[0].command,
+                [MESSAGE_GETBLOCKTXN, MESSAGE_GETDATA][usize::from(n - 1)]
+            );
+            announced.extend(
+                outcome
+                    .responses
+                    .iter()
+                    .filter(|msg| msg.command == MESSAGE_SENDCMPCT)
+                    .map(|msg| {
+                        parse_sendcmpct_runtime_payload(&msg.payload)
+                            .expect("mode")
+                            .mode
+                    }),
+            );
+        }
+        assert_eq!(announced, vec![0], "one downgrade, announced once");
+        assert_eq!(session.local_compact_mode.mode(), 0);
+        let event = with_relay_telemetry(|telemetry| telemetry.sendcmpct_mode_events())
+            .into_iter()
+            .rev()
+            .find(|event| event.peer == session.peer.addr)
+            .expect("mode change event");
+        assert_eq!((event.from, event.to), (2, 0));
+        assert_eq!(event.miss_rate_blocks, 6);
+    }
+
+    #[test]
+    fn cmpctblock_timestamp_precheck_bans_only_on_consensus_rejection() {
+        let (mut session, _client) = test_peer_session();
+        let mut engine = test_sync_engine_with_genesis();
+        let tip_hash = engine.chain_state.tip_hash;
+        let cmpctblock = |timestamp: u64| {
+            let block = build_block_bytes(tip_hash, [0u8; 32], POW_LIMIT, timestamp, &[]);
+            let mut header = [0u8; BLOCK_HEADER_BYTES];
+            header.copy_from_slice(&block[..BLOCK_HEADER_BYTES]);
+            WireMessage {
+                command: "cmpctblock".to_string(),
+                payload: encode_cmpctblock_payload(CmpctBlockPayload {
+                    header,
+                    nonce1: 0,
+                    nonce2: 0,
+                    short_ids: vec![[0x99; COMPACT_SHORT_ID_BYTES]],
+                    prefilled: Vec::new(),
+                })
+                .expect("encode cmpctblock"),
+            }
+        };
+
+        // A tip whose MTP window is missing from the blockstore is a local
+        // fault: the announcement is dropped without penalty.
+        engine.chain_state.height = 5;
+        let outcome = session
+            .collect_live_responses(cmpctblock(0), &mut engine, None)
+            .expect("dropped");
+        assert!(outcome.responses.is_empty());
+        assert_eq!(session.peer.ban_score, 0);
+        assert!(
+            session.peer.last_error.contains("precheck skipped"),
+            "{}",
+            session.peer.last_error
+        );
+
+        engine.chain_state.height = 0;
+        let err = session
+            .collect_live_responses(cmpctblock(0), &mut engine, None)
+            .expect_err("stale timestamp");
+        assert!(err.to_string().contains("BLOCK_ERR_TIMESTAMP_OLD"), "{err}");
+        assert_eq!(session.peer.ban_score, 100);
+    }
+
     #[test]
     fn sendcmpct_live_dispatch_records_peer_mode() {
         let listener = TcpListener::bind("127.0.0.1:0").expect("bind");
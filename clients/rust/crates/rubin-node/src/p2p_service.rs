@@ -73,6 +73,13 @@ impl CompleteDaSetProvider for ServiceCompleteDaSetProvider {
         };
         relay_snapshot.complete_da_set_candidates(max_payload_bytes)
     }
+
+    fn is_complete(&self, da_id: [u8; 32]) -> bool {
+        match self.da_relay.lock() {
+            Ok(relay) => relay.is_complete(da_id),
+            Err(poisoned) => poisoned.into_inner().is_complete(da_id),
+        }
+    }
 }
 
 #[derive(Clone)]
@@ -182,6 +189,7 @@ pub fn start_node_p2p_service(cfg: NodeP2PServiceConfig) -> Result<RunningNodeP2
     let da_relay = Arc::new(Mutex::new(
         DaRelayState::new(DaRelayCaps::default()).map_err(|err| format!("{err:?}"))?,
     ));
+    let prefetch_state = DaRelayPrefetchState::with_rate_config(cfg.runtime_cfg.da_prefetch_rate);
     let shared = SharedServiceState {
         stop: Arc::clone(&stop),
         runtime_cfg: cfg.runtime_cfg,
@@ -193,7 +201,7 @@ pub fn start_node_p2p_service(cfg: NodeP2PServiceConfig) -> Result<RunningNodeP2
         sync_engine: cfg.sync_engine,
         tx_pool: cfg.tx_pool,
         da_relay,
-        prefetch_state: Arc::new(Mutex::new(DaRelayPrefetchState::default())),
+        prefetch_state: Arc::new(Mutex::new(prefetch_state)),
         bootstrap_peers: Arc::new(cfg.bootstrap_peers),
         bootstrap_rotate_idx: Arc::new(AtomicUsize::new(0)),
         in_flight_dials: Arc::new(Mutex::new(HashSet::new())),
@@ -935,6 +943,11 @@ fn handle_peer(
     }
 
     while !shared.stop.load(Ordering::SeqCst) {
+        session.send_ready_da_prefetch_plans(
+            relay_ctx.prefetch,
+            relay_ctx.peer_manager,
+            relay_ctx.peer_writers,
+        );
         flush_peer_outbox(&shared, &peer_addr, |frame| session.write_raw(frame))?;
         match session.poll_read_ready(live_loop_poll_timeout(session.read_deadline())) {
             Ok(true) => {}
@@ -1067,12 +1080,32 @@ fn apply_tx_pool_cleanup(
         // parent. Match Go's post-success best-effort cleanup shape: confirmed
         // evictions, conflict removals, and successful requeues remain visible
         // even if another requeue item reports a failure.
-        tx_pool_cleanup.apply_with_report(
+        let report = tx_pool_cleanup.apply_with_report(
             &mut tx_pool,
             &chain_state,
             block_store.as_ref(),
             chain_id,
-        )
+        );
+        // Orphans whose parents just confirmed are re-admitted against the
+        // new tip; those past their TTL are dropped first.
+        let orphans = &shared.relay_state.tx_orphans;
+        orphans.expire(chain_state.height);
+        let promoted = crate::tx_orphans::promote_tx_orphans(
+            orphans,
+            &mut tx_pool,
+            tx_pool_cleanup.confirmed_txids().iter().copied(),
+            &chain_state,
+            block_store.as_ref(),
+            chain_id,
+        );
+        crate::tx_relay::relay_promoted_tx_orphans(
+            &promoted,
+            &shared.relay_state,
+            &shared.peer_manager,
+            &shared.local_addr,
+            &shared.peer_outboxes,
+        );
+        report
     };
     if report.has_requeue_failures() {
         return Ok(Some(report.requeue_failure_summary()));
@@ -1412,7 +1445,8 @@ mod tests {
     use rubin_consensus::{
         block_hash,
         constants::{COV_TYPE_DA_COMMIT, POW_LIMIT, TX_WIRE_VERSION},
-        marshal_tx, parse_tx, DaChunkCore, DaCommitCore, Tx, TxOutput, BLOCK_HEADER_BYTES,
+        marshal_tx, parse_tx, DaChunkCore, DaCommitCore, Outpoint, Tx, TxOutput,
+        BLOCK_HEADER_BYTES,
     };
     use sha3::Digest;
 
@@ -2048,6 +2082,54 @@ mod tests {
         drop(pool);
     }
 
+    #[test]
+    fn apply_tx_pool_cleanup_promotes_orphan_whose_parent_confirmed() {
+        let (shared, dir) = test_shared_state_after_genesis("rubin-node-p2p-cleanup-orphan");
+        let _dir = TempDirCleanupGuard { path: dir };
+        let (state, orphan_raw, _) = signed_conflicting_p2pk_state_and_txs(
+            CLEANUP_TEST_AMOUNT,
+            CLEANUP_TEST_FEE,
+            CLEANUP_TEST_WEIGHT,
+        );
+        let (orphan_tx, orphan_txid, _, _) = parse_tx(&orphan_raw).expect("parse orphan tx");
+        let parent = Outpoint {
+            txid: orphan_tx.inputs[0].prev_txid,
+            vout: orphan_tx.inputs[0].prev_vout,
+        };
+        let height = shared
+            .sync_engine
+            .lock()
+            .expect("sync engine")
+            .chain_state
+            .height;
+        // Arrived while its parent was unknown; the parent then confirms in
+        // a block without ever passing through the mempool.
+        shared.relay_state.tx_orphans.add(
+            orphan_txid,
+            &orphan_raw,
+            vec![parent.clone()],
+            "peer-a:8333",
+            height,
+        );
+        shared
+            .sync_engine
+            .lock()
+            .expect("sync engine")
+            .chain_state
+            .utxos = state.utxos.clone();
+        let cleanup =
+            TxPoolCleanupPlan::from_parts_for_test(vec![parent.txid], Vec::new(), Vec::new());
+
+        assert_eq!(
+            apply_tx_pool_cleanup(&shared, cleanup).expect("cleanup"),
+            None
+        );
+        let pool = shared.tx_pool.lock().expect("tx pool");
+        assert_eq!(pool.entry_source(&orphan_txid), Some(TxSource::Remote));
+        assert!(shared.relay_state.tx_orphans.is_empty());
+        assert!(shared.relay_state.relay_pool.has(&orphan_txid));
+    }
+
     #[test]
     fn maybe_apply_tx_pool_cleanup_skips_empty_plan() {
         let (sync_engine, dir) = test_engine("rubin-node-p2p-maybe-cleanup");
@@ -0,0 +1,242 @@
+//! Dust relay policy: an output is dust when spending it costs more in fees
+//! than it is worth. The cost is the weight a minimal spending input adds to
+//! a transaction (outpoint, sequence and the ML-DSA-87 witness for the
+//! covenant's cheapest spend path, including the suite's verify cost) times
+//! the dust relay fee rate. Policy only; consensus accepts any value.
+
+use rubin_consensus::constants::{
+    COV_TYPE_HTLC, COV_TYPE_MULTISIG, COV_TYPE_P2PK, COV_TYPE_VAULT, ML_DSA_87_PUBKEY_BYTES,
+    ML_DSA_87_SIG_BYTES, SUITE_ID_ML_DSA_87, SUITE_ID_SENTINEL,
+};
+use rubin_consensus::{tx_weight_and_stats_public, Tx, TxInput, WitnessItem};
+use serde::Serialize;
+
+use crate::txpool::{TxPoolConfig, DEFAULT_MEMPOOL_MIN_FEE_RATE};
+
+/// Fee rate, per weight unit, at which outputs are priced for dust when the
+/// rule is enabled. Matches the relay floor: an output is dust exactly when
+/// the floor fee for spending it exceeds its value.
+pub const DEFAULT_DUST_RELAY_FEE_RATE: u64 = DEFAULT_MEMPOOL_MIN_FEE_RATE;
+
+/// Covenant types with a dust rule, in `policyinfo` order.
+pub const DUST_COVENANT_TYPES: [u16; 4] = [
+    COV_TYPE_P2PK,
+    COV_TYPE_MULTISIG,
+    COV_TYPE_VAULT,
+    COV_TYPE_HTLC,
+];
+
+fn ml_dsa_87_item() -> WitnessItem {
+    WitnessItem {
+        suite_id: SUITE_ID_ML_DSA_87,
+        pubkey: vec![0u8; ML_DSA_87_PUBKEY_BYTES as usize],
+        signature: vec![0u8; ML_DSA_87_SIG_BYTES as usize],
+    }
+}
+
+/// Witness of the cheapest spend of `covenant_type`: one signature for P2PK
+/// and for 1-of-1 MULTISIG and VAULT; the refund selector plus one signature
+/// for HTLC. `None` for covenants without a dust rule (CORE_ANCHOR and DA
+/// commits carry no value; the rest have no fixed spend shape).
+fn minimal_spend_witness(covenant_type: u16) -> Option<Vec<WitnessItem>> {
+    match covenant_type {
+        COV_TYPE_P2PK | COV_TYPE_MULTISIG | COV_TYPE_VAULT => Some(vec![ml_dsa_87_item()]),
+        COV_TYPE_HTLC => Some(vec![
+            WitnessItem {
+                suite_id: SUITE_ID_SENTINEL,
+                pubkey: vec![0u8; 32],
+                signature: vec![0x01],
+            },
+            ml_dsa_87_item(),
+        ]),
+        _ => None,
+    }
+}
+
+fn synthetic_tx(inputs: Vec<TxInput>, witness: Vec<WitnessItem>) -> Tx {
+    Tx {
+        version: 1,
+        tx_kind: 0x00,
+        tx_nonce: 1,
+        inputs,
+        outputs: Vec::new(),
+        locktime: 0,
+        da_commit_core: None,
+        da_chunk_core: None,
+        witness,
+        da_payload: Vec::new(),
+    }
+}
+
+/// Weight one minimal spending input of `covenant_type` adds to a
+/// transaction, measured with the consensus weight function.
+pub fn dust_spend_weight(covenant_type: u16) -> Option<u64> {
+    let witness = minimal_spend_witness(covenant_type)?;
+    let input = TxInput {
+        prev_txid: [0u8; 32],
+        prev_vout: 0,
+        script_sig: Vec::new(),
+        sequence: 0,
+    };
+    let (with_input, _, _) =
+        tx_weight_and_stats_public(&synthetic_tx(vec![input], witness)).ok()?;
+    let (without, _, _) = tx_weight_and_stats_public(&synthetic_tx(Vec::new(), Vec::new())).ok()?;
+    Some(with_input - without)
+}
+
+/// Smallest standard value for an output of `covenant_type` at `fee_rate`;
+/// 0 (no rule) for covenants without a spend shape or when `fee_rate` is 0.
+pub fn dust_threshold(covenant_type: u16, fee_rate: u64) -> u64 {
+    dust_spend_weight(covenant_type).map_or(0, |weight| weight.saturating_mul(fee_rate))
+}
+
+/// Relay policy: refuses outputs below their dust threshold. CORE_ANCHOR
+/// outputs have no threshold (their value must be 0 anyway).
+pub(crate) fn reject_dust_outputs(tx: &Tx, fee_rate: u64) -> Result<(), String> {
+    if fee_rate == 0 {
+        return Ok(());
+    }
+    for (index, output) in tx.outputs.iter().enumerate() {
+        let threshold = dust_threshold(output.covenant_type, fee_rate);
+        if output.value < threshold {
+            return Err(format!(
+                "dust output (index={index} covenant_type={:#06x} value={} threshold={threshold}) (policy)",
+                output.covenant_type, output.value
+            ));
+        }
+    }
+    Ok(())
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct DustThreshold {
+    pub covenant_type: u16,
+    pub spend_weight: u64,
+    pub threshold: u64,
+}
+
+/// Economic relay defaults, as printed by `rubin-node --policyinfo`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct PolicyInfo {
+    pub min_relay_fee_rate: u64,
+    pub min_da_fee_rate: u64,
+    pub dust_relay_fee_rate: u64,
+    /// Whether the default mempool config enforces the dust rule.
+    pub dust_enforced: bool,
+    /// Thresholds at `dust_relay_fee_rate`.
+    pub dust_thresholds: Vec<DustThreshold>,
+}
+
+impl PolicyInfo {
+    pub fn defaults() -> Self {
+        let cfg = TxPoolConfig::default();
+        let dust_relay_fee_rate = DEFAULT_DUST_RELAY_FEE_RATE;
+        let dust_thresholds = DUST_COVENANT_TYPES
+            .iter()
+            .filter_map(|&covenant_type| {
+                let spend_weight = dust_spend_weight(covenant_type)?;
+                Some(DustThreshold {
+                    covenant_type,
+                    spend_weight,
+                    threshold: spend_weight.saturating_mul(dust_relay_fee_rate),
+                })
+            })
+            .collect();
+        Self {
+            min_relay_fee_rate: cfg
+                .policy_current_mempool_min_fee_rate
+                .max(DEFAULT_MEMPOOL_MIN_FEE_RATE),
+            min_da_fee_rate: cfg.policy_min_da_fee_rate,
+            dust_relay_fee_rate,
+            dust_enforced: cfg.policy_dust_relay_fee_rate != 0,
+            dust_thresholds,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rubin_consensus::constants::{COV_TYPE_ANCHOR, COV_TYPE_DA_COMMIT};
+    use rubin_consensus::TxOutput;
+
+    use super::*;
+
+    /// Reference fee rate for the pinned thresholds below. A change to the
+    /// weight model or witness sizes shows up here as a policy diff.
+    const REFERENCE_FEE_RATE: u64 = 10;
+
+    #[test]
+    fn dust_thresholds_are_pinned_at_reference_fee_rate() {
+        let pinned = [
+            (COV_TYPE_P2PK, 73_900),
+            (COV_TYPE_HTLC, 74_260),
+            (COV_TYPE_VAULT, 73_900),
+        ];
+        for (covenant_type, want) in pinned {
+            assert_eq!(
+                dust_threshold(covenant_type, REFERENCE_FEE_RATE),
+                want,
+                "covenant_type={covenant_type:#06x}"
+            );
+        }
+    }
+
+    #[test]
+    fn dust_threshold_scales_with_fee_rate_and_skips_valueless_covenants() {
+        let weight = dust_spend_weight(COV_TYPE_P2PK).expect("p2pk");
+        assert_eq!(dust_threshold(COV_TYPE_P2PK, 0), 0);
+        assert_eq!(dust_threshold(COV_TYPE_P2PK, 3), weight * 3);
+        assert_eq!(dust_spend_weight(COV_TYPE_MULTISIG), Some(weight));
+        assert!(dust_spend_weight(COV_TYPE_HTLC).expect("htlc") > weight);
+        for covenant_type in [COV_TYPE_ANCHOR, COV_TYPE_DA_COMMIT, 0x7777] {
+            assert_eq!(dust_threshold(covenant_type, u64::MAX), 0);
+        }
+    }
+
+    #[test]
+    fn reject_dust_outputs_names_the_first_dust_output() {
+        let threshold = dust_threshold(COV_TYPE_P2PK, DEFAULT_DUST_RELAY_FEE_RATE);
+        let output = |covenant_type, value| TxOutput {
+            value,
+            covenant_type,
+            covenant_data: Vec::new(),
+        };
+        let mut tx = synthetic_tx(Vec::new(), Vec::new());
+        tx.outputs = vec![
+            output(COV_TYPE_P2PK, threshold),
+            output(COV_TYPE_ANCHOR, 0),
+            output(COV_TYPE_P2PK, threshold - 1),
+        ];
+        let err = reject_dust_outputs(&tx, DEFAULT_DUST_RELAY_FEE_RATE).unwrap_err();
+        assert_eq!(
+            err,
+            format!(
+                "dust output (index=2 covenant_type=0x0000 value={} threshold={threshold}) (policy)",
+                threshold - 1
+            )
+        );
+        assert!(reject_dust_outputs(&tx, 0).is_ok());
+        tx.outputs.pop();
+        assert!(reject_dust_outputs(&tx, DEFAULT_DUST_RELAY_FEE_RATE).is_ok());
+    }
+
+    #[test]
+    fn policy_info_lists_default_thresholds() {
+        let info = PolicyInfo::defaults();
+        assert_eq!(info.min_relay_fee_rate, DEFAULT_MEMPOOL_MIN_FEE_RATE);
+        assert_eq!(info.dust_relay_fee_rate, DEFAULT_DUST_RELAY_FEE_RATE);
+        assert!(!info.dust_enforced);
+        let types: Vec<u16> = info
+            .dust_thresholds
+            .iter()
+            .map(|t| t.covenant_type)
+            .collect();
+        assert_eq!(types, DUST_COVENANT_TYPES);
+        for entry in &info.dust_thresholds {
+            assert_eq!(
+                entry.threshold,
+                dust_threshold(entry.covenant_type, DEFAULT_DUST_RELAY_FEE_RATE)
+            );
+        }
+    }
+}
@@ -0,0 +1,425 @@
+//! Per-peer inventory bookkeeping for block and tx relay.
+//!
+//! `TxRelayState` dedups globally (has this node seen the item at all);
+//! this module answers the per-connection questions: does this peer already
+//! know the item, because it announced or sent it to us or we announced or
+//! sent it to them, and is a GETDATA for it already outstanding to some
+//! peer. Announcing only to peers that do not know an item, and requesting
+//! an item from one peer at a time, keeps each connection carrying it at
+//! most once in each direction.
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use crate::p2p_runtime::{InventoryVector, PeerState, MSG_BLOCK, MSG_TX};
+use crate::tx_seen::BoundedHashSet;
+
+/// Txids remembered per peer as known to it.
+pub const ANNOUNCE_KNOWN_TX_CAPACITY: usize = 5_000;
+/// Block hashes remembered per peer as known to it.
+pub const ANNOUNCE_KNOWN_BLOCK_CAPACITY: usize = 1_024;
+/// How long a GETDATA may stay unanswered before another peer is asked.
+pub const GETDATA_IN_FLIGHT_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// sendcmpct mode in which the peer wants unsolicited compact blocks.
+const COMPACT_HIGH_BANDWIDTH_MODE: u8 = 2;
+
+/// What one peer knows and what we have asked it for.
+pub struct AnnouncementTracker {
+    known_txs: BoundedHashSet,
+    known_blocks: BoundedHashSet,
+    /// Outstanding GETDATA items sent to this peer.
+    in_flight: HashMap<InventoryVector, Instant>,
+}
+
+impl Default for AnnouncementTracker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AnnouncementTracker {
+    pub fn new() -> Self {
+        Self {
+            known_txs: BoundedHashSet::new(ANNOUNCE_KNOWN_TX_CAPACITY),
+            known_blocks: BoundedHashSet::new(ANNOUNCE_KNOWN_BLOCK_CAPACITY),
+            in_flight: HashMap::new(),
+        }
+    }
+
+    /// Records that the peer has `item`. Returns `true` if it was not
+    /// already recorded. Kinds other than block and tx are ignored.
+    pub fn mark_known(&self, item: &InventoryVector) -> bool {
+        match item.kind {
+            MSG_TX => self.known_txs.add(item.hash),
+            MSG_BLOCK => self.known_blocks.add(item.hash),
+            _ => false,
+        }
+    }
+
+    pub fn knows(&self, item: &InventoryVector) -> bool {
+        match item.kind {
+            MSG_TX => self.known_txs.has(&item.hash),
+            MSG_BLOCK => self.known_blocks.has(&item.hash),
+            _ => false,
+        }
+    }
+
+    pub fn in_flight_len(&self) -> usize {
+        self.in_flight.len()
+    }
+}
+
+/// How a new tip is announced to one peer.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BlockAnnouncement {
+    /// Block inventory; the peer fetches with GETDATA. Used for peers in
+    /// sendcmpct mode 0 or 1, since there is no `headers` message.
+    Inv,
+    /// Unsolicited `cmpctblock`, for peers in high-bandwidth mode 2.
+    CompactBlock,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct InFlightRequest {
+    addr: String,
+    requested_at: Instant,
+}
+
+/// Announcement and request policy across all connected peers.
+pub struct RelayAnnouncePolicy {
+    peers: HashMap<String, AnnouncementTracker>,
+    in_flight: HashMap<InventoryVector, InFlightRequest>,
+    getdata_timeout: Duration,
+}
+
+impl Default for RelayAnnouncePolicy {
+    fn default() -> Self {
+        Self::new(GETDATA_IN_FLIGHT_TIMEOUT)
+    }
+}
+
+impl RelayAnnouncePolicy {
+    pub fn new(getdata_timeout: Duration) -> Self {
+        Self {
+            peers: HashMap::new(),
+            in_flight: HashMap::new(),
+            getdata_timeout,
+        }
+    }
+
+    pub fn add_peer(&mut self, addr: &str) {
+        self.peers.entry(addr.to_string()).or_default();
+    }
+
+    /// Forgets the peer and frees its outstanding requests so the next
+    /// announcement of those items is fetched from someone else.
+    pub fn remove_peer(&mut self, addr: &str) {
+        self.peers.remove(addr);
+        self.in_flight.retain(|_, req| req.addr != addr);
+    }
+
+    pub fn tracker(&self, addr: &str) -> Option<&AnnouncementTracker> {
+        self.peers.get(addr)
+    }
+
+    /// Records an INV from `addr`: the peer has every announced item.
+    pub fn note_inv(&mut self, addr: &str, items: &[InventoryVector]) {
+        let tracker = self.peers.entry(addr.to_string()).or_default();
+        for item in items {
+            tracker.mark_known(item);
+        }
+    }
+
+    /// Picks which of `wanted` (items announced by `addr` that this node
+    /// lacks) to GETDATA from `addr` now, and marks them in flight. Items
+    /// already requested from any peer are skipped until that request is
+    /// older than the timeout; a timed-out request moves to `addr`.
+    pub fn select_getdata(
+        &mut self,
+        addr: &str,
+        wanted: &[InventoryVector],
+        now: Instant,
+    ) -> Vec<InventoryVector> {
+        let mut out = Vec::new();
+        for item in wanted {
+            if let Some(req) = self.in_flight.get(item) {
+                if now.saturating_duration_since(req.requested_at) < self.getdata_timeout {
+                    continue;
+                }
+                let stale = req.addr.clone();
+                if let Some(tracker) = self.peers.get_mut(&stale) {
+                    tracker.in_flight.remove(item);
+                }
+            }
+            self.in_flight.insert(
+                *item,
+                InFlightRequest {
+                    addr: addr.to_string(),
+                    requested_at: now,
+                },
+            );
+            self.peers
+                .entry(addr.to_string())
+                .or_default()
+                .in_flight
+                .insert(*item, now);
+            out.push(*item);
+        }
+        out
+    }
+
+    /// Records that `addr` delivered `item` (block or tx body): the request
+    /// is settled and the peer knows the item.
+    pub fn note_received(&mut self, addr: &str, item: &InventoryVector) {
+        if self.in_flight.get(item).is_some_and(|req| req.addr == addr) {
+            self.in_flight.remove(item);
+        }
+        let tracker = self.peers.entry(addr.to_string()).or_default();
+        tracker.in_flight.remove(item);
+        tracker.mark_known(item);
+    }
+
+    /// Records that we served `item` to `addr` in answer to its GETDATA.
+    pub fn note_sent(&mut self, addr: &str, item: &InventoryVector) {
+        self.peers
+            .entry(addr.to_string())
+            .or_default()
+            .mark_known(item);
+    }
+
+    /// Peers among `addrs` to send a tx INV for `txid`: those not known to
+    /// have it. The chosen peers are marked as knowing it.
+    pub fn tx_announce_targets(&mut self, txid: [u8; 32], addrs: &[String]) -> Vec<String> {
+        let item = InventoryVector {
+            kind: MSG_TX,
+            hash: txid,
+        };
+        addrs
+            .iter()
+            .filter(|addr| {
+                self.peers
+                    .entry((*addr).clone())
+                    .or_default()
+                    .mark_known(&item)
+            })
+            .cloned()
+            .collect()
+    }
+
+    /// How to announce a new tip `block_hash` to each peer that does not
+    /// know it yet, picked from the peer's negotiated sendcmpct mode. The
+    /// chosen peers are marked as knowing the block.
+    pub fn block_announce_targets(
+        &mut self,
+        block_hash: [u8; 32],
+        peers: &[PeerState],
+    ) -> Vec<(String, BlockAnnouncement)> {
+        let item = InventoryVector {
+            kind: MSG_BLOCK,
+            hash: block_hash,
+        };
+        let mut out = Vec::new();
+        for peer in peers {
+            if !self
+                .peers
+                .entry(peer.addr.clone())
+                .or_default()
+                .mark_known(&item)
+            {
+                continue;
+            }
+            let announcement = if peer.accepts_compact_blocks()
+                && peer.remote_compact_mode.mode == COMPACT_HIGH_BANDWIDTH_MODE
+            {
+                BlockAnnouncement::CompactBlock
+            } else {
+                BlockAnnouncement::Inv
+            };
+            out.push((peer.addr.clone(), announcement));
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{HashMap, HashSet, VecDeque};
+    use std::time::{Duration, Instant};
+
+    use super::{BlockAnnouncement, RelayAnnouncePolicy, GETDATA_IN_FLIGHT_TIMEOUT};
+    use crate::p2p_runtime::{CompactModeSnapshot, InventoryVector, PeerState, MSG_BLOCK, MSG_TX};
+
+    fn tx_item(byte: u8) -> InventoryVector {
+        InventoryVector {
+            kind: MSG_TX,
+            hash: [byte; 32],
+        }
+    }
+
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+    enum Msg {
+        Inv,
+        GetData,
+        Tx,
+    }
+
+    struct SimNode {
+        addr: String,
+        policy: RelayAnnouncePolicy,
+        have: bool,
+    }
+
+    #[test]
+    fn three_peers_gossiping_one_tx_carry_it_once_per_direction() {
+        let item = tx_item(0x42);
+        let addrs: Vec<String> = ["n0", "n1", "n2"].iter().map(|a| a.to_string()).collect();
+        let mut nodes: Vec<SimNode> = addrs
+            .iter()
+            .map(|addr| {
+                let mut policy = RelayAnnouncePolicy::default();
+                for other in addrs.iter().filter(|other| *other != addr) {
+                    policy.add_peer(other);
+                }
+                SimNode {
+                    addr: addr.clone(),
+                    policy,
+                    have: false,
+                }
+            })
+            .collect();
+        let peers_of = |idx: usize| -> Vec<String> {
+            addrs
+                .iter()
+                .enumerate()
+                .filter(|(other, _)| *other != idx)
+                .map(|(_, addr)| addr.clone())
+                .collect()
+        };
+        let index_of = |addr: &str| addrs.iter().position(|a| a == addr).expect("node");
+
+        // Every node announces to its peers as soon as it has the tx, and
+        // fetches on the first INV it hears.
+        let now = Instant::now();
+        let mut queue: VecDeque<(usize, usize, Msg)> = VecDeque::new();
+        nodes[0].have = true;
+        for target in nodes[0].policy.tx_announce_targets(item.hash, &peers_of(0)) {
+            queue.push_back((0, index_of(&target), Msg::Inv));
+        }
+        let mut carried: HashMap<(usize, usize, Msg), usize> = HashMap::new();
+        while let Some((from, to, msg)) = queue.pop_front() {
+            *carried.entry((from, to, msg)).or_default() += 1;
+            let from_addr = nodes[from].addr.clone();
+            let node = &mut nodes[to];
+            match msg {
+                Msg::Inv => {
+                    node.policy.note_inv(&from_addr, &[item]);
+                    if !node.have
+                        && !node
+                            .policy
+                            .select_getdata(&from_addr, &[item], now)
+                            .is_empty()
+                    {
+                        queue.push_back((to, from, Msg::GetData));
+                    }
+                }
+                Msg::GetData => {
+                    node.policy.note_sent(&from_addr, &item);
+                    queue.push_back((to, from, Msg::Tx));
+                }
+                Msg::Tx => {
+                    node.policy.note_received(&from_addr, &item);
+                    if !node.have {
+                        node.have = true;
+                        for target in node.policy.tx_announce_targets(item.hash, &peers_of(to)) {
+                            queue.push_back((to, index_of(&target), Msg::Inv));
+                        }
+                    }
+                }
+            }
+        }
+
+        assert!(nodes.iter().all(|node| node.have));
+        for ((from, to, msg), count) in &carried {
+            assert_eq!(*count, 1, "{msg:?} {from}->{to} carried {count} times");
+        }
+        let tx_edges: HashSet<(usize, usize)> = carried
+            .keys()
+            .filter(|(_, _, msg)| *msg == Msg::Tx)
+            .map(|(from, to, _)| (*from.min(to), *from.max(to)))
+            .collect();
+        let tx_messages = carried.keys().filter(|(_, _, m)| *m == Msg::Tx).count();
+        assert_eq!(tx_messages, 2, "each non-origin node fetches the tx once");
+        assert_eq!(
+            tx_edges.len(),
+            tx_messages,
+            "no connection carries it twice"
+        );
+        assert!(nodes.iter().all(|node| addrs.iter().all(|a| node
+            .policy
+            .tracker(a)
+            .is_none_or(|t| t.in_flight_len() == 0))));
+    }
+
+    #[test]
+    fn in_flight_item_is_requested_again_only_after_timeout_or_disconnect() {
+        let item = tx_item(0x07);
+        let mut policy = RelayAnnouncePolicy::new(Duration::from_secs(5));
+        let t0 = Instant::now();
+        assert_eq!(policy.select_getdata("a", &[item], t0), vec![item]);
+        assert!(policy.select_getdata("a", &[item], t0).is_empty());
+        assert!(policy
+            .select_getdata("b", &[item], t0 + Duration::from_secs(4))
+            .is_empty());
+        assert_eq!(
+            policy.select_getdata("b", &[item], t0 + Duration::from_secs(5)),
+            vec![item]
+        );
+        assert_eq!(policy.tracker("a").expect("a").in_flight_len(), 0);
+        assert_eq!(policy.tracker("b").expect("b").in_flight_len(), 1);
+
+        // A late answer from the timed-out peer does not settle b's request.
+        policy.note_received("a", &item);
+        assert!(policy
+            .select_getdata("c", &[item], t0 + Duration::from_secs(6))
+            .is_empty());
+
+        policy.remove_peer("b");
+        assert_eq!(
+            policy.select_getdata("c", &[item], t0 + Duration::from_secs(6)),
+            vec![item]
+        );
+        assert_eq!(GETDATA_IN_FLIGHT_TIMEOUT, Duration::from_secs(60));
+    }
+
+    #[test]
+    fn new_tip_is_announced_once_per_peer_by_compact_mode() {
+        let peer = |addr: &str, mode: u8| PeerState {
+            addr: addr.to_string(),
+            remote_compact_mode: CompactModeSnapshot { mode, version: 1 },
+            ..PeerState::default()
+        };
+        let peers = vec![peer("full", 0), peer("low", 1), peer("high", 2)];
+        let mut policy = RelayAnnouncePolicy::default();
+        policy.note_inv(
+            "low",
+            &[InventoryVector {
+                kind: MSG_BLOCK,
+                hash: [0x01; 32],
+            }],
+        );
+
+        assert_eq!(
+            policy.block_announce_targets([0x01; 32], &peers),
+            vec![
+                ("full".to_string(), BlockAnnouncement::Inv),
+                ("high".to_string(), BlockAnnouncement::CompactBlock),
+            ]
+        );
+        assert!(policy.block_announce_targets([0x01; 32], &peers).is_empty());
+        assert_eq!(
+            policy.block_announce_targets([0x02; 32], &peers[1..2]),
+            vec![("low".to_string(), BlockAnnouncement::Inv)]
+        );
+    }
+}
@@ -85,6 +85,26 @@ impl RelayTxPool {
         true
     }
 
+    /// Swap the bytes held for `txid` for another witness variant of the same
+    /// transaction. Returns `false` when `txid` is not in the pool.
+    pub fn replace(&self, txid: [u8; 32], raw: &[u8], fee: u64, size: usize) -> bool {
+        let size = if size == 0 { raw.len() } else { size };
+        let Ok(mut inner) = self.inner.lock() else {
+            return false;
+        };
+        match inner.txs.get_mut(&txid) {
+            Some(entry) if size > 0 => {
+                *entry = RelayTxEntry {
+                    raw: raw.to_vec(),
+                    fee,
+                    size,
+                };
+                true
+            }
+            _ => false,
+        }
+    }
+
     /// Retrieve raw tx bytes by txid.
     pub fn get(&self, txid: &[u8; 32]) -> Option<Vec<u8>> {
         let Ok(inner) = self.inner.lock() else {
@@ -196,6 +216,17 @@ mod tests {
         assert!(!pool.put(txid, &[1], 100, 50));
     }
 
+    #[test]
+    fn replace_swaps_bytes_only_for_present_txid() {
+        let pool = RelayTxPool::new_with_limit(10);
+        let txid = [4u8; 32];
+        assert!(!pool.replace(txid, &[1], 100, 50));
+        assert!(pool.put(txid, &[1], 100, 50));
+        assert!(pool.replace(txid, &[2, 3], 100, 0));
+        assert_eq!(pool.get(&txid).unwrap(), vec![2, 3]);
+        assert_eq!(pool.len(), 1);
+    }
+
     #[test]
     fn put_returns_false_for_zero_size_empty_raw() {
         let pool = RelayTxPool::new_with_limit(10);
@@ -0,0 +1,449 @@
+//! Compact-block and DA relay telemetry: the twelve-field set from
+//! POLICY_STRUCTURED_LOGGING_MINIMUM §7 that the CV-COMPACT
+//! `compact_telemetry_fields` op checks. Process-wide aggregate fed by the
+//! compact-block and DA relay paths in `p2p_runtime`; observability only,
+//! nothing here feeds relay or consensus decisions.
+
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::Mutex;
+
+use serde::Serialize;
+
+use crate::compact_mode::SendCmpctModeChange;
+use crate::da_relay::{relay_da_tx_kind_prefix, DaRelayState, PinnedDaAccounting};
+
+/// Field names in the order `compact_telemetry_fields` lists them.
+pub const RELAY_TELEMETRY_FIELDS: [&str; 12] = [
+    "shortid_collision_count",
+    "shortid_collision_blocks",
+    "shortid_collision_peers",
+    "da_mempool_fill_pct",
+    "orphan_pool_fill_pct",
+    "miss_rate_bytes_L1",
+    "miss_rate_bytes_DA",
+    "partial_set_count",
+    "partial_set_age_p95",
+    "recovery_success_rate",
+    "prefetch_latency_ms",
+    "peer_quality_score",
+];
+
+const COLLISION_SAMPLE_LIMIT: usize = 16;
+const PARTIAL_SET_AGE_SAMPLES: usize = 256;
+const PREFETCH_LATENCY_SAMPLES: usize = 256;
+const SENDCMPCT_MODE_EVENT_LIMIT: usize = 32;
+// Upper bound on tracked first-seen / in-flight da_ids; the DA relay state
+// already bounds the real population, this only keeps a bookkeeping drift
+// from growing without limit.
+const TRACKED_DA_IDS_LIMIT: usize = 4096;
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct RelayTelemetrySnapshot {
+    pub shortid_collision_count: u64,
+    /// Most recent colliding block hashes (hex), oldest first.
+    pub shortid_collision_blocks: Vec<String>,
+    /// Most recent peers that announced a colliding block, oldest first.
+    pub shortid_collision_peers: Vec<String>,
+    pub da_mempool_fill_pct: u64,
+    pub orphan_pool_fill_pct: u64,
+    /// Fraction of compact-block L1 tx bytes that had to be fetched.
+    #[serde(rename = "miss_rate_bytes_L1")]
+    pub miss_rate_bytes_l1: f64,
+    /// Fraction of compact-block DA tx bytes that had to be fetched.
+    #[serde(rename = "miss_rate_bytes_DA")]
+    pub miss_rate_bytes_da: f64,
+    pub partial_set_count: u64,
+    /// p95 age in seconds of DA sets at completion or expiry.
+    pub partial_set_age_p95: u64,
+    /// Percent of `getblocktxn` recoveries that completed without fallback.
+    pub recovery_success_rate: f64,
+    /// Mean `getdachunk` send to first-chunk-staged latency.
+    pub prefetch_latency_ms: u64,
+    /// Mean of `100 - ban_score` (clamped to 0..=100) over tracked peers.
+    pub peer_quality_score: u64,
+}
+
+/// One local `sendcmpct` mode change; kept beside, not inside, the §7 set.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct SendCmpctModeEvent {
+    pub peer: String,
+    pub from: u8,
+    pub to: u8,
+    pub in_ibd: bool,
+    pub miss_rate_pct: f64,
+    pub miss_rate_blocks: u64,
+}
+
+#[derive(Debug)]
+pub struct RelayTelemetry {
+    shortid_collision_count: u64,
+    shortid_collision_blocks: VecDeque<String>,
+    shortid_collision_peers: VecDeque<String>,
+    orphan_pool_fill_pct: u64,
+    partial_set_count: u64,
+    partial_set_first_seen_ms: BTreeMap<[u8; 32], u64>,
+    partial_set_ages_secs: VecDeque<u64>,
+    l1_bytes: u64,
+    l1_missed_bytes: u64,
+    da_bytes: u64,
+    da_missed_bytes: u64,
+    recovery_successes: u64,
+    recovery_failures: u64,
+    prefetch_sent_ms: BTreeMap<[u8; 32], u64>,
+    prefetch_latencies_ms: VecDeque<u64>,
+    sendcmpct_mode_changes: u64,
+    sendcmpct_mode_events: VecDeque<SendCmpctModeEvent>,
+    pinned_da: PinnedDaAccounting,
+    da_storm_mode: bool,
+    da_set_recovery_success_rate: f64,
+}
+
+static GLOBAL_RELAY_TELEMETRY: Mutex<RelayTelemetry> = Mutex::new(RelayTelemetry::new());
+
+pub(crate) fn with_relay_telemetry<R>(f: impl FnOnce(&mut RelayTelemetry) -> R) -> R {
+    let mut telemetry = GLOBAL_RELAY_TELEMETRY
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    f(&mut telemetry)
+}
+
+impl Default for RelayTelemetry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RelayTelemetry {
+    pub const fn new() -> Self {
+        Self {
+            shortid_collision_count: 0,
+            shortid_collision_blocks: VecDeque::new(),
+            shortid_collision_peers: VecDeque::new(),
+            orphan_pool_fill_pct: 0,
+            partial_set_count: 0,
+            partial_set_first_seen_ms: BTreeMap::new(),
+            partial_set_ages_secs: VecDeque::new(),
+            l1_bytes: 0,
+            l1_missed_bytes: 0,
+            da_bytes: 0,
+            da_missed_bytes: 0,
+            recovery_successes: 0,
+            recovery_failures: 0,
+            prefetch_sent_ms: BTreeMap::new(),
+            prefetch_latencies_ms: VecDeque::new(),
+            sendcmpct_mode_changes: 0,
+            sendcmpct_mode_events: VecDeque::new(),
+            pinned_da: PinnedDaAccounting::new(),
+            da_storm_mode: false,
+            da_set_recovery_success_rate: 100.0,
+        }
+    }
+
+    pub fn record_shortid_collisions(&mut self, block_hash: [u8; 32], peer: &str, count: u64) {
+        if count == 0 {
+            return;
+        }
+        self.shortid_collision_count = self.shortid_collision_count.saturating_add(count);
+        push_unique_bounded(&mut self.shortid_collision_blocks, hex::encode(block_hash));
+        if !peer.is_empty() {
+            push_unique_bounded(&mut self.shortid_collision_peers, peer.to_string());
+        }
+    }
+
+    /// Account one reconstructed compact block: `txs` is the full block body
+    /// (coinbase included) and `missed_indexes` the positions that were
+    /// fetched with `getblocktxn` rather than found locally or prefilled.
+    pub fn record_compact_block_bytes(&mut self, txs: &[Vec<u8>], missed_indexes: &[u64]) {
+        for (index, tx) in txs.iter().enumerate() {
+            let bytes = tx.len() as u64;
+            let missed = missed_indexes.contains(&(index as u64));
+            let (total, miss) = if matches!(relay_da_tx_kind_prefix(tx), Some(0x01 | 0x02)) {
+                (&mut self.da_bytes, &mut self.da_missed_bytes)
+            } else {
+                (&mut self.l1_bytes, &mut self.l1_missed_bytes)
+            };
+            *total = total.saturating_add(bytes);
+            if missed {
+                *miss = miss.saturating_add(bytes);
+            }
+        }
+    }
+
+    pub fn record_compact_recovery(&mut self, success: bool) {
+        let counter = if success {
+            &mut self.recovery_successes
+        } else {
+            &mut self.recovery_failures
+        };
+        *counter = counter.saturating_add(1);
+    }
+
+    pub fn record_sendcmpct_mode_change(&mut self, peer: &str, change: &SendCmpctModeChange) {
+        self.sendcmpct_mode_changes = self.sendcmpct_mode_changes.saturating_add(1);
+        if self.sendcmpct_mode_events.len() >= SENDCMPCT_MODE_EVENT_LIMIT {
+            self.sendcmpct_mode_events.pop_front();
+        }
+        self.sendcmpct_mode_events.push_back(SendCmpctModeEvent {
+            peer: peer.to_string(),
+            from: change.from,
+            to: change.to,
+            in_ibd: change.phase.in_ibd,
+            miss_rate_pct: change.phase.miss_rate_pct,
+            miss_rate_blocks: change.phase.miss_rate_blocks,
+        });
+    }
+
+    pub fn sendcmpct_mode_changes(&self) -> u64 {
+        self.sendcmpct_mode_changes
+    }
+
+    /// Most recent mode changes, oldest first.
+    pub fn sendcmpct_mode_events(&self) -> Vec<SendCmpctModeEvent> {
+        self.sendcmpct_mode_events.iter().cloned().collect()
+    }
+
+    /// Pinned DA payload bytes counted against the cap and the commit
+    /// overhead beside them; kept beside, not inside, the §7 set.
+    pub fn pinned_da_accounting(&self) -> PinnedDaAccounting {
+        self.pinned_da
+    }
+
+    /// DA orphan storm mode and the windowed DA set recovery rate that can
+    /// roll it back; kept beside the §7 `recovery_success_rate`, which
+    /// measures `getblocktxn` recoveries instead.
+    pub fn da_storm_mode(&self) -> bool {
+        self.da_storm_mode
+    }
+
+    pub fn da_set_recovery_success_rate(&self) -> f64 {
+        self.da_set_recovery_success_rate
+    }
+
+    pub fn record_prefetch_sent(&mut self, da_id: [u8; 32], now_ms: u64) {
+        insert_bounded(&mut self.prefetch_sent_ms, da_id, now_ms);
+    }
+
+    /// Refresh the DA gauges after a staging attempt for `da_id`, opening or
+    /// closing its partial-set age window and closing any pending prefetch.
+    pub(crate) fn record_da_staged(&mut self, relay: &DaRelayState, da_id: [u8; 32], now_ms: u64) {
+        if let Some(sent_ms) = self.prefetch_sent_ms.remove(&da_id) {
+            push_bounded(
+                &mut self.prefetch_latencies_ms,
+                now_ms.saturating_sub(sent_ms),
+                PREFETCH_LATENCY_SAMPLES,
+            );
+        }
+        match relay.da_set_complete(da_id) {
+            Some(false) if !self.partial_set_first_seen_ms.contains_key(&da_id) => {
+                insert_bounded(&mut self.partial_set_first_seen_ms, da_id, now_ms);
+            }
+            Some(true) => self.close_partial_set(da_id, now_ms),
+            _ => {}
+        }
+        self.refresh_da_gauges(relay);
+    }
+
+    /// Refresh the DA gauges after the orphan TTL advanced, closing the age
+    /// window of every `expired` set.
+    pub(crate) fn record_da_expired(
+        &mut self,
+        relay: &DaRelayState,
+        expired: &[[u8; 32]],
+        now_ms: u64,
+    ) {
+        for da_id in expired {
+            self.close_partial_set(*da_id, now_ms);
+            self.prefetch_sent_ms.remove(da_id);
+        }
+        self.refresh_da_gauges(relay);
+    }
+
+    pub fn snapshot(
+        &self,
+        da_mempool_fill_pct: u64,
+        peer_quality_score: u64,
+    ) -> RelayTelemetrySnapshot {
+        let recoveries = self
+            .recovery_successes
+            .saturating_add(self.recovery_failures);
+        RelayTelemetrySnapshot {
+            shortid_collision_count: self.shortid_collision_count,
+            shortid_collision_blocks: self.shortid_collision_blocks.iter().cloned().collect(),
+            shortid_collision_peers: self.shortid_collision_peers.iter().cloned().collect(),
+            da_mempool_fill_pct,
+            orphan_pool_fill_pct: self.orphan_pool_fill_pct,
+            miss_rate_bytes_l1: ratio(self.l1_missed_bytes, self.l1_bytes),
+            miss_rate_bytes_da: ratio(self.da_missed_bytes, self.da_bytes),
+            partial_set_count: self.partial_set_count,
+            partial_set_age_p95: percentile_95(&self.partial_set_ages_secs),
+            recovery_success_rate: if recoveries == 0 {
+                100.0
+            } else {
+                ratio(self.recovery_successes, recoveries) * 100.0
+            },
+            prefetch_latency_ms: mean(&self.prefetch_latencies_ms),
+            peer_quality_score,
+        }
+    }
+
+    fn close_partial_set(&mut self, da_id: [u8; 32], now_ms: u64) {
+        if let Some(first_seen_ms) = self.partial_set_first_seen_ms.remove(&da_id) {
+            push_bounded(
+                &mut self.partial_set_ages_secs,
+                now_ms.saturating_sub(first_seen_ms) / 1000,
+                PARTIAL_SET_AGE_SAMPLES,
+            );
+        }
+    }
+
+    fn refresh_da_gauges(&mut self, relay: &DaRelayState) {
+        self.orphan_pool_fill_pct = relay.orphan_pool_fill_pct();
+        self.partial_set_count = relay.partial_set_count();
+        self.pinned_da = relay.pinned_accounting();
+        self.da_storm_mode = relay.storm_mode_active();
+        self.da_set_recovery_success_rate = relay.set_recovery_success_rate();
+    }
+}
+
+/// Peer quality over `ban_scores`: mean of `100 - ban_score`, each clamped
+/// to 0..=100; 0 with no peers.
+pub fn peer_quality_score(ban_scores: impl IntoIterator<Item = i32>) -> u64 {
+    let (mut total, mut peers) = (0u64, 0u64);
+    for score in ban_scores {
+        total += (100 - i64::from(score).clamp(0, 100)) as u64;
+        peers += 1;
+    }
+    total.checked_div(peers).unwrap_or(0)
+}
+
+fn ratio(part: u64, total: u64) -> f64 {
+    if total == 0 {
+        0.0
+    } else {
+        part as f64 / total as f64
+    }
+}
+
+fn mean(samples: &VecDeque<u64>) -> u64 {
+    let total = samples
+        .iter()
+        .fold(0u64, |sum, value| sum.saturating_add(*value));
+    total.checked_div(samples.len() as u64).unwrap_or(0)
+}
+
+/// Nearest-rank p95; 0 with no samples.
+fn percentile_95(samples: &VecDeque<u64>) -> u64 {
+    if samples.is_empty() {
+        return 0;
+    }
+    let mut sorted: Vec<u64> = samples.iter().copied().collect();
+    sorted.sort_unstable();
+    let rank = (sorted.len() * 95).div_ceil(100);
+    sorted[rank.saturating_sub(1)]
+}
+
+fn push_bounded(ring: &mut VecDeque<u64>, value: u64, limit: usize) {
+    if ring.len() >= limit {
+        ring.pop_front();
+    }
+    ring.push_back(value);
+}
+
+fn push_unique_bounded(ring: &mut VecDeque<String>, value: String) {
+    if let Some(pos) = ring.iter().position(|existing| *existing == value) {
+        ring.remove(pos);
+    } else if ring.len() >= COLLISION_SAMPLE_LIMIT {
+        ring.pop_front();
+    }
+    ring.push_back(value);
+}
+
+fn insert_bounded(map: &mut BTreeMap<[u8; 32], u64>, da_id: [u8; 32], now_ms: u64) {
+    if map.len() >= TRACKED_DA_IDS_LIMIT && !map.contains_key(&da_id) {
+        if let Some(oldest) = map.iter().min_by_key(|(_, at)| **at).map(|(id, _)| *id) {
+            map.remove(&oldest);
+        }
+    }
+    map.insert(da_id, now_ms);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::da_relay::DaRelayCaps;
+    use rubin_consensus::constants::TX_WIRE_VERSION;
+
+    #[test]
+    fn relay_telemetry_reports_all_fields_after_synthetic_activity() {
+        let mut telemetry = RelayTelemetry::new();
+        let mut relay = DaRelayState::new(DaRelayCaps::default()).unwrap();
+        let peer = "10.0.0.1:8333";
+
+        telemetry.record_shortid_collisions([0xaa; 32], peer, 2);
+        telemetry.record_shortid_collisions([0xbb; 32], peer, 1);
+        telemetry.record_shortid_collisions([0xcc; 32], peer, 0);
+
+        let l1 = vec![0x00; 60];
+        let mut da = vec![0x00; 40];
+        da[..4].copy_from_slice(&TX_WIRE_VERSION.to_le_bytes());
+        da[4] = 0x02;
+        telemetry.record_compact_block_bytes(&[l1.clone(), da.clone(), l1.clone()], &[1, 2]);
+        telemetry.record_compact_block_bytes(&[l1.clone(), da], &[]);
+        for success in [true, true, true, false] {
+            telemetry.record_compact_recovery(success);
+        }
+
+        // Twenty sets staged at t=0 and completed 1..=20 seconds later.
+        for n in 1..=20u8 {
+            let da_id = [n; 32];
+            telemetry.record_prefetch_sent(da_id, 0);
+            relay
+                .test_stage_incomplete_da_commit(peer, da_id, 2, 10)
+                .unwrap();
+            telemetry.record_da_staged(&relay, da_id, u64::from(n) * 10);
+            assert_eq!(relay.da_set_complete(da_id), Some(false));
+        }
+        assert_eq!(telemetry.partial_set_count, 20);
+        // Set n closes n seconds after it was first seen.
+        for n in 1..=20u64 {
+            telemetry.record_da_expired(&relay, &[[n as u8; 32]], n * 10 + n * 1000);
+        }
+        let expired = relay.advance_orphan_ttl_by(3).unwrap();
+        assert_eq!(expired.len(), 20);
+        telemetry.record_da_expired(&relay, &expired, 30_000);
+
+        let snapshot = telemetry.snapshot(7, peer_quality_score([0, 20, 150]));
+        let json = serde_json::to_value(&snapshot).unwrap();
+        let object = json.as_object().unwrap();
+        for field in RELAY_TELEMETRY_FIELDS {
+            assert!(object.contains_key(field), "missing {field}");
+        }
+        assert_eq!(object.len(), RELAY_TELEMETRY_FIELDS.len());
+
+        assert_eq!(snapshot.shortid_collision_count, 3);
+        assert_eq!(
+            snapshot.shortid_collision_blocks,
+            vec![hex::encode([0xaa; 32]), hex::encode([0xbb; 32])]
+        );
+        assert_eq!(snapshot.shortid_collision_peers, vec![peer.to_string()]);
+        assert_eq!(snapshot.da_mempool_fill_pct, 7);
+        assert_eq!(snapshot.orphan_pool_fill_pct, 0);
+        assert_eq!(snapshot.miss_rate_bytes_l1, 60.0 / 180.0);
+        assert_eq!(snapshot.miss_rate_bytes_da, 0.5);
+        assert_eq!(snapshot.partial_set_count, 0);
+        assert_eq!(snapshot.partial_set_age_p95, 19);
+        assert_eq!(snapshot.recovery_success_rate, 75.0);
+        assert_eq!(snapshot.prefetch_latency_ms, 105);
+        assert_eq!(snapshot.peer_quality_score, 60);
+    }
+
+    #[test]
+    fn relay_telemetry_empty_snapshot_is_neutral() {
+        let snapshot = RelayTelemetry::new().snapshot(0, peer_quality_score([]));
+        assert_eq!(snapshot.miss_rate_bytes_l1, 0.0);
+        assert_eq!(snapshot.partial_set_age_p95, 0);
+        assert_eq!(snapshot.recovery_success_rate, 100.0);
+        assert_eq!(snapshot.prefetch_latency_ms, 0);
+        assert_eq!(snapshot.peer_quality_score, 0);
+    }
+}
@@ -0,0 +1,738 @@
+//! Partially-signed transaction interchange (RPST).
+//!
+//! An `Rpst` carries an unsigned transaction plus, per input, the prevout
+//! being spent and any witness items collected so far. Cosigners exchange
+//! RPST blobs, add their own witness items, merge, and the last party
+//! finalizes into a broadcastable `Tx` with sentinel witnesses in every slot
+//! nobody signed.
+//!
+//! Wire format (all integers little-endian, `cs` = CompactSize):
+//!
+//! ```text
+//! magic "rpst" 0xff
+//! global map                     ; exactly one entry: 0x00 -> unsigned tx bytes
+//! input map * tx.inputs.len()
+//! map   = entry* 0x00
+//! entry = cs(key_len) key cs(value_len) value
+//! input keys:
+//!   0x00            -> value u64 || covenant_type u16 || covenant_data
+//!   0x01            -> creation_height u64
+//!   0x02            -> role hint u8
+//!   0x03 || slot u16 BE -> suite_id u8 || cs(len) pubkey || cs(len) signature
+//! ```
+//!
+//! Keys within a map must be strictly increasing and unknown keys are
+//! rejected, so every `Rpst` has exactly one encoding.
+
+use std::collections::BTreeMap;
+
+use rubin_consensus::constants::{
+    COV_TYPE_HTLC, COV_TYPE_MULTISIG, COV_TYPE_P2PK, COV_TYPE_VAULT, MAX_P2PK_COVENANT_DATA,
+    SUITE_ID_SENTINEL,
+};
+use rubin_consensus::{
+    encode_compact_size, marshal_tx, parse_multisig_covenant_data, parse_tx,
+    parse_vault_covenant_data, read_compact_size_bytes, sighash_v1_digest, witness_slots, Tx,
+    TxOutput, WitnessItem,
+};
+use sha3::{Digest, Sha3_256};
+
+pub const RPST_MAGIC: [u8; 5] = *b"rpst\xff";
+
+const GLOBAL_KEY_UNSIGNED_TX: u8 = 0x00;
+const INPUT_KEY_PREVOUT: u8 = 0x00;
+const INPUT_KEY_CREATION_HEIGHT: u8 = 0x01;
+const INPUT_KEY_ROLE: u8 = 0x02;
+const INPUT_KEY_PARTIAL_WITNESS: u8 = 0x03;
+
+type MapEntry = (Vec<u8>, Vec<u8>);
+
+/// Which spend path a cosigner is expected to satisfy for an input.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RpstRole {
+    /// Key-bound signature(s): P2PK, MULTISIG or VAULT threshold signers.
+    Signer,
+    HtlcClaim,
+    HtlcRefund,
+}
+
+impl RpstRole {
+    fn to_byte(self) -> u8 {
+        match self {
+            Self::Signer => 0,
+            Self::HtlcClaim => 1,
+            Self::HtlcRefund => 2,
+        }
+    }
+
+    fn from_byte(b: u8) -> Result<Self, String> {
+        match b {
+            0 => Ok(Self::Signer),
+            1 => Ok(Self::HtlcClaim),
+            2 => Ok(Self::HtlcRefund),
+            other => Err(format!("rpst: unknown role hint {other}")),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RpstInput {
+    pub prevout: Option<TxOutput>,
+    pub creation_height: Option<u64>,
+    pub role: Option<RpstRole>,
+    /// Witness items keyed by slot within this input's witness range.
+    pub partial_witness: BTreeMap<u16, WitnessItem>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Rpst {
+    pub unsigned_tx: Tx,
+    pub inputs: Vec<RpstInput>,
+}
+
+impl Rpst {
+    /// Wraps `tx` (witness stripped) with empty per-input metadata.
+    pub fn new(mut tx: Tx) -> Result<Self, String> {
+        if tx.inputs.is_empty() {
+            return Err("rpst: transaction has no inputs".to_string());
+        }
+        tx.witness.clear();
+        let inputs = vec![RpstInput::default(); tx.inputs.len()];
+        Ok(Self {
+            unsigned_tx: tx,
+            inputs,
+        })
+    }
+
+    fn input_mut(&mut self, input_index: usize) -> Result<&mut RpstInput, String> {
+        let count = self.inputs.len();
+        self.inputs
+            .get_mut(input_index)
+            .ok_or_else(|| format!("rpst: input index {input_index} out of range ({count} inputs)"))
+    }
+
+    fn prevout(&self, input_index: usize) -> Result<&TxOutput, String> {
+        self.inputs
+            .get(input_index)
+            .ok_or_else(|| format!("rpst: input index {input_index} out of range"))?
+            .prevout
+            .as_ref()
+            .ok_or_else(|| format!("rpst: input {input_index} has no prevout"))
+    }
+
+    pub fn set_prevout(
+        &mut self,
+        input_index: usize,
+        prevout: TxOutput,
+        creation_height: u64,
+        role: RpstRole,
+    ) -> Result<(), String> {
+        witness_slots(prevout.covenant_type, &prevout.covenant_data)
+            .map_err(|err| format!("rpst: input {input_index} prevout: {err}"))?;
+        let input = self.input_mut(input_index)?;
+        input.prevout = Some(prevout);
+        input.creation_height = Some(creation_height);
+        input.role = Some(role);
+        Ok(())
+    }
+
+    /// SIGHASH_ALL digest a cosigner signs for `input_index`.
+    pub fn sighash(&self, input_index: usize, chain_id: [u8; 32]) -> Result<[u8; 32], String> {
+        let value = self.prevout(input_index)?.value;
+        sighash_v1_digest(&self.unsigned_tx, input_index as u32, value, chain_id)
+            .map_err(|err| format!("rpst: sighash: {err}"))
+    }
+
+    /// Records a key-bound signature, placing it in the witness slot whose
+    /// key id equals `sha3_256(item.pubkey)` (P2PK, MULTISIG, VAULT). HTLC
+    /// and other multi-slot paths must use [`Rpst::set_witness_item`].
+    pub fn add_signature(&mut self, input_index: usize, item: WitnessItem) -> Result<u16, String> {
+        let prevout = self.prevout(input_index)?;
+        let key_id = sha3_256(&item.pubkey);
+        let slot = match prevout.covenant_type {
+            COV_TYPE_P2PK => {
+                if prevout.covenant_data.len() != MAX_P2PK_COVENANT_DATA as usize
+                    || prevout.covenant_data[1..33] != key_id
+                {
+                    return Err(format!(
+                        "rpst: input {input_index}: pubkey does not match CORE_P2PK key id"
+                    ));
+                }
+                0
+            }
+            COV_TYPE_MULTISIG => {
+                let cov = parse_multisig_covenant_data(&prevout.covenant_data)
+                    .map_err(|err| format!("rpst: input {input_index}: {err}"))?;
+                key_slot(&cov.keys, &key_id, input_index)?
+            }
+            COV_TYPE_VAULT => {
+                let cov = parse_vault_covenant_data(&prevout.covenant_data)
+                    .map_err(|err| format!("rpst: input {input_index}: {err}"))?;
+                key_slot(&cov.keys, &key_id, input_index)?
+            }
+            other => {
+                return Err(format!(
+                    "rpst: input {input_index}: covenant_type 0x{other:04x} needs explicit witness slots"
+                ))
+            }
+        };
+        self.set_witness_item(input_index, slot, item)?;
+        Ok(slot)
+    }
+
+    /// Places `item` at `slot` of `input_index`. Re-adding an identical item
+    /// is a no-op; a different item in an occupied slot is a conflict.
+    pub fn set_witness_item(
+        &mut self,
+        input_index: usize,
+        slot: u16,
+        item: WitnessItem,
+    ) -> Result<(), String> {
+        let prevout = self.prevout(input_index)?;
+        let slots = witness_slots(prevout.covenant_type, &prevout.covenant_data)
+            .map_err(|err| format!("rpst: input {input_index}: {err}"))?;
+        if usize::from(slot) >= slots {
+            return Err(format!(
+                "rpst: input {input_index}: witness slot {slot} out of range ({slots} slots)"
+            ));
+        }
+        let input = self.input_mut(input_index)?;
+        match input.partial_witness.get(&slot) {
+            Some(existing) if *existing != item => Err(format!(
+                "rpst: input {input_index}: conflicting witness in slot {slot}"
+            )),
+            _ => {
+                input.partial_witness.insert(slot, item);
+                Ok(())
+            }
+        }
+    }
+
+    /// Folds `other` into `self`. Both must wrap the same unsigned tx; any
+    /// field present on both sides must agree.
+    pub fn merge(&mut self, other: &Rpst) -> Result<(), String> {
+        if self.unsigned_tx != other.unsigned_tx {
+            return Err("rpst: merge of different unsigned transactions".to_string());
+        }
+        for (index, (ours, theirs)) in self.inputs.iter_mut().zip(&other.inputs).enumerate() {
+            merge_field(&mut ours.prevout, &theirs.prevout, index, "prevout")?;
+            merge_field(
+                &mut ours.creation_height,
+                &theirs.creation_height,
+                index,
+                "creation_height",
+            )?;
+            merge_field(&mut ours.role, &theirs.role, index, "role")?;
+            for (slot, item) in &theirs.partial_witness {
+                match ours.partial_witness.get(slot) {
+                    Some(existing) if existing != item => {
+                        return Err(format!(
+                            "rpst: input {index}: conflicting witness in slot {slot}"
+                        ))
+                    }
+                    Some(_) => {}
+                    None => {
+                        ours.partial_witness.insert(*slot, item.clone());
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Assembles the final witness: each input contributes exactly
+    /// `witness_slots(prevout)` items, unsigned slots become sentinels.
+    /// P2PK inputs must be signed, threshold covenants must reach their
+    /// threshold, and HTLC inputs must have both slots filled.
+    pub fn finalize(&self) -> Result<Tx, String> {
+        let mut witness = Vec::new();
+        for (index, input) in self.inputs.iter().enumerate() {
+            let prevout = self.prevout(index)?;
+            let slots = witness_slots(prevout.covenant_type, &prevout.covenant_data)
+                .map_err(|err| format!("rpst: input {index}: {err}"))?;
+            if let Some(max) = input.partial_witness.keys().next_back() {
+                if usize::from(*max) >= slots {
+                    return Err(format!(
+                        "rpst: input {index}: witness slot {max} out of range ({slots} slots)"
+                    ));
+                }
+            }
+            let filled = input.partial_witness.len();
+            let required = match prevout.covenant_type {
+                COV_TYPE_MULTISIG => usize::from(
+                    parse_multisig_covenant_data(&prevout.covenant_data)
+                        .map_err(|err| format!("rpst: input {index}: {err}"))?
+                        .threshold,
+                ),
+                COV_TYPE_VAULT => usize::from(
+                    parse_vault_covenant_data(&prevout.covenant_data)
+                        .map_err(|err| format!("rpst: input {index}: {err}"))?
+                        .threshold,
+                ),
+                COV_TYPE_P2PK | COV_TYPE_HTLC => slots,
+                _ => 1,
+            };
+            if filled < required {
+                return Err(format!(
+                    "rpst: input {index}: {filled} of {required} required witness items present"
+                ));
+            }
+            for slot in 0..slots {
+                let item = input
+                    .partial_witness
+                    .get(&(slot as u16))
+                    .cloned()
+                    .unwrap_or_else(sentinel_witness);
+                witness.push(item);
+            }
+        }
+        let mut tx = self.unsigned_tx.clone();
+        tx.witness = witness;
+        Ok(tx)
+    }
+
+    pub fn serialize(&self) -> Result<Vec<u8>, String> {
+        let tx_bytes = marshal_tx(&self.unsigned_tx).map_err(|err| format!("rpst: {err}"))?;
+        let mut out = RPST_MAGIC.to_vec();
+        write_entry(&mut out, &[GLOBAL_KEY_UNSIGNED_TX], &tx_bytes);
+        out.push(0x00);
+        for input in &self.inputs {
+            if let Some(prevout) = &input.prevout {
+                let mut value = prevout.value.to_le_bytes().to_vec();
+                value.extend_from_slice(&prevout.covenant_type.to_le_bytes());
+                value.extend_from_slice(&prevout.covenant_data);
+                write_entry(&mut out, &[INPUT_KEY_PREVOUT], &value);
+            }
+            if let Some(height) = input.creation_height {
+                write_entry(
+                    &mut out,
+                    &[INPUT_KEY_CREATION_HEIGHT],
+                    &height.to_le_bytes(),
+                );
+            }
+            if let Some(role) = input.role {
+                write_entry(&mut out, &[INPUT_KEY_ROLE], &[role.to_byte()]);
+            }
+            for (slot, item) in &input.partial_witness {
+                let mut key = vec![INPUT_KEY_PARTIAL_WITNESS];
+                key.extend_from_slice(&slot.to_be_bytes());
+                let mut value = vec![item.suite_id];
+                encode_compact_size(item.pubkey.len() as u64, &mut value);
+                value.extend_from_slice(&item.pubkey);
+                encode_compact_size(item.signature.len() as u64, &mut value);
+                value.extend_from_slice(&item.signature);
+                write_entry(&mut out, &key, &value);
+            }
+            out.push(0x00);
+        }
+        Ok(out)
+    }
+
+    pub fn parse(bytes: &[u8]) -> Result<Self, String> {
+        let mut r = MapReader { bytes, pos: 0 };
+        if r.take(RPST_MAGIC.len())? != RPST_MAGIC {
+            return Err("rpst: bad magic".to_string());
+        }
+        let global = r.read_map()?;
+        let [(key, tx_bytes)] = global.as_slice() else {
+            return Err("rpst: global map must hold exactly the unsigned tx".to_string());
+        };
+        if key.as_slice() != [GLOBAL_KEY_UNSIGNED_TX] {
+            return Err("rpst: unknown global key".to_string());
+        }
+        let (tx, _, _, consumed) =
+            parse_tx(tx_bytes).map_err(|err| format!("rpst: unsigned tx: {err}"))?;
+        if consumed != tx_bytes.len() {
+            return Err("rpst: unsigned tx has trailing bytes".to_string());
+        }
+        if !tx.witness.is_empty() {
+            return Err("rpst: unsigned tx carries witness items".to_string());
+        }
+        let mut rpst = Rpst::new(tx)?;
+        for input in &mut rpst.inputs {
+            for (key, value) in r.read_map()? {
+                parse_input_entry(input, &key, &value)?;
+            }
+        }
+        if r.pos != bytes.len() {
+            return Err("rpst: trailing bytes".to_string());
+        }
+        Ok(rpst)
+    }
+}
+
+fn sha3_256(bytes: &[u8]) -> [u8; 32] {
+    Sha3_256::digest(bytes).into()
+}
+
+fn key_slot(keys: &[[u8; 32]], key_id: &[u8; 32], input_index: usize) -> Result<u16, String> {
+    keys.iter()
+        .position(|k| k == key_id)
+        .map(|pos| pos as u16)
+        .ok_or_else(|| format!("rpst: input {input_index}: pubkey is not a covenant key"))
+}
+
+fn merge_field<T: Clone + PartialEq>(
+    ours: &mut Option<T>,
+    theirs: &Option<T>,
+    index: usize,
+    name: &str,
+) -> Result<(), String> {
+    match (ours.as_ref(), theirs) {
+        (Some(a), Some(b)) if a != b => Err(format!("rpst: input {index}: conflicting {name}")),
+        (None, Some(b)) => {
+            *ours = Some(b.clone());
+            Ok(())
+        }
+        _ => Ok(()),
+    }
+}
+
+fn sentinel_witness() -> WitnessItem {
+    WitnessItem {
+        suite_id: SUITE_ID_SENTINEL,
+        pubkey: Vec::new(),
+        signature: Vec::new(),
+    }
+}
+
+fn write_entry(out: &mut Vec<u8>, key: &[u8], value: &[u8]) {
+    encode_compact_size(key.len() as u64, out);
+    out.extend_from_slice(key);
+    encode_compact_size(value.len() as u64, out);
+    out.extend_from_slice(value);
+}
+
+fn parse_input_entry(input: &mut RpstInput, key: &[u8], value: &[u8]) -> Result<(), String> {
+    match key {
+        [INPUT_KEY_PREVOUT] => {
+            if value.len() < 10 {
+                return Err("rpst: prevout too short".to_string());
+            }
+            input.prevout = Some(TxOutput {
+                value: u64::from_le_bytes(value[0..8].try_into().expect("8 bytes")),
+                covenant_type: u16::from_le_bytes([value[8], value[9]]),
+                covenant_data: value[10..].to_vec(),
+            });
+        }
+        [INPUT_KEY_CREATION_HEIGHT] => {
+            let raw: [u8; 8] = value
+                .try_into()
+                .map_err(|_| "rpst: creation_height must be 8 bytes".to_string())?;
+            input.creation_height = Some(u64::from_le_bytes(raw));
+        }
+        [INPUT_KEY_ROLE] => {
+            let [b] = value else {
+                return Err("rpst: role hint must be 1 byte".to_string());
+            };
+            input.role = Some(RpstRole::from_byte(*b)?);
+        }
+        [INPUT_KEY_PARTIAL_WITNESS, hi, lo] => {
+            let mut r = MapReader {
+                bytes: value,
+                pos: 0,
+            };
+            let suite_id = r.take(1)?[0];
+            let pubkey = r.read_var_bytes()?.to_vec();
+            let signature = r.read_var_bytes()?.to_vec();
+            if r.pos != value.len() {
+                return Err("rpst: witness item has trailing bytes".to_string());
+            }
+            input.partial_witness.insert(
+                u16::from_be_bytes([*hi, *lo]),
+                WitnessItem {
+                    suite_id,
+                    pubkey,
+                    signature,
+                },
+            );
+        }
+        _ => return Err(format!("rpst: unknown input key {}", hex::encode(key))),
+    }
+    Ok(())
+}
+
+struct MapReader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> MapReader<'a> {
+    fn take(&mut self, n: usize) -> Result<&'a [u8], String> {
+        let end = self
+            .pos
+            .checked_add(n)
+            .filter(|end| *end <= self.bytes.len())
+            .ok_or_else(|| "rpst: truncated".to_string())?;
+        let out = &self.bytes[self.pos..end];
+        self.pos = end;
+        Ok(out)
+    }
+
+    fn read_var_bytes(&mut self) -> Result<&'a [u8], String> {
+        let (len, used) = read_compact_size_bytes(&self.bytes[self.pos..])
+            .map_err(|err| format!("rpst: compactsize: {err}"))?;
+        self.pos += used;
+        let len = usize::try_from(len).map_err(|_| "rpst: length overflow".to_string())?;
+        self.take(len)
+    }
+
+    /// Reads one `entry* 0x00` map, enforcing strictly increasing keys.
+    fn read_map(&mut self) -> Result<Vec<MapEntry>, String> {
+        let mut entries: Vec<MapEntry> = Vec::new();
+        loop {
+            let key = self.read_var_bytes()?;
+            if key.is_empty() {
+                return Ok(entries);
+            }
+            if let Some((prev, _)) = entries.last() {
+                if prev.as_slice() >= key {
+                    return Err("rpst: map keys not strictly increasing".to_string());
+                }
+            }
+            let value = self.read_var_bytes()?;
+            entries.push((key.to_vec(), value.to_vec()));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rubin_consensus::constants::{
+        ML_DSA_87_PUBKEY_BYTES, ML_DSA_87_SIG_BYTES, SIGHASH_ALL, SUITE_ID_ML_DSA_87,
+        TX_WIRE_VERSION,
+    };
+    use rubin_consensus::TxInput;
+
+    fn fake_signer(fill: u8) -> (Vec<u8>, WitnessItem) {
+        let pubkey = vec![fill; ML_DSA_87_PUBKEY_BYTES as usize];
+        let mut signature = vec![fill ^ 0xff; ML_DSA_87_SIG_BYTES as usize];
+        signature.push(SIGHASH_ALL);
+        let item = WitnessItem {
+            suite_id: SUITE_ID_ML_DSA_87,
+            pubkey: pubkey.clone(),
+            signature,
+        };
+        (pubkey, item)
+    }
+
+    fn vault_prevout(keys: &[&[u8]], threshold: u8) -> TxOutput {
+        let mut key_ids: Vec<[u8; 32]> = keys.iter().map(|k| sha3_256(k)).collect();
+        key_ids.sort_unstable();
+        let mut data = vec![0x11; 32];
+        data.push(threshold);
+        data.push(key_ids.len() as u8);
+        for k in &key_ids {
+            data.extend_from_slice(k);
+        }
+        data.extend_from_slice(&1u16.to_le_bytes());
+        data.extend_from_slice(&[0x22; 32]);
+        TxOutput {
+            value: 5_000,
+            covenant_type: COV_TYPE_VAULT,
+            covenant_data: data,
+        }
+    }
+
+    fn unsigned_tx() -> Tx {
+        Tx {
+            version: TX_WIRE_VERSION,
+            tx_kind: 0x00,
+            tx_nonce: 9,
+            inputs: vec![TxInput {
+                prev_txid: [0x44; 32],
+                prev_vout: 1,
+                script_sig: Vec::new(),
+                sequence: 0,
+            }],
+            outputs: vec![TxOutput {
+                value: 4_900,
+                covenant_type: COV_TYPE_P2PK,
+                covenant_data: {
+                    let mut d = vec![0x33; MAX_P2PK_COVENANT_DATA as usize];
+                    d[0] = SUITE_ID_ML_DSA_87;
+                    d
+                },
+            }],
+            locktime: 0,
+            da_commit_core: None,
+            da_chunk_core: None,
+            witness: Vec::new(),
+            da_payload: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn two_signer_vault_spend_assembled_out_of_order() {
+        let (pk_a, sig_a) = fake_signer(0xa1);
+        let (pk_b, sig_b) = fake_signer(0xb2);
+        let (pk_c, _) = fake_signer(0xc3);
+        let prevout = vault_prevout(&[&pk_a, &pk_b, &pk_c], 2);
+
+        let mut creator = Rpst::new(unsigned_tx()).expect("new");
+        creator
+            .set_prevout(0, prevout.clone(), 12, RpstRole::Signer)
+            .expect("prevout");
+        let blob = creator.serialize().expect("serialize");
+
+        // Each cosigner works from its own decoded copy.
+        let mut signer_b = Rpst::parse(&blob).expect("parse b");
+        let slot_b = signer_b.add_signature(0, sig_b.clone()).expect("sign b");
+        let mut signer_a = Rpst::parse(&blob).expect("parse a");
+        let slot_a = signer_a.add_signature(0, sig_a.clone()).expect("sign a");
+        assert_ne!(slot_a, slot_b);
+
+        // Not finalizable with one signature.
+        let err = signer_b.finalize().expect_err("below threshold");
+        assert!(err.contains("1 of 2"), "{err}");
+
+        // B's copy arrives first; merge A's in afterwards via the wire.
+        let mut combined = Rpst::parse(&signer_b.serialize().unwrap()).expect("reparse b");
+        combined
+            .merge(&Rpst::parse(&signer_a.serialize().unwrap()).unwrap())
+            .expect("merge");
+        let tx = combined.finalize().expect("finalize");
+
+        let mut key_ids: Vec<[u8; 32]> =
+            [&pk_a, &pk_b, &pk_c].iter().map(|k| sha3_256(k)).collect();
+        key_ids.sort_unstable();
+        assert_eq!(tx.witness.len(), 3);
+        for (slot, key_id) in key_ids.iter().enumerate() {
+            let w = &tx.witness[slot];
+            if *key_id == sha3_256(&pk_a) {
+                assert_eq!(*w, sig_a);
+            } else if *key_id == sha3_256(&pk_b) {
+                assert_eq!(*w, sig_b);
+            } else {
+                assert_eq!(*w, sentinel_witness());
+            }
+        }
+        assert_eq!(tx.inputs, unsigned_tx().inputs);
+
+        // Merge order does not matter.
+        let mut reversed = signer_a.clone();
+        reversed.merge(&signer_b).expect("merge reversed");
+        assert_eq!(reversed.finalize().expect("finalize reversed"), tx);
+
+        let bytes = marshal_tx(&tx).expect("marshal");
+        let (parsed, _, _, consumed) = parse_tx(&bytes).expect("parse final");
+        assert_eq!(consumed, bytes.len());
+        assert_eq!(parsed, tx);
+    }
+
+    #[test]
+    fn merge_rejects_conflicting_fields() {
+        let (pk_a, sig_a) = fake_signer(0xa1);
+        let (pk_b, _) = fake_signer(0xb2);
+        let prevout = vault_prevout(&[&pk_a, &pk_b], 1);
+        let mut base = Rpst::new(unsigned_tx()).unwrap();
+        base.set_prevout(0, prevout.clone(), 12, RpstRole::Signer)
+            .unwrap();
+
+        let mut other = base.clone();
+        other.inputs[0].creation_height = Some(13);
+        let err = base.clone().merge(&other).unwrap_err();
+        assert!(err.contains("conflicting creation_height"), "{err}");
+
+        let mut left = base.clone();
+        left.add_signature(0, sig_a.clone()).unwrap();
+        let mut right = base.clone();
+        let mut forged = sig_a;
+        forged.signature[0] ^= 1;
+        right.add_signature(0, forged.clone()).unwrap();
+        let err = left.merge(&right).unwrap_err();
+        assert!(err.contains("conflicting witness"), "{err}");
+        let err = left.add_signature(0, forged).unwrap_err();
+        assert!(err.contains("conflicting witness"), "{err}");
+
+        let mut different_tx = unsigned_tx();
+        different_tx.tx_nonce += 1;
+        let err = base.merge(&Rpst::new(different_tx).unwrap()).unwrap_err();
+        assert!(err.contains("different unsigned"), "{err}");
+    }
+
+    #[test]
+    fn add_signature_requires_covenant_key_and_prevout() {
+        let (pk_a, _) = fake_signer(0xa1);
+        let (_, stranger) = fake_signer(0x55);
+        let mut rpst = Rpst::new(unsigned_tx()).unwrap();
+        let err = rpst.add_signature(0, stranger.clone()).unwrap_err();
+        assert!(err.contains("has no prevout"), "{err}");
+        rpst.set_prevout(0, vault_prevout(&[&pk_a], 1), 1, RpstRole::Signer)
+            .unwrap();
+        let err = rpst.add_signature(0, stranger).unwrap_err();
+        assert!(err.contains("not a covenant key"), "{err}");
+        assert!(rpst.add_signature(3, fake_signer(0xa1).1).is_err());
+    }
+
+    #[test]
+    fn parse_rejects_noncanonical_encodings() {
+        let (pk_a, sig_a) = fake_signer(0xa1);
+        let mut rpst = Rpst::new(unsigned_tx()).unwrap();
+        rpst.set_prevout(0, vault_prevout(&[&pk_a], 1), 7, RpstRole::Signer)
+            .unwrap();
+        rpst.add_signature(0, sig_a).unwrap();
+        let bytes = rpst.serialize().unwrap();
+        assert_eq!(Rpst::parse(&bytes).unwrap(), rpst);
+
+        let mut bad_magic = bytes.clone();
+        bad_magic[0] = b'x';
+        assert!(Rpst::parse(&bad_magic).is_err());
+
+        let mut trailing = bytes.clone();
+        trailing.push(0);
+        assert_eq!(Rpst::parse(&trailing).unwrap_err(), "rpst: trailing bytes");
+
+        for cut in [5, bytes.len() / 2, bytes.len() - 1] {
+            assert!(Rpst::parse(&bytes[..cut]).is_err(), "cut={cut}");
+        }
+
+        // Swap the creation_height and role entries: same content, wrong order.
+        let height_entry = [
+            &[1u8, INPUT_KEY_CREATION_HEIGHT, 8][..],
+            &7u64.to_le_bytes(),
+        ]
+        .concat();
+        let role_entry = [1u8, INPUT_KEY_ROLE, 1, 0];
+        let h = find(&bytes, &height_entry);
+        let mut swapped = bytes[..h].to_vec();
+        swapped.extend_from_slice(&role_entry);
+        swapped.extend_from_slice(&height_entry);
+        swapped.extend_from_slice(&bytes[h + height_entry.len() + role_entry.len()..]);
+        assert_eq!(
+            Rpst::parse(&swapped).unwrap_err(),
+            "rpst: map keys not strictly increasing"
+        );
+    }
+
+    fn find(haystack: &[u8], needle: &[u8]) -> usize {
+        haystack
+            .windows(needle.len())
+            .position(|w| w == needle)
+            .expect("needle present")
+    }
+
+    #[test]
+    fn finalize_checks_slot_alignment() {
+        let mut rpst = Rpst::new(unsigned_tx()).unwrap();
+        let err = rpst.finalize().unwrap_err();
+        assert!(err.contains("has no prevout"), "{err}");
+
+        let htlc = TxOutput {
+            value: 10,
+            covenant_type: COV_TYPE_HTLC,
+            covenant_data: vec![0; 105],
+        };
+        rpst.set_prevout(0, htlc, 1, RpstRole::HtlcClaim).unwrap();
+        let (_, item) = fake_signer(0x01);
+        assert!(rpst.add_signature(0, item.clone()).is_err());
+        let err = rpst.set_witness_item(0, 2, item.clone()).unwrap_err();
+        assert!(err.contains("out of range"), "{err}");
+        rpst.set_witness_item(0, 1, item.clone()).unwrap();
+        let err = rpst.finalize().unwrap_err();
+        assert!(err.contains("1 of 2"), "{err}");
+        rpst.set_witness_item(0, 0, item.clone()).unwrap();
+        assert_eq!(rpst.finalize().unwrap().witness, vec![item.clone(), item]);
+    }
+}
@@ -0,0 +1,403 @@
+//! Typed keys for every table the node persists. Key bytes are on-disk
+//! ABI: range scans and the canonical UTXO order depend on how they sort,
+//! so each key is a struct with one `encode` / `decode` pair and no caller
+//! builds key bytes by hand.
+//!
+//! Every encoded key starts with its table's prefix byte, so keys of
+//! different tables can never collide or interleave in one keyspace.
+//!
+//! | table              | prefix | layout after the prefix          | byte order sorts by     |
+//! |--------------------|--------|----------------------------------|-------------------------|
+//! | `Block`            | `0x01` | block hash (32)                  | hash bytes              |
+//! | `Header`           | `0x02` | block hash (32)                  | hash bytes              |
+//! | `Undo`             | `0x03` | block hash (32)                  | hash bytes              |
+//! | `Stats`            | `0x04` | block hash (32)                  | hash bytes              |
+//! | `Status`           | `0x05` | block hash (32)                  | hash bytes              |
+//! | `CanonicalHeight`  | `0x06` | height (u64 big-endian)          | height, numerically     |
+//! | `Utxo`             | `0x07` | txid (32) `\|\|` vout (u32 LE)   | `canonical_outpoint_key`|
+//!
+//! The UTXO key is `canonical_outpoint_key` verbatim, the order
+//! `utxo_set_hash` commits to; because vout is little-endian, outputs of
+//! one txid do not sort numerically by vout.
+//!
+//! Hash-keyed tables live in per-table blockstore directories; their
+//! `file_name` is the hex hash plus the table's historical extension.
+
+use std::fmt;
+
+use rubin_consensus::{canonical_outpoint_key, Outpoint};
+
+/// Table namespace byte leading every encoded key.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(u8)]
+pub enum StoreTable {
+    Block = 0x01,
+    Header = 0x02,
+    Undo = 0x03,
+    Stats = 0x04,
+    Status = 0x05,
+    CanonicalHeight = 0x06,
+    Utxo = 0x07,
+}
+
+impl StoreTable {
+    pub const ALL: [StoreTable; 7] = [
+        StoreTable::Block,
+        StoreTable::Header,
+        StoreTable::Undo,
+        StoreTable::Stats,
+        StoreTable::Status,
+        StoreTable::CanonicalHeight,
+        StoreTable::Utxo,
+    ];
+
+    pub fn prefix(self) -> u8 {
+        self as u8
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum KeyError {
+    Empty,
+    WrongTable {
+        want: StoreTable,
+        got: u8,
+    },
+    Length {
+        table: StoreTable,
+        want: usize,
+        got: usize,
+    },
+    FileName {
+        table: StoreTable,
+        name: String,
+    },
+}
+
+impl fmt::Display for KeyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            KeyError::Empty => write!(f, "empty store key"),
+            KeyError::WrongTable { want, got } => write!(
+                f,
+                "store key prefix {got:#04x} is not table {want:?} ({:#04x})",
+                want.prefix()
+            ),
+            KeyError::Length { table, want, got } => {
+                write!(f, "{table:?} key is {got} bytes, want {want}")
+            }
+            KeyError::FileName { table, name } => {
+                write!(f, "invalid {table:?} file name {name:?}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for KeyError {}
+
+/// A key of one table: the prefix byte followed by a fixed-width body.
+pub trait StoreKey: Sized {
+    const TABLE: StoreTable;
+    /// Encoded length including the prefix byte.
+    const ENCODED_LEN: usize;
+
+    fn encode(&self) -> Vec<u8>;
+
+    fn decode(raw: &[u8]) -> Result<Self, KeyError>;
+}
+
+/// Splits off and checks the table prefix and total length.
+fn key_body(table: StoreTable, want: usize, raw: &[u8]) -> Result<&[u8], KeyError> {
+    let (&prefix, body) = raw.split_first().ok_or(KeyError::Empty)?;
+    if prefix != table.prefix() {
+        return Err(KeyError::WrongTable {
+            want: table,
+            got: prefix,
+        });
+    }
+    if raw.len() != want {
+        return Err(KeyError::Length {
+            table,
+            want,
+            got: raw.len(),
+        });
+    }
+    Ok(body)
+}
+
+macro_rules! hash_key {
+    ($(#[$doc:meta])* $name:ident, $table:ident, $ext:literal) => {
+        $(#[$doc])*
+        #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+        pub struct $name(pub [u8; 32]);
+
+        impl $name {
+            pub fn file_name(&self) -> String {
+                format!(concat!("{}.", $ext), hex::encode(self.0))
+            }
+
+            pub fn from_file_name(name: &str) -> Result<Self, KeyError> {
+                let bad = || KeyError::FileName {
+                    table: StoreTable::$table,
+                    name: name.to_string(),
+                };
+                let hex = name.strip_suffix(concat!(".", $ext)).ok_or_else(bad)?;
+                // Only the exact lowercase form `file_name` writes is a key.
+                if hex.len() != 64 || !hex.bytes().all(|b| matches!(b, b'0'..=b'9' | b'a'..=b'f')) {
+                    return Err(bad());
+                }
+                let mut hash = [0u8; 32];
+                hex::decode_to_slice(hex, &mut hash).map_err(|_| bad())?;
+                Ok(Self(hash))
+            }
+        }
+
+        impl StoreKey for $name {
+            const TABLE: StoreTable = StoreTable::$table;
+            const ENCODED_LEN: usize = 1 + 32;
+
+            fn encode(&self) -> Vec<u8> {
+                let mut out = Vec::with_capacity(Self::ENCODED_LEN);
+                out.push(Self::TABLE.prefix());
+                out.extend_from_slice(&self.0);
+                out
+            }
+
+            fn decode(raw: &[u8]) -> Result<Self, KeyError> {
+                let body = key_body(Self::TABLE, Self::ENCODED_LEN, raw)?;
+                Ok(Self(body.try_into().expect("length checked")))
+            }
+        }
+    };
+}
+
+hash_key!(
+    /// Full block bytes, by block hash.
+    BlockKey,
+    Block,
+    "bin"
+);
+hash_key!(
+    /// Block header bytes, by block hash.
+    HeaderKey,
+    Header,
+    "bin"
+);
+hash_key!(
+    /// Undo record, by block hash.
+    UndoKey,
+    Undo,
+    "json"
+);
+hash_key!(
+    /// Per-block stats, by block hash.
+    StatsKey,
+    Stats,
+    "json"
+);
+hash_key!(
+    /// Block index entry (status, receive time), by block hash.
+    StatusKey,
+    Status,
+    "json"
+);
+
+/// Canonical chain position. Big-endian so byte order is height order.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct CanonicalHeightKey(pub u64);
+
+impl StoreKey for CanonicalHeightKey {
+    const TABLE: StoreTable = StoreTable::CanonicalHeight;
+    const ENCODED_LEN: usize = 1 + 8;
+
+    fn encode(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(Self::ENCODED_LEN);
+        out.push(Self::TABLE.prefix());
+        out.extend_from_slice(&self.0.to_be_bytes());
+        out
+    }
+
+    fn decode(raw: &[u8]) -> Result<Self, KeyError> {
+        let body = key_body(Self::TABLE, Self::ENCODED_LEN, raw)?;
+        Ok(Self(u64::from_be_bytes(
+            body.try_into().expect("length checked"),
+        )))
+    }
+}
+
+/// Unspent output, in canonical UTXO order.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct UtxoKey(pub Outpoint);
+
+impl StoreKey for UtxoKey {
+    const TABLE: StoreTable = StoreTable::Utxo;
+    const ENCODED_LEN: usize = 1 + 36;
+
+    fn encode(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(Self::ENCODED_LEN);
+        out.push(Self::TABLE.prefix());
+        out.extend_from_slice(&canonical_outpoint_key(&self.0));
+        out
+    }
+
+    fn decode(raw: &[u8]) -> Result<Self, KeyError> {
+        let body = key_body(Self::TABLE, Self::ENCODED_LEN, raw)?;
+        let (txid, vout) = body.split_at(32);
+        Ok(Self(Outpoint {
+            txid: txid.try_into().expect("length checked"),
+            vout: u32::from_le_bytes(vout.try_into().expect("length checked")),
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+    use std::fmt::Debug;
+
+    use super::*;
+
+    fn hash_boundaries() -> Vec<[u8; 32]> {
+        let mut out = vec![[0x00; 32], [0xff; 32], [0x80; 32]];
+        for i in [0usize, 1, 30, 31] {
+            let mut low = [0x00; 32];
+            low[i] = 0x01;
+            let mut high = [0xff; 32];
+            high[i] = 0xfe;
+            out.extend([low, high]);
+        }
+        // Lexicographic neighbours around a carry.
+        let mut a = [0x00; 32];
+        a[31] = 0xff;
+        let mut b = [0x00; 32];
+        b[30] = 0x01;
+        out.extend([a, b]);
+        out
+    }
+
+    fn u64_boundaries() -> Vec<u64> {
+        let mut out = vec![0, 1, u64::MAX - 1, u64::MAX];
+        for shift in [7u32, 8, 15, 16, 31, 32, 63] {
+            let v = 1u64 << shift;
+            out.extend([v - 1, v, v + 1]);
+        }
+        out
+    }
+
+    /// Round-trips every key, rejects each one re-tagged with every other
+    /// table or truncated/extended, and checks that sorting by encoded
+    /// bytes gives the same order as sorting by `logical`.
+    fn check_key_type<K, L>(keys: Vec<K>, logical: impl Fn(&K) -> L)
+    where
+        K: StoreKey + Clone + Debug + PartialEq,
+        L: Ord,
+    {
+        for key in &keys {
+            let raw = key.encode();
+            assert_eq!(raw.len(), K::ENCODED_LEN);
+            assert_eq!(raw[0], K::TABLE.prefix());
+            assert_eq!(K::decode(&raw).as_ref(), Ok(key));
+            for table in StoreTable::ALL {
+                if table == K::TABLE {
+                    continue;
+                }
+                let mut other = raw.clone();
+                other[0] = table.prefix();
+                assert_eq!(
+                    K::decode(&other),
+                    Err(KeyError::WrongTable {
+                        want: K::TABLE,
+                        got: table.prefix()
+                    })
+                );
+            }
+            assert!(matches!(
+                K::decode(&raw[..raw.len() - 1]),
+                Err(KeyError::Length { .. })
+            ));
+            let mut longer = raw.clone();
+            longer.push(0);
+            assert!(matches!(K::decode(&longer), Err(KeyError::Length { .. })));
+        }
+        assert_eq!(K::decode(&[]), Err(KeyError::Empty));
+
+        let mut by_bytes = keys.clone();
+        by_bytes.sort_by_key(|k| k.encode());
+        let mut by_logic = keys;
+        by_logic.sort_by_key(|k| logical(k));
+        assert_eq!(by_bytes, by_logic);
+    }
+
+    #[test]
+    fn table_prefixes_are_distinct() {
+        let prefixes: HashSet<u8> = StoreTable::ALL.iter().map(|t| t.prefix()).collect();
+        assert_eq!(prefixes.len(), StoreTable::ALL.len());
+        assert!(!prefixes.contains(&0x00));
+    }
+
+    #[test]
+    fn hash_keys_round_trip_and_sort_by_hash() {
+        let hashes = hash_boundaries();
+        check_key_type(hashes.iter().map(|h| BlockKey(*h)).collect(), |k| k.0);
+        check_key_type(hashes.iter().map(|h| HeaderKey(*h)).collect(), |k| k.0);
+        check_key_type(hashes.iter().map(|h| UndoKey(*h)).collect(), |k| k.0);
+        check_key_type(hashes.iter().map(|h| StatsKey(*h)).collect(), |k| k.0);
+        check_key_type(hashes.iter().map(|h| StatusKey(*h)).collect(), |k| k.0);
+    }
+
+    #[test]
+    fn hash_key_file_names_round_trip() {
+        for hash in hash_boundaries() {
+            let name = BlockKey(hash).file_name();
+            assert_eq!(name, format!("{}.bin", hex::encode(hash)));
+            assert_eq!(BlockKey::from_file_name(&name), Ok(BlockKey(hash)));
+            let name = UndoKey(hash).file_name();
+            assert_eq!(name, format!("{}.json", hex::encode(hash)));
+            assert_eq!(UndoKey::from_file_name(&name), Ok(UndoKey(hash)));
+        }
+        let hex = "ab".repeat(32);
+        for bad in [
+            format!("{hex}.json"),
+            format!("{hex}.BIN"),
+            format!("{}.bin", "AB".repeat(32)),
+            format!("{}.bin", "ab".repeat(31)),
+            format!("0x{hex}.bin"),
+            "../x.bin".to_string(),
+        ] {
+            assert!(
+                matches!(
+                    BlockKey::from_file_name(&bad),
+                    Err(KeyError::FileName { .. })
+                ),
+                "{bad}"
+            );
+        }
+    }
+
+    #[test]
+    fn height_keys_sort_numerically() {
+        check_key_type(
+            u64_boundaries()
+                .into_iter()
+                .map(CanonicalHeightKey)
+                .collect(),
+            |k| k.0,
+        );
+    }
+
+    #[test]
+    fn utxo_keys_follow_canonical_outpoint_order() {
+        let mut keys = Vec::new();
+        for txid in hash_boundaries() {
+            for vout in [0u32, 1, 0xff, 0x100, 0x1_0000, u32::MAX - 1, u32::MAX] {
+                keys.push(UtxoKey(Outpoint { txid, vout }));
+            }
+        }
+        check_key_type(keys, |k| canonical_outpoint_key(&k.0));
+
+        // Little-endian vout: byte order is not numeric vout order.
+        let txid = [0x11; 32];
+        let k = |vout| UtxoKey(Outpoint { txid, vout }).encode();
+        assert!(k(0x100) < k(0x01));
+    }
+}
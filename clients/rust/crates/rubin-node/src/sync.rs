@@ -4,13 +4,20 @@ use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
+use num_bigint::BigUint;
+
 use rubin_consensus::constants::POW_LIMIT;
-use rubin_consensus::{block_hash, parse_block_bytes, parse_block_header_bytes};
+use rubin_consensus::{
+    block_hash, check_block_timestamp, median_time_past, parse_block_bytes,
+    parse_block_header_bytes, validate_header_chain_with_params, BlockHeader, ChainParams, TxError,
+};
 use rubin_consensus::{RotationProvider, SuiteRegistry};
 
-use crate::blockstore::BlockStore;
+use crate::blockstore::{BlockStore, BlockValidationContext};
 use crate::chainstate::{ChainState, ChainStateConnectSummary};
 use crate::chainstate_recovery::should_persist_chainstate_snapshot;
+use crate::header_cache::AncestorHeaderCache;
+use crate::sync_low_work::LowWorkBlockCache;
 use crate::undo::build_block_undo;
 
 pub const DEFAULT_IBD_LAG_SECONDS: u64 = 24 * 60 * 60;
@@ -29,6 +36,17 @@ pub struct SyncConfig {
     pub suite_context: Option<SuiteContext>,
     pub parallel_validation_mode: String,
     pub pv_shadow_max_samples: u64,
+    /// Deepest fork point (blocks disconnected) a reorg may have before it
+    /// needs an operator `--force-reorg`. 0 disables the limit.
+    pub max_auto_reorg_depth: u64,
+    /// Side-branch blocks whose branch work stays below both this and the
+    /// current tip's work are held in memory only (`sync_low_work`). Zero
+    /// stores every valid side block.
+    pub minimum_chainwork: BigUint,
+    /// Retarget window, target interval and coinbase maturity for this chain
+    /// instance; `ChainParams::V1` unless a non-production genesis pack sets
+    /// `consensus_overrides`.
+    pub chain_params: ChainParams,
 }
 
 #[derive(Clone)]
@@ -477,11 +495,16 @@ pub struct SyncEngine {
     pub(crate) best_known_height: u64,
     last_reorg_depth: u64,
     reorg_count: u64,
+    refused_reorg_count: u64,
     pv_mode: ParallelValidationMode,
     pv_shadow_max_samples: u64,
     pv_shadow_mismatches: u64,
     pv_shadow_samples: Vec<String>,
     pv_telemetry: PVTelemetry,
+    /// Best-chain headers ending at the tip, so block validation contexts
+    /// are built without walking the blockstore.
+    pub(crate) ancestor_cache: AncestorHeaderCache,
+    pub(crate) low_work_blocks: LowWorkBlockCache,
     /// Test-only: drop block_store after canonical truncate (between
     /// truncate and save) to exercise the otherwise-unreachable
     /// blockstore-missing branch in disconnect_tip's save-failure
@@ -521,6 +544,9 @@ pub fn default_sync_config(
         suite_context: None,
         parallel_validation_mode: "off".to_string(),
         pv_shadow_max_samples: DEFAULT_PV_SHADOW_MAX_SAMPLES,
+        max_auto_reorg_depth: 0,
+        minimum_chainwork: BigUint::ZERO,
+        chain_params: ChainParams::V1,
     }
 }
 
@@ -564,6 +590,7 @@ impl SyncEngine {
         } else {
             0
         };
+        let ancestor_cache = AncestorHeaderCache::new(&cfg.chain_params);
         Ok(Self {
             chain_state,
             block_store,
@@ -572,11 +599,14 @@ impl SyncEngine {
             best_known_height,
             last_reorg_depth: 0,
             reorg_count: 0,
+            refused_reorg_count: 0,
             pv_mode,
             pv_shadow_max_samples,
             pv_shadow_mismatches: 0,
             pv_shadow_samples: Vec::new(),
             pv_telemetry: PVTelemetry::new(pv_mode),
+            ancestor_cache,
+            low_work_blocks: LowWorkBlockCache::default(),
             #[cfg(test)]
             drop_block_store_after_truncate: false,
         })
@@ -615,6 +645,16 @@ impl SyncEngine {
         self.reorg_count
     }
 
+    /// Number of preferred branches held back because their fork point
+    /// exceeded `max_auto_reorg_depth`.
+    pub fn refused_reorg_count(&self) -> u64 {
+        self.refused_reorg_count
+    }
+
+    pub(crate) fn note_refused_reorg(&mut self) {
+        self.refused_reorg_count = self.refused_reorg_count.saturating_add(1);
+    }
+
     pub(crate) fn note_reorg(&mut self, depth: u64) {
         self.last_reorg_depth = depth;
         if depth > 0 {
@@ -630,6 +670,10 @@ impl SyncEngine {
         self.cfg.chain_id
     }
 
+    pub fn network(&self) -> &str {
+        &self.cfg.network
+    }
+
     pub fn block_store_snapshot(&self) -> Option<BlockStore> {
         self.block_store.clone()
     }
@@ -704,7 +748,7 @@ impl SyncEngine {
         ) && self.is_in_ibd_unchecked()
     }
 
-    fn is_in_ibd_unchecked(&self) -> bool {
+    pub(crate) fn is_in_ibd_unchecked(&self) -> bool {
         let Ok(now) = SystemTime::now().duration_since(UNIX_EPOCH) else {
             return true;
         };
@@ -751,13 +795,14 @@ impl SyncEngine {
                 Some(ctx) => (Some(ctx.rotation.as_ref()), Some(ctx.registry.as_ref())),
                 None => (None, None),
             };
-        let summary = match self.chain_state.connect_block_with_suite_context(
+        let summary = match self.chain_state.connect_block_with_chain_params(
             block_bytes,
             self.cfg.expected_target,
             prev_timestamps,
             self.cfg.chain_id,
             rotation,
             registry,
+            &self.cfg.chain_params,
         ) {
             Ok(summary) => summary,
             Err(err) => {
@@ -858,20 +903,42 @@ impl SyncEngine {
         // would require repair. No rewind is needed here for the normal
         // `commit_canonical_block` error path.
         let canonical_len_before = self.block_store.as_ref().map_or(0, |bs| bs.canonical_len());
+        // Decided before the commit so a block whose snapshot will be
+        // saved anchors its `utxo_set_hash` in the same index write.
+        let persist_snapshot = self.cfg.chain_state_path.is_some()
+            && (self.block_store.is_none()
+                || should_persist_chainstate_snapshot(Some(&self.chain_state), Some(&summary)));
+        let anchored_utxo_set_hash = (persist_snapshot && self.block_store.is_some())
+            .then(|| self.chain_state.utxo_set_hash());
         if let Some(block_store) = self.block_store.as_mut() {
             // Atomic canonical commit — Go parity
             // (`clients/go/node/blockstore.go`, `CommitCanonicalBlock`).
             // Order inside the call: block bytes -> header bytes -> undo
             // -> canonical tip (last). A failure before the tip advance
             // leaves the canonical tip at its prior height, so no rewind
-            // is required on block/header/undo write failure.
-            if let Err(err) = block_store.commit_canonical_block(
-                summary.block_height,
-                block_hash_bytes,
-                &parsed.header_bytes,
-                block_bytes,
-                &undo,
-            ) {
+            // is required on block/header/undo write failure. Block stats
+            // go first under the same rule: keyed by hash, never read
+            // through the canonical index until the tip advances.
+            if let Err(err) = block_store
+                .put_block_stats(block_hash_bytes, &summary.stats)
+                .and_then(|()| match anchored_utxo_set_hash {
+                    Some(utxo_set_hash) => block_store.commit_canonical_block_with_utxo_set_hash(
+                        summary.block_height,
+                        block_hash_bytes,
+                        &parsed.header_bytes,
+                        block_bytes,
+                        &undo,
+                        utxo_set_hash,
+                    ),
+                    None => block_store.commit_canonical_block(
+                        summary.block_height,
+                        block_hash_bytes,
+                        &parsed.header_bytes,
+                        block_bytes,
+                        &undo,
+                    ),
+                })
+            {
                 self.chain_state = snapshot;
                 self.tip_timestamp = old_tip_timestamp;
                 self.best_known_height = old_best_known_height;
@@ -893,12 +960,10 @@ impl SyncEngine {
         // reconcile in main.rs) call `chain_state.save` directly and are
         // unaffected by this gate.
         //
-        // Early-return when chainstate persistence is fully disabled
-        // (`chain_state_path == None`): no save would happen anyway, so
-        // skip the cadence computation entirely on the hot path.
+        // `persist_snapshot` is false when chainstate persistence is fully
+        // disabled (`chain_state_path == None`): no save would happen
+        // anyway, so the cadence computation is skipped on the hot path.
         if let Some(chain_state_path) = self.cfg.chain_state_path.as_ref() {
-            let persist_snapshot = self.block_store.is_none()
-                || should_persist_chainstate_snapshot(Some(&self.chain_state), Some(&summary));
             if persist_snapshot {
                 if let Err(err) = self.chain_state.save(chain_state_path) {
                     // Canonical commit MAY have advanced the tip. The
@@ -928,6 +993,7 @@ impl SyncEngine {
         }
 
         self.tip_timestamp = parsed.header.timestamp;
+        self.note_connected_header(summary.block_height, block_hash_bytes, parsed.header);
         if summary.block_height > self.best_known_height {
             self.best_known_height = summary.block_height;
         }
@@ -942,6 +1008,54 @@ impl SyncEngine {
         Ok(summary)
     }
 
+    /// Extends the ancestor header window with the block just connected,
+    /// refilling it from the blockstore when the block does not extend the
+    /// window's tip (cold start, or after a reorg deeper than the window).
+    fn note_connected_header(&mut self, height: u64, hash: [u8; 32], header: BlockHeader) {
+        if self.ancestor_cache.push(height, hash, header) {
+            return;
+        }
+        let refilled = self
+            .block_store
+            .as_ref()
+            .is_some_and(|store| self.ancestor_cache.refill(store, hash, height).is_ok());
+        if !refilled {
+            self.ancestor_cache.clear();
+        }
+    }
+
+    /// Validation context for a block at `new_height` on the canonical
+    /// chain, served from the ancestor header window while it ends at the
+    /// chain tip and falling back to the blockstore otherwise.
+    fn canonical_validation_context(
+        &self,
+        block_store: &BlockStore,
+        parent_hash: [u8; 32],
+        new_height: u64,
+    ) -> Result<BlockValidationContext, String> {
+        if self.chain_state.has_tip
+            && self.ancestor_cache.tip_hash() == Some(self.chain_state.tip_hash)
+        {
+            let count = BlockValidationContext::ancestor_count_with_params(
+                new_height,
+                &self.cfg.chain_params,
+            );
+            if let Some(ancestors) = self.ancestor_cache.ancestors_for(new_height, count) {
+                return Ok(BlockValidationContext {
+                    height: new_height,
+                    ancestors,
+                    local_time: None,
+                });
+            }
+        }
+        block_store.validation_context_with_params(
+            parent_hash,
+            new_height,
+            None,
+            &self.cfg.chain_params,
+        )
+    }
+
     // ----- Rollback helpers (used by sync_disconnect / sync_reorg) -----
 
     /// Light rollback state — no canonical suffix (used by disconnect_tip).
@@ -1031,6 +1145,105 @@ impl SyncEngine {
         }
     }
 
+    /// Checks a header that extends the current tip against the MTP window
+    /// before its body is fetched. Headers on other branches are left to the
+    /// full connect path. No local-clock bound is applied (see
+    /// [`check_block_timestamp`]), so the verdict matches block connection.
+    ///
+    /// The outer `Err` is a local failure to build the MTP context; the inner
+    /// one is the consensus verdict (`BLOCK_ERR_TIMESTAMP_*`) on the header.
+    pub fn precheck_header_timestamp(
+        &self,
+        header: &BlockHeader,
+    ) -> Result<Result<(), TxError>, String> {
+        if !self.chain_state.has_tip
+            || self.block_store.is_none()
+            || header.prev_block_hash != self.chain_state.tip_hash
+        {
+            return Ok(Ok(()));
+        }
+        let prev_timestamps = self.prev_timestamps_for_next_block()?;
+        let next_height = self.chain_state.height + 1;
+        let Some(mtp) =
+            median_time_past(next_height, prev_timestamps.as_deref()).map_err(|e| e.to_string())?
+        else {
+            return Ok(Ok(()));
+        };
+        Ok(check_block_timestamp(header.timestamp, mtp, None))
+    }
+
+    /// Validates a peer's header batch whose first header extends a block on
+    /// the local canonical chain. Ancestors are loaded from the blockstore
+    /// (the MTP window, widened to the full retarget window when the batch
+    /// crosses a boundary). Any failure rejects the whole batch and reports
+    /// the index of the first bad header; the sender should be penalized as
+    /// for an invalid block.
+    pub fn validate_header_batch(
+        &self,
+        headers: &[BlockHeader],
+    ) -> Result<Vec<[u8; 32]>, (usize, String)> {
+        let Some(first) = headers.first() else {
+            return Ok(Vec::new());
+        };
+        let Some(block_store) = self.block_store.as_ref() else {
+            return Err((0, "sync engine missing blockstore".to_string()));
+        };
+        let parent_height = block_store
+            .find_canonical_height(first.prev_block_hash)
+            .map_err(|e| (0, e))?
+            .ok_or_else(|| {
+                (
+                    0,
+                    "header batch parent is not on the canonical chain".to_string(),
+                )
+            })?;
+        let start_height = parent_height + 1;
+        let end_height = start_height.saturating_add(headers.len() as u64);
+        let window_size = self.cfg.chain_params.window_size;
+        let next_boundary = start_height.next_multiple_of(window_size);
+        // Median-time-past needs the 11 headers before the batch; a batch
+        // crossing a retarget boundary also needs that window's first header.
+        let mtp_height = start_height.saturating_sub(11);
+        let from_height = if next_boundary < end_height {
+            (next_boundary - window_size).min(mtp_height)
+        } else {
+            mtp_height
+        };
+
+        let ancestors = block_store
+            .ancestor_headers(first.prev_block_hash, start_height - from_height)
+            .map_err(|e| (0, e))?;
+
+        if let Some(expected) = self.cfg.expected_target {
+            if let Some(i) = headers.iter().position(|h| h.target != expected) {
+                return Err((i, "target mismatch".to_string()));
+            }
+        }
+        validate_header_chain_with_params(&ancestors, headers, start_height, &self.cfg.chain_params)
+            .map_err(|(i, e)| (i, e.to_string()))
+    }
+
+    /// [`Self::validate_header_batch`], then records the batch's last
+    /// header as the blockstore's best header so the headers-first
+    /// progress survives a restart before its blocks are connected.
+    pub fn accept_header_batch(
+        &mut self,
+        headers: &[BlockHeader],
+    ) -> Result<Vec<[u8; 32]>, (usize, String)> {
+        let hashes = self.validate_header_batch(headers)?;
+        let (Some(last), Some(block_store)) = (hashes.last(), self.block_store.as_mut()) else {
+            return Ok(hashes);
+        };
+        let start_height = block_store
+            .find_canonical_height(headers[0].prev_block_hash)
+            .map_err(|e| (0, e))?
+            .map_or(0, |parent| parent + 1);
+        block_store
+            .record_best_header(start_height + hashes.len() as u64 - 1, *last)
+            .map_err(|e| (hashes.len() - 1, e))?;
+        Ok(hashes)
+    }
+
     pub fn prev_timestamps_for_next_block(&self) -> Result<Option<Vec<u64>>, String> {
         if !self.chain_state.has_tip {
             return Ok(None);
@@ -1044,20 +1257,9 @@ impl SyncEngine {
         };
 
         let next_height = self.chain_state.height + 1;
-        let window_len = next_height.min(11);
-        let mut out = Vec::with_capacity(window_len as usize);
-        for offset in 0..window_len {
-            let height = next_height - 1 - offset;
-            let Some(hash) = block_store.canonical_hash(height)? else {
-                return Err(format!(
-                    "missing canonical hash at height {height} for timestamp context (next_height={next_height})"
-                ));
-            };
-            let header_bytes = block_store.get_header_by_hash(hash)?;
-            let header = parse_block_header_bytes(&header_bytes).map_err(|e| e.to_string())?;
-            out.push(header.timestamp);
-        }
-        Ok(Some(out))
+        Ok(self
+            .canonical_validation_context(block_store, self.chain_state.tip_hash, next_height)?
+            .prev_timestamps())
     }
 
     /// Derive prev_timestamps for a given `next_height` from the blockstore.
@@ -1073,20 +1275,15 @@ impl SyncEngine {
         let Some(block_store) = self.block_store.as_ref() else {
             return Err("sync engine missing blockstore for timestamp context".to_string());
         };
-        let window_len = next_height.min(11);
-        let mut out = Vec::with_capacity(window_len as usize);
-        for offset in 0..window_len {
-            let height = next_height - 1 - offset;
-            let Some(hash) = block_store.canonical_hash(height)? else {
-                return Err(format!(
-                    "missing canonical hash at height {height} for timestamp context (next_height={next_height})"
-                ));
-            };
-            let header_bytes = block_store.get_header_by_hash(hash)?;
-            let header = parse_block_header_bytes(&header_bytes).map_err(|e| e.to_string())?;
-            out.push(header.timestamp);
-        }
-        Ok(Some(out))
+        let parent_height = next_height - 1;
+        let Some(parent_hash) = block_store.canonical_hash(parent_height)? else {
+            return Err(format!(
+                "missing canonical hash at height {parent_height} for timestamp context (next_height={next_height})"
+            ));
+        };
+        Ok(self
+            .canonical_validation_context(block_store, parent_hash, next_height)?
+            .prev_timestamps())
     }
 }
 
@@ -1163,13 +1360,14 @@ fn run_pv_shadow_validation(
             Some(ctx) => (Some(ctx.rotation.as_ref()), Some(ctx.registry.as_ref())),
             None => (None, None),
         };
-    shadow_state.connect_block_with_suite_context(
+    shadow_state.connect_block_with_chain_params(
         block_bytes,
         cfg.expected_target,
         prev_timestamps,
         cfg.chain_id,
         rotation,
         registry,
+        &cfg.chain_params,
     )?;
     Ok(shadow_state.utxo_set_hash())
 }
@@ -1188,16 +1386,16 @@ mod tests {
     use std::time::{SystemTime, UNIX_EPOCH};
 
     use rubin_consensus::constants::{
-        COV_TYPE_P2PK, POW_LIMIT, SUITE_ID_ML_DSA_87, TX_WIRE_VERSION,
+        COV_TYPE_P2PK, MAX_FUTURE_DRIFT, POW_LIMIT, SUITE_ID_ML_DSA_87, TX_WIRE_VERSION,
     };
     use rubin_consensus::merkle::{witness_commitment_hash, witness_merkle_root_wtxids};
     use rubin_consensus::{
         block_hash, encode_compact_size, marshal_tx, merkle_root_txids,
-        p2pk_covenant_data_for_pubkey, parse_block_bytes, parse_tx, sign_transaction,
+        p2pk_covenant_data_for_pubkey, parse_block_bytes, parse_tx, sign_transaction, BlockHeader,
         Mldsa87Keypair, NativeSuiteSet, Outpoint, RotationProvider, Tx, TxInput, TxOutput,
         UtxoEntry, BLOCK_HEADER_BYTES,
     };
-    use rubin_consensus::{DefaultRotationProvider, SuiteRegistry};
+    use rubin_consensus::{DefaultRotationProvider, ErrorCode, SuiteRegistry};
 
     use crate::blockstore::{block_store_path, BlockStore};
     use crate::chainstate::{chain_state_path, load_chain_state, ChainState};
@@ -1336,6 +1534,71 @@ mod tests {
         std::fs::remove_dir_all(&dir).expect("cleanup");
     }
 
+    /// A crash after headers-first recorded block 1's header but before
+    /// the block was connected: the anchors keep the header ahead of the
+    /// connected chain, the snapshot still matches them, and connecting
+    /// the block afterwards brings all three anchors back together.
+    #[test]
+    fn sync_engine_anchors_survive_crash_between_header_and_block_connect() {
+        use crate::blockstore::ChainAnchor;
+        use crate::chainstate::load_chain_state_with_anchors;
+        use crate::test_helpers::{genesis_info, height_one_coinbase_only_block};
+
+        let dir = unique_temp_path("rubin-node-sync-anchors-crash");
+        let chain_state_file = chain_state_path(&dir);
+        let cfg = default_sync_config(Some(POW_LIMIT), [0u8; 32], Some(chain_state_file.clone()));
+        let store = BlockStore::open(block_store_path(&dir)).expect("open blockstore");
+        let mut engine =
+            SyncEngine::new(ChainState::new(), Some(store), cfg.clone()).expect("new sync");
+        let (genesis, genesis_hash, gen_ts) = genesis_info();
+        engine.apply_block(&genesis, None).expect("genesis");
+
+        let block1 = height_one_coinbase_only_block(genesis_hash, gen_ts + 1);
+        let header1 = parse_block_bytes(&block1).expect("parse block 1").header;
+        let hash1 = block_hash(&block1[..BLOCK_HEADER_BYTES]).expect("hash");
+        let hashes = engine
+            .accept_header_batch(&[header1])
+            .expect("header batch");
+        assert_eq!(hashes, vec![hash1]);
+        drop(engine);
+
+        let store = BlockStore::open(block_store_path(&dir)).expect("reopen blockstore");
+        let anchors = store.chain_anchors().expect("anchors").expect("some");
+        let genesis_anchor = ChainAnchor {
+            height: 0,
+            hash: genesis_hash,
+        };
+        let block1_anchor = ChainAnchor {
+            height: 1,
+            hash: hash1,
+        };
+        assert_eq!(anchors.best_header, block1_anchor);
+        assert_eq!(anchors.best_block, genesis_anchor);
+        assert!(!store.has_block(hash1));
+        let state = load_chain_state_with_anchors(&chain_state_file, Some(&anchors))
+            .expect("load chainstate");
+        assert_eq!((state.height, state.tip_hash), (0, genesis_hash));
+        assert_eq!(anchors.utxo, Some((genesis_anchor, state.utxo_set_hash())));
+
+        let mut engine = SyncEngine::new(state, Some(store), cfg).expect("restart sync");
+        engine.apply_block(&block1, None).expect("block 1");
+        let anchors = engine
+            .block_store
+            .as_ref()
+            .expect("store")
+            .chain_anchors()
+            .expect("anchors")
+            .expect("some");
+        assert_eq!(anchors.best_header, block1_anchor);
+        assert_eq!(anchors.best_block, block1_anchor);
+        assert_eq!(
+            anchors.utxo,
+            Some((block1_anchor, engine.chain_state.utxo_set_hash()))
+        );
+
+        std::fs::remove_dir_all(&dir).expect("cleanup");
+    }
+
     /// B.1 sub-issue #1246: when `cfg.chain_state_path == None`,
     /// `apply_block` should skip the chainstate snapshot save path.
     /// Verified by constructing a `SyncEngine` with a blockstore but
@@ -1616,6 +1879,153 @@ mod tests {
         std::fs::remove_dir_all(&dir).expect("cleanup");
     }
 
+    #[test]
+    fn sync_engine_precheck_header_timestamp_applies_mtp_window_to_tip_children() {
+        let dir = unique_temp_path("rubin-node-sync-precheck-timestamp");
+        let mut store = BlockStore::open(block_store_path(&dir)).expect("open blockstore");
+        let block = build_block_bytes([0u8; 32], [0x11; 32], POW_LIMIT, 1_000, &[]);
+        let tip_hash = block_hash(&block[..BLOCK_HEADER_BYTES]).expect("tip hash");
+        store
+            .put_block(0, tip_hash, &block[..BLOCK_HEADER_BYTES], &block)
+            .expect("persist tip block");
+
+        let mut chain_state = ChainState::new();
+        chain_state.has_tip = true;
+        chain_state.height = 0;
+        chain_state.tip_hash = tip_hash;
+        let cfg = default_sync_config(Some(POW_LIMIT), devnet_genesis_chain_id(), None);
+        let engine = SyncEngine::new(chain_state, Some(store), cfg).expect("new sync");
+
+        let header = |prev_block_hash: [u8; 32], timestamp: u64| BlockHeader {
+            version: 1,
+            prev_block_hash,
+            merkle_root: [0u8; 32],
+            timestamp,
+            target: POW_LIMIT,
+            nonce: 0,
+        };
+        let precheck = |header: &BlockHeader| {
+            engine
+                .precheck_header_timestamp(header)
+                .expect("mtp context")
+        };
+        let err = precheck(&header(tip_hash, 1_000)).unwrap_err();
+        assert_eq!(err.code, ErrorCode::BlockErrTimestampOld, "{err}");
+        precheck(&header(tip_hash, 1_001)).expect("mtp + 1");
+        precheck(&header(tip_hash, 1_000 + MAX_FUTURE_DRIFT)).expect("upper bound");
+        let err = precheck(&header(tip_hash, 1_000 + MAX_FUTURE_DRIFT + 1)).unwrap_err();
+        assert_eq!(err.code, ErrorCode::BlockErrTimestampFuture, "{err}");
+        // Side-branch headers are not pre-checked.
+        precheck(&header([0x22; 32], 0)).expect("side branch");
+
+        std::fs::remove_dir_all(&dir).expect("cleanup");
+    }
+
+    #[test]
+    fn sync_engine_validate_header_batch_keeps_mtp_window_across_retarget_boundary() {
+        let dir = unique_temp_path("rubin-node-sync-header-batch-boundary");
+        let mut store = BlockStore::open(block_store_path(&dir)).expect("open blockstore");
+        // Window 8: the tip is height 10, so the batch starts 3 headers past
+        // the boundary at 8 and crosses the one at 16. The median of the last
+        // 11 timestamps is 5_000, so a 2_000 header must be rejected as old.
+        let mut prev = [0u8; 32];
+        for height in 0..11u64 {
+            let timestamp = if height < 8 { 5_000 } else { 1_000 + height };
+            let block = build_block_bytes(prev, [0x11; 32], POW_LIMIT, timestamp, &[]);
+            let hash = block_hash(&block[..BLOCK_HEADER_BYTES]).expect("hash");
+            store
+                .put_block(height, hash, &block[..BLOCK_HEADER_BYTES], &block)
+                .expect("persist block");
+            prev = hash;
+        }
+        let mut chain_state = ChainState::new();
+        chain_state.has_tip = true;
+        chain_state.height = 10;
+        chain_state.tip_hash = prev;
+        let mut cfg = default_sync_config(Some(POW_LIMIT), devnet_genesis_chain_id(), None);
+        cfg.chain_params.window_size = 8;
+        let engine = SyncEngine::new(chain_state, Some(store), cfg).expect("new sync");
+
+        let mut headers = Vec::new();
+        let mut parent = prev;
+        for i in 0..6u64 {
+            let mut header = BlockHeader {
+                version: 1,
+                prev_block_hash: parent,
+                merkle_root: [0x22; 32],
+                timestamp: 2_000 + i * 120,
+                target: POW_LIMIT,
+                nonce: 0,
+            };
+            while rubin_consensus::pow_check(&header.to_bytes(), POW_LIMIT).is_err() {
+                header.nonce += 1;
+            }
+            parent = block_hash(&header.to_bytes()).expect("hash");
+            headers.push(header);
+        }
+        let (idx, err) = engine.validate_header_batch(&headers).unwrap_err();
+        assert_eq!(idx, 0);
+        assert!(err.contains("BLOCK_ERR_TIMESTAMP_OLD"), "{err}");
+
+        std::fs::remove_dir_all(&dir).expect("cleanup");
+    }
+
+    #[test]
+    fn sync_engine_validate_header_batch_rejects_whole_batch_at_first_bad_header() {
+        let dir = unique_temp_path("rubin-node-sync-header-batch");
+        let mut store = BlockStore::open(block_store_path(&dir)).expect("open blockstore");
+        let mut prev = [0u8; 32];
+        for height in 0..3u64 {
+            let block = build_block_bytes(prev, [0x11; 32], POW_LIMIT, 1_000 + height * 120, &[]);
+            let hash = block_hash(&block[..BLOCK_HEADER_BYTES]).expect("hash");
+            store
+                .put_block(height, hash, &block[..BLOCK_HEADER_BYTES], &block)
+                .expect("persist block");
+            prev = hash;
+        }
+        let mut chain_state = ChainState::new();
+        chain_state.has_tip = true;
+        chain_state.height = 2;
+        chain_state.tip_hash = prev;
+        let cfg = default_sync_config(Some(POW_LIMIT), devnet_genesis_chain_id(), None);
+        let engine = SyncEngine::new(chain_state, Some(store), cfg).expect("new sync");
+
+        let mut headers = Vec::new();
+        let mut parent = prev;
+        for i in 0..3u64 {
+            let mut header = BlockHeader {
+                version: 1,
+                prev_block_hash: parent,
+                merkle_root: [0x22; 32],
+                timestamp: 1_360 + i * 120,
+                target: POW_LIMIT,
+                nonce: 0,
+            };
+            while rubin_consensus::pow_check(&header.to_bytes(), POW_LIMIT).is_err() {
+                header.nonce += 1;
+            }
+            parent = block_hash(&header.to_bytes()).expect("hash");
+            headers.push(header);
+        }
+        let hashes = engine.validate_header_batch(&headers).expect("valid batch");
+        assert_eq!(hashes.len(), 3);
+        assert_eq!(hashes[2], parent);
+
+        let mut bad = headers.clone();
+        bad[1].timestamp = 1_000;
+        let (idx, err) = engine.validate_header_batch(&bad).unwrap_err();
+        assert_eq!(idx, 1);
+        assert!(err.contains("BLOCK_ERR_TIMESTAMP_OLD"), "{err}");
+
+        let mut orphan = headers.clone();
+        orphan[0].prev_block_hash = [0x33; 32];
+        let (idx, err) = engine.validate_header_batch(&orphan).unwrap_err();
+        assert_eq!(idx, 0);
+        assert!(err.contains("not on the canonical chain"), "{err}");
+
+        std::fs::remove_dir_all(&dir).expect("cleanup");
+    }
+
     #[test]
     fn sync_engine_apply_block_no_mutation_on_failure() {
         let mut st = ChainState::new();
@@ -128,6 +128,7 @@ impl SyncEngine {
         }
 
         self.tip_timestamp = new_tip_timestamp;
+        self.ancestor_cache.pop(tip_hash);
         Ok(summary)
     }
 
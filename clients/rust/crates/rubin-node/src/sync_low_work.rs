@@ -0,0 +1,295 @@
+//! Minimum-chainwork gate for side branches.
+//!
+//! Every stored side block costs a blockstore entry, so a peer able to mine
+//! trivial-difficulty blocks off an old ancestor could otherwise grow the
+//! block index without bound. When `SyncConfig::minimum_chainwork` is set,
+//! a side block whose branch work is below both that minimum and the
+//! current tip's work passes the stateless block checks and is then kept in
+//! a small in-memory set instead of the store. Descendants accumulate work
+//! from their cached parent without touching the store; the first block
+//! that lifts its branch over the minimum (or over the tip) goes through the
+//! normal side-branch path, which validates and persists the cached
+//! ancestors along with it. The set evicts oldest first: a branch that
+//! needs more held-back blocks than the set holds can only be completed by
+//! re-fetching its evicted base, which the orphan path already does.
+
+use std::collections::{HashMap, VecDeque};
+
+use num_bigint::BigUint;
+use rubin_consensus::{work_from_target, ParsedBlock};
+
+use crate::block_preverify::check_block_structure;
+use crate::sync::SyncEngine;
+
+/// Returned by block import for a side block kept in memory only. The
+/// block is valid as far as it was checked; relaying peers are charged
+/// `LOW_WORK_SIDE_BLOCK_BAN_SCORE` per block.
+pub const LOW_WORK_SIDE_BLOCK_ERR: &str = "side branch below minimum chainwork";
+/// Ban score per low-work side block, so a peer feeding a spam branch is
+/// disconnected after a bounded number of blocks.
+pub const LOW_WORK_SIDE_BLOCK_BAN_SCORE: i32 = 2;
+/// Side blocks held in memory at most.
+pub const LOW_WORK_BLOCK_CACHE_LIMIT: usize = 128;
+
+pub fn is_low_work_side_block_err(err: &str) -> bool {
+    err == LOW_WORK_SIDE_BLOCK_ERR
+}
+
+#[derive(Clone, Debug)]
+struct LowWorkBlock {
+    block_bytes: Vec<u8>,
+    /// Work from genesis through this block.
+    chain_work: BigUint,
+}
+
+#[derive(Clone, Debug, Default)]
+pub(crate) struct LowWorkBlockCache {
+    blocks: HashMap<[u8; 32], LowWorkBlock>,
+    /// Insertion order, oldest first.
+    order: VecDeque<[u8; 32]>,
+}
+
+impl LowWorkBlockCache {
+    pub(crate) fn len(&self) -> usize {
+        self.blocks.len()
+    }
+
+    pub(crate) fn contains(&self, hash: &[u8; 32]) -> bool {
+        self.blocks.contains_key(hash)
+    }
+
+    pub(crate) fn block_bytes(&self, hash: &[u8; 32]) -> Option<&[u8]> {
+        self.blocks
+            .get(hash)
+            .map(|block| block.block_bytes.as_slice())
+    }
+
+    fn insert(&mut self, hash: [u8; 32], block: LowWorkBlock) {
+        if self.blocks.insert(hash, block).is_some() {
+            return;
+        }
+        self.order.push_back(hash);
+        while self.order.len() > LOW_WORK_BLOCK_CACHE_LIMIT {
+            if let Some(oldest) = self.order.pop_front() {
+                self.blocks.remove(&oldest);
+            }
+        }
+    }
+
+    pub(crate) fn remove(&mut self, hash: &[u8; 32]) {
+        if self.blocks.remove(hash).is_some() {
+            self.order.retain(|queued| queued != hash);
+        }
+    }
+}
+
+impl SyncEngine {
+    /// Whether `hash` is in the blockstore or held as a low-work side block.
+    pub fn has_block_or_low_work(&self, hash: [u8; 32]) -> Result<bool, String> {
+        Ok(self.low_work_blocks.contains(&hash) || self.has_block(hash)?)
+    }
+
+    /// Side blocks currently held in memory by the minimum-chainwork gate.
+    pub fn low_work_block_count(&self) -> usize {
+        self.low_work_blocks.len()
+    }
+
+    /// Keeps the side block `bh` in memory when its branch work is below
+    /// both `minimum_chainwork` and the tip's work. Returns false, leaving
+    /// the block to the normal side-branch path, when the gate is off, the
+    /// branch has enough work, or the parent is unknown.
+    pub(crate) fn defer_low_work_side_block(
+        &mut self,
+        parsed: &ParsedBlock,
+        bh: [u8; 32],
+        block_bytes: &[u8],
+    ) -> Result<bool, String> {
+        if self.cfg.minimum_chainwork == BigUint::ZERO || !self.chain_state.has_tip {
+            return Ok(false);
+        }
+        let Some(block_store) = self.block_store.as_ref() else {
+            return Ok(false);
+        };
+        let prev = parsed.header.prev_block_hash;
+        let parent_work = match self.low_work_blocks.blocks.get(&prev) {
+            Some(parent) => parent.chain_work.clone(),
+            None if block_store.has_block(prev) => block_store.chain_work(prev)?,
+            None => return Ok(false),
+        };
+        let chain_work =
+            parent_work + work_from_target(parsed.header.target).map_err(|e| e.to_string())?;
+        if chain_work >= self.cfg.minimum_chainwork
+            || chain_work >= block_store.chain_work(self.chain_state.tip_hash)?
+        {
+            return Ok(false);
+        }
+        if matches!(self.cfg.expected_target, Some(expected) if parsed.header.target != expected) {
+            return Err("target mismatch".to_string());
+        }
+        check_block_structure(bh, block_bytes.to_vec())?;
+        self.low_work_blocks.insert(
+            bh,
+            LowWorkBlock {
+                block_bytes: block_bytes.to_vec(),
+                chain_work,
+            },
+        );
+        Ok(true)
+    }
+
+    /// Drops held-back blocks once their branch has been persisted.
+    pub(crate) fn forget_low_work_blocks(&mut self, hashes: &[[u8; 32]]) {
+        for hash in hashes {
+            self.low_work_blocks.remove(hash);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::blockstore::{block_store_path, BlockStore};
+    use crate::chainstate::{chain_state_path, ChainState};
+    use crate::genesis::devnet_genesis_chain_id;
+    use crate::io_utils::unique_temp_path;
+    use crate::miner::{Miner, MinerConfig};
+    use crate::sync::default_sync_config;
+    use crate::test_helpers::{coinbase_only_block_with_gen, genesis_info};
+    use rubin_consensus::constants::POW_LIMIT;
+    use rubin_consensus::{block_hash, parse_block_bytes};
+    use std::path::Path;
+
+    fn engine_with_store(dir: &Path, expected_target: Option<[u8; 32]>) -> SyncEngine {
+        let store = BlockStore::open(block_store_path(dir)).expect("open blockstore");
+        let cfg = default_sync_config(
+            expected_target,
+            devnet_genesis_chain_id(),
+            Some(chain_state_path(dir)),
+        );
+        SyncEngine::new(ChainState::new(), Some(store), cfg).expect("new sync")
+    }
+
+    fn hash_of(block: &[u8]) -> [u8; 32] {
+        let parsed = parse_block_bytes(block).expect("parse");
+        block_hash(&parsed.header_bytes).expect("hash")
+    }
+
+    /// Entries across the blockstore's per-block directories.
+    fn block_index_len(dir: &Path) -> usize {
+        ["blocks", "headers", "status"]
+            .iter()
+            .map(|sub| {
+                std::fs::read_dir(block_store_path(dir).join(sub))
+                    .expect("read blockstore dir")
+                    .count()
+            })
+            .sum()
+    }
+
+    #[test]
+    fn low_work_spam_branch_does_not_grow_the_block_index() {
+        let dir = unique_temp_path("rubin-low-work-spam");
+        let mut engine = engine_with_store(&dir, None);
+        // Two blocks at 2^16 times the minimum difficulty outweigh a 10k
+        // block branch at the minimum.
+        let mut hard_target = POW_LIMIT;
+        hard_target[..2].copy_from_slice(&[0, 0]);
+        let miner_cfg = MinerConfig {
+            timestamp_source: || 1,
+            target: hard_target,
+            ..MinerConfig::default()
+        };
+        Miner::new(&mut engine, None, miner_cfg)
+            .expect("miner")
+            .mine_n(2, &[])
+            .expect("mine");
+        engine.cfg.minimum_chainwork = BigUint::from(1u64) << 40;
+        let genesis_hash = engine
+            .block_store
+            .as_ref()
+            .expect("store")
+            .canonical_hash(0)
+            .expect("index")
+            .expect("genesis");
+        let index_before = block_index_len(&dir);
+
+        let mut prev = genesis_hash;
+        for height in 1..=10_000u64 {
+            let block = coinbase_only_block_with_gen(height, 0, prev, 100 + height);
+            let err = engine
+                .apply_block_with_reorg(&block, None)
+                .expect_err("spam block held back");
+            assert!(is_low_work_side_block_err(&err), "height {height}: {err}");
+            prev = hash_of(&block);
+        }
+
+        assert_eq!(block_index_len(&dir), index_before);
+        assert_eq!(engine.low_work_block_count(), LOW_WORK_BLOCK_CACHE_LIMIT);
+        assert!(engine.has_block_or_low_work(prev).expect("lookup"));
+        assert!(!engine.has_block(prev).expect("lookup"));
+        assert_eq!(engine.chain_state.height, 1);
+
+        std::fs::remove_dir_all(&dir).expect("cleanup");
+    }
+
+    #[test]
+    fn competitive_fork_is_stored_with_its_held_back_ancestors() {
+        let dir = unique_temp_path("rubin-low-work-fork");
+        let mut engine = engine_with_store(&dir, Some(POW_LIMIT));
+        let (genesis, genesis_hash, gen_ts) = genesis_info();
+        engine
+            .apply_block_with_reorg(&genesis, None)
+            .expect("genesis");
+        let subsidy1 = rubin_consensus::subsidy::block_subsidy(1, 0);
+        let subsidy2 = rubin_consensus::subsidy::block_subsidy(2, u128::from(subsidy1));
+        let generated = [0, subsidy1, subsidy1 + subsidy2];
+        let mut prev = genesis_hash;
+        for (i, already_generated) in generated.iter().enumerate() {
+            let height = i as u64 + 1;
+            let block =
+                coinbase_only_block_with_gen(height, *already_generated, prev, gen_ts + height);
+            engine
+                .apply_block_with_reorg(&block, None)
+                .expect("canonical");
+            prev = hash_of(&block);
+        }
+        let tip_work = engine
+            .block_store
+            .as_ref()
+            .expect("store")
+            .chain_work(engine.chain_state.tip_hash)
+            .expect("tip work");
+        engine.cfg.minimum_chainwork = tip_work;
+
+        // Fork at genesis: the first two blocks stay below the tip's work,
+        // the third matches it and is no longer spam.
+        let mut fork = Vec::new();
+        let mut prev = genesis_hash;
+        for (i, already_generated) in generated.iter().enumerate() {
+            let height = i as u64 + 1;
+            let block = coinbase_only_block_with_gen(
+                height,
+                *already_generated,
+                prev,
+                gen_ts + 10 + height,
+            );
+            let hash = hash_of(&block);
+            let result = engine.apply_block_with_reorg(&block, None);
+            if height < 3 {
+                assert_eq!(result.expect_err("held back"), LOW_WORK_SIDE_BLOCK_ERR);
+                assert!(!engine.has_block(hash).expect("lookup"));
+            } else {
+                result.expect("competitive fork accepted");
+            }
+            fork.push(hash);
+            prev = hash;
+        }
+
+        for hash in fork {
+            assert!(engine.has_block(hash).expect("lookup"));
+        }
+        assert_eq!(engine.low_work_block_count(), 0);
+
+        std::fs::remove_dir_all(&dir).expect("cleanup");
+    }
+}
@@ -0,0 +1,376 @@
+//! Block download against a single peer, without the wire.
+//!
+//! `BlockSyncPeer` is the part of a remote node that initial sync needs: the
+//! `getblocks` answer (canonical hashes after the best locator match) and the
+//! `getdata` answer (raw block bytes by hash). `SyncEngine::import_blocks`
+//! drives a local engine from its own locator to the peer's tip through that
+//! boundary, so the headers -> blocks -> chainstate path can be exercised
+//! end to end without sockets. `SyncEngine` itself implements the trait as
+//! the serving side.
+//!
+//! Each downloaded batch is pre-verified (`check_block_structure`) by a
+//! bounded worker pool on a background thread while the calling thread
+//! connects earlier blocks in order, so the serial section only runs the
+//! contextual checks, the UTXO update and the store write.
+
+use std::sync::mpsc::{self, TryRecvError};
+use std::thread;
+
+use rubin_consensus::{run_worker_pool, WorkerCancellationToken, WorkerResult};
+
+use crate::block_preverify::{check_block_structure, PreparedBlock};
+use crate::sync::SyncEngine;
+
+/// Locator depth sent with each `getblocks`, as in the P2P runtime.
+pub const IMPORT_LOCATOR_LIMIT: usize = 32;
+/// Hashes requested per `getblocks` round, as in the P2P runtime.
+pub const IMPORT_BATCH_LIMIT: u64 = 128;
+/// Pre-verified blocks allowed to wait for the serial section; bounds the
+/// memory held ahead of the tip.
+pub const IMPORT_PREVERIFY_AHEAD_LIMIT: usize = 32;
+
+pub trait BlockSyncPeer {
+    /// Canonical block hashes following the first locator the peer knows,
+    /// oldest first and at most `limit` of them. Unknown locators start
+    /// from genesis.
+    fn hashes_after_locators(
+        &mut self,
+        locator_hashes: &[[u8; 32]],
+        limit: u64,
+    ) -> Result<Vec<[u8; 32]>, String>;
+
+    /// Raw bytes of the block with hash `block_hash_bytes`.
+    fn block_bytes(&mut self, block_hash_bytes: [u8; 32]) -> Result<Vec<u8>, String>;
+
+    /// Called once when a block this peer served for `block_hash_bytes`
+    /// fails pre-verification; the import stops right after. Peers with a
+    /// misbehavior score charge it here.
+    fn block_rejected(&mut self, _block_hash_bytes: [u8; 32], _reason: &str) {}
+}
+
+impl BlockSyncPeer for SyncEngine {
+    fn hashes_after_locators(
+        &mut self,
+        locator_hashes: &[[u8; 32]],
+        limit: u64,
+    ) -> Result<Vec<[u8; 32]>, String> {
+        SyncEngine::hashes_after_locators(self, locator_hashes, [0u8; 32], limit)
+    }
+
+    fn block_bytes(&mut self, block_hash_bytes: [u8; 32]) -> Result<Vec<u8>, String> {
+        self.get_block_by_hash(block_hash_bytes)
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct BlockImportSummary {
+    /// `getblocks` rounds sent, including the final one that found nothing new.
+    pub rounds: u64,
+    /// Blocks fetched from the peer and connected.
+    pub blocks_applied: u64,
+    /// Blocks whose pre-verification had already finished when the serial
+    /// section reached them, i.e. the work that overlapped with connecting.
+    pub blocks_preverified_ahead: u64,
+    /// Tip after the import.
+    pub tip: Option<(u64, [u8; 32])>,
+}
+
+type PreverifyResult = Result<PreparedBlock, ([u8; 32], String)>;
+
+impl SyncEngine {
+    /// Downloads and connects blocks from `peer` until it has nothing past
+    /// the local tip. Blocks already in the local store are never fetched,
+    /// so an engine reopened from its datadir resumes where it stopped.
+    pub fn import_blocks(
+        &mut self,
+        peer: &mut dyn BlockSyncPeer,
+    ) -> Result<BlockImportSummary, String> {
+        self.import_blocks_with_preverify_workers(peer, 0)
+    }
+
+    /// `import_blocks` with `workers` pre-verification threads; 0 uses the
+    /// available parallelism.
+    pub fn import_blocks_with_preverify_workers(
+        &mut self,
+        peer: &mut dyn BlockSyncPeer,
+        workers: usize,
+    ) -> Result<BlockImportSummary, String> {
+        if self.block_store.is_none() {
+            return Err("sync engine missing blockstore".to_string());
+        }
+        let workers = preverify_worker_count(workers);
+        let mut summary = BlockImportSummary::default();
+        loop {
+            summary.rounds += 1;
+            let locators = self.locator_hashes(IMPORT_LOCATOR_LIMIT)?;
+            let hashes = peer.hashes_after_locators(&locators, IMPORT_BATCH_LIMIT)?;
+            let mut downloaded = Vec::with_capacity(hashes.len());
+            for hash in hashes {
+                if self.has_block(hash)? {
+                    continue;
+                }
+                downloaded.push((hash, peer.block_bytes(hash)?));
+            }
+            if downloaded.is_empty() {
+                break;
+            }
+            self.connect_preverified(downloaded, workers, peer, &mut summary)?;
+        }
+        summary.tip = self.tip()?;
+        Ok(summary)
+    }
+
+    /// Connects `blocks` in order while a background thread pre-verifies
+    /// them at most `IMPORT_PREVERIFY_AHEAD_LIMIT` blocks ahead.
+    fn connect_preverified(
+        &mut self,
+        blocks: Vec<([u8; 32], Vec<u8>)>,
+        workers: usize,
+        peer: &mut dyn BlockSyncPeer,
+        summary: &mut BlockImportSummary,
+    ) -> Result<(), String> {
+        let token = WorkerCancellationToken::new();
+        thread::scope(|scope| {
+            // The receiver lives in this closure, so returning early drops it
+            // and unblocks the producer before the scope joins it.
+            let (tx, rx) = mpsc::sync_channel(IMPORT_PREVERIFY_AHEAD_LIMIT);
+            let producer_token = token.clone();
+            scope.spawn(move || preverify_in_order(blocks, workers, &producer_token, tx));
+            let result = loop {
+                let next = match rx.try_recv() {
+                    Ok(next) => {
+                        summary.blocks_preverified_ahead += 1;
+                        next
+                    }
+                    Err(TryRecvError::Empty) => match rx.recv() {
+                        Ok(next) => next,
+                        Err(_) => break Ok(()),
+                    },
+                    Err(TryRecvError::Disconnected) => break Ok(()),
+                };
+                let prepared = match next {
+                    Ok(prepared) => prepared,
+                    Err((hash, reason)) => {
+                        peer.block_rejected(hash, &reason);
+                        break Err(reason);
+                    }
+                };
+                if let Err(err) = self.apply_prepared_block_with_reorg(&prepared) {
+                    break Err(err);
+                }
+                summary.blocks_applied += 1;
+            };
+            token.cancel();
+            result
+        })
+    }
+}
+
+fn preverify_worker_count(workers: usize) -> usize {
+    if workers > 0 {
+        return workers;
+    }
+    thread::available_parallelism().map_or(1, |n| n.get())
+}
+
+/// Pre-verifies `blocks` in chunks of `workers` and sends the results in
+/// block order, stopping after the first failure or once the receiver is
+/// gone.
+fn preverify_in_order(
+    blocks: Vec<([u8; 32], Vec<u8>)>,
+    workers: usize,
+    token: &WorkerCancellationToken,
+    tx: mpsc::SyncSender<PreverifyResult>,
+) {
+    let mut blocks = blocks.into_iter();
+    loop {
+        let chunk: Vec<_> = blocks.by_ref().take(workers).collect();
+        if chunk.is_empty() || token.is_cancelled() {
+            return;
+        }
+        let hashes: Vec<[u8; 32]> = chunk.iter().map(|(hash, _)| *hash).collect();
+        let results =
+            match run_worker_pool(token, workers, chunk.len(), chunk, |_, (hash, bytes)| {
+                check_block_structure(hash, bytes)
+            }) {
+                Ok(results) => results,
+                Err(err) => {
+                    let _ = tx.send(Err((hashes[0], err.to_string())));
+                    return;
+                }
+            };
+        for (hash, result) in hashes.into_iter().zip(results) {
+            let next = match result {
+                WorkerResult {
+                    value: Some(prepared),
+                    ..
+                } => Ok(prepared),
+                WorkerResult { error, .. } => Err((
+                    hash,
+                    error.map_or_else(|| "missing result".to_string(), |e| e.to_string()),
+                )),
+            };
+            let failed = next.is_err();
+            if tx.send(next).is_err() || failed {
+                return;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::BlockSyncPeer;
+    use crate::blockstore::{block_store_path, BlockStore};
+    use crate::chainstate::{chain_state_path, ChainState};
+    use crate::genesis::devnet_genesis_chain_id;
+    use crate::miner::{Miner, MinerConfig};
+    use crate::sync::{default_sync_config, SyncEngine};
+    use crate::test_helpers::mutate_block_body_keeping_header;
+
+    fn store_engine(dir: &std::path::Path) -> SyncEngine {
+        let block_store = BlockStore::open(block_store_path(dir)).expect("blockstore");
+        SyncEngine::new(
+            ChainState::new(),
+            Some(block_store),
+            default_sync_config(None, devnet_genesis_chain_id(), Some(chain_state_path(dir))),
+        )
+        .expect("sync")
+    }
+
+    #[test]
+    fn sync_engine_serves_another_engine_to_its_tip() {
+        let dir = std::env::temp_dir().join(format!("rubin-sync-peer-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let mut source = store_engine(&dir.join("source"));
+        let cfg = MinerConfig {
+            timestamp_source: || 1,
+            ..MinerConfig::default()
+        };
+        Miner::new(&mut source, None, cfg)
+            .expect("miner")
+            .mine_n(4, &[])
+            .expect("mine");
+        let want_tip = source.tip().expect("tip");
+
+        let mut local = store_engine(&dir.join("local"));
+        let peer: &mut dyn BlockSyncPeer = &mut source;
+        let summary = local.import_blocks(peer).expect("import");
+        assert_eq!(summary.blocks_applied, 4);
+        assert_eq!(summary.rounds, 2);
+        assert_eq!(summary.tip, want_tip);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    /// Serves `inner`'s chain but mutates the body of `corrupt`, keeping its
+    /// header so the block still answers to the requested hash.
+    struct CorruptingPeer {
+        inner: SyncEngine,
+        corrupt: [u8; 32],
+        rejected: Vec<([u8; 32], String)>,
+    }
+
+    impl BlockSyncPeer for CorruptingPeer {
+        fn hashes_after_locators(
+            &mut self,
+            locator_hashes: &[[u8; 32]],
+            limit: u64,
+        ) -> Result<Vec<[u8; 32]>, String> {
+            self.inner
+                .hashes_after_locators(locator_hashes, [0u8; 32], limit)
+        }
+
+        fn block_bytes(&mut self, block_hash_bytes: [u8; 32]) -> Result<Vec<u8>, String> {
+            let bytes = self.inner.get_block_by_hash(block_hash_bytes)?;
+            if block_hash_bytes != self.corrupt {
+                return Ok(bytes);
+            }
+            Ok(mutate_block_body_keeping_header(&bytes))
+        }
+
+        fn block_rejected(&mut self, block_hash_bytes: [u8; 32], reason: &str) {
+            self.rejected.push((block_hash_bytes, reason.to_string()));
+        }
+    }
+
+    #[test]
+    fn import_stops_below_a_block_that_fails_preverification() {
+        let dir =
+            std::env::temp_dir().join(format!("rubin-sync-peer-preverify-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let mut source = store_engine(&dir.join("source"));
+        let cfg = MinerConfig {
+            timestamp_source: || 1,
+            ..MinerConfig::default()
+        };
+        Miner::new(&mut source, None, cfg)
+            .expect("miner")
+            .mine_n(8, &[])
+            .expect("mine");
+        let bad_height = 5;
+        let corrupt = source
+            .block_store
+            .as_ref()
+            .expect("store")
+            .canonical_hash(bad_height)
+            .expect("canonical")
+            .expect("hash");
+        let want_tip = source
+            .block_store
+            .as_ref()
+            .expect("store")
+            .canonical_hash(bad_height - 1)
+            .expect("canonical")
+            .expect("hash");
+
+        for workers in [1, 3] {
+            let mut peer = CorruptingPeer {
+                inner: store_engine(&dir.join("source")),
+                corrupt,
+                rejected: Vec::new(),
+            };
+            let local_dir = dir.join(format!("local-{workers}"));
+            let mut local = store_engine(&local_dir);
+            let err = local
+                .import_blocks_with_preverify_workers(&mut peer, workers)
+                .unwrap_err();
+            assert!(err.contains(&hex::encode(corrupt)), "{err}");
+            assert!(err.contains("BLOCK_ERR_MERKLE_INVALID"), "{err}");
+            assert_eq!(peer.rejected.len(), 1);
+            assert_eq!(peer.rejected[0].0, corrupt);
+            assert_eq!(local.tip().expect("tip"), Some((bad_height - 1, want_tip)));
+            // The header is not blamed: an honest peer may still serve it.
+            assert!(!local.has_block(corrupt).expect("has_block"));
+            assert_eq!(
+                local
+                    .block_store
+                    .as_ref()
+                    .expect("store")
+                    .get_block_status(corrupt)
+                    .expect("status"),
+                None
+            );
+        }
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn import_without_blockstore_is_refused() {
+        let mut source = SyncEngine::new(
+            ChainState::new(),
+            None,
+            default_sync_config(None, devnet_genesis_chain_id(), None),
+        )
+        .expect("sync");
+        let mut local = SyncEngine::new(
+            ChainState::new(),
+            None,
+            default_sync_config(None, devnet_genesis_chain_id(), None),
+        )
+        .expect("sync");
+        let err = local.import_blocks(&mut source).unwrap_err();
+        assert_eq!(err, "sync engine missing blockstore");
+    }
+}
@@ -4,10 +4,13 @@ use rubin_consensus::{
     BLOCK_HEADER_BYTES,
 };
 use std::ops::Deref;
+use std::time::{SystemTime, UNIX_EPOCH};
 
-use crate::blockstore::BlockStore;
-use crate::chainstate::{CanonicalAppliedBlock, ChainStateConnectSummary};
+use crate::block_preverify::PreparedBlock;
+use crate::blockstore::{BlockInvalidReason, BlockStatus, BlockStore};
+use crate::chainstate::{BlockStats, CanonicalAppliedBlock, ChainStateConnectSummary};
 use crate::sync::SyncEngine;
+use crate::sync_low_work::LOW_WORK_SIDE_BLOCK_ERR;
 use crate::txpool::{TxPool, TxPoolAdmitError, TxPoolAdmitErrorKind, TxSource};
 
 pub(crate) const PARENT_BLOCK_NOT_FOUND_ERR: &str = "parent block not found";
@@ -26,6 +29,25 @@ fn advance_prev_timestamps(prev: Option<&[u64]>, new_ts: u64) -> Vec<u64> {
     out
 }
 
+/// Records a consensus failure of an imported block as its status, and
+/// clears a stale invalid mark once the block imports cleanly (some checks,
+/// like the future-timestamp bound, depend on when the block arrives).
+fn record_import_outcome<T>(
+    block_store: &BlockStore,
+    block_hash_bytes: [u8; 32],
+    result: &Result<T, String>,
+) -> Result<(), String> {
+    match result {
+        Err(err) => match BlockInvalidReason::from_error(err) {
+            Some(reason) => {
+                block_store.put_block_status(block_hash_bytes, BlockStatus::Invalid { reason })
+            }
+            None => Ok(()),
+        },
+        Ok(_) => block_store.reconsider_block(block_hash_bytes).map(|_| ()),
+    }
+}
+
 /// A block on a candidate side-chain branch, collected while walking
 /// parent pointers back to a common ancestor on the canonical chain.
 struct ReorgBranchBlock {
@@ -118,6 +140,11 @@ impl TxPoolCleanupPlan {
             && self.requeue_block_hashes.is_empty()
     }
 
+    /// Txids of the transactions the connected block(s) confirmed.
+    pub fn confirmed_txids(&self) -> &[[u8; 32]] {
+        &self.confirmed_txids
+    }
+
     pub fn apply(
         &self,
         pool: &mut TxPool,
@@ -264,6 +291,17 @@ impl TxPoolCleanupPlan {
     }
 }
 
+/// Result of `SyncEngine::preview_reorg`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ReorgPreview {
+    pub common_ancestor_height: u64,
+    pub candidate_height: u64,
+    /// Canonical blocks that would be disconnected by the switch.
+    pub fork_depth: u64,
+    /// Whether fork choice prefers the candidate over the current tip.
+    pub preferred: bool,
+}
+
 #[derive(Clone, Debug)]
 pub struct ApplyBlockWithReorgOutcome {
     pub summary: ChainStateConnectSummary,
@@ -282,7 +320,9 @@ impl SyncEngine {
     /// Apply a block that may extend the canonical chain directly or trigger
     /// a reorg if it builds on a better fork-choice branch.
     ///
-    /// Returns the connect summary for the newly applied tip block.
+    /// Returns the connect summary for the newly applied tip block. The
+    /// block's first receive time is recorded in its index entry, and a
+    /// consensus failure is recorded there as `BlockStatus::Invalid`.
     pub fn apply_block_with_reorg(
         &mut self,
         block_bytes: &[u8],
@@ -290,25 +330,79 @@ impl SyncEngine {
     ) -> Result<ApplyBlockWithReorgOutcome, String> {
         let parsed = parse_block_bytes(block_bytes).map_err(|e| e.to_string())?;
         let bh = block_hash(&parsed.header_bytes).map_err(|e| e.to_string())?;
+        self.apply_recorded_block_with_reorg(&parsed, bh, block_bytes, prev_timestamps)
+    }
 
+    /// `apply_block_with_reorg` for a block that already passed
+    /// `check_block_structure`, reusing its parse and hash.
+    pub fn apply_prepared_block_with_reorg(
+        &mut self,
+        prepared: &PreparedBlock,
+    ) -> Result<ApplyBlockWithReorgOutcome, String> {
+        self.apply_recorded_block_with_reorg(
+            &prepared.parsed,
+            prepared.block_hash,
+            &prepared.block_bytes,
+            None,
+        )
+    }
+
+    fn apply_recorded_block_with_reorg(
+        &mut self,
+        parsed: &ParsedBlock,
+        bh: [u8; 32],
+        block_bytes: &[u8],
+        prev_timestamps: Option<&[u64]>,
+    ) -> Result<ApplyBlockWithReorgOutcome, String> {
+        // Held-back side blocks leave no trace in the block index.
+        if self.defer_low_work_side_block(parsed, bh, block_bytes)? {
+            return Err(LOW_WORK_SIDE_BLOCK_ERR.to_string());
+        }
+        if let Some(block_store) = self.block_store.as_ref() {
+            let now_unix = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |d| d.as_secs());
+            // Audit metadata only: a failed write must not fail the import.
+            if let Err(err) = block_store.note_block_received(bh, now_unix) {
+                eprintln!("sync: {err}");
+            }
+        }
+        let result = self.apply_parsed_block_with_reorg(parsed, bh, block_bytes, prev_timestamps);
+        if let Some(block_store) = self.block_store.as_ref() {
+            if let Err(err) = record_import_outcome(block_store, bh, &result) {
+                eprintln!("sync: {err}");
+            }
+        }
+        result
+    }
+
+    fn apply_parsed_block_with_reorg(
+        &mut self,
+        parsed: &ParsedBlock,
+        bh: [u8; 32],
+        block_bytes: &[u8],
+        prev_timestamps: Option<&[u64]>,
+    ) -> Result<ApplyBlockWithReorgOutcome, String> {
         // Fast path: block extends current tip or is genesis.
-        if let Some(summary) = self.apply_direct_if_possible(block_bytes, prev_timestamps)? {
+        if let Some(summary) =
+            self.apply_direct_if_possible(parsed, block_bytes, prev_timestamps)?
+        {
             return Ok(ApplyBlockWithReorgOutcome {
                 summary,
-                tx_pool_cleanup: TxPoolCleanupPlan::from_validated_block(&parsed, block_bytes)?,
+                tx_pool_cleanup: TxPoolCleanupPlan::from_validated_block(parsed, block_bytes)?,
             });
         }
 
-        let block_store = self
-            .block_store
-            .as_ref()
-            .ok_or("missing blockstore for side-chain block")?;
+        if self.block_store.is_none() {
+            return Err("missing blockstore for side-chain block".to_string());
+        }
 
         // Collect branch from this block back to a common canonical ancestor.
         // The incoming block is added directly from block_bytes (not read from
         // store), so it does not need to be persisted yet.
         let (branch, common_ancestor_hash, common_ancestor_height) =
             self.collect_branch_to_canonical(bh, block_bytes)?;
+        let branch_hashes: Vec<[u8; 32]> = branch.iter().map(|item| item.hash).collect();
 
         // Evaluate fork choice: switch if the candidate has greater work, or
         // equal work with a lexicographically smaller tip hash.
@@ -316,41 +410,189 @@ impl SyncEngine {
             self.should_switch_to_branch(&branch, common_ancestor_hash)?;
 
         if !switch {
-            // Validate the block BEFORE storing — matching Go's ordering so
-            // invalid side-chain blocks never reach the blockstore (B.2 fix,
-            // issue #1168).
-            let candidate = branch.last().ok_or("empty side branch")?;
-            let ts = self.side_branch_prev_timestamps(&branch, common_ancestor_height)?;
-            // Thread the engine's rotation provider so an active CORE_SIMPLICITY
-            // (0x0106) side-branch block is accepted, mirroring Go sync_reorg.go.
-            let (rotation, _registry) = self.suite_context();
-            validate_block_basic_with_context_at_height_and_rotation(
-                &candidate.block_bytes,
-                Some(candidate.prev_hash),
-                self.cfg.expected_target,
-                candidate_height,
-                ts.as_deref(),
-                rotation,
-            )
-            .map_err(|e| e.to_string())?;
-
-            // Validation passed — now persist the side-chain block.
-            if !block_store.has_block(candidate.hash) {
-                block_store.store_block(
-                    candidate.hash,
-                    &candidate.header_bytes,
-                    &candidate.block_bytes,
-                )?;
-            }
+            let candidate_hash =
+                self.store_side_branch_tip(&branch, common_ancestor_height, candidate_height)?;
+            self.forget_low_work_blocks(&branch_hashes);
+            return Ok(ApplyBlockWithReorgOutcome {
+                summary: self.synthetic_side_chain_summary(candidate_height, candidate_hash),
+                tx_pool_cleanup: TxPoolCleanupPlan::default(),
+            });
+        }
 
+        let fork_depth = self.fork_depth(common_ancestor_height);
+        let max_depth = self.cfg.max_auto_reorg_depth;
+        if max_depth > 0 && fork_depth > max_depth {
+            // Too deep to switch unattended: keep the candidate as a validated
+            // side block, flag it for the operator and carry on with the
+            // current tip. The peer is not at fault, so this is not an error.
+            let candidate_hash =
+                self.store_side_branch_tip(&branch, common_ancestor_height, candidate_height)?;
+            self.forget_low_work_blocks(&branch_hashes);
+            self.block_store
+                .as_ref()
+                .ok_or("sync engine has no blockstore")?
+                .put_block_status(candidate_hash, BlockStatus::NeedsManualReorg { fork_depth })?;
+            self.note_refused_reorg();
+            eprintln!(
+                "sync: REORG REFUSED: preferred tip {} forks {fork_depth} blocks below the current tip (max_auto_reorg_depth={max_depth}); run rubin-node --force-reorg {} to switch",
+                hex::encode(candidate_hash),
+                hex::encode(candidate_hash)
+            );
             return Ok(ApplyBlockWithReorgOutcome {
-                summary: self.synthetic_side_chain_summary(candidate_height, candidate.hash),
+                summary: self.synthetic_side_chain_summary(candidate_height, candidate_hash),
                 tx_pool_cleanup: TxPoolCleanupPlan::default(),
             });
         }
 
         // Execute the reorg.
-        self.apply_preferred_branch(branch, common_ancestor_height)
+        let outcome = self.apply_preferred_branch(branch, common_ancestor_height)?;
+        self.forget_low_work_blocks(&branch_hashes);
+        Ok(outcome)
+    }
+
+    /// Dry run of fork choice for a stored block: reports the fork depth
+    /// and whether the block's branch would be preferred, without touching
+    /// the chain state or the blockstore.
+    pub fn preview_reorg(&self, block_hash_bytes: [u8; 32]) -> Result<ReorgPreview, String> {
+        let (branch, common_ancestor_hash, common_ancestor_height) =
+            self.stored_branch_to_canonical(block_hash_bytes)?;
+        let (preferred, candidate_height) =
+            self.should_switch_to_branch(&branch, common_ancestor_hash)?;
+        Ok(ReorgPreview {
+            common_ancestor_height,
+            candidate_height,
+            fork_depth: self.fork_depth(common_ancestor_height),
+            preferred,
+        })
+    }
+
+    /// Operator override for `max_auto_reorg_depth`: switches to the stored
+    /// block's branch regardless of fork depth. Fork choice is re-evaluated
+    /// first, so a candidate that is no longer preferred is rejected.
+    pub fn force_reorg_to(
+        &mut self,
+        block_hash_bytes: [u8; 32],
+    ) -> Result<ApplyBlockWithReorgOutcome, String> {
+        let (branch, common_ancestor_hash, common_ancestor_height) =
+            self.stored_branch_to_canonical(block_hash_bytes)?;
+        let (preferred, _) = self.should_switch_to_branch(&branch, common_ancestor_hash)?;
+        if !preferred {
+            return Err(format!(
+                "fork choice does not prefer block {}",
+                hex::encode(block_hash_bytes)
+            ));
+        }
+        let branch_hashes: Vec<[u8; 32]> = branch.iter().map(|item| item.hash).collect();
+        let outcome = self.apply_preferred_branch(branch, common_ancestor_height)?;
+        // The switch has already been committed; a stale marker is harmless,
+        // so a failed cleanup is only reported.
+        if let Some(block_store) = self.block_store.as_ref() {
+            for hash in branch_hashes {
+                if let Err(err) = block_store.clear_block_status(hash) {
+                    eprintln!("sync: {err}");
+                }
+            }
+        }
+        Ok(outcome)
+    }
+
+    /// Number of canonical blocks a switch to a branch forking at
+    /// `common_ancestor_height` would disconnect.
+    fn fork_depth(&self, common_ancestor_height: u64) -> u64 {
+        self.chain_state
+            .height
+            .saturating_sub(common_ancestor_height)
+    }
+
+    /// Validates the tip of a side branch that is not being switched to and
+    /// persists it, returning its hash. Ancestors held back by the
+    /// minimum-chainwork gate are validated and persisted first.
+    fn store_side_branch_tip(
+        &self,
+        branch: &[ReorgBranchBlock],
+        common_ancestor_height: u64,
+        candidate_height: u64,
+    ) -> Result<[u8; 32], String> {
+        let block_store = self
+            .block_store
+            .as_ref()
+            .ok_or("missing blockstore for side-chain block")?;
+        for (i, item) in branch
+            .iter()
+            .enumerate()
+            .take(branch.len().saturating_sub(1))
+        {
+            if !self.low_work_blocks.contains(&item.hash) || block_store.has_block(item.hash) {
+                continue;
+            }
+            self.validate_side_branch_block(&branch[..=i], common_ancestor_height + 1 + i as u64)?;
+            block_store.store_block(item.hash, &item.header_bytes, &item.block_bytes)?;
+        }
+
+        // Validate the block BEFORE storing — matching Go's ordering so
+        // invalid side-chain blocks never reach the blockstore (B.2 fix,
+        // issue #1168).
+        let candidate = branch.last().ok_or("empty side branch")?;
+        self.validate_side_branch_block(branch, candidate_height)?;
+
+        // Validation passed — now persist the side-chain block.
+        if !block_store.has_block(candidate.hash) {
+            block_store.store_block(
+                candidate.hash,
+                &candidate.header_bytes,
+                &candidate.block_bytes,
+            )?;
+        }
+        Ok(candidate.hash)
+    }
+
+    /// Stateless validation of the last block of `branch` at `height`, with
+    /// the timestamp context of the branch below it.
+    fn validate_side_branch_block(
+        &self,
+        branch: &[ReorgBranchBlock],
+        height: u64,
+    ) -> Result<(), String> {
+        let block = branch.last().ok_or("empty side branch")?;
+        let common_ancestor_height = height
+            .checked_sub(branch.len() as u64)
+            .ok_or("side branch below genesis")?;
+        let ts = self.side_branch_prev_timestamps(branch, common_ancestor_height)?;
+        // Thread the engine's rotation provider so an active CORE_SIMPLICITY
+        // (0x0106) side-branch block is accepted, mirroring Go sync_reorg.go.
+        let (rotation, _registry) = self.suite_context();
+        validate_block_basic_with_context_at_height_and_rotation(
+            &block.block_bytes,
+            Some(block.prev_hash),
+            self.cfg.expected_target,
+            height,
+            ts.as_deref(),
+            rotation,
+        )
+        .map(|_| ())
+        .map_err(|e| e.to_string())
+    }
+
+    /// `collect_branch_to_canonical` for a block already in the blockstore.
+    fn stored_branch_to_canonical(
+        &self,
+        block_hash_bytes: [u8; 32],
+    ) -> Result<(Vec<ReorgBranchBlock>, [u8; 32], u64), String> {
+        let block_store = self
+            .block_store
+            .as_ref()
+            .ok_or("sync engine has no blockstore")?;
+        if block_store
+            .find_canonical_height(block_hash_bytes)?
+            .is_some()
+        {
+            return Err(format!(
+                "block {} is already canonical",
+                hex::encode(block_hash_bytes)
+            ));
+        }
+        let block_bytes = block_store.get_block_by_hash(block_hash_bytes)?;
+        self.collect_branch_to_canonical(block_hash_bytes, &block_bytes)
     }
 
     fn side_branch_prev_timestamps(
@@ -378,11 +620,10 @@ impl SyncEngine {
     /// or is the genesis block.
     fn apply_direct_if_possible(
         &mut self,
+        parsed: &ParsedBlock,
         block_bytes: &[u8],
         prev_timestamps: Option<&[u64]>,
     ) -> Result<Option<ChainStateConnectSummary>, String> {
-        let parsed = parse_block_bytes(block_bytes).map_err(|e| e.to_string())?;
-
         if !self.chain_state.has_tip {
             // Genesis: prev_block_hash must be zero.
             if parsed.header.prev_block_hash != [0u8; 32] {
@@ -529,13 +770,14 @@ impl SyncEngine {
         let mut sliding_ts = self.prev_timestamps_for_height(common_ancestor_height + 1)?;
         let (rotation, registry) = self.suite_context();
         for item in branch {
-            preview_state.connect_block_with_suite_context(
+            preview_state.connect_block_with_chain_params(
                 &item.block_bytes,
                 self.cfg.expected_target,
                 sliding_ts.as_deref(),
                 self.cfg.chain_id,
                 rotation,
                 registry,
+                &self.cfg.chain_params,
             )?;
             sliding_ts = Some(advance_prev_timestamps(
                 sliding_ts.as_deref(),
@@ -579,10 +821,14 @@ impl SyncEngine {
                 return Ok((branch, parent_hash, height));
             }
 
-            // Load the parent block from the side-chain store.
-            let parent_bytes = block_store
-                .get_block_by_hash(parent_hash)
-                .map_err(|_| PARENT_BLOCK_NOT_FOUND_ERR.to_string())?;
+            // Load the parent block from the side-chain store, or from the
+            // blocks held back by the minimum-chainwork gate.
+            let parent_bytes = match self.low_work_blocks.block_bytes(&parent_hash) {
+                Some(bytes) => bytes.to_vec(),
+                None => block_store
+                    .get_block_by_hash(parent_hash)
+                    .map_err(|_| PARENT_BLOCK_NOT_FOUND_ERR.to_string())?,
+            };
             let parent_parsed = parse_block_bytes(&parent_bytes).map_err(|e| e.to_string())?;
 
             branch.push(ReorgBranchBlock {
@@ -613,6 +859,7 @@ impl SyncEngine {
             already_generated: self.chain_state.already_generated,
             already_generated_n1: self.chain_state.already_generated,
             utxo_count: self.chain_state.utxos.len() as u64,
+            stats: BlockStats::default(),
             // Side branch stored but not switched: no block became canonical.
             canonical_applied_blocks: Vec::new(),
         }
@@ -704,8 +951,8 @@ mod tests {
     use std::sync::Arc;
 
     use rubin_consensus::constants::{
-        MAX_FUTURE_DRIFT, ML_DSA_87_PUBKEY_BYTES, ML_DSA_87_SIG_BYTES, POW_LIMIT,
-        SUITE_ID_ML_DSA_87, VERIFY_COST_ML_DSA_87,
+        COINBASE_MATURITY, MAX_FUTURE_DRIFT, ML_DSA_87_PUBKEY_BYTES, ML_DSA_87_SIG_BYTES,
+        POW_LIMIT, SUITE_ID_ML_DSA_87, VERIFY_COST_ML_DSA_87,
     };
     use rubin_consensus::{
         marshal_tx, p2pk_covenant_data_for_pubkey, parse_tx, sign_transaction, Mldsa87Keypair,
@@ -720,8 +967,8 @@ mod tests {
     use crate::io_utils::unique_temp_path;
     use crate::sync::{default_sync_config, SuiteContext, SyncEngine};
     use crate::test_helpers::{
-        block_with_txs, coinbase_only_block, coinbase_only_block_with_gen, genesis_info,
-        height_one_coinbase_only_block, signed_conflicting_p2pk_state_and_txs,
+        block_with_txs, block_with_txs_to, coinbase_only_block, coinbase_only_block_with_gen,
+        genesis_info, height_one_coinbase_only_block, signed_conflicting_p2pk_state_and_txs,
     };
 
     #[test]
@@ -919,6 +1166,55 @@ mod tests {
         std::fs::remove_dir_all(&dir).expect("cleanup");
     }
 
+    #[test]
+    fn apply_block_with_reorg_records_invalid_reason_and_receive_time() {
+        let (mut engine, dir) = engine_with_store("rubin-reorg-invalid-reason");
+        let (genesis, genesis_hash, gen_ts) = genesis_info();
+        engine
+            .apply_block_with_reorg(&genesis, None)
+            .expect("genesis");
+
+        // Claims height 2 in its coinbase while extending genesis.
+        let bad = coinbase_only_block(2, genesis_hash, gen_ts + 1);
+        let bad_hash = block_hash(&bad[..BLOCK_HEADER_BYTES]).expect("hash");
+        let err = engine
+            .apply_block_with_reorg(&bad, None)
+            .expect_err("bad coinbase height");
+        let store = engine.block_store.as_ref().expect("store");
+        let entry = store
+            .get_block_index_entry(bad_hash)
+            .expect("entry")
+            .expect("some");
+        assert!(entry.received_unix > 0);
+        let Some(BlockStatus::Invalid { reason }) = entry.status else {
+            panic!("want invalid status, got {:?}", entry.status);
+        };
+        assert!(reason.code.starts_with("BLOCK_ERR_"), "{reason:?}");
+        assert_eq!(reason.detail, err);
+        assert!(!store.has_block(bad_hash));
+
+        // Reconsidering clears the reason but keeps the receive time.
+        assert!(store.reconsider_block(bad_hash).expect("reconsider"));
+        let after = store
+            .get_block_index_entry(bad_hash)
+            .expect("entry")
+            .expect("some");
+        assert_eq!(after.status, None);
+        assert_eq!(after.received_unix, entry.received_unix);
+        assert!(!store.reconsider_block(bad_hash).expect("reconsider again"));
+
+        // Non-consensus failures say nothing about validity.
+        let orphan = height_one_coinbase_only_block([0x5a; 32], gen_ts + 1);
+        let orphan_hash = block_hash(&orphan[..BLOCK_HEADER_BYTES]).expect("hash");
+        engine
+            .apply_block_with_reorg(&orphan, None)
+            .expect_err("orphan");
+        let store = engine.block_store.as_ref().expect("store");
+        assert_eq!(store.get_block_status(orphan_hash).expect("status"), None);
+
+        std::fs::remove_dir_all(&dir).expect("cleanup");
+    }
+
     #[test]
     fn apply_block_with_reorg_tip_extension_with_pool() {
         let (mut engine, dir) = engine_with_store("rubin-reorg-tip");
@@ -1186,6 +1482,86 @@ mod tests {
         std::fs::remove_dir_all(&dir).expect("cleanup");
     }
 
+    #[test]
+    fn ancestor_cache_follows_tip_across_reorg_and_matches_cold_store() {
+        let (mut engine, dir) = engine_with_store("rubin-reorg-ancestor-cache");
+        let (genesis, genesis_hash, gen_ts) = genesis_info();
+        engine
+            .apply_block_with_reorg(&genesis, None)
+            .expect("genesis");
+        let subsidy1 = rubin_consensus::subsidy::block_subsidy(1, 0);
+        let subsidy2 = rubin_consensus::subsidy::block_subsidy(2, u128::from(subsidy1));
+
+        // Canonical: genesis -> block1 -> block2.
+        let block1 = coinbase_only_block(1, genesis_hash, gen_ts + 1);
+        let block1_hash = block_header_hash(&block1);
+        engine
+            .apply_block_with_reorg(&block1, None)
+            .expect("block1");
+        let block2 = coinbase_only_block_with_gen(2, subsidy1, block1_hash, gen_ts + 2);
+        engine
+            .apply_block_with_reorg(&block2, None)
+            .expect("block2");
+
+        let warm_vs_cold = |engine: &SyncEngine| {
+            assert_eq!(
+                engine.ancestor_cache.tip_hash(),
+                Some(engine.chain_state.tip_hash)
+            );
+            let cold = engine
+                .block_store
+                .as_ref()
+                .unwrap()
+                .validation_context(
+                    engine.chain_state.tip_hash,
+                    engine.chain_state.height + 1,
+                    None,
+                )
+                .expect("store context")
+                .prev_timestamps();
+            assert_eq!(engine.prev_timestamps_for_next_block().unwrap(), cold);
+            cold
+        };
+        warm_vs_cold(&engine);
+
+        // Heavier branch forking at genesis: both canonical blocks go.
+        let store = engine.block_store.as_ref().unwrap();
+        let block1_alt = coinbase_only_block(1, genesis_hash, gen_ts + 5);
+        let block1_alt_hash = block_header_hash(&block1_alt);
+        store
+            .store_block(
+                block1_alt_hash,
+                &block1_alt[..rubin_consensus::BLOCK_HEADER_BYTES],
+                &block1_alt,
+            )
+            .expect("store block1_alt");
+        let block2_alt = coinbase_only_block_with_gen(2, subsidy1, block1_alt_hash, gen_ts + 6);
+        let block2_alt_hash = block_header_hash(&block2_alt);
+        store
+            .store_block(
+                block2_alt_hash,
+                &block2_alt[..rubin_consensus::BLOCK_HEADER_BYTES],
+                &block2_alt,
+            )
+            .expect("store block2_alt");
+        let block3_alt =
+            coinbase_only_block_with_gen(3, subsidy1 + subsidy2, block2_alt_hash, gen_ts + 7);
+        engine
+            .apply_block_with_reorg(&block3_alt, None)
+            .expect("reorg to heavier branch");
+        assert_eq!(engine.reorg_count(), 1);
+        assert_eq!(engine.chain_state.height, 3);
+
+        let warm = warm_vs_cold(&engine);
+        assert_eq!(warm, Some(vec![gen_ts + 7, gen_ts + 6, gen_ts + 5, gen_ts]));
+
+        // A cold window (restart) yields the same answer via the store.
+        engine.ancestor_cache.clear();
+        assert_eq!(engine.prev_timestamps_for_next_block().unwrap(), warm);
+
+        std::fs::remove_dir_all(&dir).expect("cleanup");
+    }
+
     #[test]
     fn apply_block_with_reorg_reports_all_canonical_applied_blocks_in_order_on_reorg() {
         let (mut engine, dir) = engine_with_store("rubin-reorg-canon-reorg");
@@ -1234,6 +1610,363 @@ mod tests {
         std::fs::remove_dir_all(&dir).expect("cleanup");
     }
 
+    #[test]
+    fn apply_block_with_reorg_keeps_block_stats_canonical_across_reorg() {
+        let (mut engine, dir) = engine_with_store("rubin-reorg-block-stats");
+        let (genesis, genesis_hash, gen_ts) = genesis_info();
+        engine
+            .apply_block_with_reorg(&genesis, None)
+            .expect("genesis");
+
+        let subsidy1 = rubin_consensus::subsidy::block_subsidy(1, 0);
+        let subsidy2 = rubin_consensus::subsidy::block_subsidy(2, u128::from(subsidy1));
+        let block1 = coinbase_only_block(1, genesis_hash, gen_ts + 1);
+        let block1_hash = block_header_hash(&block1);
+        let summary1 = engine
+            .apply_block_with_reorg(&block1, None)
+            .expect("block1");
+        let block2 = coinbase_only_block_with_gen(2, subsidy1, block1_hash, gen_ts + 2);
+        let block2_hash = block_header_hash(&block2);
+        engine
+            .apply_block_with_reorg(&block2, None)
+            .expect("block2");
+
+        let store = engine.block_store.as_ref().unwrap();
+        let stats1 = store.get_block_stats(block1_hash).unwrap().expect("stats1");
+        assert_eq!(stats1, summary1.stats);
+        assert_eq!(stats1.tx_count, 1);
+        assert_eq!(stats1.total_fees, 0);
+        assert_eq!(stats1.coinbase_value, subsidy1);
+        assert!(stats1.total_weight > 0);
+        let before = store.cumulative_block_stats(2).expect("cumulative before");
+        assert_eq!(before.tx_count, 3);
+        assert_eq!(
+            before.coinbase_value,
+            store
+                .get_block_stats(genesis_hash)
+                .unwrap()
+                .unwrap()
+                .coinbase_value
+                + subsidy1
+                + subsidy2
+        );
+
+        // Heavier branch replaces block2: block1 -> block2_alt -> block3_alt.
+        let block2_alt = coinbase_only_block_with_gen(2, subsidy1, block1_hash, gen_ts + 3);
+        let block2_alt_hash = block_header_hash(&block2_alt);
+        store
+            .store_block(
+                block2_alt_hash,
+                &block2_alt[..rubin_consensus::BLOCK_HEADER_BYTES],
+                &block2_alt,
+            )
+            .expect("store block2_alt as side");
+        let subsidy3 = rubin_consensus::subsidy::block_subsidy(3, u128::from(subsidy1 + subsidy2));
+        let block3_alt =
+            coinbase_only_block_with_gen(3, subsidy1 + subsidy2, block2_alt_hash, gen_ts + 4);
+        engine
+            .apply_block_with_reorg(&block3_alt, None)
+            .expect("reorg to heavier branch");
+        assert_eq!(engine.reorg_count(), 1);
+
+        // The disconnected block keeps its own stats but drops out of the
+        // cumulative totals, which now follow the new canonical chain.
+        let store = engine.block_store.as_ref().unwrap();
+        assert!(store.get_block_stats(block2_hash).unwrap().is_some());
+        assert_eq!(store.find_canonical_height(block2_hash).unwrap(), None);
+        let after_2 = store.cumulative_block_stats(2).expect("cumulative at 2");
+        assert_eq!(after_2, before);
+        let after_3 = store.cumulative_block_stats(3).expect("cumulative at 3");
+        assert_eq!(after_3.tx_count, 4);
+        assert_eq!(after_3.coinbase_value, before.coinbase_value + subsidy3);
+        assert_eq!(after_3.total_fees, 0);
+
+        std::fs::remove_dir_all(&dir).expect("cleanup");
+    }
+
+    /// Canonical genesis -> block1 -> block2 with `max_auto_reorg_depth = 1`.
+    /// Returns the engine, dir, genesis hash/timestamp and block1/block2 hashes.
+    fn depth_limited_chain(
+        suffix: &str,
+    ) -> (
+        SyncEngine,
+        std::path::PathBuf,
+        [u8; 32],
+        u64,
+        [u8; 32],
+        [u8; 32],
+    ) {
+        let (mut engine, dir) = engine_with_store(suffix);
+        engine.cfg.max_auto_reorg_depth = 1;
+        let (genesis, genesis_hash, gen_ts) = genesis_info();
+        engine
+            .apply_block_with_reorg(&genesis, None)
+            .expect("genesis");
+        let subsidy1 = rubin_consensus::subsidy::block_subsidy(1, 0);
+        let block1 = coinbase_only_block(1, genesis_hash, gen_ts + 1);
+        let block1_hash = block_header_hash(&block1);
+        engine
+            .apply_block_with_reorg(&block1, None)
+            .expect("block1");
+        let block2 = coinbase_only_block_with_gen(2, subsidy1, block1_hash, gen_ts + 2);
+        let block2_hash = block_header_hash(&block2);
+        engine
+            .apply_block_with_reorg(&block2, None)
+            .expect("block2");
+        (engine, dir, genesis_hash, gen_ts, block1_hash, block2_hash)
+    }
+
+    fn store_side_block(engine: &SyncEngine, block: &[u8]) -> [u8; 32] {
+        let hash = block_header_hash(block);
+        engine
+            .block_store
+            .as_ref()
+            .unwrap()
+            .store_block(hash, &block[..rubin_consensus::BLOCK_HEADER_BYTES], block)
+            .expect("store side block");
+        hash
+    }
+
+    #[test]
+    fn apply_block_with_reorg_auto_reorgs_at_max_depth() {
+        let (mut engine, dir, _, gen_ts, block1_hash, _) =
+            depth_limited_chain("rubin-reorg-max-depth-at-limit");
+        let subsidy1 = rubin_consensus::subsidy::block_subsidy(1, 0);
+        let subsidy2 = rubin_consensus::subsidy::block_subsidy(2, u128::from(subsidy1));
+
+        // Fork at block1 disconnects only block2: depth 1 == limit.
+        let block2_alt = coinbase_only_block_with_gen(2, subsidy1, block1_hash, gen_ts + 3);
+        let block2_alt_hash = store_side_block(&engine, &block2_alt);
+        let block3_alt =
+            coinbase_only_block_with_gen(3, subsidy1 + subsidy2, block2_alt_hash, gen_ts + 4);
+        let block3_alt_hash = block_header_hash(&block3_alt);
+        engine
+            .apply_block_with_reorg(&block3_alt, None)
+            .expect("reorg at limit");
+
+        assert_eq!(engine.chain_state.tip_hash, block3_alt_hash);
+        assert_eq!(engine.reorg_count(), 1);
+        assert_eq!(engine.last_reorg_depth(), 1);
+        assert_eq!(engine.refused_reorg_count(), 0);
+        let store = engine.block_store.as_ref().unwrap();
+        assert_eq!(store.get_block_status(block3_alt_hash).unwrap(), None);
+
+        std::fs::remove_dir_all(&dir).expect("cleanup");
+    }
+
+    #[test]
+    fn apply_block_with_reorg_refuses_deeper_than_max_depth_until_forced() {
+        let (mut engine, dir, genesis_hash, gen_ts, _, block2_hash) =
+            depth_limited_chain("rubin-reorg-max-depth-over-limit");
+        let subsidy1 = rubin_consensus::subsidy::block_subsidy(1, 0);
+        let subsidy2 = rubin_consensus::subsidy::block_subsidy(2, u128::from(subsidy1));
+
+        // Fork at genesis disconnects block1 and block2: depth 2 > limit.
+        let block1_alt = coinbase_only_block(1, genesis_hash, gen_ts + 5);
+        let block1_alt_hash = store_side_block(&engine, &block1_alt);
+        let block2_alt = coinbase_only_block_with_gen(2, subsidy1, block1_alt_hash, gen_ts + 6);
+        let block2_alt_hash = store_side_block(&engine, &block2_alt);
+        let block3_alt =
+            coinbase_only_block_with_gen(3, subsidy1 + subsidy2, block2_alt_hash, gen_ts + 7);
+        let block3_alt_hash = block_header_hash(&block3_alt);
+        let outcome = engine
+            .apply_block_with_reorg(&block3_alt, None)
+            .expect("refused reorg is not an error");
+
+        assert_eq!(outcome.block_hash, block3_alt_hash);
+        assert!(outcome.canonical_applied_blocks.is_empty());
+        assert_eq!(engine.chain_state.tip_hash, block2_hash);
+        assert_eq!(engine.reorg_count(), 0);
+        assert_eq!(engine.refused_reorg_count(), 1);
+        let store = engine.block_store.as_ref().unwrap();
+        assert!(store.has_block(block3_alt_hash));
+        assert_eq!(
+            store.get_block_status(block3_alt_hash).unwrap(),
+            Some(BlockStatus::NeedsManualReorg { fork_depth: 2 })
+        );
+
+        let preview = engine.preview_reorg(block3_alt_hash).expect("preview");
+        assert_eq!(
+            preview,
+            ReorgPreview {
+                common_ancestor_height: 0,
+                candidate_height: 3,
+                fork_depth: 2,
+                preferred: true,
+            }
+        );
+        assert_eq!(engine.chain_state.tip_hash, block2_hash);
+
+        let err = engine.force_reorg_to(block2_hash).unwrap_err();
+        assert!(err.contains("already canonical"), "{err}");
+
+        let outcome = engine
+            .force_reorg_to(block3_alt_hash)
+            .expect("forced reorg");
+        assert_eq!(outcome.block_hash, block3_alt_hash);
+        assert_eq!(outcome.canonical_applied_blocks.len(), 3);
+        assert_eq!(engine.chain_state.tip_hash, block3_alt_hash);
+        assert_eq!(engine.reorg_count(), 1);
+        assert_eq!(engine.last_reorg_depth(), 2);
+        let store = engine.block_store.as_ref().unwrap();
+        assert_eq!(store.get_block_status(block3_alt_hash).unwrap(), None);
+
+        std::fs::remove_dir_all(&dir).expect("cleanup");
+    }
+
+    #[test]
+    fn force_reorg_to_rejects_branch_fork_choice_does_not_prefer() {
+        let (mut engine, dir, genesis_hash, gen_ts, _, block2_hash) =
+            depth_limited_chain("rubin-reorg-force-not-preferred");
+        let block1_alt = coinbase_only_block(1, genesis_hash, gen_ts + 5);
+        let block1_alt_hash = store_side_block(&engine, &block1_alt);
+
+        assert!(
+            !engine
+                .preview_reorg(block1_alt_hash)
+                .expect("preview")
+                .preferred
+        );
+        let err = engine.force_reorg_to(block1_alt_hash).unwrap_err();
+        assert!(err.contains("fork choice does not prefer"), "{err}");
+        assert_eq!(engine.chain_state.tip_hash, block2_hash);
+        assert_eq!(engine.reorg_count(), 0);
+
+        std::fs::remove_dir_all(&dir).expect("cleanup");
+    }
+
+    /// Coinbase-only block on the current tip at `tip_height + 1`.
+    fn next_coinbase_block(engine: &SyncEngine, gen_ts: u64) -> Vec<u8> {
+        let height = engine.chain_state.height + 1;
+        coinbase_only_block_with_gen(
+            height,
+            engine.chain_state.already_generated,
+            engine.chain_state.tip_hash,
+            gen_ts + height,
+        )
+    }
+
+    #[test]
+    fn apply_block_with_reorg_recomputes_coinbase_maturity_on_new_branch() {
+        let keypair = Mldsa87Keypair::generate().expect("OpenSSL signer unavailable");
+        let address = p2pk_covenant_data_for_pubkey(&keypair.pubkey_bytes());
+        let (mut engine, dir) = engine_with_store("rubin-reorg-coinbase-maturity");
+        let (genesis, genesis_hash, gen_ts) = genesis_info();
+        engine
+            .apply_block_with_reorg(&genesis, None)
+            .expect("genesis");
+
+        // Branch A funds the key from the height-1 coinbase.
+        let a1 = block_with_txs_to(1, 0, genesis_hash, gen_ts + 1, &address, &[]);
+        engine.apply_block_with_reorg(&a1, None).expect("a1");
+        let a1_funding = Outpoint {
+            txid: parse_block_bytes(&a1).expect("parse a1").txids[0],
+            vout: 0,
+        };
+        let entry = engine
+            .chain_state
+            .utxos
+            .get(&a1_funding)
+            .expect("a1 funding");
+        assert!(entry.created_by_coinbase);
+        assert_eq!(entry.creation_height, 1);
+
+        // Branch B pays the same key one block later, at height 2.
+        let b1 = coinbase_only_block(1, genesis_hash, gen_ts + 2);
+        let b1_hash = store_side_block(&engine, &b1);
+        let subsidy1 = rubin_consensus::subsidy::block_subsidy(1, 0);
+        let b2 = block_with_txs_to(2, subsidy1, b1_hash, gen_ts + 3, &address, &[]);
+        engine
+            .apply_block_with_reorg(&b2, None)
+            .expect("reorg to b2");
+        assert_eq!(engine.reorg_count(), 1);
+        assert!(!engine.chain_state.utxos.contains_key(&a1_funding));
+        let funding = Outpoint {
+            txid: parse_block_bytes(&b2).expect("parse b2").txids[0],
+            vout: 0,
+        };
+        let entry = engine
+            .chain_state
+            .utxos
+            .get(&funding)
+            .expect("b2 funding")
+            .clone();
+        assert!(entry.created_by_coinbase);
+        assert_eq!(entry.creation_height, 2);
+
+        let mut spend = Tx {
+            version: rubin_consensus::constants::TX_WIRE_VERSION,
+            tx_kind: 0x00,
+            tx_nonce: 1,
+            inputs: vec![TxInput {
+                prev_txid: funding.txid,
+                prev_vout: funding.vout,
+                script_sig: Vec::new(),
+                sequence: 0,
+            }],
+            outputs: vec![TxOutput {
+                value: entry.value,
+                covenant_type: rubin_consensus::constants::COV_TYPE_P2PK,
+                covenant_data: p2pk_covenant_data_for_pubkey(
+                    &[0x07; ML_DSA_87_PUBKEY_BYTES as usize],
+                ),
+            }],
+            locktime: 0,
+            da_commit_core: None,
+            da_chunk_core: None,
+            witness: Vec::new(),
+            da_payload: Vec::new(),
+        };
+        sign_transaction(
+            &mut spend,
+            &engine.chain_state.utxos,
+            engine.cfg.chain_id,
+            &keypair,
+        )
+        .expect("sign spend");
+        let spend = marshal_tx(&spend).expect("marshal spend");
+
+        while engine.chain_state.height < COINBASE_MATURITY {
+            let block = next_coinbase_block(&engine, gen_ts);
+            engine
+                .apply_block_with_reorg(&block, None)
+                .expect("extend b");
+        }
+
+        // Height 1 + COINBASE_MATURITY would be mature for branch A's
+        // coinbase, but is one block short for branch B's.
+        let spend_height = 1 + COINBASE_MATURITY;
+        let early = block_with_txs(
+            spend_height,
+            engine.chain_state.already_generated,
+            engine.chain_state.tip_hash,
+            gen_ts + spend_height,
+            std::slice::from_ref(&spend),
+        );
+        let err = engine
+            .apply_block_with_reorg(&early, None)
+            .expect_err("immature spend");
+        assert!(err.contains("TX_ERR_COINBASE_IMMATURE"), "{err}");
+        assert_eq!(engine.chain_state.height, COINBASE_MATURITY);
+
+        let block = next_coinbase_block(&engine, gen_ts);
+        engine.apply_block_with_reorg(&block, None).expect("pad");
+        let spend_height = 2 + COINBASE_MATURITY;
+        let mature = block_with_txs(
+            spend_height,
+            engine.chain_state.already_generated,
+            engine.chain_state.tip_hash,
+            gen_ts + spend_height,
+            std::slice::from_ref(&spend),
+        );
+        engine
+            .apply_block_with_reorg(&mature, None)
+            .expect("mature spend");
+        assert!(!engine.chain_state.utxos.contains_key(&funding));
+
+        std::fs::remove_dir_all(&dir).expect("cleanup");
+    }
+
     #[test]
     fn apply_block_with_reorg_uses_side_branch_timestamp_context_before_store() {
         let (mut engine, dir) = engine_with_store("rubin-reorg-side-mtp");
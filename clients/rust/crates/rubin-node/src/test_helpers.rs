@@ -4,8 +4,8 @@ use rubin_consensus::constants::POW_LIMIT;
 use rubin_consensus::merkle::{witness_commitment_hash, witness_merkle_root_wtxids};
 use rubin_consensus::{
     block_hash, encode_compact_size, marshal_tx, merkle_root_txids, p2pk_covenant_data_for_pubkey,
-    parse_block_bytes, parse_tx, sign_transaction, Mldsa87Keypair, Outpoint, Tx, TxInput, TxOutput,
-    UtxoEntry, BLOCK_HEADER_BYTES,
+    parse_block_bytes, parse_tx, sighash_v1_digest_with_type, sign_transaction, Mldsa87Keypair,
+    Outpoint, Tx, TxInput, TxOutput, UtxoEntry, BLOCK_HEADER_BYTES,
 };
 
 use crate::coinbase::{build_coinbase_tx, default_mine_address};
@@ -73,6 +73,25 @@ pub fn block_with_txs(
     prev_hash: [u8; 32],
     timestamp: u64,
     txs: &[Vec<u8>],
+) -> Vec<u8> {
+    block_with_txs_to(
+        height,
+        already_generated,
+        prev_hash,
+        timestamp,
+        &default_mine_address(),
+        txs,
+    )
+}
+
+/// `block_with_txs` with the coinbase paying `mine_address`.
+pub fn block_with_txs_to(
+    height: u64,
+    already_generated: u64,
+    prev_hash: [u8; 32],
+    timestamp: u64,
+    mine_address: &[u8],
+    txs: &[Vec<u8>],
 ) -> Vec<u8> {
     let mut txids = Vec::with_capacity(1 + txs.len());
     let mut wtxids = Vec::with_capacity(1 + txs.len());
@@ -86,13 +105,8 @@ pub fn block_with_txs(
 
     let witness_root = witness_merkle_root_wtxids(&wtxids).expect("witness root");
     let witness_commitment = witness_commitment_hash(witness_root);
-    let coinbase = build_coinbase_tx(
-        height,
-        already_generated,
-        &default_mine_address(),
-        witness_commitment,
-    )
-    .expect("coinbase");
+    let coinbase = build_coinbase_tx(height, already_generated, mine_address, witness_commitment)
+        .expect("coinbase");
     let (_, coinbase_txid, _, consumed) = parse_tx(&coinbase).expect("parse coinbase");
     assert_eq!(consumed, coinbase.len());
 
@@ -163,6 +177,67 @@ pub fn signed_conflicting_p2pk_state_and_txs(
     (state, first, second)
 }
 
+/// One signed P2PK spend in two witness variants with the same txid: the
+/// first signs with `SIGHASH_ALL`, the second with `SIGHASH_NONE`.
+pub fn signed_witness_variant_p2pk_state_and_txs(
+    input_value: u64,
+    output_value: u64,
+) -> (ChainState, Vec<u8>, Vec<u8>) {
+    use rubin_consensus::constants::SIGHASH_NONE;
+
+    let keypair = Mldsa87Keypair::generate().expect("OpenSSL signer unavailable");
+    let pubkey = keypair.pubkey_bytes();
+    let outpoint = Outpoint {
+        txid: [0x11; 32],
+        vout: 0,
+    };
+
+    let mut state = ChainState::new();
+    state.utxos.insert(
+        outpoint.clone(),
+        UtxoEntry {
+            value: input_value,
+            covenant_type: rubin_consensus::constants::COV_TYPE_P2PK,
+            covenant_data: p2pk_covenant_data_for_pubkey(&pubkey),
+            creation_height: 0,
+            created_by_coinbase: false,
+        },
+    );
+
+    let mut tx = Tx {
+        version: rubin_consensus::constants::TX_WIRE_VERSION,
+        tx_kind: 0x00,
+        tx_nonce: 7,
+        inputs: vec![TxInput {
+            prev_txid: outpoint.txid,
+            prev_vout: outpoint.vout,
+            script_sig: Vec::new(),
+            sequence: 0,
+        }],
+        outputs: vec![TxOutput {
+            value: output_value,
+            covenant_type: rubin_consensus::constants::COV_TYPE_P2PK,
+            covenant_data: p2pk_covenant_data_for_pubkey(&vec![7u8; 2592]),
+        }],
+        locktime: 0,
+        da_commit_core: None,
+        da_chunk_core: None,
+        witness: Vec::new(),
+        da_payload: Vec::new(),
+    };
+    let chain_id = devnet_genesis_chain_id();
+    sign_transaction(&mut tx, &state.utxos, chain_id, &keypair).expect("sign tx");
+    let canonical = marshal_tx(&tx).expect("marshal tx");
+
+    let digest =
+        sighash_v1_digest_with_type(&tx, 0, input_value, chain_id, SIGHASH_NONE).expect("sighash");
+    let mut signature = keypair.sign_digest32(digest).expect("sign digest");
+    signature.push(SIGHASH_NONE);
+    tx.witness[0].signature = signature;
+    let variant = marshal_tx(&tx).expect("marshal tx");
+    (state, canonical, variant)
+}
+
 /// Build a valid coinbase-only block at a given height (already_generated = 0).
 pub fn coinbase_only_block(height: u64, prev_hash: [u8; 32], timestamp: u64) -> Vec<u8> {
     coinbase_only_block_with_gen(height, 0, prev_hash, timestamp)
@@ -172,3 +247,19 @@ pub fn coinbase_only_block(height: u64, prev_hash: [u8; 32], timestamp: u64) ->
 pub fn height_one_coinbase_only_block(prev_hash: [u8; 32], timestamp: u64) -> Vec<u8> {
     coinbase_only_block(1, prev_hash, timestamp)
 }
+
+/// Flips the first body bit that still parses but breaks the merkle root,
+/// so the block keeps its header hash while its transactions change.
+pub fn mutate_block_body_keeping_header(block_bytes: &[u8]) -> Vec<u8> {
+    for i in BLOCK_HEADER_BYTES..block_bytes.len() {
+        let mut mutated = block_bytes.to_vec();
+        mutated[i] ^= 0x01;
+        let Ok(parsed) = parse_block_bytes(&mutated) else {
+            continue;
+        };
+        if merkle_root_txids(&parsed.txids).ok() != Some(parsed.header.merkle_root) {
+            return mutated;
+        }
+    }
+    panic!("no merkle-breaking mutation found");
+}
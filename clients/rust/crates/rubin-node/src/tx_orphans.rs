@@ -0,0 +1,516 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::Mutex;
+
+use rubin_consensus::Outpoint;
+
+use crate::blockstore::BlockStore;
+use crate::chainstate::ChainState;
+use crate::txpool::{RelayTxMetadata, TxPool, TxPoolAdmitError, TxSource};
+
+/// Default cap on stored orphan transactions.
+pub const DEFAULT_TX_ORPHAN_MAX_TXS: usize = 100;
+/// Default cap on the summed raw size of stored orphans.
+pub const DEFAULT_TX_ORPHAN_MAX_BYTES: usize = 4 << 20;
+/// Default cap on orphans stored on behalf of one peer.
+pub const DEFAULT_TX_ORPHAN_PER_PEER_MAX_TXS: usize = 25;
+/// Largest transaction kept as an orphan; bigger ones are dropped.
+pub const MAX_TX_ORPHAN_TX_BYTES: usize = 100_000;
+/// Orphans still waiting this many blocks after arrival are dropped.
+pub const TX_ORPHAN_TTL_BLOCKS: u64 = 20;
+
+/// Score added to a peer whose orphan turned out invalid once its parents
+/// were known. Same weight as a malformed tx.
+pub const TX_ORPHAN_INVALID_BAN_SCORE: i32 = 10;
+
+/// A peer transaction whose inputs spend outputs that are neither in the
+/// UTXO set nor in the mempool yet.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TxOrphan {
+    pub txid: [u8; 32],
+    pub raw: Vec<u8>,
+    /// Prevouts that were unknown on arrival.
+    pub missing: Vec<Outpoint>,
+    pub peer: String,
+    expires_at_height: u64,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TxOrphanAddOutcome {
+    /// Stored; `evicted` older orphans made room for it.
+    Stored {
+        evicted: usize,
+    },
+    Duplicate,
+    TooLarge,
+    /// Nothing to wait for; the caller should not have called `add`.
+    NoMissingInputs,
+}
+
+/// An orphan admitted to the mempool by `TxOrphanPool::promote`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PromotedTxOrphan {
+    pub txid: [u8; 32],
+    pub raw: Vec<u8>,
+    pub peer: String,
+    pub meta: RelayTxMetadata,
+}
+
+/// Transactions received before their parents, keyed by the prevouts they
+/// wait for. When a parent enters the mempool or confirms, `promote`
+/// re-runs admission for its waiting children. Bounded by count, bytes and
+/// per-peer count; the oldest orphan goes first. Eviction and expiry are
+/// silent: an orphan is only held against its peer once admission rejects
+/// it for a reason other than a missing input, and that penalty is parked
+/// here until the peer's own session collects it (`take_penalties`).
+pub struct TxOrphanPool {
+    inner: Mutex<TxOrphanPoolInner>,
+}
+
+struct TxOrphanPoolInner {
+    max_txs: usize,
+    max_bytes: usize,
+    per_peer_max_txs: usize,
+    orphans: HashMap<[u8; 32], TxOrphan>,
+    by_prevout: HashMap<Outpoint, HashSet<[u8; 32]>>,
+    arrival: VecDeque<[u8; 32]>,
+    per_peer: HashMap<String, usize>,
+    total_bytes: usize,
+    penalties: HashMap<String, Vec<String>>,
+}
+
+impl Default for TxOrphanPool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TxOrphanPool {
+    pub fn new() -> Self {
+        Self::new_with_limits(
+            DEFAULT_TX_ORPHAN_MAX_TXS,
+            DEFAULT_TX_ORPHAN_MAX_BYTES,
+            DEFAULT_TX_ORPHAN_PER_PEER_MAX_TXS,
+        )
+    }
+
+    /// Zero limits fall back to the defaults.
+    pub fn new_with_limits(max_txs: usize, max_bytes: usize, per_peer_max_txs: usize) -> Self {
+        let or_default = |value: usize, default: usize| if value == 0 { default } else { value };
+        Self {
+            inner: Mutex::new(TxOrphanPoolInner {
+                max_txs: or_default(max_txs, DEFAULT_TX_ORPHAN_MAX_TXS),
+                max_bytes: or_default(max_bytes, DEFAULT_TX_ORPHAN_MAX_BYTES),
+                per_peer_max_txs: or_default(per_peer_max_txs, DEFAULT_TX_ORPHAN_PER_PEER_MAX_TXS),
+                orphans: HashMap::new(),
+                by_prevout: HashMap::new(),
+                arrival: VecDeque::new(),
+                per_peer: HashMap::new(),
+                total_bytes: 0,
+                penalties: HashMap::new(),
+            }),
+        }
+    }
+
+    /// Store `raw` from `peer` until the `missing` prevouts appear. It
+    /// expires `TX_ORPHAN_TTL_BLOCKS` after `tip_height`.
+    pub fn add(
+        &self,
+        txid: [u8; 32],
+        raw: &[u8],
+        missing: Vec<Outpoint>,
+        peer: &str,
+        tip_height: u64,
+    ) -> TxOrphanAddOutcome {
+        if missing.is_empty() {
+            return TxOrphanAddOutcome::NoMissingInputs;
+        }
+        let Ok(mut inner) = self.inner.lock() else {
+            return TxOrphanAddOutcome::Duplicate;
+        };
+        if raw.len() > MAX_TX_ORPHAN_TX_BYTES || raw.len() > inner.max_bytes {
+            return TxOrphanAddOutcome::TooLarge;
+        }
+        if inner.orphans.contains_key(&txid) {
+            return TxOrphanAddOutcome::Duplicate;
+        }
+        let mut evicted = 0;
+        if inner.per_peer.get(peer).copied().unwrap_or(0) >= inner.per_peer_max_txs
+            && inner.evict_oldest_of(Some(peer))
+        {
+            evicted += 1;
+        }
+        while inner.orphans.len() >= inner.max_txs
+            || inner.total_bytes + raw.len() > inner.max_bytes
+        {
+            if !inner.evict_oldest_of(None) {
+                break;
+            }
+            evicted += 1;
+        }
+        inner.insert(TxOrphan {
+            txid,
+            raw: raw.to_vec(),
+            missing,
+            peer: peer.to_string(),
+            expires_at_height: tip_height.saturating_add(TX_ORPHAN_TTL_BLOCKS),
+        });
+        TxOrphanAddOutcome::Stored { evicted }
+    }
+
+    /// Re-runs `admit` for every orphan waiting on an output of `parents`,
+    /// then for orphans waiting on those, and so on. An orphan still
+    /// missing other inputs goes back to waiting; one rejected for any
+    /// other reason is dropped and recorded against its peer.
+    pub fn promote(
+        &self,
+        parents: impl IntoIterator<Item = [u8; 32]>,
+        mut admit: impl FnMut(&[u8]) -> Result<RelayTxMetadata, TxPoolAdmitError>,
+    ) -> Vec<PromotedTxOrphan> {
+        let Ok(mut inner) = self.inner.lock() else {
+            return Vec::new();
+        };
+        let mut queue: Vec<[u8; 32]> = parents.into_iter().collect();
+        let mut promoted = Vec::new();
+        while let Some(parent) = queue.pop() {
+            let mut waiting: Vec<[u8; 32]> = inner
+                .by_prevout
+                .iter()
+                .filter(|(prevout, _)| prevout.txid == parent)
+                .flat_map(|(_, txids)| txids.iter().copied())
+                .collect::<HashSet<_>>()
+                .into_iter()
+                .collect();
+            waiting.sort_unstable();
+            for txid in waiting {
+                let Some(mut orphan) = inner.remove(&txid) else {
+                    continue;
+                };
+                match admit(&orphan.raw) {
+                    Ok(meta) => {
+                        queue.push(orphan.txid);
+                        promoted.push(PromotedTxOrphan {
+                            txid: orphan.txid,
+                            raw: orphan.raw,
+                            peer: orphan.peer,
+                            meta,
+                        });
+                    }
+                    Err(err) if err.is_missing_utxo() => {
+                        orphan.missing.retain(|prevout| prevout.txid != parent);
+                        if !orphan.missing.is_empty() {
+                            inner.insert(orphan);
+                        }
+                    }
+                    Err(err) => {
+                        let reason = format!("orphan tx {} invalid: {err}", hex::encode(txid));
+                        inner.penalties.entry(orphan.peer).or_default().push(reason);
+                    }
+                }
+            }
+        }
+        promoted
+    }
+
+    /// Drops orphans that outlived their TTL at `tip_height`; returns how
+    /// many. Expiry does not count against the peer.
+    pub fn expire(&self, tip_height: u64) -> usize {
+        let Ok(mut inner) = self.inner.lock() else {
+            return 0;
+        };
+        let expired: Vec<[u8; 32]> = inner
+            .orphans
+            .values()
+            .filter(|orphan| orphan.expires_at_height <= tip_height)
+            .map(|orphan| orphan.txid)
+            .collect();
+        for txid in &expired {
+            inner.remove(txid);
+        }
+        expired.len()
+    }
+
+    /// Reasons recorded against `peer` by `promote`, oldest first.
+    pub fn take_penalties(&self, peer: &str) -> Vec<String> {
+        let Ok(mut inner) = self.inner.lock() else {
+            return Vec::new();
+        };
+        inner.penalties.remove(peer).unwrap_or_default()
+    }
+
+    pub fn contains(&self, txid: &[u8; 32]) -> bool {
+        let Ok(inner) = self.inner.lock() else {
+            return false;
+        };
+        inner.orphans.contains_key(txid)
+    }
+
+    pub fn len(&self) -> usize {
+        let Ok(inner) = self.inner.lock() else {
+            return 0;
+        };
+        inner.orphans.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn total_bytes(&self) -> usize {
+        let Ok(inner) = self.inner.lock() else {
+            return 0;
+        };
+        inner.total_bytes
+    }
+}
+
+/// Inputs of `tx_bytes` that spend neither a confirmed output nor an
+/// output of a pool entry. Empty for unparseable bytes.
+pub fn missing_prevouts(tx_bytes: &[u8], chain_state: &ChainState, pool: &TxPool) -> Vec<Outpoint> {
+    let Ok((tx, _, _, _)) = rubin_consensus::parse_tx(tx_bytes) else {
+        return Vec::new();
+    };
+    tx.inputs
+        .iter()
+        .map(|input| Outpoint {
+            txid: input.prev_txid,
+            vout: input.prev_vout,
+        })
+        .filter(|prevout| !chain_state.utxos.contains_key(prevout) && !pool.contains(&prevout.txid))
+        .collect()
+}
+
+/// Promotes orphans waiting on `parents`, which just entered `pool` or
+/// confirmed in the block `chain_state` now ends at. Orphans are peer
+/// txs, so they are admitted as `TxSource::Remote`.
+pub fn promote_tx_orphans(
+    orphans: &TxOrphanPool,
+    pool: &mut TxPool,
+    parents: impl IntoIterator<Item = [u8; 32]>,
+    chain_state: &ChainState,
+    block_store: Option<&BlockStore>,
+    chain_id: [u8; 32],
+) -> Vec<PromotedTxOrphan> {
+    orphans.promote(parents, |raw| {
+        pool.add_tx_with_source(raw, chain_state, block_store, chain_id, TxSource::Remote)
+            .map(|(_, meta)| meta)
+    })
+}
+
+impl TxOrphanPoolInner {
+    fn insert(&mut self, orphan: TxOrphan) {
+        for prevout in &orphan.missing {
+            self.by_prevout
+                .entry(prevout.clone())
+                .or_default()
+                .insert(orphan.txid);
+        }
+        *self.per_peer.entry(orphan.peer.clone()).or_default() += 1;
+        self.total_bytes += orphan.raw.len();
+        self.arrival.push_back(orphan.txid);
+        self.orphans.insert(orphan.txid, orphan);
+    }
+
+    fn remove(&mut self, txid: &[u8; 32]) -> Option<TxOrphan> {
+        let orphan = self.orphans.remove(txid)?;
+        for prevout in &orphan.missing {
+            if let Some(txids) = self.by_prevout.get_mut(prevout) {
+                txids.remove(txid);
+                if txids.is_empty() {
+                    self.by_prevout.remove(prevout);
+                }
+            }
+        }
+        if let Some(count) = self.per_peer.get_mut(&orphan.peer) {
+            *count -= 1;
+            if *count == 0 {
+                self.per_peer.remove(&orphan.peer);
+            }
+        }
+        self.total_bytes -= orphan.raw.len();
+        self.arrival.retain(|queued| queued != txid);
+        Some(orphan)
+    }
+
+    /// Evicts the oldest orphan, or the oldest one from `peer`.
+    fn evict_oldest_of(&mut self, peer: Option<&str>) -> bool {
+        let oldest = self.arrival.iter().copied().find(|txid| {
+            peer.is_none_or(|peer| {
+                self.orphans
+                    .get(txid)
+                    .is_some_and(|orphan| orphan.peer == peer)
+            })
+        });
+        oldest.and_then(|txid| self.remove(&txid)).is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::txpool::TxPoolAdmitErrorKind;
+
+    fn txid(tag: u8) -> [u8; 32] {
+        [tag; 32]
+    }
+
+    fn prevout(tag: u8, vout: u32) -> Outpoint {
+        Outpoint {
+            txid: txid(tag),
+            vout,
+        }
+    }
+
+    fn missing_utxo() -> TxPoolAdmitError {
+        TxPoolAdmitError {
+            kind: TxPoolAdmitErrorKind::Rejected,
+            message: "transaction rejected: TX_ERR_MISSING_UTXO: utxo not found".to_string(),
+        }
+    }
+
+    /// Test mempool: raw byte 0 is the txid tag, the rest are parent tags;
+    /// a tx is admitted once every parent is confirmed or in the mempool.
+    struct FakeMempool {
+        known: HashSet<u8>,
+        invalid: HashSet<u8>,
+    }
+
+    impl FakeMempool {
+        fn admit(&mut self, raw: &[u8]) -> Result<RelayTxMetadata, TxPoolAdmitError> {
+            if self.invalid.contains(&raw[0]) {
+                return Err(TxPoolAdmitError {
+                    kind: TxPoolAdmitErrorKind::Rejected,
+                    message: "transaction rejected: TX_ERR_SIG_INVALID: bad".to_string(),
+                });
+            }
+            if raw[1..].iter().any(|parent| !self.known.contains(parent)) {
+                return Err(missing_utxo());
+            }
+            self.known.insert(raw[0]);
+            Ok(RelayTxMetadata {
+                fee: 1,
+                size: raw.len(),
+            })
+        }
+    }
+
+    #[test]
+    fn child_then_parent_promotes_the_whole_chain() {
+        let orphans = TxOrphanPool::new();
+        let mut mempool = FakeMempool {
+            known: HashSet::new(),
+            invalid: HashSet::new(),
+        };
+        // Grandchild 3 and child 2 arrive before parent 1.
+        assert_eq!(
+            orphans.add(txid(3), &[3, 2], vec![prevout(2, 0)], "peer-a", 100),
+            TxOrphanAddOutcome::Stored { evicted: 0 }
+        );
+        assert_eq!(
+            orphans.add(txid(2), &[2, 1], vec![prevout(1, 1)], "peer-a", 100),
+            TxOrphanAddOutcome::Stored { evicted: 0 }
+        );
+        assert_eq!(
+            orphans.add(txid(2), &[2, 1], vec![prevout(1, 1)], "peer-b", 100),
+            TxOrphanAddOutcome::Duplicate
+        );
+        assert!(mempool.admit(&[1]).is_ok());
+
+        let promoted = orphans.promote([txid(1)], |raw| mempool.admit(raw));
+        let promoted: Vec<[u8; 32]> = promoted.iter().map(|p| p.txid).collect();
+        assert_eq!(promoted, vec![txid(2), txid(3)]);
+        assert!(mempool.known.is_superset(&HashSet::from([1, 2, 3])));
+        assert!(orphans.is_empty());
+        assert_eq!(orphans.total_bytes(), 0);
+    }
+
+    #[test]
+    fn orphan_waits_for_every_missing_parent() {
+        let orphans = TxOrphanPool::new();
+        let mut mempool = FakeMempool {
+            known: HashSet::new(),
+            invalid: HashSet::new(),
+        };
+        let missing = vec![prevout(1, 0), prevout(2, 0)];
+        orphans.add(txid(9), &[9, 1, 2], missing, "peer-a", 0);
+        mempool.known.insert(1);
+        assert!(orphans
+            .promote([txid(1)], |raw| mempool.admit(raw))
+            .is_empty());
+        assert!(orphans.contains(&txid(9)));
+        // A block confirming parent 2 releases it.
+        mempool.known.insert(2);
+        let promoted = orphans.promote([txid(2)], |raw| mempool.admit(raw));
+        assert_eq!(promoted.len(), 1);
+        assert_eq!(promoted[0].peer, "peer-a");
+        assert!(orphans.is_empty());
+    }
+
+    #[test]
+    fn overflow_evicts_oldest_first_and_per_peer() {
+        let orphans = TxOrphanPool::new_with_limits(3, 1_000, 2);
+        orphans.add(txid(1), &[1; 10], vec![prevout(0xA1, 0)], "peer-a", 0);
+        orphans.add(txid(2), &[2; 10], vec![prevout(0xA2, 0)], "peer-b", 0);
+        orphans.add(txid(3), &[3; 10], vec![prevout(0xA3, 0)], "peer-c", 0);
+        assert_eq!(
+            orphans.add(txid(4), &[4; 10], vec![prevout(0xA4, 0)], "peer-d", 0),
+            TxOrphanAddOutcome::Stored { evicted: 1 }
+        );
+        assert!(!orphans.contains(&txid(1)));
+        assert_eq!(orphans.len(), 3);
+
+        orphans.add(txid(5), &[5; 10], vec![prevout(0xA5, 0)], "peer-d", 0);
+        assert!(!orphans.contains(&txid(2)));
+        // peer-d is now at its cap of 2: its next orphan evicts its own
+        // oldest rather than peer-c's older entry.
+        assert_eq!(
+            orphans.add(txid(6), &[6; 10], vec![prevout(0xA6, 0)], "peer-d", 0),
+            TxOrphanAddOutcome::Stored { evicted: 1 }
+        );
+        assert!(!orphans.contains(&txid(4)));
+        assert!(orphans.contains(&txid(3)));
+        assert!(orphans.contains(&txid(5)));
+        assert!(orphans.contains(&txid(6)));
+
+        // Byte cap: one large orphan pushes out the two oldest.
+        assert_eq!(
+            orphans.add(txid(7), &[7; 985], vec![prevout(0xA7, 0)], "peer-e", 0),
+            TxOrphanAddOutcome::Stored { evicted: 2 }
+        );
+        assert_eq!(orphans.total_bytes(), 995);
+        assert_eq!(
+            orphans.add(txid(8), &[8; 1_001], vec![prevout(0xA8, 0)], "peer-e", 0),
+            TxOrphanAddOutcome::TooLarge
+        );
+        assert_eq!(
+            orphans.add(txid(8), &[8], Vec::new(), "peer-e", 0),
+            TxOrphanAddOutcome::NoMissingInputs
+        );
+        // Eviction never records a penalty.
+        for peer in ["peer-a", "peer-b", "peer-c", "peer-d"] {
+            assert!(orphans.take_penalties(peer).is_empty());
+        }
+    }
+
+    #[test]
+    fn only_invalid_promotions_penalize_the_peer() {
+        let orphans = TxOrphanPool::new();
+        let mut mempool = FakeMempool {
+            known: HashSet::from([1]),
+            invalid: HashSet::from([2]),
+        };
+        orphans.add(txid(2), &[2, 1], vec![prevout(1, 0)], "peer-a", 10);
+        orphans.add(txid(3), &[3, 4], vec![prevout(4, 0)], "peer-b", 10);
+        assert!(orphans
+            .promote([txid(1)], |raw| mempool.admit(raw))
+            .is_empty());
+        let penalties = orphans.take_penalties("peer-a");
+        assert_eq!(penalties.len(), 1);
+        assert!(penalties[0].contains("TX_ERR_SIG_INVALID"), "{penalties:?}");
+        assert!(orphans.take_penalties("peer-a").is_empty());
+
+        assert_eq!(orphans.expire(10 + TX_ORPHAN_TTL_BLOCKS - 1), 0);
+        assert_eq!(orphans.expire(10 + TX_ORPHAN_TTL_BLOCKS), 1);
+        assert!(orphans.is_empty());
+        assert!(orphans.take_penalties("peer-b").is_empty());
+    }
+}
@@ -25,7 +25,8 @@
 //! any current follow-up.
 //! Observed at HEAD, field-by-field:
 //!  - `relay_state: &TxRelayState` (this file) — fields are
-//!    `tx_seen`, `relay_pool` (`RelayTxPool`), `tx_relay_fanout`,
+//!    `tx_seen`, `relay_pool` (`RelayTxPool`), `tx_orphans`
+//!    (`TxOrphanPool`, raw bytes only), `tx_relay_fanout`,
 //!    `network`. No canonical `TxPool` field.
 //!  - `sync_engine: &SyncEngine`
 //!    (`crate::sync::SyncEngine`) — fields are `chain_state`,
@@ -80,6 +81,16 @@
 //! outcome here as proof of canonical admission — admission
 //! happens in the per-producer caller, not in this module.
 //!
+//! # Witness variants
+//!
+//! MSG_TX inventory and getdata carry the txid; the protocol defines no
+//! wtxid inventory type, so the seen set and relay pool stay txid-keyed. A
+//! peer may still send a different witness for a txid already relayed:
+//! `handle_received_tx` keeps whichever variant
+//! `crate::txpool::witness_variant_preferred` ranks first, and the canonical
+//! pool applies the same ranking on admission, so both converge on the same
+//! wtxid.
+//!
 //! # Go counterpart (API/sequence parity ONLY — NOT production-boundary parity)
 //!
 //! Note: this section describes API/sequence parity only. Current
@@ -247,13 +258,14 @@ use std::collections::HashMap;
 use std::io;
 use std::sync::Mutex;
 
-use rubin_consensus::{block_hash, parse_block_bytes};
+use rubin_consensus::{block_hash, parse_block_bytes, Tx};
 use sha3::{Digest, Sha3_256};
 
 use crate::p2p_runtime::{
     encode_inventory_vectors, InventoryVector, PeerManager, MSG_BLOCK, MSG_TX,
 };
 use crate::relay_pool::RelayTxPool;
+use crate::tx_orphans::{PromotedTxOrphan, TxOrphanPool};
 use crate::tx_seen::BoundedHashSet;
 
 /// Default TX relay fanout (matches Go `defaultTxRelayFanout`).
@@ -318,6 +330,8 @@ pub struct TxRelayState {
     pub tx_seen: BoundedHashSet,
     pub block_seen: BoundedHashSet,
     pub relay_pool: RelayTxPool,
+    /// Peer txs waiting for unconfirmed parents (see `crate::tx_orphans`).
+    pub tx_orphans: TxOrphanPool,
     pub tx_relay_fanout: usize,
     pub network: String,
 }
@@ -338,6 +352,7 @@ impl TxRelayState {
             tx_seen: BoundedHashSet::new(crate::tx_seen::DEFAULT_TX_SEEN_CAPACITY),
             block_seen: BoundedHashSet::new(crate::tx_seen::DEFAULT_BLOCK_SEEN_CAPACITY),
             relay_pool: RelayTxPool::new(),
+            tx_orphans: TxOrphanPool::new(),
             tx_relay_fanout: DEFAULT_TX_RELAY_FANOUT,
             network: network.to_string(),
         }
@@ -536,6 +551,77 @@ pub fn announce_tx(
     )
 }
 
+/// Announce a locally submitted transaction again, for the broadcast
+/// manager's periodic rebroadcast. Unlike `announce_tx` it skips the
+/// seen-set dedup, which the first announce already filled. `only_addrs`
+/// limits the INV to those peers (newly connected ones) instead of the
+/// usual fanout.
+#[allow(clippy::too_many_arguments)]
+pub fn rebroadcast_tx(
+    txid: [u8; 32],
+    tx_bytes: &[u8],
+    fee: u64,
+    only_addrs: Option<&[String]>,
+    relay_state: &TxRelayState,
+    peer_manager: &PeerManager,
+    local_addr: &str,
+    peer_writers: &Mutex<HashMap<String, PeerOutbox>>,
+) -> Result<(), String> {
+    // Peers answer the INV with getdata, served from the relay pool; the
+    // entry may have been evicted since the first announce.
+    relay_state
+        .relay_pool
+        .put(txid, tx_bytes, fee, tx_bytes.len());
+    let items = [InventoryVector {
+        kind: MSG_TX,
+        hash: txid,
+    }];
+    match only_addrs {
+        Some(addrs) => {
+            broadcast_inv_to_addrs(&items, addrs, &relay_state.network, peer_writers, false)
+        }
+        None => broadcast_inventory(
+            relay_state,
+            None,
+            &items,
+            peer_manager,
+            local_addr,
+            peer_writers,
+        ),
+    }
+}
+
+/// Relay orphans the caller just admitted to the canonical pool. They
+/// were marked seen on arrival, so unlike `announce_tx` this stores and
+/// broadcasts unconditionally, skipping the peer each one came from.
+pub fn relay_promoted_tx_orphans(
+    promoted: &[PromotedTxOrphan],
+    relay_state: &TxRelayState,
+    peer_manager: &PeerManager,
+    local_addr: &str,
+    peer_writers: &Mutex<HashMap<String, PeerOutbox>>,
+) {
+    for orphan in promoted {
+        if !relay_state
+            .relay_pool
+            .put(orphan.txid, &orphan.raw, orphan.meta.fee, orphan.meta.size)
+        {
+            continue;
+        }
+        let _ = broadcast_inventory(
+            relay_state,
+            Some(&orphan.peer),
+            &[InventoryVector {
+                kind: MSG_TX,
+                hash: orphan.txid,
+            }],
+            peer_manager,
+            local_addr,
+            peer_writers,
+        );
+    }
+}
+
 /// Announce a locally mined block after it is committed to the block store.
 ///
 /// Rust `/mine_next` uses this to mirror Go's `AnnounceBlock`: parse the
@@ -588,9 +674,13 @@ pub enum RelayTxOutcome {
     /// Relay-metadata derivation failed (fee/policy); marked seen so peers
     /// don't churn INV/GETDATA, but peer session is not penalized.
     MetadataRejected,
+    /// Tx spends an output that is neither confirmed nor known here; it
+    /// may be a child arriving before its parent. Marked seen and not
+    /// relayed; the caller decides whether to keep it as an orphan.
+    MissingInputs { txid: [u8; 32] },
     /// Relay pool rejected admission (full or lower-priority eviction).
     PoolRejected,
-    /// Payload exceeded `MAX_RELAY_MSG_BYTES`. Caller must bump ban score
+    /// Payload exceeded `MAX_TX_BYTES`. Caller must bump ban score
     /// and fail the session if over threshold (parity with Go
     /// `handleTx` oversize path).
     Oversized,
@@ -629,24 +719,29 @@ pub fn handle_received_tx(
     local_addr: &str,
     peer_writers: &Mutex<HashMap<String, PeerOutbox>>,
 ) -> io::Result<RelayTxOutcome> {
-    // Reject oversized tx payloads early (defense-in-depth).
-    if tx_bytes.len() > rubin_consensus::constants::MAX_RELAY_MSG_BYTES as usize {
+    // Reject oversized tx payloads early (defense-in-depth; the frame reader
+    // already caps `tx` payloads at the same bound).
+    if tx_bytes.len() as u64 > crate::txpool::MAX_TX_BYTES {
         return Ok(RelayTxOutcome::Oversized);
     }
 
     // Structural validation via consensus parser (matches Go's canonicalTxID + relayTxMetadata).
-    let txid = match canonical_txid(tx_bytes) {
-        Ok(txid) => txid,
+    let (tx, txid, wtxid) = match parse_canonical_tx(tx_bytes) {
+        Ok(parsed) => parsed,
         Err(reason) => return Ok(RelayTxOutcome::MalformedParse(reason)),
     };
 
-    // Mark seen BEFORE pool admission (matches Go).
-    if !relay_state.tx_seen.add(txid) {
+    // Mark seen BEFORE pool admission (matches Go). The seen set is keyed by
+    // txid, as MSG_TX inventory is; a different witness variant of a seen
+    // txid goes on only if it outranks the bytes the relay pool holds.
+    let replaces_variant = !relay_state.tx_seen.add(txid);
+    if replaces_variant && !outranks_relayed_variant(&tx, txid, wtxid, sync_engine, relay_state) {
         return Ok(RelayTxOutcome::DuplicateSeen { txid });
     }
 
     let relay_cfg = crate::txpool::TxPoolConfig {
         suite_context: sync_engine.cfg.suite_context.clone(),
+        chain_params: sync_engine.cfg.chain_params,
         ..crate::txpool::TxPoolConfig::default()
     };
     let meta = match crate::txpool::relay_metadata(
@@ -657,14 +752,23 @@ pub fn handle_received_tx(
         &relay_cfg,
     ) {
         Ok(meta) => meta,
+        Err(err) if err.is_missing_utxo() => {
+            return Ok(RelayTxOutcome::MissingInputs { txid });
+        }
         Err(_) => return Ok(RelayTxOutcome::MetadataRejected),
     };
 
     // Store in relay pool with extracted metadata.
-    if !relay_state
-        .relay_pool
-        .put(txid, tx_bytes, meta.fee, meta.size)
-    {
+    let stored = if replaces_variant {
+        relay_state
+            .relay_pool
+            .replace(txid, tx_bytes, meta.fee, meta.size)
+    } else {
+        relay_state
+            .relay_pool
+            .put(txid, tx_bytes, meta.fee, meta.size)
+    };
+    if !stored {
         return Ok(RelayTxOutcome::PoolRejected);
     }
 
@@ -685,12 +789,46 @@ pub fn handle_received_tx(
 
 /// Extract the canonical txid from raw tx bytes using consensus parsing.
 pub(crate) fn canonical_txid(tx_bytes: &[u8]) -> Result<[u8; 32], String> {
-    let (_tx, txid, _wtxid, consumed) =
+    parse_canonical_tx(tx_bytes).map(|(_tx, txid, _wtxid)| txid)
+}
+
+/// Consensus-parse raw tx bytes that must be consumed exactly, returning the
+/// tx with its txid and wtxid.
+fn parse_canonical_tx(tx_bytes: &[u8]) -> Result<(Tx, [u8; 32], [u8; 32]), String> {
+    let (tx, txid, wtxid, consumed) =
         rubin_consensus::parse_tx(tx_bytes).map_err(|e| e.to_string())?;
     if consumed != tx_bytes.len() {
         return Err("non-canonical tx bytes".to_string());
     }
-    Ok(txid)
+    Ok((tx, txid, wtxid))
+}
+
+/// Whether `tx` should replace the witness variant of `txid` held in the
+/// relay pool, per `crate::txpool::witness_variant_preferred`. A txid that
+/// is not held, or is held with the same wtxid, is a plain duplicate.
+fn outranks_relayed_variant(
+    tx: &Tx,
+    txid: [u8; 32],
+    wtxid: [u8; 32],
+    sync_engine: &crate::sync::SyncEngine,
+    relay_state: &TxRelayState,
+) -> bool {
+    let Some(relayed_raw) = relay_state.relay_pool.get(&txid) else {
+        return false;
+    };
+    let Ok((relayed, _, relayed_wtxid)) = parse_canonical_tx(&relayed_raw) else {
+        return false;
+    };
+    if relayed_wtxid == wtxid {
+        return false;
+    }
+    let registry = sync_engine
+        .cfg
+        .suite_context
+        .as_ref()
+        .map(|ctx| ctx.registry.as_ref().clone())
+        .unwrap_or_else(rubin_consensus::SuiteRegistry::default_registry);
+    crate::txpool::witness_variant_preferred(tx, &wtxid, &relayed, &relayed_wtxid, &registry)
 }
 
 #[cfg(test)]
@@ -1318,6 +1456,45 @@ mod tests {
         assert!(boxes["peer-y:8333"].is_empty());
     }
 
+    #[test]
+    fn rebroadcast_tx_ignores_seen_set_and_targets_given_peers() {
+        let tx_bytes = real_tx_bytes();
+        let txid = canonical_txid(&tx_bytes).unwrap();
+        let relay = TxRelayState::new();
+        let pm = PeerManager::new(crate::p2p_runtime::default_peer_runtime_config(
+            "devnet", 64,
+        ));
+        let outboxes: Mutex<HashMap<String, PeerOutbox>> = Mutex::new(HashMap::new());
+        for addr in ["peer-a:8333", "peer-b:8333"] {
+            let _ = pm.add_peer(crate::p2p_runtime::PeerState {
+                addr: addr.to_string(),
+                ..Default::default()
+            });
+            outboxes
+                .lock()
+                .unwrap()
+                .insert(addr.to_string(), PeerOutbox::default());
+        }
+        relay.tx_seen.add(txid);
+
+        let only = ["peer-b:8333".to_string()];
+        rebroadcast_tx(
+            txid,
+            &tx_bytes,
+            0,
+            Some(&only),
+            &relay,
+            &pm,
+            "local:8333",
+            &outboxes,
+        )
+        .expect("rebroadcast");
+        assert!(relay.relay_pool.has(&txid));
+        let boxes = outboxes.lock().unwrap();
+        assert!(boxes["peer-a:8333"].is_empty());
+        assert_eq!(boxes["peer-b:8333"].len(), 1);
+    }
+
     #[test]
     fn announce_tx_relay_pool_rejection_skips_seen_and_broadcast() {
         let tx_bytes = real_tx_bytes();
@@ -1329,6 +1506,7 @@ mod tests {
             tx_seen: BoundedHashSet::new(crate::tx_seen::DEFAULT_TX_SEEN_CAPACITY),
             block_seen: BoundedHashSet::new(crate::tx_seen::DEFAULT_BLOCK_SEEN_CAPACITY),
             relay_pool: RelayTxPool::new_with_limit(1),
+            tx_orphans: TxOrphanPool::new(),
             tx_relay_fanout: DEFAULT_TX_RELAY_FANOUT,
             network: "devnet".to_string(),
         };
@@ -1366,6 +1544,7 @@ mod tests {
             tx_seen: BoundedHashSet::new(crate::tx_seen::DEFAULT_TX_SEEN_CAPACITY),
             block_seen: BoundedHashSet::new(crate::tx_seen::DEFAULT_BLOCK_SEEN_CAPACITY),
             relay_pool: RelayTxPool::new_with_limit(1),
+            tx_orphans: TxOrphanPool::new(),
             tx_relay_fanout: DEFAULT_TX_RELAY_FANOUT,
             network: "devnet".to_string(),
         };
@@ -1393,6 +1572,34 @@ mod tests {
         assert_eq!(boxes["peer-rpc:8333"].len(), 1);
     }
 
+    #[test]
+    fn handle_received_tx_keeps_the_canonical_witness_variant() {
+        let (chain_state, canonical, variant) =
+            crate::test_helpers::signed_witness_variant_p2pk_state_and_txs(20_000, 10);
+        let cfg = default_sync_config(None, crate::genesis::devnet_genesis_chain_id(), None);
+        let sync_engine = SyncEngine::new(chain_state, None, cfg).expect("sync engine");
+        let pm = PeerManager::new(crate::p2p_runtime::default_peer_runtime_config(
+            "devnet", 64,
+        ));
+        let outboxes: Mutex<HashMap<String, PeerOutbox>> = Mutex::new(HashMap::new());
+        let txid = canonical_txid(&canonical).unwrap();
+
+        for order in [[&variant, &canonical], [&canonical, &variant]] {
+            let relay = TxRelayState::new();
+            for raw in order {
+                handle_received_tx(raw, &sync_engine, &relay, &pm, "s", "l", &outboxes)
+                    .expect("handle tx");
+            }
+            assert_eq!(relay.relay_pool.get(&txid).as_ref(), Some(&canonical));
+        }
+
+        let relay = TxRelayState::new();
+        let first = handle_received_tx(&canonical, &sync_engine, &relay, &pm, "s", "l", &outboxes);
+        assert_eq!(first.unwrap(), RelayTxOutcome::Relayed { txid });
+        let second = handle_received_tx(&variant, &sync_engine, &relay, &pm, "s", "l", &outboxes);
+        assert_eq!(second.unwrap(), RelayTxOutcome::DuplicateSeen { txid });
+    }
+
     #[test]
     fn handle_received_tx_with_valid_floor_compliant_tx_stores_and_relays() {
         // PR-1410 wave-2 fixture migration: relay_metadata now enforces
@@ -2843,8 +3050,8 @@ fn handle_received_tx() {
 
     /// C.1 parity: oversized relay payload must surface as
     /// `RelayTxOutcome::Oversized` (ban-worthy), never touching consensus
-    /// parsing. Mirrors the explicit `MAX_RELAY_MSG_BYTES` guard now added to
-    /// Go `handleTx` (see `clients/go/node/p2p/handlers_tx.go`).
+    /// parsing. Mirrors the explicit size guard in Go `handleTx` (see
+    /// `clients/go/node/p2p/handlers_tx.go`), at the tighter `MAX_TX_BYTES`.
     #[test]
     fn handle_received_tx_oversize_surfaces_ban_worthy_outcome() {
         let sync_engine = SyncEngine::new(
@@ -2860,12 +3067,12 @@ fn handle_received_tx() {
         let outboxes: Mutex<HashMap<String, PeerOutbox>> = Mutex::new(HashMap::new());
 
         // Coverage of the `Oversized` return branch requires the actual
-        // length check to fire, which needs `MAX_RELAY_MSG_BYTES + 1`
-        // bytes (~96 MB). Modern CI runners absorb this; an env-var
+        // length check to fire, which needs `MAX_TX_BYTES + 1` bytes
+        // (~72 MB). Modern CI runners absorb this; an env-var
         // skip would drop diff-coverage below the 85% gate, so the
         // allocation is accepted as the lesser evil. The check itself
         // never reads the payload, so zero-fill cost dominates.
-        let oversize = vec![0u8; rubin_consensus::constants::MAX_RELAY_MSG_BYTES as usize + 1];
+        let oversize = vec![0u8; crate::txpool::MAX_TX_BYTES as usize + 1];
         let outcome = handle_received_tx(
             &oversize,
             &sync_engine,
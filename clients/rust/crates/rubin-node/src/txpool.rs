@@ -3,13 +3,21 @@ use std::collections::{BinaryHeap, HashMap, HashSet};
 use std::sync::OnceLock;
 
 use rubin_consensus::{
-    apply_non_coinbase_tx_basic_update_with_mtp_and_core_ext_profiles_and_suite_context,
-    constants::{COV_TYPE_CORE_EXT, COV_TYPE_CORE_SIMPLICITY, MAX_RELAY_MSG_BYTES},
-    parse_block_header_bytes, parse_tx, tx_weight_and_stats_public, validate_tx_covenants_genesis,
-    DefaultRotationProvider, NativeSuiteSet, Outpoint, RotationProvider, SuiteRegistry,
+    apply_non_coinbase_tx_basic_update_with_mtp_and_core_ext_profiles_and_suite_context_and_maturity,
+    constants::{
+        COV_TYPE_ANCHOR, COV_TYPE_CORE_EXT, COV_TYPE_CORE_SIMPLICITY, COV_TYPE_DA_COMMIT,
+        MAX_BLOCK_BYTES, MAX_RELAY_MSG_BYTES, SIGHASH_ALL, SUITE_ID_SENTINEL,
+    },
+    parse_block_header_bytes, parse_tx,
+    suite_registry::is_structural_witness_carrier_suite_id,
+    tx_weight_and_stats_public, validate_tx_covenants_genesis, ChainParams,
+    DefaultRotationProvider, NativeSuiteSet, Outpoint, OutpointMap, RotationProvider,
+    SuiteRegistry, Tx, UtxoEntry, BLOCK_HEADER_BYTES,
 };
 
+use crate::policy_dust::reject_dust_outputs;
 use crate::sync::SuiteContext;
+use crate::txpool_graph::{in_pool_parents, TxGraph};
 use crate::{BlockStore, ChainState};
 
 const MAX_TX_POOL_TRANSACTIONS: usize = 300;
@@ -90,6 +98,31 @@ pub const DEFAULT_MEMPOOL_MIN_FEE_RATE: u64 = 1;
 /// change to the relay floor cannot silently change the DA floor.
 pub const DEFAULT_MIN_DA_FEE_RATE: u64 = 1;
 
+/// Default `policy_max_ancestors`: a chain of unconfirmed transactions may
+/// be at most this long, the entry itself included.
+pub const DEFAULT_MAX_ANCESTORS: u64 = 25;
+
+/// Default `policy_max_descendants`, counting the ancestor itself.
+pub const DEFAULT_MAX_DESCENDANTS: u64 = 25;
+
+/// Default `policy_max_package_weight`: 101 KWU of ancestor package.
+pub const DEFAULT_MAX_PACKAGE_WEIGHT: u64 = 101_000;
+
+/// Relay/mempool cap on one serialized transaction: the largest tx that
+/// still fits a `MAX_BLOCK_BYTES` block message beside the header and a
+/// one-byte tx_count. Policy only: consensus bounds txs by weight, and
+/// `MAX_BLOCK_BYTES` is itself an operational P2P cap, so a larger tx could
+/// never reach peers inside a block and is refused before parsing.
+pub const MAX_TX_BYTES: u64 = MAX_BLOCK_BYTES - BLOCK_HEADER_BYTES as u64 - 1;
+
+const _: () = assert!(MAX_TX_BYTES < MAX_RELAY_MSG_BYTES);
+
+/// Default `policy_max_script_sig_bytes`: a 32-byte HTLC preimage plus one
+/// byte of slack. Consensus parsing caps script_sig at
+/// `MAX_SCRIPT_SIG_BYTES` and every genesis spend requires it empty, so the
+/// default binds only if a future covenant admits script_sig bytes.
+pub const DEFAULT_MAX_SCRIPT_SIG_BYTES: u64 = 33;
+
 #[derive(Debug, Clone)]
 pub struct TxPoolConfig {
     pub policy_da_surcharge_per_byte: u64,
@@ -103,6 +136,9 @@ pub struct TxPoolConfig {
     /// is unaffected.
     pub policy_reject_simplicity_pre_activation: bool,
     pub suite_context: Option<SuiteContext>,
+    /// Chain-instance consensus parameters (the sync engine's), so
+    /// admission applies the chain's coinbase maturity.
+    pub chain_params: ChainParams,
     /// Rolling local mempool floor used by the Stage C relay-fee term.
     /// Defaults to `DEFAULT_MEMPOOL_MIN_FEE_RATE`; a live rolling floor
     /// source is wired in when the Rust standard mempool policy ships.
@@ -114,6 +150,37 @@ pub struct TxPoolConfig {
     /// Zero disables only the `da_fee_floor` term; the surcharge term is
     /// governed independently by `policy_da_surcharge_per_byte`.
     pub policy_min_da_fee_rate: u64,
+    /// Relay cap on anchor-counted covenant bytes (CORE_ANCHOR and DA
+    /// commit outputs, as summed by `tx_weight_and_stats_public`) per
+    /// transaction. Stricter than consensus `MAX_ANCHOR_PAYLOAD_SIZE`;
+    /// zero disables the cap.
+    pub policy_max_relay_anchor_bytes_per_tx: u64,
+    /// Relay cap on anchor-counted outputs per transaction; zero disables
+    /// the cap.
+    pub policy_max_anchors_per_tx: u64,
+    /// Extra weight units charged per anchor byte when ranking entries for
+    /// mining selection and eviction, so anchor-heavy transactions need a
+    /// higher fee to hold their place against payments. Zero ranks on
+    /// plain weight. Admission floors are unaffected.
+    pub policy_anchor_fee_rate_multiplier: u64,
+    /// Fee rate at which outputs are priced for the dust rule
+    /// (`policy_dust::dust_threshold`); zero disables it. Relay-only.
+    /// Defaults to zero: Go relays any value, so the rule is opt-in at
+    /// `DEFAULT_DUST_RELAY_FEE_RATE` until both clients enforce it.
+    pub policy_dust_relay_fee_rate: u64,
+    /// Cap on an entry's unconfirmed ancestry, counting the entry itself;
+    /// zero disables the cap.
+    pub policy_max_ancestors: u64,
+    /// Cap on how many unconfirmed descendants (counting itself) any
+    /// resident entry may collect; zero disables the cap.
+    pub policy_max_descendants: u64,
+    /// Cap on the summed weight of an entry and its unconfirmed ancestors;
+    /// zero disables the cap.
+    pub policy_max_package_weight: u64,
+    /// Relay cap on any one input's `script_sig` bytes, checked before
+    /// inputs are resolved so it also bounds transactions whose prevouts
+    /// are not known yet (orphans); zero disables the cap.
+    pub policy_max_script_sig_bytes: u64,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -123,6 +190,13 @@ pub struct TxPoolEntry {
     pub fee: u64,
     pub weight: u64,
     pub size: usize,
+    /// Anchor-counted covenant bytes from the admission weight walk; feeds
+    /// the `policy_anchor_fee_rate_multiplier` ranking surcharge.
+    pub anchor_bytes: u64,
+    /// Output count of the transaction, recorded at admission so the
+    /// dependency graph can tell a spent output from an out-of-range
+    /// outpoint without reparsing `raw`.
+    pub output_count: usize,
     /// Caller-declared admission origin. Mirrors Go `mempoolEntry.source`
     /// (clients/go/node/mempool.go). Recorded for observability /
     /// downstream filtering; NOT consulted by `compare_entries_for_mining`
@@ -153,7 +227,11 @@ struct TxPoolSnapshotEntry {
 pub struct TxPool {
     cfg: TxPoolConfig,
     txs: HashMap<[u8; 32], TxPoolEntry>,
+    // txid -> wtxid of the resident witness variant, for entries admitted
+    // through parsing (admission and snapshot restore).
+    wtxids: HashMap<[u8; 32], [u8; 32]>,
     spenders: HashMap<Outpoint, [u8; 32]>,
+    graph: TxGraph,
     worst_heap: BinaryHeap<WorstEntryKey>,
     // Stable admission sequence per resident txid. It also tags worst_heap
     // entries for lazy stale-entry filtering.
@@ -179,9 +257,23 @@ struct AdmitPriority<'a> {
     tie: &'a [u8],
 }
 
+/// A selection candidate with the ancestors it would pull into a block.
+struct MiningPackage<'a> {
+    txid: &'a [u8; 32],
+    entry: &'a TxPoolEntry,
+    members: HashSet<[u8; 32]>,
+    /// Totals over `members`; `weight` is the summed `priority_weight`.
+    fee: u64,
+    weight: u64,
+    size: usize,
+    multiplier: u64,
+}
+
 struct CapacityPlanEntry<'a> {
     txid: [u8; 32],
     entry: &'a TxPoolEntry,
+    /// `priority_weight` of `entry`.
+    weight: u64,
     candidate: bool,
     admission_seq: u64,
 }
@@ -249,6 +341,78 @@ pub struct RelayTxMetadata {
     pub size: usize,
 }
 
+/// A transaction `TxPool::accept_tx` admitted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AcceptedTx {
+    pub txid: [u8; 32],
+    pub fee: u64,
+    pub weight: u64,
+    pub size: usize,
+}
+
+impl AcceptedTx {
+    pub fn relay_metadata(&self) -> RelayTxMetadata {
+        RelayTxMetadata {
+            fee: self.fee,
+            size: self.size,
+        }
+    }
+}
+
+/// Why an admission was refused, splitting `Rejected` into consensus
+/// failures (the tx is invalid at the next height) and node policy.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TxRejectClass {
+    Consensus,
+    Policy,
+    Conflict,
+    Unavailable,
+}
+
+impl TxRejectClass {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Consensus => "consensus",
+            Self::Policy => "policy",
+            Self::Conflict => "conflict",
+            Self::Unavailable => "unavailable",
+        }
+    }
+}
+
+impl TxPoolAdmitError {
+    /// Consensus error code (`TX_ERR_*` / `BLOCK_ERR_*`) behind a
+    /// `Rejected` admission, if consensus validation produced it.
+    pub fn consensus_code(&self) -> Option<&str> {
+        if self.kind != TxPoolAdmitErrorKind::Rejected {
+            return None;
+        }
+        let message = self
+            .message
+            .strip_prefix("transaction rejected: ")
+            .unwrap_or(&self.message);
+        let code = message.split(':').next()?.trim();
+        (code.starts_with("TX_ERR_") || code.starts_with("BLOCK_ERR_")).then_some(code)
+    }
+
+    /// Whether the transaction spends an output that is neither confirmed
+    /// nor in the pool, i.e. it may be an orphan.
+    pub fn is_missing_utxo(&self) -> bool {
+        self.consensus_code() == Some("TX_ERR_MISSING_UTXO")
+    }
+
+    pub fn reject_class(&self) -> TxRejectClass {
+        match self.kind {
+            TxPoolAdmitErrorKind::Conflict => TxRejectClass::Conflict,
+            TxPoolAdmitErrorKind::Unavailable => TxRejectClass::Unavailable,
+            TxPoolAdmitErrorKind::Rejected if self.consensus_code().is_some() => {
+                TxRejectClass::Consensus
+            }
+            TxPoolAdmitErrorKind::Rejected => TxRejectClass::Policy,
+        }
+    }
+}
+
 impl std::fmt::Display for TxPoolAdmitError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", self.message)
@@ -267,7 +431,9 @@ impl TxPool {
         Self {
             cfg,
             txs: HashMap::new(),
+            wtxids: HashMap::new(),
             spenders: HashMap::new(),
+            graph: TxGraph::default(),
             worst_heap: BinaryHeap::new(),
             heap_seqs: HashMap::new(),
             next_heap_id: 0,
@@ -412,11 +578,15 @@ impl TxPool {
             worst_heap.push(WorstEntryKey {
                 txid: item.txid,
                 fee: item.entry.fee,
-                weight: item.entry.weight,
+                weight: self.priority_weight(&item.entry),
                 heap_id,
             });
             txs.insert(item.txid, item.entry.clone());
         }
+        let wtxids = wtxids
+            .into_iter()
+            .map(|(wtxid, txid)| (txid, wtxid))
+            .collect();
 
         if snapshot.used_bytes != used_bytes {
             return Err(rejected(format!(
@@ -436,7 +606,9 @@ impl TxPool {
 
         let floor = snapshot.current_mempool_min_fee_rate;
         self.cfg.policy_current_mempool_min_fee_rate = floor.max(DEFAULT_MEMPOOL_MIN_FEE_RATE);
+        self.graph = TxGraph::rebuild(&txs);
         self.txs = txs;
+        self.wtxids = wtxids;
         self.spenders = spenders;
         self.heap_seqs = heap_seqs;
         self.worst_heap = worst_heap;
@@ -445,11 +617,12 @@ impl TxPool {
         Ok(())
     }
 
-    /// Returns the txids of every transaction currently in the pool.
-    /// The ordering of the returned vector is not guaranteed to be stable
-    /// between calls; callers that require a deterministic order must sort.
+    /// Returns the txids of every transaction currently in the pool, sorted
+    /// ascending so RPC listings never expose `HashMap` order.
     pub fn all_txids(&self) -> Vec<[u8; 32]> {
-        self.txs.keys().copied().collect()
+        let mut txids: Vec<[u8; 32]> = self.txs.keys().copied().collect();
+        txids.sort_unstable();
+        txids
     }
 
     /// Returns a defensive clone of the raw transaction bytes for a pool entry
@@ -458,6 +631,30 @@ impl TxPool {
         self.txs.get(txid).map(|entry| entry.raw.clone())
     }
 
+    /// Share of the pool byte budget held by DA commit/chunk txs, in percent.
+    pub fn da_fill_pct(&self) -> u64 {
+        let da_bytes: usize = self
+            .txs
+            .values()
+            .filter(|entry| {
+                matches!(
+                    crate::da_relay::relay_da_tx_kind_prefix(&entry.raw),
+                    Some(0x01 | 0x02)
+                )
+            })
+            .map(|entry| entry.size)
+            .sum();
+        (da_bytes as u64 * 100)
+            .checked_div(self.max_bytes as u64)
+            .unwrap_or(0)
+    }
+
+    /// Returns the fee recorded on the pool entry with the given txid.
+    /// Returns `None` if no matching entry is present.
+    pub fn entry_fee(&self, txid: &[u8; 32]) -> Option<u64> {
+        self.txs.get(txid).map(|entry| entry.fee)
+    }
+
     /// Returns the caller-declared `TxSource` recorded on the pool entry
     /// with the given txid. Returns `None` if no matching entry is
     /// present. Source is observability metadata only and does not
@@ -480,6 +677,8 @@ impl TxPool {
                 fee: 0,
                 weight: size as u64,
                 size,
+                anchor_bytes: 0,
+                output_count: 0,
                 source: TxSource::Local,
             },
         );
@@ -491,15 +690,28 @@ impl TxPool {
         self.txs.contains_key(txid)
     }
 
+    /// Returns the wtxid of the witness variant held for `txid`. Block
+    /// templates carry these exact bytes, so the witness merkle root commits
+    /// to the same wtxid the pool reports.
+    pub fn wtxid_of(&self, txid: &[u8; 32]) -> Option<[u8; 32]> {
+        self.wtxids.get(txid).copied()
+    }
+
     pub fn select_transactions(&self, max_count: usize, max_bytes: usize) -> Vec<Vec<u8>> {
         self.select_transactions_with_filter(max_count, max_bytes, |_| false)
     }
 
-    /// Sorted mining selection that drops any raw for which `filter` returns true
-    /// before the count/byte caps (mirror of Go `pickMinerCandidateEntries`, where the
-    /// `isMiningDATxRaw` skip precedes both caps). `select_transactions` is the
-    /// unfiltered case; the miner passes `is_mining_da_tx_raw` to exclude individual
-    /// DA txs from flat candidate selection.
+    /// Package-aware mining selection that drops any raw for which `filter`
+    /// returns true before the count/byte caps (mirror of Go
+    /// `pickMinerCandidateEntries`, where the `isMiningDATxRaw` skip precedes
+    /// both caps); a dropped entry takes everything spending it along.
+    /// Entries are ranked by the fee rate of their package, the entry plus
+    /// its not-yet-selected unconfirmed ancestors, and a package is emitted
+    /// ancestors first, so a high-fee child pulls a low-fee parent in with
+    /// it (child pays for parent). Without in-pool parents this is the flat
+    /// `compare_entries_for_mining` order. `select_transactions` is the
+    /// unfiltered case; the miner passes `is_mining_da_tx_raw` to exclude
+    /// individual DA txs from flat candidate selection.
     pub fn select_transactions_with_filter(
         &self,
         max_count: usize,
@@ -509,26 +721,97 @@ impl TxPool {
         if max_count == 0 || max_bytes == 0 {
             return Vec::new();
         }
-        let mut entries: Vec<(&[u8; 32], &TxPoolEntry)> = self.txs.iter().collect();
-        entries.sort_by(compare_entries_for_mining);
-        let mut selected = Vec::with_capacity(entries.len().min(max_count));
+        let mut blocked = HashSet::new();
+        for (txid, entry) in &self.txs {
+            if filter(&entry.raw) {
+                blocked.insert(*txid);
+                blocked.extend(self.graph.descendants(txid));
+            }
+        }
+        let mut candidates: Vec<[u8; 32]> = self
+            .txs
+            .keys()
+            .filter(|txid| !blocked.contains(*txid))
+            .copied()
+            .collect();
+        let mut included = HashSet::new();
+        let mut selected = Vec::with_capacity(candidates.len().min(max_count));
         let mut used_bytes = 0usize;
-        for entry in entries {
-            if filter(&entry.1.raw) {
-                continue;
+        while selected.len() < max_count && !candidates.is_empty() {
+            let mut best: Option<(usize, MiningPackage<'_>)> = None;
+            for (index, txid) in candidates.iter().enumerate() {
+                let Some(package) = self.mining_package(txid, &included) else {
+                    continue;
+                };
+                let better = best.as_ref().is_none_or(|(_, current)| {
+                    compare_mining_packages(&package, current) == Ordering::Less
+                });
+                if better {
+                    best = Some((index, package));
+                }
             }
-            if selected.len() >= max_count {
+            let Some((index, package)) = best else {
                 break;
-            }
-            if entry.1.size > max_bytes.saturating_sub(used_bytes) {
+            };
+            candidates.swap_remove(index);
+            if package.members.len() > max_count - selected.len()
+                || package.size > max_bytes.saturating_sub(used_bytes)
+            {
                 continue;
             }
-            selected.push(entry.1.raw.clone());
-            used_bytes += entry.1.size;
+            used_bytes += package.size;
+            for member in self.graph.topological(package.members) {
+                if let Some(entry) = self.txs.get(&member) {
+                    selected.push(entry.raw.clone());
+                }
+                included.insert(member);
+            }
+            candidates.retain(|txid| !included.contains(txid));
         }
         selected
     }
 
+    /// `txid` plus its unconfirmed ancestors not yet in `included`.
+    fn mining_package(
+        &self,
+        txid: &[u8; 32],
+        included: &HashSet<[u8; 32]>,
+    ) -> Option<MiningPackage<'_>> {
+        let (txid, entry) = self.txs.get_key_value(txid)?;
+        let multiplier = self.cfg.policy_anchor_fee_rate_multiplier;
+        let node = self.graph.node(txid);
+        let mut members = HashSet::from([*txid]);
+        if let Some(node) = node {
+            members.extend(node.ancestors.difference(included).copied());
+        }
+        let member_entries = || members.iter().filter_map(|member| self.txs.get(member));
+        let (fee, weight) = match node.filter(|node| node.ancestors.is_disjoint(included)) {
+            // Nothing selected yet from this ancestry: the cached totals
+            // are the package totals.
+            Some(node) => (
+                node.ancestor_fee,
+                node.ancestor_weight
+                    .saturating_add(node.ancestor_anchor_bytes.saturating_mul(multiplier)),
+            ),
+            None => member_entries().fold((0u64, 0u64), |(fee, weight), member| {
+                (
+                    fee.saturating_add(member.fee),
+                    weight.saturating_add(priority_weight(member, multiplier)),
+                )
+            }),
+        };
+        let size = member_entries().map(|member| member.size).sum();
+        Some(MiningPackage {
+            txid,
+            entry,
+            members,
+            fee,
+            weight,
+            size,
+            multiplier,
+        })
+    }
+
     /// Backward-compatible admission entry that defaults the caller-
     /// declared source to `TxSource::Local`. New producer wiring should
     /// call `add_tx_with_source` directly with the appropriate variant
@@ -587,11 +870,8 @@ impl TxPool {
         chain_id: [u8; 32],
         source: TxSource,
     ) -> Result<([u8; 32], RelayTxMetadata), TxPoolAdmitError> {
-        let (tx, txid, _wtxid, consumed) =
-            parse_tx(tx_bytes).map_err(|err| rejected(format!("transaction rejected: {err}")))?;
-        if consumed != tx_bytes.len() {
-            return Err(rejected("transaction rejected: non-canonical tx bytes"));
-        }
+        let (tx, txid, wtxid) = parse_tx_bytes_limited_with_wtxid(tx_bytes)?;
+        reject_script_sig_policy(&tx, &self.cfg).map_err(rejected)?;
         let inputs: Vec<Outpoint> = tx
             .inputs
             .iter()
@@ -607,10 +887,14 @@ impl TxPool {
         // final `validate_fee_floor` call. The same `(weight, da_bytes)`
         // tuple anchors both the DA-side classification and the
         // rolling-floor classification so they cannot diverge.
-        let (weight, da_bytes, _) = tx_weight_and_stats_public(&tx)
+        let (weight, da_bytes, anchor_bytes) = tx_weight_and_stats_public(&tx)
             .map_err(|err| rejected(format!("transaction rejected: {err}")))?;
 
         let next_height = next_block_height(chain_state)?;
+        // Inputs spending in-pool parents resolve against the parents'
+        // outputs as if they confirmed in the next block.
+        let pool_utxos = self.in_pool_input_utxos(&tx, &chain_state.utxos, next_height);
+        let utxos = pool_utxos.as_ref().unwrap_or(&chain_state.utxos);
         let block_mtp = next_block_mtp(block_store, next_height)?;
         let (rotation, registry): (Option<&dyn RotationProvider>, Option<&SuiteRegistry>) =
             match self.cfg.suite_context.as_ref() {
@@ -642,10 +926,11 @@ impl TxPool {
         // post-consensus path.
         cheap_fee_floor_precheck(
             &tx,
-            &chain_state.utxos,
+            utxos,
             weight,
             self.cfg.policy_current_mempool_min_fee_rate,
             next_height,
+            self.cfg.chain_params.coinbase_maturity,
             rotation,
             registry,
         )?;
@@ -663,31 +948,28 @@ impl TxPool {
         // reject path untouched — full policyInputSnapshot parity for
         // every tx is a separate general-policy surface, not this gate.
         if self.cfg.policy_reject_simplicity_pre_activation
-            && covenant_policy_kind(&tx, &chain_state.utxos, COV_TYPE_CORE_SIMPLICITY).is_some()
+            && covenant_policy_kind(&tx, utxos, COV_TYPE_CORE_SIMPLICITY).is_some()
         {
-            reject_missing_policy_inputs(&tx, &chain_state.utxos)?;
-            if let Some(reason) = reject_core_simplicity_pre_activation(
-                &tx,
-                &chain_state.utxos,
-                next_height,
-                rotation,
-            )
-            .map_err(rejected)?
+            reject_missing_policy_inputs(&tx, utxos)?;
+            if let Some(reason) =
+                reject_core_simplicity_pre_activation(&tx, utxos, next_height, rotation)
+                    .map_err(rejected)?
             {
                 return Err(rejected(reason));
             }
         }
         let (_, summary) =
-            apply_non_coinbase_tx_basic_update_with_mtp_and_core_ext_profiles_and_suite_context(
+            apply_non_coinbase_tx_basic_update_with_mtp_and_core_ext_profiles_and_suite_context_and_maturity(
                 &tx,
                 txid,
-                &chain_state.utxos,
+                utxos,
                 next_height,
                 block_mtp,
                 block_mtp,
                 chain_id,
                 rotation,
                 registry,
+                self.cfg.chain_params.coinbase_maturity,
             )
             .map_err(|err| rejected(format!("transaction rejected: {err}")))?;
         // RUB-18/RUB-162 ordering: run post-consensus policy before
@@ -704,23 +986,27 @@ impl TxPool {
         // single statement instead of per-arg lines (multi-line calls
         // leave several args marked "Not covered" even when the call
         // executes). Locals shorten the argument list.
-        let utxos = &chain_state.utxos;
         let cfg = &self.cfg;
+        reject_anchor_relay_policy(&tx, anchor_bytes, cfg).map_err(rejected)?;
+        reject_dust_outputs(&tx, cfg.policy_dust_relay_fee_rate).map_err(rejected)?;
         #[rustfmt::skip]
         let policy_result = apply_post_consensus_policy_without_floor(&tx, utxos, weight, da_bytes, next_height, cfg);
         policy_result?;
 
-        if self.txs.contains_key(&txid) {
-            return Err(conflict("tx already in mempool"));
-        }
-        for input in &inputs {
-            if let Some(existing) = self.spenders.get(input) {
-                return Err(conflict(format!(
-                    "mempool double-spend conflict with {}",
-                    hex::encode(existing)
-                )));
+        // A witness variant of a resident spends the same inputs, so the
+        // only spender it meets is the resident it replaces.
+        let replaces_variant = self.resolve_witness_variant(&tx, txid, wtxid)?;
+        if !replaces_variant {
+            for input in &inputs {
+                if let Some(existing) = self.spenders.get(input) {
+                    return Err(conflict(format!(
+                        "mempool double-spend conflict with {}",
+                        hex::encode(existing)
+                    )));
+                }
             }
         }
+        self.check_package_limits(&inputs, weight)?;
         validate_fee_floor(
             summary.fee,
             weight,
@@ -733,6 +1019,8 @@ impl TxPool {
             fee: summary.fee,
             weight,
             size: tx_bytes.len(),
+            anchor_bytes,
+            output_count: tx.outputs.len(),
             source,
         };
 
@@ -740,11 +1028,25 @@ impl TxPool {
         // policy, and rolling-floor checks. The low-water byte cap is an
         // eviction target under pressure, not a hard upper bound on a
         // fitting candidate.
-        for evicted_txid in self.capacity_eviction_plan(txid, &entry)? {
+        let resident = replaces_variant
+            .then(|| self.take_witness_variant(&txid))
+            .flatten();
+        let plan = match self.capacity_eviction_plan(txid, &entry) {
+            Ok(plan) => plan,
+            Err(err) => {
+                if let Some((resident, resident_wtxid)) = resident {
+                    self.insert_entry(txid, resident);
+                    self.wtxids.insert(txid, resident_wtxid);
+                }
+                return Err(err);
+            }
+        };
+        for evicted_txid in plan {
             self.remove_entry(&evicted_txid);
         }
 
         self.insert_entry(txid, entry);
+        self.wtxids.insert(txid, wtxid);
         Ok((
             txid,
             RelayTxMetadata {
@@ -754,6 +1056,32 @@ impl TxPool {
         ))
     }
 
+    /// Local submission pipeline: parse, policy, consensus validation at
+    /// the next block's height and MTP, then insert. Shared by every local
+    /// submit surface so they report the same outcome for the same bytes.
+    pub fn accept_tx(
+        &mut self,
+        tx_bytes: &[u8],
+        chain_state: &ChainState,
+        block_store: Option<&BlockStore>,
+        chain_id: [u8; 32],
+    ) -> Result<AcceptedTx, TxPoolAdmitError> {
+        let (txid, meta) = self.add_tx_with_source(
+            tx_bytes,
+            chain_state,
+            block_store,
+            chain_id,
+            TxSource::Local,
+        )?;
+        let weight = self.txs.get(&txid).map_or(0, |entry| entry.weight);
+        Ok(AcceptedTx {
+            txid,
+            fee: meta.fee,
+            weight,
+            size: meta.size,
+        })
+    }
+
     pub fn relay_metadata_for_bytes(
         &self,
         tx_bytes: &[u8],
@@ -765,7 +1093,8 @@ impl TxPool {
     }
 
     /// Remove transactions by txid (e.g. after block confirmation).
-    /// Cleans up the spender index for any removed entries.
+    /// Cleans up the spender index for any removed entries. In-pool
+    /// children of a removed entry are kept: their parent is now confirmed.
     pub fn evict_txids(&mut self, txids: &[[u8; 32]]) {
         for txid in txids {
             self.remove_entry(txid);
@@ -793,8 +1122,57 @@ impl TxPool {
         if conflicting.is_empty() {
             return;
         }
-        let txids: Vec<[u8; 32]> = conflicting.into_iter().collect();
-        self.evict_txids(&txids);
+        // A conflicting entry can never confirm, and neither can anything
+        // spending its outputs.
+        for txid in conflicting {
+            self.remove_entry_with_descendants(&txid);
+        }
+    }
+
+    /// Same-txid admission: a resident with the same wtxid (or one whose
+    /// wtxid is unknown) is a plain duplicate; a different witness variant
+    /// replaces it only when `witness_variant_preferred` ranks it first.
+    /// Returns whether the candidate replaces a resident.
+    fn resolve_witness_variant(
+        &self,
+        tx: &Tx,
+        txid: [u8; 32],
+        wtxid: [u8; 32],
+    ) -> Result<bool, TxPoolAdmitError> {
+        let Some(resident) = self.txs.get(&txid) else {
+            return Ok(false);
+        };
+        let resident_wtxid = match self.wtxids.get(&txid) {
+            Some(resident_wtxid) if *resident_wtxid != wtxid => *resident_wtxid,
+            _ => return Err(conflict("tx already in mempool")),
+        };
+        let Ok((resident_tx, _, _, _)) = parse_tx(&resident.raw) else {
+            return Err(conflict("tx already in mempool"));
+        };
+        let registry = self
+            .cfg
+            .suite_context
+            .as_ref()
+            .map(|ctx| ctx.registry.as_ref().clone())
+            .unwrap_or_else(SuiteRegistry::default_registry);
+        if witness_variant_preferred(tx, &wtxid, &resident_tx, &resident_wtxid, &registry) {
+            return Ok(true);
+        }
+        Err(conflict(format!(
+            "witness variant of in-mempool tx {} rejected: resident wtxid {} preferred",
+            hex::encode(txid),
+            hex::encode(resident_wtxid)
+        )))
+    }
+
+    /// Removes a resident about to be replaced by a witness variant,
+    /// returning it so a failed replacement can put it back. Its in-pool
+    /// children stay: the variant has the same txid and outputs.
+    fn take_witness_variant(&mut self, txid: &[u8; 32]) -> Option<(TxPoolEntry, [u8; 32])> {
+        let entry = self.txs.get(txid)?.clone();
+        let wtxid = *self.wtxids.get(txid)?;
+        self.remove_entry(txid);
+        Some((entry, wtxid))
     }
 
     fn insert_entry(&mut self, txid: [u8; 32], entry: TxPoolEntry) {
@@ -808,23 +1186,132 @@ impl TxPool {
         self.worst_heap.push(WorstEntryKey {
             txid,
             fee: entry.fee,
-            weight: entry.weight,
+            weight: self.priority_weight(&entry),
             heap_id,
         });
         self.txs.insert(txid, entry);
+        self.graph.insert(txid, &self.txs, &self.spenders);
     }
 
+    /// Removes one entry. Its in-pool children stay and now spend what is
+    /// treated as confirmed; use `remove_entry_with_descendants` when the
+    /// entry itself is being dropped.
     fn remove_entry(&mut self, txid: &[u8; 32]) {
         if let Some(entry) = self.txs.remove(txid) {
+            self.wtxids.remove(txid);
             self.heap_seqs.remove(txid);
             self.used_bytes = self.used_bytes.saturating_sub(entry.size);
             for input in &entry.inputs {
                 self.spenders.remove(input);
             }
+            self.graph.remove(txid, &self.txs);
         }
         self.compact_worst_heap_if_needed();
     }
 
+    /// Removes an entry together with every in-pool transaction spending
+    /// its outputs, children first.
+    fn remove_entry_with_descendants(&mut self, txid: &[u8; 32]) {
+        for descendant in self.graph.descendants(txid).iter().rev() {
+            self.remove_entry(descendant);
+        }
+        self.remove_entry(txid);
+    }
+
+    /// Input view for a transaction spending in-pool parents: each input
+    /// resolves to the chain UTXO or to the parent output, created at
+    /// `next_height` by a non-coinbase. `None` when no input spends a pool
+    /// entry, so the common case validates against the chain set directly.
+    fn in_pool_input_utxos(
+        &self,
+        tx: &Tx,
+        chain_utxos: &OutpointMap<UtxoEntry>,
+        next_height: u64,
+    ) -> Option<OutpointMap<UtxoEntry>> {
+        let mut parents = HashMap::new();
+        for input in &tx.inputs {
+            if let Some(entry) = self.txs.get(&input.prev_txid) {
+                if let Ok((parent, _)) = parse_tx_bytes_limited(&entry.raw) {
+                    parents.insert(input.prev_txid, parent);
+                }
+            }
+        }
+        if parents.is_empty() {
+            return None;
+        }
+        let mut utxos = OutpointMap::default();
+        for input in &tx.inputs {
+            let outpoint = Outpoint {
+                txid: input.prev_txid,
+                vout: input.prev_vout,
+            };
+            let resolved = match parents.get(&input.prev_txid) {
+                Some(parent) => parent
+                    .outputs
+                    .get(input.prev_vout as usize)
+                    .filter(|out| {
+                        out.covenant_type != COV_TYPE_ANCHOR
+                            && out.covenant_type != COV_TYPE_DA_COMMIT
+                    })
+                    .map(|out| UtxoEntry {
+                        value: out.value,
+                        covenant_type: out.covenant_type,
+                        covenant_data: out.covenant_data.clone(),
+                        creation_height: next_height,
+                        created_by_coinbase: false,
+                    }),
+                None => chain_utxos.get(&outpoint).cloned(),
+            };
+            if let Some(utxo) = resolved {
+                utxos.insert(outpoint, utxo);
+            }
+        }
+        Some(utxos)
+    }
+
+    /// Ancestor and descendant limits for a candidate spending `inputs`.
+    fn check_package_limits(
+        &self,
+        inputs: &[Outpoint],
+        weight: u64,
+    ) -> Result<(), TxPoolAdmitError> {
+        let parents = in_pool_parents(inputs, &self.txs);
+        if parents.is_empty() {
+            return Ok(());
+        }
+        let ancestors = self.graph.ancestors_of_parents(&parents);
+        let max_ancestors = self.cfg.policy_max_ancestors;
+        let count = ancestors.len() as u64 + 1;
+        if max_ancestors != 0 && count > max_ancestors {
+            return Err(rejected(format!(
+                "too many unconfirmed ancestors (count={count} max={max_ancestors}) (policy)"
+            )));
+        }
+        let max_weight = self.cfg.policy_max_package_weight;
+        let package_weight = ancestors
+            .iter()
+            .filter_map(|txid| self.txs.get(txid))
+            .fold(weight, |sum, entry| sum.saturating_add(entry.weight));
+        if max_weight != 0 && package_weight > max_weight {
+            return Err(rejected(format!(
+                "ancestor package too heavy (weight={package_weight} max={max_weight}) (policy)"
+            )));
+        }
+        let max_descendants = self.cfg.policy_max_descendants;
+        if max_descendants != 0 {
+            for ancestor in &ancestors {
+                let count = self.graph.descendants(ancestor).len() as u64 + 2;
+                if count > max_descendants {
+                    return Err(rejected(format!(
+                        "too many unconfirmed descendants of {} (count={count} max={max_descendants}) (policy)",
+                        hex::encode(ancestor)
+                    )));
+                }
+            }
+        }
+        Ok(())
+    }
+
     fn capacity_eviction_plan(
         &self,
         candidate_txid: [u8; 32],
@@ -894,6 +1381,7 @@ impl TxPool {
             plan_pool.push(CapacityPlanEntry {
                 txid: *txid,
                 entry,
+                weight: self.priority_weight(entry),
                 candidate: false,
                 admission_seq,
             });
@@ -901,27 +1389,41 @@ impl TxPool {
         plan_pool.push(CapacityPlanEntry {
             txid: candidate_txid,
             entry: candidate,
+            weight: self.priority_weight(candidate),
             candidate: true,
             admission_seq: 0,
         });
 
+        // Evicting an entry evicts everything spending its outputs, so the
+        // candidate cannot survive the eviction of one of its ancestors.
+        let candidate_ancestors = self
+            .graph
+            .ancestors_of_parents(&in_pool_parents(&candidate.inputs, &self.txs));
         let mut evicted = Vec::new();
         while (total_count > self.max_transactions || total_bytes > target_bytes)
             && !plan_pool.is_empty()
         {
-            let worst_index = worst_capacity_plan_index(&plan_pool, ordering);
-            let worst = plan_pool.remove(worst_index);
-            if worst.candidate {
+            let worst = &plan_pool[worst_capacity_plan_index(&plan_pool, ordering)];
+            if worst.candidate || candidate_ancestors.contains(&worst.txid) {
                 return Err(unavailable(
                     "mempool capacity candidate rejected by eviction ordering",
                 ));
             }
-            if total_bytes < worst.entry.size {
-                return Err(unavailable("mempool eviction byte accounting underflow"));
+            let mut subtree = self.graph.descendants(&worst.txid);
+            subtree.reverse();
+            subtree.push(worst.txid);
+            for txid in subtree {
+                let Some(index) = plan_pool.iter().position(|item| item.txid == txid) else {
+                    continue;
+                };
+                let item = plan_pool.remove(index);
+                if total_bytes < item.entry.size {
+                    return Err(unavailable("mempool eviction byte accounting underflow"));
+                }
+                total_count = total_count.saturating_sub(1);
+                total_bytes -= item.entry.size;
+                evicted.push(txid);
             }
-            total_count = total_count.saturating_sub(1);
-            total_bytes -= worst.entry.size;
-            evicted.push(worst.txid);
         }
 
         if total_count > self.max_transactions || total_bytes > self.max_bytes {
@@ -933,6 +1435,12 @@ impl TxPool {
         Ok(evicted)
     }
 
+    /// Weight an entry is ranked by for mining and eviction: its weight
+    /// plus the configured per-anchor-byte surcharge.
+    fn priority_weight(&self, entry: &TxPoolEntry) -> u64 {
+        priority_weight(entry, self.cfg.policy_anchor_fee_rate_multiplier)
+    }
+
     fn effective_low_water_bytes(&self) -> usize {
         if self.low_water_bytes > 0 || self.max_bytes == 0 {
             return self.low_water_bytes;
@@ -1023,7 +1531,7 @@ impl TxPool {
             rebuilt.push(WorstEntryKey {
                 txid: *txid,
                 fee: entry.fee,
-                weight: entry.weight,
+                weight: priority_weight(entry, self.cfg.policy_anchor_fee_rate_multiplier),
                 heap_id,
             });
         }
@@ -1075,7 +1583,7 @@ fn validate_txpool_snapshot_entry(
             )));
         }
     }
-    let (weight, _, _) = tx_weight_and_stats_public(&tx)
+    let (weight, _, anchor_bytes) = tx_weight_and_stats_public(&tx)
         .map_err(|err| rejected(format!("invalid txpool snapshot entry weight: {err}")))?;
     if entry.weight != weight {
         return Err(rejected(format!(
@@ -1085,6 +1593,14 @@ fn validate_txpool_snapshot_entry(
             hex::encode(txid)
         )));
     }
+    if entry.anchor_bytes != anchor_bytes {
+        return Err(rejected(format!(
+            "txpool snapshot entry anchor bytes mismatch: entry={} computed={} txid={}",
+            entry.anchor_bytes,
+            anchor_bytes,
+            hex::encode(txid)
+        )));
+    }
     let inputs: Vec<Outpoint> = tx
         .inputs
         .iter()
@@ -1117,11 +1633,8 @@ pub(crate) fn relay_metadata(
     chain_id: [u8; 32],
     cfg: &TxPoolConfig,
 ) -> Result<RelayTxMetadata, TxPoolAdmitError> {
-    let (tx, txid, _wtxid, consumed) =
-        parse_tx(tx_bytes).map_err(|err| rejected(format!("transaction rejected: {err}")))?;
-    if consumed != tx_bytes.len() {
-        return Err(rejected("transaction rejected: non-canonical tx bytes"));
-    }
+    let (tx, txid) = parse_tx_bytes_limited(tx_bytes)?;
+    reject_script_sig_policy(&tx, cfg).map_err(rejected)?;
 
     let next_height = next_block_height(chain_state)?;
     let block_mtp = next_block_mtp(block_store, next_height)?;
@@ -1150,7 +1663,7 @@ pub(crate) fn relay_metadata(
         }
     }
     let (_, summary) =
-        apply_non_coinbase_tx_basic_update_with_mtp_and_core_ext_profiles_and_suite_context(
+        apply_non_coinbase_tx_basic_update_with_mtp_and_core_ext_profiles_and_suite_context_and_maturity(
             &tx,
             txid,
             &chain_state.utxos,
@@ -1160,6 +1673,7 @@ pub(crate) fn relay_metadata(
             chain_id,
             rotation,
             registry,
+            cfg.chain_params.coinbase_maturity,
         )
         .map_err(|err| rejected(format!("transaction rejected: {err}")))?;
     // RUB-162/RUB-197 relay drift-prevention: relay must run the
@@ -1171,11 +1685,13 @@ pub(crate) fn relay_metadata(
     // RUB-167 single-walk invariant: one `tx_weight_and_stats_public`
     // call here feeds both the DA-side and the rolling-floor
     // classifications inside the wrapper.
-    let (weight, da_bytes, _) = tx_weight_and_stats_public(&tx)
+    let (weight, da_bytes, anchor_bytes) = tx_weight_and_stats_public(&tx)
         .map_err(|err| rejected(format!("transaction rejected: {err}")))?;
     // `#[rustfmt::skip]` keeps the call on one line for tarpaulin /
     // Codacy diff-coverage attribution (mirror of admit_with_metadata).
     let utxos = &chain_state.utxos;
+    reject_anchor_relay_policy(&tx, anchor_bytes, cfg).map_err(rejected)?;
+    reject_dust_outputs(&tx, cfg.policy_dust_relay_fee_rate).map_err(rejected)?;
     #[rustfmt::skip]
     let policy_result = apply_post_consensus_policy_with_floor(&tx, utxos, summary.fee, weight, da_bytes, next_height, cfg);
     policy_result?;
@@ -1231,7 +1747,7 @@ pub(crate) fn relay_metadata(
 ///     `apply_policy`.
 fn apply_post_consensus_policy_with_floor(
     tx: &rubin_consensus::Tx,
-    utxos: &HashMap<Outpoint, rubin_consensus::UtxoEntry>,
+    utxos: &OutpointMap<rubin_consensus::UtxoEntry>,
     fee: u64,
     weight: u64,
     da_bytes: u64,
@@ -1244,7 +1760,7 @@ fn apply_post_consensus_policy_with_floor(
 
 fn apply_post_consensus_policy_without_floor(
     tx: &rubin_consensus::Tx,
-    utxos: &HashMap<Outpoint, rubin_consensus::UtxoEntry>,
+    utxos: &OutpointMap<rubin_consensus::UtxoEntry>,
     weight: u64,
     da_bytes: u64,
     next_height: u64,
@@ -1270,8 +1786,17 @@ impl Default for TxPoolConfig {
             // the CORE_SIMPLICITY pre-activation guardrail defaults ON.
             policy_reject_simplicity_pre_activation: true,
             suite_context: None,
+            chain_params: ChainParams::V1,
             policy_current_mempool_min_fee_rate: DEFAULT_MEMPOOL_MIN_FEE_RATE,
             policy_min_da_fee_rate: DEFAULT_MIN_DA_FEE_RATE,
+            policy_max_relay_anchor_bytes_per_tx: 0,
+            policy_max_anchors_per_tx: 0,
+            policy_anchor_fee_rate_multiplier: 0,
+            policy_dust_relay_fee_rate: 0,
+            policy_max_ancestors: DEFAULT_MAX_ANCESTORS,
+            policy_max_descendants: DEFAULT_MAX_DESCENDANTS,
+            policy_max_package_weight: DEFAULT_MAX_PACKAGE_WEIGHT,
+            policy_max_script_sig_bytes: DEFAULT_MAX_SCRIPT_SIG_BYTES,
         }
     }
 }
@@ -1344,6 +1869,70 @@ fn conflict(message: impl Into<String>) -> TxPoolAdmitError {
     }
 }
 
+/// Parses a standalone relay/mempool transaction: `MAX_TX_BYTES` is checked
+/// before the bytes are touched, and the parse must consume all of them.
+pub(crate) fn parse_tx_bytes_limited(tx_bytes: &[u8]) -> Result<(Tx, [u8; 32]), TxPoolAdmitError> {
+    parse_tx_bytes_limited_with_wtxid(tx_bytes).map(|(tx, txid, _)| (tx, txid))
+}
+
+/// [`parse_tx_bytes_limited`] that also returns the wtxid.
+fn parse_tx_bytes_limited_with_wtxid(
+    tx_bytes: &[u8],
+) -> Result<(Tx, [u8; 32], [u8; 32]), TxPoolAdmitError> {
+    if tx_bytes.len() as u64 > MAX_TX_BYTES {
+        return Err(rejected(format!(
+            "transaction rejected: tx bytes exceed MAX_TX_BYTES: {} > {MAX_TX_BYTES}",
+            tx_bytes.len()
+        )));
+    }
+    let (tx, txid, wtxid, consumed) =
+        parse_tx(tx_bytes).map_err(|err| rejected(format!("transaction rejected: {err}")))?;
+    if consumed != tx_bytes.len() {
+        return Err(rejected("transaction rejected: non-canonical tx bytes"));
+    }
+    Ok((tx, txid, wtxid))
+}
+
+/// Relay policy for witness variants of one txid: every native-suite witness
+/// item has its suite's exact shape and commits to the whole transaction
+/// (`SIGHASH_ALL` trailer). Sentinel and structural-carrier items carry no
+/// sighash choice and always pass. Consensus accepts the other sighash
+/// types; this only ranks variants against each other.
+pub fn tx_witness_canonical(tx: &Tx, registry: &SuiteRegistry) -> bool {
+    tx.witness.iter().all(|item| {
+        if item.suite_id == SUITE_ID_SENTINEL
+            || is_structural_witness_carrier_suite_id(item.suite_id)
+        {
+            return true;
+        }
+        registry.lookup(item.suite_id).is_some_and(|params| {
+            params
+                .has_canonical_witness_shape(item.pubkey.len() as u64, item.signature.len() as u64)
+                && item.signature.last() == Some(&SIGHASH_ALL)
+        })
+    })
+}
+
+/// Ranks two witness variants of the same txid: the `tx_witness_canonical`
+/// one wins, then the lower wtxid, so every node keeps the same bytes
+/// whatever order the variants arrive in.
+pub fn witness_variant_preferred(
+    candidate: &Tx,
+    candidate_wtxid: &[u8; 32],
+    resident: &Tx,
+    resident_wtxid: &[u8; 32],
+    registry: &SuiteRegistry,
+) -> bool {
+    match (
+        tx_witness_canonical(candidate, registry),
+        tx_witness_canonical(resident, registry),
+    ) {
+        (true, false) => true,
+        (false, true) => false,
+        _ => candidate_wtxid < resident_wtxid,
+    }
+}
+
 fn rejected(message: impl Into<String>) -> TxPoolAdmitError {
     TxPoolAdmitError {
         kind: TxPoolAdmitErrorKind::Rejected,
@@ -1442,12 +2031,14 @@ fn validate_fee_floor(fee: u64, weight: u64, cfg_floor: u64) -> Result<(), TxPoo
 /// `validate_fee_floor` surface). Error class is
 /// `TxPoolAdmitErrorKind::Unavailable` so callers may retry once the
 /// rolling floor drops.
+#[allow(clippy::too_many_arguments)]
 fn cheap_fee_floor_precheck(
     tx: &rubin_consensus::Tx,
-    utxos: &HashMap<Outpoint, rubin_consensus::UtxoEntry>,
+    utxos: &OutpointMap<rubin_consensus::UtxoEntry>,
     weight: u64,
     current_min_fee_rate: u64,
     next_height: u64,
+    coinbase_maturity: u64,
     rotation: Option<&dyn RotationProvider>,
     registry: Option<&SuiteRegistry>,
 ) -> Result<(), TxPoolAdmitError> {
@@ -1467,9 +2058,14 @@ fn cheap_fee_floor_precheck(
     if tx.tx_kind != 0x00 || !tx.da_payload.is_empty() || tx.tx_nonce == 0 {
         return Ok(());
     }
-    let Some(input_value) =
-        fee_precheck_p2pk_input_value(tx, utxos, next_height, rotation, registry)
-    else {
+    let Some(input_value) = fee_precheck_p2pk_input_value(
+        tx,
+        utxos,
+        next_height,
+        coinbase_maturity,
+        rotation,
+        registry,
+    ) else {
         return Ok(());
     };
     let Some(output_value) = fee_precheck_p2pk_output_value(&tx.outputs, next_height, rotation)
@@ -1512,12 +2108,13 @@ fn cheap_fee_floor_precheck(
 /// fee floor no longer applies; this is intentional.
 fn fee_precheck_p2pk_input_value(
     tx: &rubin_consensus::Tx,
-    utxos: &HashMap<Outpoint, rubin_consensus::UtxoEntry>,
+    utxos: &OutpointMap<rubin_consensus::UtxoEntry>,
     next_height: u64,
+    coinbase_maturity: u64,
     rotation: Option<&dyn RotationProvider>,
     registry: Option<&SuiteRegistry>,
 ) -> Option<u64> {
-    use rubin_consensus::constants::{COINBASE_MATURITY, MAX_P2PK_COVENANT_DATA};
+    use rubin_consensus::constants::MAX_P2PK_COVENANT_DATA;
     use rubin_consensus::is_valid_sighash_type;
     use sha3::{Digest, Sha3_256};
     if tx.inputs.len() != 1 {
@@ -1553,7 +2150,7 @@ fn fee_precheck_p2pk_input_value(
     }
     if entry.created_by_coinbase
         && (next_height < entry.creation_height
-            || next_height - entry.creation_height < COINBASE_MATURITY)
+            || next_height - entry.creation_height < coinbase_maturity)
     {
         return None;
     }
@@ -1692,7 +2289,7 @@ pub(crate) fn apply_policy(
     tx: &rubin_consensus::Tx,
     weight: u64,
     da_bytes: u64,
-    utxos: &HashMap<Outpoint, rubin_consensus::UtxoEntry>,
+    utxos: &OutpointMap<rubin_consensus::UtxoEntry>,
     next_height: u64,
     cfg: &TxPoolConfig,
 ) -> Result<(), String> {
@@ -1734,7 +2331,7 @@ pub(crate) fn apply_policy(
 
 fn reject_unsupported_core_ext_node_runtime(
     tx: &rubin_consensus::Tx,
-    utxos: &HashMap<Outpoint, rubin_consensus::UtxoEntry>,
+    utxos: &OutpointMap<rubin_consensus::UtxoEntry>,
 ) -> Option<String> {
     covenant_policy_kind(tx, utxos, COV_TYPE_CORE_EXT)
         .map(|kind| format!("CORE_EXT {kind} unsupported by Rust node runtime"))
@@ -1762,7 +2359,7 @@ fn reject_unsupported_core_ext_node_runtime(
 /// gate directly on the chain UTXO view (no snapshot).
 fn reject_missing_policy_inputs(
     tx: &rubin_consensus::Tx,
-    utxos: &HashMap<Outpoint, rubin_consensus::UtxoEntry>,
+    utxos: &OutpointMap<rubin_consensus::UtxoEntry>,
 ) -> Result<(), TxPoolAdmitError> {
     for input in &tx.inputs {
         let outpoint = Outpoint {
@@ -1813,7 +2410,7 @@ fn reject_missing_policy_inputs(
 /// that branch is statically unreachable here.
 fn reject_core_simplicity_pre_activation(
     tx: &rubin_consensus::Tx,
-    utxos: &HashMap<Outpoint, rubin_consensus::UtxoEntry>,
+    utxos: &OutpointMap<rubin_consensus::UtxoEntry>,
     height: u64,
     rotation: Option<&dyn RotationProvider>,
 ) -> Result<Option<String>, String> {
@@ -1869,7 +2466,7 @@ impl RotationProvider for ActiveSimplicityGenesisRotation<'_> {
 
 fn covenant_policy_kind(
     tx: &rubin_consensus::Tx,
-    utxos: &HashMap<Outpoint, rubin_consensus::UtxoEntry>,
+    utxos: &OutpointMap<rubin_consensus::UtxoEntry>,
     covenant_type: u16,
 ) -> Option<&'static str> {
     if tx
@@ -1905,6 +2502,69 @@ pub(crate) fn reject_non_coinbase_anchor_outputs(tx: &rubin_consensus::Tx) -> Re
     Ok(())
 }
 
+/// Relay-only anchor limits (`policy_max_relay_anchor_bytes_per_tx`,
+/// `policy_max_anchors_per_tx`), tighter than the consensus per-output and
+/// per-block anchor caps. `anchor_bytes` is the third value of the caller's
+/// single `tx_weight_and_stats_public` walk; outputs are counted with the
+/// same CORE_ANCHOR / DA commit rule that walk sums bytes over. Admission
+/// and relay run this before `apply_policy`; the miner does not.
+pub(crate) fn reject_anchor_relay_policy(
+    tx: &rubin_consensus::Tx,
+    anchor_bytes: u64,
+    cfg: &TxPoolConfig,
+) -> Result<(), String> {
+    let max_bytes = cfg.policy_max_relay_anchor_bytes_per_tx;
+    if max_bytes != 0 && anchor_bytes > max_bytes {
+        return Err(format!(
+            "anchor bytes above relay cap (anchor_bytes={anchor_bytes} max={max_bytes}) (policy)"
+        ));
+    }
+    let max_anchors = cfg.policy_max_anchors_per_tx;
+    if max_anchors != 0 {
+        let anchors = tx
+            .outputs
+            .iter()
+            .filter(|output| {
+                matches!(
+                    output.covenant_type,
+                    rubin_consensus::constants::COV_TYPE_ANCHOR
+                        | rubin_consensus::constants::COV_TYPE_DA_COMMIT
+                )
+            })
+            .count() as u64;
+        if anchors > max_anchors {
+            return Err(format!(
+                "anchor outputs above relay cap (anchors={anchors} max={max_anchors}) (policy)"
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Relay-only cap on each input's `script_sig` (`policy_max_script_sig_bytes`).
+/// Admission and relay run it right after parsing, ahead of consensus, so
+/// it applies equally to transactions that end up as orphans.
+pub(crate) fn reject_script_sig_policy(
+    tx: &rubin_consensus::Tx,
+    cfg: &TxPoolConfig,
+) -> Result<(), String> {
+    let max_bytes = cfg.policy_max_script_sig_bytes;
+    if max_bytes == 0 {
+        return Ok(());
+    }
+    match tx
+        .inputs
+        .iter()
+        .position(|input| input.script_sig.len() as u64 > max_bytes)
+    {
+        Some(index) => Err(format!(
+            "script_sig above relay cap (input={index} len={} max={max_bytes}) (policy)",
+            tx.inputs[index].script_sig.len()
+        )),
+        None => Ok(()),
+    }
+}
+
 /// Stage C DA fee policy aligned with Go's `RejectDaAnchorTxPolicy`
 /// (`POLICY_MEMPOOL_ADMISSION_GENESIS.md` Stage C):
 ///
@@ -1962,7 +2622,7 @@ pub(crate) fn reject_da_anchor_tx_policy(
     tx: &rubin_consensus::Tx,
     weight: u64,
     da_bytes: u64,
-    utxos: &HashMap<Outpoint, rubin_consensus::UtxoEntry>,
+    utxos: &OutpointMap<rubin_consensus::UtxoEntry>,
     current_mempool_min_fee_rate: u64,
     min_da_fee_rate: u64,
     da_surcharge_per_byte: u64,
@@ -2019,7 +2679,7 @@ pub(crate) fn reject_da_anchor_tx_policy(
 
 pub(crate) fn compute_fee_no_verify(
     tx: &rubin_consensus::Tx,
-    utxos: &HashMap<Outpoint, rubin_consensus::UtxoEntry>,
+    utxos: &OutpointMap<rubin_consensus::UtxoEntry>,
 ) -> Result<u64, String> {
     if tx.inputs.is_empty() {
         return Err("missing inputs".to_string());
@@ -2049,17 +2709,28 @@ pub(crate) fn compute_fee_no_verify(
     Ok(sum_in - sum_out)
 }
 
+fn priority_weight(entry: &TxPoolEntry, anchor_fee_rate_multiplier: u64) -> u64 {
+    entry.weight.saturating_add(
+        entry
+            .anchor_bytes
+            .saturating_mul(anchor_fee_rate_multiplier),
+    )
+}
+
 fn compare_entries_for_mining(
     a: &(&[u8; 32], &TxPoolEntry),
     b: &(&[u8; 32], &TxPoolEntry),
+    anchor_fee_rate_multiplier: u64,
 ) -> Ordering {
     let (a_txid, a_entry) = *a;
     let (b_txid, b_entry) = *b;
-    match compare_fee_rate(a_entry, b_entry) {
+    let a_weight = priority_weight(a_entry, anchor_fee_rate_multiplier);
+    let b_weight = priority_weight(b_entry, anchor_fee_rate_multiplier);
+    match compare_fee_rate_values(a_entry.fee, a_weight, b_entry.fee, b_weight) {
         Ordering::Greater => Ordering::Less,
         Ordering::Less => Ordering::Greater,
         Ordering::Equal => match b_entry.fee.cmp(&a_entry.fee) {
-            Ordering::Equal => match a_entry.weight.cmp(&b_entry.weight) {
+            Ordering::Equal => match a_weight.cmp(&b_weight) {
                 // Final tie-break matches Go parity
                 // (`clients/go/node/mempool.go`, `sortMempoolEntries`:
                 // `bytes.Compare(entries[i].txid[:], entries[j].txid[:])`)
@@ -2076,6 +2747,18 @@ fn compare_entries_for_mining(
     }
 }
 
+/// Higher package fee rate first; equal rates fall back to the flat
+/// per-entry mining order.
+fn compare_mining_packages(a: &MiningPackage<'_>, b: &MiningPackage<'_>) -> Ordering {
+    match compare_fee_rate_values(a.fee, a.weight, b.fee, b.weight) {
+        Ordering::Greater => Ordering::Less,
+        Ordering::Less => Ordering::Greater,
+        Ordering::Equal => {
+            compare_entries_for_mining(&(a.txid, a.entry), &(b.txid, b.entry), a.multiplier)
+        }
+    }
+}
+
 #[cfg(test)]
 fn compare_admit_priority(
     txid_a: [u8; 32],
@@ -2110,10 +2793,6 @@ fn compare_priority_values(a: AdmitPriority<'_>, b: AdmitPriority<'_>) -> Orderi
     }
 }
 
-fn compare_fee_rate(a: &TxPoolEntry, b: &TxPoolEntry) -> Ordering {
-    compare_fee_rate_values(a.fee, a.weight, b.fee, b.weight)
-}
-
 fn default_tx_pool_low_water_bytes(max_bytes: usize) -> usize {
     if max_bytes == 0 {
         return 0;
@@ -2167,19 +2846,19 @@ fn compare_count_pressure_priority(
     compare_priority_values(
         AdmitPriority {
             fee: a.entry.fee,
-            weight: a.entry.weight,
+            weight: a.weight,
             tie: &a.txid,
         },
         AdmitPriority {
             fee: b.entry.fee,
-            weight: b.entry.weight,
+            weight: b.weight,
             tie: &b.txid,
         },
     )
 }
 
 fn compare_capacity_priority(a: &CapacityPlanEntry<'_>, b: &CapacityPlanEntry<'_>) -> Ordering {
-    match compare_fee_rate_values(a.entry.fee, a.entry.weight, b.entry.fee, b.entry.weight) {
+    match compare_fee_rate_values(a.entry.fee, a.weight, b.entry.fee, b.weight) {
         Ordering::Equal => match a.entry.fee.cmp(&b.entry.fee) {
             Ordering::Equal => match a.admission_seq.cmp(&b.admission_seq) {
                 Ordering::Equal => Ordering::Equal,
@@ -2191,6 +2870,11 @@ fn compare_capacity_priority(a: &CapacityPlanEntry<'_>, b: &CapacityPlanEntry<'_
     }
 }
 
+#[cfg(test)]
+fn compare_fee_rate(a: &TxPoolEntry, b: &TxPoolEntry) -> Ordering {
+    compare_fee_rate_values(a.fee, a.weight, b.fee, b.weight)
+}
+
 fn compare_fee_rate_values(fee_a: u64, weight_a: u64, fee_b: u64, weight_b: u64) -> Ordering {
     if weight_a == 0 || weight_b == 0 {
         return Ordering::Equal;
@@ -2203,32 +2887,38 @@ fn compare_fee_rate_values(fee_a: u64, weight_a: u64, fee_b: u64, weight_b: u64)
 #[cfg(test)]
 mod tests {
     use std::cmp::Ordering;
-    use std::collections::HashMap;
+    use std::collections::HashSet;
     use std::fs;
     use std::path::PathBuf;
 
     use rubin_consensus::block::BLOCK_HEADER_BYTES;
     use rubin_consensus::constants::{
-        COV_TYPE_ANCHOR, COV_TYPE_CORE_EXT, COV_TYPE_CORE_SIMPLICITY, COV_TYPE_P2PK,
-        SUITE_ID_SENTINEL, TX_WIRE_VERSION,
+        COINBASE_MATURITY, COV_TYPE_ANCHOR, COV_TYPE_CORE_EXT, COV_TYPE_CORE_SIMPLICITY,
+        COV_TYPE_P2PK, MAX_BLOCK_BYTES, SUITE_ID_SENTINEL, TX_WIRE_VERSION,
     };
     use rubin_consensus::{
         marshal_tx, p2pk_covenant_data_for_pubkey, parse_tx, sign_transaction,
-        tx_weight_and_stats_public, DaChunkCore, Mldsa87Keypair, Outpoint, Tx, TxInput, TxOutput,
-        UtxoEntry, WitnessItem,
+        tx_weight_and_stats_public, ChainParams, DaChunkCore, Mldsa87Keypair, Outpoint,
+        OutpointMap, Tx, TxInput, TxOutput, UtxoEntry, WitnessItem,
     };
 
     use super::{
         cheap_fee_floor_precheck, compare_admit_priority, compare_entries_for_mining,
         compare_fee_rate, conflict, default_tx_pool_low_water_bytes, fee_precheck_p2pk_input_value,
         fee_precheck_p2pk_output_value, mtp_median, next_block_height, next_block_mtp,
-        reject_da_anchor_tx_policy, rejected, relay_metadata, tx_pool_byte_pressure_target,
-        unavailable, TxPool, TxPoolAdmitErrorKind, TxPoolConfig, TxPoolEntry, TxPoolSnapshot,
-        TxPoolSnapshotEntry, TxSource, DEFAULT_MEMPOOL_MIN_FEE_RATE, MAX_TX_POOL_TRANSACTIONS,
+        parse_tx_bytes_limited, reject_anchor_relay_policy, reject_da_anchor_tx_policy,
+        reject_script_sig_policy, rejected, relay_metadata, tx_pool_byte_pressure_target,
+        tx_witness_canonical, unavailable, witness_variant_preferred, TxPool, TxPoolAdmitErrorKind,
+        TxPoolConfig, TxPoolEntry, TxPoolSnapshot, TxPoolSnapshotEntry, TxRejectClass, TxSource,
+        DEFAULT_MAX_ANCESTORS, DEFAULT_MAX_PACKAGE_WEIGHT, DEFAULT_MAX_SCRIPT_SIG_BYTES,
+        DEFAULT_MEMPOOL_MIN_FEE_RATE, MAX_TX_BYTES, MAX_TX_POOL_TRANSACTIONS,
     };
     use crate::{
         block_store_path, default_sync_config, devnet_genesis_block_bytes, devnet_genesis_chain_id,
-        test_helpers::signed_conflicting_p2pk_state_and_txs, BlockStore, ChainState, SyncEngine,
+        test_helpers::{
+            signed_conflicting_p2pk_state_and_txs, signed_witness_variant_p2pk_state_and_txs,
+        },
+        BlockStore, ChainState, SyncEngine,
     };
 
     #[derive(serde::Deserialize)]
@@ -2282,6 +2972,8 @@ mod tests {
             fee,
             weight,
             size,
+            anchor_bytes: 0,
+            output_count: 0,
             source,
         }
     }
@@ -2315,6 +3007,8 @@ mod tests {
                     fee,
                     weight,
                     size,
+                    anchor_bytes: 0,
+                    output_count: tx.outputs.len(),
                     source,
                 },
                 heap_id,
@@ -2367,8 +3061,8 @@ mod tests {
         out
     }
 
-    fn fixture_utxos_to_map(items: &[FixtureUtxo]) -> HashMap<Outpoint, UtxoEntry> {
-        let mut out = HashMap::with_capacity(items.len());
+    fn fixture_utxos_to_map(items: &[FixtureUtxo]) -> OutpointMap<UtxoEntry> {
+        let mut out = OutpointMap::with_capacity_and_hasher(items.len(), Default::default());
         for item in items {
             out.insert(
                 Outpoint {
@@ -2544,6 +3238,36 @@ mod tests {
         assert!(err.message.contains("transaction rejected"));
     }
 
+    #[test]
+    fn admit_rejects_tx_over_max_tx_bytes_before_parsing() {
+        let mut pool = TxPool::new();
+        let oversize = vec![0u8; MAX_TX_BYTES as usize + 1];
+        let err = pool
+            .admit(&oversize, &ChainState::new(), None, [0u8; 32])
+            .unwrap_err();
+        assert_eq!(err.kind, TxPoolAdmitErrorKind::Rejected);
+        assert_eq!(
+            err.message,
+            format!(
+                "transaction rejected: tx bytes exceed MAX_TX_BYTES: {} > {MAX_TX_BYTES}",
+                MAX_TX_BYTES + 1
+            )
+        );
+        assert!(pool.is_empty());
+    }
+
+    #[test]
+    fn parse_tx_bytes_limited_lets_max_tx_bytes_through_to_the_parser() {
+        let at_cap = vec![0u8; MAX_TX_BYTES as usize];
+        let err = parse_tx_bytes_limited(&at_cap).unwrap_err();
+        assert!(!err.message.contains("MAX_TX_BYTES"), "{}", err.message);
+        assert_eq!(
+            MAX_TX_BYTES + BLOCK_HEADER_BYTES as u64 + 1,
+            MAX_BLOCK_BYTES,
+            "a max-size tx must still fit a block message"
+        );
+    }
+
     #[test]
     fn admit_rejects_sub_floor_conformance_tx_as_unavailable_with_atomicity() {
         // RUB-162 Phase A migration rationale (per controller Q2 record):
@@ -2820,16 +3544,203 @@ mod tests {
     }
 
     #[test]
-    fn admit_rejects_duplicate_txid() {
-        let (state, raw, _conflict) = signed_conflicting_p2pk_state_and_txs(7700, 10, 9);
+    fn admit_rejects_duplicate_txid() {
+        let (state, raw, _conflict) = signed_conflicting_p2pk_state_and_txs(7700, 10, 9);
+        let mut pool = TxPool::new();
+        pool.admit(&raw, &state, None, devnet_genesis_chain_id())
+            .expect("first admit");
+        let err = pool
+            .admit(&raw, &state, None, devnet_genesis_chain_id())
+            .unwrap_err();
+        assert_eq!(err.kind, TxPoolAdmitErrorKind::Conflict);
+        assert!(err.message.contains("already in mempool"));
+    }
+
+    #[test]
+    fn witness_variant_ranking_prefers_canonical_then_lower_wtxid() {
+        use rubin_consensus::constants::{
+            ML_DSA_87_PUBKEY_BYTES, ML_DSA_87_SIG_BYTES, SIGHASH_ALL, SIGHASH_NONE,
+            SUITE_ID_ML_DSA_87, SUITE_ID_SENTINEL,
+        };
+        let registry = rubin_consensus::SuiteRegistry::default_registry();
+        let base = Tx {
+            version: TX_WIRE_VERSION,
+            tx_kind: 0x00,
+            tx_nonce: 1,
+            inputs: Vec::new(),
+            outputs: Vec::new(),
+            locktime: 0,
+            da_commit_core: None,
+            da_chunk_core: None,
+            witness: Vec::new(),
+            da_payload: Vec::new(),
+        };
+        let with_witness = |suite_id: u8, pubkey_len: u64, sig_len: u64, trailer: u8| {
+            let mut signature = vec![0x5a; sig_len as usize];
+            signature.push(trailer);
+            Tx {
+                witness: vec![WitnessItem {
+                    suite_id,
+                    pubkey: vec![0x11; pubkey_len as usize],
+                    signature,
+                }],
+                ..base.clone()
+            }
+        };
+        let all = with_witness(
+            SUITE_ID_ML_DSA_87,
+            ML_DSA_87_PUBKEY_BYTES,
+            ML_DSA_87_SIG_BYTES,
+            SIGHASH_ALL,
+        );
+        let none = with_witness(
+            SUITE_ID_ML_DSA_87,
+            ML_DSA_87_PUBKEY_BYTES,
+            ML_DSA_87_SIG_BYTES,
+            SIGHASH_NONE,
+        );
+        let short = with_witness(SUITE_ID_ML_DSA_87, 10, 10, SIGHASH_ALL);
+        assert!(tx_witness_canonical(&all, &registry));
+        assert!(!tx_witness_canonical(&none, &registry));
+        assert!(!tx_witness_canonical(&short, &registry));
+        assert!(tx_witness_canonical(
+            &Tx {
+                witness: vec![WitnessItem {
+                    suite_id: SUITE_ID_SENTINEL,
+                    pubkey: Vec::new(),
+                    signature: Vec::new(),
+                }],
+                ..base.clone()
+            },
+            &registry
+        ));
+
+        let (low, high) = ([0x01; 32], [0x02; 32]);
+        assert!(witness_variant_preferred(
+            &all, &high, &none, &low, &registry
+        ));
+        assert!(!witness_variant_preferred(
+            &none, &low, &all, &high, &registry
+        ));
+        assert!(witness_variant_preferred(
+            &all, &low, &all, &high, &registry
+        ));
+        assert!(!witness_variant_preferred(
+            &all, &high, &all, &low, &registry
+        ));
+        assert!(witness_variant_preferred(
+            &none, &low, &short, &high, &registry
+        ));
+    }
+
+    #[test]
+    fn witness_variants_converge_on_the_canonical_one() {
+        let (state, canonical, variant) = signed_witness_variant_p2pk_state_and_txs(7700, 10);
+        let chain_id = devnet_genesis_chain_id();
+        let (tx, txid, canonical_wtxid, _) = parse_tx(&canonical).expect("parse canonical");
+        let (variant_tx, variant_txid, variant_wtxid, _) =
+            parse_tx(&variant).expect("parse variant");
+        assert_eq!(variant_txid, txid);
+        assert_ne!(variant_wtxid, canonical_wtxid);
+        let registry = rubin_consensus::SuiteRegistry::default_registry();
+        assert!(tx_witness_canonical(&tx, &registry));
+        assert!(!tx_witness_canonical(&variant_tx, &registry));
+
+        for order in [[&variant, &canonical], [&canonical, &variant]] {
+            let mut pool = TxPool::new();
+            for raw in order {
+                let _ = pool.admit(raw, &state, None, chain_id);
+            }
+            assert_eq!(pool.len(), 1);
+            assert_eq!(pool.wtxid_of(&txid), Some(canonical_wtxid));
+            assert_eq!(pool.tx_by_id(&txid).as_ref(), Some(&canonical));
+            assert_eq!(pool.used_bytes, canonical.len());
+        }
+
+        let mut pool = TxPool::new();
+        pool.admit(&canonical, &state, None, chain_id)
+            .expect("canonical");
+        let err = pool.admit(&variant, &state, None, chain_id).unwrap_err();
+        assert_eq!(err.kind, TxPoolAdmitErrorKind::Conflict);
+        assert!(err.message.contains("witness variant"));
+        let err = pool.admit(&canonical, &state, None, chain_id).unwrap_err();
+        assert!(err.message.contains("already in mempool"));
+    }
+
+    #[test]
+    fn accept_tx_reports_admitted_tx_then_conflicts() {
+        let (state, raw, double_spend) = signed_conflicting_p2pk_state_and_txs(7700, 10, 9);
+        let chain_id = devnet_genesis_chain_id();
         let mut pool = TxPool::new();
-        pool.admit(&raw, &state, None, devnet_genesis_chain_id())
-            .expect("first admit");
+        let accepted = pool
+            .accept_tx(&raw, &state, None, chain_id)
+            .expect("valid spend");
+        let (_, txid, _, _) = parse_tx(&raw).expect("parse");
+        let entry = pool.txs.get(&txid).expect("entry");
+        assert_eq!(accepted.txid, txid);
+        assert_eq!(accepted.fee, 7690);
+        assert_eq!(accepted.weight, entry.weight);
+        assert_eq!(accepted.size, raw.len());
+        assert_eq!(pool.entry_source(&txid), Some(TxSource::Local));
+
         let err = pool
-            .admit(&raw, &state, None, devnet_genesis_chain_id())
+            .accept_tx(&double_spend, &state, None, chain_id)
             .unwrap_err();
-        assert_eq!(err.kind, TxPoolAdmitErrorKind::Conflict);
-        assert!(err.message.contains("already in mempool"));
+        assert_eq!(err.reject_class(), TxRejectClass::Conflict);
+        assert!(err.message.contains("double-spend"), "{}", err.message);
+
+        let err = pool.accept_tx(&raw, &state, None, chain_id).unwrap_err();
+        assert_eq!(err.reject_class(), TxRejectClass::Conflict);
+        assert_eq!(err.message, "tx already in mempool");
+        assert_eq!(pool.len(), 1);
+    }
+
+    #[test]
+    fn accept_tx_separates_consensus_from_policy_rejections() {
+        let prev = Outpoint {
+            txid: [0x44; 32],
+            vout: 0,
+        };
+        let mut state = ChainState::new();
+        state.utxos.insert(
+            prev.clone(),
+            UtxoEntry {
+                value: 1_000,
+                covenant_type: COV_TYPE_P2PK,
+                covenant_data: p2pk_covenant_data_for_pubkey(&[0x01; 2592]),
+                creation_height: 0,
+                created_by_coinbase: false,
+            },
+        );
+        let (mut tx, _, _, _) = parse_tx(&unsigned_one_input_tx(
+            &prev,
+            vec![TxOutput {
+                value: 900,
+                covenant_type: COV_TYPE_P2PK,
+                covenant_data: p2pk_covenant_data_for_pubkey(&[0x02; 2592]),
+            }],
+        ))
+        .expect("parse");
+        tx.tx_nonce = 0;
+        let raw = marshal_tx(&tx).expect("marshal");
+
+        let mut pool = TxPool::new();
+        let err = pool
+            .accept_tx(&raw, &state, None, devnet_genesis_chain_id())
+            .unwrap_err();
+        assert_eq!(err.reject_class(), TxRejectClass::Consensus);
+        assert_eq!(err.consensus_code(), Some("TX_ERR_TX_NONCE_INVALID"));
+        assert!(pool.is_empty());
+
+        let policy = rejected("non-coinbase CORE_ANCHOR is non-standard (policy)");
+        assert_eq!(policy.reject_class(), TxRejectClass::Policy);
+        assert_eq!(policy.consensus_code(), None);
+        let bare = rejected("TX_ERR_MISSING_UTXO: utxo not found");
+        assert_eq!(bare.consensus_code(), Some("TX_ERR_MISSING_UTXO"));
+        assert_eq!(
+            unavailable("TX_ERR_X: y").reject_class(),
+            TxRejectClass::Unavailable
+        );
     }
 
     #[test]
@@ -2864,6 +3775,49 @@ mod tests {
         assert!(err.message.contains("TX_ERR_COVENANT_TYPE_INVALID"));
     }
 
+    #[test]
+    fn relay_and_admission_reject_dust_outputs_when_enabled() {
+        let threshold = crate::policy_dust::dust_threshold(
+            COV_TYPE_P2PK,
+            crate::policy_dust::DEFAULT_DUST_RELAY_FEE_RATE,
+        );
+        let (state, raw) = signed_p2pk_state_and_tx(
+            1_000_000,
+            vec![TxOutput {
+                value: threshold - 1,
+                covenant_type: COV_TYPE_P2PK,
+                covenant_data: p2pk_covenant_data_for_pubkey(&[0x44; 2592]),
+            }],
+            0x00,
+            None,
+            Vec::new(),
+        );
+        let cfg = TxPoolConfig {
+            policy_dust_relay_fee_rate: crate::policy_dust::DEFAULT_DUST_RELAY_FEE_RATE,
+            ..TxPoolConfig::default()
+        };
+
+        let err = relay_metadata(&raw, &state, None, [0u8; 32], &cfg).unwrap_err();
+        assert_eq!(err.kind, TxPoolAdmitErrorKind::Rejected);
+        assert!(
+            err.message.contains("dust output (index=0"),
+            "{}",
+            err.message
+        );
+        let err = TxPool::new_with_config(cfg)
+            .admit(&raw, &state, None, [0u8; 32])
+            .unwrap_err();
+        assert!(
+            err.message.contains("dust output (index=0"),
+            "{}",
+            err.message
+        );
+
+        // The default config leaves the rule off (Go relays any value).
+        relay_metadata(&raw, &state, None, [0u8; 32], &TxPoolConfig::default())
+            .expect("dust relays with the rule disabled");
+    }
+
     /// Mirror of Go `simplicityCovenantDataForNodeTest`: 32-byte program
     /// CMR followed by compactSize(state_len) with an empty state.
     fn simplicity_covenant_data(cmr_byte: u8) -> Vec<u8> {
@@ -2898,8 +3852,17 @@ mod tests {
             policy_reject_non_coinbase_anchor_outputs: false,
             policy_reject_simplicity_pre_activation: true,
             suite_context: None,
+            chain_params: ChainParams::V1,
             policy_current_mempool_min_fee_rate: 0,
             policy_min_da_fee_rate: 0,
+            policy_max_relay_anchor_bytes_per_tx: 0,
+            policy_max_anchors_per_tx: 0,
+            policy_anchor_fee_rate_multiplier: 0,
+            policy_dust_relay_fee_rate: 0,
+            policy_max_ancestors: 0,
+            policy_max_descendants: 0,
+            policy_max_package_weight: 0,
+            policy_max_script_sig_bytes: 0,
         }
     }
 
@@ -3207,6 +4170,8 @@ mod tests {
                     fee: 10,
                     weight: 1,
                     size: 1,
+                    anchor_bytes: 0,
+                    output_count: 0,
                     source: TxSource::Local,
                 },
             );
@@ -3266,6 +4231,8 @@ mod tests {
                     fee: 10,
                     weight: 1,
                     size: 1,
+                    anchor_bytes: 0,
+                    output_count: 0,
                     source: TxSource::Local,
                 },
             );
@@ -3327,6 +4294,8 @@ mod tests {
                 fee: 0,
                 weight: 100,
                 size: 1,
+                anchor_bytes: 0,
+                output_count: 0,
                 source: TxSource::Local,
             },
         );
@@ -3344,6 +4313,8 @@ mod tests {
                     fee: 1,
                     weight: 1,
                     size: 1,
+                    anchor_bytes: 0,
+                    output_count: 0,
                     source: TxSource::Local,
                 },
             );
@@ -3420,6 +4391,8 @@ mod tests {
                 fee: 20_000,
                 weight: 10_000,
                 size: raw_worse.len(),
+                anchor_bytes: 0,
+                output_count: 0,
                 source: TxSource::Local,
             },
         );
@@ -3437,6 +4410,8 @@ mod tests {
                     fee: 3,
                     weight: 1,
                     size: 1,
+                    anchor_bytes: 0,
+                    output_count: 0,
                     source: TxSource::Local,
                 },
             );
@@ -3745,6 +4720,8 @@ mod tests {
                 fee: 20,
                 weight: 100,
                 size: 20,
+                anchor_bytes: 0,
+                output_count: 0,
                 source: TxSource::Local,
             },
         );
@@ -3756,6 +4733,8 @@ mod tests {
                 fee: 15,
                 weight: 90,
                 size: 10,
+                anchor_bytes: 0,
+                output_count: 0,
                 source: TxSource::Local,
             },
         );
@@ -3767,6 +4746,8 @@ mod tests {
                 fee: 15,
                 weight: 80,
                 size: 10,
+                anchor_bytes: 0,
+                output_count: 0,
                 source: TxSource::Local,
             },
         );
@@ -3775,6 +4756,226 @@ mod tests {
         assert_eq!(selected, vec![vec![0x11], vec![0x33], vec![0x22]]);
     }
 
+    #[test]
+    fn anchor_relay_policy_caps_bytes_and_output_count() {
+        let funding = Outpoint {
+            txid: [0x11; 32],
+            vout: 0,
+        };
+        let anchor = |len: usize| TxOutput {
+            value: 0,
+            covenant_type: COV_TYPE_ANCHOR,
+            covenant_data: vec![0x99; len],
+        };
+        let check = |outputs: Vec<TxOutput>, cfg: &TxPoolConfig| {
+            let raw = unsigned_one_input_tx(&funding, outputs);
+            let (tx, _, _, _) = parse_tx(&raw).expect("parse tx");
+            let (_, _, anchor_bytes) = tx_weight_and_stats_public(&tx).expect("weight");
+            reject_anchor_relay_policy(&tx, anchor_bytes, cfg)
+        };
+        let cfg = TxPoolConfig {
+            policy_max_relay_anchor_bytes_per_tx: 80,
+            policy_max_anchors_per_tx: 2,
+            ..TxPoolConfig::default()
+        };
+
+        assert_eq!(check(vec![anchor(80)], &cfg), Ok(()));
+        assert_eq!(check(vec![anchor(40), anchor(40)], &cfg), Ok(()));
+        let err = check(vec![anchor(81)], &cfg).unwrap_err();
+        assert!(err.contains("anchor_bytes=81 max=80"), "{err}");
+        let err = check(vec![anchor(40), anchor(41)], &cfg).unwrap_err();
+        assert!(err.contains("anchor_bytes=81 max=80"), "{err}");
+        let err = check(vec![anchor(1), anchor(1), anchor(1)], &cfg).unwrap_err();
+        assert!(err.contains("anchors=3 max=2"), "{err}");
+
+        // Zero knobs leave only the consensus caps.
+        let open = TxPoolConfig::default();
+        assert_eq!(
+            check(vec![anchor(4096), anchor(1), anchor(1)], &open),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn script_sig_policy_bounds_orphans_before_consensus() {
+        let unknown = Outpoint {
+            txid: [0x5c; 32],
+            vout: 0,
+        };
+        let output = TxOutput {
+            value: 1,
+            covenant_type: COV_TYPE_P2PK,
+            covenant_data: p2pk_covenant_data_for_pubkey(&[0x44; 2592]),
+        };
+        let with_script_sig = |len: usize| {
+            let raw = unsigned_one_input_tx(&unknown, vec![output.clone()]);
+            let (mut tx, _, _, _) = parse_tx(&raw).expect("parse tx");
+            // Consensus rejects script_sig before its own input's lookup, so
+            // only a later input can carry it into the orphan pool.
+            let mut second = tx.inputs[0].clone();
+            second.prev_vout = 1;
+            second.script_sig = vec![0x01; len];
+            tx.inputs.push(second);
+            tx
+        };
+
+        let cfg = TxPoolConfig::default();
+        assert_eq!(
+            cfg.policy_max_script_sig_bytes,
+            DEFAULT_MAX_SCRIPT_SIG_BYTES
+        );
+        assert_eq!(reject_script_sig_policy(&with_script_sig(33), &cfg), Ok(()));
+        let err = reject_script_sig_policy(&with_script_sig(10 * 1024), &cfg).unwrap_err();
+        assert!(err.contains("input=1 len=10240 max=33"), "{err}");
+        let open = TxPoolConfig {
+            policy_max_script_sig_bytes: 0,
+            ..TxPoolConfig::default()
+        };
+        assert_eq!(
+            reject_script_sig_policy(&with_script_sig(10 * 1024), &open),
+            Ok(())
+        );
+
+        // A capped relay refuses the orphan outright instead of reporting
+        // the missing input that would park it in the orphan pool.
+        let raw = marshal_tx(&with_script_sig(2)).expect("marshal");
+        let state = ChainState::new();
+        let err = relay_metadata(&raw, &state, None, [0u8; 32], &cfg).unwrap_err();
+        assert!(err.is_missing_utxo(), "{}", err.message);
+        let strict = TxPoolConfig {
+            policy_max_script_sig_bytes: 1,
+            ..TxPoolConfig::default()
+        };
+        let err = relay_metadata(&raw, &state, None, [0u8; 32], &strict).unwrap_err();
+        assert_eq!(err.reject_class(), TxRejectClass::Policy);
+        assert!(err.message.contains("len=2 max=1"), "{}", err.message);
+        let err = TxPool::new_with_config(strict)
+            .admit(&raw, &state, None, [0u8; 32])
+            .unwrap_err();
+        assert!(err.message.contains("len=2 max=1"), "{}", err.message);
+
+        // Past the consensus parse cap the bytes never reach policy.
+        let raw = marshal_tx(&with_script_sig(10 * 1024)).expect("marshal");
+        let err = relay_metadata(&raw, &state, None, [0u8; 32], &cfg).unwrap_err();
+        assert_eq!(
+            err.consensus_code(),
+            Some("TX_ERR_PARSE"),
+            "{}",
+            err.message
+        );
+    }
+
+    #[test]
+    fn admission_and_relay_apply_chain_params_coinbase_maturity() {
+        let coinbase = Outpoint {
+            txid: [0x6d; 32],
+            vout: 0,
+        };
+        let covenant_data = p2pk_covenant_data_for_pubkey(&[0x44; 2592]);
+        let mut state = ChainState::new();
+        state.has_tip = true;
+        state.height = 4;
+        state.utxos.insert(
+            coinbase.clone(),
+            UtxoEntry {
+                value: 1_000_000,
+                covenant_type: COV_TYPE_P2PK,
+                covenant_data: covenant_data.clone(),
+                creation_height: 1,
+                created_by_coinbase: true,
+            },
+        );
+        // Unsigned, so a mature spend still fails — just not on maturity.
+        let raw = unsigned_one_input_tx(
+            &coinbase,
+            vec![TxOutput {
+                value: 900_000,
+                covenant_type: COV_TYPE_P2PK,
+                covenant_data,
+            }],
+        );
+
+        let v1 = TxPoolConfig::default();
+        let fast = TxPoolConfig {
+            chain_params: ChainParams {
+                coinbase_maturity: 4,
+                ..ChainParams::V1
+            },
+            ..TxPoolConfig::default()
+        };
+        for (cfg, immature) in [(v1, true), (fast, false)] {
+            let relay = relay_metadata(&raw, &state, None, [0u8; 32], &cfg).unwrap_err();
+            let admit = TxPool::new_with_config(cfg)
+                .admit(&raw, &state, None, [0u8; 32])
+                .unwrap_err();
+            for err in [relay, admit] {
+                assert_eq!(
+                    err.consensus_code() == Some("TX_ERR_COINBASE_IMMATURE"),
+                    immature,
+                    "{}",
+                    err.message
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn anchor_fee_rate_multiplier_reorders_selection_and_eviction() {
+        let entry = |raw: u8, fee: u64, anchor_bytes: u64| TxPoolEntry {
+            raw: vec![raw],
+            inputs: Vec::new(),
+            fee,
+            weight: 1_000,
+            size: 10,
+            anchor_bytes,
+            output_count: 0,
+            source: TxSource::Local,
+        };
+        // 1.1 per weight unit raw, 1100/1200 once each anchor byte costs
+        // two extra weight units: below the plain payment's 1.0.
+        let anchored = entry(0xA1, 1_100, 100);
+        let payment = entry(0xB2, 1_000, 0);
+        let pool_with = |multiplier: u64| {
+            let mut pool = TxPool::new_with_config(TxPoolConfig {
+                policy_anchor_fee_rate_multiplier: multiplier,
+                ..TxPoolConfig::default()
+            });
+            pool.insert_entry([0xA1; 32], anchored.clone());
+            pool.insert_entry([0xB2; 32], payment.clone());
+            pool
+        };
+
+        assert_eq!(
+            pool_with(0).select_transactions(2, 100),
+            vec![vec![0xA1], vec![0xB2]]
+        );
+        assert_eq!(
+            pool_with(2).select_transactions(2, 100),
+            vec![vec![0xB2], vec![0xA1]]
+        );
+
+        // Under count pressure a payment candidate displaces the anchored
+        // resident only when the surcharge applies.
+        let candidate = entry(0xC3, 1_050, 0);
+        let mut pool = TxPool::new();
+        pool.set_capacity_for_test(1, 1_000);
+        pool.insert_entry([0xA1; 32], anchored.clone());
+        let err = pool
+            .insert_capacity_checked_entry_for_test([0xC3; 32], candidate.clone())
+            .unwrap_err();
+        assert_eq!(err.kind, TxPoolAdmitErrorKind::Unavailable);
+
+        let mut pool = TxPool::new_with_config(TxPoolConfig {
+            policy_anchor_fee_rate_multiplier: 2,
+            ..TxPoolConfig::default()
+        });
+        pool.set_capacity_for_test(1, 1_000);
+        pool.insert_entry([0xA1; 32], anchored);
+        pool.insert_capacity_checked_entry_for_test([0xC3; 32], candidate)
+            .expect("surcharged resident evicted");
+        assert_eq!(pool.all_txids(), vec![[0xC3; 32]]);
+    }
+
     #[test]
     fn txpool_fee_rate_uses_weight_not_size() {
         let size_favored = TxPoolEntry {
@@ -3783,6 +4984,8 @@ mod tests {
             fee: 4,
             weight: 4,
             size: 1,
+            anchor_bytes: 0,
+            output_count: 0,
             source: TxSource::Local,
         };
         let weight_favored = TxPoolEntry {
@@ -3791,6 +4994,8 @@ mod tests {
             fee: 2,
             weight: 1,
             size: 1,
+            anchor_bytes: 0,
+            output_count: 0,
             source: TxSource::Local,
         };
 
@@ -3810,6 +5015,7 @@ mod tests {
             compare_entries_for_mining(
                 &(&weight_favored_txid, &weight_favored),
                 &(&size_favored_txid, &size_favored),
+                0,
             ),
             Ordering::Less,
         );
@@ -3826,6 +5032,8 @@ mod tests {
                 fee: 5,
                 weight: 10,
                 size: 2,
+                anchor_bytes: 0,
+                output_count: 0,
                 source: TxSource::Local,
             },
         );
@@ -3837,6 +5045,8 @@ mod tests {
                 fee: 100,
                 weight: 10,
                 size: 3,
+                anchor_bytes: 0,
+                output_count: 0,
                 source: TxSource::Local,
             },
         );
@@ -3890,6 +5100,8 @@ mod tests {
                     fee: idx as u64 + 1,
                     weight: idx as u64 + 1,
                     size: 1,
+                    anchor_bytes: 0,
+                    output_count: 0,
                     source: TxSource::Local,
                 },
             );
@@ -3918,6 +5130,8 @@ mod tests {
                     fee: idx as u64 + 10,
                     weight: idx as u64 + 10,
                     size: 1,
+                    anchor_bytes: 0,
+                    output_count: 0,
                     source: TxSource::Local,
                 },
             );
@@ -3932,6 +5146,8 @@ mod tests {
                 fee: 30,
                 weight: 30,
                 size: 1,
+                anchor_bytes: 0,
+                output_count: 0,
                 source: TxSource::Local,
             },
         );
@@ -3953,6 +5169,8 @@ mod tests {
             fee: 10,
             weight: 0,
             size: 10,
+            anchor_bytes: 0,
+            output_count: 0,
             source: TxSource::Local,
         };
         let normal = TxPoolEntry {
@@ -3961,6 +5179,8 @@ mod tests {
             fee: 20,
             weight: 20,
             size: 10,
+            anchor_bytes: 0,
+            output_count: 0,
             source: TxSource::Local,
         };
         assert_eq!(compare_fee_rate(&zero, &normal), Ordering::Equal);
@@ -3971,6 +5191,8 @@ mod tests {
             fee: 30,
             weight: 10,
             size: 10,
+            anchor_bytes: 0,
+            output_count: 0,
             source: TxSource::Local,
         };
         let low_fee = TxPoolEntry {
@@ -3979,6 +5201,8 @@ mod tests {
             fee: 20,
             weight: 10,
             size: 10,
+            anchor_bytes: 0,
+            output_count: 0,
             source: TxSource::Local,
         };
         assert_eq!(
@@ -3988,7 +5212,7 @@ mod tests {
         let high_txid: [u8; 32] = [0x03; 32];
         let low_txid: [u8; 32] = [0x02; 32];
         assert_eq!(
-            compare_entries_for_mining(&(&high_txid, &high_fee), &(&low_txid, &low_fee)),
+            compare_entries_for_mining(&(&high_txid, &high_fee), &(&low_txid, &low_fee), 0),
             Ordering::Less
         );
 
@@ -3998,6 +5222,8 @@ mod tests {
             fee: 20,
             weight: 5,
             size: 10,
+            anchor_bytes: 0,
+            output_count: 0,
             source: TxSource::Local,
         };
         let heavier = TxPoolEntry {
@@ -4006,12 +5232,14 @@ mod tests {
             fee: 20,
             weight: 8,
             size: 10,
+            anchor_bytes: 0,
+            output_count: 0,
             source: TxSource::Local,
         };
         let lighter_txid: [u8; 32] = [0x04; 32];
         let heavier_txid: [u8; 32] = [0x05; 32];
         assert_eq!(
-            compare_entries_for_mining(&(&lighter_txid, &lighter), &(&heavier_txid, &heavier),),
+            compare_entries_for_mining(&(&lighter_txid, &lighter), &(&heavier_txid, &heavier), 0),
             Ordering::Less
         );
 
@@ -4025,6 +5253,8 @@ mod tests {
             fee: 20,
             weight: 10,
             size: 10,
+            anchor_bytes: 0,
+            output_count: 0,
             source: TxSource::Local,
         };
         let equal_b = TxPoolEntry {
@@ -4033,12 +5263,14 @@ mod tests {
             fee: 20,
             weight: 10,
             size: 10,
+            anchor_bytes: 0,
+            output_count: 0,
             source: TxSource::Local,
         };
         let lo_txid: [u8; 32] = [0x01; 32];
         let hi_txid: [u8; 32] = [0x02; 32];
         assert_eq!(
-            compare_entries_for_mining(&(&lo_txid, &equal_a), &(&hi_txid, &equal_b)),
+            compare_entries_for_mining(&(&lo_txid, &equal_a), &(&hi_txid, &equal_b), 0),
             Ordering::Less,
             "lower txid must sort first regardless of raw bytes",
         );
@@ -4809,6 +6041,8 @@ mod tests {
                 fee: 100,
                 weight: 1,
                 size: 1,
+                anchor_bytes: 0,
+                output_count: 0,
                 source: TxSource::Local,
             },
         );
@@ -4896,6 +6130,8 @@ mod tests {
                 fee: 100,
                 weight: 1,
                 size: 1,
+                anchor_bytes: 0,
+                output_count: 0,
                 source: TxSource::Local,
             },
         );
@@ -4940,6 +6176,8 @@ mod tests {
                 fee: 100,
                 weight: 1,
                 size: 1,
+                anchor_bytes: 0,
+                output_count: 0,
                 source: TxSource::Local,
             },
         );
@@ -4987,6 +6225,8 @@ mod tests {
                 fee: 30,
                 weight: 3,
                 size: 3,
+                anchor_bytes: 0,
+                output_count: 0,
                 source: TxSource::Remote,
             },
         );
@@ -4998,6 +6238,8 @@ mod tests {
                 fee: 50,
                 weight: 5,
                 size: 5,
+                anchor_bytes: 0,
+                output_count: 0,
                 source: TxSource::Reorg,
             },
         );
@@ -5072,6 +6314,8 @@ mod tests {
                 fee: 70,
                 weight: 7,
                 size: 7,
+                anchor_bytes: 0,
+                output_count: 0,
                 source: TxSource::Remote,
             },
         );
@@ -5093,6 +6337,8 @@ mod tests {
                 fee: 110,
                 weight: 11,
                 size: 11,
+                anchor_bytes: 0,
+                output_count: 0,
                 source: TxSource::Reorg,
             },
         );
@@ -5330,6 +6576,8 @@ mod tests {
                     fee: 100,
                     weight: 1,
                     size: 1,
+                    anchor_bytes: 0,
+                    output_count: 0,
                     source: TxSource::Local,
                 },
             );
@@ -5704,6 +6952,7 @@ mod tests {
             &zero_inputs,
             &state.utxos,
             /* next_height */ 1,
+            COINBASE_MATURITY,
             None,
             None,
         );
@@ -5722,6 +6971,7 @@ mod tests {
             &two_inputs,
             &state.utxos,
             /* next_height */ 1,
+            COINBASE_MATURITY,
             None,
             None,
         );
@@ -5786,6 +7036,7 @@ mod tests {
             /* weight */ 0,
             /* min_fee_rate */ 1,
             /* next_height */ 1,
+            /* coinbase_maturity */ COINBASE_MATURITY,
             /* rotation */ None,
             /* registry */ None,
         );
@@ -5825,6 +7076,7 @@ mod tests {
             /* weight */ 1,
             /* min_fee_rate */ 1,
             /* next_height */ 1,
+            /* coinbase_maturity */ COINBASE_MATURITY,
             /* rotation */ None,
             /* registry */ None,
         );
@@ -5856,6 +7108,7 @@ mod tests {
             /* weight */ 1,
             /* min_fee_rate */ 1,
             /* next_height */ 1,
+            /* coinbase_maturity */ COINBASE_MATURITY,
             /* rotation */ None,
             /* registry */ None,
         );
@@ -5987,6 +7240,7 @@ mod tests {
             &parsed,
             &state.utxos,
             /* next_height */ 1,
+            COINBASE_MATURITY,
             None,
             None,
         );
@@ -6013,6 +7267,7 @@ mod tests {
             &parsed,
             &state.utxos,
             /* next_height */ 1,
+            COINBASE_MATURITY,
             None,
             None,
         );
@@ -6043,6 +7298,7 @@ mod tests {
             &zero_witness,
             &state.utxos,
             /* next_height */ 1,
+            COINBASE_MATURITY,
             None,
             None,
         );
@@ -6059,6 +7315,7 @@ mod tests {
             &two_witness,
             &state.utxos,
             /* next_height */ 1,
+            COINBASE_MATURITY,
             None,
             None,
         );
@@ -6087,6 +7344,7 @@ mod tests {
             &parsed,
             &state.utxos,
             /* next_height */ 1,
+            COINBASE_MATURITY,
             None,
             None,
         );
@@ -6112,7 +7370,6 @@ mod tests {
     /// rating of P2 ("transient -> transient") was wrong.
     #[test]
     fn rub166_precheck_defers_when_p2pk_input_is_immature_coinbase() {
-        use rubin_consensus::constants::COINBASE_MATURITY;
         let (mut state, raw, _conflict) = signed_conflicting_p2pk_state_and_txs(20, 10, 9);
         let (parsed, _txid, _wtxid, _consumed) = parse_tx(&raw).expect("parse tx");
         // Mark the input UTXO as a coinbase output created at height 0.
@@ -6125,8 +7382,14 @@ mod tests {
         entry.creation_height = 0;
         // next_height < COINBASE_MATURITY threshold => immature.
         let immature_height = COINBASE_MATURITY - 1;
-        let result =
-            fee_precheck_p2pk_input_value(&parsed, &state.utxos, immature_height, None, None);
+        let result = fee_precheck_p2pk_input_value(
+            &parsed,
+            &state.utxos,
+            immature_height,
+            COINBASE_MATURITY,
+            None,
+            None,
+        );
         assert!(
             result.is_none(),
             "immature coinbase spend must return None so precheck defers; got {:?}",
@@ -6135,13 +7398,32 @@ mod tests {
         // At maturity threshold the defer no longer fires (sanity
         // pin for the negative branch).
         let mature_height = COINBASE_MATURITY;
-        let result_mature =
-            fee_precheck_p2pk_input_value(&parsed, &state.utxos, mature_height, None, None);
+        let result_mature = fee_precheck_p2pk_input_value(
+            &parsed,
+            &state.utxos,
+            mature_height,
+            COINBASE_MATURITY,
+            None,
+            None,
+        );
         assert!(
             result_mature.is_some(),
             "mature coinbase spend must NOT defer (precheck returns Some); got {:?}",
             result_mature
         );
+        // A chain with overridden maturity moves the threshold with it.
+        let fast = ChainParams {
+            target_block_interval: 2,
+            window_size: 16,
+            coinbase_maturity: 4,
+        };
+        let maturity = fast.coinbase_maturity;
+        let fast_immature =
+            fee_precheck_p2pk_input_value(&parsed, &state.utxos, 3, maturity, None, None);
+        assert!(fast_immature.is_none(), "got {:?}", fast_immature);
+        let fast_mature =
+            fee_precheck_p2pk_input_value(&parsed, &state.utxos, 4, maturity, None, None);
+        assert!(fast_mature.is_some(), "got {:?}", fast_mature);
     }
 
     /// Wave-15 panic-safety: defer when `entry.covenant_data.len()`
@@ -6164,7 +7446,14 @@ mod tests {
         {
             let entry = state.utxos.get_mut(&outpoint).expect("test utxo present");
             entry.covenant_data = vec![];
-            let result = fee_precheck_p2pk_input_value(&parsed, &state.utxos, 1, None, None);
+            let result = fee_precheck_p2pk_input_value(
+                &parsed,
+                &state.utxos,
+                1,
+                COINBASE_MATURITY,
+                None,
+                None,
+            );
             assert!(
                 result.is_none(),
                 "empty covenant_data must defer (None) — guards [0] index against panic; got {:?}",
@@ -6175,7 +7464,14 @@ mod tests {
         {
             let entry = state.utxos.get_mut(&outpoint).expect("test utxo present");
             entry.covenant_data = vec![0u8; 64];
-            let result = fee_precheck_p2pk_input_value(&parsed, &state.utxos, 1, None, None);
+            let result = fee_precheck_p2pk_input_value(
+                &parsed,
+                &state.utxos,
+                1,
+                COINBASE_MATURITY,
+                None,
+                None,
+            );
             assert!(
                 result.is_none(),
                 "oversized covenant_data must defer (None); got {:?}",
@@ -6193,7 +7489,14 @@ mod tests {
             let mut cov = vec![rubin_consensus::constants::SUITE_ID_ML_DSA_87];
             cov.extend_from_slice(&pubkey_hash);
             entry.covenant_data = cov;
-            let result = fee_precheck_p2pk_input_value(&parsed, &state.utxos, 1, None, None);
+            let result = fee_precheck_p2pk_input_value(
+                &parsed,
+                &state.utxos,
+                1,
+                COINBASE_MATURITY,
+                None,
+                None,
+            );
             assert!(
                 result.is_some(),
                 "valid 33-byte covenant_data with matching SHA3 binding must NOT defer; got {:?}",
@@ -6219,7 +7522,14 @@ mod tests {
         let mut bad_trailer = parsed.clone();
         let sig_len = bad_trailer.witness[0].signature.len();
         bad_trailer.witness[0].signature[sig_len - 1] = 0x05;
-        let bad_result = fee_precheck_p2pk_input_value(&bad_trailer, &state.utxos, 1, None, None);
+        let bad_result = fee_precheck_p2pk_input_value(
+            &bad_trailer,
+            &state.utxos,
+            1,
+            COINBASE_MATURITY,
+            None,
+            None,
+        );
         assert!(
             bad_result.is_none(),
             "invalid sighash trailer 0x05 must defer (None); got {:?}",
@@ -6231,7 +7541,14 @@ mod tests {
         let mut none_trailer = parsed.clone();
         let sig_len = none_trailer.witness[0].signature.len();
         none_trailer.witness[0].signature[sig_len - 1] = SIGHASH_NONE;
-        let none_result = fee_precheck_p2pk_input_value(&none_trailer, &state.utxos, 1, None, None);
+        let none_result = fee_precheck_p2pk_input_value(
+            &none_trailer,
+            &state.utxos,
+            1,
+            COINBASE_MATURITY,
+            None,
+            None,
+        );
         // Sanity: SIGHASH_ALL trailer (default) must also accept.
         assert_ne!(SIGHASH_NONE, SIGHASH_ALL);
         // The valid non-ALL trailer should NOT defer due to sighash check
@@ -6262,7 +7579,8 @@ mod tests {
         // Mutate first byte of pubkey so SHA3(pubkey) differs from
         // covenant_data[1..33] (which still binds the original pubkey).
         parsed.witness[0].pubkey[0] ^= 0xFF;
-        let result = fee_precheck_p2pk_input_value(&parsed, &state.utxos, 1, None, None);
+        let result =
+            fee_precheck_p2pk_input_value(&parsed, &state.utxos, 1, COINBASE_MATURITY, None, None);
         assert!(
             result.is_none(),
             "pubkey key-binding mismatch must defer (None); got {:?}",
@@ -6295,7 +7613,14 @@ mod tests {
         let (state, raw, _conflict) = signed_conflicting_p2pk_state_and_txs(7700, 10, 9);
         let (parsed, _txid, _wtxid, _consumed) = parse_tx(&raw).expect("parse tx");
         let rotation = EmptySpendRotation;
-        let result = fee_precheck_p2pk_input_value(&parsed, &state.utxos, 1, Some(&rotation), None);
+        let result = fee_precheck_p2pk_input_value(
+            &parsed,
+            &state.utxos,
+            1,
+            COINBASE_MATURITY,
+            Some(&rotation),
+            None,
+        );
         assert!(
             result.is_none(),
             "rotation with empty native_spend_suites must defer (None); got {:?}",
@@ -6305,7 +7630,8 @@ mod tests {
         // SUITE_ID_ML_DSA_87 in the spend set, so the same fixture with
         // `rotation=None` must NOT defer. Confirms the rotation
         // provider is the only difference exercised by this test.
-        let baseline = fee_precheck_p2pk_input_value(&parsed, &state.utxos, 1, None, None);
+        let baseline =
+            fee_precheck_p2pk_input_value(&parsed, &state.utxos, 1, COINBASE_MATURITY, None, None);
         assert!(
             baseline.is_some(),
             "default rotation must NOT defer on signed valid P2PK fixture; got {:?}",
@@ -6332,8 +7658,14 @@ mod tests {
         // SUITE_ID_ML_DSA_87 carried by the signed fixture witness)
         // returns None, so the wave-14 registry guard fires.
         let empty_registry = SuiteRegistry::with_suites(BTreeMap::new());
-        let result =
-            fee_precheck_p2pk_input_value(&parsed, &state.utxos, 1, None, Some(&empty_registry));
+        let result = fee_precheck_p2pk_input_value(
+            &parsed,
+            &state.utxos,
+            1,
+            COINBASE_MATURITY,
+            None,
+            Some(&empty_registry),
+        );
         assert!(
             result.is_none(),
             "empty registry must defer (lookup miss returns None); got {:?}",
@@ -6343,7 +7675,8 @@ mod tests {
         // SUITE_ID_ML_DSA_87 with canonical params, so the same fixture
         // with `registry=None` must NOT defer. Confirms the registry
         // provider is the only difference exercised by this test.
-        let baseline = fee_precheck_p2pk_input_value(&parsed, &state.utxos, 1, None, None);
+        let baseline =
+            fee_precheck_p2pk_input_value(&parsed, &state.utxos, 1, COINBASE_MATURITY, None, None);
         assert!(
             baseline.is_some(),
             "default registry must NOT defer on signed valid P2PK fixture; got {:?}",
@@ -6368,7 +7701,8 @@ mod tests {
         {
             let mut p = parsed.clone();
             p.witness[0].pubkey.pop().expect("witness pubkey non-empty");
-            let result = fee_precheck_p2pk_input_value(&p, &state.utxos, 1, None, None);
+            let result =
+                fee_precheck_p2pk_input_value(&p, &state.utxos, 1, COINBASE_MATURITY, None, None);
             assert!(
                 result.is_none(),
                 "truncated pubkey must defer (None); got {:?}",
@@ -6379,7 +7713,8 @@ mod tests {
         {
             let mut p = parsed.clone();
             p.witness[0].signature.push(0u8);
-            let result = fee_precheck_p2pk_input_value(&p, &state.utxos, 1, None, None);
+            let result =
+                fee_precheck_p2pk_input_value(&p, &state.utxos, 1, COINBASE_MATURITY, None, None);
             assert!(
                 result.is_none(),
                 "oversized signature must defer (None); got {:?}",
@@ -6387,7 +7722,8 @@ mod tests {
             );
         }
         // Sanity (negative-branch pin): canonical lengths must NOT defer.
-        let baseline = fee_precheck_p2pk_input_value(&parsed, &state.utxos, 1, None, None);
+        let baseline =
+            fee_precheck_p2pk_input_value(&parsed, &state.utxos, 1, COINBASE_MATURITY, None, None);
         assert!(
             baseline.is_some(),
             "canonical pubkey/signature lengths must NOT defer; got {:?}",
@@ -6430,7 +7766,8 @@ mod tests {
         );
         new_cov[0] = 0xFE;
         entry.covenant_data = new_cov;
-        let result = fee_precheck_p2pk_input_value(&parsed, &state.utxos, 1, None, None);
+        let result =
+            fee_precheck_p2pk_input_value(&parsed, &state.utxos, 1, COINBASE_MATURITY, None, None);
         assert!(
             result.is_none(),
             "covenant_data[0] != witness.suite_id must defer (None); got {:?}",
@@ -6712,6 +8049,8 @@ mod tests {
             fee: 100,
             weight: 1,
             size: 1,
+            anchor_bytes: 0,
+            output_count: 0,
             source,
         };
 
@@ -6736,4 +8075,172 @@ mod tests {
              that differ ONLY in source assignment (comparator must be source-blind)"
         );
     }
+
+    /// Test entry spending output 0 of each of `parents`, with a raw that
+    /// identifies it in selection output.
+    fn chained_entry(tag: u8, fee: u64, weight: u64, parents: &[[u8; 32]]) -> TxPoolEntry {
+        TxPoolEntry {
+            raw: vec![tag],
+            inputs: parents
+                .iter()
+                .map(|txid| Outpoint {
+                    txid: *txid,
+                    vout: 0,
+                })
+                .collect(),
+            fee,
+            weight,
+            size: weight as usize,
+            anchor_bytes: 0,
+            output_count: 1,
+            source: TxSource::Local,
+        }
+    }
+
+    fn chain_txid(i: u8) -> [u8; 32] {
+        let mut txid = [0xC0; 32];
+        txid[31] = i;
+        txid
+    }
+
+    #[test]
+    fn ancestor_limit_stops_a_long_unconfirmed_chain() {
+        let mut pool = TxPool::new();
+        let mut admitted = 0u8;
+        let mut first_err = None;
+        for i in 0..30u8 {
+            let parents = if i == 0 {
+                Vec::new()
+            } else {
+                vec![chain_txid(i - 1)]
+            };
+            let entry = chained_entry(i, 100, 10, &parents);
+            if let Err(err) = pool.check_package_limits(&entry.inputs, entry.weight) {
+                first_err = Some(err);
+                break;
+            }
+            pool.insert_entry(chain_txid(i), entry);
+            admitted += 1;
+        }
+        assert_eq!(u64::from(admitted), DEFAULT_MAX_ANCESTORS);
+        let err = first_err.expect("26th link rejected");
+        assert_eq!(err.kind, TxPoolAdmitErrorKind::Rejected);
+        assert_eq!(
+            err.message,
+            "too many unconfirmed ancestors (count=26 max=25) (policy)"
+        );
+        let tip = pool.graph.node(&chain_txid(24)).expect("tip");
+        assert_eq!(tip.ancestors.len(), 24);
+        assert_eq!((tip.ancestor_fee, tip.ancestor_weight), (2_500, 250));
+
+        // A second child of the root also runs into the root's descendant cap.
+        let sibling = chained_entry(0xEE, 100, 10, &[chain_txid(0)]);
+        let err = pool
+            .check_package_limits(&sibling.inputs, sibling.weight)
+            .unwrap_err();
+        assert!(
+            err.message.starts_with("too many unconfirmed descendants"),
+            "{err}"
+        );
+
+        // Confirming the root shortens every remaining chain by one.
+        pool.evict_txids(&[chain_txid(0)]);
+        let next = chained_entry(25, 100, 10, &[chain_txid(24)]);
+        pool.check_package_limits(&next.inputs, next.weight)
+            .expect("room after confirmation");
+
+        let heavy = chained_entry(0xEF, 100, DEFAULT_MAX_PACKAGE_WEIGHT, &[chain_txid(24)]);
+        let err = pool
+            .check_package_limits(&heavy.inputs, heavy.weight)
+            .unwrap_err();
+        assert_eq!(
+            err.message,
+            format!(
+                "ancestor package too heavy (weight={} max={DEFAULT_MAX_PACKAGE_WEIGHT}) (policy)",
+                DEFAULT_MAX_PACKAGE_WEIGHT + 240
+            )
+        );
+    }
+
+    #[test]
+    fn selection_lets_a_high_fee_child_pull_in_its_low_fee_parent() {
+        let (parent, child, other) = ([0xD1; 32], [0xD2; 32], [0xD3; 32]);
+        let mut pool = TxPool::new();
+        pool.insert_entry(parent, chained_entry(1, 10, 100, &[]));
+        pool.insert_entry(child, chained_entry(2, 1_000, 100, &[parent]));
+        pool.insert_entry(other, chained_entry(3, 300, 100, &[]));
+
+        // Package (parent, child) pays 1010/200 against 300/100 alone, and
+        // the parent is emitted first.
+        assert_eq!(
+            pool.select_transactions(10, usize::MAX),
+            vec![vec![1], vec![2], vec![3]]
+        );
+        // Two slots: the package still wins over the single tx.
+        assert_eq!(
+            pool.select_transactions(2, usize::MAX),
+            vec![vec![1], vec![2]]
+        );
+        // One slot cannot hold the package, so the child never lands alone.
+        assert_eq!(pool.select_transactions(1, usize::MAX), vec![vec![3]]);
+        // Filtering the parent drops the child with it.
+        assert_eq!(
+            pool.select_transactions_with_filter(10, usize::MAX, |raw| raw == vec![1]),
+            vec![vec![3]]
+        );
+    }
+
+    #[test]
+    fn eviction_removes_whole_descendant_subtrees() {
+        let (root, mid, leaf, side) = ([0xE1; 32], [0xE2; 32], [0xE3; 32], [0xE4; 32]);
+        let build = || {
+            let mut pool = TxPool::new();
+            let mut root_entry = chained_entry(1, 1, 100, &[]);
+            root_entry.inputs.push(Outpoint {
+                txid: [0x99; 32],
+                vout: 7,
+            });
+            pool.insert_entry(root, root_entry);
+            pool.insert_entry(mid, chained_entry(2, 10, 100, &[root]));
+            pool.insert_entry(leaf, chained_entry(3, 10, 100, &[mid]));
+            pool.insert_entry(side, chained_entry(4, 50, 100, &[]));
+            pool
+        };
+
+        // A block spending the root's input invalidates the whole chain.
+        let mut conflicted = build();
+        conflicted.remove_conflicting_outpoints(&[Outpoint {
+            txid: [0x99; 32],
+            vout: 7,
+        }]);
+        assert_eq!(conflicted.all_txids(), vec![side]);
+        assert_eq!(conflicted.used_bytes, 100);
+        assert_eq!(conflicted.spenders.len(), 0);
+        assert!(conflicted.graph.node(&mid).is_none());
+
+        // Confirming the root keeps its children, now rooted at `mid`.
+        let mut confirmed = build();
+        confirmed.evict_txids(&[root]);
+        assert_eq!(confirmed.len(), 3);
+        let node = confirmed.graph.node(&leaf).expect("leaf");
+        assert_eq!(node.ancestors, HashSet::from([mid]));
+        assert_eq!(node.ancestor_fee, 20);
+
+        // Capacity pressure evicts the worst entry (the root) with its
+        // subtree, children before parents.
+        let mut full = build();
+        full.set_capacity_for_test(4, 10_000);
+        let candidate = chained_entry(5, 1_000, 100, &[]);
+        let plan = full.capacity_eviction_plan([0xE5; 32], &candidate).unwrap();
+        assert_eq!(plan, vec![leaf, mid, root]);
+
+        // ... unless the candidate itself descends from the worst entry.
+        let grandchild = chained_entry(6, 1_000, 100, &[leaf]);
+        let err = full
+            .capacity_eviction_plan([0xE6; 32], &grandchild)
+            .unwrap_err();
+        assert!(err
+            .message
+            .contains("candidate rejected by eviction ordering"));
+    }
 }
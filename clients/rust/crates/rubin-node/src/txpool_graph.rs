@@ -0,0 +1,362 @@
+//! Unconfirmed-dependency graph of the transaction pool.
+//!
+//! An edge runs from a pool entry to every pool entry spending one of its
+//! outputs. Each node caches its full in-pool ancestor set and the fee,
+//! weight and anchor bytes of that set plus the entry itself, so admission
+//! limits and package (child-pays-for-parent) selection read aggregates
+//! without walking the chain. The graph holds txids only; entry metadata
+//! stays in the pool's `txs` map and is passed in by the caller.
+
+use std::collections::{HashMap, HashSet};
+
+use rubin_consensus::Outpoint;
+
+use crate::txpool::TxPoolEntry;
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub(crate) struct TxGraphNode {
+    pub(crate) parents: HashSet<[u8; 32]>,
+    pub(crate) children: HashSet<[u8; 32]>,
+    pub(crate) ancestors: HashSet<[u8; 32]>,
+    /// Totals over `ancestors` plus the entry itself.
+    pub(crate) ancestor_fee: u64,
+    pub(crate) ancestor_weight: u64,
+    pub(crate) ancestor_anchor_bytes: u64,
+}
+
+#[derive(Clone, Debug, Default)]
+pub(crate) struct TxGraph {
+    nodes: HashMap<[u8; 32], TxGraphNode>,
+}
+
+/// Pool entries whose outputs `inputs` spend. A matching txid alone is not
+/// enough: the outpoint must name one of that entry's outputs.
+pub(crate) fn in_pool_parents(
+    inputs: &[Outpoint],
+    txs: &HashMap<[u8; 32], TxPoolEntry>,
+) -> HashSet<[u8; 32]> {
+    inputs
+        .iter()
+        .filter(|input| {
+            txs.get(&input.txid)
+                .is_some_and(|entry| (input.vout as usize) < entry.output_count)
+        })
+        .map(|input| input.txid)
+        .collect()
+}
+
+impl TxGraph {
+    pub(crate) fn node(&self, txid: &[u8; 32]) -> Option<&TxGraphNode> {
+        self.nodes.get(txid)
+    }
+
+    /// Ancestor set of an entry whose in-pool parents are `parents`.
+    pub(crate) fn ancestors_of_parents(&self, parents: &HashSet<[u8; 32]>) -> HashSet<[u8; 32]> {
+        let mut ancestors = HashSet::new();
+        for parent in parents {
+            ancestors.insert(*parent);
+            if let Some(node) = self.nodes.get(parent) {
+                ancestors.extend(node.ancestors.iter().copied());
+            }
+        }
+        ancestors
+    }
+
+    /// In-pool descendants of `txid`, excluding `txid`, parents before
+    /// children.
+    pub(crate) fn descendants(&self, txid: &[u8; 32]) -> Vec<[u8; 32]> {
+        let mut found = HashSet::new();
+        let mut stack = vec![*txid];
+        while let Some(current) = stack.pop() {
+            let Some(node) = self.nodes.get(&current) else {
+                continue;
+            };
+            for child in &node.children {
+                if found.insert(*child) {
+                    stack.push(*child);
+                }
+            }
+        }
+        self.topological(found)
+    }
+
+    /// Links `txid` (already present in `txs`) to its in-pool parents and
+    /// to any resident entries spending its outputs, found through the
+    /// pool's `spenders` index, then refreshes the cached ancestors of the
+    /// entry and everything below it.
+    pub(crate) fn insert(
+        &mut self,
+        txid: [u8; 32],
+        txs: &HashMap<[u8; 32], TxPoolEntry>,
+        spenders: &HashMap<Outpoint, [u8; 32]>,
+    ) {
+        let Some(entry) = txs.get(&txid) else {
+            return;
+        };
+        let parents = in_pool_parents(&entry.inputs, txs);
+        let children: HashSet<[u8; 32]> = (0..entry.output_count)
+            .filter_map(|vout| {
+                spenders.get(&Outpoint {
+                    txid,
+                    vout: vout as u32,
+                })
+            })
+            .copied()
+            .collect();
+        for parent in &parents {
+            if let Some(node) = self.nodes.get_mut(parent) {
+                node.children.insert(txid);
+            }
+        }
+        for child in &children {
+            if let Some(node) = self.nodes.get_mut(child) {
+                node.parents.insert(txid);
+            }
+        }
+        self.nodes.insert(
+            txid,
+            TxGraphNode {
+                parents,
+                children,
+                ..TxGraphNode::default()
+            },
+        );
+        self.refresh(txid, txs);
+        for descendant in self.descendants(&txid) {
+            self.refresh(descendant, txs);
+        }
+    }
+
+    /// Unlinks `txid` (already gone from `txs`). Its children stay in the
+    /// graph with the removed entry dropped from their ancestry, which is
+    /// right for a confirmed parent; callers evicting an invalid parent
+    /// remove the descendants first.
+    pub(crate) fn remove(&mut self, txid: &[u8; 32], txs: &HashMap<[u8; 32], TxPoolEntry>) {
+        let descendants = self.descendants(txid);
+        let Some(node) = self.nodes.remove(txid) else {
+            return;
+        };
+        for parent in &node.parents {
+            if let Some(parent_node) = self.nodes.get_mut(parent) {
+                parent_node.children.remove(txid);
+            }
+        }
+        for child in &node.children {
+            if let Some(child_node) = self.nodes.get_mut(child) {
+                child_node.parents.remove(txid);
+            }
+        }
+        for descendant in descendants {
+            self.refresh(descendant, txs);
+        }
+    }
+
+    pub(crate) fn rebuild(txs: &HashMap<[u8; 32], TxPoolEntry>) -> Self {
+        let mut graph = Self::default();
+        for (txid, entry) in txs {
+            let parents = in_pool_parents(&entry.inputs, txs);
+            for parent in &parents {
+                graph
+                    .nodes
+                    .entry(*parent)
+                    .or_default()
+                    .children
+                    .insert(*txid);
+            }
+            graph.nodes.entry(*txid).or_default().parents = parents;
+        }
+        let all: HashSet<[u8; 32]> = graph.nodes.keys().copied().collect();
+        for txid in graph.topological(all) {
+            graph.refresh(txid, txs);
+        }
+        graph
+    }
+
+    /// Recomputes the ancestor set and totals of `txid` from its parents,
+    /// whose caches must already be current.
+    fn refresh(&mut self, txid: [u8; 32], txs: &HashMap<[u8; 32], TxPoolEntry>) {
+        let Some(parents) = self.nodes.get(&txid).map(|node| node.parents.clone()) else {
+            return;
+        };
+        let ancestors = self.ancestors_of_parents(&parents);
+        let (mut fee, mut weight, mut anchor_bytes) = txs.get(&txid).map_or((0, 0, 0), |entry| {
+            (entry.fee, entry.weight, entry.anchor_bytes)
+        });
+        for ancestor in &ancestors {
+            if let Some(entry) = txs.get(ancestor) {
+                fee = fee.saturating_add(entry.fee);
+                weight = weight.saturating_add(entry.weight);
+                anchor_bytes = anchor_bytes.saturating_add(entry.anchor_bytes);
+            }
+        }
+        if let Some(node) = self.nodes.get_mut(&txid) {
+            node.ancestors = ancestors;
+            node.ancestor_fee = fee;
+            node.ancestor_weight = weight;
+            node.ancestor_anchor_bytes = anchor_bytes;
+        }
+    }
+
+    /// Orders `set` so every member follows its parents within the set.
+    /// Ties are broken by txid for determinism.
+    pub(crate) fn topological(&self, set: HashSet<[u8; 32]>) -> Vec<[u8; 32]> {
+        let mut pending: HashMap<[u8; 32], usize> = set
+            .iter()
+            .map(|txid| {
+                let in_set = self.nodes.get(txid).map_or(0, |node| {
+                    node.parents
+                        .iter()
+                        .filter(|parent| set.contains(*parent))
+                        .count()
+                });
+                (*txid, in_set)
+            })
+            .collect();
+        let mut ready: Vec<[u8; 32]> = pending
+            .iter()
+            .filter(|(_, count)| **count == 0)
+            .map(|(txid, _)| *txid)
+            .collect();
+        let mut ordered = Vec::with_capacity(set.len());
+        while !ready.is_empty() {
+            ready.sort_unstable_by(|a, b| b.cmp(a));
+            let Some(txid) = ready.pop() else {
+                break;
+            };
+            pending.remove(&txid);
+            ordered.push(txid);
+            let Some(node) = self.nodes.get(&txid) else {
+                continue;
+            };
+            for child in &node.children {
+                if let Some(count) = pending.get_mut(child) {
+                    *count -= 1;
+                    if *count == 0 {
+                        ready.push(*child);
+                    }
+                }
+            }
+        }
+        ordered
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::txpool::TxSource;
+
+    /// One-output entry spending output 0 of each of `parents`.
+    fn entry(fee: u64, weight: u64, parents: &[[u8; 32]]) -> TxPoolEntry {
+        TxPoolEntry {
+            raw: vec![0x01],
+            inputs: parents
+                .iter()
+                .map(|txid| Outpoint {
+                    txid: *txid,
+                    vout: 0,
+                })
+                .collect(),
+            fee,
+            weight,
+            size: weight as usize,
+            anchor_bytes: 0,
+            output_count: 1,
+            source: TxSource::Local,
+        }
+    }
+
+    /// Adds `entry` to `txs` and `spenders` the way the pool does, then
+    /// links it into `graph`.
+    fn admit(
+        graph: &mut TxGraph,
+        txs: &mut HashMap<[u8; 32], TxPoolEntry>,
+        spenders: &mut HashMap<Outpoint, [u8; 32]>,
+        txid: [u8; 32],
+        entry: TxPoolEntry,
+    ) {
+        for input in &entry.inputs {
+            spenders.insert(input.clone(), txid);
+        }
+        txs.insert(txid, entry);
+        graph.insert(txid, txs, spenders);
+    }
+
+    #[test]
+    fn insert_and_remove_keep_ancestor_totals_current() {
+        let (a, b, c) = ([0xa1; 32], [0xb2; 32], [0xc3; 32]);
+        let mut txs = HashMap::new();
+        let mut spenders = HashMap::new();
+        let mut graph = TxGraph::default();
+        // Child first: linking the parent later must refresh it.
+        admit(&mut graph, &mut txs, &mut spenders, c, entry(30, 300, &[b]));
+        admit(&mut graph, &mut txs, &mut spenders, a, entry(10, 100, &[]));
+        admit(&mut graph, &mut txs, &mut spenders, b, entry(20, 200, &[a]));
+
+        let node = graph.node(&c).expect("c");
+        assert_eq!(node.ancestors, HashSet::from([a, b]));
+        assert_eq!((node.ancestor_fee, node.ancestor_weight), (60, 600));
+        assert_eq!(graph.descendants(&a), vec![b, c]);
+        assert_eq!(graph.topological(HashSet::from([a, b, c])), vec![a, b, c]);
+
+        // Confirming `a` leaves `b` and `c` with `a` dropped from ancestry.
+        txs.remove(&a);
+        graph.remove(&a, &txs);
+        let node = graph.node(&c).expect("c");
+        assert_eq!(node.ancestors, HashSet::from([b]));
+        assert_eq!((node.ancestor_fee, node.ancestor_weight), (50, 500));
+        assert!(graph.node(&b).expect("b").parents.is_empty());
+
+        let rebuilt = TxGraph::rebuild(&txs);
+        assert_eq!(rebuilt.node(&c), graph.node(&c));
+        assert_eq!(rebuilt.node(&b), graph.node(&b));
+    }
+
+    #[test]
+    fn diamond_counts_shared_ancestor_once() {
+        let (a, b, c, d) = ([1; 32], [2; 32], [3; 32], [4; 32]);
+        let mut txs = HashMap::new();
+        txs.insert(a, entry(1, 10, &[]));
+        txs.insert(b, entry(2, 10, &[a]));
+        txs.insert(c, entry(3, 10, &[a]));
+        txs.insert(d, entry(4, 10, &[b, c]));
+        let graph = TxGraph::rebuild(&txs);
+        let node = graph.node(&d).expect("d");
+        assert_eq!(node.ancestors.len(), 3);
+        assert_eq!((node.ancestor_fee, node.ancestor_weight), (10, 40));
+        assert_eq!(graph.descendants(&a), vec![b, c, d]);
+    }
+
+    #[test]
+    fn parent_requires_the_outpoint_to_be_one_of_its_outputs() {
+        let (a, b) = ([0xa1; 32], [0xb2; 32]);
+        let mut txs = HashMap::new();
+        txs.insert(a, entry(1, 10, &[]));
+        let out_of_range = [Outpoint { txid: a, vout: 1 }];
+        assert!(in_pool_parents(&out_of_range, &txs).is_empty());
+        assert_eq!(
+            in_pool_parents(&[Outpoint { txid: a, vout: 0 }], &txs),
+            HashSet::from([a])
+        );
+
+        // An entry without outputs has nothing to spend, and a resident
+        // spender of its txid is not linked as its child.
+        let child = [0xc3; 32];
+        let mut spenders = HashMap::new();
+        let mut graph = TxGraph::rebuild(&txs);
+        admit(
+            &mut graph,
+            &mut txs,
+            &mut spenders,
+            child,
+            entry(1, 10, &[b]),
+        );
+        let no_outputs = TxPoolEntry {
+            output_count: 0,
+            ..entry(1, 10, &[])
+        };
+        admit(&mut graph, &mut txs, &mut spenders, b, no_outputs);
+        assert!(in_pool_parents(&[Outpoint { txid: b, vout: 0 }], &txs).is_empty());
+        assert!(graph.descendants(&b).is_empty());
+    }
+}
@@ -1,8 +1,12 @@
 use std::collections::HashSet;
 
 use rubin_consensus::constants::{COV_TYPE_ANCHOR, COV_TYPE_DA_COMMIT};
-use rubin_consensus::{block_hash, parse_block_bytes, Outpoint, UtxoEntry};
+use rubin_consensus::{
+    block_hash, canonical_utxo_entry_bytes, compact_size_decode_bounded, encode_compact_size,
+    parse_block_bytes, Outpoint, UtxoEntry,
+};
 use serde::{Deserialize, Serialize};
+use sha3::{Digest, Sha3_256};
 
 use crate::chainstate::ChainState;
 use crate::io_utils::parse_hex32;
@@ -248,7 +252,191 @@ impl ChainState {
 }
 
 // ---------------------------------------------------------------------------
-// JSON serialization (disk format)
+// Binary serialization (disk format)
+// ---------------------------------------------------------------------------
+//
+// Version 1 record layout (integers little-endian):
+//
+//   version                     u8   (= UNDO_RECORD_VERSION)
+//   body_len                    u32  (bytes in `body`)
+//   body:
+//     block_height              u64
+//     previous_already_generated u64
+//     tx_count                  compactsize
+//     per tx: spent_count       compactsize
+//       per spent: outpoint key (txid || vout u32), value u64,
+//                  covenant_type u16, compactsize covenant_data,
+//                  creation_height u64, coinbase flag u8 (0 | 1)
+//   checksum                    SHA3-256(version || body_len || body)
+//
+// The length frame and trailing checksum let `unmarshal_block_undo` reject
+// a record truncated or torn by a crash instead of handing
+// `disconnect_block` a short spent list. Version 0 is the legacy
+// pretty-JSON record, still accepted on read.
+
+/// Version byte of records written by `marshal_block_undo`.
+pub const UNDO_RECORD_VERSION: u8 = 1;
+/// Version assigned to legacy JSON undo records (they carry no version
+/// byte; they are recognised by their leading `{`).
+pub const UNDO_RECORD_LEGACY_VERSION: u8 = 0;
+
+const UNDO_RECORD_HEADER_BYTES: usize = 1 + 4;
+const UNDO_RECORD_CHECKSUM_BYTES: usize = 32;
+// Smallest possible spent entry: key, value, type, empty data, height, flag.
+const UNDO_SPENT_MIN_BYTES: usize = 36 + 8 + 2 + 1 + 8 + 1;
+
+pub fn marshal_block_undo(undo: &BlockUndo) -> Result<Vec<u8>, String> {
+    let mut body = Vec::new();
+    body.extend_from_slice(&undo.block_height.to_le_bytes());
+    body.extend_from_slice(&undo.previous_already_generated.to_le_bytes());
+    encode_compact_size(undo.txs.len() as u64, &mut body);
+    for tx_undo in &undo.txs {
+        encode_compact_size(tx_undo.spent.len() as u64, &mut body);
+        for spent in &tx_undo.spent {
+            body.extend_from_slice(&canonical_utxo_entry_bytes(&spent.outpoint, &spent.entry));
+        }
+    }
+    let body_len = u32::try_from(body.len())
+        .map_err(|_| format!("encode undo: body of {} bytes exceeds u32", body.len()))?;
+
+    let mut raw = Vec::with_capacity(UNDO_RECORD_HEADER_BYTES + body.len() + 32);
+    raw.push(UNDO_RECORD_VERSION);
+    raw.extend_from_slice(&body_len.to_le_bytes());
+    raw.extend_from_slice(&body);
+    let checksum = Sha3_256::digest(&raw);
+    raw.extend_from_slice(&checksum);
+    Ok(raw)
+}
+
+/// Decodes an undo record of any supported version, verifying the length
+/// frame and checksum of versioned records.
+pub fn unmarshal_block_undo(raw: &[u8]) -> Result<BlockUndo, String> {
+    match undo_record_version(raw)? {
+        UNDO_RECORD_LEGACY_VERSION => unmarshal_block_undo_v0(raw),
+        _ => unmarshal_block_undo_v1(raw),
+    }
+}
+
+/// Returns the record version without decoding the body.
+pub fn undo_record_version(raw: &[u8]) -> Result<u8, String> {
+    match raw.first() {
+        None => Err("decode undo: empty record".into()),
+        Some(b'{') => Ok(UNDO_RECORD_LEGACY_VERSION),
+        Some(&UNDO_RECORD_VERSION) => Ok(UNDO_RECORD_VERSION),
+        Some(&v) => Err(format!("decode undo: unsupported record version {v}")),
+    }
+}
+
+fn unmarshal_block_undo_v1(raw: &[u8]) -> Result<BlockUndo, String> {
+    if raw.len() < UNDO_RECORD_HEADER_BYTES + UNDO_RECORD_CHECKSUM_BYTES {
+        return Err(format!(
+            "decode undo: truncated record ({} bytes)",
+            raw.len()
+        ));
+    }
+    let body_len = u32::from_le_bytes(raw[1..UNDO_RECORD_HEADER_BYTES].try_into().unwrap());
+    let body_end = UNDO_RECORD_HEADER_BYTES + body_len as usize;
+    let want = body_end + UNDO_RECORD_CHECKSUM_BYTES;
+    if raw.len() != want {
+        return Err(format!(
+            "decode undo: record length {} does not match framed length {want}",
+            raw.len()
+        ));
+    }
+    let checksum: [u8; 32] = Sha3_256::digest(&raw[..body_end]).into();
+    if checksum[..] != raw[body_end..] {
+        return Err("decode undo: checksum mismatch".into());
+    }
+
+    let mut r = UndoReader {
+        b: &raw[UNDO_RECORD_HEADER_BYTES..body_end],
+    };
+    let block_height = r.u64("block_height")?;
+    let previous_already_generated = r.u64("previous_already_generated")?;
+    // A tx entry is at least its one-byte spent count.
+    let tx_count = r.count("tx_count", 1)?;
+    let mut txs = Vec::with_capacity(tx_count);
+    for tx_index in 0..tx_count {
+        let spent_count = r.count(
+            &format!("undo[{tx_index}].spent_count"),
+            UNDO_SPENT_MIN_BYTES,
+        )?;
+        let mut spent = Vec::with_capacity(spent_count);
+        for spent_index in 0..spent_count {
+            let field = |name: &str| format!("undo[{tx_index}].spent[{spent_index}].{name}");
+            let txid: [u8; 32] = r.take(&field("txid"), 32)?.try_into().unwrap();
+            let vout = u32::from_le_bytes(r.take(&field("vout"), 4)?.try_into().unwrap());
+            let value = r.u64(&field("value"))?;
+            let covenant_type =
+                u16::from_le_bytes(r.take(&field("covenant_type"), 2)?.try_into().unwrap());
+            let data_len = r.count(&field("covenant_data"), 1)?;
+            let covenant_data = r.take(&field("covenant_data"), data_len)?.to_vec();
+            let creation_height = r.u64(&field("creation_height"))?;
+            let created_by_coinbase = match r.take(&field("created_by_coinbase"), 1)?[0] {
+                0 => false,
+                1 => true,
+                flag => {
+                    return Err(format!(
+                        "decode undo: {}: invalid flag {flag}",
+                        field("created_by_coinbase")
+                    ))
+                }
+            };
+            spent.push(SpentUndo {
+                outpoint: Outpoint { txid, vout },
+                entry: UtxoEntry {
+                    value,
+                    covenant_type,
+                    covenant_data,
+                    creation_height,
+                    created_by_coinbase,
+                },
+            });
+        }
+        txs.push(TxUndo { spent });
+    }
+    if !r.b.is_empty() {
+        return Err(format!("decode undo: {} trailing body bytes", r.b.len()));
+    }
+    Ok(BlockUndo {
+        block_height,
+        previous_already_generated,
+        txs,
+    })
+}
+
+struct UndoReader<'a> {
+    b: &'a [u8],
+}
+
+impl<'a> UndoReader<'a> {
+    fn take(&mut self, field: &str, n: usize) -> Result<&'a [u8], String> {
+        if self.b.len() < n {
+            return Err(format!("decode undo: {field}: truncated body"));
+        }
+        let (head, rest) = self.b.split_at(n);
+        self.b = rest;
+        Ok(head)
+    }
+
+    fn u64(&mut self, field: &str) -> Result<u64, String> {
+        Ok(u64::from_le_bytes(self.take(field, 8)?.try_into().unwrap()))
+    }
+
+    /// Reads a compactsize count of items at least `min_item_bytes` long
+    /// each, rejecting counts the remaining body cannot hold before
+    /// anything is allocated from them.
+    fn count(&mut self, field: &str, min_item_bytes: usize) -> Result<usize, String> {
+        let max = self.b.len() / min_item_bytes;
+        let (n, used) = compact_size_decode_bounded(self.b, max)
+            .map_err(|e| format!("decode undo: {field}: {e}"))?;
+        self.b = &self.b[used..];
+        Ok(n)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Legacy JSON records (version 0)
 // ---------------------------------------------------------------------------
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -274,14 +462,16 @@ struct SpentUndoDisk {
     created_by_coinbase: bool,
 }
 
-pub fn marshal_block_undo(undo: &BlockUndo) -> Result<Vec<u8>, String> {
+/// Encodes the legacy version-0 JSON record. Only kept for migration
+/// tests and JSON-speaking tooling; the store writes version 1.
+pub fn marshal_block_undo_v0(undo: &BlockUndo) -> Result<Vec<u8>, String> {
     let disk = block_undo_to_disk(undo);
     let mut raw = serde_json::to_vec_pretty(&disk).map_err(|e| format!("encode undo: {e}"))?;
     raw.push(b'\n');
     Ok(raw)
 }
 
-pub fn unmarshal_block_undo(raw: &[u8]) -> Result<BlockUndo, String> {
+fn unmarshal_block_undo_v0(raw: &[u8]) -> Result<BlockUndo, String> {
     let disk: BlockUndoDisk =
         serde_json::from_slice(raw).map_err(|e| format!("decode undo: {e}"))?;
     block_undo_from_disk(disk)
@@ -351,16 +541,14 @@ fn block_undo_from_disk(disk: BlockUndoDisk) -> Result<BlockUndo, String> {
 
 #[cfg(test)]
 mod tests {
-    use std::collections::HashMap;
-
     use crate::devnet_genesis_chain_id;
     use crate::test_helpers::block_with_txs;
 
     use super::*;
     use rubin_consensus::constants::{COV_TYPE_P2PK, POW_LIMIT, TX_WIRE_VERSION};
     use rubin_consensus::{
-        marshal_tx, p2pk_covenant_data_for_pubkey, parse_tx, sign_transaction, Mldsa87Keypair, Tx,
-        TxInput, TxOutput,
+        marshal_tx, p2pk_covenant_data_for_pubkey, parse_tx, sign_transaction, Mldsa87Keypair,
+        OutpointMap, Tx, TxInput, TxOutput,
     };
 
     fn sample_outpoint(seed: u8) -> Outpoint {
@@ -397,7 +585,7 @@ mod tests {
             height: 42,
             tip_hash: [0x22; 32],
             already_generated: 7,
-            utxos: HashMap::new(),
+            utxos: OutpointMap::default(),
         };
         prev_state.utxos.insert(
             source_outpoint.clone(),
@@ -515,7 +703,7 @@ mod tests {
             height: 7,
             tip_hash: [0x44; 32],
             already_generated: 3,
-            utxos: HashMap::new(),
+            utxos: OutpointMap::default(),
         };
         prev_state.utxos.insert(
             source_outpoint.clone(),
@@ -636,7 +824,7 @@ mod tests {
             height: 5,
             tip_hash: [0xAA; 32],
             already_generated: 100,
-            utxos: HashMap::new(),
+            utxos: OutpointMap::default(),
         };
         let undo = BlockUndo {
             block_height: 3, // mismatched
@@ -676,6 +864,143 @@ mod tests {
         assert!(err.contains("txid"));
     }
 
+    fn mixed_undo() -> BlockUndo {
+        let mut htlc = sample_entry(7);
+        htlc.covenant_type = 0x0100;
+        htlc.covenant_data = (0..105u8).collect();
+        htlc.creation_height = u64::MAX;
+        htlc.created_by_coinbase = true;
+        BlockUndo {
+            block_height: 9,
+            previous_already_generated: 123_456,
+            txs: vec![
+                TxUndo { spent: vec![] },
+                TxUndo {
+                    spent: vec![
+                        SpentUndo {
+                            outpoint: sample_outpoint(0x04),
+                            entry: htlc,
+                        },
+                        SpentUndo {
+                            outpoint: sample_outpoint(0x05),
+                            entry: UtxoEntry {
+                                covenant_data: Vec::new(),
+                                ..sample_entry(1)
+                            },
+                        },
+                    ],
+                },
+            ],
+        }
+    }
+
+    /// Re-frames `body` as a version-1 record with a valid checksum, so
+    /// body-level decode errors are reached past the checksum gate.
+    fn framed(body: &[u8]) -> Vec<u8> {
+        let mut raw = vec![UNDO_RECORD_VERSION];
+        raw.extend_from_slice(&(body.len() as u32).to_le_bytes());
+        raw.extend_from_slice(body);
+        let checksum = Sha3_256::digest(&raw);
+        raw.extend_from_slice(&checksum);
+        raw
+    }
+
+    fn body_of(raw: &[u8]) -> Vec<u8> {
+        raw[UNDO_RECORD_HEADER_BYTES..raw.len() - UNDO_RECORD_CHECKSUM_BYTES].to_vec()
+    }
+
+    #[test]
+    fn undo_record_v1_layout_and_roundtrip() {
+        let undo = mixed_undo();
+        let raw = marshal_block_undo(&undo).expect("marshal");
+        assert_eq!(raw[0], UNDO_RECORD_VERSION);
+        assert_eq!(undo_record_version(&raw), Ok(UNDO_RECORD_VERSION));
+        let body = body_of(&raw);
+        assert_eq!(&raw[1..5], &(body.len() as u32).to_le_bytes());
+        assert_eq!(&body[..8], &9u64.to_le_bytes());
+        assert_eq!(&body[8..16], &123_456u64.to_le_bytes());
+        // tx_count, then the coinbase's empty spent list, then tx 1's count.
+        assert_eq!(&body[16..19], &[2, 0, 2]);
+        let spent = &undo.txs[1].spent[0];
+        assert_eq!(
+            &body[19..19 + 36 + 8 + 2 + 1 + 105 + 8 + 1],
+            canonical_utxo_entry_bytes(&spent.outpoint, &spent.entry).as_slice()
+        );
+        assert_eq!(unmarshal_block_undo(&raw).expect("unmarshal"), undo);
+    }
+
+    #[test]
+    fn undo_record_v1_rejects_every_corruption() {
+        let raw = marshal_block_undo(&mixed_undo()).expect("marshal");
+        let expect_err = |raw: &[u8], want: &str| {
+            let err = unmarshal_block_undo(raw).expect_err(want);
+            assert!(
+                err.starts_with("decode undo:") && err.contains(want),
+                "{err}"
+            );
+        };
+
+        expect_err(&[], "empty record");
+        expect_err(&[2], "unsupported record version 2");
+        expect_err(&raw[..UNDO_RECORD_HEADER_BYTES + 31], "truncated record");
+        // Every strict prefix (a torn write) and any extension fails the frame.
+        for cut in UNDO_RECORD_HEADER_BYTES + 32..raw.len() {
+            expect_err(&raw[..cut], "framed length");
+        }
+        let mut longer = raw.clone();
+        longer.push(0);
+        expect_err(&longer, "framed length");
+        // Any single bit flip past the version byte is caught.
+        for i in 1..raw.len() {
+            let mut flipped = raw.clone();
+            flipped[i] ^= 0x10;
+            assert!(unmarshal_block_undo(&flipped).is_err(), "flip at {i}");
+        }
+        let mut flipped = raw.clone();
+        flipped[UNDO_RECORD_HEADER_BYTES] ^= 0x01;
+        expect_err(&flipped, "checksum mismatch");
+
+        // Body errors behind a valid checksum.
+        let body = body_of(&raw);
+        expect_err(&framed(&body[..7]), "block_height: truncated body");
+        expect_err(&framed(&body[..16]), "tx_count");
+        let mut huge_count = body[..16].to_vec();
+        huge_count.extend_from_slice(&[0xff; 9]);
+        expect_err(&framed(&huge_count), "tx_count");
+        let mut non_minimal = body[..16].to_vec();
+        non_minimal.extend_from_slice(&[0xfd, 0x02, 0x00]);
+        expect_err(&framed(&non_minimal), "tx_count");
+        let mut trailing = body.clone();
+        trailing.push(0);
+        expect_err(&framed(&trailing), "1 trailing body bytes");
+        let flag_at = 19 + 36 + 8 + 2 + 1 + 105 + 8;
+        expect_err(&framed(&body[..19 + 20]), "undo[1].spent_count");
+        expect_err(
+            &framed(&body[..flag_at - 4]),
+            "undo[1].spent[0].creation_height: truncated body",
+        );
+        let mut bad_flag = body.clone();
+        assert_eq!(bad_flag[flag_at], 1);
+        bad_flag[flag_at] = 2;
+        expect_err(&framed(&bad_flag), "created_by_coinbase: invalid flag 2");
+        let mut long_data = body.clone();
+        long_data[19 + 36 + 8 + 2] = 0xfc;
+        expect_err(&framed(&long_data), "undo[1].spent[0].covenant_data");
+    }
+
+    #[test]
+    fn legacy_json_undo_records_decode_as_version_0() {
+        let undo = mixed_undo();
+        let legacy = marshal_block_undo_v0(&undo).expect("marshal v0");
+        assert_eq!(legacy[0], b'{');
+        assert_eq!(undo_record_version(&legacy), Ok(UNDO_RECORD_LEGACY_VERSION));
+        assert_eq!(unmarshal_block_undo(&legacy).expect("unmarshal v0"), undo);
+        // Re-encoding migrates the record to the current version.
+        let migrated = marshal_block_undo(&unmarshal_block_undo(&legacy).unwrap()).unwrap();
+        assert_eq!(undo_record_version(&migrated), Ok(UNDO_RECORD_VERSION));
+        assert_eq!(unmarshal_block_undo(&migrated).unwrap(), undo);
+    }
+
     #[test]
     fn build_block_undo_ignores_same_block_spends() {
         let (prev_state, source_outpoint, block_bytes, block_height) = same_block_spend_fixture();
@@ -741,6 +1066,99 @@ mod tests {
         assert_eq!(summary.utxo_count, prev_state.utxos.len() as u64);
     }
 
+    #[test]
+    fn disconnect_block_restores_coinbase_provenance_of_spent_output() {
+        // Build/disconnect do not verify witnesses, so an unsigned spend
+        // is enough to exercise the undo path.
+        let source_outpoint = sample_outpoint(0x55);
+        let coinbase_entry = UtxoEntry {
+            created_by_coinbase: true,
+            creation_height: 3,
+            ..sample_entry(100)
+        };
+        let mut prev_state = ChainState {
+            has_tip: true,
+            height: 149,
+            tip_hash: [0x66; 32],
+            already_generated: 9,
+            utxos: OutpointMap::default(),
+        };
+        prev_state
+            .utxos
+            .insert(source_outpoint.clone(), coinbase_entry.clone());
+        let spend = Tx {
+            version: TX_WIRE_VERSION,
+            tx_kind: 0x00,
+            tx_nonce: 1,
+            inputs: vec![TxInput {
+                prev_txid: source_outpoint.txid,
+                prev_vout: source_outpoint.vout,
+                script_sig: Vec::new(),
+                sequence: 0,
+            }],
+            outputs: vec![TxOutput {
+                value: 90,
+                covenant_type: COV_TYPE_P2PK,
+                covenant_data: vec![0xCD; 33],
+            }],
+            locktime: 0,
+            da_commit_core: None,
+            da_chunk_core: None,
+            witness: Vec::new(),
+            da_payload: Vec::new(),
+        };
+        let block_height = prev_state.height + 1;
+        let block_bytes = block_with_txs(
+            block_height,
+            prev_state.already_generated,
+            prev_state.tip_hash,
+            1_777_000_123,
+            &[marshal_tx(&spend).expect("marshal spend")],
+        );
+
+        let undo = build_block_undo(&prev_state, &block_bytes, block_height).expect("build undo");
+        let undo = unmarshal_block_undo(&marshal_block_undo(&undo).expect("marshal undo"))
+            .expect("unmarshal undo");
+        assert_eq!(undo.txs[1].spent[0].entry, coinbase_entry);
+
+        let pb = parse_block_bytes(&block_bytes).expect("parse block");
+        let mut connected_state = prev_state.clone();
+        connected_state.utxos.remove(&source_outpoint);
+        for (tx_index, tx) in pb.txs.iter().enumerate() {
+            for (vout, out) in tx.outputs.iter().enumerate() {
+                if !is_spendable_output(out.covenant_type) {
+                    continue;
+                }
+                connected_state.utxos.insert(
+                    Outpoint {
+                        txid: pb.txids[tx_index],
+                        vout: vout as u32,
+                    },
+                    UtxoEntry {
+                        value: out.value,
+                        covenant_type: out.covenant_type,
+                        covenant_data: out.covenant_data.clone(),
+                        creation_height: block_height,
+                        created_by_coinbase: tx_index == 0,
+                    },
+                );
+            }
+        }
+        connected_state.height = block_height;
+        connected_state.tip_hash = block_hash(&pb.header_bytes).expect("block hash");
+
+        connected_state
+            .disconnect_block(&block_bytes, &undo)
+            .expect("disconnect block");
+        assert_eq!(connected_state, prev_state);
+        let restored = connected_state
+            .utxos
+            .get(&source_outpoint)
+            .expect("restored");
+        assert!(restored.created_by_coinbase);
+        assert_eq!(restored.creation_height, 3);
+    }
+
     #[test]
     fn disconnect_block_rejects_undo_tx_count_mismatch() {
         let (prev_state, _source_outpoint, block_bytes, block_height) = same_block_spend_fixture();
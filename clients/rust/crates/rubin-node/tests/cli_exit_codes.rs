@@ -0,0 +1,101 @@
+//! The `rubin-node` exit-code contract, checked against the built binary:
+//! one representative failure per class, asserting the code and the
+//! stderr token scripts key on.
+
+use std::path::PathBuf;
+use std::process::{Command, Output};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use rubin_node::{build_coinbase_tx, default_mine_address};
+
+fn rubin_node(args: &[&str], envs: &[(&str, &str)]) -> (i32, String, String) {
+    let output: Output = Command::new(env!("CARGO_BIN_EXE_rubin-node"))
+        .args(args)
+        .envs(envs.iter().copied())
+        .output()
+        .expect("spawn rubin-node");
+    (
+        output.status.code().expect("exit code"),
+        String::from_utf8(output.stdout).expect("stdout utf8"),
+        String::from_utf8(output.stderr).expect("stderr utf8"),
+    )
+}
+
+fn unique_temp_dir(prefix: &str) -> PathBuf {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("clock before epoch")
+        .as_nanos();
+    let dir = std::env::temp_dir().join(format!("{prefix}-{}-{nanos}", std::process::id()));
+    std::fs::create_dir_all(&dir).expect("create temp dir");
+    dir
+}
+
+#[test]
+fn unknown_flag_is_a_usage_error() {
+    let (code, _, stderr) = rubin_node(&["--no-such-flag"], &[]);
+    assert_eq!(code, 2, "{stderr}");
+    assert!(stderr.contains("unknown flag: --no-such-flag"), "{stderr}");
+}
+
+#[test]
+fn malformed_hex_is_an_input_error() {
+    let (code, _, stderr) = rubin_node(&["--verify-coinbase", "zz", "--coinbase-height", "1"], &[]);
+    assert_eq!(code, 3, "{stderr}");
+    assert!(stderr.contains("invalid transaction hex"), "{stderr}");
+}
+
+#[test]
+fn consensus_rejection_names_the_canonical_code() {
+    let coinbase = hex::encode(
+        build_coinbase_tx(2, 0, &default_mine_address(), [0x11; 32]).expect("coinbase"),
+    );
+    let args = [
+        "--verify-coinbase",
+        coinbase.as_str(),
+        "--coinbase-height",
+        "3",
+        "--coinbase-already-generated",
+        "0",
+    ];
+    let (code, _, stderr) = rubin_node(&args, &[]);
+    assert_eq!(code, 4, "{stderr}");
+    assert!(
+        stderr.contains("verify coinbase rejected: BLOCK_ERR_COINBASE_INVALID"),
+        "{stderr}"
+    );
+
+    let mut json_args = args.to_vec();
+    json_args.push("--json");
+    let (code, stdout, _) = rubin_node(&json_args, &[]);
+    assert_eq!(code, 4);
+    let envelope: serde_json::Value = serde_json::from_str(&stdout).expect("envelope");
+    assert_eq!(envelope["error_code"], "BLOCK_ERR_COINBASE_INVALID");
+}
+
+#[test]
+fn missing_blockstore_is_a_store_error() {
+    let dir = unique_temp_dir("rubin-cli-exit-store");
+    let datadir = dir.display().to_string();
+    let hash = "11".repeat(32);
+    let (code, _, stderr) = rubin_node(&["--datadir", &datadir, "--get-block", &hash], &[]);
+    assert_eq!(code, 5, "{stderr}");
+    assert!(
+        stderr.contains("get block requires a blockstore"),
+        "{stderr}"
+    );
+    std::fs::remove_dir_all(&dir).expect("cleanup");
+}
+
+#[test]
+fn openssl_bootstrap_failure_is_a_crypto_provider_error() {
+    let dir = unique_temp_dir("rubin-cli-exit-crypto");
+    let datadir = dir.join("devnet").display().to_string();
+    let (code, _, stderr) = rubin_node(
+        &["devnet", "--blocks", "1", "--datadir", &datadir],
+        &[("RUBIN_OPENSSL_FIPS_MODE", "not-a-mode")],
+    );
+    assert_eq!(code, 6, "{stderr}");
+    assert!(stderr.contains("openssl"), "{stderr}");
+    std::fs::remove_dir_all(&dir).expect("cleanup");
+}
@@ -0,0 +1,216 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use rubin_node::{
+    block_store_path, chain_state_path, default_sync_config, devnet_genesis_block_bytes,
+    devnet_genesis_chain_id, load_chain_state, BlockStore, BlockSyncPeer, ChainState, Miner,
+    MinerConfig, SyncEngine,
+};
+
+const CHAIN_LEN: u64 = 50;
+
+/// Serves a fixed canonical chain and counts what it is asked for.
+struct MockPeer {
+    chain: Vec<[u8; 32]>,
+    blocks: HashMap<[u8; 32], Vec<u8>>,
+    getblocks_calls: usize,
+    fetched: Vec<[u8; 32]>,
+}
+
+impl MockPeer {
+    fn from_engine(source: &SyncEngine) -> Self {
+        let (tip_height, _) = source.tip().expect("source tip").expect("source has tip");
+        let store = source.block_store_snapshot().expect("source store");
+        let mut chain = Vec::new();
+        let mut blocks = HashMap::new();
+        for height in 0..=tip_height {
+            let hash = store
+                .canonical_hash(height)
+                .expect("canonical hash")
+                .expect("hash at height");
+            blocks.insert(hash, store.get_block_by_hash(hash).expect("block bytes"));
+            chain.push(hash);
+        }
+        Self {
+            chain,
+            blocks,
+            getblocks_calls: 0,
+            fetched: Vec::new(),
+        }
+    }
+}
+
+impl BlockSyncPeer for MockPeer {
+    fn hashes_after_locators(
+        &mut self,
+        locator_hashes: &[[u8; 32]],
+        limit: u64,
+    ) -> Result<Vec<[u8; 32]>, String> {
+        self.getblocks_calls += 1;
+        let start = locator_hashes
+            .iter()
+            .find_map(|locator| self.chain.iter().position(|hash| hash == locator))
+            .map_or(0, |pos| pos + 1);
+        Ok(self
+            .chain
+            .iter()
+            .skip(start)
+            .take(limit as usize)
+            .copied()
+            .collect())
+    }
+
+    fn block_bytes(&mut self, block_hash_bytes: [u8; 32]) -> Result<Vec<u8>, String> {
+        self.fetched.push(block_hash_bytes);
+        self.blocks
+            .get(&block_hash_bytes)
+            .cloned()
+            .ok_or_else(|| format!("unknown block {}", hex::encode(block_hash_bytes)))
+    }
+}
+
+fn unique_temp_dir(prefix: &str) -> PathBuf {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("clock before epoch")
+        .as_nanos();
+    let dir = std::env::temp_dir().join(format!("{prefix}-{}-{nanos}", std::process::id()));
+    std::fs::create_dir_all(&dir).expect("create temp dir");
+    dir
+}
+
+fn open_engine(dir: &Path) -> SyncEngine {
+    let chain_state_file = chain_state_path(dir);
+    let chain_state = if chain_state_file.exists() {
+        load_chain_state(&chain_state_file).expect("load chainstate")
+    } else {
+        ChainState::new()
+    };
+    let block_store = BlockStore::open(block_store_path(dir)).expect("open block store");
+    SyncEngine::new(
+        chain_state,
+        Some(block_store),
+        default_sync_config(None, devnet_genesis_chain_id(), Some(chain_state_file)),
+    )
+    .expect("new sync engine")
+}
+
+/// Devnet genesis followed by `CHAIN_LEN - 1` mined blocks at the pow limit.
+fn premined_source(dir: &Path) -> SyncEngine {
+    let mut source = open_engine(dir);
+    source
+        .apply_block(&devnet_genesis_block_bytes(), None)
+        .expect("apply genesis");
+    let cfg = MinerConfig {
+        timestamp_source: || 1,
+        ..MinerConfig::default()
+    };
+    let mut miner = Miner::new(&mut source, None, cfg).expect("miner");
+    let mined = miner
+        .mine_n((CHAIN_LEN - 1) as usize, &[])
+        .expect("mine chain");
+    assert_eq!(mined.last().expect("mined").height, CHAIN_LEN - 1);
+    source
+}
+
+#[test]
+fn import_blocks_syncs_genesis_to_tip_and_resumes_after_restart() {
+    let source_dir = unique_temp_dir("rubin-e2e-sync-source");
+    let local_dir = unique_temp_dir("rubin-e2e-sync-local");
+    let source = premined_source(&source_dir);
+    let want_tip = source.tip().expect("source tip").expect("source has tip");
+    let want_utxo_hash = source.chain_state_snapshot().utxo_set_hash();
+    let mut peer = MockPeer::from_engine(&source);
+    assert_eq!(peer.chain.len() as u64, CHAIN_LEN);
+
+    let mut local = open_engine(&local_dir);
+    let summary = local.import_blocks(&mut peer).expect("initial import");
+    assert_eq!(summary.blocks_applied, CHAIN_LEN);
+    assert_eq!(summary.tip, Some(want_tip));
+    assert_eq!(want_tip.0, CHAIN_LEN - 1);
+    assert_eq!(local.tip().expect("local tip"), Some(want_tip));
+    let state = local.chain_state_snapshot();
+    assert_eq!(state.height, CHAIN_LEN - 1);
+    assert_eq!(state.tip_hash, want_tip.1);
+    assert_eq!(state.utxo_set_hash(), want_utxo_hash);
+    assert_eq!(
+        peer.fetched, peer.chain,
+        "each block fetched once, in order"
+    );
+    drop(local);
+
+    let mut restarted = open_engine(&local_dir);
+    assert_eq!(restarted.tip().expect("restarted tip"), Some(want_tip));
+    assert_eq!(
+        restarted.chain_state_snapshot().utxo_set_hash(),
+        want_utxo_hash
+    );
+    peer.fetched.clear();
+    peer.getblocks_calls = 0;
+    let summary = restarted.import_blocks(&mut peer).expect("resume import");
+    assert_eq!(summary.blocks_applied, 0);
+    assert_eq!(summary.rounds, 1);
+    assert_eq!(summary.tip, Some(want_tip));
+    assert!(peer.fetched.is_empty(), "restart must not refetch blocks");
+    assert_eq!(peer.getblocks_calls, 1);
+
+    let _ = std::fs::remove_dir_all(&source_dir);
+    let _ = std::fs::remove_dir_all(&local_dir);
+}
+
+#[test]
+fn import_blocks_resumes_a_partial_sync_from_the_persisted_tip() {
+    let source_dir = unique_temp_dir("rubin-e2e-sync-partial-source");
+    let local_dir = unique_temp_dir("rubin-e2e-sync-partial-local");
+    let source = premined_source(&source_dir);
+    let want_tip = source.tip().expect("source tip").expect("source has tip");
+    let mut peer = MockPeer::from_engine(&source);
+
+    let mut local = open_engine(&local_dir);
+    for hash in &peer.chain[..20] {
+        local
+            .apply_block(&peer.blocks[hash], None)
+            .expect("apply prefix block");
+    }
+    drop(local);
+
+    let mut restarted = open_engine(&local_dir);
+    let summary = restarted.import_blocks(&mut peer).expect("resume import");
+    assert_eq!(summary.blocks_applied, CHAIN_LEN - 20);
+    assert_eq!(summary.tip, Some(want_tip));
+    assert_eq!(peer.fetched, peer.chain[20..].to_vec());
+    assert_eq!(
+        restarted.chain_state_snapshot().utxo_set_hash(),
+        source.chain_state_snapshot().utxo_set_hash()
+    );
+
+    let _ = std::fs::remove_dir_all(&source_dir);
+    let _ = std::fs::remove_dir_all(&local_dir);
+}
+
+#[test]
+fn import_blocks_rejects_a_peer_serving_the_wrong_block() {
+    let source_dir = unique_temp_dir("rubin-e2e-sync-wrong-source");
+    let local_dir = unique_temp_dir("rubin-e2e-sync-wrong-local");
+    let source = premined_source(&source_dir);
+    let mut peer = MockPeer::from_engine(&source);
+    let (first, second) = (peer.chain[1], peer.chain[2]);
+    let swapped = peer.blocks[&second].clone();
+    peer.blocks.insert(first, swapped);
+
+    let mut local = open_engine(&local_dir);
+    let err = local.import_blocks(&mut peer).unwrap_err();
+    assert_eq!(
+        err,
+        format!(
+            "peer served block {} for {}",
+            hex::encode(second),
+            hex::encode(first)
+        )
+    );
+    assert_eq!(local.tip().expect("local tip").map(|(h, _)| h), Some(0));
+
+    let _ = std::fs::remove_dir_all(&source_dir);
+    let _ = std::fs::remove_dir_all(&local_dir);
+}
@@ -0,0 +1,182 @@
+//! Deterministic golden-vector generator for cross-client checks.
+//!
+//! Usage: `rubin-vectors <seed> <scenario|all> <out_dir>`
+//!
+//! Writes `<out_dir>/CV-GEN-<SCENARIO>.json` in the conformance fixture layout
+//! for each requested scenario (`tx`, `retarget`, `merkle`, `utxo`). Output is
+//! a pure function of `(seed, scenario)`: the Go client, or a later Rust
+//! build, can replay the files through `conformance/runner/run_cv_bundle.py`
+//! and must reproduce every `expect_*` field byte-for-byte.
+
+mod scenarios;
+
+use std::env;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::process;
+
+use scenarios::{gate_name, generate, SCENARIOS};
+
+const PROGRAM: &str = "rubin-vectors";
+
+fn usage() -> String {
+    format!(
+        "usage: {PROGRAM} <seed> <scenario|all> <out_dir>\n       scenarios: {}",
+        SCENARIOS.join(", ")
+    )
+}
+
+fn parse_args(args: &[String]) -> Result<(u64, Vec<&'static str>, PathBuf), String> {
+    let [seed, scenario, out_dir] = args else {
+        return Err(usage());
+    };
+    let seed = seed
+        .parse::<u64>()
+        .map_err(|_| format!("invalid seed: {seed}"))?;
+    let scenarios = match scenario.as_str() {
+        "all" => SCENARIOS.to_vec(),
+        name => match SCENARIOS.iter().find(|s| **s == name) {
+            Some(s) => vec![*s],
+            None => return Err(format!("unknown scenario: {name}\n{}", usage())),
+        },
+    };
+    Ok((seed, scenarios, PathBuf::from(out_dir)))
+}
+
+/// Generate and write each scenario; returns the written paths in order.
+fn write_scenarios(seed: u64, scenarios: &[&str], out_dir: &Path) -> Result<Vec<PathBuf>, String> {
+    fs::create_dir_all(out_dir).map_err(|err| format!("create {}: {err}", out_dir.display()))?;
+    let mut written = Vec::with_capacity(scenarios.len());
+    for scenario in scenarios {
+        let fixture = generate(scenario, seed)?;
+        let mut encoded =
+            serde_json::to_string_pretty(&fixture).map_err(|err| format!("encode: {err}"))?;
+        encoded.push('\n');
+        let path = out_dir.join(format!("{}.json", gate_name(scenario)));
+        fs::write(&path, encoded).map_err(|err| format!("write {}: {err}", path.display()))?;
+        written.push(path);
+    }
+    Ok(written)
+}
+
+fn run(args: &[String]) -> Result<(), String> {
+    if matches!(args.first().map(String::as_str), Some("-h" | "--help")) {
+        println!("{}", usage());
+        return Ok(());
+    }
+    let (seed, scenarios, out_dir) = parse_args(args)?;
+    for path in write_scenarios(seed, &scenarios, &out_dir)? {
+        println!("{}", path.display());
+    }
+    Ok(())
+}
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    if let Err(err) = run(&args) {
+        let _ = writeln!(io::stderr(), "{err}");
+        process::exit(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use scenarios::{merkle_branch, merkle_branch_root};
+    use serde_json::Value;
+
+    fn temp_dir(tag: &str) -> PathBuf {
+        let dir = env::temp_dir().join(format!("{PROGRAM}-{tag}-{}", process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    fn read_all(paths: &[PathBuf]) -> Vec<Vec<u8>> {
+        paths.iter().map(|p| fs::read(p).expect("read")).collect()
+    }
+
+    #[test]
+    fn double_run_is_byte_identical() {
+        let (a, b) = (temp_dir("run-a"), temp_dir("run-b"));
+        let first = write_scenarios(7, SCENARIOS, &a).expect("first run");
+        let second = write_scenarios(7, SCENARIOS, &b).expect("second run");
+        assert_eq!(first.len(), SCENARIOS.len());
+        assert_eq!(read_all(&first), read_all(&second));
+
+        let c = temp_dir("run-c");
+        let other_seed = write_scenarios(8, SCENARIOS, &c).expect("other seed");
+        for (x, y) in read_all(&first).iter().zip(read_all(&other_seed).iter()) {
+            assert_ne!(x, y, "seed must influence every scenario");
+        }
+        for dir in [a, b, c] {
+            let _ = fs::remove_dir_all(dir);
+        }
+    }
+
+    #[test]
+    fn fixtures_use_conformance_layout() {
+        for scenario in SCENARIOS {
+            let fixture = generate(scenario, 1).expect("generate");
+            assert_eq!(fixture["gate"], gate_name(scenario));
+            let vectors = fixture["vectors"].as_array().expect("vectors");
+            assert!(!vectors.is_empty(), "{scenario}");
+            let mut ids: Vec<&str> = vectors.iter().map(|v| v["id"].as_str().unwrap()).collect();
+            for v in vectors {
+                assert!(v["op"].is_string(), "{scenario}: {v}");
+                assert_eq!(v["expect_ok"], Value::Bool(true));
+            }
+            ids.sort_unstable();
+            ids.dedup();
+            assert_eq!(ids.len(), vectors.len(), "{scenario}: duplicate ids");
+        }
+    }
+
+    #[test]
+    fn every_creatable_covenant_type_is_covered() {
+        let fixture = generate("tx", 3).expect("generate");
+        let parsed: Vec<&str> = fixture["vectors"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter(|v| v["op"] == "parse_tx")
+            .map(|v| v["id"].as_str().unwrap())
+            .collect();
+        assert_eq!(
+            parsed,
+            [
+                "GEN-TX-P2PK-PARSE",
+                "GEN-TX-ANCHOR-PARSE",
+                "GEN-TX-HTLC-PARSE",
+                "GEN-TX-VAULT-PARSE",
+                "GEN-TX-MULTISIG-PARSE",
+                "GEN-TX-STEALTH-PARSE",
+                "GEN-TX-DA_COMMIT-PARSE",
+            ]
+        );
+    }
+
+    #[test]
+    fn merkle_branches_fold_to_root() {
+        let mut rng = scenarios::SeedStream::new(11, "merkle-test");
+        for n in 1..=9usize {
+            let ids: Vec<[u8; 32]> = (0..n).map(|_| rng.bytes32()).collect();
+            let root = rubin_consensus::merkle_root_txids(&ids).expect("root");
+            for (index, id) in ids.iter().enumerate() {
+                let branch = merkle_branch(&ids, index);
+                assert_eq!(merkle_branch_root(id, &branch), root, "n={n} index={index}");
+            }
+        }
+    }
+
+    #[test]
+    fn parse_args_rejects_bad_input() {
+        let args = |items: &[&str]| items.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        assert!(parse_args(&args(&["1", "all"])).is_err());
+        assert!(parse_args(&args(&["x", "all", "/tmp/o"])).is_err());
+        let err = parse_args(&args(&["1", "nope", "/tmp/o"])).expect_err("unknown");
+        assert!(err.contains("unknown scenario"), "got: {err}");
+        let (seed, scenarios, _) = parse_args(&args(&["5", "merkle", "/tmp/o"])).expect("ok");
+        assert_eq!((seed, scenarios), (5, vec!["merkle"]));
+    }
+}
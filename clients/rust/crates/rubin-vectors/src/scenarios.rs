@@ -0,0 +1,507 @@
+//! Deterministic golden-vector scenarios.
+//!
+//! Every scenario draws its randomness from a [`SeedStream`] keyed by
+//! `(seed, scenario)` and builds vectors in a fixed order, so the emitted JSON
+//! is a pure function of its inputs on every platform. Fixtures use the
+//! conformance layout (`{gate, description, vectors}`) with the same op names
+//! and `expect_*` fields the consensus CLIs and `run_cv_bundle.py` replay.
+
+use rubin_consensus::constants::{
+    COV_TYPE_ANCHOR, COV_TYPE_CORE_STEALTH, COV_TYPE_DA_COMMIT, COV_TYPE_HTLC, COV_TYPE_MULTISIG,
+    COV_TYPE_P2PK, COV_TYPE_VAULT, LOCK_MODE_HEIGHT, MAX_STEALTH_COVENANT_DATA, POW_LIMIT,
+    SUITE_ID_ML_DSA_87, TARGET_BLOCK_INTERVAL, TX_WIRE_VERSION, WINDOW_SIZE,
+};
+use rubin_consensus::{
+    block_hash, marshal_tx, merkle::witness_merkle_root_wtxids, merkle_root_txids, parse_tx,
+    retarget_v1, sighash_v1_digest, tx_weight_and_stats_public, utxo_set_hash,
+    validate_tx_covenants_genesis, BlockHeader, DaCommitCore, Outpoint, OutpointMap, Tx, TxInput,
+    TxOutput, UtxoEntry, WitnessItem, BLOCK_HEADER_BYTES,
+};
+use serde_json::{json, Value};
+use sha3::{Digest, Sha3_256};
+
+/// Scenario names in emission order; `all` expands to this list.
+pub const SCENARIOS: &[&str] = &["tx", "retarget", "merkle", "utxo"];
+
+/// SHA3-256 counter-mode byte stream. The domain binds seed and scenario so
+/// adding a scenario never perturbs the vectors of another.
+pub struct SeedStream {
+    domain: Vec<u8>,
+    counter: u64,
+}
+
+impl SeedStream {
+    pub fn new(seed: u64, scenario: &str) -> Self {
+        let mut domain = b"RUBIN-VECTORS/".to_vec();
+        domain.extend_from_slice(scenario.as_bytes());
+        domain.push(b'/');
+        domain.extend_from_slice(&seed.to_le_bytes());
+        Self { domain, counter: 0 }
+    }
+
+    pub fn bytes32(&mut self) -> [u8; 32] {
+        let mut h = Sha3_256::new();
+        h.update(&self.domain);
+        h.update(self.counter.to_le_bytes());
+        self.counter += 1;
+        h.finalize().into()
+    }
+
+    pub fn bytes(&mut self, len: usize) -> Vec<u8> {
+        let mut out = Vec::with_capacity(len + 32);
+        while out.len() < len {
+            out.extend_from_slice(&self.bytes32());
+        }
+        out.truncate(len);
+        out
+    }
+
+    pub fn u64(&mut self) -> u64 {
+        u64::from_le_bytes(self.bytes32()[..8].try_into().expect("8 bytes"))
+    }
+
+    /// Uniform-enough draw from `0..n` (modulo bias is irrelevant here).
+    pub fn below(&mut self, n: u64) -> u64 {
+        self.u64() % n.max(1)
+    }
+
+    fn sorted_unique_32(&mut self, n: usize) -> Vec<[u8; 32]> {
+        let mut out: Vec<[u8; 32]> = (0..n).map(|_| self.bytes32()).collect();
+        out.sort_unstable();
+        out.dedup();
+        out
+    }
+}
+
+pub fn gate_name(scenario: &str) -> String {
+    format!("CV-GEN-{}", scenario.to_ascii_uppercase())
+}
+
+/// Build the fixture for `scenario`. The result depends only on `(scenario, seed)`.
+pub fn generate(scenario: &str, seed: u64) -> Result<Value, String> {
+    let mut rng = SeedStream::new(seed, scenario);
+    let (description, vectors) = match scenario {
+        "tx" => (
+            "canonical txs per creatable covenant type: txid/wtxid, weight, sighash",
+            tx_vectors(&mut rng)?,
+        ),
+        "retarget" => (
+            "retarget_v1 across the clamp range and boundary headers carrying target_new",
+            retarget_vectors(&mut rng)?,
+        ),
+        "merkle" => (
+            "tx/witness merkle roots and per-leaf branches for 1..=9 leaves",
+            merkle_vectors(&mut rng)?,
+        ),
+        "utxo" => (
+            "utxo_set_hash over canonical small UTXO sets",
+            utxo_vectors(&mut rng),
+        ),
+        _ => return Err(format!("unknown scenario: {scenario}")),
+    };
+    Ok(json!({
+        "gate": gate_name(scenario),
+        "description": format!("rubin-vectors seed={seed} scenario={scenario}: {description}"),
+        "vectors": vectors,
+    }))
+}
+
+fn err_str(e: rubin_consensus::TxError) -> String {
+    format!("{}: {}", e.code.as_str(), e.msg)
+}
+
+// ---------------------------------------------------------------------------
+// tx
+// ---------------------------------------------------------------------------
+
+fn p2pk_data(rng: &mut SeedStream) -> Vec<u8> {
+    let mut out = vec![SUITE_ID_ML_DSA_87];
+    out.extend_from_slice(&rng.bytes32());
+    out
+}
+
+fn htlc_data(rng: &mut SeedStream) -> Vec<u8> {
+    let mut out = rng.bytes32().to_vec();
+    out.push(LOCK_MODE_HEIGHT);
+    out.extend_from_slice(&(1 + rng.below(1_000_000)).to_le_bytes());
+    let [claim, refund] = [rng.bytes32(), rng.bytes32()];
+    out.extend_from_slice(&claim);
+    out.extend_from_slice(&refund);
+    out
+}
+
+fn multisig_data(rng: &mut SeedStream) -> Vec<u8> {
+    let keys = rng.sorted_unique_32(3);
+    let mut out = vec![2, keys.len() as u8];
+    keys.iter().for_each(|k| out.extend_from_slice(k));
+    out
+}
+
+fn vault_data(rng: &mut SeedStream) -> Vec<u8> {
+    let owner_lock_id = rng.bytes32();
+    let keys = rng.sorted_unique_32(3);
+    let whitelist: Vec<[u8; 32]> = rng
+        .sorted_unique_32(2)
+        .into_iter()
+        .filter(|h| *h != owner_lock_id)
+        .collect();
+    let mut out = owner_lock_id.to_vec();
+    out.push(2);
+    out.push(keys.len() as u8);
+    keys.iter().for_each(|k| out.extend_from_slice(k));
+    out.extend_from_slice(&(whitelist.len() as u16).to_le_bytes());
+    whitelist.iter().for_each(|h| out.extend_from_slice(h));
+    out
+}
+
+/// (name, tx_kind, output) for every covenant type creatable at genesis.
+fn covenant_outputs(rng: &mut SeedStream) -> Vec<(&'static str, u8, TxOutput)> {
+    let mut value = || 1 + rng.below(1_000_000_000);
+    let values: Vec<u64> = (0..5).map(|_| value()).collect();
+    vec![
+        ("P2PK", 0x00, out(values[0], COV_TYPE_P2PK, p2pk_data(rng))),
+        ("ANCHOR", 0x00, out(0, COV_TYPE_ANCHOR, rng.bytes(32))),
+        ("HTLC", 0x00, out(values[1], COV_TYPE_HTLC, htlc_data(rng))),
+        (
+            "VAULT",
+            0x00,
+            out(values[2], COV_TYPE_VAULT, vault_data(rng)),
+        ),
+        (
+            "MULTISIG",
+            0x00,
+            out(values[3], COV_TYPE_MULTISIG, multisig_data(rng)),
+        ),
+        (
+            "STEALTH",
+            0x00,
+            out(
+                values[4],
+                COV_TYPE_CORE_STEALTH,
+                rng.bytes(MAX_STEALTH_COVENANT_DATA as usize),
+            ),
+        ),
+        ("DA_COMMIT", 0x01, out(0, COV_TYPE_DA_COMMIT, rng.bytes(32))),
+    ]
+}
+
+fn out(value: u64, covenant_type: u16, covenant_data: Vec<u8>) -> TxOutput {
+    TxOutput {
+        value,
+        covenant_type,
+        covenant_data,
+    }
+}
+
+fn build_tx(rng: &mut SeedStream, tx_kind: u8, output: TxOutput) -> Tx {
+    let input_count = 1 + rng.below(2) as usize;
+    let inputs = (0..input_count)
+        .map(|_| TxInput {
+            prev_txid: rng.bytes32(),
+            prev_vout: rng.below(4) as u32,
+            script_sig: Vec::new(),
+            sequence: 0xffff_ffff,
+        })
+        .collect();
+    let change = TxOutput {
+        value: 1 + rng.below(1_000_000),
+        covenant_type: COV_TYPE_P2PK,
+        covenant_data: p2pk_data(rng),
+    };
+    let (da_commit_core, da_payload) = if tx_kind == 0x01 {
+        let core = DaCommitCore {
+            da_id: rng.bytes32(),
+            chunk_count: 1,
+            retl_domain_id: rng.bytes32(),
+            batch_number: rng.below(1 << 32),
+            tx_data_root: rng.bytes32(),
+            state_root: rng.bytes32(),
+            withdrawals_root: rng.bytes32(),
+            batch_sig_suite: 0,
+            batch_sig: Vec::new(),
+        };
+        (Some(core), rng.bytes(64))
+    } else {
+        (None, Vec::new())
+    };
+    Tx {
+        version: TX_WIRE_VERSION,
+        tx_kind,
+        tx_nonce: 1 + rng.below(1 << 32),
+        inputs,
+        outputs: vec![output, change],
+        locktime: 0,
+        da_commit_core,
+        da_chunk_core: None,
+        witness: vec![
+            WitnessItem {
+                suite_id: 0,
+                pubkey: Vec::new(),
+                signature: Vec::new(),
+            };
+            input_count
+        ],
+        da_payload,
+    }
+}
+
+fn tx_vectors(rng: &mut SeedStream) -> Result<Vec<Value>, String> {
+    let chain_id = rng.bytes32();
+    let mut vectors = Vec::new();
+    for (name, tx_kind, output) in covenant_outputs(rng) {
+        let tx = build_tx(rng, tx_kind, output);
+        validate_tx_covenants_genesis(&tx, 0, None)
+            .map_err(|e| format!("{name}: generated tx not canonical: {}", err_str(e)))?;
+        let raw = marshal_tx(&tx).map_err(err_str)?;
+        let tx_hex = hex::encode(&raw);
+        let (_, txid, wtxid, consumed) = parse_tx(&raw).map_err(err_str)?;
+        vectors.push(json!({
+            "id": format!("GEN-TX-{name}-PARSE"),
+            "op": "parse_tx",
+            "tx_hex": tx_hex,
+            "expect_ok": true,
+            "expect_txid": hex::encode(txid),
+            "expect_wtxid": hex::encode(wtxid),
+            "expect_consumed": consumed,
+        }));
+
+        let (weight, da_bytes, anchor_bytes) = tx_weight_and_stats_public(&tx).map_err(err_str)?;
+        vectors.push(json!({
+            "id": format!("GEN-TX-{name}-WEIGHT"),
+            "op": "tx_weight_and_stats",
+            "tx_hex": tx_hex,
+            "expect_ok": true,
+            "expect_weight": weight,
+            "expect_da_bytes": da_bytes,
+            "expect_anchor_bytes": anchor_bytes,
+        }));
+
+        for input_index in 0..tx.inputs.len() as u32 {
+            let input_value = 1 + rng.below(1_000_000_000);
+            let digest =
+                sighash_v1_digest(&tx, input_index, input_value, chain_id).map_err(err_str)?;
+            vectors.push(json!({
+                "id": format!("GEN-TX-{name}-SIGHASH-{input_index}"),
+                "op": "sighash_v1",
+                "tx_hex": tx_hex,
+                "input_index": input_index,
+                "input_value": input_value,
+                "chain_id": hex::encode(chain_id),
+                "expect_ok": true,
+                "expect_digest": hex::encode(digest),
+            }));
+        }
+    }
+    Ok(vectors)
+}
+
+// ---------------------------------------------------------------------------
+// retarget
+// ---------------------------------------------------------------------------
+
+fn header_bytes(h: &BlockHeader) -> Vec<u8> {
+    let mut out = Vec::with_capacity(BLOCK_HEADER_BYTES);
+    out.extend_from_slice(&h.version.to_le_bytes());
+    out.extend_from_slice(&h.prev_block_hash);
+    out.extend_from_slice(&h.merkle_root);
+    out.extend_from_slice(&h.timestamp.to_le_bytes());
+    out.extend_from_slice(&h.target);
+    out.extend_from_slice(&h.nonce.to_le_bytes());
+    out
+}
+
+fn retarget_vectors(rng: &mut SeedStream) -> Result<Vec<Value>, String> {
+    let expected = TARGET_BLOCK_INTERVAL * WINDOW_SIZE;
+    // Spans covering the 4x clamp in both directions, its edges, and the
+    // degenerate zero-length window.
+    let spans = [
+        0,
+        1,
+        expected / 8,
+        expected / 4,
+        expected / 4 + 1,
+        expected / 2,
+        expected,
+        expected * 2,
+        expected * 4 - 1,
+        expected * 4,
+        expected * 8,
+    ];
+    let mut mid_target = rng.bytes32();
+    mid_target[0] = 0x00;
+    mid_target[1] |= 0x01;
+    let targets = [("POWLIMIT", POW_LIMIT), ("MID", mid_target)];
+
+    let mut vectors = Vec::new();
+    let mut window = 1u64;
+    for (label, target_old) in targets {
+        for (i, span) in spans.iter().enumerate() {
+            let timestamp_first = 1_700_000_000 + rng.below(1_000_000);
+            let timestamp_last = timestamp_first + span;
+            let target_new =
+                retarget_v1(target_old, timestamp_first, timestamp_last).map_err(err_str)?;
+            vectors.push(json!({
+                "id": format!("GEN-RETARGET-{label}-{i:02}"),
+                "op": "retarget_v1",
+                "target_old": hex::encode(target_old),
+                "timestamp_first": timestamp_first,
+                "timestamp_last": timestamp_last,
+                "expect_ok": true,
+                "expect_target_new": hex::encode(target_new),
+            }));
+
+            let header = BlockHeader {
+                version: 1,
+                prev_block_hash: rng.bytes32(),
+                merkle_root: rng.bytes32(),
+                timestamp: timestamp_last + TARGET_BLOCK_INTERVAL,
+                target: target_new,
+                nonce: rng.u64(),
+            };
+            let bytes = header_bytes(&header);
+            let hash = block_hash(&bytes).map_err(err_str)?;
+            vectors.push(json!({
+                "id": format!("GEN-RETARGET-{label}-{i:02}-BOUNDARY"),
+                "op": "block_hash",
+                "height": window * WINDOW_SIZE,
+                "header_hex": hex::encode(&bytes),
+                "expect_ok": true,
+                "expect_block_hash": hex::encode(hash),
+            }));
+            window += 1;
+        }
+    }
+    Ok(vectors)
+}
+
+// ---------------------------------------------------------------------------
+// merkle
+// ---------------------------------------------------------------------------
+
+fn tagged_hash(tag: u8, parts: &[&[u8; 32]]) -> [u8; 32] {
+    let mut h = Sha3_256::new();
+    h.update([tag]);
+    parts.iter().for_each(|p| h.update(p));
+    h.finalize().into()
+}
+
+/// Sibling path from leaf `index` to the tx merkle root (leaf tag 0x00, node
+/// tag 0x01). Levels where the node is promoted under the odd rule contribute
+/// no sibling. Each step is `(sibling, sibling_is_left)`.
+pub fn merkle_branch(ids: &[[u8; 32]], mut index: usize) -> Vec<([u8; 32], bool)> {
+    let mut level: Vec<[u8; 32]> = ids.iter().map(|id| tagged_hash(0x00, &[id])).collect();
+    let mut branch = Vec::new();
+    while level.len() > 1 {
+        let sibling = index ^ 1;
+        if sibling < level.len() {
+            branch.push((level[sibling], sibling < index));
+        }
+        level = level
+            .chunks(2)
+            .map(|pair| match pair {
+                [l, r] => tagged_hash(0x01, &[l, r]),
+                [only] => *only,
+                _ => unreachable!(),
+            })
+            .collect();
+        index /= 2;
+    }
+    branch
+}
+
+/// Fold a branch produced by [`merkle_branch`] back to a root.
+#[cfg(test)]
+pub fn merkle_branch_root(id: &[u8; 32], branch: &[([u8; 32], bool)]) -> [u8; 32] {
+    branch
+        .iter()
+        .fold(tagged_hash(0x00, &[id]), |acc, (sibling, is_left)| {
+            if *is_left {
+                tagged_hash(0x01, &[sibling, &acc])
+            } else {
+                tagged_hash(0x01, &[&acc, sibling])
+            }
+        })
+}
+
+fn merkle_vectors(rng: &mut SeedStream) -> Result<Vec<Value>, String> {
+    let mut vectors = Vec::new();
+    for n in 1..=9usize {
+        let txids: Vec<[u8; 32]> = (0..n).map(|_| rng.bytes32()).collect();
+        let root = merkle_root_txids(&txids).map_err(err_str)?;
+        let branches: Vec<Value> = (0..n)
+            .map(|index| {
+                let steps: Vec<Value> = merkle_branch(&txids, index)
+                    .into_iter()
+                    .map(|(hash, is_left)| {
+                        json!({"hash": hex::encode(hash), "side": if is_left { "left" } else { "right" }})
+                    })
+                    .collect();
+                json!({"index": index, "siblings": steps})
+            })
+            .collect();
+        vectors.push(json!({
+            "id": format!("GEN-MERKLE-TX-{n}"),
+            "op": "merkle_root",
+            "txids": txids.iter().map(hex::encode).collect::<Vec<_>>(),
+            "merkle_branches": branches,
+            "expect_ok": true,
+            "expect_merkle_root": hex::encode(root),
+        }));
+
+        let wtxids: Vec<[u8; 32]> = (0..n).map(|_| rng.bytes32()).collect();
+        let witness_root = witness_merkle_root_wtxids(&wtxids).map_err(err_str)?;
+        vectors.push(json!({
+            "id": format!("GEN-MERKLE-WITNESS-{n}"),
+            "op": "witness_merkle_root",
+            "wtxids": wtxids.iter().map(hex::encode).collect::<Vec<_>>(),
+            "expect_ok": true,
+            "expect_witness_merkle_root": hex::encode(witness_root),
+        }));
+    }
+    Ok(vectors)
+}
+
+// ---------------------------------------------------------------------------
+// utxo
+// ---------------------------------------------------------------------------
+
+fn utxo_vectors(rng: &mut SeedStream) -> Vec<Value> {
+    let mut vectors = Vec::new();
+    for (i, size) in [0usize, 1, 2, 3, 5, 8].into_iter().enumerate() {
+        let mut utxos = OutpointMap::with_capacity_and_hasher(size, Default::default());
+        let mut rows = Vec::with_capacity(size);
+        for j in 0..size {
+            let outpoint = Outpoint {
+                txid: rng.bytes32(),
+                vout: rng.below(4) as u32,
+            };
+            let entry = UtxoEntry {
+                value: 1 + rng.below(1_000_000_000),
+                covenant_type: COV_TYPE_P2PK,
+                covenant_data: p2pk_data(rng),
+                creation_height: rng.below(100_000),
+                created_by_coinbase: j % 2 == 0,
+            };
+            // Rows keep generation order; the digest itself is order-independent.
+            rows.push(json!({
+                "txid": hex::encode(outpoint.txid),
+                "vout": outpoint.vout,
+                "value": entry.value,
+                "covenant_type": entry.covenant_type,
+                "covenant_data": hex::encode(&entry.covenant_data),
+                "creation_height": entry.creation_height,
+                "created_by_coinbase": entry.created_by_coinbase,
+            }));
+            utxos.insert(outpoint, entry);
+        }
+        vectors.push(json!({
+            "id": format!("GEN-UTXO-HASH-{i:02}"),
+            "op": "utxo_set_hash",
+            "utxos": rows,
+            "expect_ok": true,
+            "expect_utxo_set_hash": hex::encode(utxo_set_hash(&utxos)),
+            "expect_utxo_count": utxos.len(),
+        }));
+    }
+    vectors
+}
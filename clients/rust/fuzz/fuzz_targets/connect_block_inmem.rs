@@ -1,6 +1,5 @@
 #![no_main]
 use libfuzzer_sys::fuzz_target;
-use std::collections::HashMap;
 
 fuzz_target!(|data: &[u8]| {
     // Cap input to avoid OOM.
@@ -19,7 +18,7 @@ fuzz_target!(|data: &[u8]| {
     chain_id[..chain_id_raw.len()].copy_from_slice(chain_id_raw);
 
     let mut state = rubin_consensus::InMemoryChainState {
-        utxos: HashMap::new(),
+        utxos: rubin_consensus::OutpointMap::default(),
         already_generated: 0u128,
     };
 
@@ -36,7 +35,7 @@ fuzz_target!(|data: &[u8]| {
 
     // Determinism: second call with fresh state must produce same result class.
     let mut state2 = rubin_consensus::InMemoryChainState {
-        utxos: HashMap::new(),
+        utxos: rubin_consensus::OutpointMap::default(),
         already_generated: 0u128,
     };
 
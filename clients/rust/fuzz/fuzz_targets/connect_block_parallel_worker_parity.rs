@@ -1,13 +1,12 @@
 #![no_main]
 
 use libfuzzer_sys::fuzz_target;
-use std::collections::HashMap;
 
 const CTX_LEN: usize = 32 + 32 + 8 + 8 + 2;
 
 fn empty_state(already_generated: u128) -> rubin_consensus::InMemoryChainState {
     rubin_consensus::InMemoryChainState {
-        utxos: HashMap::new(),
+        utxos: rubin_consensus::OutpointMap::default(),
         already_generated,
     }
 }
@@ -2,7 +2,7 @@
 
 use libfuzzer_sys::fuzz_target;
 
-fuzz_target!(|data: &[u8]| {
-    let _ = rubin_consensus::parse_block_bytes(data);
-});
+#[path = "../invariants.rs"]
+mod invariants;
 
+fuzz_target!(|data: &[u8]| invariants::check_parse_block_bytes(data));
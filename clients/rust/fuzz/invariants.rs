@@ -0,0 +1,196 @@
+//! Invariants shared by the decode fuzz targets and the `cargo test` replay of
+//! `fuzz/regressions/` (`rubin-consensus/tests/decode_regressions.rs`). Each
+//! check panics on a violation. Every decoder must be deterministic and
+//! canonical: an accepted input re-encodes to exactly the bytes it was
+//! decoded from.
+#![allow(dead_code)]
+
+use rubin_consensus::constants::{
+    LOCK_MODE_HEIGHT, LOCK_MODE_TIMESTAMP, MAX_MULTISIG_KEYS, MAX_VAULT_KEYS,
+    MAX_VAULT_WHITELIST_ENTRIES,
+};
+use rubin_consensus::{
+    block_hash, marshal_tx, parse_block_bytes, parse_block_header_bytes,
+    parse_core_ext_covenant_data, parse_htlc_covenant_data, parse_multisig_covenant_data,
+    parse_stealth_covenant_data, parse_tx, parse_vault_covenant_data, BLOCK_HEADER_BYTES,
+};
+
+pub struct Target {
+    /// The `fuzz_targets/` binary and its `regressions/` directory.
+    pub name: &'static str,
+    /// The bare decoder call, for measuring its allocations.
+    pub decode: fn(&[u8]) -> bool,
+    pub check: fn(&[u8]),
+}
+
+pub const TARGETS: &[Target] = &[
+    Target {
+        name: "parse_tx",
+        decode: |data| parse_tx(data).is_ok(),
+        check: check_parse_tx,
+    },
+    Target {
+        name: "parse_block_bytes",
+        decode: |data| parse_block_bytes(data).is_ok(),
+        check: check_parse_block_bytes,
+    },
+    Target {
+        name: "parse_block_header",
+        decode: |data| parse_block_header_bytes(data).is_ok(),
+        check: check_parse_block_header,
+    },
+    Target {
+        name: "parse_htlc",
+        decode: |data| parse_htlc_covenant_data(data).is_ok(),
+        check: check_parse_htlc,
+    },
+    Target {
+        name: "parse_vault",
+        decode: |data| parse_vault_covenant_data(data).is_ok(),
+        check: check_parse_vault,
+    },
+    Target {
+        name: "parse_multisig",
+        decode: |data| parse_multisig_covenant_data(data).is_ok(),
+        check: check_parse_multisig,
+    },
+    Target {
+        name: "parse_stealth",
+        decode: |data| parse_stealth_covenant_data(data).is_ok(),
+        check: check_parse_stealth,
+    },
+    Target {
+        name: "parse_core_ext",
+        decode: |data| parse_core_ext_covenant_data(data).is_ok(),
+        check: check_parse_core_ext,
+    },
+];
+
+fn strictly_sorted_unique_32(xs: &[[u8; 32]]) -> bool {
+    xs.windows(2).all(|w| w[0] < w[1])
+}
+
+pub fn check_parse_tx(data: &[u8]) {
+    let (tx, txid, wtxid, consumed) = match parse_tx(data) {
+        Ok(parsed) => parsed,
+        Err(_) => return,
+    };
+    assert_eq!(
+        parse_tx(data).expect("reparse tx"),
+        (tx.clone(), txid, wtxid, consumed),
+        "parse_tx non-deterministic"
+    );
+
+    // Accepted encodings are canonical: re-marshalling reproduces exactly the
+    // consumed prefix, so trailing bytes are the only slack the parser allows.
+    let bytes = marshal_tx(&tx).expect("marshal parsed tx");
+    assert_eq!(
+        bytes,
+        data[..consumed],
+        "marshal(parse(x)) != x[..consumed]"
+    );
+}
+
+pub fn check_parse_block_bytes(data: &[u8]) {
+    let block = match parse_block_bytes(data) {
+        Ok(block) => block,
+        Err(_) => return,
+    };
+    assert_eq!(block.tx_count as usize, block.txs.len());
+    assert_eq!(block.txs.len(), block.txids.len());
+    assert_eq!(block.txs.len(), block.wtxids.len());
+    assert_eq!(block.header_bytes[..], data[..BLOCK_HEADER_BYTES]);
+
+    // Blocks reject trailing bytes, so the round trip covers the whole input.
+    let bytes = block.to_bytes().expect("re-serialize parsed block");
+    assert_eq!(bytes, data, "to_bytes(parse_block_bytes(x)) != x");
+}
+
+pub fn check_parse_block_header(data: &[u8]) {
+    let header = match parse_block_header_bytes(data) {
+        Ok(header) => header,
+        Err(_) => {
+            assert_ne!(
+                data.len(),
+                BLOCK_HEADER_BYTES,
+                "exact-length header rejected"
+            );
+            return;
+        }
+    };
+    assert_eq!(data.len(), BLOCK_HEADER_BYTES);
+    assert_eq!(header.to_bytes()[..], data[..], "header round trip");
+    assert_eq!(
+        block_hash(&header.to_bytes()).expect("hash header"),
+        block_hash(data).expect("hash input")
+    );
+}
+
+pub fn check_parse_htlc(data: &[u8]) {
+    let htlc = match parse_htlc_covenant_data(data) {
+        Ok(htlc) => htlc,
+        Err(_) => return,
+    };
+    assert_eq!(parse_htlc_covenant_data(data).expect("reparse htlc"), htlc);
+    assert!(matches!(
+        htlc.lock_mode,
+        LOCK_MODE_HEIGHT | LOCK_MODE_TIMESTAMP
+    ));
+    assert!(htlc.lock_value > 0);
+    assert_ne!(htlc.claim_key_id, htlc.refund_key_id);
+    assert_eq!(htlc.to_bytes(), data, "htlc round trip");
+}
+
+pub fn check_parse_vault(data: &[u8]) {
+    let vault = match parse_vault_covenant_data(data) {
+        Ok(vault) => vault,
+        Err(_) => return,
+    };
+    assert_eq!(
+        parse_vault_covenant_data(data).expect("reparse vault"),
+        vault
+    );
+    assert!(vault.key_count > 0 && vault.key_count <= MAX_VAULT_KEYS);
+    assert!(vault.threshold > 0 && vault.threshold <= vault.key_count);
+    assert_eq!(vault.key_count as usize, vault.keys.len());
+    assert!(strictly_sorted_unique_32(&vault.keys));
+    assert!(vault.whitelist_count > 0 && vault.whitelist_count <= MAX_VAULT_WHITELIST_ENTRIES);
+    assert_eq!(vault.whitelist_count as usize, vault.whitelist.len());
+    assert!(strictly_sorted_unique_32(&vault.whitelist));
+    assert!(vault
+        .whitelist
+        .iter()
+        .all(|entry| entry != &vault.owner_lock_id));
+    assert_eq!(vault.to_bytes(), data, "vault round trip");
+}
+
+pub fn check_parse_multisig(data: &[u8]) {
+    let multisig = match parse_multisig_covenant_data(data) {
+        Ok(multisig) => multisig,
+        Err(_) => return,
+    };
+    assert_eq!(
+        parse_multisig_covenant_data(data).expect("reparse multisig"),
+        multisig
+    );
+    assert!(multisig.key_count > 0 && multisig.key_count <= MAX_MULTISIG_KEYS);
+    assert!(multisig.threshold > 0 && multisig.threshold <= multisig.key_count);
+    assert!(strictly_sorted_unique_32(&multisig.keys));
+    assert_eq!(multisig.to_bytes(), data, "multisig round trip");
+}
+
+pub fn check_parse_stealth(data: &[u8]) {
+    let stealth = match parse_stealth_covenant_data(data) {
+        Ok(stealth) => stealth,
+        Err(_) => return,
+    };
+    assert_eq!(stealth.to_bytes(), data, "stealth round trip");
+}
+
+pub fn check_parse_core_ext(data: &[u8]) {
+    let core_ext = match parse_core_ext_covenant_data(data) {
+        Ok(core_ext) => core_ext,
+        Err(_) => return,
+    };
+    assert_eq!(core_ext.to_bytes(), data, "core_ext round trip");
+}
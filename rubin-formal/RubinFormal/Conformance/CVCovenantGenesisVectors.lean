@@ -29,7 +29,8 @@ def cvCovenantGenesisVectors : List CVCovenantGenesisVector := [
   { id := "CV-COV-14", txHex := "0x010000000000000000000000000001010000000000000000010199000000000000", expectOk := false, expectErr := some "TX_ERR_COVENANT_TYPE_INVALID" },
   { id := "CV-COV-15", txHex := "0x0100000000000000000000000000010100000000000000ff000101000000000000", expectOk := false, expectErr := some "TX_ERR_COVENANT_TYPE_INVALID" },
   { id := "CV-COV-16", txHex := "0x0100000000000000000000000000010100000000000000020103010000000000000000", expectOk := false, expectErr := some "TX_ERR_COVENANT_TYPE_INVALID" },
-  { id := "CV-COV-17", txHex := "0x0100000000000000000000000000010000000000000000020103010000000000000000", expectOk := false, expectErr := some "TX_ERR_COVENANT_TYPE_INVALID" }
+  { id := "CV-COV-17", txHex := "0x0100000000000000000000000000010000000000000000020103010000000000000000", expectOk := false, expectErr := some "TX_ERR_COVENANT_TYPE_INVALID" },
+  { id := "CV-COV-18", txHex := "0x010000000000000000000000000001010000000000000001016402df1a5f0efdbe1c2919d832305725c746beafd58c777783423ffacabd900ec901011100000000000000000000000000000000000000000000000000000000000000010002df1a5f0efdbe1c2919d832305725c746beafd58c777783423ffacabd900ec9000000000000", expectOk := false, expectErr := some "TX_ERR_VAULT_OWNER_DESTINATION_FORBIDDEN" }
 ]
 
 end RubinFormal.Conformance
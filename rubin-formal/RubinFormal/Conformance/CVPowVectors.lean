@@ -47,7 +47,9 @@ def cvPowVectors : List CVPowVector := [
   { id := "POW-06", op := .pow_check, expectOk := false, expectErr := some "BLOCK_ERR_POW_INVALID", targetOldHex := none, timestampFirst := none, timestampLast := none, windowPattern := none, headerHex := some ("0x0100000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000"), targetHex := some ("0x6df1cafaee3b81e81e298bc474b514cf8f4ba09e36f527a2d715957dd3360fff"), expectedBytesHex := none },
   { id := "POW-07", op := .pow_check, expectOk := false, expectErr := some "BLOCK_ERR_TARGET_INVALID", targetOldHex := none, timestampFirst := none, timestampLast := none, windowPattern := none, headerHex := some ("0x0100000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000"), targetHex := some ("0x0000000000000000000000000000000000000000000000000000000000000000"), expectedBytesHex := none },
   { id := "POW-09", op := .retarget_v1, expectOk := true, expectErr := none, targetOldHex := some ("0x0000000000000000000000000000000000000000000000000000000000000001"), timestampFirst := some 0, timestampLast := some 1209000, windowPattern := none, headerHex := none, targetHex := none, expectedBytesHex := some ("0x0000000000000000000000000000000000000000000000000000000000000001") },
-  { id := "POW-10", op := .retarget_v1, expectOk := true, expectErr := none, targetOldHex := some ("0x0000000000000000000000000000000000000000000000000000000000000001"), timestampFirst := some 500000, timestampLast := some 500000, windowPattern := none, headerHex := none, targetHex := none, expectedBytesHex := some ("0x0000000000000000000000000000000000000000000000000000000000000001") }
+  { id := "POW-10", op := .retarget_v1, expectOk := true, expectErr := none, targetOldHex := some ("0x0000000000000000000000000000000000000000000000000000000000000001"), timestampFirst := some 500000, timestampLast := some 500000, windowPattern := none, headerHex := none, targetHex := none, expectedBytesHex := some ("0x0000000000000000000000000000000000000000000000000000000000000001") },
+  { id := "POW-11", op := .retarget_v1, expectOk := true, expectErr := none, targetOldHex := some ("0x0000000000000000000000000000000000000000000000000000000000100000"), timestampFirst := some 1000000, timestampLast := some 1604740, windowPattern := some { windowSize := 10080, start := 1000000, step := 60, lastJump := 0 }, headerHex := none, targetHex := none, expectedBytesHex := some ("0x000000000000000000000000000000000000000000000000000000000007ffcb") },
+  { id := "POW-12", op := .retarget_v1, expectOk := true, expectErr := none, targetOldHex := some ("0x000000000000000000000000000000000000000000000000000000000007ffcb"), timestampFirst := some 1604800, timestampLast := some 4028520, windowPattern := some { windowSize := 10080, start := 1604800, step := 240, lastJump := 5000 }, headerHex := none, targetHex := none, expectedBytesHex := some ("0x00000000000000000000000000000000000000000000000000000000001000ce") }
 ]
 
 end RubinFormal.Conformance